@@ -64,7 +64,10 @@ impl IndexMut<usize> for AbscissaCollection {
 
 // Having the Index & IndexMut traits implemented for AbscissaCollection
 // and the From<Coord> and Into<Coord> implemented for Abscissa, it is
-// next to trivial to implement the CoordinateSet trait
+// next to trivial to implement the CoordinateSet trait. CoordinateSet
+// requires CoordinateMetadata, but the defaults suffice here
+impl CoordinateMetadata for AbscissaCollection {}
+
 impl CoordinateSet for AbscissaCollection {
     fn get_coord(&self, index: usize) -> Coor4D {
         self[index].into()
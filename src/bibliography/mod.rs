@@ -2,6 +2,12 @@
 
 /// Some literature, that has been useful in designing and implementing this library.
 pub enum Bibliography {
+    /// B.A. Archinal, C.H. Acton, M.F. A'Hearn, et al., 2018:
+    /// *Report of the IAU Working Group on Cartographic Coordinates and Rotational Elements: 2015*.
+    /// Celestial Mechanics and Dynamical Astronomy 130(22).
+    /// [DOI](https://doi.org/10.1007/s10569-017-9805-5)
+    Arc18,
+
     /// B.R. Bowring, 1976: *Transformation from spatial to geographical coordinates*.
     /// Survey Review 23(181), pp. 323–327.
     Bow76,
@@ -103,6 +109,10 @@ pub enum Bibliography {
     /// [pdf](https://gfzpublic.gfz-potsdam.de/rest/items/item_8827_5/component/file_130038/content).
     Kru12,
 
+    /// L.P. Lee, 1976: *Conformal Projections Based on Elliptic Functions*.
+    /// Cartographica Monograph 16, supplement no. 1 to Canadian Cartographer 13, 128 pp.
+    Lee76,
+
     /// Martin Losch and Verena Seufer, 2003:
     /// *How to Compute Geoid Undulations (Geoid Height Relative to a Given Reference Ellipsoid)
     /// from Spherical Harmonic Coefficients for Satellite Altimetry Applications*
@@ -111,6 +121,11 @@ pub enum Bibliography {
     /// [pdf](https://mitgcm.org/~mlosch/geoidcookbook.pdf).
     Los03,
 
+    /// H. Moritz, 2000: *Geodetic Reference System 1980*.
+    /// Journal of Geodesy 74(1), pp. 128–133.
+    /// [DOI](https://doi.org/10.1007/s001900050278)
+    Mor00,
+
     /// Gérard Petit and Brian Luzum (eds), 2010:
     /// *IERS conventions (2010)*.
     /// IERS technical note 36.
@@ -1,9 +1,14 @@
 use clap::Parser;
+use geodesy::authoring::{
+    builtin_operator, builtin_operators, BaseGrid, Grid, Jacobian, Ntv2Grid, OpParameter,
+};
 use geodesy::prelude::*;
-use log::{info, trace}; // debug, error, warn: not used
+use log::{info, trace, warn}; // debug, error: not used
+use rayon::prelude::*;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time;
 
 /// KP: The Rust Geodesy "Coordinate Processing" program. Called `kp` in honor
@@ -15,9 +20,29 @@ use std::time;
 #[command(name = "kp")]
 #[command(author, version, about = "KP: The Rust Geodesy 'Coordinate Processing' program", long_about = None)]
 struct Cli {
-    /// The operation to carry out e.g. 'kp "utm zone=32"'
+    /// The operation to carry out e.g. 'kp "utm zone=32"'. Alternatively one
+    /// of the geodesic modes 'direct', 'inverse', or 'geodesic' (cf. `geod`),
+    /// 'gridinfo' for grid inspection, 'ops' for operator documentation, or
+    /// 'test' for running test files in the style of PROJ's `gie`.
+    /// Not given when the operation is read from file (`--file`)
+    #[clap(
+        default_value = "",
+        hide_default_value = true,
+        required_unless_present = "file"
+    )]
     operation: String,
 
+    /// Read the operation from a file. May be repeated, concatenating the
+    /// pipeline fragments. Comments and line continuations are handled as
+    /// in resource files
+    #[clap(short = 'f', long, value_name = "FILE")]
+    file: Vec<PathBuf>,
+
+    /// Expand references to environment variables, `${NAME}`, in the
+    /// operation and in resources
+    #[clap(long)]
+    env: bool,
+
     /// Inverse operation
     #[clap(long = "inv")]
     inverse: bool,
@@ -38,18 +63,128 @@ struct Cli {
     #[clap(short = 'D', long)]
     dimension: Option<usize>,
 
+    /// Output column order, e.g. '2,1,3': The coordinate elements (counting
+    /// from 1) to output, in the order given. Overrides `--dimension`
+    #[clap(long, value_name = "ORDER", value_delimiter = ',')]
+    output_order: Option<Vec<usize>>,
+
+    /// Output the first two coordinate elements (i.e. latitude and longitude,
+    /// in degrees) as degrees, minutes and seconds, with hemisphere letters.
+    /// The number of decimals (`-d`) applies to the seconds
+    #[clap(long)]
+    dms: bool,
+
     /// Activate debug mode
     #[clap(long)]
     debug: bool,
 
-    /// Report fwd-inv roundtrip deviation
+    /// Report fwd-inv roundtrip deviation: The coordinate differences and
+    /// the closure error (in meters) for each point, followed by summary
+    /// statistics
     #[clap(short, long)]
     roundtrip: bool,
 
-    /// Echo input to output
+    /// Roundtrip tolerance (meters): Exit with an error if the closure error
+    /// of any point exceeds the tolerance
+    #[clap(long, value_name = "METERS", requires = "roundtrip")]
+    tolerance: Option<f64>,
+
+    /// Echo input to output: Append the original input record, as a
+    /// comment, to each output line
     #[clap(short, long)]
     echo: bool,
 
+    /// Tag column: Exclude column number COLUMN (counting from 1) of the
+    /// text input from the coordinate, and append it to each output line
+    #[clap(long, value_name = "COLUMN")]
+    tag: Option<usize>,
+
+    /// Input format: 'text' (whitespace separated columns), 'bin' (packed
+    /// little-endian f64 records of `--input-dimension` elements), or
+    /// 'geojson' (transforming all geometries of a GeoJSON object)
+    #[clap(long, value_enum, default_value = "text")]
+    input_format: Format,
+
+    /// Output format: 'text', 'bin' (packed little-endian f64 records of
+    /// the output dimensionality elements), or 'geojson'
+    #[clap(long, value_enum, default_value = "text")]
+    output_format: Format,
+
+    /// Input and output format (overrides `--input-format` and `--output-format`)
+    #[clap(long, value_enum)]
+    format: Option<Format>,
+
+    /// Read and write CSV, with a header line naming the columns. Only the
+    /// columns selected by `--x-col` etc. are transformed, while all other
+    /// columns are passed through unchanged
+    #[clap(long)]
+    csv: bool,
+
+    /// Name of the CSV column holding the first coordinate element
+    #[clap(long, value_name = "NAME")]
+    x_col: Option<String>,
+
+    /// Name of the CSV column holding the second coordinate element
+    #[clap(long, value_name = "NAME")]
+    y_col: Option<String>,
+
+    /// Name of the CSV column holding the third coordinate element (optional)
+    #[clap(long, value_name = "NAME")]
+    z_col: Option<String>,
+
+    /// Name of the CSV column holding the fourth coordinate element (optional)
+    #[clap(long, value_name = "NAME")]
+    t_col: Option<String>,
+
+    /// CSV field delimiter
+    #[clap(long, default_value_t = ',')]
+    delimiter: char,
+
+    /// Streaming mode: Transform and output each record as soon as it is
+    /// read. Malformed records are skipped with a warning, and a summary is
+    /// given at end of input, or when interrupted (Ctrl-C)
+    #[clap(long)]
+    stream: bool,
+
+    /// In streaming mode, collect records for up to MS milliseconds (checked
+    /// as records arrive), rather than transforming each record on arrival
+    #[clap(long, value_name = "MS", requires = "stream")]
+    flush_interval: Option<u64>,
+
+    /// Benchmark: Transform an in-memory copy of the input N times, and
+    /// report the throughput (points/second) of each step of the pipeline,
+    /// rather than the transformed coordinates
+    #[clap(long, value_name = "N")]
+    bench: Option<usize>,
+
+    /// Verify the implementation of the operators used by the operation, by
+    /// running their builtin test vectors, rather than transforming input
+    #[clap(long)]
+    selftest: bool,
+
+    /// Number of elements in each binary input record (2, 3, or 4)
+    #[clap(short = 'I', long, default_value_t = 4)]
+    input_dimension: usize,
+
+    /// Number of parallel workers used for the transformation. Input is
+    /// read in chunks, and output order is preserved
+    #[clap(short = 'j', long, default_value_t = 1)]
+    jobs: usize,
+
+    /// Ellipsoid for the geodesic modes
+    #[clap(long, default_value = "GRS80")]
+    ellps: String,
+
+    /// Densify geodesics in the 'direct' and 'geodesic' modes, with a point
+    /// for every `step` meters
+    #[clap(long)]
+    step: Option<f64>,
+
+    /// Report projection factors: Meridian convergence (degrees), meridional
+    /// and parallel scale, areal scale, and the Tissot semiaxes, cf. 'proj -S'
+    #[clap(short = 'S', long)]
+    factors: bool,
+
     #[clap(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
 
@@ -61,6 +196,14 @@ struct Cli {
     args: Vec<String>,
 }
 
+/// Input and output formats
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Text,
+    Bin,
+    Geojson,
+}
+
 fn main() -> Result<(), anyhow::Error> {
     let mut options = Cli::parse();
     env_logger::Builder::new()
@@ -77,222 +220,1482 @@ fn main() -> Result<(), anyhow::Error> {
         eprintln!("options: {options:#?}");
     }
 
+    // With the operation read from file, any positional argument is an input file
+    if !options.file.is_empty() {
+        if !options.operation.is_empty() {
+            options.args.insert(0, options.operation.clone());
+        }
+        options.operation = operation_from_files(&options.file)?;
+    }
+
+    if let Some(format) = options.format {
+        options.input_format = format;
+        options.output_format = format;
+    }
+
+    // Grid inspection works on files, not on streams
+    if options.operation == "gridinfo" {
+        return gridinfo(&options);
+    }
+
+    // Operator documentation
+    if options.operation == "ops" {
+        print!("{}", operator_documentation(options.args.first())?);
+        return Ok(());
+    }
+
+    // Test files in the style of PROJ's `gie`
+    if options.operation == "test" {
+        return gie(&options);
+    }
+
     // A dash, '-', given as file name indicates stdin
     if options.args.is_empty() {
         options.args.push("-".to_string());
     }
 
+    // The geodesic modes are not operators, but stand-ins for PROJ's `geod`
+    if ["direct", "inverse", "geodesic"].contains(&options.operation.as_str()) {
+        return geodesics(&options);
+    }
+
     // Create context and operator
     let start = time::Instant::now();
     let mut ctx = Plain::new();
+    ctx.interpolate_environment(options.env);
     let duration = start.elapsed();
     trace!("Created context in: {duration:?}");
+    if options.debug {
+        eprintln!("resource paths: {:?}", ctx.paths());
+    }
     let op = ctx.op(&options.operation)?;
     let duration = start.elapsed();
     trace!("Created operation in: {duration:?}");
     trace!("{op:#?}");
 
+    if options.selftest {
+        let (report, failed) = selftest(&mut ctx, op)?;
+        print!("{report}");
+        if failed > 0 {
+            return Err(anyhow::anyhow!("Self test failed"));
+        }
+        return Ok(());
+    }
+
+    if options.factors && (options.inverse || options.roundtrip) {
+        return Err(anyhow::anyhow!(
+            "The 'factors' option cannot be combined with 'inv' or 'roundtrip'"
+        ));
+    }
+    if options.factors && options.output_format == Format::Bin {
+        return Err(anyhow::anyhow!(
+            "The 'factors' option cannot be combined with binary output"
+        ));
+    }
+    if options.input_format == Format::Bin && !(2..=4).contains(&options.input_dimension) {
+        return Err(anyhow::anyhow!(
+            "Binary input records must have 2, 3, or 4 elements"
+        ));
+    }
+    let geojson_in = options.input_format == Format::Geojson;
+    let geojson_out = options.output_format == Format::Geojson;
+    if geojson_in != geojson_out {
+        return Err(anyhow::anyhow!(
+            "GeoJSON must be selected for both input and output (e.g. '--format geojson')"
+        ));
+    }
+    if geojson_in && (options.factors || options.roundtrip) {
+        return Err(anyhow::anyhow!(
+            "GeoJSON cannot be combined with 'factors' or 'roundtrip'"
+        ));
+    }
+
+    if let Some(order) = &options.output_order {
+        if order.is_empty() || order.iter().any(|i| !(1..=4).contains(i)) {
+            return Err(anyhow::anyhow!(
+                "The output order must consist of coordinate elements in the range 1-4"
+            ));
+        }
+    }
+    if options.dms && options.output_format != Format::Text {
+        return Err(anyhow::anyhow!("The 'dms' option requires text output"));
+    }
+    if (options.dms || options.output_order.is_some())
+        && (options.csv || options.output_format == Format::Geojson)
+    {
+        return Err(anyhow::anyhow!(
+            "The 'dms' and 'output-order' options cannot be combined with CSV or GeoJSON"
+        ));
+    }
+
+    if options.echo || options.tag.is_some() {
+        if options.input_format != Format::Text || options.output_format != Format::Text {
+            return Err(anyhow::anyhow!(
+                "The 'echo' and 'tag' options require text input and output"
+            ));
+        }
+        if options.csv {
+            return Err(anyhow::anyhow!(
+                "The 'echo' and 'tag' options cannot be combined with CSV"
+            ));
+        }
+        if options.tag == Some(0) {
+            return Err(anyhow::anyhow!("Tag columns are counted from 1"));
+        }
+    }
+
+    if options.csv {
+        if options.input_format != Format::Text || options.output_format != Format::Text {
+            return Err(anyhow::anyhow!(
+                "CSV cannot be combined with other input or output formats"
+            ));
+        }
+        if options.factors || options.roundtrip {
+            return Err(anyhow::anyhow!(
+                "CSV cannot be combined with 'factors' or 'roundtrip'"
+            ));
+        }
+        if options.x_col.is_none() || options.y_col.is_none() {
+            return Err(anyhow::anyhow!(
+                "CSV requires at least the 'x-col' and 'y-col' options"
+            ));
+        }
+        if !options.delimiter.is_ascii() {
+            return Err(anyhow::anyhow!(
+                "The CSV delimiter must be an ASCII character"
+            ));
+        }
+    }
+
+    if options.stream {
+        if options.input_format != Format::Text
+            || options.output_format != Format::Text
+            || options.csv
+        {
+            return Err(anyhow::anyhow!(
+                "Streaming mode requires plain text input and output"
+            ));
+        }
+        ctrlc::set_handler(|| {
+            eprintln!("{STREAM}");
+            std::process::exit(130);
+        })?;
+    }
+
+    if let Some(iterations) = options.bench {
+        if iterations == 0 {
+            return Err(anyhow::anyhow!(
+                "The number of benchmark iterations must be positive"
+            ));
+        }
+        if options.stream || options.csv || geojson_in || options.factors || options.roundtrip {
+            return Err(anyhow::anyhow!(
+                "The 'bench' option cannot be combined with 'stream', 'csv', 'geojson', 'factors', or 'roundtrip'"
+            ));
+        }
+    }
+
+    if options.jobs > 1 {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(options.jobs)
+            .build_global()?;
+    }
+
+    if geojson_in {
+        return geojson(&options, &ctx, op);
+    }
+    if options.csv {
+        return csv(&options, &ctx, op);
+    }
+
     // Get ready to read and transform input data
     let mut number_of_operands_read = 0_usize;
     let mut number_of_operands_succesfully_transformed = 0_usize;
     let mut number_of_dimensions_in_input = 0;
     let mut operands = Vec::new();
+    let mut annotations = Vec::new();
+    let mut closure = Closure::new(options.tolerance);
     let start = time::Instant::now();
+    let flush_interval = time::Duration::from_millis(options.flush_interval.unwrap_or(0));
+    let mut last_flush = time::Instant::now();
 
     // Now loop over all input files (of which stdin may be one)
     for arg in &options.args {
-        let reader: Box<dyn BufRead> = if arg == "-" {
+        let mut reader: Box<dyn BufRead> = if arg == "-" {
             Box::new(BufReader::new(std::io::stdin().lock()))
         } else {
             Box::new(BufReader::new(File::open(arg)?))
         };
-        for line in reader.lines() {
-            let line = line?;
-            let line = line.trim();
-            let mut args: Vec<&str> = line.split_whitespace().collect();
+        let mut line = String::new();
+        let mut record = vec![0_u8; 8 * options.input_dimension];
+        loop {
+            let mut b = [0., 0., 0., f64::NAN];
 
-            // Remove comments
-            for (n, arg) in args.iter().enumerate() {
-                if arg.starts_with('#') {
-                    args.truncate(n);
+            if options.input_format == Format::Bin {
+                if !read_record(&mut reader, &mut record)? {
                     break;
                 }
-            }
-            let n = args.len();
+                for (i, element) in record.chunks_exact(8).enumerate() {
+                    b[i] = f64::from_le_bytes(element.try_into()?);
+                }
+                number_of_dimensions_in_input = options.input_dimension;
+            } else {
+                line.clear();
+                let bytes = if options.stream {
+                    read_line_lossy(&mut reader, &mut line)?
+                } else {
+                    reader.read_line(&mut line)?
+                };
+                if bytes == 0 {
+                    break;
+                }
+                let mut args = text_args(&line);
+                if options.echo || options.tag.is_some() {
+                    annotations.push(annotation(options.echo, options.tag, &line, &mut args));
+                }
+                let n = args.len();
 
-            // Empty line
-            if n < 1 {
-                continue;
-            }
+                // Empty line
+                if n < 1 {
+                    annotations.truncate(operands.len());
+                    continue;
+                }
+
+                // In streaming mode, garbage is skipped rather than passed on as NaNs
+                if options.stream && malformed(&args) {
+                    let skipped = STREAM.skipped.fetch_add(1, Ordering::Relaxed) + 1;
+                    warn!("Skipping malformed record #{skipped}: '{}'", line.trim());
+                    annotations.truncate(operands.len());
+                    continue;
+                }
 
-            number_of_dimensions_in_input = number_of_dimensions_in_input.max(n);
+                number_of_dimensions_in_input = number_of_dimensions_in_input.max(n);
 
-            // Convert the text representation to a Coor4D
-            args.extend(&(["0", "0", "0", "NaN", "0"][args.len()..]));
-            let mut b: Vec<f64> = vec![];
-            for e in args {
-                b.push(angular::parse_sexagesimal(e));
+                // Convert the text representation to a Coor4D. A single element
+                // may be a complete ISO 6709 coordinate string
+                if let Some(c) = iso6709(&args) {
+                    b = c.0;
+                    number_of_dimensions_in_input = number_of_dimensions_in_input.max(3);
+                } else {
+                    for (i, e) in args.iter().take(4).enumerate() {
+                        b[i] = angular::parse_sexagesimal(e);
+                    }
+                }
             }
             b[2] = options.height.unwrap_or(b[2]);
             b[3] = options.time.unwrap_or(b[3]);
 
-            let coord = Coor4D([b[0], b[1], b[2], b[3]]);
+            let coord = Coor4D(b);
             number_of_operands_read += 1;
             operands.push(coord);
 
+            if options.stream {
+                STREAM.read.fetch_add(1, Ordering::Relaxed);
+                if last_flush.elapsed() >= flush_interval {
+                    let n = transform(
+                        &options,
+                        op,
+                        number_of_dimensions_in_input,
+                        &mut operands,
+                        &annotations,
+                        &mut closure,
+                        &ctx,
+                    )?;
+                    std::io::stdout().flush()?;
+                    STREAM.transformed.fetch_add(n, Ordering::Relaxed);
+                    number_of_operands_succesfully_transformed += n;
+                    operands.truncate(0);
+                    annotations.truncate(0);
+                    last_flush = time::Instant::now();
+                }
+                continue;
+            }
+
             // To avoid unlimited buffer growth, we send material
             // on to the transformation factory every time, we have
-            // 25000 operands to operate on
-            if operands.len() == 25000 {
+            // 25000 operands (per worker) to operate on. Except when
+            // benchmarking, where we need all input in memory
+            if options.bench.is_none() && operands.len() == 25000 * options.jobs.max(1) {
                 number_of_operands_succesfully_transformed += transform(
                     &options,
                     op,
                     number_of_dimensions_in_input,
                     &mut operands,
+                    &annotations,
+                    &mut closure,
                     &ctx,
                 )?;
                 operands.truncate(0);
+                annotations.truncate(0);
             }
         }
     }
 
+    if let Some(iterations) = options.bench {
+        println!(
+            "Benchmark: {} points, {iterations} iterations",
+            operands.len()
+        );
+        for (step, rate) in benchmark(&options, &mut ctx, op, &operands, iterations)? {
+            println!("{rate:>14.0} points/s  {step}");
+        }
+        return Ok(());
+    }
+
     // Transform the remaining coordinates
-    number_of_operands_succesfully_transformed += transform(
+    let n = transform(
         &options,
         op,
         number_of_dimensions_in_input,
         &mut operands,
+        &annotations,
+        &mut closure,
         &ctx,
     )?;
+    number_of_operands_succesfully_transformed += n;
 
     let duration = start.elapsed();
     info!("Read {number_of_operands_read} coordinates and succesfully transformed {number_of_operands_succesfully_transformed} in {duration:?}");
 
+    if options.stream {
+        STREAM.transformed.fetch_add(n, Ordering::Relaxed);
+        eprintln!("{STREAM}");
+    }
+
+    if options.roundtrip {
+        eprintln!("{closure}");
+        if closure.failures > 0 {
+            return Err(anyhow::anyhow!(
+                "Roundtrip closure error exceeds tolerance for {} points",
+                closure.failures
+            ));
+        }
+    }
+
     Ok(())
 }
 
-// Transformation - this is the actual geodetic content
-fn transform(
-    options: &Cli,
-    op: OpHandle,
-    number_of_dimensions_in_input: usize,
-    operands: &mut Vec<Coor4D>,
-    ctx: &Plain,
-) -> Result<usize, geodesy::Error> {
-    let output_dimension = options.dimension.unwrap_or(number_of_dimensions_in_input);
+// Concatenate the pipeline fragments read from `files` into one pipeline.
+// Comments and line continuations are left for the tokenizer to handle, so
+// the fragments are separated by line breaks, to keep any trailing comments
+// from swallowing the step separator
+fn operation_from_files(files: &[PathBuf]) -> Result<String, anyhow::Error> {
+    let mut fragments = Vec::new();
+    for file in files {
+        let fragment = std::fs::read_to_string(file)
+            .map_err(|e| anyhow::anyhow!("Cannot read '{}': {e}", file.display()))?;
+        fragments.push(fragment.trim().to_string());
+    }
+    Ok(fragments.join("\n|\n"))
+}
 
-    // When roundtripping, we must keep a copy of the input to be able
-    // to compute the roundtrip differences
-    let mut buffer = Vec::new();
-    if options.roundtrip {
-        buffer.clone_from(operands);
+// The whitespace separated elements of a line of text input, sans comments
+fn text_args(line: &str) -> Vec<&str> {
+    let mut args: Vec<&str> = line.split_whitespace().collect();
+    for (n, arg) in args.iter().enumerate() {
+        if arg.starts_with('#') {
+            args.truncate(n);
+            break;
+        }
     }
+    args
+}
 
-    let mut n = if options.inverse {
-        ctx.apply(op, Inv, operands)?
-    } else {
-        ctx.apply(op, Fwd, operands)?
-    };
+// The annotation appended to the output line of a text input record: The
+// contents of the `tag` column (which is removed from `args`), and/or, if
+// `echo` is set, the original input record as a comment
+fn annotation(echo: bool, tag: Option<usize>, line: &str, args: &mut Vec<&str>) -> String {
+    let mut annotation = Vec::new();
+    if let Some(column) = tag {
+        if (1..=args.len()).contains(&column) {
+            annotation.push(args.remove(column - 1).to_string());
+        }
+    }
+    if echo {
+        annotation.push(format!("# {}", line.trim()));
+    }
+    annotation.join(" ")
+}
 
-    // Roundtrip
-    let m = if options.roundtrip {
-        let m = if options.inverse {
-            ctx.apply(op, Fwd, operands)?
+// The geodesic modes, stand-ins for PROJ's `geod`. Input angles are in
+// degrees, with latitude before longitude, distances in meters:
+//
+// - direct:   lat lon azimuth distance  ->  lat2 lon2 azimuth2
+// - inverse:  lat1 lon1 lat2 lon2       ->  azimuth1 azimuth2 distance
+// - geodesic: lat1 lon1 lat2 lon2       ->  lat lon azimuth distance, for
+//   each point along the geodesic
+//
+// With `--step`, the direct mode also outputs the points along the geodesic
+fn geodesics(options: &Cli) -> Result<(), anyhow::Error> {
+    let ellps = Ellipsoid::named(&options.ellps)?;
+    if options.step.is_some_and(|step| step <= 0.) {
+        return Err(anyhow::anyhow!("The 'step' option must be positive"));
+    }
+    let angular = options.decimals.unwrap_or(10);
+    let linear = options.decimals.unwrap_or(5);
+
+    for arg in &options.args {
+        let reader: Box<dyn BufRead> = if arg == "-" {
+            Box::new(BufReader::new(std::io::stdin().lock()))
         } else {
-            ctx.apply(op, Inv, operands)?
+            Box::new(BufReader::new(File::open(arg)?))
         };
-        if m != n {
-            return Err(Error::General(
-                "Roundtrip - mismatch between number of Fwd and Inv results",
-            ));
-        }
+        for line in reader.lines() {
+            let line = line?;
+            let args = text_args(&line);
+            if args.is_empty() {
+                continue;
+            }
+            if args.len() < 4 {
+                return Err(anyhow::anyhow!("Expected 4 input elements, got: '{line}'"));
+            }
+            let b: Vec<f64> = args[..4]
+                .iter()
+                .map(|arg| angular::parse_sexagesimal(arg))
+                .collect();
+            let from = Coor2D::geo(b[0], b[1]);
 
-        for index in 0..n {
-            operands[index] = operands[index] - buffer[index];
-        }
+            let (azimuth, distance) = match options.operation.as_str() {
+                "direct" => (b[2].to_radians(), b[3]),
+                _ => {
+                    let to = Coor2D::geo(b[2], b[3]);
+                    let d = ellps.geodesic_inv(&from, &to);
+                    if options.operation == "inverse" {
+                        println!(
+                            "{1:.0$} {2:.0$} {4:.3$}",
+                            angular,
+                            d[0].to_degrees(),
+                            d[1].to_degrees(),
+                            linear,
+                            d[2]
+                        );
+                        continue;
+                    }
+                    (d[0], d[2])
+                }
+            };
 
-        m
-    } else {
-        n
-    };
+            if options.operation == "direct" && options.step.is_none() {
+                let d = ellps.geodesic_fwd(&from, azimuth, distance);
+                let (lon, lat, azi) = (d[0].to_degrees(), d[1].to_degrees(), d[2].to_degrees());
+                println!("{1:.0$} {2:.0$} {3:.0$}", angular, lat, lon, azi);
+                continue;
+            }
 
-    n = n.min(m);
+            // Densification: The points along the geodesic
+            for s in geodesic_stations(distance, options.step) {
+                let d = ellps.geodesic_fwd(&from, azimuth, s);
+                let (lon, lat, azi) = (d[0].to_degrees(), d[1].to_degrees(), d[2].to_degrees());
+                println!(
+                    "{1:.0$} {2:.0$} {3:.0$} {5:.4$}",
+                    angular, lat, lon, azi, linear, s
+                );
+            }
+        }
+    }
+    Ok(())
+}
 
-    // If the number of output decimals are not given as option "-d",
-    // we try guess a reasonable value, using the heuristic that if
-    // the first coordinate is larger than 1000, the output is most
-    // probably not in degrees. Hence give 5 decimals for linear units,
-    // 10 for angular
-    let decimals = options
-        .decimals
-        .unwrap_or(if operands[0][0] > 1000. { 5 } else { 10 });
+// The distances from the origin of the points along a geodesic of length
+// `distance`, with a spacing of `step`, always including both end points
+fn geodesic_stations(distance: f64, step: Option<f64>) -> Vec<f64> {
+    let step = step.unwrap_or(distance.abs());
+    let mut stations = vec![0.];
+    if distance == 0. || !distance.is_finite() || step <= 0. {
+        return stations;
+    }
+    let n = (distance.abs() / step).ceil() as usize;
+    for i in 1..n {
+        stations.push((i as f64 * step).copysign(distance));
+    }
+    stations.push(distance);
+    stations
+}
 
-    // Finally output the transformed coordinates
-    for coord in operands {
-        match output_dimension {
-            0 | 4 => println!(
-                "{1:.0$} {2:.0$} {3:.0$} {4:.0$} ",
-                decimals, coord[0], coord[1], coord[2], coord[3]
-            ),
-            1 => println!("{1:.0$} ", decimals, coord[0]),
-            2 => println!("{1:.0$} {2:.0$} ", decimals, coord[0], coord[1]),
-            3 => println!(
-                "{1:.0$} {2:.0$} {3:.0$} ",
-                decimals, coord[0], coord[1], coord[2]
-            ),
-            _ => println!(
-                "{1:.0$} {2:.0$} {3:.0$} {4:.0$} ",
-                decimals, coord[0], coord[1], coord[2], coord[3]
-            ),
+// Operator documentation: Without `name`, a list of all builtin operators.
+// Otherwise the description and parameter gamut of the operator `name`
+fn operator_documentation(name: Option<&String>) -> Result<String, geodesy::Error> {
+    let Some(name) = name else {
+        let mut list = String::new();
+        for doc in builtin_operators() {
+            list += &format!("{:<12} {}\n", doc.name, doc.description);
         }
+        return Ok(list);
+    };
+
+    let doc = builtin_operator(name)?;
+    let mut text = format!("{}: {}\n", doc.name, doc.description);
+    if doc.gamut.is_empty() {
+        return Ok(text);
     }
-    Ok(n)
+    text += "\nParameters:\n";
+    for parameter in doc.gamut {
+        let (key, kind, default) = match parameter {
+            OpParameter::Flag { key } => (key, "flag", None),
+            OpParameter::Natural { key, default } => {
+                (key, "natural", default.map(|d| d.to_string()))
+            }
+            OpParameter::Integer { key, default } => {
+                (key, "integer", default.map(|d| d.to_string()))
+            }
+            OpParameter::Real { key, default } => (key, "real", default.map(|d| d.to_string())),
+            OpParameter::Series { key, default } => (key, "series", default.map(String::from)),
+            OpParameter::Text { key, default } => (key, "text", default.map(String::from)),
+            OpParameter::Texts { key, default } => (key, "texts", default.map(String::from)),
+        };
+        let default = match (kind, default) {
+            ("flag", _) => String::new(),
+            (_, Some(default)) => format!("default: {default}"),
+            (_, None) => "required".to_string(),
+        };
+        text += format!("    {key:<12} {kind:<8} {default}").trim_end();
+        text.push('\n');
+    }
+    if !doc.test_vectors.is_empty() {
+        text += "\nTest vectors:\n";
+        for vector in doc.test_vectors {
+            text += &format!("    {}\n", vector.source);
+        }
+    }
+    Ok(text)
 }
 
-// ----- T E S T S ------------------------------------------------------------------
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use float_eq::assert_float_eq;
+// Run the builtin test vectors of the operators used by `op`, reporting the
+// outcome for each. Returns the report and the number of failures
+fn selftest(ctx: &mut Plain, op: OpHandle) -> Result<(String, usize), geodesy::Error> {
+    let mut report = String::new();
+    let results = ctx.selftest(op)?;
+    for result in &results {
+        let verdict = if result.passed { "PASS" } else { "FAIL" };
+        let d = result.deviation;
+        let i = result.inverse_deviation;
+        report += &format!(
+            "{verdict} {}: {}\n    fwd deviation: {:.3e} {:.3e} {:.3e}\n    inv deviation: {:.3e} {:.3e} {:.3e}\n",
+            result.operator, result.source, d[0], d[1], d[2], i[0], i[1], i[2]
+        );
+    }
+    let failed = results.iter().filter(|r| !r.passed).count();
+    report += &format!(
+        "{} test vectors, {} passed, {failed} failed\n",
+        results.len(),
+        results.len() - failed
+    );
+    Ok((report, failed))
+}
 
-    fn some_basic_coordinates() -> [Coor4D; 2] {
-        let copenhagen = Coor4D::raw(55., 12., 0., 0.);
-        let stockholm = Coor4D::raw(59., 18., 0., 0.);
-        [copenhagen, stockholm]
+// Run the `gie` style test files given as arguments, reporting failures and
+// a summary for each
+fn gie(options: &Cli) -> Result<(), anyhow::Error> {
+    if options.args.is_empty() {
+        return Err(anyhow::anyhow!("Usage: kp test <file>..."));
+    }
+    let mut failures = 0;
+    for arg in &options.args {
+        let source = std::fs::read_to_string(arg)
+            .map_err(|e| anyhow::anyhow!("Cannot read '{arg}': {e}"))?;
+        let mut ctx = Plain::new();
+        let report = geodesy::gie::run(&mut ctx, &source)?;
+        for message in report.messages.iter().chain(&report.failures) {
+            println!("{arg}: {message}");
+        }
+        println!(
+            "{arg}: {} tests, {} passed, {} failed",
+            report.tests,
+            report.passed(),
+            report.failures.len()
+        );
+        failures += report.failures.len();
     }
+    if failures > 0 {
+        return Err(anyhow::anyhow!("{failures} tests failed"));
+    }
+    Ok(())
+}
 
-    #[test]
-    fn introspection() -> Result<(), Error> {
-        let mut ctx = Minimal::new();
+// Grid inspection: Report the characteristics of the grid in the file given
+// as first argument. Any further arguments are taken as latitude-longitude
+// pairs (in degrees, or northing-easting for projected grids), for which the
+// containing subgrid and the interpolated grid value are reported
+fn gridinfo(options: &Cli) -> Result<(), anyhow::Error> {
+    let Some(name) = options.args.first() else {
+        return Err(anyhow::anyhow!("Usage: kp gridinfo <file> [lat lon]..."));
+    };
+    let points: Vec<f64> = options.args[1..]
+        .iter()
+        .map(|arg| angular::parse_sexagesimal(arg))
+        .collect();
+    if points.len() % 2 != 0 {
+        return Err(anyhow::anyhow!("Expected latitude-longitude pairs"));
+    }
 
-        let op = ctx.op("geo:in | utm zone=32 | neu:out")?;
+    let buf = std::fs::read(name)?;
+    let ntv2 = name.to_lowercase().ends_with(".gsb");
+    let par = name.to_lowercase().ends_with(".par");
+    let nadcon5 = name.to_lowercase().ends_with(".b");
+    let gtx = name.to_lowercase().ends_with(".gtx");
+    let ostn15 = !ntv2 && buf.starts_with(b"Point_ID");
+    let grid: Box<dyn Grid> = if ntv2 {
+        Box::new(Ntv2Grid::new(&buf)?)
+    } else if par {
+        Box::new(BaseGrid::par(&buf)?)
+    } else if nadcon5 {
+        Box::new(BaseGrid::nadcon5(&buf)?)
+    } else if gtx {
+        Box::new(BaseGrid::gtx(&buf)?)
+    } else if ostn15 {
+        Box::new(BaseGrid::ostn15(&buf)?)
+    } else {
+        Box::new(BaseGrid::gravsoft(&buf)?)
+    };
 
-        let mut data = some_basic_coordinates();
-        let expected = [6098907.825005002, 691875.6321396609, 0., 0.];
+    let format = if ntv2 {
+        "NTv2"
+    } else if par {
+        "GSI .par"
+    } else if nadcon5 {
+        "NADCON5"
+    } else if gtx {
+        "GTX"
+    } else if ostn15 {
+        "OSTN15"
+    } else {
+        "Gravsoft"
+    };
+    println!("Grid:       {name} ({format})");
+    let points: Vec<[f64; 2]> = points.chunks_exact(2).map(|p| [p[0], p[1]]).collect();
+    print!("{}", grid_report(grid.as_ref(), &points));
+    Ok(())
+}
 
-        ctx.apply(op, Fwd, &mut data)?;
-        assert_float_eq!(data[0].0, expected, abs_all <= 1e-9);
+// The characteristics of `grid`, its subgrid tree, and the containing subgrid
+// and interpolated value at each of `points` (latitude-longitude in degrees)
+fn grid_report(grid: &dyn Grid, points: &[[f64; 2]]) -> String {
+    let info = grid.info();
 
-        // The text definitions of each step
-        let steps = ctx.steps(op)?;
-        assert_eq!(steps.len(), 3);
-        assert_eq!(steps[0], "geo:in");
-        assert_eq!(steps[1], "utm zone=32");
-        assert_eq!(steps[2], "neu:out");
+    // Grids in angular units are given in radians, and reported in degrees
+    let angular = info.iter().all(|i| {
+        [i.lat_n, i.lat_s, i.lon_w, i.lon_e]
+            .iter()
+            .all(|v| v.abs() <= 4. * std::f64::consts::PI)
+    });
+    let (unit, scale) = if angular {
+        ("degrees", 1_f64.to_degrees())
+    } else {
+        ("grid units", 1.)
+    };
 
-        // Behind the curtains, the two i/o-macros are just calls to the 'adapt' operator
-        assert_eq!("adapt", ctx.params(op, 0)?.name);
-        assert_eq!("adapt", ctx.params(op, 2)?.name);
+    let mut report = format!("Bands:      {}\n", grid.bands());
+    report += &format!("Subgrids:   {}\n", info.len());
+    for subgrid in &info {
+        // Indentation reflects the depth in the subgrid tree
+        let mut depth = 0;
+        let mut parent = &subgrid.parent;
+        while let Some(p) = info
+            .iter()
+            .find(|i| &i.name == parent && i.name != subgrid.name)
+        {
+            depth += 1;
+            parent = &p.parent;
+        }
+        let indent = "    ".repeat(depth);
+        let name = if subgrid.name.is_empty() {
+            "-"
+        } else {
+            &subgrid.name
+        };
+        report += &format!("{indent}Subgrid {name} (parent: {})\n", subgrid.parent);
+        report += &format!(
+            "{indent}    Extent:     lat {:.6} .. {:.6}, lon {:.6} .. {:.6} ({unit})\n",
+            subgrid.lat_s * scale,
+            subgrid.lat_n * scale,
+            subgrid.lon_w * scale,
+            subgrid.lon_e * scale
+        );
+        report += &format!(
+            "{indent}    Resolution: dlat {:.6}, dlon {:.6} ({unit})\n",
+            subgrid.dlat.abs() * scale,
+            subgrid.dlon.abs() * scale
+        );
+        report += &format!(
+            "{indent}    Size:       {} rows x {} columns x {} bands\n",
+            subgrid.rows, subgrid.cols, subgrid.bands
+        );
+    }
 
-        // While the utm step really is the 'utm' operator, not 'tmerc'-with-extras
-        assert_eq!("utm", ctx.params(op, 1)?.name);
+    for point in points {
+        let coord = Coor4D::raw(point[1] / scale, point[0] / scale, 0., 0.);
+        report += &format!("Point {} {}: ", point[0], point[1]);
+        let Some(name) = grid.which_subgrid_contains(&coord, 0.) else {
+            report += "outside of grid\n";
+            continue;
+        };
+        let name = if name.is_empty() { "-" } else { &name };
+        let value = grid.at(&coord, 0.).unwrap_or(Coor4D::nan());
 
-        // All the 'common' elements (lat_?, lon_?, x_?, y_? etc.) defaults to 0,
+        // Horizontal datum shifts in angular units are reported in seconds of
+        // arc, and in latitude-longitude order (followed by any height shift,
+        // in meters). Other grid values as is
+        let value = if grid.units().is_angular() && grid.bands() > 1 {
+            let (dlat, dlon) = (value[1].to_degrees() * 3600., value[0].to_degrees() * 3600.);
+            if grid.bands() > 2 {
+                format!("{dlat:.6} {dlon:.6} (arcsec) {:.6} (m)", value[2])
+            } else {
+                format!("{dlat:.6} {dlon:.6} (arcsec)")
+            }
+        } else {
+            let bands: Vec<String> = (0..grid.bands().min(4))
+                .map(|i| format!("{:.6}", value[i]))
+                .collect();
+            bands.join(" ")
+        };
+        report += &format!("subgrid {name}, value {value}\n");
+    }
+    report
+}
+
+// GeoJSON: Transform all coordinates of all geometries of each input file
+// through `op`, leaving everything else (e.g. properties) untouched
+fn geojson(options: &Cli, ctx: &Plain, op: OpHandle) -> Result<(), anyhow::Error> {
+    for arg in &options.args {
+        let reader: Box<dyn BufRead> = if arg == "-" {
+            Box::new(BufReader::new(std::io::stdin().lock()))
+        } else {
+            Box::new(BufReader::new(File::open(arg)?))
+        };
+        let mut document: serde_json::Value = serde_json::from_reader(reader)?;
+        geojson_transform(options, ctx, op, &mut document)?;
+        println!("{}", serde_json::to_string(&document)?);
+    }
+    Ok(())
+}
+
+// Transform all positions of a GeoJSON object, in place
+fn geojson_transform(
+    options: &Cli,
+    ctx: &Plain,
+    op: OpHandle,
+    document: &mut serde_json::Value,
+) -> Result<usize, geodesy::Error> {
+    let mut positions = Vec::new();
+    geojson_positions(document, &mut positions);
+
+    let mut operands: Vec<Coor4D> = positions
+        .iter()
+        .map(|position| {
+            let mut b = [0., 0., 0., f64::NAN];
+            for (i, element) in position.iter().take(4).enumerate() {
+                b[i] = element.as_f64().unwrap_or(f64::NAN);
+            }
+            b[2] = options.height.unwrap_or(b[2]);
+            b[3] = options.time.unwrap_or(b[3]);
+            Coor4D(b)
+        })
+        .collect();
+    let successes = apply(options, ctx, op, !options.inverse, &mut operands)?;
+
+    // Write back, retaining the dimensionality of each position
+    for (position, coord) in positions.iter_mut().zip(operands) {
+        for (i, element) in position.iter_mut().take(4).enumerate() {
+            let value = match options.decimals {
+                Some(d) => (coord[i] * 10_f64.powi(d as i32)).round() / 10_f64.powi(d as i32),
+                None => coord[i],
+            };
+            *element = serde_json::Value::from(value);
+        }
+    }
+    Ok(successes)
+}
+
+// Collect all positions (i.e. arrays of numbers) found in the geometries of
+// a GeoJSON object: A FeatureCollection, a Feature, or a bare geometry.
+fn geojson_positions<'a>(
+    value: &'a mut serde_json::Value,
+    positions: &mut Vec<&'a mut Vec<serde_json::Value>>,
+) {
+    use serde_json::Value;
+    match value {
+        Value::Object(object) => {
+            let kind = object.get("type").and_then(Value::as_str).unwrap_or("");
+            let member = match kind {
+                "FeatureCollection" => "features",
+                "Feature" => "geometry",
+                "GeometryCollection" => "geometries",
+                _ => "coordinates",
+            };
+            if let Some(member) = object.get_mut(member) {
+                geojson_positions(member, positions);
+            }
+        }
+        Value::Array(array) => {
+            if array.first().is_some_and(Value::is_number) {
+                positions.push(array);
+            } else {
+                for element in array {
+                    geojson_positions(element, positions);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// CSV: Transform the columns selected by `--x-col` etc. of each input file
+// through `op`, passing all other columns through unchanged. The header of
+// the first file is written to the output, and the remaining files must
+// have identical headers
+fn csv(options: &Cli, ctx: &Plain, op: OpHandle) -> Result<(), anyhow::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter as u8)
+        .from_writer(std::io::stdout().lock());
+    let mut header = None;
+    for arg in &options.args {
+        let reader: Box<dyn BufRead> = if arg == "-" {
+            Box::new(BufReader::new(std::io::stdin().lock()))
+        } else {
+            Box::new(BufReader::new(File::open(arg)?))
+        };
+        header = Some(csv_transform(
+            options,
+            ctx,
+            op,
+            reader,
+            &mut writer,
+            header,
+        )?);
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// Transform the CSV records from `reader`, writing the results to `writer`.
+// The header is written, unless already given in `header` (from a previous
+// file), in which case the headers must match. Returns the header
+fn csv_transform<W: Write>(
+    options: &Cli,
+    ctx: &Plain,
+    op: OpHandle,
+    reader: impl std::io::Read,
+    writer: &mut csv::Writer<W>,
+    header: Option<csv::StringRecord>,
+) -> Result<csv::StringRecord, anyhow::Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter as u8)
+        .from_reader(reader);
+    let headers = reader.headers()?.clone();
+    match header {
+        Some(header) if header != headers => {
+            return Err(anyhow::anyhow!("CSV header mismatch between input files"))
+        }
+        Some(_) => (),
+        None => writer.write_record(&headers)?,
+    }
+
+    // Map the selected column names to column indices
+    let mut columns = [None; 4];
+    let names = [
+        &options.x_col,
+        &options.y_col,
+        &options.z_col,
+        &options.t_col,
+    ];
+    for (column, name) in columns.iter_mut().zip(names) {
+        let Some(name) = name else {
+            continue;
+        };
+        let Some(index) = headers.iter().position(|h| h.trim() == name) else {
+            return Err(anyhow::anyhow!("CSV column '{name}' not found"));
+        };
+        *column = Some(index);
+    }
+
+    let mut records = Vec::new();
+    let mut operands = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let mut b = [0., 0., 0., f64::NAN];
+        for (i, column) in columns.iter().enumerate() {
+            if let Some(field) = column.and_then(|index| record.get(index)) {
+                b[i] = angular::parse_sexagesimal(field.trim());
+            }
+        }
+        b[2] = options.height.unwrap_or(b[2]);
+        b[3] = options.time.unwrap_or(b[3]);
+        operands.push(Coor4D(b));
+        records.push(record);
+
+        if operands.len() == 25000 * options.jobs.max(1) {
+            csv_flush(
+                options,
+                ctx,
+                op,
+                &columns,
+                &mut records,
+                &mut operands,
+                writer,
+            )?;
+        }
+    }
+    csv_flush(
+        options,
+        ctx,
+        op,
+        &columns,
+        &mut records,
+        &mut operands,
+        writer,
+    )?;
+    Ok(headers)
+}
+
+// Transform the buffered operands, and write the records, with the selected
+// columns replaced by the transformed values
+fn csv_flush<W: Write>(
+    options: &Cli,
+    ctx: &Plain,
+    op: OpHandle,
+    columns: &[Option<usize>; 4],
+    records: &mut Vec<csv::StringRecord>,
+    operands: &mut Vec<Coor4D>,
+    writer: &mut csv::Writer<W>,
+) -> Result<(), anyhow::Error> {
+    if operands.is_empty() {
+        return Ok(());
+    }
+    apply(options, ctx, op, !options.inverse, operands)?;
+
+    // Same heuristic as for the text output: Linear units get 5 decimals,
+    // angular units 10
+    let decimals = options
+        .decimals
+        .unwrap_or(if operands[0][0] > 1000. { 5 } else { 10 });
+
+    for (record, coord) in records.iter().zip(operands.iter()) {
+        let mut fields: Vec<String> = record.iter().map(String::from).collect();
+        for (i, column) in columns.iter().enumerate() {
+            if let Some(field) = column.and_then(|index| fields.get_mut(index)) {
+                *field = format!("{:.1$}", coord[i], decimals);
+            }
+        }
+        writer.write_record(&fields)?;
+    }
+    records.clear();
+    operands.clear();
+    Ok(())
+}
+
+// Throughput, in points/second, of each step of `op`, and of `op` as a whole,
+// when applied `iterations` times to `input`. Each step is timed on its own
+// input, i.e. the output of the preceding step. Steps depending on their
+// neighbours (`push`, `pop`, `stack`, `tissot`) cannot be timed in isolation,
+// so pipelines including these are only timed as a whole
+fn benchmark(
+    options: &Cli,
+    ctx: &mut Plain,
+    op: OpHandle,
+    input: &[Coor4D],
+    iterations: usize,
+) -> Result<Vec<(String, f64)>, geodesy::Error> {
+    let forward = !options.inverse;
+    let omit = if forward { "omit_fwd" } else { "omit_inv" };
+    let definitions = ctx.steps(op)?.clone();
+
+    let mut steps = Vec::new();
+    let mut separable = true;
+    for (i, definition) in definitions.iter().enumerate() {
+        let params = ctx.params(op, i)?;
+        if ["push", "pop", "stack", "tissot"].contains(&params.name.as_str()) {
+            separable = false;
+        }
+        if !params.boolean(omit) {
+            steps.push((definition.clone(), ctx.op(definition)?));
+        }
+    }
+    if !forward {
+        steps.reverse();
+    }
+    if !separable || definitions.len() < 2 {
+        steps.clear();
+    }
+
+    let mut rates = Vec::new();
+    let mut stage = input.to_vec();
+    for (definition, step) in steps {
+        let (rate, output) = throughput(options, ctx, step, forward, &stage, iterations)?;
+        rates.push((definition, rate));
+        stage = output;
+    }
+    let (rate, _) = throughput(options, ctx, op, forward, input, iterations)?;
+    rates.push(("(total)".to_string(), rate));
+    Ok(rates)
+}
+
+// Apply `op` `iterations` times to the same `input`. Returns the throughput
+// in points/second, and the output, for feeding the next step
+fn throughput(
+    options: &Cli,
+    ctx: &Plain,
+    op: OpHandle,
+    forward: bool,
+    input: &[Coor4D],
+    iterations: usize,
+) -> Result<(f64, Vec<Coor4D>), geodesy::Error> {
+    let mut operands = input.to_vec();
+    let mut elapsed = time::Duration::ZERO;
+    for _ in 0..iterations {
+        operands.copy_from_slice(input);
+        let start = time::Instant::now();
+        apply(options, ctx, op, forward, &mut operands)?;
+        elapsed += start.elapsed();
+    }
+    let points = (iterations * input.len()) as f64;
+    Ok((points / elapsed.as_secs_f64().max(1e-9), operands))
+}
+
+// Read a line of text into `line`, replacing any invalid UTF-8 rather than
+// failing. Returns the number of bytes read, i.e. 0 at end-of-file
+fn read_line_lossy(reader: &mut dyn BufRead, line: &mut String) -> Result<usize, std::io::Error> {
+    let mut bytes = Vec::new();
+    let n = reader.read_until(b'\n', &mut bytes)?;
+    line.push_str(&String::from_utf8_lossy(&bytes));
+    Ok(n)
+}
+
+// A text record consisting of a single element may be an ISO 6709 string,
+// e.g. `+5530.6+01245.6+100/`
+fn iso6709(args: &[&str]) -> Option<Coor4D> {
+    match args {
+        [single] => single.parse().ok(),
+        _ => None,
+    }
+}
+
+// A text record is malformed if any of its (up to 4) coordinate elements
+// cannot be parsed
+fn malformed(args: &[&str]) -> bool {
+    iso6709(args).is_none()
+        && args
+            .iter()
+            .take(4)
+            .any(|e| angular::parse_sexagesimal(e).is_nan())
+}
+
+// Record counts for the streaming mode summary. Static, since the summary
+// is also given by the interrupt handler
+struct StreamCounts {
+    read: AtomicUsize,
+    transformed: AtomicUsize,
+    skipped: AtomicUsize,
+}
+
+static STREAM: StreamCounts = StreamCounts::new();
+
+impl StreamCounts {
+    const fn new() -> Self {
+        StreamCounts {
+            read: AtomicUsize::new(0),
+            transformed: AtomicUsize::new(0),
+            skipped: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl std::fmt::Display for StreamCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Streamed {} records: {} transformed, {} malformed records skipped",
+            self.read.load(Ordering::Relaxed),
+            self.transformed.load(Ordering::Relaxed),
+            self.skipped.load(Ordering::Relaxed)
+        )
+    }
+}
+
+// Read a binary record into `record`. Returns false on a clean end-of-file,
+// i.e. if no part of the record could be read
+fn read_record(reader: &mut dyn BufRead, record: &mut [u8]) -> Result<bool, std::io::Error> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(false);
+    }
+    reader.read_exact(record).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => {
+            std::io::Error::new(e.kind(), "truncated binary input record")
+        }
+        _ => e,
+    })?;
+    Ok(true)
+}
+
+// Apply `op` to `operands`, in the forward direction if `forward` is true,
+// otherwise in the inverse. With more than one job, the operands are split
+// in chunks, which are transformed in parallel, in place, so the order is
+// preserved
+fn apply(
+    options: &Cli,
+    ctx: &Plain,
+    op: OpHandle,
+    forward: bool,
+    operands: &mut [Coor4D],
+) -> Result<usize, geodesy::Error> {
+    let direction = if forward { Fwd } else { Inv };
+    let jobs = options.jobs.max(1);
+    if jobs == 1 {
+        let mut operands = operands;
+        return ctx.apply(op, direction, &mut operands);
+    }
+
+    let chunk_size = ((operands.len() + jobs - 1) / jobs).max(1);
+    operands
+        .par_chunks_mut(chunk_size)
+        .map(|mut chunk| ctx.apply(op, direction, &mut chunk))
+        .try_reduce(|| 0, |a, b| Ok(a + b))
+}
+
+// Transformation - this is the actual geodetic content
+fn transform(
+    options: &Cli,
+    op: OpHandle,
+    number_of_dimensions_in_input: usize,
+    operands: &mut Vec<Coor4D>,
+    annotations: &[String],
+    closure: &mut Closure,
+    ctx: &Plain,
+) -> Result<usize, geodesy::Error> {
+    if operands.is_empty() {
+        return Ok(0);
+    }
+    let output_dimension = options.dimension.unwrap_or(number_of_dimensions_in_input);
+
+    // When roundtripping, we must keep a copy of the input to be able
+    // to compute the roundtrip differences
+    let mut buffer = Vec::new();
+    if options.roundtrip {
+        buffer.clone_from(operands);
+    }
+
+    // Projection factors must be evaluated at the input coordinates
+    let mut factors = Vec::new();
+    if options.factors {
+        let (scale, swap, ellps) = factors_setup(ctx, op)?;
+        for coord in operands.iter() {
+            let at = Coor2D::raw(coord[0], coord[1]);
+            factors.push(Jacobian::new(ctx, op, scale, swap, ellps, at)?.factors());
+        }
+    }
+
+    let mut n = apply(options, ctx, op, !options.inverse, operands)?;
+
+    // Roundtrip
+    let mut errors = Vec::new();
+    let m = if options.roundtrip {
+        let m = apply(options, ctx, op, options.inverse, operands)?;
+        if m != n {
+            return Err(Error::General(
+                "Roundtrip - mismatch between number of Fwd and Inv results",
+            ));
+        }
+
+        let geographic = geographic_setup(ctx, op, options.inverse)?;
+        for (coord, original) in operands.iter_mut().zip(&buffer) {
+            let error = closure_error(geographic.as_ref(), original, coord);
+            closure.add(error);
+            errors.push(error);
+            *coord = *coord - *original;
+        }
+
+        m
+    } else {
+        n
+    };
+
+    n = n.min(m);
+
+    // If the number of output decimals are not given as option "-d",
+    // we try guess a reasonable value, using the heuristic that if
+    // the first coordinate is larger than 1000, the output is most
+    // probably not in degrees. Hence give 5 decimals for linear units,
+    // 10 for angular. In DMS output, 5 decimals on the seconds is ample
+    let decimals = options
+        .decimals
+        .unwrap_or(if options.dms || operands[0][0] > 1000. {
+            5
+        } else {
+            10
+        });
+
+    // Finally output the transformed coordinates
+    let columns = output_columns(options.output_order.as_deref(), output_dimension);
+    if options.output_format == Format::Bin {
+        let mut writer = BufWriter::new(std::io::stdout().lock());
+        for coord in operands.iter() {
+            for &i in &columns {
+                writer.write_all(&coord[i].to_le_bytes())?;
+            }
+        }
+        writer.flush()?;
+        return Ok(n);
+    }
+
+    for (index, coord) in operands.iter().enumerate() {
+        let mut line = String::new();
+        for &i in &columns {
+            line += &format_element(coord, i, decimals, options.dms);
+            line.push(' ');
+        }
+        let mut line = match factors.get(index) {
+            Some(f) => format!(
+                "{line}<{:.8} {:.8} {:.8} {:.8} {:.8} {:.8}>",
+                f.meridian_convergence,
+                f.meridional_scale,
+                f.parallel_scale,
+                f.areal_scale,
+                f.tissot_semimajor,
+                f.tissot_semiminor
+            ),
+            None => line,
+        };
+        if let Some(error) = errors.get(index) {
+            line = format!("{line}{error:.3e}");
+        }
+        if let Some(annotation) = annotations.get(index).filter(|a| !a.is_empty()) {
+            line = format!("{} {annotation}", line.trim_end());
+        }
+        println!("{line}");
+    }
+    Ok(n)
+}
+
+// Roundtrip closure error statistics, and the number of points exceeding
+// the tolerance (if any). Non-finite errors are considered failures
+#[derive(Debug)]
+struct Closure {
+    tolerance: Option<f64>,
+    count: usize,
+    min: f64,
+    max: f64,
+    sum: f64,
+    sum_of_squares: f64,
+    failures: usize,
+}
+
+impl Closure {
+    fn new(tolerance: Option<f64>) -> Self {
+        Closure {
+            tolerance,
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.,
+            sum_of_squares: 0.,
+            failures: 0,
+        }
+    }
+
+    fn add(&mut self, error: f64) {
+        if self
+            .tolerance
+            .is_some_and(|tolerance| error.is_nan() || error > tolerance)
+        {
+            self.failures += 1;
+        }
+        if !error.is_finite() {
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(error);
+        self.max = self.max.max(error);
+        self.sum += error;
+        self.sum_of_squares += error * error;
+    }
+
+    fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    fn rms(&self) -> f64 {
+        (self.sum_of_squares / self.count as f64).sqrt()
+    }
+}
+
+impl std::fmt::Display for Closure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.count == 0 {
+            return write!(f, "Roundtrip closure error (m): No finite values");
+        }
+        write!(
+            f,
+            "Roundtrip closure error (m): n={} min={:.3e} mean={:.3e} max={:.3e} rms={:.3e}",
+            self.count,
+            self.min,
+            self.mean(),
+            self.max,
+            self.rms()
+        )?;
+        if let Some(tolerance) = self.tolerance {
+            write!(f, " exceeding {tolerance:e}: {}", self.failures)?;
+        }
+        Ok(())
+    }
+}
+
+// If the coordinates at the input end of `op` (i.e. at the output end when
+// running inversely) are geographic, as given by an angular `adapt` step,
+// return the size of the angular unit in radians, whether the order is
+// latitude-longitude, and the ellipsoid of the pipeline
+fn geographic_setup(
+    ctx: &dyn Context,
+    op: OpHandle,
+    inverse: bool,
+) -> Result<Option<(f64, bool, Ellipsoid)>, geodesy::Error> {
+    let steps = ctx.steps(op)?.len();
+    let params = ctx.params(op, if inverse { steps.max(1) - 1 } else { 0 })?;
+    if params.name != "adapt" || params.boolean("noop") {
+        return Ok(None);
+    }
+
+    // Depending on the direction, the multiplier converts either to or from
+    // radians, but the unit at the end facing the user is the non-radian one
+    let mult = params.series("mult")?[0].abs();
+    if mult == 1. {
+        return Ok(None);
+    }
+    let unit = if mult > 1. { 1. / mult } else { mult };
+    let swap = params.series("post")?[0] == 1.;
+    let (_, _, ellps) = factors_setup(ctx, op)?;
+    Ok(Some((unit, swap, ellps)))
+}
+
+// The distance (in meters) between the original and roundtripped versions of
+// a point. For geographic coordinates, the ellipsoidal distance combined with
+// the height difference, otherwise the 3D euclidean distance
+fn closure_error(
+    geographic: Option<&(f64, bool, Ellipsoid)>,
+    original: &Coor4D,
+    roundtripped: &Coor4D,
+) -> f64 {
+    let dz = roundtripped[2] - original[2];
+    let Some((unit, swap, ellps)) = geographic else {
+        let dx = roundtripped[0] - original[0];
+        let dy = roundtripped[1] - original[1];
+        return dx.hypot(dy).hypot(dz);
+    };
+    let lonlat = |c: &Coor4D| {
+        let (lon, lat) = if *swap { (c[1], c[0]) } else { (c[0], c[1]) };
+        Coor2D::raw(lon * unit, lat * unit)
+    };
+    let distance = ellps.geodesic_inv(&lonlat(original), &lonlat(roundtripped))[2];
+    distance.hypot(dz)
+}
+
+// The (zero based) indices of the coordinate elements to output: Either
+// as given by `order` (one based), or the first `dimension` elements
+fn output_columns(order: Option<&[usize]>, dimension: usize) -> Vec<usize> {
+    if let Some(order) = order {
+        return order.iter().map(|i| i - 1).collect();
+    }
+    match dimension {
+        1..=3 => (0..dimension).collect(),
+        _ => (0..4).collect(),
+    }
+}
+
+// Format element `i` of `coord` for text output. In DMS mode, the first
+// two elements are taken to be latitude and longitude, in degrees
+fn format_element(coord: &Coor4D, i: usize, decimals: usize, dms: bool) -> String {
+    match (dms, i) {
+        (true, 0) => angular::format_sexagesimal(coord[0], decimals, ['N', 'S']),
+        (true, 1) => angular::format_sexagesimal(coord[1], decimals, ['E', 'W']),
+        _ => format!("{:.1$}", coord[i], decimals),
+    }
+}
+
+// The scaling and axis swapping needed for computing the projection factors
+// of `op`. Input adaptation is read from the first step, if it is an `adapt`
+// step, otherwise input is assumed to be longitude/latitude in radians.
+// Similarly, output is assumed to be easting/northing unless the last step
+// is an axis swapping `adapt` step. The ellipsoid is taken from the first
+// step having one, and defaults to GRS80
+fn factors_setup(
+    ctx: &dyn Context,
+    op: OpHandle,
+) -> Result<([f64; 2], [bool; 2], Ellipsoid), geodesy::Error> {
+    let steps = ctx.steps(op)?.len();
+    let mut scale = [1f64.to_degrees(), 1.];
+    let mut swap = [false, false];
+
+    let first = ctx.params(op, 0)?;
+    if first.name == "adapt" {
+        let post = first.series("post")?;
+        let mult = first.series("mult")?;
+        let mult = if first.boolean("inv") {
+            1. / mult[0]
+        } else {
+            mult[0]
+        };
+        scale[0] = mult.abs().to_degrees();
+        swap[0] = post[0] == 1.;
+    }
+
+    let last = ctx.params(op, steps.max(1) - 1)?;
+    if steps > 1 && last.name == "adapt" {
+        swap[1] = last.series("post")?[0] == 1.;
+    }
+
+    let mut ellps = Ellipsoid::default();
+    for index in 0..steps {
+        let params = ctx.params(op, index)?;
+        if params.text.contains_key("ellps") {
+            ellps = params.ellps(0)?;
+            break;
+        }
+    }
+    Ok((scale, swap, ellps))
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_eq::assert_float_eq;
+
+    fn some_basic_coordinates() -> [Coor4D; 2] {
+        let copenhagen = Coor4D::raw(55., 12., 0., 0.);
+        let stockholm = Coor4D::raw(59., 18., 0., 0.);
+        [copenhagen, stockholm]
+    }
+
+    #[test]
+    fn introspection() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        let op = ctx.op("geo:in | utm zone=32 | neu:out")?;
+
+        let mut data = some_basic_coordinates();
+        let expected = [6098907.825005002, 691875.6321396609, 0., 0.];
+
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_float_eq!(data[0].0, expected, abs_all <= 1e-9);
+
+        // The text definitions of each step
+        let steps = ctx.steps(op)?;
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0], "geo:in");
+        assert_eq!(steps[1], "utm zone=32");
+        assert_eq!(steps[2], "neu:out");
+
+        // Behind the curtains, the two i/o-macros are just calls to the 'adapt' operator
+        assert_eq!("adapt", ctx.params(op, 0)?.name);
+        assert_eq!("adapt", ctx.params(op, 2)?.name);
+
+        // While the utm step really is the 'utm' operator, not 'tmerc'-with-extras
+        assert_eq!("utm", ctx.params(op, 1)?.name);
+
+        // All the 'common' elements (lat_?, lon_?, x_?, y_? etc.) defaults to 0,
         // while ellps_? defaults to GRS80 - so they are there even though we havent
         // set them
         let params = ctx.params(op, 1)?;
-        let ellps = params.ellps(0);
+        let ellps = params.ellps(0)?;
         assert_eq!(ellps.semimajor_axis(), 6378137.);
         assert_eq!(0., ctx.params(op, 1)?.lat(0));
 
@@ -306,4 +1709,420 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn factors() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Input in degrees, latitude first, ellipsoid from the projection step
+        let op = ctx.op("geo:in | utm zone=32 ellps=intl | neu:out")?;
+        let (scale, swap, ellps) = factors_setup(&ctx, op)?;
+        assert_float_eq!(scale, [1., 1.], abs_all <= 1e-12);
+        assert_eq!(swap, [true, true]);
+        assert_eq!(ellps.semimajor_axis(), 6378388.);
+
+        // No adaptor: Input in radians, GIS axis order, and GRS80
+        let op = ctx.op("merc")?;
+        let (scale, swap, ellps) = factors_setup(&ctx, op)?;
+        assert_float_eq!(scale, [1f64.to_degrees(), 1.], abs_all <= 1e-12);
+        assert_eq!(swap, [false, false]);
+        assert_eq!(ellps.semimajor_axis(), 6378137.);
+
+        // Mercator is conformal, with no meridian convergence
+        let op = ctx.op("gis:in | merc")?;
+        let (scale, swap, ellps) = factors_setup(&ctx, op)?;
+        let at = Coor2D::raw(12., 55.);
+        let f = Jacobian::new(&ctx, op, scale, swap, ellps, at)?.factors();
+        assert!(f.meridian_convergence.abs() < 1e-12);
+        assert!((f.meridional_scale - f.parallel_scale).abs() < 1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn binary_records() -> Result<(), Error> {
+        let data: Vec<u8> = [55_f64, 12., 59., 18.]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+
+        // Two complete 2D records, then a clean end-of-file
+        let mut reader: &[u8] = &data;
+        let mut record = [0_u8; 16];
+        assert!(read_record(&mut reader, &mut record)?);
+        assert_eq!(f64::from_le_bytes(record[8..].try_into().unwrap()), 12.);
+        assert!(read_record(&mut reader, &mut record)?);
+        assert_eq!(f64::from_le_bytes(record[..8].try_into().unwrap()), 59.);
+        assert!(!read_record(&mut reader, &mut record)?);
+
+        // A 3D record cannot be read from 32 bytes without truncation
+        let mut reader: &[u8] = &data;
+        let mut record = [0_u8; 24];
+        assert!(read_record(&mut reader, &mut record)?);
+        assert!(read_record(&mut reader, &mut record).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parallel() -> Result<(), Error> {
+        let mut ctx = Plain::new();
+        let op = ctx.op("geo:in | utm zone=32")?;
+        let input: Vec<Coor4D> = (0..1001)
+            .map(|i| Coor4D::raw(50. + i as f64 / 100., 12., 0., 0.))
+            .collect();
+
+        let serial = Cli::parse_from(["kp", "geo:in | utm zone=32"]);
+        let mut expected = input.clone();
+        assert_eq!(apply(&serial, &ctx, op, true, &mut expected)?, 1001);
+
+        // Parallel processing gives the same results, in the same order
+        let parallel = Cli::parse_from(["kp", "-j", "4", "geo:in | utm zone=32"]);
+        let mut operands = input.clone();
+        assert_eq!(apply(&parallel, &ctx, op, true, &mut operands)?, 1001);
+        assert_eq!(operands, expected);
+
+        // ...also in the inverse direction
+        assert_eq!(apply(&parallel, &ctx, op, false, &mut operands)?, 1001);
+        for (a, b) in operands.iter().zip(input) {
+            assert!((a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn geojson() -> Result<(), Error> {
+        let mut ctx = Plain::new();
+        let op = ctx.op("gis:in | utm zone=32")?;
+        let options = Cli::parse_from([
+            "kp",
+            "--format",
+            "geojson",
+            "-d",
+            "3",
+            "gis:in | utm zone=32",
+        ]);
+
+        let mut document: serde_json::Value = serde_json::from_str(
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"name": "Copenhagen", "coordinates": [1, 2]},
+                 "geometry": {"type": "Point", "coordinates": [12, 55]}},
+                {"type": "Feature", "properties": null,
+                 "geometry": {"type": "GeometryCollection", "geometries": [
+                    {"type": "LineString", "coordinates": [[12, 55, 100], [18, 59, 200]]},
+                    {"type": "MultiPolygon", "coordinates": [[[[12, 55], [18, 59], [12, 59], [12, 55]]]]}
+                 ]}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(geojson_transform(&options, &ctx, op, &mut document)?, 7);
+
+        // Points, lines, and multipolygons within collections are transformed...
+        let point = &document["features"][0]["geometry"]["coordinates"];
+        assert_eq!(point, &serde_json::json!([691875.632, 6098907.825]));
+        let line = &document["features"][1]["geometry"]["geometries"][0]["coordinates"];
+        assert_eq!(line[0], serde_json::json!([691875.632, 6098907.825, 100.0]));
+        assert_eq!(
+            line[1],
+            serde_json::json!([1016066.614, 6574904.395, 200.0])
+        );
+        let polygon = &document["features"][1]["geometry"]["geometries"][1]["coordinates"];
+        let expected = serde_json::json!([1016066.614, 6574904.395]);
+        assert_eq!(polygon[0][0][1], expected);
+
+        // ...retaining the dimensionality of each position, while the
+        // properties are left untouched
+        let properties = &document["features"][0]["properties"];
+        assert_eq!(properties["coordinates"], serde_json::json!([1, 2]));
+        assert_eq!(
+            document["features"][1]["properties"],
+            serde_json::Value::Null
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn csv() -> Result<(), anyhow::Error> {
+        let mut ctx = Plain::new();
+        let op = ctx.op("gis:in | utm zone=32")?;
+        let args = ["kp", "--csv", "--x-col", "lon", "--y-col", "lat", "-d", "3"];
+        let options = Cli::parse_from(args.into_iter().chain(["gis:in | utm zone=32"]));
+
+        // Transform `input`, returning the header and the output text
+        let run = |options: &Cli, input: &str, header| -> Result<_, anyhow::Error> {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            let header = csv_transform(options, &ctx, op, input.as_bytes(), &mut writer, header)?;
+            Ok((header, String::from_utf8(writer.into_inner()?)?))
+        };
+
+        // Only the selected columns are transformed, while everything else,
+        // including the quoting of fields containing the delimiter, survives
+        let input = "name,lon,lat,h\nCopenhagen,12,55,10\n\"Stockholm, Sweden\",18,59,20\n";
+        let (header, output) = run(&options, input, None)?;
+        let expected = "name,lon,lat,h\n\
+            Copenhagen,691875.632,6098907.825,10\n\
+            \"Stockholm, Sweden\",1016066.614,6574904.395,20\n";
+        assert_eq!(output, expected);
+
+        // Subsequent files must have the same header, which is not repeated
+        let input = "name,lon,lat,h\nCopenhagen,12,55,10\n";
+        let (_, output) = run(&options, input, Some(header.clone()))?;
+        assert_eq!(output, "Copenhagen,691875.632,6098907.825,10\n");
+        let input = "name,lat,lon\nCopenhagen,55,12\n";
+        assert!(run(&options, input, Some(header)).is_err());
+
+        // Unknown columns are reported
+        let args = [
+            "kp",
+            "--csv",
+            "--x-col",
+            "x",
+            "--y-col",
+            "lat",
+            "utm zone=32",
+        ];
+        let options = Cli::parse_from(args);
+        let input = "name,lon,lat,h\nCopenhagen,12,55,10\n";
+        assert!(run(&options, input, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn annotations() {
+        let line = "P17 55 12 # Copenhagen\n";
+        let mut args = text_args(line);
+        assert_eq!(annotation(false, None, line, &mut args), "");
+        assert_eq!(args, ["P17", "55", "12"]);
+
+        // The tag column is removed from the coordinate...
+        assert_eq!(annotation(false, Some(1), line, &mut args), "P17");
+        assert_eq!(args, ["55", "12"]);
+
+        // ...while the echo is the full input record, as a comment
+        let mut args = text_args(line);
+        let expected = "P17 # P17 55 12 # Copenhagen";
+        assert_eq!(annotation(true, Some(1), line, &mut args), expected);
+
+        // Missing tag columns result in empty tags
+        let mut args = text_args(line);
+        assert_eq!(annotation(false, Some(4), line, &mut args), "");
+        assert_eq!(args.len(), 3);
+    }
+
+    #[test]
+    fn output_formatting() {
+        assert_eq!(output_columns(None, 0), [0, 1, 2, 3]);
+        assert_eq!(output_columns(None, 2), [0, 1]);
+        assert_eq!(output_columns(Some(&[2, 1, 3]), 2), [1, 0, 2]);
+
+        let coord = Coor4D::raw(55.51, -12.0014, 10., f64::NAN);
+        assert_eq!(format_element(&coord, 0, 3, false), "55.510");
+        assert_eq!(format_element(&coord, 0, 3, true), "55°30'36.000\"N");
+        assert_eq!(format_element(&coord, 1, 1, true), "12°00'05.0\"W");
+        assert_eq!(format_element(&coord, 2, 1, true), "10.0");
+        assert_eq!(format_element(&coord, 3, 1, true), "NaN");
+    }
+
+    #[test]
+    fn roundtrip() -> Result<(), Error> {
+        let mut ctx = Plain::new();
+
+        // Geographic input: Ellipsoidal distance, here 1 arcsecond of
+        // latitude, i.e. approximately 30.9 m at 55N
+        let op = ctx.op("geo:in | utm zone=32")?;
+        let geographic = geographic_setup(&ctx, op, false)?;
+        let (unit, swap, _) = geographic.unwrap();
+        assert!((unit - 1f64.to_radians()).abs() < 1e-15);
+        assert!(swap);
+        let a = Coor4D::raw(55., 12., 0., 0.);
+        let b = Coor4D::raw(55. + 1. / 3600., 12., 0., 0.);
+        let error = closure_error(geographic.as_ref(), &a, &b);
+        assert!((error - 30.92).abs() < 0.01);
+
+        // ...but projected at the other end
+        assert!(geographic_setup(&ctx, op, true)?.is_none());
+        let a = Coor4D::raw(691875., 6098907., 0., 0.);
+        let b = Coor4D::raw(691878., 6098911., 0., 0.);
+        assert_eq!(closure_error(None, &a, &b), 5.);
+
+        // Aggregate statistics and tolerance checks
+        let mut closure = Closure::new(Some(3.));
+        for error in [1., 2., 4., f64::NAN] {
+            closure.add(error);
+        }
+        assert_eq!(closure.count, 3);
+        assert_eq!(closure.failures, 2);
+        assert_eq!((closure.min, closure.max), (1., 4.));
+        assert!((closure.mean() - 7. / 3.).abs() < 1e-15);
+        assert!((closure.rms() - 7f64.sqrt()).abs() < 1e-15);
+        Ok(())
+    }
+
+    #[test]
+    fn gridinfo() -> Result<(), anyhow::Error> {
+        let buf = std::fs::read("geodesy/gsb/5458_with_subgrid.gsb")?;
+        let grid = Ntv2Grid::new(&buf)?;
+        let report = grid_report(&grid, &[[55.5, 13.], [57., 10.], [40., 10.]]);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Bands:      2");
+        assert_eq!(lines[1], "Subgrids:   2");
+        assert_eq!(lines[2], "Subgrid 5458 (parent: NONE)");
+        assert!(lines[3].contains("lat 54.000000 .. 58.000000"));
+
+        // The subgrid tree is reflected by the indentation
+        assert_eq!(lines[6], "    Subgrid 5556 (parent: 5458)");
+
+        // Points are reported with the containing subgrid
+        assert!(lines[10].starts_with("Point 55.5 13: subgrid 5556, value "));
+        assert!(lines[11].starts_with("Point 57 10: subgrid 5458, value "));
+        assert_eq!(lines[12], "Point 40 10: outside of grid");
+
+        // Single grids have no name
+        let buf = std::fs::read("geodesy/geoid/test.geoid")?;
+        let grid = BaseGrid::gravsoft(&buf)?;
+        let report = grid_report(&grid, &[[55., 12.]]);
+        assert!(report.contains("Subgrid - (parent: NONE)"));
+        assert!(report.contains("Point 55 12: subgrid -, value 55.1199"));
+        Ok(())
+    }
+
+    #[test]
+    fn operator_docs() -> Result<(), Error> {
+        let list = operator_documentation(None)?;
+        assert!(list.lines().any(|l| l.starts_with("tmerc ")));
+        assert_eq!(list.lines().count(), builtin_operators().len());
+
+        let tmerc = operator_documentation(Some(&"tmerc".to_string()))?;
+        assert!(tmerc.starts_with("tmerc: Transverse Mercator"));
+        assert!(tmerc.contains("    inv          flag"));
+        assert!(tmerc.contains("    ellps        text     default: GRS80"));
+        assert!(tmerc.contains("    k_0          real     default: 1"));
+
+        let utm = operator_documentation(Some(&"utm".to_string()))?;
+        assert!(utm.contains("    zone         natural  required"));
+        assert!(utm.contains("Test vectors:\n    PROJ, cct +proj=utm +zone=32"));
+
+        assert!(operator_documentation(Some(&"no such op".to_string())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn operation_from_file() -> Result<(), anyhow::Error> {
+        let dir = std::env::temp_dir();
+        let first = dir.join("kp-test-first.geodesy");
+        let second = dir.join("kp-test-second.geodesy");
+        std::fs::write(
+            &first,
+            "# Input\ngeo:in  # degrees, lat/lon\n| utm\n: zone=32\n",
+        )?;
+        std::fs::write(&second, "\n# Output\nneu:out\n")?;
+
+        let operation = operation_from_files(&[first.clone(), second.clone()])?;
+        let mut ctx = Plain::new();
+        let op = ctx.op(&operation)?;
+        assert_eq!(*ctx.steps(op)?, vec!["geo:in", "utm zone=32", "neu:out"]);
+
+        // With the operation read from file, the positional argument is an
+        // input file
+        let options = Cli::parse_from(["kp", "-f", "a.geodesy", "-f", "b.geodesy", "data.txt"]);
+        assert_eq!(options.file.len(), 2);
+        assert_eq!(options.operation, "data.txt");
+
+        std::fs::remove_file(first)?;
+        std::fs::remove_file(second)?;
+        assert!(operation_from_files(&[dir.join("kp-test-no-such-file")]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn streaming() -> Result<(), anyhow::Error> {
+        // Invalid UTF-8 is replaced, not fatal
+        let mut reader = BufReader::new(&b"55 12\n55 \xff\n"[..]);
+        let mut line = String::new();
+        assert_eq!(read_line_lossy(&mut reader, &mut line)?, 6);
+        assert_eq!(line, "55 12\n");
+        line.clear();
+        assert_eq!(read_line_lossy(&mut reader, &mut line)?, 5);
+        assert_eq!(line, "55 \u{FFFD}\n");
+        line.clear();
+        assert_eq!(read_line_lossy(&mut reader, &mut line)?, 0);
+
+        // ...but makes the record malformed
+        assert!(malformed(&text_args("55 \u{FFFD}")));
+        assert!(malformed(&text_args("$GPGGA,123519,4807.038,N")));
+        assert!(!malformed(&text_args("55:30N 12E 10 2020 # comment")));
+
+        // Only the coordinate elements are checked
+        assert!(!malformed(&text_args("55 12 0 0 extra")));
+
+        let counts = StreamCounts::new();
+        counts.read.fetch_add(3, Ordering::Relaxed);
+        counts.transformed.fetch_add(2, Ordering::Relaxed);
+        counts.skipped.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(
+            counts.to_string(),
+            "Streamed 3 records: 2 transformed, 1 malformed records skipped"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bench() -> Result<(), Error> {
+        let mut ctx = Plain::new();
+        let input = some_basic_coordinates();
+
+        let options = Cli::parse_from(["kp", "--bench", "10", "geo:in | utm zone=32"]);
+        let op = ctx.op(&options.operation)?;
+        let rates = benchmark(&options, &mut ctx, op, &input, 10)?;
+        let steps: Vec<&str> = rates.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(steps, ["geo:in", "utm zone=32", "(total)"]);
+        assert!(rates.iter().all(|r| r.1 > 0.));
+
+        // Inverse: The steps are timed in reverse order
+        let options = Cli::parse_from(["kp", "--inv", "--bench", "10", "geo:in | utm zone=32"]);
+        let rates = benchmark(&options, &mut ctx, op, &input, 10)?;
+        let steps: Vec<&str> = rates.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(steps, ["utm zone=32", "geo:in", "(total)"]);
+
+        // Steps depending on their neighbours are not timed in isolation
+        let op = ctx.op("geo:in | push v_3 | utm zone=32 | pop v_3")?;
+        let rates = benchmark(&options, &mut ctx, op, &input, 10)?;
+        assert_eq!(rates.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn gie() -> Result<(), anyhow::Error> {
+        let file = std::env::temp_dir().join("kp-test.gie");
+        std::fs::write(
+            &file,
+            "operation +proj=utm +zone=32\naccept 12 55\nexpect 691875.6321 6098907.8250\n",
+        )?;
+        let name = file.to_string_lossy().to_string();
+        let options = Cli::parse_from(["kp", "test", &name]);
+        assert!(super::gie(&options).is_ok());
+
+        std::fs::write(&file, "operation utm zone=32\naccept 12 55\nexpect 0 0\n")?;
+        assert!(super::gie(&options).is_err());
+        std::fs::remove_file(file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn self_test() -> Result<(), Error> {
+        let mut ctx = Plain::new();
+        let op = ctx.op("geo:in | utm zone=32 | neu:out")?;
+        let (report, failed) = selftest(&mut ctx, op)?;
+        assert_eq!(failed, 0);
+        assert!(report.starts_with("PASS utm: PROJ, cct +proj=utm +zone=32"));
+        assert!(report.ends_with("1 test vectors, 1 passed, 0 failed\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn stations() {
+        assert_eq!(geodesic_stations(100., None), [0., 100.]);
+        assert_eq!(geodesic_stations(100., Some(40.)), [0., 40., 80., 100.]);
+        assert_eq!(geodesic_stations(100., Some(50.)), [0., 50., 100.]);
+        assert_eq!(geodesic_stations(-100., Some(50.)), [0., -50., -100.]);
+        assert_eq!(geodesic_stations(0., Some(50.)), [0.]);
+    }
 }
@@ -40,6 +40,9 @@ impl Context for Minimal {
         direction: Direction,
         operands: &mut dyn CoordinateSet,
     ) -> Result<usize, Error> {
+        if let Some(epoch) = operands.coordinate_epoch() {
+            return self.apply_at_epoch(op, direction, operands, epoch.value());
+        }
         let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
         Ok(op.apply(self, operands, direction))
     }
@@ -48,6 +51,39 @@ impl Context for Minimal {
         BTreeMap::from([("ellps".to_string(), "GRS80".to_string())])
     }
 
+    fn partial_derivatives(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Result<Option<[f64; 4]>, Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        Ok(op.partial_derivatives(self, direction, at))
+    }
+
+    fn composed_partial_derivatives(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Result<Option<[f64; 4]>, Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        Ok(op.composed_partial_derivatives(self, direction, at))
+    }
+
+    fn set_crs(&mut self, op: OpHandle, source: Crs, target: Crs) -> Result<(), Error> {
+        let op = self.operators.get_mut(&op).ok_or(BAD_ID_MESSAGE)?;
+        op.descriptor.source_crs = source;
+        op.descriptor.target_crs = target;
+        Ok(())
+    }
+
+    fn crs(&self, op: OpHandle) -> Result<(Crs, Crs), Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        let descriptor = &op.descriptor;
+        Ok((descriptor.source_crs.clone(), descriptor.target_crs.clone()))
+    }
+
     fn steps(&self, op: OpHandle) -> Result<&Vec<String>, Error> {
         let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
         Ok(&op.descriptor.steps)
@@ -70,6 +106,11 @@ impl Context for Minimal {
         Ok(op.steps[index].params.clone())
     }
 
+    fn snapshot(&self, op: OpHandle) -> Result<OpSnapshot, Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        Ok(op.snapshot())
+    }
+
     fn register_op(&mut self, name: &str, constructor: OpConstructor) {
         self.constructors.insert(String::from(name), constructor);
     }
@@ -154,12 +195,221 @@ mod tests {
         assert_eq!(data[1].x(), 59.);
 
         let params = ctx.params(op, 1)?;
-        let ellps = params.ellps(0);
+        let ellps = params.ellps(0)?;
         assert_eq!(ellps.semimajor_axis(), 6378137.);
 
         Ok(())
     }
 
+    #[test]
+    fn roundtrip() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Geographical coordinates are compared on the ellipsoid
+        let op = ctx.op("utm zone=32")?;
+        let mut data = [Coor4D::geo(55., 12., 0., 0.), Coor4D::geo(59., 18., 0., 0.)];
+        let metric = Metric::Geodesic(Ellipsoid::named("GRS80")?);
+        let errors = ctx.roundtrip(op, &mut data, 1e-6, metric)?;
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| *e < 1e-6));
+        assert!((data[0][1].to_degrees() - 55.).abs() < 1e-12);
+
+        // A deliberately non-invertible pipeline
+        let op = ctx.op("addone | addone omit_inv")?;
+        let mut data = crate::test_data::coor2d();
+        let errors = ctx.roundtrip(op, &mut data, f64::INFINITY, Metric::Planar)?;
+        assert_eq!(errors, [1., 1.]);
+        assert_eq!(data[0][0], 56.);
+        let mut data = crate::test_data::coor3d();
+        assert!(ctx
+            .roundtrip(op, &mut data, 0.5, Metric::Cartesian)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_slice() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | cart | helmert x=1 y=2 z=3 | cart inv | geo:out")?;
+
+        // More points than fit in a single chunk
+        let reference: Vec<Coor4D> = (0..2500)
+            .map(|i| Coor4D::raw(55. + i as f64 * 1e-3, 12., 0., 0.))
+            .collect();
+        let mut expected = reference.clone();
+        assert_eq!(ctx.apply(op, Fwd, &mut expected)?, 2500);
+
+        // 2D coordinates keep their height through the intermediate
+        // cartesian steps, since these are carried out in 4D. But the
+        // NaN time coordinate makes `cart` report failure
+        let mut data: Vec<Coor2D> = reference.iter().map(|c| Coor2D::raw(c[0], c[1])).collect();
+        assert_eq!(ctx.apply_slice(op, Fwd, &mut data)?, 0);
+        for (d, e) in data.iter().zip(&expected) {
+            assert_eq!(d.0, [e[0], e[1]]);
+        }
+        ctx.apply_slice(op, Inv, &mut data[..])?;
+        assert!((data[2499][0] - 57.499).abs() < 1e-9);
+
+        // ...and 4D coordinates give the same results as `apply()`
+        let mut data = reference.clone();
+        assert_eq!(ctx.apply_slice(op, Fwd, &mut data)?, 2500);
+        assert_eq!(data, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_into() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("geo:in | utm zone=32 | helmert x=1")?;
+
+        // More points than fit in a single chunk
+        let input: Vec<Coor4D> = (0..2500)
+            .map(|i| Coor4D::raw(55. + i as f64 * 1e-3, 12., 0., 0.))
+            .collect();
+        let mut expected = input.clone();
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        let mut output = vec![Coor4D::default(); 2500];
+        assert_eq!(ctx.apply_into(op, Fwd, &input, &mut output)?, 2500);
+        assert_eq!(output, expected);
+        assert_eq!(input[2499][0], 57.499);
+
+        // Different types in and out
+        let input2d: Vec<Coor2D> = input.iter().map(|c| Coor2D::raw(c[0], c[1])).collect();
+        let mut output = vec![Coor4D::default(); 2500];
+        ctx.apply_into(op, Fwd, &input2d, &mut output)?;
+        assert_eq!(output[17].xy(), expected[17].xy());
+
+        // The convenience version
+        let output = ctx.apply_to_vec(op, Fwd, &input2d)?;
+        assert_eq!(output[17].0, [expected[17][0], expected[17][1]]);
+        let back = ctx.apply_to_vec(op, Inv, &output)?;
+        assert!((back[2499][0] - 57.499).abs() < 1e-9);
+
+        // Too short output
+        let mut output = vec![Coor4D::default(); 10];
+        assert!(ctx.apply_into(op, Fwd, &input, &mut output).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_with_covariance() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let sigma = 0.01_f64;
+        let isotropic = [
+            sigma * sigma,
+            0.,
+            0.,
+            0.,
+            sigma * sigma,
+            0.,
+            0.,
+            0.,
+            sigma * sigma,
+        ];
+
+        // Numerical differentiation: A scaling helmert scales the covariances,
+        // by the square of the scale factor
+        let op = ctx.op("helmert s=1000")?;
+        let mut operands = [Coor4D::raw(3e6, 1e6, 5e6, 0.)];
+        let mut covariances = [isotropic];
+        assert_eq!(
+            ctx.apply_with_covariance(op, Fwd, &mut operands, &mut covariances)?,
+            1
+        );
+        for (i, c) in covariances[0].iter().enumerate() {
+            assert!((c - isotropic[i] * 1.001 * 1.001).abs() < 1e-12);
+        }
+
+        // ...and a rotation preserves the trace
+        let op = ctx.op("helmert rz=3600 exact")?;
+        let mut covariances = [[1e-4, 0., 0., 0., 4e-4, 0., 0., 0., 9e-4]];
+        ctx.apply_with_covariance(op, Fwd, &mut operands, &mut covariances)?;
+        let c = covariances[0];
+        assert!((c[0] + c[4] + c[8] - 14e-4).abs() < 1e-12);
+        assert!(c[1] != 0. && (c[1] - c[3]).abs() < 1e-15);
+
+        // Analytical derivatives: At the equator, a Mercator easting varies
+        // with longitude by the semimajor axis
+        let op = ctx.op("merc")?;
+        let a = Ellipsoid::default().semimajor_axis();
+        let s = 1e-8;
+        let mut operands = [Coor2D::raw(0.1, 0.)];
+        let mut covariances = [[s * s, 0., 0., 0., 0., 0., 0., 0., 0.]];
+        ctx.apply_with_covariance(op, Fwd, &mut operands, &mut covariances)?;
+        assert!((covariances[0][0].sqrt() - a * s).abs() < 1e-9);
+        assert_eq!(covariances[0][4], 0.);
+
+        // Failing points get NaN covariances
+        let op = ctx.op("geo:in | utm zone=32")?;
+        let mut operands = [Coor4D::raw(f64::NAN, 12., 0., 0.)];
+        let mut covariances = [isotropic];
+        ctx.apply_with_covariance(op, Fwd, &mut operands, &mut covariances)?;
+        assert!(covariances[0][0].is_nan());
+
+        // Too few covariances
+        assert!(ctx
+            .apply_with_covariance(op, Fwd, &mut operands, &mut [])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn crs() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op_with_crs("geo:in | utm zone=32", "epsg:4258", "EPSG:25832")?;
+        let (source, target) = ctx.crs(op)?;
+        assert_eq!(source, Crs::RegisterItem("EPSG".into(), "4258".into()));
+        assert_eq!(target.to_string(), "EPSG:25832");
+
+        // Free text and unknown CRS
+        let op = ctx.op_with_crs("helmert x=1", "ETRS89 / DKTM1", "")?;
+        let (source, target) = ctx.crs(op)?;
+        assert_eq!(source, Crs::Text("ETRS89 / DKTM1".into()));
+        assert!(!target.is_known());
+        let noop = ctx.op("noop")?;
+        assert_eq!(ctx.crs(noop)?, (Crs::Unknown, Crs::Unknown));
+
+        // Operands without CRS metadata are accepted as is
+        let mut operands = [Coor2D::raw(1., 2.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 1);
+        assert_eq!(operands[0], Coor2D::raw(2., 2.));
+        Ok(())
+    }
+
+    #[test]
+    fn selftest() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Each operator is tested once, with its own test vectors
+        let op = ctx.op("geo:in | utm zone=32 | cart inv | helmert x=1 | cart | neu:out")?;
+        let results = ctx.selftest(op)?;
+        let operators: Vec<&str> = results.iter().map(|r| r.operator.as_str()).collect();
+        assert_eq!(operators, ["utm", "cart", "helmert"]);
+        assert!(results.iter().all(|r| r.passed));
+        assert!(results[1].deviation[0].abs() < 1e-3);
+
+        // Operators are tested by their own definitions, e.g. `utm` by `utm`
+        for vector in builtin_operator("utm")?.test_vectors {
+            assert!(vector.definition.contains("utm zone="));
+        }
+
+        // All builtin test vectors pass
+        for documentation in builtin_operators() {
+            for vector in documentation.test_vectors {
+                let op = ctx.op(vector.definition)?;
+                let results = ctx.selftest(op)?;
+                assert!(!results.is_empty());
+                assert!(results.iter().all(|r| r.passed), "{results:#?}");
+            }
+        }
+
+        // Operators without test vectors are silently passed by
+        let op = ctx.op("addone")?;
+        assert!(ctx.selftest(op)?.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn introspection() -> Result<(), Error> {
         let mut ctx = Minimal::new();
@@ -194,7 +444,7 @@ mod tests {
         // while ellps_? defaults to GRS80 - so they are there even though we havent
         // set them
         let params = ctx.params(op, 1)?;
-        let ellps = params.ellps(0);
+        let ellps = params.ellps(0)?;
         assert_eq!(ellps.semimajor_axis(), 6378137.);
         assert_eq!(0., params.real("lat_0")?);
 
@@ -224,7 +474,7 @@ mod tests {
         let op = ctx.op("utm zone=32")?;
         let steps = ctx.steps(op)?;
         assert!(steps.len() == 1);
-        let ellps = ctx.params(op, 0)?.ellps(0);
+        let ellps = ctx.params(op, 0)?.ellps(0)?;
         let jac = Jacobian::new(
             &ctx,
             op,
@@ -270,12 +520,14 @@ mod tests {
 
         // Then input in degrees (i.e. no scaling), scaling on output, and swapping on both input and output
         // (yes - the 'helmert s=3e6' scales by a factor of 4: 1 + 3 million ppm = 4)
+        // The helmert step is differentiated numerically in the projected domain,
+        // hence the slightly larger tolerance
         let op = ctx.op("geo:in | utm zone=32 |neu:out | helmert s=3e6")?;
         let jac = Jacobian::new(&ctx, op, [1., 0.25], [true, true], ellps, cph)?;
         assert_float_eq!(
             [jac.dx_dlam, jac.dy_dlam, jac.dx_dphi, jac.dy_dphi],
             expected,
-            abs_all <= 1e-12
+            abs_all <= 1e-10
         );
 
         Ok(())
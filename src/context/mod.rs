@@ -8,6 +8,9 @@ pub mod plain;
 
 // ----- T H E   C O N T E X T   T R A I T ---------------------------------------------
 
+// The number of coordinate tuples handled at a time by `Context::apply_slice()`
+const APPLY_CHUNK_SIZE: usize = 1024;
+
 /// Modes of communication between the *Rust Geodesy* internals and the external
 /// world (i.e. resources like grids, transformation definitions, or ellipsoid parameters).
 pub trait Context {
@@ -20,7 +23,54 @@ pub trait Context {
     /// Instantiate the operation given by `definition`
     fn op(&mut self, definition: &str) -> Result<OpHandle, Error>;
 
-    /// Apply operation `op` to `operands`
+    /// Instantiate the operation given by `definition`, and record `source` and
+    /// `target` (e.g. `EPSG:4326` or free text) as the CRS of its input and
+    /// output, for later retrieval by [`crs()`](Self::crs). When the operation
+    /// is applied to operands with a known, but different, CRS (as given by
+    /// [`CoordinateMetadata::crs()`]), a warning is logged.
+    /// Empty strings indicate an unknown CRS.
+    fn op_with_crs(
+        &mut self,
+        definition: &str,
+        source: &str,
+        target: &str,
+    ) -> Result<OpHandle, Error> {
+        let op = self.op(definition)?;
+        self.set_crs(op, Crs::new(source), Crs::new(target))?;
+        Ok(op)
+    }
+
+    /// Record `source` and `target` as the CRS of the input and output of `op`.
+    /// Cf. [`op_with_crs()`](Self::op_with_crs)
+    fn set_crs(&mut self, _op: OpHandle, _source: Crs, _target: Crs) -> Result<(), Error> {
+        Err(Error::Unsupported("set_crs".to_string()))
+    }
+
+    /// The CRS of the input and output of `op`, as recorded by
+    /// [`op_with_crs()`](Self::op_with_crs)
+    fn crs(&self, _op: OpHandle) -> Result<(Crs, Crs), Error> {
+        Ok((Crs::Unknown, Crs::Unknown))
+    }
+
+    /// Instantiate the transformation from the output of `src` to the output
+    /// of `dst`, e.g. from one projected CRS to another, when both are given by
+    /// definitions starting from the same geographical coordinates. The result
+    /// is the inverse of `src`, followed by `dst`. With `optimize`, steps shared
+    /// by the leading parts of `src` and `dst` are removed, since they would cancel
+    /// each other anyway - unless they are lossy (e.g. `round`), or have no inverse.
+    /// Cf. [`between()`](crate::ctx::between) for the definition
+    fn between(&mut self, src: &str, dst: &str, optimize: bool) -> Result<OpHandle, Error>
+    where
+        Self: Sized,
+    {
+        let definition = crate::op::between(src, dst, optimize, self)?;
+        self.op(&definition)
+    }
+
+    /// Apply operation `op` to `operands`. If the operands provide a
+    /// [coordinate epoch](CoordinateMetadata::coordinate_epoch), it should be
+    /// used as the observation time for coordinate tuples lacking one, e.g.
+    /// by deferring to [`apply_at_epoch()`](Self::apply_at_epoch)
     fn apply(
         &self,
         op: OpHandle,
@@ -28,6 +78,287 @@ pub trait Context {
         operands: &mut dyn CoordinateSet,
     ) -> Result<usize, Error>;
 
+    /// Apply operation `op` to `operands`, using `epoch` as the observation
+    /// time for those coordinate tuples lacking one (i.e. having a NaN 4th
+    /// coordinate element). This makes it possible to feed 2D and 3D
+    /// coordinates to time dependent operators (`deformation`, time
+    /// dependent `helmert`, etc.)
+    fn apply_at_epoch(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+        epoch: f64,
+    ) -> Result<usize, Error> {
+        let mut operands = AtEpoch { operands, epoch };
+        self.apply(op, direction, &mut operands)
+    }
+
+    /// Apply operation `op` to a slice of coordinate tuples of any of the
+    /// built in types. A fast path for point heavy workloads: The tuples are
+    /// converted to `Coor4D` once, rather than once per step of a pipeline,
+    /// and they pass through all steps in chunks small enough to stay in the
+    /// cache, while operators take the direct, non-dispatched route through
+    /// [`CoordinateSet::as_coor4d_mut()`].
+    ///
+    /// Like the `CoordinateSet` implementations, 2D tuples get a third
+    /// coordinate of 0, and 2D and 3D tuples a fourth coordinate of NaN.
+    /// Since the intermediate results are kept in 4D, 2D coordinates pass
+    /// unharmed through e.g. `cart | helmert | cart inv`
+    fn apply_slice<T: CoordinateTuple>(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut [T],
+    ) -> Result<usize, Error>
+    where
+        Self: Sized,
+    {
+        let mut buffer = Vec::with_capacity(APPLY_CHUNK_SIZE.min(operands.len()));
+        let mut successes = 0;
+        for chunk in operands.chunks_mut(APPLY_CHUNK_SIZE) {
+            buffer.clear();
+            buffer.extend(chunk.iter().map(|c| {
+                let mut coord = Coor4D([0., 0., 0., f64::NAN]);
+                for i in 0..c.dim() {
+                    coord[i] = c.nth_unchecked(i);
+                }
+                coord
+            }));
+            successes += self.apply(op, direction, &mut buffer)?;
+            for (c, coord) in chunk.iter_mut().zip(&buffer) {
+                for i in 0..c.dim() {
+                    c.set_nth_unchecked(i, coord[i]);
+                }
+            }
+        }
+        Ok(successes)
+    }
+
+    /// Apply operation `op` to a copy of `input`, leaving the result in `output`,
+    /// and `input` untouched. The coordinates pass through a small intermediate
+    /// `Coor4D` buffer, chunk by chunk, so there is no need to clone the entire
+    /// input beforehand. Hence, `input` and `output` may be of different types,
+    /// e.g. `Coor2D` in, and `Coor4D` out. The coordinate epoch of `input`, if
+    /// any, is respected, as in [`apply()`](Self::apply).
+    ///
+    /// `output` must hold at least as many coordinate tuples as `input`.
+    fn apply_into(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        input: &dyn CoordinateSet,
+        output: &mut dyn CoordinateSet,
+    ) -> Result<usize, Error> {
+        if output.len() < input.len() {
+            return Err(Error::General("apply_into: Output shorter than input"));
+        }
+        let epoch = input.coordinate_epoch().map(|e| e.value());
+        let mut buffer = Vec::with_capacity(APPLY_CHUNK_SIZE.min(input.len()));
+        let mut successes = 0;
+        for start in (0..input.len()).step_by(APPLY_CHUNK_SIZE) {
+            let end = (start + APPLY_CHUNK_SIZE).min(input.len());
+            buffer.clear();
+            buffer.extend((start..end).map(|i| input.get_coord(i)));
+            successes += match epoch {
+                Some(epoch) => self.apply_at_epoch(op, direction, &mut buffer, epoch)?,
+                None => self.apply(op, direction, &mut buffer)?,
+            };
+            for (i, coord) in (start..end).zip(&buffer) {
+                output.set_coord(i, coord);
+            }
+        }
+        Ok(successes)
+    }
+
+    /// Apply operation `op` to a copy of `input`, and return the result,
+    /// leaving `input` untouched. Points where the operation fails are
+    /// stomped on with NaN, as usual. Use [`apply_into()`](Self::apply_into)
+    /// if the number of successes is needed.
+    fn apply_to_vec<T: CoordinateTuple + Clone>(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        input: &[T],
+    ) -> Result<Vec<T>, Error>
+    where
+        Self: Sized,
+    {
+        let mut output = input.to_vec();
+        self.apply_slice(op, direction, &mut output)?;
+        Ok(output)
+    }
+
+    /// Apply operation `op` to `operands`, while propagating the covariance
+    /// matrices of the first three coordinate elements, given in `covariances`,
+    /// through the operation. The matrices are 3×3, in row major order, and in
+    /// the units of the operands (i.e. radians for angles). For 2D operands,
+    /// leave the third row and column at 0.
+    ///
+    /// The propagation follows the usual law, *C' = J C Jᵀ*, with the
+    /// Jacobian, *J*, of the operation evaluated at each point. *J* is estimated
+    /// by numerical differentiation, except for the horizontal part, which is
+    /// given by the [partial derivatives](Self::partial_derivatives), i.e.
+    /// analytically, for operations providing them. Covariances of points
+    /// where the operation fails are set to NaN.
+    ///
+    /// `covariances` must hold at least as many matrices as `operands` has
+    /// coordinate tuples.
+    fn apply_with_covariance(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        operands: &mut dyn CoordinateSet,
+        covariances: &mut [[f64; 9]],
+    ) -> Result<usize, Error> {
+        if covariances.len() < operands.len() {
+            return Err(Error::General(
+                "apply_with_covariance: Fewer covariances than operands",
+            ));
+        }
+        // The Jacobians, all evaluated before the operands are transformed
+        let mut jacobians = Vec::with_capacity(operands.len());
+        for i in 0..operands.len() {
+            let at = operands.get_coord(i);
+            let mut jacobian = numerical_jacobian(self, op, direction, &at)?;
+            if let Some([a, b, c, d]) = self.partial_derivatives(op, direction, &at)? {
+                [jacobian[0], jacobian[1], jacobian[3], jacobian[4]] = [a, c, b, d];
+            }
+            jacobians.push(jacobian);
+        }
+
+        let successes = self.apply(op, direction, operands)?;
+        for (i, j) in jacobians.iter().enumerate() {
+            let (x, y, z) = operands.xyz(i);
+            covariances[i] = if x.is_nan() || y.is_nan() || z.is_nan() {
+                [f64::NAN; 9]
+            } else {
+                propagate(j, &covariances[i])
+            };
+        }
+        Ok(successes)
+    }
+
+    /// The partial derivatives of the first two output coordinates of `op` with
+    /// respect to its first two input coordinates, at the point `at`: Analytical
+    /// where available, numerical otherwise, cf. [`Op::partial_derivatives()`].
+    /// `Ok(None)` indicates that the derivatives are not available, e.g. outside
+    /// of the domain of `op`, or because the context does not support them.
+    fn partial_derivatives(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Result<Option<[f64; 4]>, Error> {
+        let _ = (op, direction, at);
+        Ok(None)
+    }
+
+    /// The partial derivatives of `op`, in the same form as for
+    /// [`partial_derivatives()`](Self::partial_derivatives), but strictly
+    /// composed from the derivatives of the individual steps of a pipeline,
+    /// cf. [`Op::composed_partial_derivatives()`]. `Ok(None)` indicates that
+    /// the derivatives cannot be composed, e.g. due to stack manipulations.
+    fn composed_partial_derivatives(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Result<Option<[f64; 4]>, Error> {
+        let _ = (op, direction, at);
+        Ok(None)
+    }
+
+    /// Verify the implementation of the operators used by `op` on the present
+    /// platform, by running the builtin test vectors of each of its steps
+    /// (cf. [`OpTestVector`]) in both directions. Note that the test vectors
+    /// are run with their own parameters, not with those of `op`. The test
+    /// operators are instantiated once per distinct definition, and remain
+    /// in the context afterwards, so avoid calling `selftest` in a loop.
+    fn selftest(&mut self, op: OpHandle) -> Result<Vec<SelfTest>, Error> {
+        let mut names = Vec::new();
+        for index in 0..self.steps(op)?.len() {
+            let name = self.params(op, index)?.name;
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        let mut results = Vec::new();
+        let mut tests = BTreeMap::<&str, OpHandle>::new();
+        for name in names {
+            let Ok(documentation) = builtin_operator(&name) else {
+                continue;
+            };
+            for vector in documentation.test_vectors {
+                let test = match tests.get(vector.definition) {
+                    Some(test) => *test,
+                    None => self.op(vector.definition)?,
+                };
+                tests.insert(vector.definition, test);
+                let mut operands = [Coor4D(vector.input)];
+                self.apply(test, Fwd, &mut operands)?;
+                let deviation = operands[0] - Coor4D(vector.output);
+                let mut operands = [Coor4D(vector.output)];
+                self.apply(test, Inv, &mut operands)?;
+                let inverse_deviation = operands[0] - Coor4D(vector.input);
+
+                let within = |deviation: &Coor4D, tolerance: &[f64; 4]| {
+                    (0..4).all(|i| deviation[i].abs() <= tolerance[i])
+                };
+                let passed = within(&deviation, &vector.tolerance)
+                    && within(&inverse_deviation, &vector.inverse_tolerance);
+                results.push(SelfTest {
+                    operator: name.clone(),
+                    source: vector.source,
+                    deviation,
+                    inverse_deviation,
+                    passed,
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    /// Validate `op` by applying it to `operands` in the forward direction,
+    /// then in the inverse, and return the closure error of each point, as
+    /// measured by `metric`. The operands are left in their roundtripped
+    /// state. If the closure error of any point exceeds `tolerance` (or is
+    /// NaN), an error is returned instead, so use `f64::INFINITY` to obtain
+    /// the closure errors unconditionally.
+    fn roundtrip(
+        &self,
+        op: OpHandle,
+        operands: &mut dyn CoordinateSet,
+        tolerance: f64,
+        metric: Metric,
+    ) -> Result<Vec<f64>, Error> {
+        let original: Vec<Coor4D> = (0..operands.len()).map(|i| operands.get_coord(i)).collect();
+        self.apply(op, Fwd, operands)?;
+        self.apply(op, Inv, operands)?;
+
+        let errors: Vec<f64> = original
+            .iter()
+            .enumerate()
+            .map(|(i, a)| metric.distance(a, &operands.get_coord(i)))
+            .collect();
+        let exceeding = errors
+            .iter()
+            .filter(|e| e.is_nan() || **e > tolerance)
+            .count();
+        if exceeding > 0 {
+            let worst = errors.iter().fold(0_f64, |max, e| max.max(*e));
+            return Err(Error::Unexpected {
+                message: format!(
+                    "Roundtrip closure error exceeds tolerance for {exceeding} points"
+                ),
+                expected: tolerance.to_string(),
+                found: worst.to_string(),
+            });
+        }
+        Ok(errors)
+    }
+
     /// Globally defined default values (typically just `ellps=GRS80`)
     fn globals(&self) -> BTreeMap<String, String>;
 
@@ -37,6 +368,12 @@ pub trait Context {
     /// Parsed parameters of a specific step
     fn params(&self, op: OpHandle, index: usize) -> Result<ParsedParameters, Error>;
 
+    /// A self contained representation of an operator, for instantiation in
+    /// another context, possibly in another process, cf. [`OpSnapshot`]
+    fn snapshot(&self, _op: OpHandle) -> Result<OpSnapshot, Error> {
+        Err(Error::Unsupported("snapshot".to_string()))
+    }
+
     /// Register a new user-defined operator
     fn register_op(&mut self, name: &str, constructor: OpConstructor);
     /// Register a new user-defined resource (macro, ellipsoid parameter set...)
@@ -54,6 +391,147 @@ pub trait Context {
     fn get_grid(&self, name: &str) -> Result<Arc<dyn Grid>, Error>;
 }
 
+// Helper for `Context::apply_with_covariance()`: The 3×3 Jacobian (row major)
+// of the first three output coordinates of `op` with respect to the first
+// three input coordinates, at `at`, by central differences. The step sizes
+// are relative to the magnitude of the coordinates, so they work for angles
+// in radians, as well as for lengths in meters
+fn numerical_jacobian(
+    ctx: &(impl Context + ?Sized),
+    op: OpHandle,
+    direction: Direction,
+    at: &Coor4D,
+) -> Result<[f64; 9], Error> {
+    let mut steps = [0.; 3];
+    let mut points = Vec::with_capacity(6);
+    for (k, step) in steps.iter_mut().enumerate() {
+        *step = 1e-6 * at[k].abs().max(1.);
+        for sign in [1., -1.] {
+            let mut point = *at;
+            point[k] += sign * *step;
+            points.push(point);
+        }
+    }
+    ctx.apply(op, direction, &mut points)?;
+
+    let mut jacobian = [0.; 9];
+    for (k, step) in steps.iter().enumerate() {
+        for row in 0..3 {
+            let delta = points[2 * k][row] - points[2 * k + 1][row];
+            jacobian[3 * row + k] = delta / (2. * step);
+        }
+    }
+    Ok(jacobian)
+}
+
+// Helper for `Context::apply_with_covariance()`: J C Jᵀ, for 3×3 matrices
+// in row major order
+fn propagate(j: &[f64; 9], c: &[f64; 9]) -> [f64; 9] {
+    let mut jc = [0.; 9];
+    for row in 0..3 {
+        for col in 0..3 {
+            jc[3 * row + col] = (0..3).map(|k| j[3 * row + k] * c[3 * k + col]).sum();
+        }
+    }
+    let mut res = [0.; 9];
+    for row in 0..3 {
+        for col in 0..3 {
+            res[3 * row + col] = (0..3).map(|k| jc[3 * row + k] * j[3 * col + k]).sum();
+        }
+    }
+    res
+}
+
+/// Distance measures for roundtrip closure errors, cf. [`Context::roundtrip()`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    /// The planar distance between the first two coordinate elements
+    Planar,
+    /// The distance between the first three coordinate elements
+    Cartesian,
+    /// The geodesic distance on the ellipsoid, between the first two
+    /// coordinate elements, taken as longitude and latitude in radians,
+    /// combined with the difference between the third elements (heights)
+    Geodesic(Ellipsoid),
+}
+
+impl Metric {
+    /// The distance between `a` and `b`
+    pub fn distance(&self, a: &Coor4D, b: &Coor4D) -> f64 {
+        match self {
+            Metric::Planar => a.hypot2(b),
+            Metric::Cartesian => a.hypot3(b),
+            Metric::Geodesic(ellps) => {
+                let distance = ellps.distance(&Coor2D::raw(a[0], a[1]), &Coor2D::raw(b[0], b[1]));
+                distance.hypot(b[2] - a[2])
+            }
+        }
+    }
+}
+
+/// The outcome of running a builtin test vector, cf. [`Context::selftest()`]
+#[derive(Debug, Clone)]
+pub struct SelfTest {
+    /// The name of the operator tested
+    pub operator: String,
+    /// The source of the test vector
+    pub source: &'static str,
+    /// The deviation of the forward output from the expected
+    pub deviation: Coor4D,
+    /// The deviation of the inverse output from the expected
+    pub inverse_deviation: Coor4D,
+    /// Are all deviations within the tolerances of the test vector?
+    pub passed: bool,
+}
+
+// Helper for `Context::apply_at_epoch()`: A `CoordinateSet` wrapper, replacing
+// NaN-valued observation times with a fixed epoch. Deliberately without any
+// metadata, to avoid having `apply()` wrap the wrapper once again
+struct AtEpoch<'a> {
+    operands: &'a mut dyn CoordinateSet,
+    epoch: f64,
+}
+
+impl CoordinateMetadata for AtEpoch<'_> {}
+
+impl CoordinateSet for AtEpoch<'_> {
+    fn len(&self) -> usize {
+        self.operands.len()
+    }
+
+    fn dim(&self) -> usize {
+        self.operands.dim()
+    }
+
+    fn get_coord(&self, index: usize) -> Coor4D {
+        let mut coord = self.operands.get_coord(index);
+        if coord[3].is_nan() {
+            coord[3] = self.epoch;
+        }
+        coord
+    }
+
+    fn set_coord(&mut self, index: usize, value: &Coor4D) {
+        self.operands.set_coord(index, value);
+    }
+
+    fn xy(&self, index: usize) -> (f64, f64) {
+        self.operands.xy(index)
+    }
+
+    fn set_xy(&mut self, index: usize, x: f64, y: f64) {
+        self.operands.set_xy(index, x, y);
+    }
+
+    fn xyz(&self, index: usize) -> (f64, f64, f64) {
+        self.operands.xyz(index)
+    }
+
+    fn set_xyz(&mut self, index: usize, x: f64, y: f64, z: f64) {
+        self.operands.set_xyz(index, x, y, z);
+    }
+}
+
 /// Help context providers provide canonically named, built in coordinate adaptors
 #[rustfmt::skip]
 pub const BUILTIN_ADAPTORS: [(&str, &str); 8] = [
@@ -1,9 +1,12 @@
 #[cfg(feature = "with_plain")]
 use crate::authoring::*;
 use crate::grid::ntv2::Ntv2Grid;
+use crate::grid::{is_ostn15, nadcon5_units};
 use std::{
-    path::PathBuf,
+    io::{BufRead, Read},
+    path::{Path, PathBuf},
     sync::{Arc, Mutex, OnceLock},
+    time::SystemTime,
 };
 
 // ----- T H E   P L A I N   C O N T E X T ---------------------------------------------
@@ -18,28 +21,54 @@ pub struct Plain {
     resources: BTreeMap<String, String>,
     operators: BTreeMap<OpHandle, Op>,
     paths: Vec<std::path::PathBuf>,
+    // The grids handed out by this context, by name
+    grids: Mutex<BTreeMap<String, LoadedGrid>>,
+    // Hot reload: The files read while instantiating the current operator,
+    // and the definitions and source files of all operators instantiated
+    hot_reload: bool,
+    // Expand `${NAME}` references to environment variables?
+    environment: bool,
+    recent: Mutex<Vec<(PathBuf, Stamp)>>,
+    dependencies: BTreeMap<OpHandle, (String, Vec<(PathBuf, Stamp)>)>,
+}
+
+// The modification time and size of a file, for detection of changes
+type Stamp = Option<(SystemTime, u64)>;
+
+// A grid, with the resolved path and stamp of the file it was read from
+type LoadedGrid = (PathBuf, Stamp, Arc<dyn Grid>);
+
+fn file_stamp(path: &Path) -> Stamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
 }
 
 // Helper for Plain: Provide grid access for all `Op`s
 // in all instantiations of `Plain` by handing out
 // reference counted clones to a single heap allocation
+// per grid file. The collection is keyed by the resolved
+// path of the file, so contexts with different search
+// paths never mix up grids of the same name, while the
+// same file is loaded only once, however it is found
 
 static GRIDS: OnceLock<Mutex<GridCollection>> = OnceLock::new();
 
 fn init_grids() -> Mutex<GridCollection> {
-    Mutex::new(GridCollection(BTreeMap::<String, Arc<dyn Grid>>::new()))
+    Mutex::new(GridCollection(BTreeMap::new()))
 }
 
-struct GridCollection(BTreeMap<String, Arc<dyn Grid>>);
+struct GridCollection(BTreeMap<PathBuf, (Stamp, Arc<dyn Grid>)>);
 impl GridCollection {
-    fn get_grid(&mut self, name: &str, paths: &[PathBuf]) -> Result<Arc<dyn Grid>, Error> {
-        // If the grid is already there, just return a reference clone
-        if let Some(grid) = self.0.get(name) {
-            return Ok(grid.clone());
-        }
-
-        // Otherwise, we must look for it in the data path
-        let n = PathBuf::from(name);
+    // Returns the resolved path and the file stamp along with the grid.
+    // If `validate` is set, grids changed on disk since loading are read anew
+    fn get_grid(
+        &mut self,
+        name: &str,
+        paths: &[PathBuf],
+        validate: bool,
+    ) -> Result<LoadedGrid, Error> {
+        // Compressed grids are found in the directory of the uncompressed format
+        let n = PathBuf::from(name.strip_suffix(".gz").unwrap_or(name));
         let ext = n
             .extension()
             .unwrap_or_default()
@@ -50,25 +79,52 @@ impl GridCollection {
             let mut path = path.clone();
             path.push(ext);
             path.push(name);
-            let Ok(grid) = std::fs::read(path) else {
+            let Ok(path) = path.canonicalize() else {
                 continue;
             };
 
-            if ext == "gsb" {
-                self.0
-                    .insert(name.to_string(), Arc::new(Ntv2Grid::new(&grid)?));
-            } else {
-                self.0
-                    .insert(name.to_string(), Arc::new(BaseGrid::gravsoft(&grid)?));
-            }
-            if let Some(grid) = self.0.get(name) {
-                return Ok(grid.clone());
+            // If the grid is already there, just return a reference clone
+            if let Some((stamp, grid)) = self.0.get(&path) {
+                if !validate || *stamp == file_stamp(&path) {
+                    return Ok((path, *stamp, grid.clone()));
+                }
             }
+
+            // Otherwise, we must read it. Gravsoft grids, which may be large,
+            // are parsed while streaming, the other formats from memory
+            let stamp = file_stamp(&path);
+            let Ok(file) = std::fs::File::open(&path) else {
+                continue;
+            };
+            let mut reader = std::io::BufReader::new(file);
+            let grid: Arc<dyn Grid> = if ext == "gsb" {
+                Arc::new(Ntv2Grid::new(&read_all(reader)?)?)
+            } else if ext == "b" {
+                let grid = BaseGrid::nadcon5(&read_all(reader)?)?;
+                Arc::new(grid.with_units(nadcon5_units(name)))
+            } else if ext == "gtx" {
+                Arc::new(BaseGrid::gtx(&read_all(reader)?)?)
+            } else if ext == "par" {
+                Arc::new(BaseGrid::par(&read_all(reader)?)?)
+            } else if is_ostn15(reader.fill_buf()?) {
+                Arc::new(BaseGrid::ostn15(&read_all(reader)?)?)
+            } else {
+                Arc::new(BaseGrid::gravsoft_from_reader(reader)?)
+            };
+            self.0.insert(path.clone(), (stamp, grid.clone()));
+            return Ok((path, stamp, grid));
         }
         Err(Error::NotFound(name.to_string(), ": Grid".to_string()))
     }
 }
 
+// The remaining content of `reader`
+fn read_all(mut reader: impl Read) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
 const BAD_ID_MESSAGE: Error = Error::General("Plain: Unknown operator id");
 
 impl Plain {
@@ -76,12 +132,208 @@ impl Plain {
     /// growing through the roof, we may clear it occasionally.
     /// As the grids are behind an `Arc` reference counter, this is safe to do
     /// even though they may still be in use by some remaining operator
-    /// instantiations.
+    /// instantiations. Existing contexts keep handing out the grids they have
+    /// already provided, while new contexts will load them anew.
     pub fn clear_grids() {
         if let Some(grids) = GRIDS.get() {
             grids.lock().unwrap().0.clear();
         }
     }
+
+    /// A `Plain` context searching the directories of `paths`, in the order
+    /// given, before the directories of the default search path (i.e. those
+    /// listed in the environment variable `GEODESY_RESOURCE_PATH`, followed
+    /// by the local `./geodesy`, the user's `geodesy` data directory, and on
+    /// Unix, the system wide `/usr/local/share/geodesy` and `/usr/share/geodesy`)
+    pub fn with_paths<P: AsRef<Path>>(paths: &[P]) -> Plain {
+        let mut ctx = Plain::new();
+        let given = paths.iter().map(|p| p.as_ref().to_path_buf());
+        ctx.paths.splice(0..0, given);
+        ctx
+    }
+
+    /// The resource directories searched, in order of precedence
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Enable (or disable) expansion of references to environment variables,
+    /// `${NAME}`, in operator definitions and resources, so site specific
+    /// values, e.g. grid directories, need not be hardcoded. References to
+    /// undefined variables are errors
+    pub fn interpolate_environment(&mut self, enabled: bool) {
+        self.environment = enabled;
+    }
+
+    // The actual resource lookup, for `get_resource()`
+    fn find_resource(&self, name: &str) -> Result<String, Error> {
+        // There may be an unidentified use case for user registered
+        // resources lacking the ':'-sigil. So we postpone the check
+        // for sigil until we know it is not a run-time user defined
+        // resource we're looking for
+        if let Some(result) = self.resources.get(name) {
+            return Ok(result.to_string());
+        }
+
+        // TODO: Check for "known prefixes": 'ellps:', 'datum:', etc.
+        let parts = name.split(':').collect::<Vec<_>>();
+        if parts.len() != 2 {
+            return Err(Error::BadParam(
+                "needing prefix:suffix format".to_string(),
+                name.to_string(),
+            ));
+        }
+        let prefix = parts[0];
+        let suffix = parts[1];
+        let section = "resources";
+
+        // We do not know yet whether the resource is in a separate resource
+        // file or in a resource register, so we generate file names for
+        // both cases.
+        let resource = prefix.to_string() + "_" + suffix + ".resource";
+        let register = prefix.to_string() + ".md";
+        let tag = "```geodesy:".to_string() + suffix + "\n";
+
+        for path in &self.paths {
+            // Is it in a separate file?
+            let mut full_path = path.clone();
+            full_path.push(section);
+            full_path.push(&resource);
+            if let Ok(result) = std::fs::read_to_string(&full_path) {
+                self.track(&full_path, file_stamp(&full_path));
+                return Ok(result.trim().to_string());
+            }
+
+            // If not, search in a resource register
+            let mut full_path = path.clone();
+            full_path.push(section);
+            full_path.push(&register);
+            if let Ok(mut result) = std::fs::read_to_string(&full_path) {
+                result = result.replace('\r', "\n");
+                let Some(mut start) = result.find(&tag) else {
+                    continue;
+                };
+                self.track(&full_path, file_stamp(&full_path));
+                start += tag.len();
+                let Some(length) = result[start..].find("```") else {
+                    // Search for end-of-item reached end-of-file
+                    let result = result[start..].trim().to_string();
+                    return Ok(result);
+                };
+                let result = result[start..start + length].trim().to_string();
+                return Ok(result);
+            }
+        }
+
+        Err(Error::NotFound(
+            name.to_string(),
+            ": User defined resource".to_string(),
+        ))
+    }
+
+    // Apply the environment variable expansion, if enabled
+    fn interpolate(&self, text: String) -> Result<String, Error> {
+        if !self.environment {
+            return Ok(text);
+        }
+        interpolate_environment(&text)
+    }
+
+    /// Enable (or disable) hot reload mode, for interactive development of
+    /// macros, resource registers and grids: In hot reload mode, the context
+    /// keeps track of the files read when instantiating each operator, and
+    /// re-instantiates the operators whose files have changed on disk, when
+    /// [`reload()`](Plain::reload) is called, and before instantiating new
+    /// operators. Only operators instantiated while the mode is enabled are
+    /// tracked
+    pub fn hot_reload(&mut self, enabled: bool) {
+        self.hot_reload = enabled;
+    }
+
+    /// The operators depending on files which have changed (or vanished)
+    /// since the operators were instantiated. Always empty, unless in
+    /// [`hot_reload`](Plain::hot_reload) mode
+    pub fn stale(&self) -> Vec<OpHandle> {
+        self.dependencies
+            .iter()
+            .filter(|(_, (_, sources))| {
+                sources
+                    .iter()
+                    .any(|(path, stamp)| *stamp != file_stamp(path))
+            })
+            .map(|(op, _)| *op)
+            .collect()
+    }
+
+    /// Re-instantiate all [`stale`](Plain::stale) operators, re-reading the
+    /// changed files. The operators keep their handles. Returns the handles
+    /// of the re-instantiated operators
+    pub fn reload(&mut self) -> Result<Vec<OpHandle>, Error> {
+        let stale = self.stale();
+        for id in &stale {
+            let definition = self.dependencies[id].0.clone();
+            let mut op = self.instantiate(&definition)?;
+            if let Some(sources) = self.dependencies.remove(&op.id) {
+                self.dependencies.insert(*id, sources);
+            }
+            op.id = *id;
+            self.operators.insert(*id, op);
+        }
+        Ok(stale)
+    }
+
+    // Instantiate an operator, and in hot reload mode, record its sources
+    fn instantiate(&mut self, definition: &str) -> Result<Op, Error> {
+        self.recent.lock().unwrap().clear();
+        let op = Op::new(definition, self)?;
+        if self.hot_reload {
+            let sources = std::mem::take(&mut *self.recent.lock().unwrap());
+            self.dependencies
+                .insert(op.id, (definition.to_string(), sources));
+        }
+        Ok(op)
+    }
+
+    // In hot reload mode: Record that `path` is needed by the operator
+    // currently being instantiated
+    fn track(&self, path: &Path, stamp: Stamp) {
+        if self.hot_reload {
+            self.recent
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), stamp));
+        }
+    }
+}
+
+// The environment variable listing additional resource directories,
+// separated as in the `PATH` variable of the platform (i.e. by `:` on
+// Unix, by `;` on Windows)
+const RESOURCE_PATH_VARIABLE: &str = "GEODESY_RESOURCE_PATH";
+
+// The default search path: The directories listed in `variable` (i.e. the
+// value of GEODESY_RESOURCE_PATH), followed by the local, the user, and (on
+// Unix) the system locations
+fn default_paths(variable: Option<std::ffi::OsString>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(variable) = variable {
+        let listed = std::env::split_paths(&variable).filter(|p| !p.as_os_str().is_empty());
+        paths.extend(listed);
+    }
+
+    let localpath: PathBuf = [".", "geodesy"].iter().collect();
+    paths.push(localpath);
+
+    if let Some(mut userpath) = dirs::data_local_dir() {
+        userpath.push("geodesy");
+        paths.push(userpath);
+    }
+
+    #[cfg(unix)]
+    for system in ["/usr/local/share/geodesy", "/usr/share/geodesy"] {
+        paths.push(PathBuf::from(system));
+    }
+    paths
 }
 
 impl Default for Plain {
@@ -89,21 +341,18 @@ impl Default for Plain {
         let constructors = BTreeMap::new();
         let resources = BTreeMap::new();
         let operators = BTreeMap::new();
-        let mut paths = Vec::new();
-
-        let localpath: PathBuf = [".", "geodesy"].iter().collect();
-        paths.push(localpath);
-
-        if let Some(mut userpath) = dirs::data_local_dir() {
-            userpath.push("geodesy");
-            paths.push(userpath);
-        }
+        let paths = default_paths(std::env::var_os(RESOURCE_PATH_VARIABLE));
 
         Plain {
             constructors,
             resources,
             operators,
             paths,
+            grids: Mutex::new(BTreeMap::new()),
+            hot_reload: false,
+            environment: false,
+            recent: Mutex::new(Vec::new()),
+            dependencies: BTreeMap::new(),
         }
     }
 }
@@ -124,9 +373,13 @@ impl Context for Plain {
     /// and [`token::normalize()`](crate::token::Tokenize::normalize())
     fn op(&mut self, definition: &str) -> Result<OpHandle, Error> {
         // It may be a PROJ string, so we filter it through the PROJ parser
-        let definition = parse_proj(definition)?;
+        let definition = self.interpolate(parse_proj(definition)?)?;
 
-        let op = Op::new(&definition, self)?;
+        if self.hot_reload {
+            self.reload()?;
+        }
+
+        let op = self.instantiate(&definition)?;
         let id = op.id;
         self.operators.insert(id, op);
         assert!(self.operators.contains_key(&id));
@@ -139,10 +392,49 @@ impl Context for Plain {
         direction: Direction,
         operands: &mut dyn CoordinateSet,
     ) -> Result<usize, Error> {
+        if let Some(epoch) = operands.coordinate_epoch() {
+            return self.apply_at_epoch(op, direction, operands, epoch.value());
+        }
+        if self.hot_reload && self.stale().contains(&op) {
+            warn!("Plain: Operator sources changed on disk. Consider calling `reload()`");
+        }
         let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
         Ok(op.apply(self, operands, direction))
     }
 
+    fn partial_derivatives(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Result<Option<[f64; 4]>, Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        Ok(op.partial_derivatives(self, direction, at))
+    }
+
+    fn composed_partial_derivatives(
+        &self,
+        op: OpHandle,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Result<Option<[f64; 4]>, Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        Ok(op.composed_partial_derivatives(self, direction, at))
+    }
+
+    fn set_crs(&mut self, op: OpHandle, source: Crs, target: Crs) -> Result<(), Error> {
+        let op = self.operators.get_mut(&op).ok_or(BAD_ID_MESSAGE)?;
+        op.descriptor.source_crs = source;
+        op.descriptor.target_crs = target;
+        Ok(())
+    }
+
+    fn crs(&self, op: OpHandle) -> Result<(Crs, Crs), Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        let descriptor = &op.descriptor;
+        Ok((descriptor.source_crs.clone(), descriptor.target_crs.clone()))
+    }
+
     fn steps(&self, op: OpHandle) -> Result<&Vec<String>, Error> {
         let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
         Ok(&op.descriptor.steps)
@@ -169,6 +461,11 @@ impl Context for Plain {
         BTreeMap::from([("ellps".to_string(), "GRS80".to_string())])
     }
 
+    fn snapshot(&self, op: OpHandle) -> Result<OpSnapshot, Error> {
+        let op = self.operators.get(&op).ok_or(BAD_ID_MESSAGE)?;
+        Ok(op.snapshot())
+    }
+
     fn register_op(&mut self, name: &str, constructor: OpConstructor) {
         self.constructors.insert(String::from(name), constructor);
     }
@@ -190,66 +487,7 @@ impl Context for Plain {
     }
 
     fn get_resource(&self, name: &str) -> Result<String, Error> {
-        // There may be an unidentified use case for user registered
-        // resources lacking the ':'-sigil. So we postpone the check
-        // for sigil until we know it is not a run-time user defined
-        // resource we're looking for
-        if let Some(result) = self.resources.get(name) {
-            return Ok(result.to_string());
-        }
-
-        // TODO: Check for "known prefixes": 'ellps:', 'datum:', etc.
-        let parts = name.split(':').collect::<Vec<_>>();
-        if parts.len() != 2 {
-            return Err(Error::BadParam(
-                "needing prefix:suffix format".to_string(),
-                name.to_string(),
-            ));
-        }
-        let prefix = parts[0];
-        let suffix = parts[1];
-        let section = "resources";
-
-        // We do not know yet whether the resource is in a separate resource
-        // file or in a resource register, so we generate file names for
-        // both cases.
-        let resource = prefix.to_string() + "_" + suffix + ".resource";
-        let register = prefix.to_string() + ".md";
-        let tag = "```geodesy:".to_string() + suffix + "\n";
-
-        for path in &self.paths {
-            // Is it in a separate file?
-            let mut full_path = path.clone();
-            full_path.push(section);
-            full_path.push(&resource);
-            if let Ok(result) = std::fs::read_to_string(full_path) {
-                return Ok(result.trim().to_string());
-            }
-
-            // If not, search in a resource register
-            let mut full_path = path.clone();
-            full_path.push(section);
-            full_path.push(&register);
-            if let Ok(mut result) = std::fs::read_to_string(full_path) {
-                result = result.replace('\r', "\n");
-                let Some(mut start) = result.find(&tag) else {
-                    continue;
-                };
-                start += tag.len();
-                let Some(length) = result[start..].find("```") else {
-                    // Search for end-of-item reached end-of-file
-                    let result = result[start..].trim().to_string();
-                    return Ok(result);
-                };
-                let result = result[start..start + length].trim().to_string();
-                return Ok(result);
-            }
-        }
-
-        Err(Error::NotFound(
-            name.to_string(),
-            ": User defined resource".to_string(),
-        ))
+        self.interpolate(self.find_resource(name)?)
     }
 
     fn get_blob(&self, name: &str) -> Result<Vec<u8>, Error> {
@@ -263,7 +501,8 @@ impl Context for Plain {
             let mut path = path.clone();
             path.push(ext);
             path.push(name);
-            if let Ok(result) = std::fs::read(path) {
+            if let Ok(result) = std::fs::read(&path) {
+                self.track(&path, file_stamp(&path));
                 return Ok(result);
             }
         }
@@ -272,14 +511,31 @@ impl Context for Plain {
 
     /// Access grid resources by identifier
     fn get_grid(&self, name: &str) -> Result<Arc<dyn Grid>, Error> {
-        // The GridCollection does all the hard work here, but accessing GRIDS,
-        // which is a mutable static is (mis-)diagnosed as unsafe by the compiler,
-        // even though the mutable static is behind a Mutex guard
-        GRIDS
-            .get_or_init(init_grids)
+        // Grids already provided by this context are found without
+        // probing the file system - unless in hot reload mode
+        let known = self.grids.lock().unwrap().get(name).cloned();
+        if let Some((path, stamp, grid)) = known {
+            if !self.hot_reload || stamp == file_stamp(&path) {
+                self.track(&path, stamp);
+                return Ok(grid);
+            }
+        }
+
+        // Otherwise, the GridCollection does all the hard work, but accessing
+        // GRIDS, which is a mutable static is (mis-)diagnosed as unsafe by the
+        // compiler, even though the mutable static is behind a Mutex guard
+        let grid = GRIDS.get_or_init(init_grids).lock().unwrap().get_grid(
+            name,
+            &self.paths,
+            self.hot_reload,
+        )?;
+        let (path, stamp, grid) = grid;
+        self.track(&path, stamp);
+        self.grids
             .lock()
             .unwrap()
-            .get_grid(name, &self.paths)
+            .insert(name.to_string(), (path, stamp, grid.clone()));
+        Ok(grid)
     }
 }
 
@@ -337,6 +593,13 @@ mod tests {
         assert!(ctx.get_resource("stupid:way")?.starts_with('#'));
         assert!(ctx.get_resource("stupid:way")?.ends_with("addone"));
 
+        // Versioned register items
+        assert_eq!(ctx.get_resource("stupid:way_too@1")?, "addone | addone");
+        let op = ctx.op("stupid:way_too version=1")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0].x(), 57.);
+
         // Now make sure, we can actually also *instantiate* a recipe
         // from a register
         let op = ctx.op("stupid:way_too")?;
@@ -393,6 +656,116 @@ mod tests {
         let _op2 = ctx.op("gridshift grids=5458.gsb, 5458_with_subgrid.gsb")?;
         let _op3 = ctx.op("gridshift grids=test.geoid")?;
         assert!(ctx.op("gridshift grids=non.existing").is_err());
+
+        // Repeated references are served by the same allocation - even after
+        // the global collection has been cleared
+        let first = ctx.get_grid("test.geoid")?;
+        Plain::clear_grids();
+        let second = ctx.get_grid("test.geoid")?;
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Compressed grids are found along with their uncompressed kin
+        #[cfg(feature = "gzip")]
+        {
+            let gzipped = ctx.get_grid("test.geoid.gz")?;
+            let c = Coor4D::geo(55.5, 12.5, 0., 0.);
+            assert_eq!(gzipped.at(&c, 0.), first.at(&c, 0.));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn paths() -> Result<(), Error> {
+        // Directories from the environment come first, then the local one
+        let variable = std::env::join_paths(["/first/dir", "/second/dir"]).unwrap();
+        let paths = default_paths(Some(variable));
+        assert_eq!(paths[0], PathBuf::from("/first/dir"));
+        assert_eq!(paths[1], PathBuf::from("/second/dir"));
+        assert_eq!(paths[2], PathBuf::from("./geodesy"));
+        assert_eq!(default_paths(None)[0], PathBuf::from("./geodesy"));
+
+        // ...but directories given explicitly take precedence
+        let ctx = Plain::with_paths(&["/explicit/dir"]);
+        assert_eq!(ctx.paths()[0], PathBuf::from("/explicit/dir"));
+        assert!(ctx.paths().contains(&PathBuf::from("./geodesy")));
+
+        // Resources are found in any of the directories
+        let mut ctx = Plain::with_paths(&["/non/existing", "./geodesy"]);
+        assert!(ctx.op("stupid:way").is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn environment() -> Result<(), Error> {
+        std::env::set_var("GEODESY_PLAIN_TEST_SHIFT", "2");
+        let mut ctx = Plain::new();
+        ctx.register_resource("env:shift", "helmert x=${GEODESY_PLAIN_TEST_SHIFT}");
+
+        // Opt-in only
+        assert!(ctx.op("helmert x=${GEODESY_PLAIN_TEST_SHIFT}").is_err());
+        ctx.interpolate_environment(true);
+
+        // In definitions, and in resources
+        for definition in ["helmert x=${GEODESY_PLAIN_TEST_SHIFT}", "env:shift"] {
+            let op = ctx.op(definition)?;
+            let mut data = crate::test_data::coor2d();
+            ctx.apply(op, Fwd, &mut data)?;
+            assert_eq!(data[0].x(), 57.);
+        }
+        assert!(ctx.op("helmert x=${GEODESY_PLAIN_TEST_UNDEFINED}").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn hot_reload() -> Result<(), Error> {
+        let dir = std::env::temp_dir().join(format!("geodesy-hot-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("resources"))?;
+        std::fs::create_dir_all(dir.join("geoid"))?;
+        let macro_file = dir.join("resources").join("hot_reload.resource");
+        let grid_file = dir.join("geoid").join("hot.geoid");
+        let geoid = |n: &str| format!("54 58 8 16 1 1\n{}", format!("{n} ").repeat(45));
+        std::fs::write(&macro_file, "addone")?;
+        std::fs::write(&grid_file, geoid("1.0"))?;
+
+        let mut ctx = Plain::with_paths(&[&dir]);
+        ctx.hot_reload(true);
+        let op = ctx.op("hot:reload")?;
+        let geo = ctx.op("geo:in | gridshift grids=hot.geoid")?;
+
+        let mut data = crate::test_data::coor3d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0].x(), 56.);
+        let mut data = crate::test_data::coor3d();
+        ctx.apply(geo, Fwd, &mut data)?;
+        assert_eq!(data[0][2], -1.);
+        assert!(ctx.stale().is_empty());
+
+        // Change the macro and the grid...
+        std::fs::write(&macro_file, "addone | addone")?;
+        std::fs::write(&grid_file, geoid("10.0"))?;
+        let mut stale = ctx.stale();
+        stale.sort();
+        let mut expected = vec![op, geo];
+        expected.sort();
+        assert_eq!(stale, expected);
+
+        // ...reload, and the changes are reflected under the existing handles
+        assert_eq!(ctx.reload()?.len(), 2);
+        assert!(ctx.stale().is_empty());
+        let mut data = crate::test_data::coor3d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0].x(), 57.);
+        let mut data = crate::test_data::coor3d();
+        ctx.apply(geo, Fwd, &mut data)?;
+        assert_eq!(data[0][2], -10.);
+
+        // Without hot reload, nothing is tracked
+        let mut ctx = Plain::with_paths(&[&dir]);
+        ctx.op("hot:reload")?;
+        std::fs::write(&macro_file, "addone")?;
+        assert!(ctx.stale().is_empty());
+
+        std::fs::remove_dir_all(dir)?;
         Ok(())
     }
 }
@@ -89,7 +89,7 @@ impl Coor2D {
         Coor2D([1., 1.])
     }
 
-    /// Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
+    // Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
 
     /// Multiply by a scalar
     #[must_use]
@@ -128,6 +128,20 @@ mod tests {
         assert_eq!(d.x().to_degrees(), c.x().to_degrees());
     }
 
+    #[test]
+    fn gon_and_turns() {
+        let c = Coor2D::gis(90., -180.);
+        let g = c.to_gon();
+        assert!((g.x() - 100.).abs() < 1e-12);
+        assert!((g.y() + 200.).abs() < 1e-12);
+        assert!(c.hypot2(&g.from_gon()) < 1e-12);
+
+        let t = c.to_turns();
+        assert!((t.x() - 0.25).abs() < 1e-15);
+        assert!((t.y() + 0.5).abs() < 1e-15);
+        assert!(c.hypot2(&t.from_turns()) < 1e-12);
+    }
+
     #[test]
     fn array() {
         let b = Coor2D::raw(7., 8.);
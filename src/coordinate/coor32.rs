@@ -97,7 +97,7 @@ impl Coor32 {
         Coor32([1., 1.])
     }
 
-    /// Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
+    // Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
 
     /// Multiply by a scalar
     #[must_use]
@@ -0,0 +1,149 @@
+use super::*;
+
+/// Generic 3D Coordinate tuple in single precision, with no fixed interpretation
+/// of the elements. The 3D sibling of [`Coor32`](super::Coor32), intended for
+/// compact storage of large point clouds: Just half the weight of a
+/// [`Coor3D`](super::Coor3D).
+///
+/// # Accuracy
+///
+/// An `f32` has a 24 bit mantissa, i.e. a relative resolution of roughly
+/// 6e-8. For angles in radians, this corresponds to up to 0.8 m on the
+/// surface of the Earth, and for projected coordinates with 7 digit
+/// northings (e.g. UTM) to around 0.5 m. So for survey grade point clouds,
+/// store coordinates relative to a local origin (e.g. the lower left corner
+/// of the tile), and add it back, in `f64`, before transformation: With
+/// offsets below 1 km, the resolution is better than 0.1 mm.
+/// Heights (the third element) are in general safe at the mm level.
+///
+/// Internally, all computations are carried out in `f64`, so the loss of
+/// accuracy happens only when storing results.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct Coor3D32(pub [f32; 3]);
+
+impl CoordinateTuple for Coor3D32 {
+    fn new(fill: f64) -> Self {
+        Coor3D32([fill as f32; 3])
+    }
+
+    fn dim(&self) -> usize {
+        3
+    }
+
+    fn nth_unchecked(&self, n: usize) -> f64 {
+        self.0[n] as f64
+    }
+
+    fn set_nth_unchecked(&mut self, n: usize, value: f64) {
+        self.0[n] = value as f32;
+    }
+}
+
+// ----- C O N S T R U C T O R S ---------------------------------------------
+
+/// Constructors
+impl Coor3D32 {
+    /// A `Coor3D32` from latitude/longitude/height, with the angular input in degrees
+    #[must_use]
+    pub fn geo(latitude: f64, longitude: f64, height: f64) -> Coor3D32 {
+        Coor3D32([
+            longitude.to_radians() as f32,
+            latitude.to_radians() as f32,
+            height as f32,
+        ])
+    }
+
+    /// A `Coor3D32` from longitude/latitude/height, with the angular input in degrees
+    #[must_use]
+    pub fn gis(longitude: f64, latitude: f64, height: f64) -> Coor3D32 {
+        Coor3D32([
+            longitude.to_radians() as f32,
+            latitude.to_radians() as f32,
+            height as f32,
+        ])
+    }
+
+    /// A `Coor3D32` from longitude/latitude/height, with the angular input in radians
+    #[must_use]
+    pub fn raw(first: f64, second: f64, third: f64) -> Coor3D32 {
+        Coor3D32([first as f32, second as f32, third as f32])
+    }
+
+    /// A `Coor3D32` consisting of 3 `NaN`s
+    #[must_use]
+    pub fn nan() -> Coor3D32 {
+        Coor3D32([f32::NAN, f32::NAN, f32::NAN])
+    }
+
+    /// A `Coor3D32` consisting of 3 `0`s
+    #[must_use]
+    pub fn origin() -> Coor3D32 {
+        Coor3D32([0., 0., 0.])
+    }
+
+    /// A `Coor3D32` consisting of 3 `1`s
+    #[must_use]
+    pub fn ones() -> Coor3D32 {
+        Coor3D32([1., 1., 1.])
+    }
+
+    // Arithmetic (also see the operator trait implementations `add, sub, mul, div`)
+
+    /// Multiply by a scalar
+    #[must_use]
+    pub fn scale(&self, factor: f64) -> Coor3D32 {
+        let factor = factor as f32;
+        Coor3D32([self[0] * factor, self[1] * factor, self[2] * factor])
+    }
+
+    /// Scalar product
+    #[must_use]
+    pub fn dot(&self, other: Coor3D32) -> f64 {
+        self.0
+            .iter()
+            .zip(other.0)
+            .map(|(a, b)| *a as f64 * b as f64)
+            .sum()
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn coor3d32() {
+        let c = Coor3D32::raw(12., 55., 100.).to_radians();
+        let d = Coor3D32::gis(12., 55., 100.);
+        assert_eq!(c, d);
+        assert_eq!(d[0], 12f32.to_radians());
+        assert_eq!(d.z(), 100.);
+        assert_eq!(Coor3D32::geo(55., 12., 100.), d);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Coor3D32([1., 2., 3.]);
+        let b = Coor3D32([4., 3., 2.]);
+        assert_eq!(a.dot(b), 16.);
+        assert_eq!(a + b, Coor3D32([5., 5., 5.]));
+        assert_eq!(a.scale(2.), Coor3D32([2., 4., 6.]));
+    }
+
+    #[test]
+    fn point_cloud() -> Result<(), Error> {
+        // Local offsets keep the accuracy at the sub-mm level
+        let origin = Coor3D::raw(691000., 6174000., 0.);
+        let mut cloud = vec![Coor3D32::raw(123.4567, 876.5432, 42.123)];
+        let mut ctx = Minimal::new();
+        let op = ctx.op("addone")?;
+        assert_eq!(ctx.apply(op, Fwd, &mut cloud)?, 1);
+        let c = Coor3D::from(cloud[0]) + origin;
+        assert!((c[0] - 691124.4567).abs() < 1e-4);
+        assert!((c[1] - 6174876.5432).abs() < 1e-4);
+        assert!((c[2] - 42.123).abs() < 1e-5);
+        Ok(())
+    }
+}
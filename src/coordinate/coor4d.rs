@@ -120,6 +120,39 @@ mod tests {
         assert_eq!(e.x(), c.to_degrees().x());
     }
 
+    #[test]
+    fn conversions() {
+        // Promotion: Height 0, time NaN
+        let c = Coor4D::from(Coor2D::raw(1., 2.));
+        assert_eq!(c.xyz(), (1., 2., 0.));
+        assert!(c.t().is_nan());
+        let c: Coor4D = Coor3D::raw(1., 2., 3.).into();
+        assert_eq!(c.xyz(), (1., 2., 3.));
+        assert!(c.t().is_nan());
+        assert_eq!(Coor3D::from(Coor32::raw(1., 2.)), Coor3D::raw(1., 2., 0.));
+        assert_eq!(Coor2D::from(Coor32::raw(1., 2.)), Coor2D::raw(1., 2.));
+
+        // Demotion
+        let c = Coor4D::raw(1., 2., 3., 4.);
+        assert_eq!(c.truncated::<Coor3D>(), Coor3D::raw(1., 2., 3.));
+        assert_eq!(c.truncated::<Coor2D>(), Coor2D::raw(1., 2.));
+        assert_eq!(c.truncated::<Coor32>(), Coor32::raw(1., 2.));
+
+        // Explicit values for the extra elements
+        assert_eq!(Coor2D::raw(1., 2.).with_height(3.), Coor3D::raw(1., 2., 3.));
+        assert_eq!(
+            Coor32::raw(1., 2.).with_epoch(4.),
+            Coor4D::raw(1., 2., 0., 4.)
+        );
+        assert_eq!(Coor3D::raw(1., 2., 3.).with_epoch(4.), c);
+        assert_eq!(c.with_height(5.), Coor4D::raw(1., 2., 5., 4.));
+
+        // Point clouds
+        let cloud = vec![Coor2D::raw(1., 2.), Coor2D::raw(3., 4.)];
+        let cloud: Vec<Coor3D> = cloud.into_iter().map(Coor3D::from).collect();
+        assert_eq!(cloud[1], Coor3D::raw(3., 4., 0.));
+    }
+
     #[test]
     fn array() {
         let b = Coor4D::raw(7., 8., 9., 10.);
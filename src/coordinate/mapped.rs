@@ -0,0 +1,152 @@
+//! A `CoordinateSet` backed by a memory mapped file of binary coordinate
+//! records, for transforming data sets larger than the available RAM.
+//!
+//! The file format is the one read and written by `kp` in binary mode: A
+//! headerless sequence of records, each consisting of `dim` little endian
+//! `f64`s. Changes made through `set_coord()` go directly to the mapping,
+//! and are written back to the file by the operating system, or explicitly
+//! by [`MappedCoordinateSet::flush()`].
+use super::*;
+use memmap2::MmapMut;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+
+/// A read-write `CoordinateSet` view of a file of `dim`-dimensional binary
+/// coordinate records, cf. the [module documentation](self).
+///
+/// Note that the file must not be truncated, or modified by other processes,
+/// while mapped: This would be undefined behaviour, but cannot be prevented
+/// by the type system.
+pub struct MappedCoordinateSet {
+    map: MmapMut,
+    dim: usize,
+}
+
+impl MappedCoordinateSet {
+    /// Map the existing file at `path`, taking it as consisting of records
+    /// of `dim` elements each
+    pub fn open(path: impl AsRef<Path>, dim: usize) -> Result<Self, Error> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Self::map(&file, dim)
+    }
+
+    /// Create a file at `path`, with room for `len` records of `dim` elements
+    /// each, initially all zero, and map it
+    pub fn create(path: impl AsRef<Path>, dim: usize, len: usize) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len((len * dim * 8) as u64)?;
+        Self::map(&file, dim)
+    }
+
+    fn map(file: &File, dim: usize) -> Result<Self, Error> {
+        if !(1..=4).contains(&dim) {
+            return Err(Error::Invalid(format!(
+                "Coordinate dimension must be 1..=4, got {dim}"
+            )));
+        }
+        let size = file.metadata()?.len() as usize;
+        if size % (dim * 8) != 0 {
+            return Err(Error::Invalid(format!(
+                "File size {size} is not a whole number of {dim}D binary records"
+            )));
+        }
+        // SAFETY: The mapping is only valid as long as no one else truncates
+        // or modifies the file. This cannot be enforced, so it is documented
+        // as a precondition in the type documentation
+        let map = unsafe { MmapMut::map_mut(file)? };
+        Ok(MappedCoordinateSet { map, dim })
+    }
+
+    /// Write any outstanding changes back to the file
+    pub fn flush(&self) -> Result<(), Error> {
+        Ok(self.map.flush()?)
+    }
+
+    fn element(&self, index: usize, element: usize) -> f64 {
+        let start = (index * self.dim + element) * 8;
+        f64::from_le_bytes(self.map[start..start + 8].try_into().unwrap())
+    }
+
+    fn set_element(&mut self, index: usize, element: usize, value: f64) {
+        let start = (index * self.dim + element) * 8;
+        self.map[start..start + 8].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+impl CoordinateMetadata for MappedCoordinateSet {}
+
+impl CoordinateSet for MappedCoordinateSet {
+    fn len(&self) -> usize {
+        self.map.len() / (8 * self.dim)
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    // Missing elements are filled as for the 2D and 3D coordinate sets:
+    // 0 for the height, and NaN for the time
+    fn get_coord(&self, index: usize) -> Coor4D {
+        let mut coord = Coor4D([0., 0., 0., f64::NAN]);
+        for i in 0..self.dim {
+            coord[i] = self.element(index, i);
+        }
+        coord
+    }
+
+    fn set_coord(&mut self, index: usize, value: &Coor4D) {
+        for i in 0..self.dim {
+            self.set_element(index, i, value[i]);
+        }
+    }
+
+    fn xy(&self, index: usize) -> (f64, f64) {
+        let y = if self.dim > 1 {
+            self.element(index, 1)
+        } else {
+            0.
+        };
+        (self.element(index, 0), y)
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapped() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("addone")?;
+        let path = std::env::temp_dir().join(format!("geodesy-mapped-{}.bin", std::process::id()));
+
+        let mut data = MappedCoordinateSet::create(&path, 3, 2)?;
+        assert_eq!(data.len(), 2);
+        assert_eq!(data.dim(), 3);
+        data.set_coord(0, &Coor4D::raw(55., 12., 100., 2020.));
+        data.set_coord(1, &Coor4D::raw(59., 18., 200., 2020.));
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        data.flush()?;
+        drop(data);
+
+        // Reopen, and check that the changes went to the file
+        let data = MappedCoordinateSet::open(&path, 3)?;
+        assert_eq!(data.len(), 2);
+        assert_eq!(data.xyz(0), (56., 12., 100.));
+        assert!(data.get_coord(1)[3].is_nan());
+        assert_eq!(data.xy(1), (60., 18.));
+        drop(data);
+
+        // The file size must match the record size
+        assert!(MappedCoordinateSet::open(&path, 4).is_err());
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,16 @@
 use crate::prelude::*;
+use std::f64::consts::{PI, TAU};
 pub mod set;
 pub mod tuple;
 
 pub mod coor2d;
 pub mod coor32;
 pub mod coor3d;
+pub mod coor3d32;
 pub mod coor4d;
+#[cfg(feature = "mmap")]
+pub mod mapped;
+mod text;
 
 /// Methods for changing the coordinate representation of angles.
 /// Dimensionality untold, the methods operate on the first two
@@ -21,6 +26,23 @@ pub trait AngularUnits {
     /// of arc.
     fn to_arcsec(&self) -> Self;
 
+    /// Transform the first two elements of a coordinate tuple from radians to
+    /// gradians (gon), i.e. 400ths of a full circle
+    fn to_gon(&self) -> Self;
+
+    /// Transform the first two elements of a coordinate tuple from gradians (gon)
+    /// to radians
+    #[allow(clippy::wrong_self_convention)]
+    fn from_gon(&self) -> Self;
+
+    /// Transform the first two elements of a coordinate tuple from radians to turns,
+    /// i.e. full circles
+    fn to_turns(&self) -> Self;
+
+    /// Transform the first two elements of a coordinate tuple from turns to radians
+    #[allow(clippy::wrong_self_convention)]
+    fn from_turns(&self) -> Self;
+
     /// Transform the internal lon/lat(/h/t)-in-radians to lat/lon(/h/t)-in-degrees
     fn to_geo(&self) -> Self;
 }
@@ -53,6 +75,38 @@ where
         res
     }
 
+    /// Convert the first two elements of `self` from radians to gradians
+    fn to_gon(&self) -> Self {
+        let (x, y) = self.xy();
+        let mut res = *self;
+        res.set_xy(x * 200. / PI, y * 200. / PI);
+        res
+    }
+
+    /// Convert the first two elements of `self` from gradians to radians
+    fn from_gon(&self) -> Self {
+        let (x, y) = self.xy();
+        let mut res = *self;
+        res.set_xy(x * PI / 200., y * PI / 200.);
+        res
+    }
+
+    /// Convert the first two elements of `self` from radians to turns
+    fn to_turns(&self) -> Self {
+        let (x, y) = self.xy();
+        let mut res = *self;
+        res.set_xy(x / TAU, y / TAU);
+        res
+    }
+
+    /// Convert the first two elements of `self` from turns to radians
+    fn from_turns(&self) -> Self {
+        let (x, y) = self.xy();
+        let mut res = *self;
+        res.set_xy(x * TAU, y * TAU);
+        res
+    }
+
     /// Convert-and-swap the first two elements of `self` from radians to degrees
     fn to_geo(&self) -> Self {
         let (x, y) = self.xy();
@@ -85,18 +139,27 @@ pub struct DataEpoch(f64);
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub struct MdIdentifier(uuid::Uuid);
 
-/// CRS given as a register item
+/// CRS given as a register item (authority and code, e.g. `EPSG:4326`),
+/// or as free text
 #[derive(Debug, Default, PartialEq, PartialOrd, Clone)]
 pub enum Crs {
     #[default]
     Unknown,
     RegisterItem(String, String),
+    Text(String),
 }
 
 // ----- Interface: Coordinate Metadata ---------------------------------------
 
 /// The ISO-19111 Coordinate Metadata gamut includes an optional
-///  epoch and one of two possible ways of representing the CRS
+///  epoch and one of two possible ways of representing the CRS.
+///
+/// All methods have default implementations, so for user defined
+/// `CoordinateSet`s without metadata, an empty `impl CoordinateMetadata
+/// for ... {}` block is sufficient. If `coordinate_epoch()` returns an
+/// epoch, [`Context::apply()`](crate::ctx::Context::apply) will use it as
+/// the observation time for the coordinate tuples lacking one (i.e. having
+/// a NaN 4th element)
 pub trait CoordinateMetadata {
     fn crs_id(&self) -> Option<MdIdentifier> {
         None
@@ -116,6 +179,3 @@ pub trait CoordinateMetadata {
         // TODO: check for coordinate_epoch.is_some() for dynamic crs
     }
 }
-
-// Preliminary empty blanket implementation: Defaults for all items, for all types
-impl<T> CoordinateMetadata for T where T: ?Sized {}
@@ -77,6 +77,33 @@ pub trait CoordinateSet: CoordinateMetadata {
         self.get_coord(index).xyzt()
     }
 
+    /// Direct access to the underlying coordinate tuples, for sets stored
+    /// contiguously as `Coor4D`. Operators may use this as a fast path,
+    /// bypassing the per-tuple `get_coord()`/`set_coord()` calls. `None`
+    /// (the default) for all other sets
+    fn as_coor4d_mut(&mut self) -> Option<&mut [Coor4D]> {
+        None
+    }
+
+    /// Convert the first two elements of all coordinate tuples in the set
+    /// from degrees to radians, in place. The default version goes through
+    /// [`Self::xy()`] and [`Self::set_xy()`], so it is as efficient as those
+    fn to_radians_in_place(&mut self) {
+        for i in 0..self.len() {
+            let (x, y) = self.xy(i);
+            self.set_xy(i, x.to_radians(), y.to_radians());
+        }
+    }
+
+    /// Convert the first two elements of all coordinate tuples in the set
+    /// from radians to degrees, in place. Cf. [`Self::to_radians_in_place()`]
+    fn to_degrees_in_place(&mut self) {
+        for i in 0..self.len() {
+            let (x, y) = self.xy(i);
+            self.set_xy(i, x.to_degrees(), y.to_degrees());
+        }
+    }
+
     /// Set all coordinate tuples in the set to NaN
     fn stomp(&mut self) {
         let nanny = Coor4D::nan();
@@ -161,7 +188,6 @@ macro_rules! coordinate_set_impl_3d_subset {
 /// `(T, f64, f64) where T: CoordinateSet`, so any data structure implementing the
 /// `CoordinateSet` trait can be combined with two fixed values for third and fourth
 /// coordinate dimension.
-
 macro_rules! coordinate_set_impl_for_coor2d {
     ($kind:ident) => {
         coordinate_set_impl_2d_subset!(2, $kind);
@@ -244,6 +270,35 @@ impl CoordinateSet for Vec<Coor3D> {
     coordinate_set_impl_for_coor3d!(vec);
 }
 
+// ----- CoordinateSet implementations for some Coor3D32 containers ----------
+
+macro_rules! coordinate_set_impl_for_coor3d32 {
+    ($kind:ident) => {
+        coordinate_set_impl_3d_subset!(3, $kind);
+
+        fn get_coord(&self, index: usize) -> Coor4D {
+            let c = self[index];
+            Coor4D([c[0] as f64, c[1] as f64, c[2] as f64, f64::NAN])
+        }
+
+        fn set_coord(&mut self, index: usize, value: &Coor4D) {
+            self[index] = Coor3D32([value[0] as f32, value[1] as f32, value[2] as f32]);
+        }
+    };
+}
+
+impl<const N: usize> CoordinateSet for [Coor3D32; N] {
+    coordinate_set_impl_for_coor3d32!(array);
+}
+
+impl CoordinateSet for &mut [Coor3D32] {
+    coordinate_set_impl_for_coor3d32!(slice);
+}
+
+impl CoordinateSet for Vec<Coor3D32> {
+    coordinate_set_impl_for_coor3d32!(vec);
+}
+
 // ----- CoordinateSet implementations for some Coor4D containers ------------
 
 macro_rules! coordinate_set_impl_for_coor4d {
@@ -265,6 +320,10 @@ macro_rules! coordinate_set_impl_for_coor4d {
         fn set_xyzt(&mut self, index: usize, x: f64, y: f64, z: f64, t: f64) {
             self[index].set_xyzt(x, y, z, t);
         }
+
+        fn as_coor4d_mut(&mut self) -> Option<&mut [Coor4D]> {
+            Some(&mut self[..])
+        }
     };
 }
 
@@ -324,6 +383,118 @@ where
     }
 }
 
+// ----- Views into other CoordinateSets --------------------------------------
+
+/// A view of the sub-range `range` of another `CoordinateSet`, itself acting
+/// as a `CoordinateSet`, without copying any data. Indices into the view are
+/// relative to the start of the range, and the metadata are those of the
+/// underlying set.
+///
+/// Intended for chunked processing, progress reporting, and re-processing
+/// of parts of a data set, e.g.:
+///
+/// ```
+/// # use geodesy::prelude::*;
+/// # fn main() -> Result<(), Error> {
+/// let mut ctx = Minimal::new();
+/// let op = ctx.op("addone")?;
+/// let mut data = vec![Coor2D::raw(1., 2.); 1000];
+/// for start in (0..data.len()).step_by(100) {
+///     let mut chunk = CoordinateSubset::new(&mut data, start..start + 100);
+///     ctx.apply(op, Fwd, &mut chunk)?;
+/// }
+/// assert_eq!(data[999], Coor2D::raw(2., 2.));
+/// # Ok(())
+/// # }
+/// ```
+pub struct CoordinateSubset<'a> {
+    operands: &'a mut dyn CoordinateSet,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a> CoordinateSubset<'a> {
+    /// A view of the elements of `operands` within `range`. The range is
+    /// clipped to the extent of `operands`
+    pub fn new(operands: &'a mut dyn CoordinateSet, range: std::ops::Range<usize>) -> Self {
+        let offset = range.start.min(operands.len());
+        let len = range.end.min(operands.len()).saturating_sub(offset);
+        CoordinateSubset {
+            operands,
+            offset,
+            len,
+        }
+    }
+}
+
+impl CoordinateMetadata for CoordinateSubset<'_> {
+    fn crs_id(&self) -> Option<MdIdentifier> {
+        self.operands.crs_id()
+    }
+    fn crs(&self) -> Option<Crs> {
+        self.operands.crs()
+    }
+    fn coordinate_epoch(&self) -> Option<DataEpoch> {
+        self.operands.coordinate_epoch()
+    }
+}
+
+impl CoordinateSet for CoordinateSubset<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn dim(&self) -> usize {
+        self.operands.dim()
+    }
+
+    fn get_coord(&self, index: usize) -> Coor4D {
+        if index >= self.len {
+            return Coor4D::nan();
+        }
+        self.operands.get_coord(self.offset + index)
+    }
+
+    fn set_coord(&mut self, index: usize, value: &Coor4D) {
+        if index < self.len {
+            self.operands.set_coord(self.offset + index, value);
+        }
+    }
+
+    fn xy(&self, index: usize) -> (f64, f64) {
+        if index >= self.len {
+            return (f64::NAN, f64::NAN);
+        }
+        self.operands.xy(self.offset + index)
+    }
+
+    fn set_xy(&mut self, index: usize, x: f64, y: f64) {
+        if index < self.len {
+            self.operands.set_xy(self.offset + index, x, y);
+        }
+    }
+
+    fn xyz(&self, index: usize) -> (f64, f64, f64) {
+        if index >= self.len {
+            return (f64::NAN, f64::NAN, f64::NAN);
+        }
+        self.operands.xyz(self.offset + index)
+    }
+
+    fn set_xyz(&mut self, index: usize, x: f64, y: f64, z: f64) {
+        if index < self.len {
+            self.operands.set_xyz(self.offset + index, x, y, z);
+        }
+    }
+
+    fn as_coor4d_mut(&mut self) -> Option<&mut [Coor4D]> {
+        let (offset, len) = (self.offset, self.len);
+        self.operands
+            .as_coor4d_mut()
+            .map(|all| &mut all[offset..offset + len])
+    }
+}
+
 // ----- Implementations: Coordinate Metadata ---------------------------------
 impl MdIdentifier {
     pub fn new() -> Self {
@@ -336,10 +507,102 @@ impl Default for MdIdentifier {
     }
 }
 
+impl Crs {
+    /// Interpret `text` as a register item, if it looks like `authority:code`,
+    /// and as free text otherwise. Empty text is an unknown CRS. The authority
+    /// is case insensitive, and normalized to upper case
+    pub fn new(text: &str) -> Self {
+        let text = text.trim();
+        if text.is_empty() {
+            return Crs::Unknown;
+        }
+        if let Some((authority, code)) = text.split_once(':') {
+            let well_formed = |s: &str| !s.is_empty() && !s.contains(char::is_whitespace);
+            if well_formed(authority) && well_formed(code) && !code.contains(':') {
+                return Crs::RegisterItem(authority.to_uppercase(), code.to_string());
+            }
+        }
+        Crs::Text(text.to_string())
+    }
+
+    pub fn is_known(&self) -> bool {
+        *self != Crs::Unknown
+    }
+}
+
+impl std::fmt::Display for Crs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Crs::Unknown => write!(f, "unknown"),
+            Crs::RegisterItem(authority, code) => write!(f, "{authority}:{code}"),
+            Crs::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
 impl DataEpoch {
     pub fn new() -> Self {
         DataEpoch(f64::NAN)
     }
+
+    /// The epoch as a decimal year
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+impl From<f64> for DataEpoch {
+    fn from(epoch: f64) -> Self {
+        DataEpoch(epoch)
+    }
+}
+
+// The builtin containers carry no metadata, so they get the defaults
+impl<const N: usize> CoordinateMetadata for [Coor2D; N] {}
+impl CoordinateMetadata for &mut [Coor2D] {}
+impl CoordinateMetadata for Vec<Coor2D> {}
+impl<const N: usize> CoordinateMetadata for [Coor32; N] {}
+impl CoordinateMetadata for &mut [Coor32] {}
+impl CoordinateMetadata for Vec<Coor32> {}
+impl<const N: usize> CoordinateMetadata for [Coor3D; N] {}
+impl CoordinateMetadata for &mut [Coor3D] {}
+impl CoordinateMetadata for Vec<Coor3D> {}
+impl<const N: usize> CoordinateMetadata for [Coor3D32; N] {}
+impl CoordinateMetadata for &mut [Coor3D32] {}
+impl CoordinateMetadata for Vec<Coor3D32> {}
+impl<const N: usize> CoordinateMetadata for [Coor4D; N] {}
+impl CoordinateMetadata for &mut [Coor4D] {}
+impl CoordinateMetadata for Vec<Coor4D> {}
+
+// ...while the fixed-value wrappers inherit the metadata of what they wrap
+impl<T> CoordinateMetadata for (T, f64, f64)
+where
+    T: CoordinateSet,
+{
+    fn crs_id(&self) -> Option<MdIdentifier> {
+        self.0.crs_id()
+    }
+    fn crs(&self) -> Option<Crs> {
+        self.0.crs()
+    }
+    fn coordinate_epoch(&self) -> Option<DataEpoch> {
+        self.0.coordinate_epoch()
+    }
+}
+
+impl<T> CoordinateMetadata for (T, f64)
+where
+    T: CoordinateSet,
+{
+    fn crs_id(&self) -> Option<MdIdentifier> {
+        self.0.crs_id()
+    }
+    fn crs(&self) -> Option<Crs> {
+        self.0.crs()
+    }
+    fn coordinate_epoch(&self) -> Option<DataEpoch> {
+        self.0.coordinate_epoch()
+    }
 }
 
 // ----- T E S T S ---------------------------------------------------
@@ -417,6 +680,52 @@ mod tests {
         );
     }
 
+    // Test the bulk, in place, angular conversions
+    #[test]
+    fn angular_in_place() {
+        let mut operands = Vec::from(crate::test_data::coor2d());
+        operands.to_radians_in_place();
+        assert_eq!(operands[0], Coor2D::raw(55., 12.).to_radians());
+        assert_eq!(operands[1], Coor2D::raw(59., 18.).to_radians());
+        operands.to_degrees_in_place();
+        assert!((operands[0][0] - 55.).abs() < 1e-12);
+        assert!((operands[1][1] - 18.).abs() < 1e-12);
+
+        // Height and time are left untouched
+        let mut operands = ([Coor2D::raw(55., 12.)], 100., 2020.);
+        operands.to_radians_in_place();
+        assert_eq!(
+            operands.get_coord(0),
+            Coor4D::raw(55., 12., 100., 2020.).to_radians()
+        );
+    }
+
+    // Test the sub-range views
+    #[test]
+    fn subset() {
+        let mut operands: Vec<Coor4D> =
+            (0..10).map(|i| Coor4D::raw(i as f64, 0., 0., 0.)).collect();
+        let mut view = CoordinateSubset::new(&mut operands, 3..6);
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.dim(), 4);
+        assert_eq!(view.get_coord(0)[0], 3.);
+        assert_eq!(view.xy(2), (5., 0.));
+        assert!(view.get_coord(3)[0].is_nan());
+        assert_eq!(view.as_coor4d_mut().unwrap().len(), 3);
+
+        view.set_xy(0, 42., 43.);
+        view.stomp();
+        assert_eq!(operands[2][0], 2.);
+        assert!(operands[3][0].is_nan());
+        assert!(operands[5][0].is_nan());
+        assert_eq!(operands[6][0], 6.);
+
+        // Ranges are clipped to the extent of the underlying set
+        let mut operands = Vec::from(crate::test_data::coor2d());
+        assert_eq!(CoordinateSubset::new(&mut operands, 1..100).len(), 1);
+        assert!(CoordinateSubset::new(&mut operands, 5..100).is_empty());
+    }
+
     #[test]
     fn setting_and_getting_as_f64() {
         let first = Coor4D([11., 12., 13., 14.]);
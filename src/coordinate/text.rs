@@ -0,0 +1,212 @@
+//! Text representations of the coordinate tuple types, for parsing coordinates
+//! from, and printing them to, text in the same way as `kp` does.
+//!
+//! Parsing (`FromStr`) accepts:
+//!
+//! - Whitespace and/or comma separated elements, each of which may be given
+//!   as a real number, or in any of the sexagesimal formats handled by
+//!   [`parse_sexagesimal()`](crate::math::angular::parse_sexagesimal),
+//!   e.g. `55:30:36N, 12°45'36"E` (but note that whitespace separates elements,
+//!   so `55 30 36` is three elements, not one sexagesimal angle)
+//! - The ISO 6709 (Annex H) string representation, e.g. `+5530.6+01245.6+100/`,
+//!   with latitude and longitude given as ±DD.D, ±DDMM.M or ±DDMMSS.S (and
+//!   ±DDD.D, ±DDDMM.M or ±DDDMMSS.S respectively), optionally followed by
+//!   an altitude and a CRS identifier
+//!
+//! The elements are returned in the order given, with any angles in degrees,
+//! i.e. *without* the conversion to radians and lon/lat order performed by
+//! constructors like `Coor2D::geo()`. Missing elements are filled as for the
+//! `From` conversions between the coordinate types: A third element of 0,
+//! and a fourth of NaN. Too many, or too few (less than 2), elements is an error.
+//!
+//! Formatting (`Display`) writes the elements separated by single spaces. The
+//! formatter's precision and width apply to each element, and the alternate
+//! form (`{:#}`) writes the first two elements as sexagesimal latitude and
+//! longitude, e.g. `format!("{:#.1}", c)` gives `55°30'36.0"N 12°45'36.0"E`.
+use super::*;
+use crate::math::angular;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Parse `text` into at most `dim` elements
+fn parse_elements(text: &str, dim: usize) -> Result<Vec<f64>, Error> {
+    let error = || Error::Syntax(format!("Cannot parse '{text}' as a coordinate tuple"));
+    let trimmed = text.trim();
+    let mut elements = if is_iso6709(trimmed) {
+        parse_iso6709(trimmed).ok_or_else(error)?
+    } else {
+        let mut elements = Vec::new();
+        for element in trimmed.split(|c: char| c.is_whitespace() || c == ',') {
+            if element.is_empty() {
+                continue;
+            }
+            let value = angular::parse_sexagesimal(element);
+            if value.is_nan() && element != "NaN" {
+                return Err(error());
+            }
+            elements.push(value);
+        }
+        elements
+    };
+    if elements.len() < 2 || elements.len() > dim {
+        return Err(error());
+    }
+    // Fill rules: Height 0, time NaN
+    elements.resize(dim.min(3).max(elements.len()), 0.);
+    elements.resize(dim, f64::NAN);
+    Ok(elements)
+}
+
+// The ISO 6709 string representation starts with the sign of the latitude,
+// and the elements are delimited by their signs, so it contains no separators
+fn is_iso6709(text: &str) -> bool {
+    text.starts_with(['+', '-'])
+        && text[1..].contains(['+', '-'])
+        && !text.contains(|c: char| c.is_whitespace() || c == ',' || c == ':')
+}
+
+// Latitude, longitude, and (optionally) altitude from an ISO 6709 string, e.g.
+// `+55.51+012.76/`, `+5530.6+01245.6+100CRSWGS_84/`, or `+553036-0124536/`
+fn parse_iso6709(text: &str) -> Option<Vec<f64>> {
+    let text = text.trim_end_matches('/');
+    let text = text.split("CRS").next()?;
+
+    // Split into elements, each starting with its sign
+    let mut elements = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices().skip(1) {
+        if c == '+' || c == '-' {
+            elements.push(&text[start..i]);
+            start = i;
+        }
+    }
+    elements.push(&text[start..]);
+    if elements.len() < 2 || elements.len() > 3 {
+        return None;
+    }
+
+    // The number of integer digits tells the format: Degrees, degrees and
+    // minutes, or degrees, minutes and seconds
+    let angle = |element: &str, degree_digits: usize| -> Option<f64> {
+        let value = element.parse::<f64>().ok()?;
+        let digits = element[1..].split('.').next()?.len();
+        match digits.checked_sub(degree_digits)? {
+            0 => Some(value),
+            2 => Some(angular::iso_dm_to_dd(value)),
+            4 => Some(angular::iso_dms_to_dd(value)),
+            _ => None,
+        }
+    };
+    let latitude = angle(elements[0], 2)?;
+    let longitude = angle(elements[1], 3)?;
+    let mut result = vec![latitude, longitude];
+    if let Some(altitude) = elements.get(2) {
+        result.push(altitude.parse::<f64>().ok()?);
+    }
+    Some(result)
+}
+
+// Write `elements`, separated by single spaces, honoring the precision and
+// width of `f`, and in the alternate form, writing the first two elements
+// as sexagesimal latitude and longitude
+fn format_elements(f: &mut Formatter<'_>, elements: &[f64]) -> std::fmt::Result {
+    let width = f.width().unwrap_or(0);
+    for (i, &e) in elements.iter().enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        let text = match (f.alternate(), i, f.precision()) {
+            (true, 0, p) => angular::format_sexagesimal(e, p.unwrap_or(3), ['N', 'S']),
+            (true, 1, p) => angular::format_sexagesimal(e, p.unwrap_or(3), ['E', 'W']),
+            (_, _, Some(p)) => format!("{e:.p$}"),
+            (_, _, None) => format!("{e}"),
+        };
+        write!(f, "{text:>width$}")?;
+    }
+    Ok(())
+}
+
+macro_rules! coord_text {
+    ($type:ident, $dim:expr) => {
+        impl FromStr for $type {
+            type Err = Error;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let elements = parse_elements(s, $dim)?;
+                let mut res = $type::new(0.);
+                for (i, e) in elements.into_iter().enumerate() {
+                    res.set_nth_unchecked(i, e);
+                }
+                Ok(res)
+            }
+        }
+
+        impl Display for $type {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                let elements: Vec<f64> = (0..$dim).map(|i| self.nth_unchecked(i)).collect();
+                format_elements(f, &elements)
+            }
+        }
+    };
+}
+
+coord_text!(Coor2D, 2);
+coord_text!(Coor3D, 3);
+coord_text!(Coor4D, 4);
+coord_text!(Coor32, 2);
+coord_text!(Coor3D32, 3);
+
+// ----- T E S T S ---------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() -> Result<(), Error> {
+        // Whitespace and/or comma separated, fill rules
+        assert_eq!("55 12".parse::<Coor2D>()?, Coor2D::raw(55., 12.));
+        assert_eq!("55, 12,100".parse::<Coor3D>()?, Coor3D::raw(55., 12., 100.));
+        let c: Coor4D = "55 12".parse()?;
+        assert_eq!(c.xyz(), (55., 12., 0.));
+        assert!(c.t().is_nan());
+        assert_eq!("1 2 3 NaN".parse::<Coor4D>()?.xyz(), (1., 2., 3.));
+
+        // Sexagesimal
+        let c: Coor2D = "55:30:36N 12°45'36\"W".parse()?;
+        assert!((c[0] - 55.51).abs() < 1e-12);
+        assert!((c[1] + 12.76).abs() < 1e-12);
+
+        // ISO 6709, in degrees, degrees and minutes, and degrees, minutes and seconds
+        let c: Coor3D = "+55.51-012.76+100/".parse()?;
+        assert_eq!(c, Coor3D::raw(55.51, -12.76, 100.));
+        let c: Coor3D = "+5530.6+01245.6CRSWGS_84/".parse()?;
+        assert!((c[0] - 55.51).abs() < 1e-12 && (c[1] - 12.76).abs() < 1e-12);
+        assert_eq!(c[2], 0.);
+        let c: Coor2D = "-553036+0124536".parse()?;
+        assert!((c[0] + 55.51).abs() < 1e-12 && (c[1] - 12.76).abs() < 1e-12);
+
+        // Garbage, too few and too many elements
+        assert!("55 foo".parse::<Coor2D>().is_err());
+        assert!("55".parse::<Coor2D>().is_err());
+        assert!("1 2 3".parse::<Coor2D>().is_err());
+        assert!("+5530.6+12".parse::<Coor2D>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<(), Error> {
+        let c = Coor4D::raw(55.51, -12.76, 100., 2020.);
+        assert_eq!(c.to_string(), "55.51 -12.76 100 2020");
+        assert_eq!(format!("{c:.2}"), "55.51 -12.76 100.00 2020.00");
+        assert_eq!(format!("{:7.1}", Coor2D::raw(1., 2.)), "    1.0     2.0");
+        assert_eq!(
+            format!("{c:#.1}"),
+            "55°30'36.0\"N 12°45'36.0\"W 100.0 2020.0"
+        );
+
+        // Roundtrip
+        let c = Coor3D::raw(55.123456789012, 12.5, -3.25);
+        assert_eq!(c.to_string().parse::<Coor3D>()?, c);
+        Ok(())
+    }
+}
@@ -25,6 +25,7 @@ coord_indexing!(Coor2D, f64);
 coord_indexing!(Coor3D, f64);
 coord_indexing!(Coor4D, f64);
 coord_indexing!(Coor32, f32);
+coord_indexing!(Coor3D32, f32);
 
 // ---- Vector space operators for the primary CoorND types ----
 
@@ -73,6 +74,17 @@ macro_rules! coor32 {
     }
 }
 
+// Helper for the coord_operator! macro
+macro_rules! coor3d32 {
+    ($symbol:tt, $self:ident, $other:ident) => {
+        Coor3D32([
+            $self.0[0] $symbol ($other.0[0] as f32),
+            $self.0[1] $symbol ($other.0[1] as f32),
+            $self.0[2] $symbol ($other.0[2] as f32),
+        ])
+    }
+}
+
 // Generate the vector space operators Add, Sub, Mul, Div for $type
 macro_rules! coord_operator {
     ($type:ty, $othertype:ty, $typemacro:ident, $op:ident, $symbol:tt, $function:ident) => {
@@ -100,12 +112,16 @@ all_coord_operators!(Coor3D, &Coor3D, coor3d);
 all_coord_operators!(Coor2D, &Coor2D, coor2d);
 all_coord_operators!(Coor2D, &Coor32, coor2d);
 all_coord_operators!(Coor32, &Coor32, coor32);
+all_coord_operators!(Coor3D, &Coor3D32, coor3d);
+all_coord_operators!(Coor3D32, &Coor3D32, coor3d32);
 
 all_coord_operators!(Coor4D, Coor4D, coor4d);
 all_coord_operators!(Coor3D, Coor3D, coor3d);
 all_coord_operators!(Coor2D, Coor2D, coor2d);
 all_coord_operators!(Coor2D, Coor32, coor2d);
 all_coord_operators!(Coor32, Coor32, coor32);
+all_coord_operators!(Coor3D, Coor3D32, coor3d);
+all_coord_operators!(Coor3D32, Coor3D32, coor3d32);
 
 /// `CoordinateTuple` is the ISO-19111 atomic spatial/spatiotemporal
 /// referencing element. So loosely speaking, a CoordinateSet is a
@@ -436,10 +452,31 @@ pub trait CoordinateTuple {
         }
         res
     }
+
+    /// Convert to another coordinate tuple type, typically one of lower
+    /// dimension (or precision), e.g. `Coor4D` to `Coor2D`: Surplus elements
+    /// are dropped. If the target type has higher dimension, the missing
+    /// elements are filled as for the `From` conversions, i.e. a third
+    /// element of 0, and a fourth element of NaN.
+    fn truncated<T: CoordinateTuple>(&self) -> T
+    where
+        Self: Sized,
+    {
+        let mut res = T::new(0.);
+        for i in 0..res.dim() {
+            let value = match i {
+                i if i < self.dim() => self.nth_unchecked(i),
+                2 => 0.,
+                _ => f64::NAN,
+            };
+            res.set_nth_unchecked(i, value);
+        }
+        res
+    }
 }
 
 // The CoordiateTuple trait is implemented for the main
-// newtypes Coor2D, Coor3D, Coor4D, Coor32 in their files
+// newtypes Coor2D, Coor3D, Coor4D, Coor32, Coor3D32 in their files
 // below. But for good measure, let's also implement it
 // for a plain 2D f64 tuple
 
@@ -467,3 +504,58 @@ impl CoordinateTuple for (f64, f64) {
         }
     }
 }
+
+// ---- Conversions between the primary CoorND types ----
+
+// Promotion to a type of higher dimension (or precision) is lossless, so we
+// implement `From`. The missing elements are filled as for the `CoordinateSet`
+// implementations: A third element (height) of 0, and a fourth (time) of NaN.
+// Demotion is lossy, and must be requested explicitly, using `truncated()`
+macro_rules! coord_promotion {
+    ($from:ty, $to:ty) => {
+        impl From<$from> for $to {
+            fn from(c: $from) -> Self {
+                c.truncated()
+            }
+        }
+    };
+}
+
+coord_promotion!(Coor32, Coor2D);
+coord_promotion!(Coor32, Coor3D);
+coord_promotion!(Coor32, Coor4D);
+coord_promotion!(Coor32, Coor3D32);
+coord_promotion!(Coor3D32, Coor3D);
+coord_promotion!(Coor3D32, Coor4D);
+coord_promotion!(Coor2D, Coor3D);
+coord_promotion!(Coor2D, Coor4D);
+coord_promotion!(Coor3D, Coor4D);
+
+macro_rules! coord_with_height_and_epoch {
+    ($type:ty, $with_height:ty) => {
+        impl $type {
+            /// Promote to (or update) a 3D coordinate, with `height` as the third element
+            #[must_use]
+            pub fn with_height(&self, height: f64) -> $with_height {
+                let mut res: $with_height = self.truncated();
+                res.set_nth_unchecked(2, height);
+                res
+            }
+
+            /// Promote to (or update) a 4D coordinate, with `epoch` as the fourth
+            /// element. A missing third element (height) is set to 0
+            #[must_use]
+            pub fn with_epoch(&self, epoch: f64) -> Coor4D {
+                let mut res: Coor4D = self.truncated();
+                res[3] = epoch;
+                res
+            }
+        }
+    };
+}
+
+coord_with_height_and_epoch!(Coor32, Coor3D);
+coord_with_height_and_epoch!(Coor2D, Coor3D);
+coord_with_height_and_epoch!(Coor3D, Coor3D);
+coord_with_height_and_epoch!(Coor3D32, Coor3D32);
+coord_with_height_and_epoch!(Coor4D, Coor4D);
@@ -35,15 +35,47 @@ impl Ellipsoid {
         }
     }
 
+    /// Ellipsoid defined by its semimajor axis, and one of the shape parameters
+    /// known from PROJ: `b` (the semiminor axis), `rf` (the reciproque
+    /// flattening), `f` (the flattening), `e` (the eccentricity), or `es`
+    /// (the squared eccentricity)
+    pub fn from_shape(semimajor_axis: f64, shape: &str, value: f64) -> Result<Ellipsoid, Error> {
+        let a = semimajor_axis;
+        let f = match shape {
+            "b" => 1. - value / a,
+            // EPSG convention: zero reciproque flattening indicates zero flattening
+            "rf" if value == 0. => 0.,
+            "rf" => 1. / value,
+            "f" => value,
+            "e" => 1. - (1. - value * value).sqrt(),
+            "es" => 1. - (1. - value).sqrt(),
+            _ => return Err(Error::BadParam(shape.to_string(), value.to_string())),
+        };
+        if a.is_nan() || a <= 0. {
+            return Err(Error::BadParam("a".to_string(), a.to_string()));
+        }
+        if !(0. ..1.).contains(&f) {
+            return Err(Error::BadParam(shape.to_string(), value.to_string()));
+        }
+        Ok(Ellipsoid::new(a, f))
+    }
+
     /// Predefined ellipsoid; built-in, defined in asset collections, or given as a
-    /// string formatted (a, rf) tuple, e.g. "6378137, 298.25"
+    /// string formatted (a, rf) tuple, e.g. "6378137, 298.25".
+    ///
+    /// Spheres may be given by their radius, e.g. "6371000", or as a radius
+    /// derived from an ellipsoid, in the form `R_x:ellipsoid`, e.g. "R_A:GRS80",
+    /// where, as in PROJ, `R_x` is one of `R_A` (the authalic radius), `R_V`
+    /// (the volumetric radius), and the arithmetic, geometric, and harmonic
+    /// means of the semiaxes, `R_a`, `R_g` and `R_h`.
+    ///
+    /// The built-ins include the celestial bodies listed by [`bodies`](Self::bodies).
+    /// For triaxial bodies, the semimedian axis is ignored: Use
+    /// [`TriaxialEllipsoid::named`](crate::ellps::TriaxialEllipsoid::named)
+    /// to get the full shape.
     pub fn named(name: &str) -> Result<Ellipsoid, Error> {
-        // Is it one of the few builtins?
-        if let Some(index) = super::constants::ELLIPSOID_LIST
-            .iter()
-            .position(|&ellps| ellps.0 == name)
-        {
-            let e = super::constants::ELLIPSOID_LIST[index];
+        // Is it one of the builtins?
+        if let Some(e) = super::constants::builtin(name) {
             let ax: f64 = e.1.parse().unwrap();
             let rf: f64 = e.3.parse().unwrap();
             // EPSG convention: zero reciproque flattening indicates zero flattening
@@ -62,17 +94,70 @@ impl Ellipsoid {
         if a_and_rf.len() == 2_usize {
             if let Ok(a) = a_and_rf[0].trim().parse::<f64>() {
                 if let Ok(rf) = a_and_rf[1].trim().parse::<f64>() {
-                    return Ok(Ellipsoid::new(a, 1. / rf));
+                    let f = if rf != 0.0 { 1.0 / rf } else { rf };
+                    return Ok(Ellipsoid::new(a, f));
                 }
             }
         }
 
+        // A sphere, given by its radius, e.g. "6371000"
+        if let Ok(radius) = name.trim().parse::<f64>() {
+            return Ok(Ellipsoid::new(radius, 0.));
+        }
+
+        // A sphere, with a radius derived from an ellipsoid, e.g. "R_A:GRS80"
+        if let Some((radius, ellps)) = name.split_once(':') {
+            let ellps = Ellipsoid::named(ellps.trim())?;
+            let a = ellps.semimajor_axis();
+            let b = ellps.semiminor_axis();
+            let radius = match radius.trim() {
+                "R_A" => ellps.authalic_radius(),
+                "R_V" => ellps.volumetric_radius(),
+                "R_a" => (a + b) / 2.,
+                "R_g" => (a * b).sqrt(),
+                "R_h" => 2. * a * b / (a + b),
+                _ => {
+                    return Err(Error::NotFound(
+                        String::from(name),
+                        String::from("Ellipsoid::named()"),
+                    ))
+                }
+            };
+            return Ok(Ellipsoid::new(radius, 0.));
+        }
+
         // TODO: Search asset collection
         Err(Error::NotFound(
             String::from(name),
             String::from("Ellipsoid::named()"),
         ))
     }
+
+    /// The name of the built-in ellipsoid most similar to `name`, as a
+    /// suggestion when `name` is not found
+    pub(crate) fn similar(name: &str) -> Option<&'static str> {
+        super::constants::similar(name)
+    }
+
+    /// Names and descriptions of the built-in ellipsoids for celestial bodies
+    /// other than the Earth, following the IAU
+    /// ([Archinal et al., 2018](crate::Bibliography::Arc18)).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use geodesy::prelude::*;
+    /// for (name, _description) in Ellipsoid::bodies() {
+    ///     assert!(Ellipsoid::named(name).is_ok());
+    /// }
+    /// let mars = Ellipsoid::named("mars").unwrap();
+    /// assert_eq!(mars.semiminor_axis().round(), 3_376_200.);
+    /// ```
+    pub fn bodies() -> impl Iterator<Item = (&'static str, &'static str)> {
+        super::constants::BODY_LIST
+            .iter()
+            .map(|body| (body.0, body.4))
+    }
 }
 
 // ----- Tests ---------------------------------------------------------------------
@@ -106,4 +191,85 @@ mod tests {
         assert!((4.0 * ellps.meridian_quadrant() - 40_007_862.916_921_8).abs() < 1e-7);
         Ok(())
     }
+
+    #[test]
+    fn from_shape() -> Result<(), Error> {
+        let grs80 = Ellipsoid::named("GRS80")?;
+        let a = grs80.semimajor_axis();
+        let shapes = [
+            ("b", grs80.semiminor_axis()),
+            ("rf", 1. / grs80.flattening()),
+            ("f", grs80.flattening()),
+            ("e", grs80.eccentricity()),
+            ("es", grs80.eccentricity_squared()),
+        ];
+        for (shape, value) in shapes {
+            let ellps = Ellipsoid::from_shape(a, shape, value)?;
+            assert_eq!(ellps.semimajor_axis(), a);
+            assert!(
+                (ellps.flattening() - grs80.flattening()).abs() < 1e-15,
+                "{shape}"
+            );
+        }
+        assert_eq!(Ellipsoid::from_shape(a, "rf", 0.)?.flattening(), 0.);
+        assert!(Ellipsoid::from_shape(a, "g", 1.).is_err());
+        assert!(Ellipsoid::from_shape(a, "b", 2. * a).is_err());
+        assert!(Ellipsoid::from_shape(-a, "f", 0.).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn spheres() -> Result<(), Error> {
+        let sphere = Ellipsoid::named("6371000")?;
+        assert_eq!(sphere.semimajor_axis(), 6_371_000.);
+        assert_eq!(sphere.flattening(), 0.);
+        assert_eq!(Ellipsoid::named("6371000, 0")?, sphere);
+
+        // Radii derived from the GRS80 ellipsoid
+        let authalic = Ellipsoid::named("R_A:GRS80")?;
+        assert!((authalic.semimajor_axis() - 6_371_007.180_9).abs() < 1e-4);
+        assert_eq!(authalic.flattening(), 0.);
+        let volumetric = Ellipsoid::named("R_V:GRS80")?;
+        assert!((volumetric.semimajor_axis() - 6_371_000.790_0).abs() < 1e-4);
+        let arithmetic = Ellipsoid::named("R_a:GRS80")?;
+        let geometric = Ellipsoid::named("R_g:GRS80")?;
+        let harmonic = Ellipsoid::named("R_h:GRS80")?;
+        assert!(arithmetic.semimajor_axis() > geometric.semimajor_axis());
+        assert!(geometric.semimajor_axis() > harmonic.semimajor_axis());
+
+        // ...and from ellipsoids given by their parameters
+        assert_eq!(
+            Ellipsoid::named("R_A:6378137, 298.257222100882711")?,
+            authalic
+        );
+        assert!(Ellipsoid::named("R_X:GRS80").is_err());
+        assert_eq!(Ellipsoid::similar("GRS1980"), Some("GRS80"));
+        assert_eq!(Ellipsoid::similar("wgs84"), Some("WGS84"));
+        assert_eq!(Ellipsoid::similar("atlantis"), None);
+        assert!(Ellipsoid::named("R_A:atlantis").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn celestial_bodies() -> Result<(), Error> {
+        assert_eq!(Ellipsoid::bodies().count(), 19);
+        assert!(Ellipsoid::bodies().any(|(name, _)| name == "europa"));
+
+        let mars = Ellipsoid::named("mars")?;
+        assert_eq!(mars.semimajor_axis(), 3_396_190.);
+        assert!((mars.semiminor_axis() - 3_376_200.).abs() < 1e-6);
+
+        let moon = Ellipsoid::named("moon")?;
+        assert_eq!(moon.semimajor_axis(), 1_737_400.);
+        assert_eq!(moon.flattening(), 0.);
+
+        // Triaxial bodies: The semimedian axis is only available for triaxials
+        let vesta = Ellipsoid::named("vesta")?;
+        assert_eq!(vesta.semimedian_axis(), 289_000.);
+        assert!((vesta.semiminor_axis() - 229_000.).abs() < 1e-6);
+        let vesta = crate::ellps::TriaxialEllipsoid::named("vesta")?;
+        assert_eq!(vesta.semimedian_axis(), 280_000.);
+        assert!((vesta.semiminor_axis() - 229_000.).abs() < 1e-6);
+        Ok(())
+    }
 }
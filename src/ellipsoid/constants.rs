@@ -1,5 +1,3 @@
-use crate::math::PolynomialCoefficients;
-
 // A HashMap would have been a better choice,for the OPERATOR_LIST, except
 // for the annoying fact that it cannot be compile-time constructed
 #[rustfmt::skip]
@@ -53,83 +51,471 @@ pub(super) const ELLIPSOID_LIST: [(&str, &str, &str, &str, &str); 47] = [
     ("unitsphere",      "1",             "1",      "0.",                 "Unit Sphere (r=1)"),
 ];
 
+// Celestial body ellipsoids from the IAU Working Group on Cartographic
+// Coordinates and Rotational Elements (Archinal et al., 2018), in meters.
+// The Mars values date back to the IAU 2000 report, and are unchanged since.
+// As above, the columns are name, semimajor axis, semimedian axis,
+// reciproque flattening (wrt. the semimajor axis), and description.
+// Spheres have zero reciproque flattening
 #[rustfmt::skip]
-pub(super) const RECTIFYING: PolynomialCoefficients = PolynomialCoefficients {
-    // Geodetic to rectifying: Coefficients for converting 𝜙 to 𝜇.
-    // Eq. A5 in [Karney (2022)](crate::bibliography::Kar22)
-    fwd: [
-        [-3.0 / 2., 0., 9.0 / 16., 0., -3.0 / 32., 0.],
-        [0., 15.0 / 16., 0., -15.0 / 32., 0., 135.0 / 2048.],
-        [0., 0., -35.0 / 48., 0., 105.0 / 256., 0.],
-        [0., 0., 0., 315.0 / 512., 0., -189.0 / 512.],
-        [0., 0., 0., 0., -693.0 / 1280., 0.],
-        [0., 0., 0., 0., 0., 1001.0 / 2048.]
-    ],
+pub(super) const BODY_LIST: [(&str, &str, &str, &str, &str); 19] = [
+    ("mercury",   "2440530",       "2440530",      "1075.123348017621",  "Mercury (IAU 2015)"),
+    ("venus",     "6051800",       "6051800",      "0.",                 "Venus (IAU 2015)"),
+    ("mars",      "3396190",       "3396190",      "169.8944472236118",  "Mars (IAU 2000)"),
+    ("moon",      "1737400",       "1737400",      "0.",                 "Moon (IAU 2015)"),
+    ("phobos",    "13000",         "11400",        "3.3333333333333335", "Phobos (IAU 2015)"),
+    ("deimos",    "7800",          "6000",         "2.888888888888889",  "Deimos (IAU 2015)"),
+    ("ceres",     "487300",        "487300",       "11.799031476997579", "Ceres (IAU 2015)"),
+    ("vesta",     "289000",        "280000",       "4.816666666666666",  "Vesta (IAU 2015)"),
+    ("jupiter",   "71492000",      "71492000",     "15.414402759810264", "Jupiter (IAU 2015)"),
+    ("io",        "1829400",       "1819400",      "133.53284671532847", "Io (IAU 2015)"),
+    ("europa",    "1562600",       "1560300",      "504.06451612903226", "Europa (IAU 2015)"),
+    ("ganymede",  "2631200",       "2631200",      "0.",                 "Ganymede (IAU 2015)"),
+    ("callisto",  "2410300",       "2410300",      "0.",                 "Callisto (IAU 2015)"),
+    ("saturn",    "60268000",      "60268000",     "10.2079945799458",   "Saturn (IAU 2015)"),
+    ("enceladus", "256600",        "251400",       "30.91566265060241",  "Enceladus (IAU 2015)"),
+    ("titan",     "2575150",       "2574780",      "3786.985294117647",  "Titan (IAU 2015)"),
+    ("uranus",    "25559000",      "25559000",     "43.6160409556314",   "Uranus (IAU 2015)"),
+    ("neptune",   "24764000",      "24764000",     "58.543735224586285", "Neptune (IAU 2015)"),
+    ("pluto",     "1188300",       "1188300",      "0.",                 "Pluto (IAU 2015)"),
+];
 
-    // Rectifying to geodetic: Coefficients for converting 𝜇 to 𝜙.
-    // Eq. A6 in [Karney (2022)](crate::bibliography::Kar22)
-    inv: [
-        [3.0 / 2., 0., -27.0 / 32., 0., 269.0 / 512., 0.],
-        [0., 21.0 / 16., 0., -55.0 / 32., 0., 6759.0 / 4096.],
-        [0., 0., 151.0 / 96., 0., -417.0 / 128., 0.],
-        [0., 0., 0., 1097.0 / 512., 0., -15543.0 / 2560.],
-        [0., 0., 0., 0., 8011.0 / 2560., 0.],
-        [0., 0., 0., 0., 0., 293393.0 / 61440.]
-    ]
-};
+/// Look up a built-in ellipsoid, terrestrial or celestial
+pub(super) fn builtin(
+    name: &str,
+) -> Option<(
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+)> {
+    ELLIPSOID_LIST
+        .iter()
+        .chain(BODY_LIST.iter())
+        .find(|ellps| ellps.0 == name)
+        .copied()
+}
 
-#[rustfmt::skip]
-pub(super) const CONFORMAL: PolynomialCoefficients = PolynomialCoefficients {
-    // Geodetic to conformal: Coefficients for converting 𝜙 to 𝜒.
-    // Eq. A11 in [Karney (2022)](crate::bibliography::Kar22)
-    fwd: [
-        [-2., 2./3.,  4./3.,  -82./45.,  32./45.,  4642./4725.],
-        [0., 5./3.,  -16./15.,  -13./9.,  904./315.,  -1522./945.],
-        [0., 0., -26./15.,  34./21.,  8./5.,  -12686./2835.],
-        [0., 0., 0., 1237./630.,  -12./5.,  -24832./14175.],
-        [0., 0., 0., 0., -734./315.,  109598./31185.],
-        [0., 0., 0., 0., 0., 444337./155925.]
-    ],
+// The name of the builtin ellipsoid most similar to `name`, if any is
+// reasonably similar, for "did you mean...?" suggestions. Similarity is
+// measured as the case insensitive edit (Levenshtein) distance
+pub(super) fn similar(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    let tolerance = (name.chars().count() / 3).max(2);
+    ELLIPSOID_LIST
+        .iter()
+        .chain(BODY_LIST.iter())
+        .map(|ellps| (edit_distance(&name, &ellps.0.to_lowercase()), ellps.0))
+        .filter(|(distance, _)| *distance <= tolerance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
 
-    // Conformal to geodetic: Coefficients for converting 𝜒 to 𝜙.
-    // Eq. A12 in [Karney (2022)](crate::bibliography::Kar22)
-    inv: [
-        [2.,  -2./3.,  -2.,  116./45.,  26./45.,  -2854./675.],
-        [0., 7./3.,  -8./5.,  -227./45.,  2704./315.,  2323./945.],
-        [0., 0., 56./15.,  -136./35.,  -1262./105.,  73814./2835.],
-        [0., 0., 0., 4279./630.,  -332./35.,  -399572./14175.],
-        [0., 0., 0., 0., 4174./315.,  -144838./6237.],
-        [0., 0., 0., 0., 0., 601676./22275.]
-    ]
-};
+// The number of single character insertions, deletions and substitutions
+// needed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
 
+/// Coefficients for converting between the six auxiliary latitudes of
+/// [Karney (2022)](crate::Bibliography::Kar22): Geographic, 𝜙, parametric, 𝛽,
+/// geocentric, 𝜃, rectifying, 𝜇, conformal, 𝜒, and authalic, 𝜉.
+///
+/// Indexed as `AUXILIARY_LATITUDES[from][to][k][j]`, where *k + 1* is the order
+/// of the Fourier term, *sin(2(k + 1)·ζ)*, and *j + 1* is the power of the third
+/// flattening, *n*. So e.g. `AUXILIARY_LATITUDES[0][4]` is eq. A11 of Karney (2022),
+/// converting 𝜙 to 𝜒.
+///
+/// The conversions to and from 𝜙 are eqs. A1-A6, A11-A12 and A19-A20 of
+/// Karney (2022). The remaining ones are obtained by composing these series,
+/// going via 𝜙, and truncating the result after the *n⁶* terms.
 #[rustfmt::skip]
-pub(super) const AUTHALIC: PolynomialCoefficients = PolynomialCoefficients {
-    // Geodetic to authalic: Coefficients for converting 𝜙 to 𝜉.
-    // Eq. A19 in [Karney (2022)](crate::bibliography::Kar22)
-    fwd: [
-        [-4./3.,  -4./45.,  88./315.,  538./4725.,  20824./467775.,  -44732./2837835.],
-        [0.,  34./45.,  8./105.,  -2482./14175.,  -37192./467775.,  -12467764./212837625.],
-        [0.,  0.,  -1532./2835.,  -898./14175.,  54968./467775.,  100320856./1915538625.],
-        [0.,  0.,  0.,  6007./14175.,  24496./467775.,  -5884124./70945875.],
-        [0.,  0.,  0.,  0.,  -23356./66825.,  -839792./19348875.],
-        [0., 0., 0., 0., 0., 570284222./1915538625.]
+pub(super) const AUXILIARY_LATITUDES: [[[[f64; 6]; 6]; 6]; 6] = [
+    [
+        // 𝜙 → 𝜙
+        [
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+        ],
+        // 𝜙 → 𝛽
+        [
+            [-1., 0., 0., 0., 0., 0.],
+            [0., 1./2., 0., 0., 0., 0.],
+            [0., 0., -1./3., 0., 0., 0.],
+            [0., 0., 0., 1./4., 0., 0.],
+            [0., 0., 0., 0., -1./5., 0.],
+            [0., 0., 0., 0., 0., 1./6.],
+        ],
+        // 𝜙 → 𝜃
+        [
+            [-2., 0., 2., 0., -2., 0.],
+            [0., 2., 0., -4., 0., 6.],
+            [0., 0., -8./3., 0., 8., 0.],
+            [0., 0., 0., 4., 0., -16.],
+            [0., 0., 0., 0., -32./5., 0.],
+            [0., 0., 0., 0., 0., 32./3.],
+        ],
+        // 𝜙 → 𝜇
+        [
+            [-3./2., 0., 9./16., 0., -3./32., 0.],
+            [0., 15./16., 0., -15./32., 0., 135./2048.],
+            [0., 0., -35./48., 0., 105./256., 0.],
+            [0., 0., 0., 315./512., 0., -189./512.],
+            [0., 0., 0., 0., -693./1280., 0.],
+            [0., 0., 0., 0., 0., 1001./2048.],
+        ],
+        // 𝜙 → 𝜒
+        [
+            [-2., 2./3., 4./3., -82./45., 32./45., 4642./4725.],
+            [0., 5./3., -16./15., -13./9., 904./315., -1522./945.],
+            [0., 0., -26./15., 34./21., 8./5., -12686./2835.],
+            [0., 0., 0., 1237./630., -12./5., -24832./14175.],
+            [0., 0., 0., 0., -734./315., 109598./31185.],
+            [0., 0., 0., 0., 0., 444337./155925.],
+        ],
+        // 𝜙 → 𝜉
+        [
+            [-4./3., -4./45., 88./315., 538./4725., 20824./467775., -44732./2837835.],
+            [0., 34./45., 8./105., -2482./14175., -37192./467775., -12467764./212837625.],
+            [0., 0., -1532./2835., -898./14175., 54968./467775., 100320856./1915538625.],
+            [0., 0., 0., 6007./14175., 24496./467775., -5884124./70945875.],
+            [0., 0., 0., 0., -23356./66825., -839792./19348875.],
+            [0., 0., 0., 0., 0., 570284222./1915538625.],
+        ],
     ],
-
-    // Authalic to geodetic: Coefficients for converting 𝜉 to 𝜙.
-    // Eq. A20 in [Karney (2022)](crate::bibliography::Kar22)
-    inv: [
-        [4./3.,  4./45.,  -16./35.,  -2582./14175.,  60136./467775.,  28112932./212837625.],
-        [0.,  46./45.,  152./945.,  -11966./14175.,  -21016./51975.,  251310128./638512875.],
-        [0.,  0.,  3044./2835.,  3802./14175.,  -94388./66825.,  -8797648./10945935.],
-        [0.,  0.,  0.,  6059./4725.,  41072./93555.,  -1472637812./638512875.],
-        [0.,  0.,  0.,  0.,  768272./467775.,  455935736./638512875.],
-        [0., 0., 0., 0., 0., 4210684958./1915538625.]
-    ]
-};
+    [
+        // 𝛽 → 𝜙
+        [
+            [1., 0., 0., 0., 0., 0.],
+            [0., 1./2., 0., 0., 0., 0.],
+            [0., 0., 1./3., 0., 0., 0.],
+            [0., 0., 0., 1./4., 0., 0.],
+            [0., 0., 0., 0., 1./5., 0.],
+            [0., 0., 0., 0., 0., 1./6.],
+        ],
+        // 𝛽 → 𝛽
+        [
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+        ],
+        // 𝛽 → 𝜃
+        [
+            [-1., 0., 0., 0., 0., 0.],
+            [0., 1./2., 0., 0., 0., 0.],
+            [0., 0., -1./3., 0., 0., 0.],
+            [0., 0., 0., 1./4., 0., 0.],
+            [0., 0., 0., 0., -1./5., 0.],
+            [0., 0., 0., 0., 0., 1./6.],
+        ],
+        // 𝛽 → 𝜇
+        [
+            [-1./2., 0., 3./16., 0., -1./32., 0.],
+            [0., -1./16., 0., 1./32., 0., -9./2048.],
+            [0., 0., -1./48., 0., 3./256., 0.],
+            [0., 0., 0., -5./512., 0., 3./512.],
+            [0., 0., 0., 0., -7./1280., 0.],
+            [0., 0., 0., 0., 0., -7./2048.],
+        ],
+        // 𝛽 → 𝜒
+        [
+            [-1., 2./3., 0., -16./45., 2./5., -998./4725.],
+            [0., 1./6., -2./5., 19./45., -22./105., -2./27.],
+            [0., 0., -1./15., 16./105., -22./105., 116./567.],
+            [0., 0., 0., 17./1260., -8./105., 2123./14175.],
+            [0., 0., 0., 0., -1./105., 128./4455.],
+            [0., 0., 0., 0., 0., 149./311850.],
+        ],
+        // 𝛽 → 𝜉
+        [
+            [-1./3., -4./45., 32./315., 34./675., 2476./467775., -70496./8513505.],
+            [0., -7./90., -4./315., 74./2025., 3992./467775., 53836./212837625.],
+            [0., 0., -83./2835., 2./14175., 7052./467775., -661844./1915538625.],
+            [0., 0., 0., -797./56700., 934./467775., 1425778./212837625.],
+            [0., 0., 0., 0., -3673./467775., 390088./212837625.],
+            [0., 0., 0., 0., 0., -18623681./3831077250.],
+        ],
+    ],
+    [
+        // 𝜃 → 𝜙
+        [
+            [2., 0., -2., 0., 2., 0.],
+            [0., 2., 0., -4., 0., 6.],
+            [0., 0., 8./3., 0., -8., 0.],
+            [0., 0., 0., 4., 0., -16.],
+            [0., 0., 0., 0., 32./5., 0.],
+            [0., 0., 0., 0., 0., 32./3.],
+        ],
+        // 𝜃 → 𝛽
+        [
+            [1., 0., 0., 0., 0., 0.],
+            [0., 1./2., 0., 0., 0., 0.],
+            [0., 0., 1./3., 0., 0., 0.],
+            [0., 0., 0., 1./4., 0., 0.],
+            [0., 0., 0., 0., 1./5., 0.],
+            [0., 0., 0., 0., 0., 1./6.],
+        ],
+        // 𝜃 → 𝜃
+        [
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+        ],
+        // 𝜃 → 𝜇
+        [
+            [1./2., 0., 13./16., 0., -15./32., 0.],
+            [0., -1./16., 0., 33./32., 0., -1673./2048.],
+            [0., 0., -5./16., 0., 349./256., 0.],
+            [0., 0., 0., -261./512., 0., 963./512.],
+            [0., 0., 0., 0., -921./1280., 0.],
+            [0., 0., 0., 0., 0., -6037./6144.],
+        ],
+        // 𝜃 → 𝜒
+        [
+            [0., 2./3., 2./3., -2./9., -14./45., 1042./4725.],
+            [0., -1./3., 4./15., 43./45., -4./45., -712./945.],
+            [0., 0., -2./5., 2./105., 124./105., 274./2835.],
+            [0., 0., 0., -55./126., -16./105., 21068./14175.],
+            [0., 0., 0., 0., -22./45., -9202./31185.],
+            [0., 0., 0., 0., 0., -90263./155925.],
+        ],
+        // 𝜃 → 𝜉
+        [
+            [2./3., -4./45., 62./105., 778./4725., -193082./467775., -4286228./42567525.],
+            [0., 4./45., -32./315., 12338./14175., 92696./467775., -61623938./70945875.],
+            [0., 0., -524./2835., -1618./14175., 612536./467775., 427003576./1915538625.],
+            [0., 0., 0., -5933./14175., -8324./66825., 427770788./212837625.],
+            [0., 0., 0., 0., -320044./467775., -9153184./70945875.],
+            [0., 0., 0., 0., 0., -1978771378./1915538625.],
+        ],
+    ],
+    [
+        // 𝜇 → 𝜙
+        [
+            [3./2., 0., -27./32., 0., 269./512., 0.],
+            [0., 21./16., 0., -55./32., 0., 6759./4096.],
+            [0., 0., 151./96., 0., -417./128., 0.],
+            [0., 0., 0., 1097./512., 0., -15543./2560.],
+            [0., 0., 0., 0., 8011./2560., 0.],
+            [0., 0., 0., 0., 0., 293393./61440.],
+        ],
+        // 𝜇 → 𝛽
+        [
+            [1./2., 0., -9./32., 0., 205./1536., 0.],
+            [0., 5./16., 0., -37./96., 0., 1335./4096.],
+            [0., 0., 29./96., 0., -75./128., 0.],
+            [0., 0., 0., 539./1536., 0., -2391./2560.],
+            [0., 0., 0., 0., 3467./7680., 0.],
+            [0., 0., 0., 0., 0., 38081./61440.],
+        ],
+        // 𝜇 → 𝜃
+        [
+            [-1./2., 0., -23./32., 0., 499./1536., 0.],
+            [0., 5./16., 0., -5./96., 0., 6565./12288.],
+            [0., 0., 1./32., 0., -77./128., 0.],
+            [0., 0., 0., 283./1536., 0., -4037./7680.],
+            [0., 0., 0., 0., 1301./7680., 0.],
+            [0., 0., 0., 0., 0., 17089./61440.],
+        ],
+        // 𝜇 → 𝜇
+        [
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+        ],
+        // 𝜇 → 𝜒
+        [
+            [-1./2., 2./3., -37./96., 1./360., 81./512., -96199./604800.],
+            [0., -1./48., -1./15., 437./1440., -46./105., 1118711./3870720.],
+            [0., 0., -17./480., 37./840., 209./4480., -5569./90720.],
+            [0., 0., 0., -4397./161280., 11./504., 830251./7257600.],
+            [0., 0., 0., 0., -4583./161280., 108847./3991680.],
+            [0., 0., 0., 0., 0., -20648693./638668800.],
+        ],
+        // 𝜇 → 𝜉
+        [
+            [1./6., -4./45., -817./10080., 1297./18900., 7764059./239500800., -9292991./302702400.],
+            [0., 49./720., -2./35., -29609./453600., 35474./467775., 36019108271./871782912000.],
+            [0., 0., 4463./90720., -2917./56700., -4306823./59875200., 3026004511./30648618000.],
+            [0., 0., 0., 331799./7257600., -102293./1871100., -368661577./4036032000.],
+            [0., 0., 0., 0., 11744233./239500800., -875457073./13621608000.],
+            [0., 0., 0., 0., 0., 453002260127./7846046208000.],
+        ],
+    ],
+    [
+        // 𝜒 → 𝜙
+        [
+            [2., -2./3., -2., 116./45., 26./45., -2854./675.],
+            [0., 7./3., -8./5., -227./45., 2704./315., 2323./945.],
+            [0., 0., 56./15., -136./35., -1262./105., 73814./2835.],
+            [0., 0., 0., 4279./630., -332./35., -399572./14175.],
+            [0., 0., 0., 0., 4174./315., -144838./6237.],
+            [0., 0., 0., 0., 0., 601676./22275.],
+        ],
+        // 𝜒 → 𝛽
+        [
+            [1., -2./3., -1./3., 38./45., -1./3., -3118./4725.],
+            [0., 5./6., -14./15., -7./9., 50./21., -247./270.],
+            [0., 0., 16./15., -34./21., -5./3., 17564./2835.],
+            [0., 0., 0., 2069./1260., -28./9., -49877./14175.],
+            [0., 0., 0., 0., 883./315., -28244./4455.],
+            [0., 0., 0., 0., 0., 797222./155925.],
+        ],
+        // 𝜒 → 𝜃
+        [
+            [0., -2./3., -2./3., 4./9., 2./9., -3658./4725.],
+            [0., 1./3., -4./15., -23./45., 68./45., 61./135.],
+            [0., 0., 2./5., -24./35., -46./35., 9446./2835.],
+            [0., 0., 0., 83./126., -80./63., -34712./14175.],
+            [0., 0., 0., 0., 52./45., -2362./891.],
+            [0., 0., 0., 0., 0., 335882./155925.],
+        ],
+        // 𝜒 → 𝜇
+        [
+            [1./2., -2./3., 5./16., 41./180., -127./288., 7891./37800.],
+            [0., 13./48., -3./5., 557./1440., 281./630., -1983433./1935360.],
+            [0., 0., 61./240., -103./140., 15061./26880., 167603./181440.],
+            [0., 0., 0., 49561./161280., -179./168., 6601661./7257600.],
+            [0., 0., 0., 0., 34729./80640., -3418889./1995840.],
+            [0., 0., 0., 0., 0., 212378941./319334400.],
+        ],
+        // 𝜒 → 𝜒
+        [
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+        ],
+        // 𝜒 → 𝜉
+        [
+            [2./3., -34./45., 46./315., 2458./4725., -55222./93555., 2706758./42567525.],
+            [0., 19./45., -256./315., 3413./14175., 516944./467775., -340492279./212837625.],
+            [0., 0., 248./567., -15958./14175., 206834./467775., 4430783356./1915538625.],
+            [0., 0., 0., 16049./28350., -832976./467775., 62016436./70945875.],
+            [0., 0., 0., 0., 15602./18711., -651151712./212837625.],
+            [0., 0., 0., 0., 0., 2561772812./1915538625.],
+        ],
+    ],
+    [
+        // 𝜉 → 𝜙
+        [
+            [4./3., 4./45., -16./35., -2582./14175., 60136./467775., 28112932./212837625.],
+            [0., 46./45., 152./945., -11966./14175., -21016./51975., 251310128./638512875.],
+            [0., 0., 3044./2835., 3802./14175., -94388./66825., -8797648./10945935.],
+            [0., 0., 0., 6059./4725., 41072./93555., -1472637812./638512875.],
+            [0., 0., 0., 0., 768272./467775., 455935736./638512875.],
+            [0., 0., 0., 0., 0., 4210684958./1915538625.],
+        ],
+        // 𝜉 → 𝛽
+        [
+            [1./3., 4./45., -46./315., -1082./14175., 11824./467775., 7947332./212837625.],
+            [0., 17./90., 68./945., -338./2025., -16672./155925., 39946703./638512875.],
+            [0., 0., 461./2835., 1102./14175., -101069./467775., -255454./1563705.],
+            [0., 0., 0., 3161./18900., 1786./18711., -189032762./638512875.],
+            [0., 0., 0., 0., 88868./467775., 80274086./638512875.],
+            [0., 0., 0., 0., 0., 880980241./3831077250.],
+        ],
+        // 𝜉 → 𝜃
+        [
+            [-2./3., 4./45., -158./315., -2102./14175., 109042./467775., 216932./2627625.],
+            [0., 16./45., -16./945., 934./14175., -7256./155925., 117952358./638512875.],
+            [0., 0., -232./2835., 922./14175., -25286./66825., -7391576./54729675.],
+            [0., 0., 0., 719./4725., 268./18711., -67048172./638512875.],
+            [0., 0., 0., 0., 14354./467775., 46774256./638512875.],
+            [0., 0., 0., 0., 0., 253129538./1915538625.],
+        ],
+        // 𝜉 → 𝜇
+        [
+            [-1./6., 4./45., 121./1680., -1609./28350., -384229./14968800., 12674323./851350500.],
+            [0., -29./720., 26./945., 16463./453600., -431./17325., -31621753811./1307674368000.],
+            [0., 0., -1003./45360., 449./28350., 3746047./119750400., -32844781./1751349600.],
+            [0., 0., 0., -40457./2419200., 629./53460., 10650637121./326918592000.],
+            [0., 0., 0., 0., -1800439./119750400., 205072597./20432412000.],
+            [0., 0., 0., 0., 0., -59109051671./3923023104000.],
+        ],
+        // 𝜉 → 𝜒
+        [
+            [-2./3., 34./45., -88./315., -2312./14175., 27128./93555., -55271278./212837625.],
+            [0., 1./45., -184./945., 6079./14175., -65864./155925., 106691108./638512875.],
+            [0., 0., -106./2835., 772./14175., -14246./467775., 5921152./54729675.],
+            [0., 0., 0., -167./9450., -5312./467775., 75594328./638512875.],
+            [0., 0., 0., 0., -248./13365., 2837636./638512875.],
+            [0., 0., 0., 0., 0., -34761247./1915538625.],
+        ],
+        // 𝜉 → 𝜉
+        [
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., 0.],
+        ],
+    ],
+];
 
 /// Coefficients for expansion of the normalized meridian arc unit in terms
 /// of *n²*, the square of the third flattening.
 /// See [Karney 2010](crate::Bibliography::Kar10) eq. (29)
 pub(super) const MERIDIAN_ARC_COEFFICIENTS: [f64; 5] =
     [1., 1. / 4., 1. / 64., 1. / 256., 25. / 16384.];
+
+/// Coefficients for the series expansion of the rectifying latitude, *𝜇*,
+/// in terms of the geographic latitude, *𝜙*: *𝜇 = 𝜙 + Σ αₖ(n) sin(2k𝜙)*.
+///
+/// Indexed as `MERIDIAN_ARC_FWD[k][j]`, where *k + 1* is the order of the
+/// Fourier term, and *j + 1* is the power of the third flattening, *n*.
+/// Derived from Helmert's expansion of the meridian arc, extended to *n⁸*,
+/// as in [Deakin et al (2012)](crate::Bibliography::Dea12)
+#[rustfmt::skip]
+pub(super) const MERIDIAN_ARC_FWD: [[f64; 8]; 8] = [
+    [-3. / 2., 0., 9. / 16., 0., -3. / 32., 0., 57. / 2048., 0.],
+    [0., 15. / 16., 0., -15. / 32., 0., 135. / 2048., 0., -105. / 4096.],
+    [0., 0., -35. / 48., 0., 105. / 256., 0., -105. / 2048., 0.],
+    [0., 0., 0., 315. / 512., 0., -189. / 512., 0., 693. / 16384.],
+    [0., 0., 0., 0., -693. / 1280., 0., 693. / 2048., 0.],
+    [0., 0., 0., 0., 0., 1001. / 2048., 0., -1287. / 4096.],
+    [0., 0., 0., 0., 0., 0., -6435. / 14336., 0.],
+    [0., 0., 0., 0., 0., 0., 0., 109395. / 262144.],
+];
+
+/// Coefficients for the series expansion of the geographic latitude, *𝜙*,
+/// in terms of the rectifying latitude, *𝜇*: *𝜙 = 𝜇 + Σ βₖ(n) sin(2k𝜇)*,
+/// i.e. the reversion of [`MERIDIAN_ARC_FWD`], indexed the same way
+#[rustfmt::skip]
+pub(super) const MERIDIAN_ARC_INV: [[f64; 8]; 8] = [
+    [3. / 2., 0., -27. / 32., 0., 269. / 512., 0., -6607. / 24576., 0.],
+    [0., 21. / 16., 0., -55. / 32., 0., 6759. / 4096., 0., -155113. / 122880.],
+    [0., 0., 151. / 96., 0., -417. / 128., 0., 87963. / 20480., 0.],
+    [0., 0., 0., 1097. / 512., 0., -15543. / 2560., 0., 2514467. / 245760.],
+    [0., 0., 0., 0., 8011. / 2560., 0., -69119. / 6144., 0.],
+    [0., 0., 0., 0., 0., 293393. / 61440., 0., -5962461. / 286720.],
+    [0., 0., 0., 0., 0., 0., 6459601. / 860160., 0.],
+    [0., 0., 0., 0., 0., 0., 0., 332287993. / 27525120.],
+];
@@ -8,11 +8,16 @@ pub trait GeoCart: EllipsoidBase {
     ///
     /// Follows the the derivation given by
     /// Bowring ([1976](crate::Bibliography::Bow76) and
-    /// [1985](crate::Bibliography::Bow85))
+    /// [1985](crate::Bibliography::Bow85)).
+    /// Triaxial ellipsoids are handled by
+    /// [the triaxial algorithm](crate::ellps::TriaxialEllipsoid).
     #[must_use]
     #[allow(non_snake_case)] // make it possible to mimic math notation from original paper
     #[allow(clippy::many_single_char_names)] // ditto
     fn cartesian<C: CoordinateTuple>(&self, geographic: &C) -> Coor4D {
+        if self.is_triaxial() {
+            return super::triaxial::cartesian(self, geographic);
+        }
         let (lam, phi, h, t) = geographic.xyzt();
 
         let N = self.prime_vertical_radius_of_curvature(phi);
@@ -30,11 +35,16 @@ pub trait GeoCart: EllipsoidBase {
     ///
     /// Follows the the derivation given by
     /// Bowring ([1976](crate::Bibliography::Bow76) and
-    /// [1985](crate::Bibliography::Bow85))
+    /// [1985](crate::Bibliography::Bow85)).
+    /// Triaxial ellipsoids are handled by
+    /// [the triaxial algorithm](crate::ellps::TriaxialEllipsoid).
     #[must_use]
     #[allow(non_snake_case)] // make it possible to mimic math notation from original paper
     #[allow(clippy::many_single_char_names)] // ditto
     fn geographic<C: CoordinateTuple>(&self, cartesian: &C) -> Coor4D {
+        if self.is_triaxial() {
+            return super::triaxial::geographic(self, cartesian);
+        }
         let (X, Y, Z, t) = cartesian.xyzt();
 
         // We need a few additional ellipsoidal parameters
@@ -4,19 +4,83 @@ use super::*;
 
 /// Geodesics
 pub trait Geodesics: EllipsoidBase {
-    /// For general geodesics, we use the algorithm by Vincenty
+    /// The direct geodesic problem: Starting at `from`, travel `distance`
+    /// meters along the geodesic with initial azimuth `azimuth` (radians).
+    /// Returns the longitude and latitude of the destination, the forward
+    /// azimuth of the geodesic at the destination, and the number of
+    /// iterations needed (always 0: The direct problem is solved without
+    /// iteration).
+    ///
+    /// We use the series based algorithm by Karney
+    /// ([2012](crate::Bibliography::Kar12), [2013](crate::Bibliography::Kar13)),
+    /// which is accurate to a few nanometers for terrestrial ellipsoids, and
+    /// converges everywhere - including the near-antipodal cases where the
+    /// classical Vincenty algorithm fails.
+    ///
+    /// The Vincenty algorithm is still available for comparison, as
+    /// [`geodesic_fwd_vincenty`](Self::geodesic_fwd_vincenty).
+    ///
+    /// Triaxial ellipsoids are handled by numerical integration, as described
+    /// for [`TriaxialEllipsoid`](crate::ellps::TriaxialEllipsoid).
+    #[must_use]
+    fn geodesic_fwd<C: CoordinateTuple>(&self, from: &C, azimuth: f64, distance: f64) -> Coor4D {
+        if self.is_triaxial() {
+            return super::triaxial::geodesic_fwd(self, from, azimuth, distance);
+        }
+        let (lon1, lat1) = from.xy();
+        let g = Karney::new(self.semimajor_axis(), self.flattening());
+        let (lon2, lat2, azi2) = g.direct(
+            lat1.to_degrees(),
+            lon1.to_degrees(),
+            azimuth.to_degrees(),
+            distance,
+        );
+        Coor4D::raw(lon2.to_radians(), lat2.to_radians(), azi2.to_radians(), 0.)
+    }
+
+    /// The inverse geodesic problem: Find the shortest path between `from`
+    /// and `to`. Returns the forward azimuth at `from`, the forward azimuth
+    /// at `to` (both in radians), the distance in meters, and the number of
+    /// Newton iterations needed.
+    ///
+    /// See [`geodesic_fwd`](Self::geodesic_fwd) for the algorithm, and
+    /// [`geodesic_inv_vincenty`](Self::geodesic_inv_vincenty) for the Vincenty
+    /// alternative.
+    #[must_use]
+    fn geodesic_inv<C: CoordinateTuple>(&self, from: &C, to: &C) -> Coor4D {
+        if self.is_triaxial() {
+            return super::triaxial::geodesic_inv(self, from, to);
+        }
+        let (lon1, lat1) = from.xy();
+        let (lon2, lat2) = to.xy();
+        let g = Karney::new(self.semimajor_axis(), self.flattening());
+        let (azi1, azi2, s12, iterations) = g.inverse(
+            lat1.to_degrees(),
+            lon1.to_degrees(),
+            lat2.to_degrees(),
+            lon2.to_degrees(),
+        );
+        Coor4D::raw(azi1.to_radians(), azi2.to_radians(), s12, iterations as f64)
+    }
+
+    /// The direct geodesic problem, solved using the algorithm by Vincenty
     /// ([1975](crate::Bibliography::Vin75)), with updates by the same author
     /// ([1976](crate::Bibliography::Vin76)).
     /// The Vincenty algorithm is relatively simple to implement, but for near-antipodal
     /// cases, it suffers from lack of convergence and loss of accuracy.
+    /// The 4th element of the return value is the number of iterations needed,
+    /// and exceeds 990 in case of non-convergence.
     ///
-    /// Karney ([2012](crate::Bibliography::Kar12), [2013](crate::Bibliography::Kar13))
-    /// presented an algorithm which is exact to machine precision, and converges everywhere.
-    /// The crate [geographiclib-rs](https://crates.io/crates/geographiclib-rs), by
-    /// Federico Dolce and Michael Kirk, provides a Rust implementation of Karney's algorithm.
+    /// Mostly useful for comparison: Use [`geodesic_fwd`](Self::geodesic_fwd)
+    /// for general work.
     #[must_use]
     #[allow(non_snake_case)]
-    fn geodesic_fwd<C: CoordinateTuple>(&self, from: &C, azimuth: f64, distance: f64) -> Coor4D {
+    fn geodesic_fwd_vincenty<C: CoordinateTuple>(
+        &self,
+        from: &C,
+        azimuth: f64,
+        distance: f64,
+    ) -> Coor4D {
         // Coordinates of the point of origin, P1
         let (L1, B1) = from.xy();
 
@@ -92,10 +156,11 @@ pub trait Geodesics: EllipsoidBase {
         Coor4D::raw(L2, B2, aa2, f64::from(i))
     }
 
-    /// See [`geodesic_fwd`](Self::geodesic_fwd)
+    /// The inverse geodesic problem, solved using the algorithm by Vincenty.
+    /// See [`geodesic_fwd_vincenty`](Self::geodesic_fwd_vincenty).
     #[must_use]
     #[allow(non_snake_case)] // So we can use the mathematical notation from the original text
-    fn geodesic_inv<C: CoordinateTuple>(&self, from: &C, to: &C) -> Coor4D {
+    fn geodesic_inv_vincenty<C: CoordinateTuple>(&self, from: &C, to: &C) -> Coor4D {
         let (L1, B1) = from.xy();
         let (L2, B2) = to.xy();
         let B = B2 - B1;
@@ -203,6 +268,988 @@ pub trait Geodesics: EllipsoidBase {
     }
 }
 
+// ----- Karney's algorithm ------------------------------------------------------------
+
+// The implementation below follows the C implementation in GeographicLib
+// (geodesic.c, MIT licensed, by Charles F.F. Karney), using series of order 6.
+// Angles are handled in degrees internally, since that allows exact argument
+// reduction for the trigonometric functions.
+
+const ORDER: usize = 6;
+const NC3X: usize = 15;
+const MAXIT1: usize = 20;
+const MAXIT2: usize = MAXIT1 + f64::MANTISSA_DIGITS as usize + 10;
+
+const TOL0: f64 = f64::EPSILON;
+const TOL1: f64 = 200. * TOL0;
+const TOLB: f64 = TOL0;
+
+// sqrt(f64::MIN_POSITIVE)
+const TINY: f64 = 1.4916681462400413e-154;
+
+#[allow(non_snake_case)]
+struct Karney {
+    a: f64,
+    f: f64,
+    f1: f64,
+    ep2: f64,
+    n: f64,
+    b: f64,
+    etol2: f64,
+    A3x: [f64; ORDER],
+    C3x: [f64; NC3X],
+}
+
+#[allow(non_snake_case)]
+impl Karney {
+    fn new(a: f64, f: f64) -> Self {
+        let f1 = 1. - f;
+        let e2 = f * (2. - f);
+        let ep2 = e2 / (f1 * f1);
+        let n = f / (2. - f);
+        let b = a * f1;
+        let tol2 = TOL0.sqrt();
+        let etol2 = 0.1 * tol2 / ((f.abs().max(0.001) * (1. - f / 2.).min(1.)) / 2.).sqrt();
+
+        // The coefficients of A3, as polynomials in n
+        #[rustfmt::skip]
+        const A3COEFF: [f64; 18] = [
+            -3., 128.,
+            -2., -3., 64.,
+            -1., -3., -1., 16.,
+            3., -1., -2., 8.,
+            1., -1., 2.,
+            1., 1.,
+        ];
+        let mut A3x = [0.; ORDER];
+        let mut o = 0;
+        for (k, j) in (0..ORDER).rev().enumerate() {
+            let m = (ORDER - j - 1).min(j);
+            A3x[k] = polyval(m, &A3COEFF[o..], n) / A3COEFF[o + m + 1];
+            o += m + 2;
+        }
+
+        // The coefficients of C3, as polynomials in n
+        #[rustfmt::skip]
+        const C3COEFF: [f64; 45] = [
+            3., 128.,
+            2., 5., 128.,
+            -1., 3., 3., 64.,
+            -1., 0., 1., 8.,
+            -1., 1., 4.,
+            5., 256.,
+            1., 3., 128.,
+            -3., -2., 3., 64.,
+            1., -3., 2., 32.,
+            7., 512.,
+            -10., 9., 384.,
+            5., -9., 5., 192.,
+            7., 512.,
+            -14., 7., 512.,
+            21., 2560.,
+        ];
+        let mut C3x = [0.; NC3X];
+        let mut o = 0;
+        let mut k = 0;
+        for l in 1..ORDER {
+            for j in (l..ORDER).rev() {
+                let m = (ORDER - j - 1).min(j);
+                C3x[k] = polyval(m, &C3COEFF[o..], n) / C3COEFF[o + m + 1];
+                k += 1;
+                o += m + 2;
+            }
+        }
+
+        Karney {
+            a,
+            f,
+            f1,
+            ep2,
+            n,
+            b,
+            etol2,
+            A3x,
+            C3x,
+        }
+    }
+
+    fn A3f(&self, eps: f64) -> f64 {
+        polyval(ORDER - 1, &self.A3x, eps)
+    }
+
+    fn C3f(&self, eps: f64, c: &mut [f64; ORDER + 1]) {
+        let mut mult = 1.;
+        let mut o = 0;
+        // Elements c[1] through c[ORDER - 1] are set
+        for (l, cl) in c.iter_mut().enumerate().take(ORDER).skip(1) {
+            let m = ORDER - l - 1;
+            mult *= eps;
+            *cl = mult * polyval(m, &self.C3x[o..], eps);
+            o += m + 1;
+        }
+    }
+
+    // Distance, s12b, and reduced length, m12b, (both in units of the
+    // semiminor axis), the coefficient of the secular term of the reduced
+    // length, m0, and the geodesic scales, M12 and M21.
+    #[allow(clippy::too_many_arguments)]
+    fn lengths(
+        &self,
+        eps: f64,
+        sig12: f64,
+        (ssig1, csig1, dn1): (f64, f64, f64),
+        (ssig2, csig2, dn2): (f64, f64, f64),
+        cbet1: f64,
+        cbet2: f64,
+    ) -> (f64, f64, f64, f64, f64) {
+        let mut Ca = [0.; ORDER + 1];
+        let mut Cb = [0.; ORDER + 1];
+        let A1 = A1m1f(eps);
+        C1f(eps, &mut Ca);
+        let A2 = A2m1f(eps);
+        C2f(eps, &mut Cb);
+        let m0 = A1 - A2;
+        let A1 = 1. + A1;
+        let A2 = 1. + A2;
+
+        let B1 = sin_series(ssig2, csig2, &Ca) - sin_series(ssig1, csig1, &Ca);
+        let s12b = A1 * (sig12 + B1);
+        let B2 = sin_series(ssig2, csig2, &Cb) - sin_series(ssig1, csig1, &Cb);
+        let J12 = m0 * sig12 + (A1 * B1 - A2 * B2);
+
+        // Parentheses ensure accurate cancellation for coincident points
+        let m12b = dn2 * (csig1 * ssig2) - dn1 * (ssig1 * csig2) - csig1 * csig2 * J12;
+        let csig12 = csig1 * csig2 + ssig1 * ssig2;
+        let t = self.ep2 * (cbet1 - cbet2) * (cbet1 + cbet2) / (dn1 + dn2);
+        let M12 = csig12 + (t * ssig2 - csig2 * J12) * ssig1 / dn1;
+        let M21 = csig12 - (t * ssig1 - csig1 * J12) * ssig2 / dn2;
+        (s12b, m12b, m0, M12, M21)
+    }
+
+    /// Solve the direct problem. Angles in degrees. Returns (lon2, lat2, azi2)
+    fn direct(&self, lat1: f64, lon1: f64, azi1: f64, s12: f64) -> (f64, f64, f64) {
+        let lat1 = lat_fix(lat1);
+        let azi1 = ang_normalize(azi1);
+        let (salp1, calp1) = sincosd(ang_round(azi1));
+
+        let (mut sbet1, mut cbet1) = sincosd(ang_round(lat1));
+        sbet1 *= self.f1;
+        norm2(&mut sbet1, &mut cbet1);
+        cbet1 = cbet1.max(TINY);
+
+        // Evaluate alp0 from sin(alp1) * cos(bet1) = sin(alp0)
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        // sig1 and omg1: The arc lengths on the auxiliary sphere from the
+        // equator crossing to P1, measured along the geodesic, and the equator
+        let mut ssig1 = sbet1;
+        let somg1 = salp0 * sbet1;
+        let mut csig1 = if sbet1 != 0. || calp1 != 0. {
+            cbet1 * calp1
+        } else {
+            1.
+        };
+        let comg1 = csig1;
+        norm2(&mut ssig1, &mut csig1);
+
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2. * (1. + (1. + k2).sqrt()) + k2);
+
+        let mut C1a = [0.; ORDER + 1];
+        let mut C1pa = [0.; ORDER + 1];
+        let mut C3a = [0.; ORDER + 1];
+        let A1m1 = A1m1f(eps);
+        C1f(eps, &mut C1a);
+        C1pf(eps, &mut C1pa);
+        self.C3f(eps, &mut C3a);
+        let B11 = sin_series(ssig1, csig1, &C1a);
+        let (s, c) = B11.sin_cos();
+        let stau1 = ssig1 * c + csig1 * s;
+        let ctau1 = csig1 * c - ssig1 * s;
+        let A3c = -self.f * salp0 * self.A3f(eps);
+        let B31 = sin_series(ssig1, csig1, &C3a);
+
+        // Distance to arc length on the auxiliary sphere
+        let tau12 = s12 / (self.b * (1. + A1m1));
+        let (s, c) = tau12.sin_cos();
+        let B12 = -sin_series(stau1 * c + ctau1 * s, ctau1 * c - stau1 * s, &C1pa);
+        let mut sig12 = tau12 - (B12 - B11);
+        let (mut ssig12, mut csig12) = sig12.sin_cos();
+
+        // The reverted distance series is inaccurate for |f| > 1/100,
+        // so we correct sig12 with one Newton iteration
+        if self.f.abs() > 0.01 {
+            let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+            let csig2 = csig1 * csig12 - ssig1 * ssig12;
+            let B12 = sin_series(ssig2, csig2, &C1a);
+            let serr = (1. + A1m1) * (sig12 + (B12 - B11)) - s12 / self.b;
+            sig12 -= serr / (1. + k2 * ssig2 * ssig2).sqrt();
+            (ssig12, csig12) = sig12.sin_cos();
+        }
+
+        // sig2 = sig1 + sig12
+        let ssig2 = ssig1 * csig12 + csig1 * ssig12;
+        let mut csig2 = csig1 * csig12 - ssig1 * ssig12;
+
+        // sin(bet2) = cos(alp0) * sin(sig2)
+        let sbet2 = calp0 * ssig2;
+        let mut cbet2 = salp0.hypot(calp0 * csig2);
+        if cbet2 == 0. {
+            // I.e. salp0 = 0, csig2 = 0. Break the degeneracy
+            cbet2 = TINY;
+            csig2 = TINY;
+        }
+
+        // tan(alp0) = cos(sig2) * tan(alp2)
+        let salp2 = salp0;
+        let calp2 = calp0 * csig2;
+
+        // tan(omg2) = sin(alp0) * tan(sig2)
+        let somg2 = salp0 * ssig2;
+        let comg2 = csig2;
+        let omg12 = (somg2 * comg1 - comg2 * somg1).atan2(comg2 * comg1 + somg2 * somg1);
+        let lam12 = omg12 + A3c * (sig12 + (sin_series(ssig2, csig2, &C3a) - B31));
+        let lon12 = lam12.to_degrees();
+        let lon2 = ang_normalize(ang_normalize(lon1) + ang_normalize(lon12));
+        let lat2 = atan2d(sbet2, self.f1 * cbet2);
+        let azi2 = atan2d(salp2, calp2);
+        (lon2, lat2, azi2)
+    }
+
+    /// Solve the inverse problem. Angles in degrees.
+    /// Returns (azi1, azi2, s12, number of iterations)
+    fn inverse(&self, lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> (f64, f64, f64, usize) {
+        // Longitude difference, and its rounding error
+        let (mut lon12, mut lon12s) = ang_diff(lon1, lon2);
+
+        // Make longitude difference positive
+        let mut lonsign: f64 = if lon12.is_sign_negative() { -1. } else { 1. };
+        lon12 *= lonsign;
+        lon12s *= lonsign;
+        let lam12 = lon12.to_radians();
+        let (slam12, clam12) = sincosde(lon12, lon12s);
+        // The supplementary longitude difference
+        lon12s = (180. - lon12) - lon12s;
+
+        // If really close to the equator, treat as on equator
+        let mut lat1 = ang_round(lat_fix(lat1));
+        let mut lat2 = ang_round(lat_fix(lat2));
+
+        // Swap points so that point with higher (abs) latitude is point 1.
+        // If one latitude is a NaN, it becomes lat1
+        let swapp: f64 = if lat1.abs() < lat2.abs() || lat2.is_nan() {
+            -1.
+        } else {
+            1.
+        };
+        if swapp < 0. {
+            lonsign *= -1.;
+            std::mem::swap(&mut lat1, &mut lat2);
+        }
+
+        // Make lat1 <= -0
+        let latsign: f64 = if lat1.is_sign_negative() { 1. } else { -1. };
+        lat1 *= latsign;
+        lat2 *= latsign;
+
+        // Now we have 0 <= lon12 <= 180, -90 <= lat1 <= -0, lat1 <= lat2 <= -lat1.
+        // lonsign, swapp, latsign register the transformation bringing the
+        // coordinates to this canonical form.
+        let (mut sbet1, mut cbet1) = sincosd(lat1);
+        sbet1 *= self.f1;
+        norm2(&mut sbet1, &mut cbet1);
+        cbet1 = cbet1.max(TINY);
+
+        let (mut sbet2, mut cbet2) = sincosd(lat2);
+        sbet2 *= self.f1;
+        norm2(&mut sbet2, &mut cbet2);
+        cbet2 = cbet2.max(TINY);
+
+        // Force bet2 = +/- bet1 exactly, when the measure of their difference vanishes
+        if cbet1 < -sbet1 {
+            if cbet2 == cbet1 {
+                sbet2 = sbet1.copysign(sbet2);
+            }
+        } else if sbet2.abs() == -sbet1 {
+            cbet2 = cbet1;
+        }
+
+        let dn1 = (1. + self.ep2 * sbet1 * sbet1).sqrt();
+        let dn2 = (1. + self.ep2 * sbet2 * sbet2).sqrt();
+
+        let mut s12x = 0.;
+        let mut salp1 = 0.;
+        let mut calp1 = 0.;
+        let mut salp2 = 0.;
+        let mut calp2 = 0.;
+        let mut numit = 0;
+
+        let mut meridian = lat1 == -90. || slam12 == 0.;
+        if meridian {
+            // Endpoints are on a single full meridian, so the geodesic might lie on it
+            calp1 = clam12;
+            salp1 = slam12;
+            calp2 = 1.;
+            salp2 = 0.;
+
+            // tan(bet) = tan(sig) * cos(alp)
+            let ssig1 = sbet1;
+            let csig1 = calp1 * cbet1;
+            let ssig2 = sbet2;
+            let csig2 = calp2 * cbet2;
+
+            // sig12 = sig2 - sig1
+            let sig12 = (0_f64.max(csig1 * ssig2 - ssig1 * csig2) + 0.)
+                .atan2(csig1 * csig2 + ssig1 * ssig2);
+            let (s12b, m12b, ..) = self.lengths(
+                self.n,
+                sig12,
+                (ssig1, csig1, dn1),
+                (ssig2, csig2, dn2),
+                cbet1,
+                cbet2,
+            );
+
+            // A meridional geodesic with sig12 > pi/2 is not a shortest path
+            if sig12 < 1. || m12b >= 0. {
+                // Prevent negative s12 for short lines
+                if sig12 < 3. * TINY || (sig12 < TOL0 && (s12b < 0. || m12b < 0.)) {
+                    s12x = 0.;
+                } else {
+                    s12x = s12b * self.b;
+                }
+            } else {
+                // m12 < 0, i.e. prolate and too close to anti-podal
+                meridian = false;
+            }
+        }
+
+        if !meridian && sbet1 == 0. && (self.f <= 0. || lon12s >= self.f * 180.) {
+            // Geodesic runs along the equator
+            calp1 = 0.;
+            calp2 = 0.;
+            salp1 = 1.;
+            salp2 = 1.;
+            s12x = self.a * lam12;
+        } else if !meridian {
+            // Now point 1 and point 2 belong within a hemisphere bounded by a
+            // meridian, and the geodesic is neither meridional nor equatorial.
+            // Find a starting point for Newton's method.
+            let (sig12, dnm);
+            (sig12, salp1, calp1, salp2, calp2, dnm) = self.inverse_start(
+                (sbet1, cbet1, dn1),
+                (sbet2, cbet2, dn2),
+                lam12,
+                slam12,
+                clam12,
+            );
+
+            if sig12 >= 0. {
+                // Short lines: inverse_start has set salp2, calp2, dnm
+                s12x = sig12 * self.b * dnm;
+            } else {
+                // Newton's method for solving lambda12(alp1) - lam12 = 0,
+                // maintaining a bracket (alp1a, alp1b) around the root, and
+                // resorting to bisection whenever Newton misbehaves
+                let mut salp1a = TINY;
+                let mut calp1a = 1.;
+                let mut salp1b = TINY;
+                let mut calp1b = -1.;
+                let mut tripn = false;
+                let mut tripb = false;
+                let mut l;
+                loop {
+                    l = self.lambda12(
+                        (sbet1, cbet1, dn1),
+                        (sbet2, cbet2, dn2),
+                        salp1,
+                        calp1,
+                        slam12,
+                        clam12,
+                        numit < MAXIT1,
+                    );
+                    let v = l.lam12;
+                    let dv = l.dlam12;
+                    salp2 = l.salp2;
+                    calp2 = l.calp2;
+
+                    // Let NaNs escape as well
+                    let tol = if tripn { 8. } else { 1. } * TOL0;
+                    let converged = v.abs() < tol || v.is_nan();
+                    if tripb || converged || numit == MAXIT2 {
+                        break;
+                    }
+
+                    // Update bracketing values
+                    if v > 0. && (numit > MAXIT1 || calp1 / salp1 > calp1b / salp1b) {
+                        salp1b = salp1;
+                        calp1b = calp1;
+                    } else if v < 0. && (numit > MAXIT1 || calp1 / salp1 < calp1a / salp1a) {
+                        salp1a = salp1;
+                        calp1a = calp1;
+                    }
+                    numit += 1;
+
+                    if numit <= MAXIT1 && dv > 0. {
+                        let dalp1 = -v / dv;
+                        if dalp1.abs() < std::f64::consts::PI {
+                            let (sdalp1, cdalp1) = dalp1.sin_cos();
+                            let nsalp1 = salp1 * cdalp1 + calp1 * sdalp1;
+                            if nsalp1 > 0. {
+                                calp1 = calp1 * cdalp1 - salp1 * sdalp1;
+                                salp1 = nsalp1;
+                                norm2(&mut salp1, &mut calp1);
+                                // In some regimes we don't get quadratic convergence
+                                // because slope -> 0. So use convergence conditions
+                                // based on epsilon instead of sqrt(epsilon).
+                                tripn = v.abs() <= 16. * TOL0;
+                                continue;
+                            }
+                        }
+                    }
+
+                    // Either dv was not positive, or the updated value was outside
+                    // the legal range: Use the midpoint of the bracket instead
+                    salp1 = (salp1a + salp1b) / 2.;
+                    calp1 = (calp1a + calp1b) / 2.;
+                    norm2(&mut salp1, &mut calp1);
+                    tripn = false;
+                    tripb = (salp1a - salp1).abs() + (calp1a - calp1) < TOLB
+                        || (salp1 - salp1b).abs() + (calp1 - calp1b) < TOLB;
+                }
+                let (s12b, ..) = self.lengths(
+                    l.eps,
+                    l.sig12,
+                    (l.ssig1, l.csig1, dn1),
+                    (l.ssig2, l.csig2, dn2),
+                    cbet1,
+                    cbet2,
+                );
+                s12x = s12b * self.b;
+            }
+        }
+
+        // Convert -0 to 0
+        let s12 = 0. + s12x;
+
+        // Convert calp, salp to azimuth accounting for lonsign, swapp, latsign
+        if swapp < 0. {
+            std::mem::swap(&mut salp1, &mut salp2);
+            std::mem::swap(&mut calp1, &mut calp2);
+        }
+        salp1 *= swapp * lonsign;
+        calp1 *= swapp * latsign;
+        salp2 *= swapp * lonsign;
+        calp2 *= swapp * latsign;
+
+        (atan2d(salp1, calp1), atan2d(salp2, calp2), s12, numit)
+    }
+
+    // Starting point for Newton's method: Returns (sig12, salp1, calp1,
+    // salp2, calp2, dnm). If Newton's method is not needed, sig12 >= 0,
+    // and salp2, calp2, and dnm are valid.
+    fn inverse_start(
+        &self,
+        (sbet1, cbet1, dn1): (f64, f64, f64),
+        (sbet2, cbet2, dn2): (f64, f64, f64),
+        lam12: f64,
+        slam12: f64,
+        clam12: f64,
+    ) -> (f64, f64, f64, f64, f64, f64) {
+        use std::f64::consts::PI;
+        let mut sig12 = -1.;
+        let mut salp2 = 0.;
+        let mut calp2 = 0.;
+        let mut dnm = 0.;
+
+        // bet12 = bet2 - bet1 in [0, pi); bet12a = bet2 + bet1 in (-pi, 0]
+        let sbet12 = sbet2 * cbet1 - cbet2 * sbet1;
+        let cbet12 = cbet2 * cbet1 + sbet2 * sbet1;
+        let sbet12a = sbet2 * cbet1 + cbet2 * sbet1;
+        let shortline = cbet12 >= 0. && sbet12 < 0.5 && cbet2 * lam12 < 0.5;
+
+        let (mut somg12, mut comg12);
+        if shortline {
+            let mut sbetm2 = (sbet1 + sbet2) * (sbet1 + sbet2);
+            sbetm2 /= sbetm2 + (cbet1 + cbet2) * (cbet1 + cbet2);
+            dnm = (1. + self.ep2 * sbetm2).sqrt();
+            let omg12 = lam12 / (self.f1 * dnm);
+            (somg12, comg12) = omg12.sin_cos();
+        } else {
+            somg12 = slam12;
+            comg12 = clam12;
+        }
+
+        let mut salp1 = cbet2 * somg12;
+        let mut calp1 = if comg12 >= 0. {
+            sbet12 + cbet2 * sbet1 * somg12 * somg12 / (1. + comg12)
+        } else {
+            sbet12a - cbet2 * sbet1 * somg12 * somg12 / (1. - comg12)
+        };
+
+        let ssig12 = salp1.hypot(calp1);
+        let csig12 = sbet1 * sbet2 + cbet1 * cbet2 * comg12;
+
+        if shortline && ssig12 < self.etol2 {
+            // Really short lines
+            salp2 = cbet1 * somg12;
+            calp2 = sbet12
+                - cbet1
+                    * sbet2
+                    * if comg12 >= 0. {
+                        somg12 * somg12 / (1. + comg12)
+                    } else {
+                        1. - comg12
+                    };
+            norm2(&mut salp2, &mut calp2);
+            sig12 = ssig12.atan2(csig12);
+        } else if self.n.abs() > 0.1
+            || csig12 >= 0.
+            || ssig12 >= 6. * self.n.abs() * PI * cbet1 * cbet1
+        {
+            // Nothing to do: The zeroth order spherical approximation is OK
+        } else {
+            // Scale lam12 and bet2 to an x, y coordinate system where the
+            // antipodal point is at the origin, and the singular point is at
+            // y = 0, x = -1
+            let lam12x = (-slam12).atan2(-clam12); // lam12 - pi
+            let (x, y, lamscale);
+            if self.f >= 0. {
+                // x = dlong, y = dlat
+                let k2 = sbet1 * sbet1 * self.ep2;
+                let eps = k2 / (2. * (1. + (1. + k2).sqrt()) + k2);
+                lamscale = self.f * cbet1 * self.A3f(eps) * PI;
+                let betscale = lamscale * cbet1;
+                x = lam12x / lamscale;
+                y = sbet12a / betscale;
+            } else {
+                // x = dlat, y = dlong
+                let cbet12a = cbet2 * cbet1 - sbet2 * sbet1;
+                let bet12a = sbet12a.atan2(cbet12a);
+                let (_, m12b, m0, ..) = self.lengths(
+                    self.n,
+                    PI + bet12a,
+                    (sbet1, -cbet1, dn1),
+                    (sbet2, cbet2, dn2),
+                    cbet1,
+                    cbet2,
+                );
+                x = -1. + m12b / (cbet1 * cbet2 * m0 * PI);
+                let betscale = if x < -0.01 {
+                    sbet12a / x
+                } else {
+                    -self.f * cbet1 * cbet1 * PI
+                };
+                lamscale = betscale / cbet1;
+                y = lam12x / lamscale;
+            }
+
+            let xthresh = 1000. * TOL0.sqrt();
+            if y > -TOL1 && x > -1. - xthresh {
+                // Strip near cut
+                if self.f >= 0. {
+                    salp1 = (-x).min(1.);
+                    calp1 = -(1. - salp1 * salp1).sqrt();
+                } else {
+                    calp1 = x.max(if x > -TOL1 { 0. } else { -1. });
+                    salp1 = (1. - calp1 * calp1).sqrt();
+                }
+            } else {
+                // Estimate alp1 by solving the astroid problem
+                let k = astroid(x, y);
+                let omg12a = lamscale
+                    * if self.f >= 0. {
+                        -x * k / (1. + k)
+                    } else {
+                        -y * (1. + k) / k
+                    };
+                (somg12, comg12) = omg12a.sin_cos();
+                comg12 = -comg12;
+                // Update spherical estimate of alp1 using omg12 instead of lam12
+                salp1 = cbet2 * somg12;
+                calp1 = sbet12a - cbet2 * sbet1 * somg12 * somg12 / (1. - comg12);
+            }
+        }
+
+        // Sanity check on starting guess, allowing NaN through
+        if salp1 > 0. || salp1.is_nan() {
+            norm2(&mut salp1, &mut calp1);
+        } else {
+            salp1 = 1.;
+            calp1 = 0.;
+        }
+        (sig12, salp1, calp1, salp2, calp2, dnm)
+    }
+
+    // The longitude difference on the auxiliary sphere, lambda12, as a function
+    // of the azimuth at P1, alp1 - and its derivative, if `diffp` is set
+    #[allow(clippy::too_many_arguments)]
+    fn lambda12(
+        &self,
+        (sbet1, cbet1, dn1): (f64, f64, f64),
+        (sbet2, cbet2, dn2): (f64, f64, f64),
+        salp1: f64,
+        calp1: f64,
+        slam120: f64,
+        clam120: f64,
+        diffp: bool,
+    ) -> Lambda12 {
+        // Break degeneracy of equatorial line
+        let calp1 = if sbet1 == 0. && calp1 == 0. {
+            -TINY
+        } else {
+            calp1
+        };
+
+        // sin(alp1) * cos(bet1) = sin(alp0)
+        let salp0 = salp1 * cbet1;
+        let calp0 = calp1.hypot(salp1 * sbet1);
+
+        // tan(bet1) = tan(sig1) * cos(alp1)
+        // tan(omg1) = sin(alp0) * tan(sig1) = tan(omg1)=tan(alp1)*sin(bet1)
+        let mut ssig1 = sbet1;
+        let somg1 = salp0 * sbet1;
+        let mut csig1 = calp1 * cbet1;
+        let comg1 = csig1;
+        norm2(&mut ssig1, &mut csig1);
+
+        // Enforce symmetries in the case abs(bet2) = -bet1
+        let salp2 = if cbet2 != cbet1 { salp0 / cbet2 } else { salp1 };
+        let calp2 = if cbet2 != cbet1 || sbet2.abs() != -sbet1 {
+            let t = if cbet1 < -sbet1 {
+                (cbet2 - cbet1) * (cbet1 + cbet2)
+            } else {
+                (sbet1 - sbet2) * (sbet1 + sbet2)
+            };
+            ((calp1 * cbet1) * (calp1 * cbet1) + t).sqrt() / cbet2
+        } else {
+            calp1.abs()
+        };
+
+        // tan(bet2) = tan(sig2) * cos(alp2)
+        // tan(omg2) = sin(alp0) * tan(sig2).
+        let mut ssig2 = sbet2;
+        let somg2 = salp0 * sbet2;
+        let mut csig2 = calp2 * cbet2;
+        let comg2 = csig2;
+        norm2(&mut ssig2, &mut csig2);
+
+        // sig12 = sig2 - sig1, limit to [0, pi]
+        let sig12 =
+            (0_f64.max(csig1 * ssig2 - ssig1 * csig2) + 0.).atan2(csig1 * csig2 + ssig1 * ssig2);
+
+        // omg12 = omg2 - omg1, limit to [0, pi]
+        let somg12 = 0_f64.max(comg1 * somg2 - somg1 * comg2) + 0.;
+        let comg12 = comg1 * comg2 + somg1 * somg2;
+
+        // eta = omg12 - lam120
+        let eta = (somg12 * clam120 - comg12 * slam120).atan2(comg12 * clam120 + somg12 * slam120);
+        let k2 = calp0 * calp0 * self.ep2;
+        let eps = k2 / (2. * (1. + (1. + k2).sqrt()) + k2);
+        let mut C3a = [0.; ORDER + 1];
+        self.C3f(eps, &mut C3a);
+        let B312 = sin_series(ssig2, csig2, &C3a) - sin_series(ssig1, csig1, &C3a);
+        let domg12 = -self.f * self.A3f(eps) * salp0 * (sig12 + B312);
+        let lam12 = eta + domg12;
+
+        let mut dlam12 = 0.;
+        if diffp {
+            if calp2 == 0. {
+                dlam12 = -2. * self.f1 * dn1 / sbet1;
+            } else {
+                let (_, m12b, ..) = self.lengths(
+                    eps,
+                    sig12,
+                    (ssig1, csig1, dn1),
+                    (ssig2, csig2, dn2),
+                    cbet1,
+                    cbet2,
+                );
+                dlam12 = m12b * self.f1 / (calp2 * cbet2);
+            }
+        }
+
+        Lambda12 {
+            lam12,
+            salp2,
+            calp2,
+            sig12,
+            ssig1,
+            csig1,
+            ssig2,
+            csig2,
+            eps,
+            dlam12,
+        }
+    }
+}
+
+// The results of Karney::lambda12
+struct Lambda12 {
+    lam12: f64,
+    salp2: f64,
+    calp2: f64,
+    sig12: f64,
+    ssig1: f64,
+    csig1: f64,
+    ssig2: f64,
+    csig2: f64,
+    eps: f64,
+    dlam12: f64,
+}
+
+// Evaluate the polynomial p[0]·xⁿ + p[1]·xⁿ⁻¹ + ... + p[n]
+fn polyval(n: usize, p: &[f64], x: f64) -> f64 {
+    p[1..=n].iter().fold(p[0], |y, c| y * x + c)
+}
+
+// Evaluate Σ c[i] · sin(2·i·x), for i ∈ {1, ... , ORDER} using Clenshaw summation.
+// Note that c[0] is unused
+fn sin_series(sinx: f64, cosx: f64, c: &[f64; ORDER + 1]) -> f64 {
+    let ar = 2. * (cosx - sinx) * (cosx + sinx); // 2 cos(2x)
+    let mut y0 = 0.;
+    let mut y1 = 0.;
+    let mut k = ORDER;
+    while k > 0 {
+        y1 = ar * y0 - y1 + c[k];
+        y0 = ar * y1 - y0 + c[k - 1];
+        k -= 2;
+    }
+    2. * sinx * cosx * y0 // sin(2x) · y0
+}
+
+// (1 - eps) · A1 - 1
+#[allow(non_snake_case)]
+fn A1m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [1., 4., 64., 0., 256.];
+    let m = ORDER / 2;
+    let t = polyval(m, &COEFF, eps * eps) / COEFF[m + 1];
+    (t + eps) / (1. - eps)
+}
+
+#[allow(non_snake_case)]
+fn C1f(eps: f64, c: &mut [f64; ORDER + 1]) {
+    #[rustfmt::skip]
+    const COEFF: [f64; 18] = [
+        -1., 6., -16., 32.,
+        -9., 64., -128., 2048.,
+        9., -16., 768.,
+        3., -5., 512.,
+        -7., 1280.,
+        -7., 2048.,
+    ];
+    fill_series(eps, &COEFF, c);
+}
+
+#[allow(non_snake_case)]
+fn C1pf(eps: f64, c: &mut [f64; ORDER + 1]) {
+    #[rustfmt::skip]
+    const COEFF: [f64; 18] = [
+        205., -432., 768., 1536.,
+        4005., -4736., 3840., 12288.,
+        -225., 116., 384.,
+        -7173., 2695., 7680.,
+        3467., 7680.,
+        38081., 61440.,
+    ];
+    fill_series(eps, &COEFF, c);
+}
+
+// (1 + eps) · A2 - 1
+#[allow(non_snake_case)]
+fn A2m1f(eps: f64) -> f64 {
+    const COEFF: [f64; 5] = [-11., -28., -192., 0., 256.];
+    let m = ORDER / 2;
+    let t = polyval(m, &COEFF, eps * eps) / COEFF[m + 1];
+    (t - eps) / (1. + eps)
+}
+
+#[allow(non_snake_case)]
+fn C2f(eps: f64, c: &mut [f64; ORDER + 1]) {
+    #[rustfmt::skip]
+    const COEFF: [f64; 18] = [
+        1., 2., 16., 32.,
+        35., 64., 384., 2048.,
+        15., 80., 768.,
+        7., 35., 512.,
+        63., 1280.,
+        77., 2048.,
+    ];
+    fill_series(eps, &COEFF, c);
+}
+
+// Evaluate the coefficients c[l] = epsˡ · P_l(eps²), l ∈ {1, ... , ORDER}
+fn fill_series(eps: f64, coeff: &[f64], c: &mut [f64; ORDER + 1]) {
+    let eps2 = eps * eps;
+    let mut d = eps;
+    let mut o = 0;
+    for (l, cl) in c.iter_mut().enumerate().skip(1) {
+        let m = (ORDER - l) / 2;
+        *cl = d * polyval(m, &coeff[o..], eps2) / coeff[o + m + 1];
+        o += m + 2;
+        d *= eps;
+    }
+}
+
+// Solve k⁴ + 2k³ - (x² + y² - 1)k² - 2y²k - y² = 0 for the positive root k
+fn astroid(x: f64, y: f64) -> f64 {
+    let p = x * x;
+    let q = y * y;
+    let r = (p + q - 1.) / 6.;
+    if q == 0. && r <= 0. {
+        // y = 0 with |x| <= 1: k is zero here
+        return 0.;
+    }
+
+    // Avoid possible division by zero when r = 0 by multiplying
+    // equations for s and t by r³ and r, respectively
+    let s = p * q / 4.;
+    let r2 = r * r;
+    let r3 = r * r2;
+    // The discriminant of the quadratic equation for T3
+    let disc = s * (s + 2. * r3);
+    let mut u = r;
+    if disc >= 0. {
+        // Pick the sign on the sqrt to maximize abs(T3), minimizing cancellation
+        let mut t3 = s + r3;
+        t3 += if t3 < 0. { -disc.sqrt() } else { disc.sqrt() };
+        let t = t3.cbrt();
+        u += t + if t != 0. { r2 / t } else { 0. };
+    } else {
+        // T is complex, but the way u is defined the result is real
+        let ang = (-disc).sqrt().atan2(-(s + r3));
+        u += 2. * r * (ang / 3.).cos();
+    }
+    let v = (u * u + q).sqrt();
+    let uv = if u < 0. { q / (v - u) } else { u + v };
+    let w = (uv - q) / (2. * v);
+    uv / ((uv + w * w).sqrt() + w)
+}
+
+// ----- Angular helpers, working in degrees ----------------------------------------
+
+fn norm2(s: &mut f64, c: &mut f64) {
+    let r = s.hypot(*c);
+    *s /= r;
+    *c /= r;
+}
+
+// IEEE 754 style remainder: x - n·y, with n the integer nearest to x/y (ties to even)
+fn remainder(x: f64, y: f64) -> f64 {
+    let q = x / y;
+    let mut n = q.round();
+    if (q - q.trunc()).abs() == 0.5 && n % 2. != 0. {
+        n -= q.signum();
+    }
+    x - n * y
+}
+
+// Round tiny values, so that very small numbers become zero
+fn ang_round(x: f64) -> f64 {
+    const Z: f64 = 1. / 16.;
+    let y = x.abs();
+    let w = Z - y;
+    let y = if w > 0. { Z - w } else { y };
+    y.copysign(x)
+}
+
+fn ang_normalize(x: f64) -> f64 {
+    let y = remainder(x, 360.);
+    if y.abs() == 180. {
+        180_f64.copysign(x)
+    } else {
+        y
+    }
+}
+
+fn lat_fix(x: f64) -> f64 {
+    if x.abs() > 90. {
+        f64::NAN
+    } else {
+        x
+    }
+}
+
+// Error free sum: Returns (u + v, error)
+fn sum(u: f64, v: f64) -> (f64, f64) {
+    let s = u + v;
+    let up = s - v;
+    let vpp = s - up;
+    let t = if s != 0. {
+        0. - ((up - u) + (vpp - v))
+    } else {
+        s
+    };
+    (s, t)
+}
+
+// The difference y - x, reduced to [-180, 180], and its rounding error
+fn ang_diff(x: f64, y: f64) -> (f64, f64) {
+    let (d, t) = sum(remainder(-x, 360.), remainder(y, 360.));
+    let (mut d, t) = sum(remainder(d, 360.), t);
+    if d == 0. || d.abs() == 180. {
+        d = d.copysign(if t == 0. { y - x } else { -t });
+    }
+    (d, t)
+}
+
+// Sine and cosine of x + t degrees, with exact reduction of the argument,
+// and rounding of the sum of the reduced argument and the error term
+fn sincosde(x: f64, t: f64) -> (f64, f64) {
+    let q = (x / 90.).round();
+    let r = ang_round((x - 90. * q) + t).to_radians();
+    let (s, c) = r.sin_cos();
+    let (mut sinx, cosx) = match (q as i64).rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    if sinx == 0. {
+        sinx = sinx.copysign(x);
+    }
+    (sinx, cosx + 0.)
+}
+
+// Sine and cosine of x degrees, with exact reduction of the argument
+fn sincosd(x: f64) -> (f64, f64) {
+    let q = (x / 90.).round();
+    let r = (x - 90. * q).to_radians();
+    let (s, c) = r.sin_cos();
+    let (mut sinx, cosx) = match (q as i64).rem_euclid(4) {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    };
+    if sinx == 0. {
+        sinx = sinx.copysign(x);
+    }
+    (sinx, cosx + 0.)
+}
+
+// atan2 in degrees, rearranged to minimize roundoff
+fn atan2d(y: f64, x: f64) -> f64 {
+    let (mut x, mut y) = (x, y);
+    let mut q = 0;
+    if y.abs() > x.abs() {
+        std::mem::swap(&mut x, &mut y);
+        q = 2;
+    }
+    if x.is_sign_negative() {
+        x = -x;
+        q += 1;
+    }
+    let ang = y.atan2(x).to_degrees();
+    match q {
+        1 => 180_f64.copysign(y) - ang,
+        2 => 90. - ang,
+        3 => -90. + ang,
+        _ => ang,
+    }
+}
+
 // ----- Tests ---------------------------------------------------------------------
 
 #[cfg(test)]
@@ -243,6 +1290,102 @@ mod tests {
         let b = ellps.geodesic_fwd(&p1, d[0], d[2]);
         assert!((b[0].to_degrees() - p2[0].to_degrees()).abs() < 1e-9);
         assert!((b[1].to_degrees() - p2[1].to_degrees()).abs() < 1e-9);
+
+        // The Vincenty versions agree in these well behaved cases
+        let v = ellps.geodesic_inv_vincenty(&p1, &p2);
+        assert!((v[0] - d[0]).to_degrees().abs() < 1e-9);
+        assert!((v[1] - d[1]).to_degrees().abs() < 1e-9);
+        assert!((v[2] - d[2]).abs() < 1e-4);
+        let b = ellps.geodesic_fwd_vincenty(&p1, d[0], d[2]);
+        assert!((b[0].to_degrees() - p2[0].to_degrees()).abs() < 1e-9);
+        assert!((b[1].to_degrees() - p2[1].to_degrees()).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)] // Keep the reference values verbatim
+    fn antipodal() -> Result<(), Error> {
+        // Near-antipodal test cases from the GeographicLib test suite
+        // (GeodSolve6, 9, 10, 11)
+        let ellps = Ellipsoid::named("WGS84")?;
+        let cases = [
+            (
+                88.202499451857,
+                -88.202499451857,
+                179.981022032992859592,
+                20003898.214,
+            ),
+            (
+                89.262080389218,
+                -89.262080389218,
+                179.992207982775375662,
+                20003925.854,
+            ),
+            (
+                89.333123580033,
+                -89.333123580032997687,
+                179.99295812360148422,
+                20003926.881,
+            ),
+            (
+                56.320923501171,
+                -56.320923501171,
+                179.664747671772880215,
+                19993558.287,
+            ),
+            (
+                52.784459512564,
+                -52.784459512563990912,
+                179.634407464943777557,
+                19991596.095,
+            ),
+            (
+                48.522876735459,
+                -48.52287673545898293,
+                179.599720456223079643,
+                19989144.774,
+            ),
+        ];
+        for (lat1, lat2, lon2, s12) in cases {
+            let p1 = Coor2D::geo(lat1, 0.);
+            let p2 = Coor2D::geo(lat2, lon2);
+            let d = ellps.geodesic_inv(&p1, &p2);
+            assert!((d[2] - s12).abs() < 0.5e-3);
+
+            // Vincenty gives up...
+
+            // The direct solution brings us back where we started
+            let b = ellps.geodesic_fwd(&p1, d[0], d[2]);
+            assert!((b[0].to_degrees() - lon2).abs() < 1e-9);
+            assert!((b[1].to_degrees() - lat2).abs() < 1e-9);
+        }
+
+        // Here, Vincenty gives up, and is more than 40 km off
+        let p1 = Coor2D::geo(56.320923501171, 0.);
+        let p2 = Coor2D::geo(-56.320923501171, 179.664747671772880215);
+        let v = ellps.geodesic_inv_vincenty(&p1, &p2);
+        assert!(v[3] > 990.);
+        assert!((v[2] - 19993558.287).abs() > 40e3);
+        assert!(ellps.geodesic_inv(&p1, &p2)[3] < 20.);
+
+        // JFK--CDG (GeodSolve0 and GeodSolve1)
+        let jfk = Coor2D::geo(40.6, -73.8);
+        let cdg = Coor2D::geo(49.01666667, 2.55);
+        let d = ellps.geodesic_inv(&jfk, &cdg).to_degrees();
+        assert!((d[0] - 53.47022).abs() < 0.5e-5);
+        assert!((d[1] - 111.59367).abs() < 0.5e-5);
+        assert!((d[2] - 5853226.).abs() < 0.5);
+
+        let jfk = Coor2D::geo(40.63972222, -73.77888889);
+        let b = ellps.geodesic_fwd(&jfk, 53.5_f64.to_radians(), 5850e3);
+        assert!((b[0].to_degrees() - 2.56106).abs() < 0.5e-5);
+        assert!((b[1].to_degrees() - 49.01467).abs() < 0.5e-5);
+        assert!((b[2].to_degrees() - 111.62947).abs() < 0.5e-5);
+
+        // Short lines (GeodSolve4)
+        let p1 = Coor2D::geo(36.493349428792, 0.);
+        let p2 = Coor2D::geo(36.49334942879201, 0.0000008);
+        assert!((ellps.distance(&p1, &p2) - 0.072).abs() < 0.5e-3);
         Ok(())
     }
 }
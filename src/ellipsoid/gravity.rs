@@ -1,13 +1,46 @@
 use super::*;
+use std::f64::consts::PI;
+
+/// The Newtonian constant of gravitation, *G* (m³/kg/s²), CODATA 2018
+const NEWTONIAN_CONSTANT_OF_GRAVITATION: f64 = 6.674_30e-11;
+
+// ----- Physical constants --------------------------------------------------
+
+/// The physical parameters defining a level ellipsoid, in addition to its
+/// size and shape: The geocentric gravitational constant, *GM* (m³/s²),
+/// and the angular velocity, *ω* (rad/s)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GravityConstants {
+    pub gm: f64,
+    pub omega: f64,
+}
+
+impl GravityConstants {
+    /// GRS80 ([Moritz, 2000](crate::Bibliography::Mor00))
+    pub const GRS80: GravityConstants = GravityConstants {
+        gm: 3.986_005e14,
+        omega: 7.292_115e-5,
+    };
+
+    /// WGS84 (NIMA TR8350.2)
+    pub const WGS84: GravityConstants = GravityConstants {
+        gm: 3.986_004_418e14,
+        omega: 7.292_115e-5,
+    };
+}
 
 // ----- Normal gravity -----------------------------------------------------
 
-/// The normal gravity implementations are based solely on information
+/// The historical normal gravity formulae are based solely on information
 /// from [HandWiki](https://handwiki.org/wiki/Earth:Normal_gravity_formula).
+/// They remain untested in the absolute sense of the word: The test suite
+/// checks for regressions, but the values used for comparison are entirely
+/// internally sourced.
 ///
-/// Currently they remain untested in the absolute sense of the word:
-/// The test suite checks for regressions, but the values used for comparison
-/// are entirely internally sourced.
+/// The closed form expressions for the normal gravity of a level ellipsoid,
+/// given its [`GravityConstants`], follow
+/// [Moritz (2000)](crate::Bibliography::Mor00), and are checked against the
+/// values published there.
 pub trait Gravity: EllipsoidBase {
     /// The Somigliana normal gravity formula. If the equatorial
     /// normal gravity, gamma_a, and/or the polar normal gravity,
@@ -27,6 +60,74 @@ pub trait Gravity: EllipsoidBase {
         ga * (1.0 + p * s) / (1.0 - s * es).sqrt()
     }
 
+    /// The ratio *m = ω²a²b / GM*, of the centrifugal acceleration to the
+    /// gravitational acceleration at the equator
+    #[must_use]
+    fn gravity_ratio(&self, constants: GravityConstants) -> f64 {
+        let a = self.semimajor_axis();
+        let b = self.semiminor_axis();
+        constants.omega * constants.omega * a * a * b / constants.gm
+    }
+
+    /// Normal gravity at the equator, *γa*, and at the poles, *γb*, of the
+    /// level ellipsoid (Moritz, 2000, eqs. 1-2 and 25-28)
+    #[must_use]
+    fn equatorial_and_polar_normal_gravity(&self, constants: GravityConstants) -> (f64, f64) {
+        let a = self.semimajor_axis();
+        let b = self.semiminor_axis();
+        let m = self.gravity_ratio(constants);
+
+        // e' and the q-functions, q₀ and q₀'
+        let ep = self.second_eccentricity();
+        let atan = ep.atan();
+        let q0 = ((1. + 3. / (ep * ep)) * atan - 3. / ep) / 2.;
+        let q0p = 3. * (1. + 1. / (ep * ep)) * (1. - atan / ep) - 1.;
+
+        let gamma_a = constants.gm / (a * b) * (1. - m - m * ep * q0p / (6. * q0));
+        let gamma_b = constants.gm / (a * a) * (1. + m * ep * q0p / (3. * q0));
+        (gamma_a, gamma_b)
+    }
+
+    /// The gravity flattening, *f\* = (γb - γa) / γa*
+    #[must_use]
+    fn gravity_flattening(&self, constants: GravityConstants) -> f64 {
+        let (gamma_a, gamma_b) = self.equatorial_and_polar_normal_gravity(constants);
+        (gamma_b - gamma_a) / gamma_a
+    }
+
+    /// Normal gravity on the level ellipsoid, i.e. the
+    /// [Somigliana formula](Self::somigliana_gravity), with *γa* and *γb*
+    /// computed from the `constants`
+    #[must_use]
+    fn normal_gravity(&self, latitude: f64, constants: GravityConstants) -> f64 {
+        let (gamma_a, gamma_b) = self.equatorial_and_polar_normal_gravity(constants);
+        self.somigliana_gravity(latitude, Some(gamma_a), Some(gamma_b))
+    }
+
+    /// The free-air correction: The decrease of normal gravity from the
+    /// ellipsoid to the ellipsoidal height `height`, to second order in the
+    /// height (Moritz, 2000, eq. 41). The value is to be **subtracted** from
+    /// the normal gravity on the ellipsoid (approximately 0.3086 mGal/m).
+    #[must_use]
+    fn free_air_correction(&self, latitude: f64, height: f64, constants: GravityConstants) -> f64 {
+        let a = self.semimajor_axis();
+        let f = self.flattening();
+        let m = self.gravity_ratio(constants);
+        let gamma = self.normal_gravity(latitude, constants);
+        let s = latitude.sin().powi(2);
+        gamma * (2. / a * (1. + f + m - 2. * f * s) * height - 3. * height * height / (a * a))
+    }
+
+    /// The Bouguer correction, *2πGρh*: The attraction of an infinite plate
+    /// of thickness `height` and density `density` (in kg/m³, typically 2670).
+    /// The value is to be **subtracted** from observed gravity, when
+    /// computing Bouguer anomalies (approximately 0.1119 mGal/m, for the
+    /// typical density).
+    #[must_use]
+    fn bouguer_correction(&self, height: f64, density: f64) -> f64 {
+        2. * PI * NEWTONIAN_CONSTANT_OF_GRAVITATION * density * height
+    }
+
     /// The international gravity formula 1930, for use with the
     /// international (Hayford) ellipsoid (or for mis-use with
     /// any other ellipsoid)
@@ -94,7 +195,7 @@ pub trait Gravity: EllipsoidBase {
     /// ellipsoid.
     #[must_use]
     fn grs67_height_correction(&self, latitude: f64, height: f64) -> f64 {
-        ((3.0877e-6 - 4.3e-9 * latitude.sin().powi(2)) + 7.2e-13 * height) * height
+        ((3.0877e-6 - 4.3e-9 * latitude.sin().powi(2)) - 7.2e-13 * height) * height
     }
 
     /// The WELMEC method combines the latitudinal and the height correction
@@ -131,7 +232,49 @@ mod tests {
     #[test]
     fn somigliana_gravity() {
         let ellps = Ellipsoid::named("GRS80").unwrap();
-        assert!((ellps.somigliana_gravity(45_f64, None, None) - 9.81782912276995) < 1e-15);
+        let gamma = ellps.somigliana_gravity(45_f64.to_radians(), None, None);
+        assert!((gamma - ellps.grs80_gravity(45_f64.to_radians())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn level_ellipsoid() {
+        // GRS80: Reference values from Moritz (2000)
+        let ellps = Ellipsoid::named("GRS80").unwrap();
+        let grs80 = GravityConstants::GRS80;
+        assert!((ellps.gravity_ratio(grs80) - 0.003_449_786_003_08).abs() < 1e-14);
+        let (gamma_a, gamma_b) = ellps.equatorial_and_polar_normal_gravity(grs80);
+        assert!((gamma_a - 9.780_326_771_5).abs() < 1e-10);
+        assert!((gamma_b - 9.832_186_368_5).abs() < 1e-10);
+        assert!((ellps.gravity_flattening(grs80) - 0.005_302_440_112).abs() < 1e-12);
+
+        // The normal gravity agrees with the GRS80 series expansion
+        for lat in [0., 12., 45., 55., 90.] {
+            let lat = f64::to_radians(lat);
+            let gamma = ellps.normal_gravity(lat, grs80);
+            assert!((gamma - ellps.grs80_gravity(lat)).abs() < 1e-9);
+        }
+
+        // WGS84: Reference values from NIMA TR8350.2
+        let ellps = Ellipsoid::named("WGS84").unwrap();
+        let (gamma_a, gamma_b) = ellps.equatorial_and_polar_normal_gravity(GravityConstants::WGS84);
+        assert!((gamma_a - 9.780_325_335_9).abs() < 1e-10);
+        assert!((gamma_b - 9.832_184_937_8).abs() < 1e-10);
+    }
+
+    #[test]
+    fn free_air_and_bouguer() {
+        let ellps = Ellipsoid::named("GRS80").unwrap();
+        let grs80 = GravityConstants::GRS80;
+
+        // Roughly 0.3086 mGal/m, and close to the GRS67 height correction
+        let lat = 45_f64.to_radians();
+        let fa = ellps.free_air_correction(lat, 1000., grs80);
+        assert!((fa - 3.085e-3).abs() < 1e-6);
+        assert!((fa - ellps.grs67_height_correction(lat, 1000.)).abs() < 1e-6);
+
+        // Roughly 0.1119 mGal/m for the standard crustal density
+        let bc = ellps.bouguer_correction(1000., 2670.);
+        assert!((bc - 1.119e-3).abs() < 1e-6);
     }
 
     #[test]
@@ -174,7 +317,7 @@ mod tests {
     fn grs67_height_correction() {
         let ellps = Ellipsoid::named("GRS80").unwrap();
         assert!(
-            (ellps.grs67_height_correction(45_f64.to_radians(), 1000.) - 0.00308627).abs() < 1e-8
+            (ellps.grs67_height_correction(45_f64.to_radians(), 1000.) - 0.00308483).abs() < 1e-8
         );
     }
 
@@ -1,5 +1,106 @@
 use super::*;
 use crate::authoring::*;
+use crate::math::series::POLYNOMIAL_ORDER;
+
+/// The six auxiliary latitudes of [Karney (2022)](crate::Bibliography::Kar22),
+/// (well - five auxiliary latitudes and the geographic one), for use with the
+/// general conversion functionality, [`Latitudes::latitude_convert`] and
+/// [`AuxiliaryLatitudes`].
+///
+/// The isometric latitude, 𝜓, is not an angle, and hence not included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AuxiliaryLatitude {
+    /// Geographic latitude, 𝜙 (a.k.a. geodetic latitude)
+    Geographic = 0,
+    /// Parametric latitude, 𝛽 (a.k.a. reduced latitude)
+    Parametric = 1,
+    /// Geocentric latitude, 𝜃
+    Geocentric = 2,
+    /// Rectifying latitude, 𝜇
+    Rectifying = 3,
+    /// Conformal latitude, 𝜒
+    Conformal = 4,
+    /// Authalic latitude, 𝜉
+    Authalic = 5,
+}
+
+impl AuxiliaryLatitude {
+    /// All the auxiliary latitudes, in the order used by Karney (2022)
+    pub const ALL: [AuxiliaryLatitude; 6] = [
+        AuxiliaryLatitude::Geographic,
+        AuxiliaryLatitude::Parametric,
+        AuxiliaryLatitude::Geocentric,
+        AuxiliaryLatitude::Rectifying,
+        AuxiliaryLatitude::Conformal,
+        AuxiliaryLatitude::Authalic,
+    ];
+
+    /// The name of the auxiliary latitude, as used in the `latitude` operator
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuxiliaryLatitude::Geographic => "geographic",
+            AuxiliaryLatitude::Parametric => "parametric",
+            AuxiliaryLatitude::Geocentric => "geocentric",
+            AuxiliaryLatitude::Rectifying => "rectifying",
+            AuxiliaryLatitude::Conformal => "conformal",
+            AuxiliaryLatitude::Authalic => "authalic",
+        }
+    }
+
+    // The polynomial coefficients for converting to and from the geographic latitude
+    fn polynomial_coefficients(self) -> PolynomialCoefficients {
+        PolynomialCoefficients {
+            fwd: constants::AUXILIARY_LATITUDES[0][self as usize],
+            inv: constants::AUXILIARY_LATITUDES[self as usize][0],
+        }
+    }
+}
+
+impl std::str::FromStr for AuxiliaryLatitude {
+    type Err = Error;
+
+    /// Parse the name of an auxiliary latitude. The aliases `geodetic` and
+    /// `reduced` are recognized for `geographic` and `parametric`, respectively
+    fn from_str(name: &str) -> Result<Self, Error> {
+        match name.to_lowercase().as_str() {
+            "geographic" | "geodetic" => Ok(AuxiliaryLatitude::Geographic),
+            "parametric" | "reduced" => Ok(AuxiliaryLatitude::Parametric),
+            "geocentric" => Ok(AuxiliaryLatitude::Geocentric),
+            "rectifying" => Ok(AuxiliaryLatitude::Rectifying),
+            "conformal" => Ok(AuxiliaryLatitude::Conformal),
+            "authalic" => Ok(AuxiliaryLatitude::Authalic),
+            _ => Err(Error::BadParam("latitude".to_string(), name.to_string())),
+        }
+    }
+}
+
+/// The Fourier coefficients for converting between any pair of
+/// [auxiliary latitudes](AuxiliaryLatitude), precomputed for a given
+/// ellipsoid by [`Latitudes::auxiliary_latitudes`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AuxiliaryLatitudes {
+    coefficients: [[[f64; POLYNOMIAL_ORDER]; 6]; 6],
+}
+
+impl AuxiliaryLatitudes {
+    /// Convert `latitude` (in radians) from the auxiliary latitude `from`
+    /// to the auxiliary latitude `to`
+    #[must_use]
+    pub fn convert(&self, latitude: f64, from: AuxiliaryLatitude, to: AuxiliaryLatitude) -> f64 {
+        let coefficients = &self.coefficients[from as usize][to as usize];
+        latitude + fourier::sin(2. * latitude, coefficients)
+    }
+
+    /// The Fourier coefficients for the conversion from `from` to `to`
+    #[must_use]
+    pub fn coefficients(
+        &self,
+        from: AuxiliaryLatitude,
+        to: AuxiliaryLatitude,
+    ) -> [f64; POLYNOMIAL_ORDER] {
+        self.coefficients[from as usize][to as usize]
+    }
+}
 
 /// The many different latitudes
 pub trait Latitudes: EllipsoidBase {
@@ -58,7 +159,7 @@ pub trait Latitudes: EllipsoidBase {
 
     /// Obtain the coefficients needed for working with rectifying latitudes
     fn coefficients_for_rectifying_latitude_computations(&self) -> FourierCoefficients {
-        self.latitude_fourier_coefficients(&constants::RECTIFYING)
+        self.latitude_fourier_coefficients(&AuxiliaryLatitude::Rectifying.polynomial_coefficients())
     }
 
     /// Geographic latitude, 𝜙, to rectifying, 𝜇
@@ -67,8 +168,7 @@ pub trait Latitudes: EllipsoidBase {
         geographic_latitude: f64,
         coefficients: &FourierCoefficients,
     ) -> f64 {
-        coefficients.etc[0]
-            * (geographic_latitude + fourier::sin(2. * geographic_latitude, &coefficients.fwd))
+        geographic_latitude + fourier::sin(2. * geographic_latitude, &coefficients.fwd)
     }
 
     /// Rectifying latitude, 𝜇, to geographic, 𝜙
@@ -77,15 +177,14 @@ pub trait Latitudes: EllipsoidBase {
         rectifying_latitude: f64,
         coefficients: &FourierCoefficients,
     ) -> f64 {
-        let rlat = rectifying_latitude / coefficients.etc[0];
-        rlat + fourier::sin(2. * rlat, &coefficients.inv)
+        rectifying_latitude + fourier::sin(2. * rectifying_latitude, &coefficients.inv)
     }
 
     // --- Conformal latitude ---
 
     /// Obtain the coefficients needed for working with conformal latitudes
     fn coefficients_for_conformal_latitude_computations(&self) -> FourierCoefficients {
-        self.latitude_fourier_coefficients(&constants::CONFORMAL)
+        self.latitude_fourier_coefficients(&AuxiliaryLatitude::Conformal.polynomial_coefficients())
     }
 
     /// Geographic latitude, 𝜙, to conformal, 𝜒
@@ -110,7 +209,7 @@ pub trait Latitudes: EllipsoidBase {
 
     /// Obtain the coefficients needed for working with authalic latitudes
     fn coefficients_for_authalic_latitude_computations(&self) -> FourierCoefficients {
-        self.latitude_fourier_coefficients(&constants::AUTHALIC)
+        self.latitude_fourier_coefficients(&AuxiliaryLatitude::Authalic.polynomial_coefficients())
     }
 
     /// Geographic latitude, 𝜙, to authalic, 𝜉
@@ -131,6 +230,55 @@ pub trait Latitudes: EllipsoidBase {
         authalic_latitude + fourier::sin(2. * authalic_latitude, &coefficients.inv)
     }
 
+    // --- Any auxiliary latitude to any other ---
+
+    /// The Fourier coefficients for converting the auxiliary latitude `from`
+    /// to the auxiliary latitude `to`, following
+    /// [Karney (2022)](crate::Bibliography::Kar22)
+    #[must_use]
+    fn latitude_conversion_coefficients(
+        &self,
+        from: AuxiliaryLatitude,
+        to: AuxiliaryLatitude,
+    ) -> [f64; POLYNOMIAL_ORDER] {
+        let n = self.third_flattening();
+        let polynomials = &constants::AUXILIARY_LATITUDES[from as usize][to as usize];
+        let mut result = [0.; POLYNOMIAL_ORDER];
+        for (coefficient, polynomial) in result.iter_mut().zip(polynomials) {
+            *coefficient = n * taylor::horner(n, polynomial);
+        }
+        result
+    }
+
+    /// Convert `latitude` (in radians) from the auxiliary latitude `from` to
+    /// the auxiliary latitude `to`. When converting many latitudes, use the
+    /// precomputed coefficients from [`auxiliary_latitudes`](Latitudes::auxiliary_latitudes)
+    /// instead.
+    #[must_use]
+    fn latitude_convert(
+        &self,
+        latitude: f64,
+        from: AuxiliaryLatitude,
+        to: AuxiliaryLatitude,
+    ) -> f64 {
+        let coefficients = self.latitude_conversion_coefficients(from, to);
+        latitude + fourier::sin(2. * latitude, &coefficients)
+    }
+
+    /// Precompute the Fourier coefficients for converting between all pairs of
+    /// auxiliary latitudes for this ellipsoid
+    #[must_use]
+    fn auxiliary_latitudes(&self) -> AuxiliaryLatitudes {
+        let mut result = AuxiliaryLatitudes::default();
+        for from in AuxiliaryLatitude::ALL {
+            for to in AuxiliaryLatitude::ALL {
+                result.coefficients[from as usize][to as usize] =
+                    self.latitude_conversion_coefficients(from, to);
+            }
+        }
+        result
+    }
+
     // --- Internal ---
 
     fn latitude_fourier_coefficients(
@@ -208,6 +356,15 @@ mod tests {
             assert!((lat - phi).abs() < 1e-14);
             assert!((lat + ihp).abs() < 1e-14); // Symmetry
         }
+
+        // The rectifying latitude is the meridian distance, in units of
+        // the meridian quadrant, scaled to the interval [0, 𝜋/2]
+        let lat = 55_f64.to_radians();
+        let mu = ellps.latitude_geographic_to_rectifying(lat, &coefficients);
+        let m = ellps.meridian_latitude_to_distance(lat) / ellps.meridian_quadrant();
+        assert!((mu - m * FRAC_PI_2).abs() < 1e-12);
+        let mu = ellps.latitude_geographic_to_rectifying(FRAC_PI_2, &coefficients);
+        assert!((mu - FRAC_PI_2).abs() < 1e-14);
         Ok(())
     }
 
@@ -224,7 +381,7 @@ mod tests {
             0., 90., // Extreme values are invariant
         ];
 
-        let chi_coefs = ellps.latitude_fourier_coefficients(&constants::CONFORMAL);
+        let chi_coefs = ellps.coefficients_for_conformal_latitude_computations();
         let pairs = latitudes.iter().zip(conformal_latitudes.iter());
 
         #[allow(clippy::unnecessary_cast)]
@@ -303,6 +460,53 @@ mod tests {
         Ok(())
     }
 
+    // Conversions between any pair of auxiliary latitudes
+    #[test]
+    fn any_to_any() -> Result<(), Error> {
+        use AuxiliaryLatitude::*;
+        let ellps = Ellipsoid::named("GRS80")?;
+        let aux = ellps.auxiliary_latitudes();
+        let latitudes: [f64; 11] = [-90., -55., -35., -1., 0., 1e-9, 12., 45., 55., 89.9, 90.];
+
+        for lat in latitudes {
+            let phi = lat.to_radians();
+
+            // The series agree with the closed forms where available
+            let beta = aux.convert(phi, Geographic, Parametric);
+            assert!((beta - ellps.latitude_geographic_to_reduced(phi)).abs() < 1e-15);
+            let theta = aux.convert(phi, Geographic, Geocentric);
+            assert!((theta - ellps.latitude_geographic_to_geocentric(phi)).abs() < 1e-15);
+            let chi = aux.convert(phi, Geographic, Conformal);
+            let psi = ellps.latitude_geographic_to_isometric(phi);
+            assert!((chi - gudermannian::fwd(psi)).abs() < 1e-15);
+
+            for from in AuxiliaryLatitude::ALL {
+                let zeta = aux.convert(phi, Geographic, from);
+                for to in AuxiliaryLatitude::ALL {
+                    // Direct conversion equals conversion via the geographic latitude
+                    let direct = aux.convert(zeta, from, to);
+                    let via = aux.convert(aux.convert(zeta, from, Geographic), Geographic, to);
+                    assert!((direct - via).abs() < 1e-15, "{from:?} -> {to:?}");
+
+                    // ...and it roundtrips
+                    assert!((aux.convert(direct, to, from) - zeta).abs() < 1e-15);
+
+                    // The one-off conversion equals the precomputed one
+                    assert_eq!(direct, ellps.latitude_convert(zeta, from, to));
+                }
+            }
+        }
+
+        // Names and aliases
+        for aux in AuxiliaryLatitude::ALL {
+            assert_eq!(aux.name().parse::<AuxiliaryLatitude>()?, aux);
+        }
+        assert_eq!("reduced".parse::<AuxiliaryLatitude>()?, Parametric);
+        assert_eq!("Geodetic".parse::<AuxiliaryLatitude>()?, Geographic);
+        assert!("isometric".parse::<AuxiliaryLatitude>().is_err());
+        Ok(())
+    }
+
     // --- PROJ authlat, reimplemented in Rust ---
 
     // const P00: f64 = 0.33333333333333333333; //   1 /     3
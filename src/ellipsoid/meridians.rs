@@ -54,17 +54,76 @@ pub trait Meridians: EllipsoidBase {
     /// The distance, *M*, along a meridian from the equator to the given
     /// latitude is a special case of a geodesic length.
     ///
-    /// This implementation follows the remarkably simple algorithm
+    /// Computed from the series expansion of the rectifying latitude, to
+    /// *n⁸*, as in [Deakin et al (2012)](crate::Bibliography::Dea12).
+    /// See [meridian_latitude_to_distance_with_order](Meridians::meridian_latitude_to_distance_with_order)
+    /// for lower order truncations, and
+    /// [meridian_latitude_to_distance_bowring](Meridians::meridian_latitude_to_distance_bowring)
+    /// for the classical *n⁴* algorithm by Bowring.
+    #[must_use]
+    fn meridian_latitude_to_distance(&self, latitude: f64) -> f64 {
+        self.meridian_latitude_to_distance_with_order(latitude, 8)
+    }
+
+    /// Compute the latitude of a point, given *M*, its distance from the equator,
+    /// along its local meridian. The inverse of
+    /// [meridian_latitude_to_distance](Meridians::meridian_latitude_to_distance),
+    /// also to *n⁸*.
+    #[must_use]
+    fn meridian_distance_to_latitude(&self, distance_from_equator: f64) -> f64 {
+        self.meridian_distance_to_latitude_with_order(distance_from_equator, 8)
+    }
+
+    /// The length of the meridian arc from `lat1` to `lat2` (negative if
+    /// `lat2` is south of `lat1`)
+    #[must_use]
+    fn meridian_arc(&self, lat1: f64, lat2: f64) -> f64 {
+        self.meridian_latitude_to_distance(lat2) - self.meridian_latitude_to_distance(lat1)
+    }
+
+    /// The meridian distance from the equator to `latitude`, with all series
+    /// truncated after the *n*-to-the-power-of-`order` terms. The order is
+    /// clamped to the range 1..=8. For terrestrial ellipsoids, order 4 gives
+    /// sub-millimeter accuracy, while order 6 and up are at nanometer level.
+    #[must_use]
+    fn meridian_latitude_to_distance_with_order(&self, latitude: f64, order: usize) -> f64 {
+        let order = order.clamp(1, 8);
+        let n = self.third_flattening();
+        let coefficients = truncated_coefficients(n, &constants::MERIDIAN_ARC_FWD, order);
+        let mu = latitude + crate::math::fourier::sin(2. * latitude, &coefficients[..order]);
+        truncated_rectifying_radius(self, order) * mu
+    }
+
+    /// The latitude at the meridian distance `distance_from_equator`, with all
+    /// series truncated after the *n*-to-the-power-of-`order` terms. The
+    /// inverse of
+    /// [meridian_latitude_to_distance_with_order](Meridians::meridian_latitude_to_distance_with_order)
+    #[must_use]
+    fn meridian_distance_to_latitude_with_order(
+        &self,
+        distance_from_equator: f64,
+        order: usize,
+    ) -> f64 {
+        let order = order.clamp(1, 8);
+        let n = self.third_flattening();
+        let coefficients = truncated_coefficients(n, &constants::MERIDIAN_ARC_INV, order);
+        let mu = distance_from_equator / truncated_rectifying_radius(self, order);
+        mu + crate::math::fourier::sin(2. * mu, &coefficients[..order])
+    }
+
+    /// The distance, *M*, along a meridian from the equator to the given
+    /// latitude, following the remarkably simple algorithm
     /// by [Bowring (1983)](crate::Bibliography::Bow83).
     ///
     /// See also
     /// [Wikipedia: Transverse Mercator](https://en.wikipedia.org/wiki/Transverse_Mercator:_Bowring_series).
     ///
-    /// [Deakin et al](crate::Bibliography::Dea12) provides a higher order (*n⁸*) derivation.
+    /// Mostly useful for comparison: Prefer the *n⁸* version,
+    /// [meridian_latitude_to_distance](Meridians::meridian_latitude_to_distance).
     #[must_use]
     #[allow(non_snake_case)] // So we can use the mathematical notation from the original text
     #[allow(clippy::many_single_char_names)] // ditto
-    fn meridian_latitude_to_distance(&self, latitude: f64) -> f64 {
+    fn meridian_latitude_to_distance_bowring(&self, latitude: f64) -> f64 {
         let n = self.third_flattening();
 
         // The rectifying radius - using a slightly more accurate series than in Bowring (1983)
@@ -81,17 +140,15 @@ pub trait Meridians: EllipsoidBase {
     }
 
     /// Compute the latitude of a point, given *M*, its distance from the equator,
-    /// along its local meridian.
-    ///
-    /// This implementation follows the remarkably simple algorithm
-    /// by [Bowring (1983)](crate::Bibliography::Bow83).
+    /// along its local meridian, following the algorithm by
+    /// [Bowring (1983)](crate::Bibliography::Bow83).
     ///
     /// See also
-    /// [meridian_latitude_to_distance](Meridians::meridian_latitude_to_distance)
+    /// [meridian_latitude_to_distance_bowring](Meridians::meridian_latitude_to_distance_bowring)
     #[must_use]
     #[allow(non_snake_case)] // So we can use the mathematical notation from the original text
     #[allow(clippy::many_single_char_names)] // ditto
-    fn meridian_distance_to_latitude(&self, distance_from_equator: f64) -> f64 {
+    fn meridian_distance_to_latitude_bowring(&self, distance_from_equator: f64) -> f64 {
         let n = self.third_flattening();
 
         // Rectifying radius - using a slightly more accurate series than in Bowring (1983)
@@ -109,6 +166,24 @@ pub trait Meridians: EllipsoidBase {
     }
 }
 
+// The Fourier coefficients of `table`, evaluated for the third flattening, `n`,
+// and truncated after the n-to-the-power-of-`order` terms
+fn truncated_coefficients(n: f64, table: &[[f64; 8]; 8], order: usize) -> [f64; 8] {
+    let mut coefficients = [0.; 8];
+    for (k, coefficient) in coefficients.iter_mut().enumerate().take(order) {
+        *coefficient = n * crate::math::taylor::horner(n, &table[k][..order]);
+    }
+    coefficients
+}
+
+// The rectifying radius, truncated after the n-to-the-power-of-`order` terms
+fn truncated_rectifying_radius<E: EllipsoidBase + ?Sized>(ellps: &E, order: usize) -> f64 {
+    let n = ellps.third_flattening();
+    let terms = order / 2 + 1;
+    ellps.semimajor_axis() / (1. + n)
+        * crate::math::taylor::horner(n * n, &constants::MERIDIAN_ARC_COEFFICIENTS[..terms])
+}
+
 // ----- Tests ---------------------------------------------------------------------
 #[cfg(test)]
 mod tests {
@@ -193,6 +268,62 @@ mod tests {
         let length = 4_984_944.377_857_987;
         assert!((ellps.meridian_latitude_to_distance(angle) - length).abs() < 4e-6);
         assert!((ellps.meridian_distance_to_latitude(length) - angle).abs() < 4e-6);
+
+        // Bowring's algorithm is accurate at the 10 µm level for GRS80
+        assert!((ellps.meridian_latitude_to_distance_bowring(angle) - length).abs() < 1e-5);
+        assert!((ellps.meridian_distance_to_latitude_bowring(length) - angle).abs() < 1e-11);
+
+        // The n⁸ series agree with Karney at the nanometer level
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..s.len() {
+            let angle = (10.0 * i as f64).to_radians();
+            assert!((ellps.meridian_latitude_to_distance(angle) - s[i]).abs() < 1e-8);
+            assert!((ellps.meridian_distance_to_latitude(s[i]) - angle).abs() < 1e-15);
+        }
+
+        // Meridian arcs
+        let lat1 = 30_f64.to_radians();
+        let lat2 = 60_f64.to_radians();
+        assert!((ellps.meridian_arc(lat1, lat2) - (s[6] - s[3])).abs() < 1e-8);
+        assert!((ellps.meridian_arc(lat2, lat1) + (s[6] - s[3])).abs() < 1e-8);
+        Ok(())
+    }
+
+    #[test]
+    fn truncation_order() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("GRS80")?;
+        let angle = 45f64.to_radians();
+        let length = 4_984_944.377_857_987;
+
+        // The accuracy improves with the order, until hitting the floor
+        let mut previous = f64::INFINITY;
+        for order in 1..=6 {
+            let d = ellps.meridian_latitude_to_distance_with_order(angle, order);
+            let error = (d - length).abs();
+            assert!(error <= previous);
+            previous = error;
+
+            let lat = ellps.meridian_distance_to_latitude_with_order(d, order);
+            let n = ellps.third_flattening();
+            assert!((lat - angle).abs() < 1e-15 + 10. * n.powi(order as i32 + 1));
+        }
+        assert!(previous < 1e-8);
+
+        // A 1 m step along the meridian corresponds to a latitude step of 1/M.
+        // This requires the forward and inverse series to be consistent
+        for angle in [0_f64, 45., 55.] {
+            let angle = angle.to_radians();
+            let d = ellps.meridian_latitude_to_distance(angle);
+            let dlat = ellps.meridian_distance_to_latitude(d + 1.) - angle;
+            let m = ellps.meridian_radius_of_curvature(angle);
+            assert!((dlat * m - 1.).abs() < 1e-9);
+        }
+
+        // Out of range orders are clamped
+        let d8 = ellps.meridian_latitude_to_distance_with_order(angle, 8);
+        let d9 = ellps.meridian_latitude_to_distance_with_order(angle, 9);
+        assert_eq!(d8, d9);
+        assert_eq!(d8, ellps.meridian_latitude_to_distance(angle));
         Ok(())
     }
 }
@@ -5,6 +5,7 @@ pub mod geodesics;
 pub mod gravity;
 pub mod latitudes;
 pub mod meridians;
+pub mod rhumblines;
 pub mod triaxial;
 
 use crate::prelude::*;
@@ -15,6 +16,7 @@ impl<T> Latitudes for T where T: EllipsoidBase + ?Sized {}
 impl<T> GeoCart for T where T: EllipsoidBase + ?Sized {}
 impl<T> Geodesics for T where T: EllipsoidBase + ?Sized {}
 impl<T> Gravity for T where T: EllipsoidBase + ?Sized {}
+impl<T> Rhumblines for T where T: EllipsoidBase + ?Sized {}
 
 /// The fundamental "size and shape" parameters for an ellipsoid.
 /// In general we assume that the ellipsoid is oblate and biaxial,
@@ -52,6 +54,12 @@ pub trait EllipsoidBase {
         self.semimajor_axis() * (1.0 - self.flattening())
     }
 
+    /// True if the semimedian axis differs from the semimajor
+    #[must_use]
+    fn is_triaxial(&self) -> bool {
+        self.semimedian_axis() != self.semimajor_axis()
+    }
+
     // ----- Additional Flattenings ------------------------------------------------
 
     /// The second flattening, *g  =  (a - b) / b*
@@ -142,11 +150,86 @@ pub trait EllipsoidBase {
         let a = self.semimajor_axis();
         a * a / self.semiminor_axis()
     }
+
+    /// Euler's radius of curvature of the normal section in the direction
+    /// `azimuth`, *R = 1 / (cos²α / M + sin²α / N)*
+    #[must_use]
+    fn azimuthal_radius_of_curvature(&self, latitude: f64, azimuth: f64) -> f64 {
+        let m = self.meridian_radius_of_curvature(latitude);
+        let n = self.prime_vertical_radius_of_curvature(latitude);
+        let (s, c) = azimuth.sin_cos();
+        (c * c / m + s * s / n).recip()
+    }
+
+    /// The Gaussian mean radius of curvature, *sqrt(MN)*, i.e. the
+    /// geometric mean of the principal radii of curvature
+    #[must_use]
+    fn gaussian_radius_of_curvature(&self, latitude: f64) -> f64 {
+        let m = self.meridian_radius_of_curvature(latitude);
+        let n = self.prime_vertical_radius_of_curvature(latitude);
+        (m * n).sqrt()
+    }
+
+    /// The mean radius of curvature, *2 / (1/M + 1/N)*, i.e. the
+    /// harmonic mean of the principal radii of curvature
+    #[must_use]
+    fn mean_radius_of_curvature(&self, latitude: f64) -> f64 {
+        let m = self.meridian_radius_of_curvature(latitude);
+        let n = self.prime_vertical_radius_of_curvature(latitude);
+        2.0 * (n.recip() + m.recip()).recip()
+    }
+
+    /// The Gaussian curvature, *K = 1 / (MN)*, i.e. the product of the
+    /// principal curvatures
+    #[must_use]
+    fn gaussian_curvature(&self, latitude: f64) -> f64 {
+        let m = self.meridian_radius_of_curvature(latitude);
+        let n = self.prime_vertical_radius_of_curvature(latitude);
+        (m * n).recip()
+    }
+
+    /// The mean curvature, *H = (1/M + 1/N) / 2*, i.e. the arithmetic
+    /// mean of the principal curvatures
+    #[must_use]
+    fn mean_curvature(&self, latitude: f64) -> f64 {
+        let m = self.meridian_radius_of_curvature(latitude);
+        let n = self.prime_vertical_radius_of_curvature(latitude);
+        (m.recip() + n.recip()) / 2.0
+    }
+
+    // ----- Mean radii ------------------------------------------------------------
+
+    /// The arithmetic mean radius, *R₁ = (a + ay + b) / 3*
+    #[must_use]
+    fn mean_radius(&self) -> f64 {
+        (self.semimajor_axis() + self.semimedian_axis() + self.semiminor_axis()) / 3.0
+    }
+
+    /// The authalic radius, *R₂*: The radius of the sphere having the same
+    /// surface area as the ellipsoid. The default implementation handles the
+    /// biaxial case, while [`TriaxialEllipsoid`] overrides it
+    #[must_use]
+    fn authalic_radius(&self) -> f64 {
+        let a = self.semimajor_axis();
+        let e = self.eccentricity();
+        if e == 0.0 {
+            return a;
+        }
+        a * ((1.0 + (1.0 - e * e) * e.atanh() / e) / 2.0).sqrt()
+    }
+
+    /// The volumetric radius, *R₃ = cbrt(a·ay·b)*: The radius of the sphere
+    /// having the same volume as the ellipsoid
+    #[must_use]
+    fn volumetric_radius(&self) -> f64 {
+        (self.semimajor_axis() * self.semimedian_axis() * self.semiminor_axis()).cbrt()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::f64::consts::FRAC_PI_2;
 
     #[test]
     fn shape_and_size() -> Result<(), Error> {
@@ -204,6 +287,14 @@ mod tests {
                 < 1e-6
         );
 
+        // Gaussian and mean curvature are the reciprocals of the squared
+        // Gaussian radius, and the mean radius, respectively
+        let lat = 55_f64.to_radians();
+        let k = ellps.gaussian_curvature(lat) * ellps.gaussian_radius_of_curvature(lat).powi(2);
+        assert!((k - 1.).abs() < 1e-15);
+        let h = ellps.mean_curvature(lat) * ellps.mean_radius_of_curvature(lat);
+        assert!((h - 1.).abs() < 1e-15);
+
         // The curvatures at the Equator
         assert!((ellps.meridian_radius_of_curvature(0.0) - 6_335_439.327_1).abs() < 1.0e-4);
         assert!(
@@ -251,6 +342,35 @@ mod tests {
             assert!((n - prime_vertical_radii_of_curvature[i]).abs() < 1e-9);
             assert!((m - meridian_radii_of_curvature[i]).abs() < 1e-9);
         }
+
+        // Euler's formula reduces to M and N along the principal directions
+        let lat = 55_f64.to_radians();
+        let m = ellps.meridian_radius_of_curvature(lat);
+        let n = ellps.prime_vertical_radius_of_curvature(lat);
+        assert!((ellps.azimuthal_radius_of_curvature(lat, 0.) - m).abs() < 1e-8);
+        assert!((ellps.azimuthal_radius_of_curvature(lat, FRAC_PI_2) - n).abs() < 1e-8);
+        let r = ellps.azimuthal_radius_of_curvature(lat, 30_f64.to_radians());
+        assert!(m < r && r < n);
+        let r = ellps.gaussian_radius_of_curvature(lat);
+        assert!(m < r && r < n);
+        let r = ellps.mean_radius_of_curvature(lat);
+        assert!(m < r && r < n);
+        Ok(())
+    }
+
+    #[test]
+    fn mean_radii() -> Result<(), Error> {
+        // Reference values from Moritz (2000)
+        let ellps = Ellipsoid::named("GRS80")?;
+        assert!((ellps.mean_radius() - 6_371_008.771_4).abs() < 1e-3);
+        assert!((ellps.authalic_radius() - 6_371_007.181_0).abs() < 1e-3);
+        assert!((ellps.volumetric_radius() - 6_371_000.790_0).abs() < 1e-3);
+
+        // All mean radii of a sphere equal its radius
+        let ellps = Ellipsoid::named("sphere")?;
+        assert_eq!(ellps.authalic_radius(), 6_370_997.);
+        assert!((ellps.mean_radius() - 6_370_997.).abs() < 1e-9);
+        assert!((ellps.volumetric_radius() - 6_370_997.).abs() < 1e-9);
         Ok(())
     }
 }
@@ -0,0 +1,172 @@
+use crate::coordinate::tuple::CoordinateTuple;
+use std::f64::consts::FRAC_PI_2;
+use std::f64::consts::PI;
+
+use super::*;
+
+/// Rhumb lines (loxodromes): Curves crossing all meridians at the same angle.
+///
+/// A rhumb line is a straight line in the Mercator projection, hence the
+/// traditional importance for navigation. The northward component of a
+/// rhumb line is handled using the rectifying latitude, 𝜇, (so the meridian
+/// distance is *A·Δ𝜇*, with *A* the
+/// [rectifying radius](crate::ellps::Meridians::rectifying_radius)), while
+/// the eastward component follows from the isometric latitude, 𝜓, since
+/// *Δ𝜆 = tan(α)·Δ𝜓*.
+///
+/// The ratio *Δ𝜓 / Δ𝜇* is replaced by its limiting value when the latitudes
+/// coincide, so east-west lines are handled gracefully.
+/// See [Karney (2024)](https://geographiclib.sourceforge.io/C++/doc/rhumb.html)
+/// for a more elaborate treatment.
+pub trait Rhumblines: EllipsoidBase {
+    /// The direct rhumb line problem: Starting at `from`, travel `distance`
+    /// meters along the rhumb line with azimuth `azimuth` (radians).
+    /// Returns the longitude and latitude of the destination, the azimuth
+    /// of the rhumb line (which is constant), and 0 (for symmetry with
+    /// [`geodesic_fwd`](crate::ellps::Geodesics::geodesic_fwd)).
+    ///
+    /// Rhumb lines passing a pole are undefined beyond the pole: In that
+    /// case, the longitude and latitude of the destination are NaN.
+    #[must_use]
+    fn rhumb_fwd<C: CoordinateTuple>(&self, from: &C, azimuth: f64, distance: f64) -> Coor4D {
+        let (lon1, lat1) = from.xy();
+        let coefficients = self.coefficients_for_rectifying_latitude_computations();
+        let rectifying_radius = self.rectifying_radius();
+
+        let (azisin, azicos) = azimuth.sin_cos();
+        let mu1 = self.latitude_geographic_to_rectifying(lat1, &coefficients);
+        let mu2 = mu1 + distance * azicos / rectifying_radius;
+        if mu2.abs() > FRAC_PI_2 {
+            return Coor4D::raw(f64::NAN, f64::NAN, azimuth, 0.);
+        }
+        let lat2 = self.latitude_rectifying_to_geographic(mu2, &coefficients);
+
+        let q = self.rhumb_ratio(lat1, lat2, mu1, mu2);
+        let lon2 = normalize_longitude(lon1 + q * distance * azisin / rectifying_radius);
+        Coor4D::raw(lon2, lat2, azimuth, 0.)
+    }
+
+    /// The inverse rhumb line problem: Find the rhumb line from `from`
+    /// to `to`. Returns the azimuth of the rhumb line (twice, for symmetry
+    /// with [`geodesic_inv`](crate::ellps::Geodesics::geodesic_inv)), the
+    /// distance in meters, and 0.
+    ///
+    /// The rhumb line always follows the shorter way around, i.e. with
+    /// a longitude difference no larger than 180 degrees.
+    #[must_use]
+    fn rhumb_inv<C: CoordinateTuple>(&self, from: &C, to: &C) -> Coor4D {
+        let (lon1, lat1) = from.xy();
+        let (lon2, lat2) = to.xy();
+        let coefficients = self.coefficients_for_rectifying_latitude_computations();
+
+        let dlon = normalize_longitude(lon2 - lon1);
+        let mu1 = self.latitude_geographic_to_rectifying(lat1, &coefficients);
+        let mu2 = self.latitude_geographic_to_rectifying(lat2, &coefficients);
+        let dmu = mu2 - mu1;
+
+        // Δ𝜓 = q·Δ𝜇
+        let q = self.rhumb_ratio(lat1, lat2, mu1, mu2);
+        let azimuth = dlon.atan2(q * dmu);
+        let distance = self.rectifying_radius() * dmu.hypot(dlon / q);
+        Coor4D::raw(azimuth, azimuth, distance, 0.)
+    }
+
+    /// The length of the rhumb line between two points. Assumes the first
+    /// coordinate is longitude, second is latitude.
+    #[must_use]
+    fn rhumb_distance<C: CoordinateTuple>(&self, from: &C, to: &C) -> f64 {
+        self.rhumb_inv(from, to)[2]
+    }
+
+    /// The ratio *Δ𝜓 / Δ𝜇* between the differences in isometric and
+    /// rectifying latitude of two points, or its limiting value,
+    /// *A / (N·cos 𝜙)*, when the points are at (almost) the same latitude
+    #[must_use]
+    fn rhumb_ratio(&self, lat1: f64, lat2: f64, mu1: f64, mu2: f64) -> f64 {
+        let dmu = mu2 - mu1;
+        if dmu.abs() > 1e-9 {
+            let psi1 = self.latitude_geographic_to_isometric(lat1);
+            let psi2 = self.latitude_geographic_to_isometric(lat2);
+            return (psi2 - psi1) / dmu;
+        }
+        let lat = (lat1 + lat2) / 2.;
+        self.rectifying_radius() / (self.prime_vertical_radius_of_curvature(lat) * lat.cos())
+    }
+}
+
+// Reduce a longitude (difference) to the interval [-π, π]
+fn normalize_longitude(lon: f64) -> f64 {
+    if lon.abs() <= PI {
+        return lon;
+    }
+    (lon + PI).rem_euclid(2. * PI) - PI
+}
+
+// ----- Tests ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coord::Coor2D;
+
+    #[test]
+    fn rhumblines() -> Result<(), Error> {
+        let ellps = Ellipsoid::named("WGS84")?;
+
+        // JFK--LHR (expected values from direct numerical integration of
+        // the meridian arc, and the closed form isometric latitude)
+        let jfk = Coor2D::geo(
+            angular::dms_to_dd(40, 38, 23.),
+            -angular::dms_to_dd(73, 46, 44.),
+        );
+        let lhr = Coor2D::geo(angular::dms_to_dd(51, 28, 38.), -(27. + 35. / 60.) / 60.);
+        let d = ellps.rhumb_inv(&jfk, &lhr);
+        assert!((d[0].to_degrees() - 77.957_483_280_015).abs() < 1e-9);
+        assert!((d[2] - 5_773_717.129_699).abs() < 1e-5);
+
+        // And back again
+        let b = ellps.rhumb_fwd(&jfk, d[0], d[2]);
+        assert!((b[0] - lhr[0]).to_degrees().abs() < 1e-9);
+        assert!((b[1] - lhr[1]).to_degrees().abs() < 1e-9);
+
+        // Along a meridian, the rhumb line is a geodesic
+        let p1 = Coor2D::geo(55., 12.);
+        let p2 = Coor2D::geo(-35., 12.);
+        let d = ellps.rhumb_inv(&p1, &p2);
+        assert!((d[0].to_degrees() - 180.).abs() < 1e-12);
+        assert!((d[2] - ellps.distance(&p1, &p2)).abs() < 1e-6);
+
+        // ...and so it is along the equator
+        let p1 = Coor2D::geo(0., 12.);
+        let p2 = Coor2D::geo(0., 2.);
+        let d = ellps.rhumb_inv(&p1, &p2);
+        assert!((d[0].to_degrees() + 90.).abs() < 1e-12);
+        assert!((d[2] - ellps.semimajor_axis() * 10_f64.to_radians()).abs() < 1e-6);
+
+        // Along other parallels, we get the length of the parallel arc,
+        // even for lines that are *almost* parallels
+        let p1 = Coor2D::geo(55., 12.);
+        for dlat in [0., 1e-12, 1e-10, 1e-8] {
+            let p2 = Coor2D::geo(55. + dlat, 2.);
+            let d = ellps.rhumb_inv(&p1, &p2);
+            let r = ellps.prime_vertical_radius_of_curvature(p1[1]) * p1[1].cos();
+            assert!((d[2] - r * 10_f64.to_radians()).abs() < 1e-3);
+            let b = ellps.rhumb_fwd(&p1, d[0], d[2]);
+            assert!((b[0] - p2[0]).to_degrees().abs() < 1e-11);
+            assert!((b[1] - p2[1]).to_degrees().abs() < 1e-11);
+        }
+
+        // Across the date line, we go the short way around
+        let p1 = Coor2D::geo(10., 179.);
+        let p2 = Coor2D::geo(11., -179.);
+        let d = ellps.rhumb_inv(&p1, &p2);
+        assert!(d[0] > 0. && d[0] < FRAC_PI_2);
+        let b = ellps.rhumb_fwd(&p1, d[0], d[2]);
+        assert!((b[0] - p2[0]).to_degrees().abs() < 1e-9);
+
+        // There is no way beyond the pole
+        let b = ellps.rhumb_fwd(&p1, 0., 1e7);
+        assert!(b[0].is_nan() && b[1].is_nan());
+        Ok(())
+    }
+}
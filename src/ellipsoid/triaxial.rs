@@ -1,6 +1,12 @@
 use crate::prelude::*;
 
-/// A triaxial ellipsoid. Currently mostly a placeholder for future use.
+/// A triaxial ellipsoid, with semiaxes *a ≥ ay ≥ b* along the X, Y and Z axes
+/// respectively.
+///
+/// Geographic coordinates are *geodetic*: The latitude and longitude give the
+/// direction of the surface normal, and the height is measured along the
+/// normal. The [`GeoCart`] and [`Geodesics`] traits hand over to the triaxial
+/// algorithms below, whenever the semimajor and semimedian axes differ.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TriaxialEllipsoid {
     a: f64,
@@ -27,6 +33,16 @@ impl EllipsoidBase for TriaxialEllipsoid {
     fn flattening(&self) -> f64 {
         self.f
     }
+
+    /// The authalic radius, *R₂*: The radius of the sphere having the same
+    /// surface area as the ellipsoid. The area is *4π·a·ay·b·R_G(a⁻², ay⁻², b⁻²)*,
+    /// where *R_G* is Carlson's symmetric elliptic integral of the second kind
+    fn authalic_radius(&self) -> f64 {
+        let a = self.semimajor_axis();
+        let ay = self.semimedian_axis();
+        let b = self.semiminor_axis();
+        (a * ay * b * carlson_rg(a.powi(-2), ay.powi(-2), b.powi(-2))).sqrt()
+    }
 }
 
 /// Constructors for `TriaxialEllipsoid`
@@ -44,12 +60,8 @@ impl TriaxialEllipsoid {
     /// Predefined ellipsoid; built-in, defined in asset collections, or given as a string formatted
     /// (a, rf) or (ax, ay, rx) tuple, e.g. "6378137, 298.25" or "6378137, 6345678, 300"
     pub fn named(name: &str) -> Result<TriaxialEllipsoid, Error> {
-        // Is it one of the few builtins?
-        if let Some(index) = super::constants::ELLIPSOID_LIST
-            .iter()
-            .position(|&ellps| ellps.0 == name)
-        {
-            let e = super::constants::ELLIPSOID_LIST[index];
+        // Is it one of the builtins?
+        if let Some(e) = super::constants::builtin(name) {
             let ax: f64 = e.1.parse().unwrap();
             let ay: f64 = e.2.parse().unwrap();
             let rf: f64 = e.3.parse().unwrap();
@@ -86,10 +98,297 @@ impl TriaxialEllipsoid {
     }
 }
 
+// ----- Carlson's symmetric elliptic integrals --------------------------------------
+
+// The elliptic integral of the first kind, R_F(x, y, z), by duplication,
+// following Carlson (1995), https://doi.org/10.1007/BF02198293
+pub(crate) fn carlson_rf(x: f64, y: f64, z: f64) -> f64 {
+    let (mut x, mut y, mut z) = (x, y, z);
+    loop {
+        let lambda = x.sqrt() * y.sqrt() + y.sqrt() * z.sqrt() + z.sqrt() * x.sqrt();
+        x = (x + lambda) / 4.;
+        y = (y + lambda) / 4.;
+        z = (z + lambda) / 4.;
+        let mu = (x + y + z) / 3.;
+        let (dx, dy, dz) = (1. - x / mu, 1. - y / mu, 1. - z / mu);
+        if dx.abs().max(dy.abs()).max(dz.abs()) < 0.0025 {
+            let e2 = dx * dy - dz * dz;
+            let e3 = dx * dy * dz;
+            return (1. + (e2 / 24. - 0.1 - 3. * e3 / 44.) * e2 + e3 / 14.) / mu.sqrt();
+        }
+    }
+}
+
+// The elliptic integral of the second kind, R_D(x, y, z), by duplication,
+// following Carlson (1995)
+pub(crate) fn carlson_rd(x: f64, y: f64, z: f64) -> f64 {
+    let (mut x, mut y, mut z) = (x, y, z);
+    let mut sum = 0.;
+    let mut fac = 1.;
+    loop {
+        let lambda = x.sqrt() * (y.sqrt() + z.sqrt()) + y.sqrt() * z.sqrt();
+        sum += fac / (z.sqrt() * (z + lambda));
+        fac /= 4.;
+        x = (x + lambda) / 4.;
+        y = (y + lambda) / 4.;
+        z = (z + lambda) / 4.;
+        let mu = (x + y + 3. * z) / 5.;
+        let (dx, dy, dz) = (1. - x / mu, 1. - y / mu, 1. - z / mu);
+        if dx.abs().max(dy.abs()).max(dz.abs()) < 0.0015 {
+            let ea = dx * dy;
+            let eb = dz * dz;
+            let ec = ea - eb;
+            let ed = ea - 6. * eb;
+            let ee = ed + ec + ec;
+            let (c1, c2, c3, c4) = (3. / 14., 1. / 6., 9. / 22., 3. / 26.);
+            let (c5, c6) = (c3 / 4., 1.5 * c4);
+            let series = 1.
+                + ed * (-c1 + c5 * ed - c6 * dz * ee)
+                + dz * (c2 * ee + dz * (-c3 * ec + dz * c4 * ea));
+            return 3. * sum + fac * series / (mu * mu.sqrt());
+        }
+    }
+}
+
+// The completely symmetric elliptic integral of the second kind, R_G(x, y, z),
+// from R_F and R_D, by DLMF 19.21.10. Valid for any z > 0, but only free of
+// cancellation for z between x and y. With the largest argument last, as in
+// the call above, the subtracted term is of the order of the squared
+// flattening, which is harmless for real world ellipsoids
+fn carlson_rg(x: f64, y: f64, z: f64) -> f64 {
+    let rf = carlson_rf(x, y, z);
+    let rd = carlson_rd(x, y, z);
+    (z * rf - (x - z) * (y - z) * rd / 3. + (x * y / z).sqrt()) / 2.
+}
+
+// ----- Geodetic <-> cartesian ---------------------------------------------------
+
+// The squared semiaxes, (a², ay², b²)
+fn squared_axes<E: EllipsoidBase + ?Sized>(ellps: &E) -> Coor3D {
+    let a = ellps.semimajor_axis();
+    let ay = ellps.semimedian_axis();
+    let b = ellps.semiminor_axis();
+    Coor3D::raw(a * a, ay * ay, b * b)
+}
+
+// The unit vectors pointing along the normal, east, and north, at (lon, lat)
+fn local_frame(lon: f64, lat: f64) -> (Coor3D, Coor3D, Coor3D) {
+    let (sinlam, coslam) = lon.sin_cos();
+    let (sinphi, cosphi) = lat.sin_cos();
+    let normal = Coor3D::raw(cosphi * coslam, cosphi * sinlam, sinphi);
+    let east = Coor3D::raw(-sinlam, coslam, 0.);
+    let north = Coor3D::raw(-sinphi * coslam, -sinphi * sinlam, cosphi);
+    (normal, east, north)
+}
+
+// Longitude, latitude, and azimuth of a direction `v`, at the surface point `p`
+fn lon_lat_azimuth(axes2: Coor3D, p: Coor3D, v: Coor3D) -> (f64, f64, f64) {
+    let n = p / axes2;
+    let lon = n[1].atan2(n[0]);
+    let lat = n[2].atan2(n[0].hypot(n[1]));
+    let (_, east, north) = local_frame(lon, lat);
+    (lon, lat, v.dot(east).atan2(v.dot(north)))
+}
+
+/// Geodetic to cartesian conversion on a triaxial ellipsoid: The surface
+/// point having the normal given by (lon, lat) is *a²n / sqrt(n·a²n)*
+pub(super) fn cartesian<E, C>(ellps: &E, geographic: &C) -> Coor4D
+where
+    E: EllipsoidBase + ?Sized,
+    C: CoordinateTuple,
+{
+    let (lon, lat, h, t) = geographic.xyzt();
+    let axes2 = squared_axes(ellps);
+    let (n, _, _) = local_frame(lon, lat);
+    let p = (axes2 * n).scale(1. / (axes2 * n).dot(n).sqrt());
+    let r = p + n.scale(h);
+    Coor4D::raw(r[0], r[1], r[2], t)
+}
+
+/// Cartesian to geodetic conversion on a triaxial ellipsoid.
+///
+/// The foot point of `r` is *p = a²r / (a² + τ)* (elementwise), where τ is
+/// the root of *F(τ) = Σ (a·r / (a² + τ))² - 1*. F is convex and decreasing
+/// for τ > -b², so starting from a τ where F(τ) ≥ 0, Newton's method
+/// converges monotonically.
+/// Points very deep inside the ellipsoid (i.e. on the "wrong" side of the
+/// evolute) are not supported.
+pub(super) fn geographic<E, C>(ellps: &E, cartesian: &C) -> Coor4D
+where
+    E: EllipsoidBase + ?Sized,
+    C: CoordinateTuple,
+{
+    let (x, y, z, t) = cartesian.xyzt();
+    let r = Coor3D::raw(x, y, z);
+    let axes2 = squared_axes(ellps);
+    let axes = Coor3D::raw(axes2[0].sqrt(), axes2[1].sqrt(), axes2[2].sqrt());
+
+    // Each term of F equals 1 at τ = a(|r| - a), so F ≥ 0 at their maximum
+    let mut tau = (0..3)
+        .map(|i| axes[i] * (r[i].abs() - axes[i]))
+        .fold(f64::MIN, f64::max);
+    for _ in 0..100 {
+        let denom = axes2 + Coor3D::new(tau);
+        let q = axes * r / denom;
+        let f = q.dot(q) - 1.;
+        let df = -2. * q.dot(q / denom);
+        let step = f / df;
+        // Monotonic convergence: Stop when rounding makes us turn around (or NaN)
+        if step >= 0. || step.is_nan() {
+            break;
+        }
+        tau -= step;
+    }
+
+    let p = axes2 * r / (axes2 + Coor3D::new(tau));
+    let (lon, lat, _) = lon_lat_azimuth(axes2, p, Coor3D::origin());
+    let (n, _, _) = local_frame(lon, lat);
+    let h = (r - p).dot(n);
+    Coor4D::raw(lon, lat, h, t)
+}
+
+// ----- Geodesics -----------------------------------------------------------------
+
+// The geodesic equations for a unit speed curve, r(s), on the ellipsoid
+// Σ r²/a² = 1 are r'' = -κg, where g = r/a² is the (unnormalized) surface
+// normal. Differentiating g·r' = 0 gives κ = (r'·r'/a²) / (g·g)
+fn acceleration(axes2: Coor3D, r: Coor3D, v: Coor3D) -> Coor3D {
+    let g = r / axes2;
+    g.scale(-v.dot(v / axes2) / g.dot(g))
+}
+
+// The number of integration steps for a geodesic of length `distance`:
+// Step lengths of at most 1/1000 of the semiminor axis
+fn integration_steps<E: EllipsoidBase + ?Sized>(ellps: &E, distance: f64) -> usize {
+    let steps = (1000. * distance.abs() / ellps.semiminor_axis()).ceil();
+    (steps as usize).max(16)
+}
+
+// Integrate the geodesic equations from the surface point `r`, in the unit
+// direction `v`, over `distance` meters, using `steps` classical Runge-Kutta
+// steps. After each step, the point is pulled back onto the surface, and the
+// direction is made a unit tangent vector.
+fn integrate(
+    axes2: Coor3D,
+    mut r: Coor3D,
+    mut v: Coor3D,
+    distance: f64,
+    steps: usize,
+) -> (Coor3D, Coor3D) {
+    let h = distance / steps as f64;
+    for _ in 0..steps {
+        let k1r = v;
+        let k1v = acceleration(axes2, r, v);
+        let k2r = v + k1v.scale(h / 2.);
+        let k2v = acceleration(axes2, r + k1r.scale(h / 2.), k2r);
+        let k3r = v + k2v.scale(h / 2.);
+        let k3v = acceleration(axes2, r + k2r.scale(h / 2.), k3r);
+        let k4r = v + k3v.scale(h);
+        let k4v = acceleration(axes2, r + k3r.scale(h), k4r);
+        r = r + (k1r + k2r.scale(2.) + k3r.scale(2.) + k4r).scale(h / 6.);
+        v = v + (k1v + k2v.scale(2.) + k3v.scale(2.) + k4v).scale(h / 6.);
+
+        r = r.scale(1. / r.dot(r / axes2).sqrt());
+        let g = r / axes2;
+        v = v - g.scale(v.dot(g) / g.dot(g));
+        v = v.scale(1. / v.dot(v).sqrt());
+    }
+    (r, v)
+}
+
+// The surface point and the unit tangent vector at (lon, lat), azimuth
+fn start(axes2: Coor3D, lon: f64, lat: f64, azimuth: f64) -> (Coor3D, Coor3D) {
+    let (n, east, north) = local_frame(lon, lat);
+    let p = (axes2 * n).scale(1. / (axes2 * n).dot(n).sqrt());
+    let (sinaz, cosaz) = azimuth.sin_cos();
+    (p, north.scale(cosaz) + east.scale(sinaz))
+}
+
+/// The direct geodesic problem on a triaxial ellipsoid. Returns the
+/// longitude and latitude of the destination, the forward azimuth at the
+/// destination, and 0.
+///
+/// Jacobi (1839) showed that the geodesic problem on the triaxial ellipsoid
+/// separates in ellipsoidal coordinates, but the resulting integrals are
+/// unwieldy. Here, we integrate the geodesic equations numerically instead,
+/// in cartesian space. Along a geodesic, the Jacobi-Joachimsthal integral,
+/// *(g·g)(v·v/a²)*, is conserved, which makes a handy check.
+pub(super) fn geodesic_fwd<E, C>(ellps: &E, from: &C, azimuth: f64, distance: f64) -> Coor4D
+where
+    E: EllipsoidBase + ?Sized,
+    C: CoordinateTuple,
+{
+    let axes2 = squared_axes(ellps);
+    let (lon1, lat1) = from.xy();
+    let (r, v) = start(axes2, lon1, lat1, azimuth);
+    let (r, v) = integrate(axes2, r, v, distance, integration_steps(ellps, distance));
+    let (lon2, lat2, azi2) = lon_lat_azimuth(axes2, r, v);
+    Coor4D::raw(lon2, lat2, azi2, 0.)
+}
+
+/// The inverse geodesic problem on a triaxial ellipsoid: Returns the forward
+/// azimuths at `from` and `to`, the distance, and the number of iterations.
+///
+/// Solved by shooting: Starting from the solution on the biaxial ellipsoid
+/// with the mean equatorial radius, Newton iteration adjusts the initial
+/// azimuth and the length, until the [direct](geodesic_fwd) solution hits `to`.
+/// For long lines on strongly triaxial ellipsoids, the geodesic found is not
+/// necessarily the shortest one. In case of non-convergence, NaNs are returned.
+pub(super) fn geodesic_inv<E, C>(ellps: &E, from: &C, to: &C) -> Coor4D
+where
+    E: EllipsoidBase + ?Sized,
+    C: CoordinateTuple,
+{
+    let axes2 = squared_axes(ellps);
+    let (lon1, lat1) = from.xy();
+    let (lon2, lat2) = to.xy();
+
+    // Initial guess from the biaxial approximation
+    let a = (ellps.semimajor_axis() + ellps.semimedian_axis()) / 2.;
+    let biaxial = Ellipsoid::new(a, 1. - ellps.semiminor_axis() / a);
+    let guess = biaxial.geodesic_inv(from, to);
+    let (mut azi1, mut distance) = (guess[0], guess[2]);
+    if distance == 0. {
+        return Coor4D::raw(azi1, guess[1], 0., 0.);
+    }
+
+    let (target, _) = start(axes2, lon2, lat2, 0.);
+    let (_, east, north) = local_frame(lon2, lat2);
+    let tolerance = 1e-12 * ellps.semimajor_axis();
+    let steps = integration_steps(ellps, 1.5 * distance);
+    let delta = 1e-7;
+
+    for i in 1..=50 {
+        let (r, v) = start(axes2, lon1, lat1, azi1);
+        let (p, w) = integrate(axes2, r, v, distance, steps);
+        let miss = p - target;
+        if miss.dot(miss).sqrt() < tolerance {
+            let (_, _, azi2) = lon_lat_azimuth(axes2, p, w);
+            return Coor4D::raw(azi1, azi2, distance, i as f64);
+        }
+
+        // The partial derivatives of the end point wrt. the initial
+        // azimuth (numerically), and wrt. the distance (i.e. `w`)
+        let (r, v) = start(axes2, lon1, lat1, azi1 + delta);
+        let (q, _) = integrate(axes2, r, v, distance, steps);
+        let dazi = (q - p).scale(1. / delta);
+
+        // Solve the 2x2 system in the tangent plane at the target
+        let (a11, a12) = (dazi.dot(east), w.dot(east));
+        let (a21, a22) = (dazi.dot(north), w.dot(north));
+        let (b1, b2) = (-miss.dot(east), -miss.dot(north));
+        let det = a11 * a22 - a12 * a21;
+        azi1 += (b1 * a22 - b2 * a12) / det;
+        distance += (a11 * b2 - a21 * b1) / det;
+    }
+    Coor4D::nan()
+}
+
 // ----- Tests ---------------------------------------------------------------------
 
 #[cfg(test)]
 mod tests {
+    use crate::ellps::Ellipsoid;
     use crate::ellps::EllipsoidBase;
     use crate::ellps::Meridians;
     use crate::ellps::TriaxialEllipsoid;
@@ -114,6 +413,15 @@ mod tests {
         assert_eq!(ellps.semimedian_axis(), 6345678.0);
         assert_eq!(ellps.flattening(), 1. / 298.25);
 
+        // Authalic radius, checked against an independent evaluation of R_G (mpmath),
+        // and against the biaxial and spherical cases
+        assert!((ellps.authalic_radius() - 6_360_186.072_662).abs() < 1e-6);
+        let biaxial = Ellipsoid::named("GRS80")?;
+        let triaxial = TriaxialEllipsoid::named("GRS80")?;
+        assert!((biaxial.authalic_radius() - triaxial.authalic_radius()).abs() < 1e-8);
+        let sphere = TriaxialEllipsoid::new(6e6, 6e6, 0.);
+        assert!((sphere.authalic_radius() - 6e6).abs() < 1e-8);
+
         let ellps = TriaxialEllipsoid::named("GRS80")?;
         assert_eq!(ellps.semimajor_axis(), 6378137.0);
         assert_eq!(ellps.flattening(), 1. / 298.257_222_100_882_7);
@@ -122,4 +430,102 @@ mod tests {
         assert!((4.0 * ellps.meridian_quadrant() - 40_007_862.916_921_8).abs() < 1e-7);
         Ok(())
     }
+
+    #[test]
+    fn triaxial_geocart() -> Result<(), Error> {
+        use crate::prelude::*;
+
+        // In the biaxial case, the triaxial algorithms agree with Bowring's
+        let grs80 = Ellipsoid::named("GRS80")?;
+        for (lat, lon, h) in [(55., 12., 100.), (-89.99, 33., -1e3), (45., -170., 1e5)] {
+            let geo = Coor4D::geo(lat, lon, h, 0.);
+            let cart = grs80.cartesian(&geo);
+            assert!(cart.hypot3(&super::cartesian(&grs80, &geo)) < 1e-8);
+            let back = super::geographic(&grs80, &cart);
+            assert!((back[0] - geo[0]).abs() < 1e-14);
+            assert!((back[1] - geo[1]).abs() < 1e-14);
+            assert!((back[2] - geo[2]).abs() < 1e-8);
+        }
+
+        // A strongly triaxial body, roughly the shape of the Martian moon Phobos
+        let phobos = TriaxialEllipsoid::new(13_000., 11_400., 1. - 9_100. / 13_000.);
+        assert!(phobos.is_triaxial());
+        for lat in [-90., -60., -1., 0., 33., 89.] {
+            for lon in [-180., -135., -12., 0., 55., 90., 179.] {
+                for h in [-1000., 0., 1000.] {
+                    let geo = Coor4D::geo(lat, lon, h, 0.);
+                    let cart = phobos.cartesian(&geo);
+                    let back = phobos.geographic(&cart);
+                    assert!((back[2] - h).abs() < 1e-9);
+                    assert!((back[1] - geo[1]).abs() < 1e-14);
+                    // Longitude is undefined at the poles
+                    if lat.abs() < 90. {
+                        assert!(angular::normalize_symmetric(back[0] - geo[0]).abs() < 1e-14);
+                    }
+                }
+            }
+        }
+
+        // Points on the axes
+        let cart = phobos.cartesian(&Coor4D::geo(0., 90., 0., 0.));
+        assert!((cart[1] - 11_400.).abs() < 1e-9);
+        let cart = phobos.cartesian(&Coor4D::geo(90., 0., 10., 0.));
+        assert!((cart[2] - 9_110.).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn triaxial_geodesics() -> Result<(), Error> {
+        use crate::prelude::*;
+
+        // In the biaxial case, the numerical integration agrees with Karney
+        let grs80 = Ellipsoid::named("GRS80")?;
+        let p1 = Coor2D::geo(55., 12.);
+        for (lat, lon) in [(49., 2.), (-30., 150.), (10., -100.)] {
+            let p2 = Coor2D::geo(lat, lon);
+            let karney = grs80.geodesic_inv(&p1, &p2);
+            let fwd = super::geodesic_fwd(&grs80, &p1, karney[0], karney[2]);
+            assert!((fwd[0] - p2[0]).to_degrees().abs() < 1e-11);
+            assert!((fwd[1] - p2[1]).to_degrees().abs() < 1e-11);
+            assert!((fwd[2] - karney[1]).to_degrees().abs() < 1e-11);
+            let inv = super::geodesic_inv(&grs80, &p1, &p2);
+            assert!((inv[2] - karney[2]).abs() < 1e-6);
+        }
+
+        let phobos = TriaxialEllipsoid::new(13_000., 11_400., 1. - 9_100. / 13_000.);
+        let axes2 = super::squared_axes(&phobos);
+
+        // The principal sections are geodesics: Follow the X-Z ellipse to the
+        // pole, over the quadrant of a biaxial ellipsoid with the same shape
+        let quadrant = Ellipsoid::new(13_000., 1. - 9_100. / 13_000.).meridian_quadrant();
+        let origin = Coor2D::geo(0., 0.);
+        let pole = phobos.geodesic_fwd(&origin, 0., quadrant);
+        assert!((pole[1].to_degrees() - 90.).abs() < 1e-8);
+
+        // Jacobi-Joachimsthal: (g·g)(v·v/a²) is constant along geodesics
+        let joachimsthal = |r: Coor3D, v: Coor3D| {
+            let g = r / axes2;
+            g.dot(g) * v.dot(v / axes2)
+        };
+        let (r, v) = super::start(axes2, 0.3, 0.2, 1.);
+        let constant = joachimsthal(r, v);
+        for distance in [1e3, 1e4, 5e4, 1e5] {
+            let steps = super::integration_steps(&phobos, distance);
+            let (r, v) = super::integrate(axes2, r, v, distance, steps);
+            assert!((joachimsthal(r, v) / constant - 1.).abs() < 1e-12);
+        }
+
+        // Direct and inverse problems agree
+        let p1 = Coor2D::geo(12., 34.);
+        for (lat, lon) in [(15., 40.), (-40., 100.), (60., -45.), (0., -170.)] {
+            let p2 = Coor2D::geo(lat, lon);
+            let inv = phobos.geodesic_inv(&p1, &p2);
+            assert!(inv[3] > 0. && inv[3] < 50.);
+            let fwd = phobos.geodesic_fwd(&p1, inv[0], inv[2]);
+            assert!((fwd[0] - p2[0]).to_degrees().abs() < 1e-9);
+            assert!((fwd[1] - p2[1]).to_degrees().abs() < 1e-9);
+            assert!((fwd[2] - inv[1]).to_degrees().abs() < 1e-9);
+        }
+        Ok(())
+    }
 }
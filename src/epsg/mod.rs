@@ -0,0 +1,155 @@
+//! A minimal, embedded registry of projected CRS, keyed by EPSG code.
+//!
+//! The registry is embedded from `epsg.csv` at compile time, but parsed at
+//! runtime, on each lookup, so malformed entries are caught by the test suite
+//! rather than the compiler. It maps identifiers of the form `epsg:25832`
+//! to the corresponding builtin operator definition (here:
+//! `utm zone=32 ellps=GRS80`). Only the *conversion* part of each CRS
+//! is represented: There is no datum database ambition here, so
+//! `epsg:25832` and `epsg:32632` differ only by their ellipsoids.
+//!
+//! An `epsg:NNNN` identifier denotes the CRS, not the projection, so in the
+//! forward direction it converts *from* the projected coordinates *to*
+//! geographical coordinates, ready for further processing in a pipeline:
+//! `epsg:27700 | geo:out` reads British National Grid coordinates and writes
+//! latitude and longitude in degrees, while `geo:in | epsg:27700 inv` goes
+//! the other way.
+//!
+//! The registry is consulted by [`Op::op()`](crate::op::Op::op) *after* the
+//! resources provided by the context, so a user defined `epsg:NNNN` macro
+//! takes precedence over the builtin one.
+use crate::Error;
+
+const REGISTRY: &str = include_str!("epsg.csv");
+
+/// The definition of the projected CRS with the given EPSG `code`, if it is
+/// one of the entries in the embedded registry
+pub fn definition(code: usize) -> Option<String> {
+    entries()
+        .find(|entry| entry.0 == code)
+        .map(|entry| entry.2.to_string())
+}
+
+/// The name of the projected CRS with the given EPSG `code`, if it is
+/// one of the entries in the embedded registry
+pub fn name(code: usize) -> Option<String> {
+    entries()
+        .find(|entry| entry.0 == code)
+        .map(|entry| entry.1.to_string())
+}
+
+/// The EPSG codes of all entries in the embedded registry
+pub fn codes() -> Vec<usize> {
+    entries().map(|entry| entry.0).collect()
+}
+
+/// Look up a resource name of the form `epsg:NNNN` in the embedded registry,
+/// and return the macro definition converting from the CRS to geographical
+/// coordinates. The prefix is case insensitive, so `EPSG:NNNN` is recognized
+/// as well.
+pub(crate) fn resource(name: &str) -> Result<String, Error> {
+    let parts = name.split(':').collect::<Vec<_>>();
+    if parts.len() != 2 || parts[0].to_lowercase() != "epsg" {
+        return Err(Error::BadParam(
+            "needing epsg:code format".to_string(),
+            name.to_string(),
+        ));
+    }
+    let Ok(code) = parts[1].trim().parse::<usize>() else {
+        return Err(Error::BadParam("epsg".to_string(), name.to_string()));
+    };
+    let Some(definition) = definition(code) else {
+        return Err(Error::NotFound(
+            name.to_string(),
+            ": EPSG registry".to_string(),
+        ));
+    };
+    Ok(definition + " inv")
+}
+
+// Iterate over the (code, name, definition) triplets of the registry,
+// skipping comments, blank lines, and the header
+fn entries() -> impl Iterator<Item = (usize, &'static str, &'static str)> {
+    REGISTRY.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let mut fields = line.splitn(3, ',');
+        let code = fields.next()?.trim().parse::<usize>().ok()?;
+        let name = fields.next()?.trim();
+        let definition = fields.next()?.trim();
+        Some((code, name, definition))
+    })
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use float_eq::assert_float_eq;
+
+    #[test]
+    fn registry() -> Result<(), Error> {
+        // Every entry must be well formed, and the codes must be unique
+        let codes = codes();
+        assert!(codes.len() > 200);
+        let mut sorted = codes.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(codes.len(), sorted.len());
+
+        assert_eq!(definition(25832).unwrap(), "utm zone=32 ellps=GRS80");
+        assert_eq!(name(27700).unwrap(), "OSGB36 / British National Grid");
+        assert!(definition(4326).is_none());
+
+        assert!(matches!(resource("epsg:4326"), Err(Error::NotFound(_, _))));
+        assert!(matches!(resource("epsg:foo"), Err(Error::BadParam(_, _))));
+        assert!(matches!(resource("foo:25832"), Err(Error::BadParam(_, _))));
+        assert!(resource("EPSG:25832").is_ok());
+
+        // And every entry must be instantiable
+        let mut ctx = Minimal::new();
+        for code in codes {
+            let op = ctx.op(&format!("epsg:{code}"));
+            assert!(op.is_ok(), "Cannot instantiate epsg:{code}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn instantiation() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // ETRS89 / UTM zone 32N
+        assert_eq!(resource("epsg:25832")?, "utm zone=32 ellps=GRS80 inv");
+        let op = ctx.op("geo:in | epsg:25832 inv")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        let expected = [691875.6321396609, 6098907.825005002];
+        assert_float_eq!(data[0].0, expected, abs_all <= 1e-9);
+
+        // OSGB36 / British National Grid. Test point from the OS
+        // "Guide to coordinate systems in Great Britain", sect. C.1
+        let op = ctx.op("epsg:27700 | geo:out")?;
+        let mut data = [Coor2D::raw(651409.903, 313177.270)];
+        ctx.apply(op, Fwd, &mut data)?;
+        let lat = angular::dms_to_dd(52, 39, 27.2531);
+        let lon = angular::dms_to_dd(1, 43, 4.5177);
+        assert_float_eq!(data[0].0, [lat, lon], abs_all <= 1e-8);
+
+        // The inverse works as well
+        ctx.apply(op, Inv, &mut data)?;
+        assert_float_eq!(data[0].0, [651409.903, 313177.270], abs_all <= 1e-3);
+
+        // User defined resources take precedence over the builtin registry
+        ctx.register_resource("epsg:25832", "addone");
+        let op = ctx.op("epsg:25832")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0].x(), 56.);
+        Ok(())
+    }
+}
@@ -0,0 +1,467 @@
+//! An interpreter for test files in the style of PROJ's `gie`, the
+//! "Geospatial Integrity Investigation Environment".
+//!
+//! A test file consists of commands, each starting with a keyword at the
+//! beginning of a line, and extending to the beginning of the next command,
+//! so long operation definitions may be continued over several lines. If the
+//! file includes `<gie>` and `</gie>` markers, only the material between the
+//! markers is interpreted. Comments start with `#`. The commands supported
+//! are:
+//!
+//! - `operation`: The operation to test, in Geodesy or PROJ syntax. Resets
+//!   the direction to forward, and the tolerance to 0.5 mm
+//! - `direction forward|inverse`: The direction of the tests following
+//! - `tolerance 1 mm`: The tolerance of the tests following. The unit may
+//!   be one of `km`, `m`, `dm`, `cm`, `mm`, `um`, `nm` (default `m`)
+//! - `accept x y [z [t]]`: The input coordinate of the tests following
+//! - `expect x y [z [t]]`: Test that the output corresponding to the
+//!   accepted input matches this coordinate, within the tolerance.
+//!   `expect failure` tests that the operation, or the transformation,
+//!   fails
+//! - `roundtrip [n [tolerance [unit]]]`: Test that the accepted input
+//!   survives `n` (default 100) roundtrips through the operation
+//! - `echo text`: Pass `text` on to the report
+//! - `skip`: Ignore the remainder of the file
+//!
+//! Other PROJ `gie` commands (`ignore`, `require_grid`, etc.) are noted in
+//! the report, but otherwise ignored.
+//!
+//! As in `gie`, angular coordinates are given in degrees, in longitude,
+//! latitude order, and deviations of angular output are measured as
+//! distances on the GRS80 ellipsoid. Whether the input and output of an
+//! operation are angular is determined from its first and last steps.
+use crate::authoring::*;
+
+/// The outcome of interpreting a test file
+#[derive(Debug, Default)]
+pub struct GieReport {
+    /// The number of `expect` and `roundtrip` tests carried out
+    pub tests: usize,
+    /// Descriptions of the tests failed, prefixed by their line numbers
+    pub failures: Vec<String>,
+    /// Output from `echo` commands, and notes on unsupported commands
+    pub messages: Vec<String>,
+}
+
+impl GieReport {
+    /// The number of tests passed
+    pub fn passed(&self) -> usize {
+        self.tests - self.failures.len()
+    }
+}
+
+/// Interpret the test file `source`, running the tests against `ctx`.
+/// Failing tests are reported in the [`GieReport`], while malformed
+/// commands result in an error.
+pub fn run(ctx: &mut dyn Context, source: &str) -> Result<GieReport, Error> {
+    let mut report = GieReport::default();
+    let mut test = Test::default();
+
+    for (line, keyword, args) in commands(source) {
+        let at = |message: String| format!("line {line}: {message}");
+        match keyword {
+            "operation" => test = Test::new(ctx, &args),
+            "direction" => {
+                test.direction = match args.to_lowercase().as_str() {
+                    "forward" | "fwd" => Fwd,
+                    "inverse" | "inv" => Inv,
+                    _ => return Err(Error::Syntax(at(format!("bad direction '{args}'")))),
+                }
+            }
+            "tolerance" => test.tolerance = tolerance(&args).map_err(|e| Error::Syntax(at(e)))?,
+            "accept" => test.accept = coordinate(&args).map_err(|e| Error::Syntax(at(e)))?,
+            "expect" => {
+                report.tests += 1;
+                let outcome = if args.split_whitespace().next() == Some("failure") {
+                    test.expect_failure(ctx)
+                } else {
+                    let expected = coordinate(&args).map_err(|e| Error::Syntax(at(e)))?;
+                    test.expect(ctx, &expected)
+                };
+                if let Err(message) = outcome {
+                    report.failures.push(at(message));
+                }
+            }
+            "roundtrip" => {
+                report.tests += 1;
+                let mut args = args.split_whitespace();
+                let n = match args.next() {
+                    Some(n) => n
+                        .parse::<usize>()
+                        .map_err(|_| Error::Syntax(at(format!("bad roundtrip count '{n}'"))))?,
+                    None => 100,
+                };
+                let rest = args.collect::<Vec<_>>().join(" ");
+                let tolerance = if rest.is_empty() {
+                    test.tolerance
+                } else {
+                    tolerance(&rest).map_err(|e| Error::Syntax(at(e)))?
+                };
+                if let Err(message) = test.roundtrip(ctx, n, tolerance) {
+                    report.failures.push(at(message));
+                }
+            }
+            "echo" => report.messages.push(args),
+            "skip" => break,
+            _ => report
+                .messages
+                .push(at(format!("'{keyword}' not supported - ignored"))),
+        }
+    }
+    Ok(report)
+}
+
+// ----- P A R S I N G -----------------------------------------------------------------
+
+const KEYWORDS: [&str; 14] = [
+    "operation",
+    "direction",
+    "tolerance",
+    "accept",
+    "expect",
+    "roundtrip",
+    "echo",
+    "skip",
+    "ignore",
+    "require_grid",
+    "use_proj4_init_rules",
+    "builtins",
+    "banner",
+    "verbose",
+];
+
+// Split the source into (line number, keyword, arguments) triplets. Lines not
+// starting with a keyword continue the arguments of the preceding command
+fn commands(source: &str) -> Vec<(usize, &str, String)> {
+    let marked = source.contains("<gie");
+    let mut inside = !marked;
+    let mut commands: Vec<(usize, &str, String)> = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("<gie") {
+            inside = true;
+            continue;
+        }
+        if trimmed.starts_with("</gie") {
+            inside = false;
+            continue;
+        }
+        if !inside {
+            continue;
+        }
+
+        // Comments, and the decorative dashed lines of gie files
+        let text = match trimmed.find('#') {
+            Some(i) => trimmed[..i].trim(),
+            None => trimmed,
+        };
+        if text.is_empty() || text.chars().all(|c| c == '-') {
+            continue;
+        }
+
+        let (first, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        if let Some(keyword) = KEYWORDS.iter().find(|&&k| k == first) {
+            commands.push((index + 1, keyword, rest.trim().to_string()));
+        } else if let Some(command) = commands.last_mut() {
+            command.2 = format!("{} {text}", command.2).trim().to_string();
+        }
+    }
+    commands
+}
+
+// A coordinate of 2-4 elements. As in gie, missing elements default to 0
+fn coordinate(args: &str) -> Result<Vec<f64>, String> {
+    let elements: Vec<f64> = args
+        .split_whitespace()
+        .map(angular::parse_sexagesimal)
+        .collect();
+    if !(2..=4).contains(&elements.len()) || elements.iter().any(|e| e.is_nan()) {
+        return Err(format!("bad coordinate '{args}'"));
+    }
+    Ok(elements)
+}
+
+// A tolerance, converted to meters
+fn tolerance(args: &str) -> Result<f64, String> {
+    let mut args = args.split_whitespace();
+    let value = args
+        .next()
+        .and_then(|v| v.parse::<f64>().ok())
+        .ok_or_else(|| "bad tolerance".to_string())?;
+    let scale = match args.next().unwrap_or("m") {
+        "km" => 1e3,
+        "m" => 1.,
+        "dm" => 1e-1,
+        "cm" => 1e-2,
+        "mm" => 1e-3,
+        "um" | "µm" => 1e-6,
+        "nm" => 1e-9,
+        unit => return Err(format!("unsupported tolerance unit '{unit}'")),
+    };
+    Ok(value * scale)
+}
+
+// ----- T E S T   E X E C U T I O N ---------------------------------------------------
+
+// Operators taking geographical coordinates as input in the forward
+// direction, and giving linear coordinates as output
+const PROJECTIONS: [&str; 10] = [
+    "btmerc", "cart", "laea", "lcc", "merc", "omerc", "somerc", "tmerc", "utm", "webmerc",
+];
+
+// Operators with geographical coordinates as both input and output
+const GEOGRAPHICAL: [&str; 4] = ["gridshift", "latitude", "molodensky", "primem"];
+
+// Steps not affecting the coordinate representation
+const TRANSPARENT: [&str; 4] = ["noop", "push", "pop", "stack"];
+
+// The state of the tests of the current operation
+struct Test {
+    definition: String,
+    op: Result<OpHandle, String>,
+    direction: Direction,
+    tolerance: f64,
+    accept: Vec<f64>,
+}
+
+impl Default for Test {
+    fn default() -> Self {
+        Test {
+            definition: String::new(),
+            op: Err("no operation given".to_string()),
+            direction: Fwd,
+            tolerance: 0.5e-3,
+            accept: Vec::new(),
+        }
+    }
+}
+
+impl Test {
+    fn new(ctx: &mut dyn Context, definition: &str) -> Self {
+        let op = parse_proj(definition)
+            .and_then(|definition| ctx.op(&definition))
+            .map_err(|e| format!("'{definition}': {e}"));
+        Test {
+            definition: definition.to_string(),
+            op,
+            ..Default::default()
+        }
+    }
+
+    fn expect(&self, ctx: &dyn Context, expected: &[f64]) -> Result<(), String> {
+        let op = self.op.clone()?;
+        let (angular_in, angular_out) = angularity(ctx, op, &self.direction);
+        let mut operands = [self.input(angular_in)?];
+        let n = ctx
+            .apply(op, self.direction(false), &mut operands)
+            .map_err(|e| e.to_string())?;
+        let got = operands[0];
+        let deviation = deviation(angular_out, expected, &got);
+        if n == 1 && deviation <= self.tolerance {
+            return Ok(());
+        }
+
+        let mut got = got;
+        if angular_out {
+            got[0] = got[0].to_degrees();
+            got[1] = got[1].to_degrees();
+        }
+        let got = &got.0[..expected.len()];
+        Err(format!(
+            "{}: expected {expected:?}, got {got:?}, deviation {deviation:.3e} m (tolerance {:.3e} m)",
+            self.definition, self.tolerance
+        ))
+    }
+
+    fn expect_failure(&self, ctx: &dyn Context) -> Result<(), String> {
+        let Ok(op) = self.op.clone() else {
+            return Ok(());
+        };
+        let (angular_in, _) = angularity(ctx, op, &self.direction);
+        let mut operands = [self.input(angular_in)?];
+        match ctx.apply(op, self.direction(false), &mut operands) {
+            Ok(1) if !operands[0][0].is_nan() && !operands[0][1].is_nan() => Err(format!(
+                "{}: expected failure, got {:?}",
+                self.definition, operands[0].0
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn roundtrip(&self, ctx: &dyn Context, n: usize, tolerance: f64) -> Result<(), String> {
+        let op = self.op.clone()?;
+        let (angular_in, _) = angularity(ctx, op, &self.direction);
+        let input = self.input(angular_in)?;
+        let mut operands = [input];
+        for _ in 0..n {
+            ctx.apply(op, self.direction(false), &mut operands)
+                .and_then(|_| ctx.apply(op, self.direction(true), &mut operands))
+                .map_err(|e| e.to_string())?;
+        }
+
+        let deviation = deviation(angular_in, &self.accept, &operands[0]);
+        if deviation <= tolerance {
+            return Ok(());
+        }
+        Err(format!(
+            "{}: {n} roundtrips of {:?}, deviation {deviation:.3e} m (tolerance {tolerance:.3e} m)",
+            self.definition, self.accept
+        ))
+    }
+
+    // The accepted coordinate, with angular elements in radians
+    fn input(&self, angular: bool) -> Result<Coor4D, String> {
+        if self.accept.is_empty() {
+            return Err("no input coordinate accepted".to_string());
+        }
+        let mut input = Coor4D::origin();
+        input.0[..self.accept.len()].copy_from_slice(&self.accept);
+        if angular {
+            input[0] = input[0].to_radians();
+            input[1] = input[1].to_radians();
+        }
+        Ok(input)
+    }
+
+    // The direction of the test, or its opposite
+    fn direction(&self, opposite: bool) -> Direction {
+        match (&self.direction, opposite) {
+            (Fwd, false) | (Inv, true) => Fwd,
+            _ => Inv,
+        }
+    }
+}
+
+// Are the input and output of `op`, when applied in `direction`, angular?
+fn angularity(ctx: &dyn Context, op: OpHandle, direction: &Direction) -> (bool, bool) {
+    let Ok(steps) = ctx.steps(op) else {
+        return (false, false);
+    };
+    let omit = if *direction == Fwd {
+        "omit_fwd"
+    } else {
+        "omit_inv"
+    };
+
+    // Each step, with a flag telling whether it is applied in the forward
+    // direction, in the order of application
+    let mut params: Vec<(String, bool)> = (0..steps.len())
+        .filter_map(|i| ctx.params(op, i).ok())
+        .filter(|p| !p.boolean(omit) && !TRANSPARENT.contains(&p.name.as_str()))
+        .map(|p| (p.name.clone(), p.boolean("inv") == (*direction == Inv)))
+        .collect();
+    if *direction == Inv {
+        params.reverse();
+    }
+
+    let angular = |step: Option<&(String, bool)>, input: bool| match step {
+        Some((name, _)) if GEOGRAPHICAL.contains(&name.as_str()) => true,
+        Some((name, forward)) if PROJECTIONS.contains(&name.as_str()) => *forward == input,
+        _ => false,
+    };
+    (angular(params.first(), true), angular(params.last(), false))
+}
+
+// The deviation between the expected and the obtained coordinates, in meters.
+// Angular coordinates (expected in degrees, obtained in radians) are compared
+// by their distance on the GRS80 ellipsoid
+fn deviation(angular: bool, expected: &[f64], got: &Coor4D) -> f64 {
+    let dz = if expected.len() > 2 {
+        got[2] - expected[2]
+    } else {
+        0.
+    };
+    let horizontal = if angular {
+        let expected = Coor2D::geo(expected[1], expected[0]);
+        let got = Coor2D::raw(got[0], got[1]);
+        Ellipsoid::default().distance(&expected, &got)
+    } else {
+        (got[0] - expected[0]).hypot(got[1] - expected[1])
+    };
+    if horizontal.is_nan() || dz.is_nan() {
+        return f64::INFINITY;
+    }
+    horizontal.hypot(dz)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+Text outside of the gie markers is ignored
+<gie>
+-------------------------------------------------------------------------------
+operation +proj=utm +zone=32
+          +ellps=GRS80 # a continuation line
+-------------------------------------------------------------------------------
+tolerance 0.1 mm
+accept    12 55
+expect    691875.632139661 6098907.825005012
+roundtrip 10
+
+direction inverse
+accept    691875.632139661 6098907.825005012
+expect    12 55
+
+# Deliberately wrong
+tolerance 1 m
+accept    691875.632139661 6098907.825005012
+expect    12 55.001
+
+operation geo:in | utm zone=32
+accept    55 12
+expect    691875.632139661 6098907.825005012
+
+operation cart ellps=GRS80
+accept    12 55 100
+expect    3586525.7611 762339.5841 5201465.4383
+roundtrip 100 1 um
+
+operation no_such_operator
+expect failure
+echo That's all folks
+require_grid foo.gsb
+skip
+expect failure
+</gie>
+"#;
+
+    #[test]
+    fn gie() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let report = run(&mut ctx, SOURCE)?;
+        assert_eq!(report.tests, 8);
+        assert_eq!(report.passed(), 7);
+        assert_eq!(report.failures.len(), 1);
+        assert!(report.failures[0].starts_with("line 20: "));
+        assert!(report.failures[0].contains("deviation 1.1"));
+        assert_eq!(report.messages[0], "That's all folks");
+        assert!(report.messages[1].contains("'require_grid' not supported"));
+
+        // Malformed commands are errors
+        assert!(run(&mut ctx, "operation utm zone=32\naccept 12").is_err());
+        assert!(run(&mut ctx, "tolerance 1 furlong").is_err());
+        assert!(run(&mut ctx, "direction sideways").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn angularity() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let op = ctx.op("utm zone=32")?;
+        assert_eq!(super::angularity(&ctx, op, &Fwd), (true, false));
+        assert_eq!(super::angularity(&ctx, op, &Inv), (false, true));
+
+        let op = ctx.op("cart inv | helmert x=1 | cart")?;
+        assert_eq!(super::angularity(&ctx, op, &Fwd), (false, false));
+        assert_eq!(super::angularity(&ctx, op, &Inv), (false, false));
+
+        let op = ctx.op("push v_3 | cart | helmert x=1 | cart inv | pop v_3")?;
+        assert_eq!(super::angularity(&ctx, op, &Fwd), (true, true));
+        Ok(())
+    }
+}
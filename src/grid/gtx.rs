@@ -0,0 +1,102 @@
+//! The NOAA/NGS `.gtx` grid format.
+//!
+//! GTX is the vertical grid format of the VDatum distribution, also widely
+//! used for geoid models by PROJ (`+geoidgrids`). A GTX file is a single band
+//! grid, with a 40 byte big-endian header:
+//!
+//! ```txt
+//! lat_s, lon_w, dlat, dlon: f64 (degrees, longitudes positive east)
+//! rows, cols:               i32
+//! ```
+//!
+//! followed by `rows` rows of `cols` big-endian f32 values (meters), from
+//! south to north. Nodes without data are marked by the value -88.8888.
+use super::BaseGrid;
+use crate::Error;
+
+// The value marking nodes without data
+const NODATA: f32 = -88.8888;
+
+impl BaseGrid {
+    /// Read a GTX grid. The result is a single band grid, with the values
+    /// given in meters, as in the file. Nodes without data are set to NaN, so
+    /// points in cells with missing corner nodes are outside of the grid.
+    pub fn gtx(buf: &[u8]) -> Result<Self, Error> {
+        let Some(header) = buf.get(..40) else {
+            return Err(Error::General("Truncated GTX grid"));
+        };
+        let real = |i: usize| f64::from_be_bytes(header[8 * i..8 * i + 8].try_into().unwrap());
+        let int = |i: usize| i32::from_be_bytes(header[i..i + 4].try_into().unwrap());
+
+        let lat_s = real(0);
+        let mut lon_w = real(1);
+        let (dlat, dlon) = (real(2), real(3));
+        let (rows, cols) = (int(32), int(36));
+        if rows < 2 || cols < 2 || !(dlat > 0. && dlon > 0.) {
+            return Err(Error::General("Unsupported GTX grid header"));
+        }
+        let (rows, cols) = (rows as usize, cols as usize);
+        if lon_w >= 180. {
+            lon_w -= 360.;
+        }
+
+        let Some(values) = buf.get(40..40 + 4 * rows * cols) else {
+            return Err(Error::General("Truncated GTX grid"));
+        };
+        let mut grid = Vec::with_capacity(rows * cols);
+        // BaseGrid expects the northernmost row first
+        for row in values.chunks_exact(4 * cols).rev() {
+            for value in row.chunks_exact(4) {
+                let value = f32::from_be_bytes(value.try_into().unwrap());
+                grid.push(if value == NODATA { f32::NAN } else { value });
+            }
+        }
+
+        let header = [
+            (lat_s + (rows - 1) as f64 * dlat).to_radians(),
+            lat_s.to_radians(),
+            lon_w.to_radians(),
+            (lon_w + (cols - 1) as f64 * dlon).to_radians(),
+            dlat.to_radians(),
+            dlon.to_radians(),
+            1.,
+        ];
+        BaseGrid::plain(&header, Some(&grid), None)
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::prelude::*;
+
+    #[test]
+    fn gtx() -> Result<(), Error> {
+        // The test grid holds the same values as the Gravsoft `test.geoid`
+        let buf = include_bytes!("../../geodesy/gtx/test.gtx");
+        let grid = BaseGrid::gtx(buf)?;
+        let geoid = BaseGrid::gravsoft(include_bytes!("../../geodesy/geoid/test.geoid"))?;
+        assert_eq!(grid.bands(), 1);
+        assert_eq!(grid.info()[0].rows, 5);
+        assert_eq!(grid.info()[0].cols, 9);
+        for (lat, lon) in [(55.5, 12.5), (54., 8.), (58., 16.), (56.25, 9.75)] {
+            let at = Coor4D::geo(lat, lon, 0., 0.);
+            let (d, expected) = (grid.at(&at, 0.).unwrap(), geoid.at(&at, 0.).unwrap());
+            assert!((d[0] - expected[0]).abs() < 1e-6);
+        }
+
+        // Nodes without data: The first value is the southwest corner
+        let mut buf = buf.to_vec();
+        buf[40..44].copy_from_slice(&NODATA.to_be_bytes());
+        let grid = BaseGrid::gtx(&buf)?;
+        assert!(grid.at(&Coor4D::geo(54.5, 8.5, 0., 0.), 0.).is_none());
+        assert!(grid.at(&Coor4D::geo(55.5, 9.5, 0., 0.), 0.).is_some());
+
+        assert!(BaseGrid::gtx(&buf[..100]).is_err());
+        assert!(BaseGrid::gtx(b"54. 58.   8. 16.   1. 1.").is_err());
+        Ok(())
+    }
+}
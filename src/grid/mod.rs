@@ -1,8 +1,25 @@
 //! Grid characteristics and interpolation.
 
+mod gtx;
+mod nadcon5;
 pub mod ntv2;
+mod ostn15;
+mod par;
+mod tin;
 use crate::prelude::*;
-use std::{fmt::Debug, io::BufRead, sync::Arc};
+#[cfg(feature = "with_plain")]
+pub(crate) use nadcon5::nadcon5_units;
+#[cfg(feature = "with_plain")]
+pub(crate) use ostn15::is_ostn15;
+use std::{
+    fmt::Debug,
+    io::BufRead,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+pub use tin::Tin;
 
 pub trait Grid: Debug + Sync + Send {
     fn bands(&self) -> usize;
@@ -14,6 +31,133 @@ pub trait Grid: Debug + Sync + Send {
     /// considered contained if it is inside a margin of `margin` grid units of
     /// the grid.
     fn at(&self, at: &Coor4D, margin: f64) -> Option<Coor4D>;
+    /// The characteristics of the grid, and of any subgrids, for inspection
+    /// purposes. Empty if not provided by the implementation
+    fn info(&self) -> Vec<GridInfo> {
+        Vec::new()
+    }
+    /// The name of the (sub)grid containing `coord`, in the sense of the
+    /// `contains` method. Unnamed grids are reported as an empty string
+    fn which_subgrid_contains(&self, coord: &Coor4D, margin: f64) -> Option<String> {
+        self.contains(coord, margin).then(String::new)
+    }
+    /// True if the grid is georeferenced in linear (typically projected)
+    /// coordinates, rather than in geographical coordinates
+    fn is_projected(&self) -> bool {
+        false
+    }
+    /// The units and axis order of the grid values, as stored. Irrespective
+    /// of these, `at` returns values converted to the internal conventions,
+    /// cf. [`GridUnits`]. Unless overridden, 2-band grids (horizontal datum
+    /// shifts) are taken to be in radians, and all others in meters
+    fn units(&self) -> GridUnits {
+        GridUnits::for_bands(self.bands())
+    }
+}
+
+/// The unit of the values of a grid
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GridUnit {
+    /// E.g. geoid undulations, and corrections in projected coordinates
+    #[default]
+    Meters,
+    /// Gravsoft style 3-band grids: (north, east, up) corrections, or
+    /// deformation velocities, if used as such
+    Millimeters,
+    /// Deformation velocities
+    MillimetersPerYear,
+    /// Horizontal corrections, converted from their source units on reading
+    Radians,
+    /// Horizontal corrections, as given by most sources
+    Arcsec,
+}
+
+/// The order of the horizontal components of the values of a grid
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AxisOrder {
+    #[default]
+    EastNorth,
+    NorthEast,
+}
+
+/// The units and axis order of the values of a grid, as stored.
+///
+/// For grids in linear units, the unit applies to all bands. For grids in
+/// angular units, it applies to the horizontal bands (or to the single band
+/// of a 1-band grid), while a third band (height correction) is in meters.
+///
+/// `Grid::at` returns the values converted to the internal conventions, i.e.
+/// in east-north order, in radians for angular units, and in meters (per
+/// year, for velocities) for linear units
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GridUnits {
+    pub unit: GridUnit,
+    pub order: AxisOrder,
+}
+
+impl GridUnits {
+    pub const METERS: GridUnits = GridUnits::new(GridUnit::Meters, AxisOrder::EastNorth);
+    pub const RADIANS: GridUnits = GridUnits::new(GridUnit::Radians, AxisOrder::EastNorth);
+
+    pub const fn new(unit: GridUnit, order: AxisOrder) -> GridUnits {
+        GridUnits { unit, order }
+    }
+
+    /// The units assumed for grids not stating theirs: Radians for 2-band
+    /// grids (horizontal datum shifts), meters otherwise, in east-north order
+    pub const fn for_bands(bands: usize) -> GridUnits {
+        if bands == 2 {
+            return GridUnits::RADIANS;
+        }
+        GridUnits::METERS
+    }
+
+    /// True if the (horizontal) values are angles, rather than lengths
+    pub fn is_angular(&self) -> bool {
+        matches!(self.unit, GridUnit::Radians | GridUnit::Arcsec)
+    }
+
+    /// True if the values are velocities
+    pub fn is_rate(&self) -> bool {
+        self.unit == GridUnit::MillimetersPerYear
+    }
+
+    // Convert the first `bands` elements of the stored value `v` to the
+    // internal conventions
+    fn normalize(&self, bands: usize, mut v: [f64; 4]) -> [f64; 4] {
+        if bands > 1 && self.order == AxisOrder::NorthEast {
+            v.swap(0, 1);
+        }
+        let (scale, scaled) = match self.unit {
+            GridUnit::Meters | GridUnit::Radians => return v,
+            GridUnit::Millimeters | GridUnit::MillimetersPerYear => (1e-3, bands),
+            GridUnit::Arcsec => ((1. / 3600_f64).to_radians(), bands.min(2)),
+        };
+        for value in v.iter_mut().take(scaled) {
+            *value *= scale;
+        }
+        v
+    }
+}
+
+/// The characteristics of a grid, or one of its subgrids. For geographical
+/// grids, the extent and resolution are in radians, otherwise in the linear
+/// unit of the grid (typically meters)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GridInfo {
+    /// The name of the subgrid. Empty for grids without subgrids
+    pub name: String,
+    /// The name of the parent subgrid, or "NONE" for root grids
+    pub parent: String,
+    pub lat_n: f64,
+    pub lat_s: f64,
+    pub lon_w: f64,
+    pub lon_e: f64,
+    pub dlat: f64,
+    pub dlon: f64,
+    pub rows: usize,
+    pub cols: usize,
+    pub bands: usize,
 }
 
 /// Grid characteristics and interpolation.
@@ -34,8 +178,10 @@ pub struct BaseGrid {
     rows: usize,
     cols: usize,
     pub bands: usize,
-    offset: usize,  // typically 0, but may be any number for externally stored grids
+    offset: usize,    // typically 0, but may be any number for externally stored grids
     grid: Vec<f32>, // May be zero sized in cases where the Context provides access to an externally stored grid
+    id: usize,      // Unique identity, keying the cell cache of `at`
+    units: GridUnits, // Units and axis order of the grid values
 }
 
 impl Grid for BaseGrid {
@@ -83,10 +229,113 @@ impl Grid for BaseGrid {
     // leads to a significantly larger code base, much harder to maintain and
     // comprehend.
     fn at(&self, at: &Coor4D, margin: f64) -> Option<Coor4D> {
-        if !self.contains(at, margin) {
-            return None;
+        let dlat = self.dlat.abs();
+        let dlon = self.dlon.abs();
+
+        // Successive points tend to fall in the same grid cell (think rasters
+        // and scanlines), so we start by checking the most recently visited
+        // cell. If it contains the point, so does the grid, and we may skip
+        // both the containment check and the corner value lookup
+        let cached = LAST_CELL
+            .with(|last| last.get())
+            .filter(|cell| cell.grid == self.id && cell.contains(at, dlat, dlon));
+
+        let cell = match cached {
+            Some(cell) => cell,
+            None => {
+                if !self.contains(at, margin) {
+                    return None;
+                }
+                let cell = self.cell(at);
+                LAST_CELL.with(|last| last.set(Some(cell)));
+                cell
+            }
         };
 
+        // Cell relative, cell unit coordinates in a right handed CS
+        let rlon = (at[0] - cell.ll_lon) / dlon;
+        let rlat = (at[1] - cell.ll_lat) / dlat;
+
+        // We cannot return more than 4 bands in a Coor4D, so we ignore
+        // any exceeding bands
+        let bands = self.bands.min(4);
+        let [ll, lr, ul, ur] = cell.corners;
+        let mut left = Coor4D::origin();
+
+        // Interpolate (or extrapolate, if we're outside of the physical grid)
+        for i in 0..bands {
+            left[i] = (1. - rlat) * ll[i] + rlat * ul[i];
+        }
+        let mut right = Coor4D::origin();
+        for i in 0..bands {
+            right[i] = (1. - rlat) * lr[i] + rlat * ur[i];
+        }
+
+        let mut result = Coor4D::origin();
+        for i in 0..bands {
+            result[i] = (1. - rlon) * left[i] + rlon * right[i];
+        }
+
+        // Cells with missing (NaN) corner values are outside of the grid
+        if result.0.iter().any(|v| v.is_nan()) {
+            return None;
+        }
+
+        Some(result)
+    }
+
+    fn info(&self) -> Vec<GridInfo> {
+        vec![self.info_named("", "NONE")]
+    }
+
+    fn units(&self) -> GridUnits {
+        self.units
+    }
+
+    // Following the Gravsoft convention: If any boundary is outside of
+    // [-720; 720], the grid must (by a wide margin) be in projected coordinates
+    fn is_projected(&self) -> bool {
+        [self.lat_n, self.lat_s, self.lon_w, self.lon_e]
+            .iter()
+            .any(|h| h.abs() > 720.)
+    }
+}
+
+/// A grid cell, as seen by the interpolator: The identity of the grid it
+/// belongs to, the coordinates of its lower left corner, and the values of
+/// the first (up to) 4 bands at its lower left, lower right, upper left and
+/// upper right corners
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    grid: usize,
+    ll_lon: f64,
+    ll_lat: f64,
+    corners: [[f64; 4]; 4],
+}
+
+impl Cell {
+    /// Is `at` inside (or on the border of) the cell?
+    fn contains(&self, at: &Coor4D, dlat: f64, dlon: f64) -> bool {
+        let (lon, lat) = (at[0], at[1]);
+        lon >= self.ll_lon
+            && lon <= self.ll_lon + dlon
+            && lat >= self.ll_lat
+            && lat <= self.ll_lat + dlat
+    }
+}
+
+thread_local! {
+    // The most recently visited cell of any BaseGrid, for use by `BaseGrid::at`
+    static LAST_CELL: std::cell::Cell<Option<Cell>> = const { std::cell::Cell::new(None) };
+}
+
+// Source of unique BaseGrid identities, for use as cell cache keys
+static GRID_ID: AtomicUsize = AtomicUsize::new(1);
+
+impl BaseGrid {
+    // The cell containing `at` - or, in the case of extrapolation: the
+    // nearest cell inside the grid
+    fn cell(&self, at: &Coor4D) -> Cell {
         let grid = &self.grid;
 
         // For now, we support top-to-bottom, left-to-right scan order only.
@@ -101,8 +350,7 @@ impl Grid for BaseGrid {
         let rlat = self.lat_n - at[1];
 
         // The (row, column) of the lower left node of the grid cell containing
-        // the interpolation coordinate - or, in the case of extrapolation:
-        // the nearest cell inside the grid.
+        // the interpolation coordinate
         let row = (rlat / dlat).ceil() as i64;
         let col = (rlon / dlon).floor() as i64;
 
@@ -111,53 +359,59 @@ impl Grid for BaseGrid {
 
         // Index of the first band element of each corner value
         #[rustfmt::skip]
-        let (ll, lr, ul, ur) = (
+        let indices = [
             self.offset + self.bands * (self.cols *  row      + col    ),
             self.offset + self.bands * (self.cols *  row      + col + 1),
             self.offset + self.bands * (self.cols * (row - 1) + col    ),
             self.offset + self.bands * (self.cols * (row - 1) + col + 1),
-        );
-
-        let ll_lon = self.lon_w + col as f64 * dlon;
-        let ll_lat = self.lat_n - row as f64 * dlat;
+        ];
 
-        // Cell relative, cell unit coordinates in a right handed CS
-        let rlon = (at[0] - ll_lon) / dlon;
-        let rlat = (at[1] - ll_lat) / dlat;
-
-        // We cannot return more than 4 bands in a Coor4D, so we ignore
-        // any exceeding bands
+        // Converted to the internal conventions once and for all, as the
+        // cell is cached for subsequent lookups
         let bands = self.bands.min(4);
-        let mut left = Coor4D::origin();
-
-        // Interpolate (or extrapolate, if we're outside of the physical grid)
-        for i in 0..bands {
-            let lower = grid[ll + i] as f64;
-            let upper = grid[ul + i] as f64;
-            left[i] = (1. - rlat) * lower + rlat * upper;
-        }
-        let mut right = Coor4D::origin();
-        for i in 0..bands {
-            let lower = grid[lr + i] as f64;
-            let upper = grid[ur + i] as f64;
-            right[i] = (1. - rlat) * lower + rlat * upper;
+        let mut corners = [[0.; 4]; 4];
+        for (corner, index) in corners.iter_mut().zip(indices) {
+            for i in 0..bands {
+                corner[i] = grid[index + i] as f64;
+            }
+            *corner = self.units.normalize(self.bands, *corner);
         }
 
-        let mut result = Coor4D::origin();
-        for i in 0..bands {
-            result[i] = (1. - rlon) * left[i] + rlon * right[i];
+        Cell {
+            grid: self.id,
+            ll_lon: self.lon_w + col as f64 * dlon,
+            ll_lat: self.lat_n - row as f64 * dlat,
+            corners,
         }
+    }
 
-        Some(result)
+    // The characteristics of the grid, under the given subgrid name
+    pub(crate) fn info_named(&self, name: &str, parent: &str) -> GridInfo {
+        GridInfo {
+            name: name.to_string(),
+            parent: parent.to_string(),
+            lat_n: self.lat_n,
+            lat_s: self.lat_s,
+            lon_w: self.lon_w,
+            lon_e: self.lon_e,
+            dlat: self.dlat,
+            dlon: self.dlon,
+            rows: self.rows,
+            cols: self.cols,
+            bands: self.bands,
+        }
     }
-}
 
-impl BaseGrid {
     pub fn plain(
         header: &[f64],
         grid: Option<&[f32]>,
         offset: Option<usize>,
     ) -> Result<Self, Error> {
+        BaseGrid::from_parts(header, Vec::from(grid.unwrap_or(&[])), offset)
+    }
+
+    // As `plain`, but taking ownership of the grid values, to avoid a copy
+    fn from_parts(header: &[f64], grid: Vec<f32>, offset: Option<usize>) -> Result<Self, Error> {
         if header.len() < 7 {
             return Err(Error::General("Malformed header"));
         }
@@ -175,8 +429,6 @@ impl BaseGrid {
 
         let offset = offset.unwrap_or(0);
 
-        let grid = Vec::from(grid.unwrap_or(&[]));
-
         if elements == 0 || (offset == 0 && elements > grid.len()) || bands < 1 {
             return Err(Error::General("Malformed grid"));
         }
@@ -193,22 +445,64 @@ impl BaseGrid {
             bands,
             offset,
             grid,
+            id: GRID_ID.fetch_add(1, Ordering::Relaxed),
+            units: GridUnits::for_bands(bands),
         })
     }
 
+    /// Set the units and axis order of the grid values. Grids from
+    /// [`BaseGrid::plain`] default to [`GridUnits::for_bands`]
+    pub fn with_units(mut self, units: GridUnits) -> Self {
+        self.units = units;
+        // A fresh identity, as cells cached under the old one are outdated
+        self.id = GRID_ID.fetch_add(1, Ordering::Relaxed);
+        self
+    }
+
     pub fn gravsoft(buf: &[u8]) -> Result<Self, Error> {
-        let (header, grid) = gravsoft_grid_reader(buf)?;
-        BaseGrid::plain(&header, Some(&grid), None)
+        BaseGrid::gravsoft_from_reader(buf)
+    }
+
+    /// Read a Gravsoft grid line by line from `reader`, so the text form of
+    /// the grid is never held in memory in full. Gzip compressed input is
+    /// recognized, and decompressed on the fly (requires the `gzip` feature)
+    pub fn gravsoft_from_reader(mut reader: impl BufRead) -> Result<Self, Error> {
+        let (header, grid, units) = if is_gzip(reader.fill_buf()?) {
+            gravsoft_grid_reader(gunzip(reader)?)?
+        } else {
+            gravsoft_grid_reader(reader)?
+        };
+        Ok(BaseGrid::from_parts(&header, grid, None)?.with_units(units))
     }
 }
 
-// If the Gravsoft grid appears to be in angular units, convert it to radians
-fn normalize_gravsoft_grid_values(header: &mut [f64], grid: &mut [f32]) {
+// Does `buf` start with the gzip magic number?
+pub(crate) fn is_gzip(buf: &[u8]) -> bool {
+    buf.starts_with(&[0x1f, 0x8b])
+}
+
+#[cfg(feature = "gzip")]
+fn gunzip(reader: impl BufRead) -> Result<impl BufRead, Error> {
+    let decoder = flate2::bufread::MultiGzDecoder::new(reader);
+    Ok(std::io::BufReader::new(decoder))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(reader: impl BufRead) -> Result<impl BufRead, Error> {
+    let _ = reader;
+    Err::<&[u8], _>(Error::Unsupported(
+        "Gzip compressed grid - enable the `gzip` feature".to_string(),
+    ))
+}
+
+// If the Gravsoft grid appears to be in angular units, convert its header to
+// radians. In any case, return the units of the grid values
+fn gravsoft_grid_units(header: &mut [f64]) -> GridUnits {
     // If any boundary is outside of [-720; 720], the grid must (by a wide margin) be
     // in projected coordinates and the correction in meters, so we simply return.
     for h in header.iter().take(4) {
         if h.abs() > 720. {
-            return;
+            return GridUnits::METERS;
         }
     }
 
@@ -217,47 +511,28 @@ fn normalize_gravsoft_grid_values(header: &mut [f64], grid: &mut [f32]) {
         *h = h.to_radians();
     }
 
-    // If we're handling a geoid grid, we're done: Grid values are in meters
-    let h = BaseGrid::plain(header, Some(grid), None).unwrap_or_default();
-    if h.bands == 1 {
-        return;
-    }
-
-    // For horizontal datum shifts, the grid values are in seconds-of-arc
-    // and in latitude/longitude order. Swap them and convert into radians.
-    if h.bands == 2 {
-        for i in 0..grid.len() {
-            grid[i] = (grid[i] / 3600.0).to_radians();
-            if i % 2 == 1 {
-                grid.swap(i, i - 1);
-            }
-        }
-        return;
-    }
-
-    // For deformation grids, the grid values are in millimeters/year
-    // and in latitude/longitude/height order. Swap them and convert
-    // to meters/year
-    if h.bands == 3 {
-        for i in 0..grid.len() {
-            if i % 3 == 0 {
-                grid.swap(i, i + 1);
-            }
-            grid[i] /= 1000.0;
-        }
+    match header.get(6).map_or(0, |&bands| bands as usize) {
+        // Geoid grids are in meters
+        1 => GridUnits::METERS,
+        // Horizontal datum shifts are in seconds-of-arc, in latitude/longitude order
+        2 => GridUnits::new(GridUnit::Arcsec, AxisOrder::NorthEast),
+        // 3-band grids, i.e. deformation grids and combined horizontal and
+        // vertical datum shift grids, are in millimeters (per year, for
+        // deformation grids), in north/east/up order
+        _ => GridUnits::new(GridUnit::Millimeters, AxisOrder::NorthEast),
     }
 }
 
 // Read a gravsoft grid. Discard '#'-style comments
-fn gravsoft_grid_reader(buf: &[u8]) -> Result<(Vec<f64>, Vec<f32>), Error> {
-    let all = std::io::BufReader::new(buf);
+fn gravsoft_grid_reader(mut input: impl BufRead) -> Result<(Vec<f64>, Vec<f32>, GridUnits), Error> {
     let mut grid = Vec::<f32>::new();
     let mut header = Vec::<f64>::new();
 
-    for line in all.lines() {
+    // Reuse the line buffer, to avoid an allocation per line
+    let mut buf = String::new();
+    while input.read_line(&mut buf)? > 0 {
         // Remove comments
-        let line = line?;
-        let line = line.split('#').collect::<Vec<_>>()[0];
+        let line = buf.split('#').next().unwrap_or_default();
         // Convert to f64
         for item in line.split_whitespace() {
             let value = item.parse::<f64>().unwrap_or(f64::NAN);
@@ -268,6 +543,7 @@ fn gravsoft_grid_reader(buf: &[u8]) -> Result<(Vec<f64>, Vec<f32>), Error> {
                 grid.push(value as f32);
             }
         }
+        buf.clear();
     }
 
     if header.len() < 6 {
@@ -310,8 +586,8 @@ fn gravsoft_grid_reader(buf: &[u8]) -> Result<(Vec<f64>, Vec<f32>), Error> {
     header.push(bands as f64);
 
     // Handle linear/angular conversions
-    normalize_gravsoft_grid_values(&mut header, &mut grid);
-    Ok((header, grid))
+    let units = gravsoft_grid_units(&mut header);
+    Ok((header, grid, units))
 }
 
 /// Find the most appropriate grid value from a stack (i.e. slice) of grids.
@@ -319,17 +595,27 @@ fn gravsoft_grid_reader(buf: &[u8]) -> Result<(Vec<f64>, Vec<f32>), Error> {
 /// If no hits are found, try once more, this time adding a half grid-cell
 /// margin around each grid
 pub fn grids_at(grids: &[Arc<dyn Grid>], coord: &Coor4D, use_null_grid: bool) -> Option<Coor4D> {
+    grids_at_with_units(grids, coord, use_null_grid).map(|(d, _)| d)
+}
+
+/// As [`grids_at`], but also returning the units of the grid providing
+/// the value, for the caller to interpret it. Null grid values are zero,
+/// in whatever units
+pub fn grids_at_with_units(
+    grids: &[Arc<dyn Grid>],
+    coord: &Coor4D,
+    use_null_grid: bool,
+) -> Option<(Coor4D, GridUnits)> {
     for margin in [0.0, 0.5] {
         for grid in grids.iter() {
-            let d = grid.at(coord, margin);
-            if d.is_some() {
-                return d;
+            if let Some(d) = grid.at(coord, margin) {
+                return Some((d, grid.units()));
             }
         }
     }
 
     if use_null_grid {
-        return Some(Coor4D::origin());
+        return Some((Coor4D::origin(), GridUnits::default()));
     }
 
     None
@@ -369,18 +655,22 @@ mod tests {
         // Create a datum correction grid (2 bands)
         let mut datum_header = Vec::from(HEADER);
 
-        // Since we use normalize_gravsoft...(...) to handle angular normalization,
+        // Since we use gravsoft_grid_units(...) to handle angular normalization,
         // we need a Gravsoft style header here
         datum_header.swap(0, 1);
         datum_header[4] = -datum_header[4];
         datum_header.push(2_f64); // 2 bands
-        let mut datum_grid = Vec::from(DATUM);
-        normalize_gravsoft_grid_values(&mut datum_header, &mut datum_grid);
+        let units = gravsoft_grid_units(&mut datum_header);
+        assert_eq!(
+            units,
+            GridUnits::new(GridUnit::Arcsec, AxisOrder::NorthEast)
+        );
+        let datum_grid = Vec::from(DATUM);
 
         // But Since we use BaseGrid::plain(...) to instantiate, we need a plain header here
         datum_header.swap(0, 1);
         datum_header[4] = -datum_header[4];
-        let datum = BaseGrid::plain(&datum_header, Some(&datum_grid), None)?;
+        let datum = BaseGrid::plain(&datum_header, Some(&datum_grid), None)?.with_units(units);
 
         // Extrapolation
         let c = Coor4D::geo(100., 50., 0., 0.);
@@ -418,6 +708,145 @@ mod tests {
 
         let n = geoid.at(&c, 1.0).unwrap();
         assert!((n[0] - (58.75 + 0.0825)).abs() < 0.0001);
+
+        // Introspection
+        let info = geoid.info();
+        assert_eq!(info.len(), 1);
+        assert_eq!((info[0].rows, info[0].cols, info[0].bands), (5, 9, 1));
+        assert_eq!(info[0].parent, "NONE");
+        assert!((info[0].lat_n.to_degrees() - 58.).abs() < 1e-12);
+        assert!((info[0].dlat.to_degrees() + 1.).abs() < 1e-12);
+        assert_eq!(geoid.which_subgrid_contains(&c, 1.0), Some(String::new()));
+        assert_eq!(geoid.which_subgrid_contains(&c, 0.0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn cell_cache() -> Result<(), Error> {
+        // Two geoid grids, differing by a constant 1 m
+        let mut header = Vec::from(HEADER);
+        header.push(1.);
+        for h in header.iter_mut().take(6) {
+            *h = h.to_radians();
+        }
+        let geoid = BaseGrid::plain(&header, Some(&GEOID), None)?;
+        let shifted: Vec<f32> = GEOID.iter().map(|g| g + 1.).collect();
+        let other = BaseGrid::plain(&header, Some(&shifted), None)?;
+
+        // A scanline, crossing several cells in both directions, alternating
+        // between the grids, and leaving the grid at both ends. Since the
+        // grid values are a linear function of the position, the interpolation
+        // is exact (to within the f32 precision of the grid)
+        for i in -20..180 {
+            let lat = 58.5 - i as f64 / 40.;
+            let lon = 7.5 + i as f64 / 20.;
+            let c = Coor4D::geo(lat, lon, 0., 0.);
+            let inside = (54.0..=58.0).contains(&lat) && (8.0..=16.0).contains(&lon);
+
+            let n = geoid.at(&c, 0.0);
+            assert_eq!(n.is_some(), inside);
+            let m = other.at(&c, 0.0);
+            assert_eq!(m.is_some(), inside);
+            if let (Some(n), Some(m)) = (n, m) {
+                assert!((n[0] - (lat + lon / 100.)).abs() < 1e-5);
+                assert!((m[0] - n[0] - 1.).abs() < 1e-5);
+            }
+
+            // Extrapolation, just north of the grid, also involves the cache
+            if inside {
+                let c = Coor4D::geo(58.5, lon, 0., 0.);
+                assert!(geoid.at(&c, 0.0).is_none());
+                let n = geoid.at(&c, 1.0).unwrap();
+                assert!((n[0] - (58.5 + lon / 100.)).abs() < 1e-5);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn grid_units() -> Result<(), Error> {
+        // A 3-band grid, with constant (north, east, up) values in millimeters
+        let mut header = HEADER.map(f64::to_radians).to_vec();
+        header.push(3.);
+        let values: Vec<f32> = [10., 20., 30.].repeat(5 * 9);
+        let grid = BaseGrid::plain(&header, Some(&values), None)?;
+        let c = Coor4D::geo(55.5, 12.5, 0., 0.);
+
+        // By default, the values are taken as given: Meters, in east-north order
+        assert_eq!(grid.units(), GridUnits::METERS);
+        assert_eq!(grid.at(&c, 0.).unwrap(), Coor4D::raw(10., 20., 30., 0.));
+
+        // ...except for 2-band grids, i.e. horizontal datum shifts, in radians
+        header[6] = 2.;
+        let values: Vec<f32> = [1e-6, 2e-6].repeat(5 * 9);
+        let datum = BaseGrid::plain(&header, Some(&values), None)?;
+        assert_eq!(datum.units(), GridUnits::RADIANS);
+        assert!(datum.units().is_angular());
+
+        // With explicit units, values are converted on lookup. Cells cached
+        // under the old units must not be reused
+        let mm = GridUnits::new(GridUnit::Millimeters, AxisOrder::NorthEast);
+        let grid = grid.with_units(mm);
+        assert!(!grid.units().is_angular());
+        let d = grid.at(&c, 0.).unwrap();
+        for (value, expected) in d.0.iter().zip([0.02, 0.01, 0.03, 0.]) {
+            assert!((value - expected).abs() < 1e-10);
+        }
+
+        // For angular units, the height band is in meters
+        let grid = grid.with_units(GridUnits::new(GridUnit::Arcsec, AxisOrder::NorthEast));
+        assert!(grid.units().is_angular());
+        let d = grid.at(&c, 0.).unwrap();
+        assert!((d[0].to_degrees() * 3600. - 20.).abs() < 1e-9);
+        assert!((d[1].to_degrees() * 3600. - 10.).abs() < 1e-9);
+        assert_eq!(d[2], 30.);
+
+        // The Gravsoft parser records the units of the format
+        let deformation =
+            BaseGrid::gravsoft(include_bytes!("../../geodesy/deformation/test.deformation"))?;
+        assert_eq!(deformation.units(), mm);
+        let datum = BaseGrid::gravsoft(include_bytes!("../../geodesy/datum/test.datum"))?;
+        assert_eq!(datum.units().unit, GridUnit::Arcsec);
+        let geoid = BaseGrid::gravsoft(include_bytes!("../../geodesy/geoid/test.geoid"))?;
+        assert_eq!(geoid.units(), GridUnits::METERS);
+        Ok(())
+    }
+
+    #[test]
+    fn gravsoft_streaming() -> Result<(), Error> {
+        let text = include_bytes!("../../geodesy/datum/test.datum");
+        let grid = BaseGrid::gravsoft(text)?;
+
+        // Reading from a `BufRead` gives the same result as from a slice
+        let file = std::fs::File::open("geodesy/datum/test.datum")?;
+        let streamed = BaseGrid::gravsoft_from_reader(std::io::BufReader::new(file))?;
+        assert_eq!(streamed.info(), grid.info());
+        assert_eq!(streamed.grid, grid.grid);
+
+        // Comments are discarded, also at the end of lines with values
+        let mut commented = b"# A comment\n".to_vec();
+        for line in std::str::from_utf8(text).unwrap().lines() {
+            commented.extend_from_slice(line.as_bytes());
+            commented.extend_from_slice(b" # Another comment\n");
+        }
+        let streamed = BaseGrid::gravsoft_from_reader(&commented[..])?;
+        assert_eq!(streamed.grid, grid.grid);
+
+        // Gzip compressed grids are decompressed on the fly
+        let gzipped = include_bytes!("../../geodesy/geoid/test.geoid.gz");
+        assert!(is_gzip(gzipped));
+        #[cfg(feature = "gzip")]
+        {
+            let expected = BaseGrid::gravsoft(include_bytes!("../../geodesy/geoid/test.geoid"))?;
+            let geoid = BaseGrid::gravsoft_from_reader(&gzipped[..])?;
+            assert_eq!(geoid.info(), expected.info());
+            assert_eq!(geoid.grid, expected.grid);
+        }
+        #[cfg(not(feature = "gzip"))]
+        assert!(matches!(
+            BaseGrid::gravsoft(gzipped),
+            Err(Error::Unsupported(_))
+        ));
         Ok(())
     }
 }
@@ -0,0 +1,145 @@
+//! The NADCON5 `.b` grid format.
+//!
+//! NADCON5 distributes each step of a datum transformation as (up to)
+//! three single band grids of latitude and longitude shifts (seconds of
+//! arc), and ellipsoidal height shifts (meters). The grids are Fortran
+//! sequential unformatted files, i.e. each record is framed by its length
+//! in bytes. The first record is the header:
+//!
+//! ```txt
+//! glamn, glomn, dla, dlo: f64 (degrees, longitudes positive east)
+//! nla, nlo, ikind:        i32
+//! ```
+//!
+//! followed by one record of `nlo` f32 values per row, from south to north.
+//! The files are big-endian, but little-endian files, and files with f32
+//! header values, are recognized as well.
+use super::BaseGrid;
+use crate::Error;
+
+// Read the `n` byte value at `offset` as a u64, respecting the endianness
+fn read(buf: &[u8], offset: usize, n: usize, big: bool) -> Result<u64, Error> {
+    let Some(bytes) = buf.get(offset..offset + n) else {
+        return Err(Error::General("Truncated NADCON5 grid"));
+    };
+    let mut value = 0_u64;
+    for i in 0..n {
+        let byte = if big { bytes[i] } else { bytes[n - 1 - i] };
+        value = (value << 8) | byte as u64;
+    }
+    Ok(value)
+}
+
+/// The units of the NADCON5 grid `name`. These are not given in the file,
+/// but follow from the naming convention of the distribution, cf.
+/// `BaseGrid::nadcon5()`
+#[cfg(feature = "with_plain")]
+pub(crate) fn nadcon5_units(name: &str) -> super::GridUnits {
+    use super::{AxisOrder, GridUnit, GridUnits};
+    if name.contains(".lat.") || name.contains(".lon.") {
+        return GridUnits::new(GridUnit::Arcsec, AxisOrder::EastNorth);
+    }
+    GridUnits::METERS
+}
+
+impl BaseGrid {
+    /// Read a NADCON5 `.b` grid. The result is a single band grid, with the
+    /// values given as in the file, i.e. in seconds of arc for latitude and
+    /// longitude shifts, and in meters for ellipsoidal height shifts. Since
+    /// the kind of grid is given only by its name, the units are left at the
+    /// default, for the caller to set (cf. [`BaseGrid::with_units()`])
+    pub fn nadcon5(buf: &[u8]) -> Result<Self, Error> {
+        // The length of the header record tells both the endianness and
+        // the size of the floating point header values
+        let (big, real) = match (read(buf, 0, 4, true)?, read(buf, 0, 4, false)?) {
+            (44, _) => (true, 8),
+            (28, _) => (true, 4),
+            (_, 44) => (false, 8),
+            (_, 28) => (false, 4),
+            _ => return Err(Error::General("Unrecognized NADCON5 grid header")),
+        };
+
+        let real_at = |offset: usize| -> Result<f64, Error> {
+            let bits = read(buf, offset, real, big)?;
+            Ok(if real == 8 {
+                f64::from_bits(bits)
+            } else {
+                f32::from_bits(bits as u32) as f64
+            })
+        };
+        let int_at = |offset: usize| -> Result<i64, Error> {
+            Ok(read(buf, offset, 4, big)? as u32 as i32 as i64)
+        };
+
+        let glamn = real_at(4)?;
+        let mut glomn = real_at(4 + real)?;
+        let dla = real_at(4 + 2 * real)?;
+        let dlo = real_at(4 + 3 * real)?;
+        let nla = int_at(4 + 4 * real)?;
+        let nlo = int_at(8 + 4 * real)?;
+        let ikind = int_at(12 + 4 * real)?;
+        if ikind != 1 || nla < 2 || nlo < 2 || !(dla > 0. && dlo > 0.) {
+            return Err(Error::General("Unsupported NADCON5 grid header"));
+        }
+        let (rows, cols) = (nla as usize, nlo as usize);
+        if glomn > 180. {
+            glomn -= 360.;
+        }
+
+        // The rows, skipping the header record and the record framing
+        let first = 4 + 4 * real + 12 + 4;
+        let record = 4 * cols + 8;
+        if buf.len() < first + rows * record {
+            return Err(Error::General("Truncated NADCON5 grid"));
+        }
+        let mut grid = Vec::with_capacity(rows * cols);
+        // BaseGrid expects the northernmost row first
+        for row in (0..rows).rev() {
+            let start = first + row * record + 4;
+            for col in 0..cols {
+                let bits = read(buf, start + 4 * col, 4, big)? as u32;
+                grid.push(f32::from_bits(bits));
+            }
+        }
+
+        let header = [
+            (glamn + (rows - 1) as f64 * dla).to_radians(),
+            glamn.to_radians(),
+            glomn.to_radians(),
+            (glomn + (cols - 1) as f64 * dlo).to_radians(),
+            dla.to_radians(),
+            dlo.to_radians(),
+            1.,
+        ];
+        BaseGrid::plain(&header, Some(&grid), None)
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::prelude::*;
+
+    #[test]
+    fn nadcon5() -> Result<(), Error> {
+        let buf = include_bytes!("../../geodesy/b/nadcon5.test_a.test_b.test.lat.trn.20160901.b");
+        let grid = BaseGrid::nadcon5(buf)?;
+        assert_eq!(grid.bands(), 1);
+        let info = &grid.info()[0];
+        assert_eq!((info.rows, info.cols), (11, 15));
+        assert!((info.lon_w.to_degrees() - -105.).abs() < 1e-12);
+        assert!((info.lat_n.to_degrees() - 40.).abs() < 1e-12);
+
+        // The test grid values are 0.5 + 0.01 row + 0.002 column, counting
+        // rows from the south, so at 37N, 100W (row 4, column 10)
+        let d = grid.at(&Coor4D::geo(37., -100., 0., 0.), 0.).unwrap();
+        assert!((d[0] - 0.56).abs() < 1e-6);
+
+        assert!(BaseGrid::nadcon5(&buf[..100]).is_err());
+        assert!(BaseGrid::nadcon5(b"54. 58.   8. 16.   1. 1.").is_err());
+        Ok(())
+    }
+}
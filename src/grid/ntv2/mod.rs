@@ -3,7 +3,11 @@ mod subgrid;
 
 use self::subgrid::NODE_SIZE;
 use super::BaseGrid;
-use crate::{coord::Coor4D, grid::Grid, Error};
+use crate::{
+    coord::Coor4D,
+    grid::{Grid, GridInfo, GridUnits},
+    Error,
+};
 use parser::{NTv2Parser, HEADER_SIZE};
 use std::collections::BTreeMap;
 
@@ -65,6 +69,16 @@ impl Ntv2Grid {
         })
     }
 
+    // Collect the characteristics of the descendants of `parent`, depth first
+    fn subgrid_info(&self, parent: &str, info: &mut Vec<GridInfo>) {
+        for name in self.lookup_table.get(parent).into_iter().flatten() {
+            if let Some(grid) = self.subgrids.get(name) {
+                info.push(grid.info_named(name, parent));
+                self.subgrid_info(name, info);
+            }
+        }
+    }
+
     // As defined by the FGRID subroutine in the NTv2 [spec](https://web.archive.org/web/20140127204822if_/http://www.mgs.gov.on.ca:80/stdprodconsume/groups/content/@mgs/@iandit/documents/resourcelist/stel02_047447.pdf) (page 42)
     fn find_grid(&self, coord: &Coor4D, margin: f64) -> Option<(String, &BaseGrid)> {
         // Start with the base grids whose parent id is `NONE`
@@ -128,6 +142,11 @@ impl Grid for Ntv2Grid {
         2
     }
 
+    // The corrections are converted to radians, in longitude/latitude order, on reading
+    fn units(&self) -> GridUnits {
+        GridUnits::RADIANS
+    }
+
     /// Checks if a `Coord4D` is within the grid limits +- `margin` grid units
     fn contains(&self, position: &Coor4D, margin: f64) -> bool {
         self.find_grid(position, margin).is_some()
@@ -137,6 +156,17 @@ impl Grid for Ntv2Grid {
         self.find_grid(coord, margin)
             .and_then(|grid| grid.1.at(coord, margin))
     }
+
+    // The subgrids, in the order of a depth first traversal of the subgrid tree
+    fn info(&self) -> Vec<GridInfo> {
+        let mut info = Vec::new();
+        self.subgrid_info("NONE", &mut info);
+        info
+    }
+
+    fn which_subgrid_contains(&self, coord: &Coor4D, margin: f64) -> Option<String> {
+        self.find_grid(coord, margin).map(|(name, _)| name)
+    }
 }
 
 // ----- T E S T S ---------------------------------------------------------------------
@@ -209,6 +239,24 @@ mod tests {
         // Grids with no children do not appear in the lookup table
         assert!(!ntv2_grid.lookup_table.contains_key("5556"));
 
+        // Introspection
+        let info = ntv2_grid.info();
+        assert_eq!(info.len(), 2);
+        assert_eq!(
+            (info[0].name.as_str(), info[0].parent.as_str()),
+            ("5458", "NONE")
+        );
+        assert_eq!(
+            (info[1].name.as_str(), info[1].parent.as_str()),
+            ("5556", "5458")
+        );
+        assert!(info.iter().all(|i| i.bands == 2));
+        let coord = Coor4D::geo(55.5, 13.0, 0.0, 0.0);
+        assert_eq!(
+            ntv2_grid.which_subgrid_contains(&coord, 1e-6).unwrap(),
+            "5556"
+        );
+
         Ok(())
     }
 
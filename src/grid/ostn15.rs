@@ -0,0 +1,141 @@
+//! The Ordnance Survey OSTN15/OSGM15 data file format.
+//!
+//! The official transformation between ETRS89 and the British National Grid
+//! (OSGB36, with heights referring to ODN) is distributed as a CSV file,
+//! `OSTN15_OSGM15_DataFile.txt`, with one record per node of a 1 km grid in
+//! the ETRS89 based transverse Mercator projection of the National Grid,
+//! covering eastings 0-700 km, and northings 0-1250 km. Each record holds
+//! the easting, northing and height shifts from ETRS89 to OSGB36/ODN:
+//!
+//! ```txt
+//! Point_ID,ETRS89_Easting,ETRS89_Northing,ETRS89_OSGB36_EShift,ETRS89_OSGB36_NShift,ETRS89_ODN_HeightShift,Height_Datum_Flag
+//! 1,0,0,0.000,0.000,0.000,0
+//! ```
+//!
+//! The easting and northing shifts are *added*, while the height shift
+//! is *subtracted*, when going from ETRS89 to OSGB36/ODN.
+use super::{BaseGrid, GridUnits};
+use crate::Error;
+use std::collections::BTreeMap;
+
+const SPACING: f64 = 1000.;
+
+/// True if `buf` looks like an OSTN15/OSGM15 data file
+#[cfg(any(feature = "with_plain", test))]
+pub(crate) fn is_ostn15(buf: &[u8]) -> bool {
+    buf.strip_prefix(b"\xEF\xBB\xBF")
+        .unwrap_or(buf)
+        .starts_with(b"Point_ID")
+}
+
+impl BaseGrid {
+    /// Read an OSTN15/OSGM15 data file, or a rectangular subset of one.
+    ///
+    /// The result is a 3-band grid in linear (projected) units, holding
+    /// (east, north, up) corrections in meters, all to be *added* when going
+    /// from ETRS89 to OSGB36/ODN. Hence, the sign of the height shift is
+    /// swapped, compared to the file. The `Height_Datum_Flag` is ignored.
+    pub fn ostn15(buf: &[u8]) -> Result<Self, Error> {
+        let text = std::str::from_utf8(buf)?;
+        let mut nodes = BTreeMap::<(usize, usize), [f32; 3]>::new();
+
+        for line in text.lines().skip(1) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let fields: Vec<f64> = line
+                .split(',')
+                .map(|field| field.trim().parse::<f64>().unwrap_or(f64::NAN))
+                .collect();
+            if fields.len() < 6 || fields[1..6].iter().any(|f| f.is_nan()) {
+                return Err(Error::Invalid(format!("OSTN15 record: '{line}'")));
+            }
+            let col = (fields[1] / SPACING).round() as usize;
+            let row = (fields[2] / SPACING).round() as usize;
+            let shifts = [fields[3] as f32, fields[4] as f32, -fields[5] as f32];
+            nodes.insert((row, col), shifts);
+        }
+
+        let Some(((row_s, _), _)) = nodes.first_key_value() else {
+            return Err(Error::General("Empty OSTN15 grid"));
+        };
+        let Some(((row_n, _), _)) = nodes.last_key_value() else {
+            return Err(Error::General("Empty OSTN15 grid"));
+        };
+        let (row_s, row_n) = (*row_s, *row_n);
+        let col_w = nodes.keys().map(|k| k.1).min().unwrap_or_default();
+        let col_e = nodes.keys().map(|k| k.1).max().unwrap_or_default();
+        let rows = row_n - row_s + 1;
+        let cols = col_e - col_w + 1;
+        if rows < 2 || cols < 2 || nodes.len() != rows * cols {
+            return Err(Error::General("Incomplete OSTN15 grid"));
+        }
+
+        // BaseGrid expects the northernmost row first
+        let mut grid = Vec::with_capacity(rows * cols * 3);
+        for row in (row_s..=row_n).rev() {
+            for col in col_w..=col_e {
+                grid.extend_from_slice(&nodes[&(row, col)]);
+            }
+        }
+
+        let header = [
+            row_n as f64 * SPACING,
+            row_s as f64 * SPACING,
+            col_w as f64 * SPACING,
+            col_e as f64 * SPACING,
+            SPACING,
+            SPACING,
+            3.,
+        ];
+        Ok(BaseGrid::plain(&header, Some(&grid), None)?.with_units(GridUnits::METERS))
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::prelude::*;
+
+    #[test]
+    fn ostn15() -> Result<(), Error> {
+        let buf = include_bytes!("../../geodesy/txt/ostn15_test.txt");
+        assert!(is_ostn15(buf));
+        let grid = BaseGrid::ostn15(buf)?;
+        assert_eq!(grid.bands(), 3);
+        assert!(grid.is_projected());
+
+        // At a node
+        let d = grid
+            .at(&Coor4D::raw(651_000., 313_000., 0., 0.), 0.)
+            .unwrap();
+        assert!((d[0] - 102.015).abs() < 1e-4);
+        assert!((d[1] - -77.999).abs() < 1e-4);
+        assert!((d[2] - -44.01).abs() < 1e-4);
+
+        // The test grid is linear in both directions, so bilinear
+        // interpolation reproduces it (to within f32 precision)
+        let d = grid
+            .at(&Coor4D::raw(651_500., 314_250., 0., 0.), 0.)
+            .unwrap();
+        assert!((d[0] - (102. + 0.015 + 0.01125)).abs() < 1e-4);
+        assert!((d[1] - (-78. - 0.0045 + 0.009)).abs() < 1e-4);
+        assert!((d[2] - -(44. + 0.03 - 0.0225)).abs() < 1e-4);
+
+        // Outside of the subset
+        assert!(grid
+            .at(&Coor4D::raw(600_000., 313_000., 0., 0.), 0.)
+            .is_none());
+
+        // Incomplete grids are rejected
+        let text = std::str::from_utf8(buf)?;
+        let incomplete: Vec<&str> = text.lines().take(10).collect();
+        assert!(BaseGrid::ostn15(incomplete.join("\n").as_bytes()).is_err());
+        assert!(!is_ostn15(b"54. 58.   8. 16.   1. 1."));
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+//! The GSI `.par` grid formats.
+//!
+//! The Geospatial Information Authority of Japan (GSI) distributes the
+//! official Japanese datum corrections (TKY2JGD), crustal deformation
+//! corrections (SemiDynaEXE), and seismic patches (PatchJGD) as text files
+//! with one record per node of the third order (30" x 45") standard grid
+//! square mesh. Each record holds the 8 digit mesh code, and up to three
+//! corrections: Latitude and longitude (seconds of arc), and height (meters).
+//!
+//! ```txt
+//! JGD2000-TokyoDatum Ver.2.1.2
+//! MeshCode   dB(sec)   dL(sec)
+//! 46303582  12.79799  -8.13354
+//! ```
+//!
+//! The number of header lines differs between the files, so any line not
+//! starting with a mesh code is taken to be a header line.
+use super::{AxisOrder, BaseGrid, GridUnit, GridUnits};
+use crate::prelude::*;
+use std::collections::BTreeMap;
+
+// The node spacing of the third order mesh, in degrees
+const DLAT: f64 = 30. / 3600.;
+const DLON: f64 = 45. / 3600.;
+
+/// The (row, column) of the south-west corner of the third order mesh
+/// `code`, counted in units of the node spacing, from (0°N, 100°E)
+fn mesh_node(code: u32) -> (usize, usize) {
+    let digit = |n: u32| ((code / 10_u32.pow(n)) % 10) as usize;
+    let pq = (code / 1_000_000) as usize;
+    let rs = ((code / 10_000) % 100) as usize;
+    let (t, u, v, w) = (digit(3), digit(2), digit(1), digit(0));
+    (pq * 80 + t * 10 + v, rs * 80 + u * 10 + w)
+}
+
+impl BaseGrid {
+    /// Read a GSI `.par` file (TKY2JGD, SemiDynaEXE, PatchJGD).
+    ///
+    /// Nodes missing from the file (typically at sea) are set to NaN, so
+    /// points in cells with missing corner nodes are outside of the grid.
+    /// The corrections are kept in the units and order of the file, and
+    /// recorded in the grid units, such that in the forward direction of
+    /// the `gridshift` operator, they take the input datum to the output
+    /// datum of the file:
+    ///
+    /// - 1 band (dH): The height correction in meters, with its sign swapped
+    /// - 2 bands (dB, dL): Latitude and longitude corrections in seconds of arc
+    /// - 3 bands (dB, dL, dH): As for 2 bands, plus the height correction
+    ///   in meters
+    pub fn par(buf: &[u8]) -> Result<Self, Error> {
+        let text = std::str::from_utf8(buf)?;
+        let mut nodes = BTreeMap::<(usize, usize), Vec<f64>>::new();
+        let mut bands = 0;
+
+        for line in text.lines() {
+            let mut tokens = line.split_whitespace();
+            let Some(code) = tokens.next() else {
+                continue;
+            };
+            if code.len() != 8 || !code.bytes().all(|b| b.is_ascii_digit()) {
+                continue;
+            }
+            let values: Vec<f64> = tokens.map(|v| v.parse().unwrap_or(f64::NAN)).collect();
+            if values.is_empty() || values.len() > 3 || values.iter().any(|v| v.is_nan()) {
+                return Err(Error::Invalid(format!("GSI .par record: '{line}'")));
+            }
+            if bands == 0 {
+                bands = values.len();
+            }
+            if values.len() != bands {
+                return Err(Error::Invalid(format!("GSI .par record: '{line}'")));
+            }
+            nodes.insert(mesh_node(code.parse().unwrap_or_default()), values);
+        }
+
+        if nodes.is_empty() {
+            return Err(Error::General("Empty GSI .par grid"));
+        }
+        let row_s = nodes.keys().map(|k| k.0).min().unwrap_or_default();
+        let row_n = nodes.keys().map(|k| k.0).max().unwrap_or_default();
+        let col_w = nodes.keys().map(|k| k.1).min().unwrap_or_default();
+        let col_e = nodes.keys().map(|k| k.1).max().unwrap_or_default();
+        if row_n == row_s || col_e == col_w {
+            return Err(Error::General("Degenerate GSI .par grid"));
+        }
+
+        let mut grid = Vec::with_capacity((row_n - row_s + 1) * (col_e - col_w + 1) * bands);
+        // BaseGrid expects the northernmost row first
+        for row in (row_s..=row_n).rev() {
+            for col in col_w..=col_e {
+                let Some(values) = nodes.get(&(row, col)) else {
+                    grid.extend(std::iter::repeat(f32::NAN).take(bands));
+                    continue;
+                };
+                match bands {
+                    1 => grid.push(-values[0] as f32),
+                    _ => grid.extend(values.iter().map(|&v| v as f32)),
+                }
+            }
+        }
+
+        let header = [
+            (row_n as f64 * DLAT).to_radians(),
+            (row_s as f64 * DLAT).to_radians(),
+            (100. + col_w as f64 * DLON).to_radians(),
+            (100. + col_e as f64 * DLON).to_radians(),
+            DLAT.to_radians(),
+            DLON.to_radians(),
+            bands as f64,
+        ];
+        let units = match bands {
+            1 => GridUnits::METERS,
+            _ => GridUnits::new(GridUnit::Arcsec, AxisOrder::NorthEast),
+        };
+        Ok(BaseGrid::plain(&header, Some(&grid), None)?.with_units(units))
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn mesh_codes() {
+        // Mesh 53394611 is at 35°40'30"N, 139°45'45"E, in central Tokyo
+        let (row, col) = mesh_node(53394611);
+        assert!((row as f64 * DLAT - (35. + 40. / 60. + 30. / 3600.)).abs() < 1e-12);
+        assert!((100. + col as f64 * DLON - (139. + 45. / 60. + 45. / 3600.)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn par() -> Result<(), Error> {
+        let buf = include_bytes!("../../geodesy/par/test.par");
+        let grid = BaseGrid::par(buf)?;
+        assert_eq!(grid.bands(), 2);
+        assert!(!grid.is_projected());
+
+        // At the node of mesh 53394622
+        let node = Coor4D::geo(35. + 41. / 60., 139. + 46.5 / 60., 0., 0.);
+        let d = grid.at(&node, 0.).unwrap();
+        assert!((d[0].to_degrees() * 3600. - -11.714).abs() < 1e-4);
+        assert!((d[1].to_degrees() * 3600. - 11.624).abs() < 1e-4);
+
+        // SemiDynaEXE style, with a missing node
+        let semidyna = "SemiDynaEXE\nMeshCode dB(sec) dL(sec) dH(m)\n\
+            53394611 0.036 0.072 0.1\n53394612 0.036 0.072 0.1\n53394613 0.036 0.072 0.1\n\
+            53394621 0.036 0.072 0.1\n53394622 0.036 0.072 0.1\n";
+        let grid = BaseGrid::par(semidyna.as_bytes())?;
+        assert_eq!(grid.bands(), 3);
+        let sw = Coor4D::geo(35. + 40.5 / 60., 139. + 45.75 / 60., 0., 0.);
+        assert_eq!(grid.units().unit, GridUnit::Arcsec);
+        let d = grid.at(&sw, 0.).unwrap();
+        // Longitude and latitude corrections in radians, height in meters
+        assert!((d[0].to_degrees() * 3600. - 0.072).abs() < 1e-6);
+        assert!((d[1].to_degrees() * 3600. - 0.036).abs() < 1e-6);
+        assert!((d[2] - 0.1).abs() < 1e-6);
+        // The north-east corner of the next cell is missing
+        let ne = Coor4D::geo(35. + 40.75 / 60., 139. + 46.9 / 60., 0., 0.);
+        assert!(grid.at(&ne, 0.).is_none());
+
+        assert!(BaseGrid::par(b"MeshCode dB(sec) dL(sec)\n53394611 1 2 3 4\n").is_err());
+        Ok(())
+    }
+}
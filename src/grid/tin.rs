@@ -0,0 +1,524 @@
+//! Triangulated irregular networks (TINs), in the PROJ `tinshift` JSON format.
+//!
+//! Triangulation based transformations, like the Swiss FINELTRA based
+//! CHENyx06 transformation between LV03 and LV95, are given as a set of
+//! vertices with known coordinates in both systems, and a triangulation of
+//! the vertices. The transformation is an affine transformation within
+//! each triangle, i.e. linear interpolation of the shifts at the vertices.
+//!
+//! The PROJ `tinshift` format is a JSON file, of which we read the
+//! following members:
+//!
+//! ```txt
+//! {
+//!   "file_type": "triangulation_file",
+//!   "format_version": "1.0",
+//!   "transformed_components": [ "horizontal" ],
+//!   "vertices_columns": [ "source_x", "source_y", "target_x", "target_y" ],
+//!   "triangles_columns": [ "idx_vertex1", "idx_vertex2", "idx_vertex3" ],
+//!   "vertices": [ [2484000, 1075000, 484000, 75000], ... ],
+//!   "triangles": [ [0, 1, 2], ... ]
+//! }
+//! ```
+//!
+//! The vertical component is given by the `source_z` and `target_z`
+//! columns, or (from format version 1.1) by the `offset_z` column.
+use super::{Grid, GridUnits};
+use crate::prelude::*;
+
+/// A triangulated irregular network, seen as a grid of shifts.
+///
+/// In the sense of the `gridshift` operator, a TIN transforming only the
+/// horizontal component is a 2-band grid of (x, y) shifts, a TIN
+/// transforming only the vertical component a 1-band grid, with the sign
+/// of the height shift swapped (so it behaves like a geoid model), and a
+/// TIN transforming both components a 3-band grid of (x, y, z) shifts.
+/// In all cases, the shifts are in the linear unit of the triangulation.
+#[derive(Debug, Default, Clone)]
+pub struct Tin {
+    bands: usize,
+    vertices: Vec<[f64; 2]>,
+    shifts: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+    // West, south, east, north
+    bbox: [f64; 4],
+    // Triangles overlapping each cell of a regular index grid of size
+    // `buckets` x `buckets`, covering the bbox
+    buckets: usize,
+    index: Vec<Vec<usize>>,
+}
+
+impl Grid for Tin {
+    fn bands(&self) -> usize {
+        self.bands
+    }
+
+    // Triangulations have no cells, so the margin is ignored
+    fn contains(&self, at: &Coor4D, _margin: f64) -> bool {
+        self.triangle(at).is_some()
+    }
+
+    fn at(&self, at: &Coor4D, _margin: f64) -> Option<Coor4D> {
+        let (t, weights) = self.triangle(at)?;
+        let mut shift = [0.; 3];
+        for (vertex, weight) in self.triangles[t].iter().zip(weights) {
+            for (i, s) in shift.iter_mut().enumerate() {
+                *s += weight * self.shifts[*vertex][i];
+            }
+        }
+        let result = match self.bands {
+            1 => Coor4D::raw(-shift[2], 0., 0., 0.),
+            2 => Coor4D::raw(shift[0], shift[1], 0., 0.),
+            _ => Coor4D::raw(shift[0], shift[1], shift[2], 0.),
+        };
+        Some(result)
+    }
+
+    fn is_projected(&self) -> bool {
+        true
+    }
+
+    // The shifts are in the linear unit of the triangulation, whatever the
+    // number of bands
+    fn units(&self) -> GridUnits {
+        GridUnits::METERS
+    }
+}
+
+impl Tin {
+    /// Read a triangulation in the PROJ `tinshift` JSON format
+    pub fn tinshift(buf: &[u8]) -> Result<Self, Error> {
+        let text = std::str::from_utf8(buf)?;
+        let json = Json::parse(text)?;
+
+        if json.get("file_type").and_then(Json::as_str) != Some("triangulation_file") {
+            return Err(Error::Unsupported("tinshift: file_type".to_string()));
+        }
+        let version = json.get("format_version").and_then(Json::as_str);
+        if !version.is_some_and(|v| v.starts_with("1.")) {
+            return Err(Error::Unsupported("tinshift: format_version".to_string()));
+        }
+
+        let strings = |key: &str| -> Result<Vec<&str>, Error> {
+            let missing = || Error::Invalid(format!("tinshift: '{key}'"));
+            let array = json.get(key).and_then(Json::as_array).ok_or_else(missing)?;
+            array
+                .iter()
+                .map(|s| s.as_str().ok_or_else(missing))
+                .collect()
+        };
+        let components = strings("transformed_components")?;
+        let horizontal = components.contains(&"horizontal");
+        let vertical = components.contains(&"vertical");
+        let bands = match (horizontal, vertical) {
+            (true, false) => 2,
+            (false, true) => 1,
+            (true, true) => 3,
+            _ => {
+                return Err(Error::Invalid(
+                    "tinshift: transformed_components".to_string(),
+                ))
+            }
+        };
+
+        // The column numbers of the vertex elements we need
+        let columns = strings("vertices_columns")?;
+        let column = |name: &str| columns.iter().position(|c| *c == name);
+        let required = |name: &str| {
+            column(name).ok_or_else(|| Error::Invalid(format!("tinshift: missing column '{name}'")))
+        };
+        let source = [required("source_x")?, required("source_y")?];
+        let target = if horizontal {
+            Some([required("target_x")?, required("target_y")?])
+        } else {
+            None
+        };
+        let z = match (vertical, column("offset_z")) {
+            (false, _) => None,
+            (true, Some(offset)) => Some((None, offset)),
+            (true, None) => Some((Some(required("source_z")?), required("target_z")?)),
+        };
+
+        let rows = |key: &str| -> Result<Vec<Vec<f64>>, Error> {
+            let invalid = || Error::Invalid(format!("tinshift: '{key}'"));
+            let array = json.get(key).and_then(Json::as_array).ok_or_else(invalid)?;
+            array
+                .iter()
+                .map(|row| {
+                    let row = row.as_array().ok_or_else(invalid)?;
+                    row.iter().map(|v| v.as_f64().ok_or_else(invalid)).collect()
+                })
+                .collect()
+        };
+
+        let mut tin = Tin {
+            bands,
+            ..Default::default()
+        };
+        for row in rows("vertices")? {
+            if row.len() != columns.len() {
+                return Err(Error::Invalid("tinshift: vertex size".to_string()));
+            }
+            let mut shift = [0.; 3];
+            if let Some(target) = target {
+                shift[0] = row[target[0]] - row[source[0]];
+                shift[1] = row[target[1]] - row[source[1]];
+            }
+            if let Some((source_z, target_z)) = z {
+                shift[2] = row[target_z] - source_z.map_or(0., |s| row[s]);
+            }
+            tin.vertices.push([row[source[0]], row[source[1]]]);
+            tin.shifts.push(shift);
+        }
+
+        let columns = strings("triangles_columns")?;
+        let column = |name: &str| {
+            columns
+                .iter()
+                .position(|c| *c == name)
+                .ok_or_else(|| Error::Invalid(format!("tinshift: missing column '{name}'")))
+        };
+        let corners = [
+            column("idx_vertex1")?,
+            column("idx_vertex2")?,
+            column("idx_vertex3")?,
+        ];
+        for row in rows("triangles")? {
+            let mut triangle = [0; 3];
+            for (vertex, c) in triangle.iter_mut().zip(corners) {
+                let index = row.get(c).copied().unwrap_or(-1.);
+                if index < 0. || index >= tin.vertices.len() as f64 {
+                    return Err(Error::Invalid("tinshift: vertex index".to_string()));
+                }
+                *vertex = index as usize;
+            }
+            tin.triangles.push(triangle);
+        }
+
+        if tin.triangles.is_empty() {
+            return Err(Error::General("Empty tinshift triangulation"));
+        }
+        tin.build_index();
+        Ok(tin)
+    }
+
+    /// The inverse triangulation, i.e. the triangulation of the target
+    /// vertices, with the shifts negated
+    pub fn inverse(&self) -> Tin {
+        let mut tin = self.clone();
+        for (vertex, shift) in tin.vertices.iter_mut().zip(tin.shifts.iter_mut()) {
+            vertex[0] += shift[0];
+            vertex[1] += shift[1];
+            *shift = shift.map(|s| -s);
+        }
+        tin.build_index();
+        tin
+    }
+
+    // Set up the bbox, and the index of the triangles overlapping each index cell
+    fn build_index(&mut self) {
+        let mut bbox = [f64::INFINITY, f64::INFINITY, -f64::INFINITY, -f64::INFINITY];
+        for v in &self.vertices {
+            bbox = [
+                bbox[0].min(v[0]),
+                bbox[1].min(v[1]),
+                bbox[2].max(v[0]),
+                bbox[3].max(v[1]),
+            ];
+        }
+        self.bbox = bbox;
+        self.buckets = ((self.triangles.len() as f64).sqrt().ceil() as usize).max(1);
+        self.index = vec![Vec::new(); self.buckets * self.buckets];
+
+        for (t, triangle) in self.triangles.iter().enumerate() {
+            let xs = triangle.map(|v| self.vertices[v][0]);
+            let ys = triangle.map(|v| self.vertices[v][1]);
+            let (x0, y0) = self.bucket(xs[0].min(xs[1]).min(xs[2]), ys[0].min(ys[1]).min(ys[2]));
+            let (x1, y1) = self.bucket(xs[0].max(xs[1]).max(xs[2]), ys[0].max(ys[1]).max(ys[2]));
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    self.index[y * self.buckets + x].push(t);
+                }
+            }
+        }
+    }
+
+    // The (column, row) of the index cell containing (x, y), clamped to the index
+    fn bucket(&self, x: f64, y: f64) -> (usize, usize) {
+        let n = self.buckets as f64;
+        let width = (self.bbox[2] - self.bbox[0]).max(f64::MIN_POSITIVE);
+        let height = (self.bbox[3] - self.bbox[1]).max(f64::MIN_POSITIVE);
+        let col = ((x - self.bbox[0]) / width * n).floor().clamp(0., n - 1.);
+        let row = ((y - self.bbox[1]) / height * n).floor().clamp(0., n - 1.);
+        (col as usize, row as usize)
+    }
+
+    // The triangle containing `at`, and the barycentric coordinates of `at`
+    fn triangle(&self, at: &Coor4D) -> Option<(usize, [f64; 3])> {
+        let (x, y) = (at[0], at[1]);
+        if !(x >= self.bbox[0] && x <= self.bbox[2] && y >= self.bbox[1] && y <= self.bbox[3]) {
+            return None;
+        }
+        let (col, row) = self.bucket(x, y);
+        for &t in &self.index[row * self.buckets + col] {
+            let [a, b, c] = self.triangles[t].map(|v| self.vertices[v]);
+            let det = (b[1] - c[1]) * (a[0] - c[0]) + (c[0] - b[0]) * (a[1] - c[1]);
+            if det == 0. {
+                continue;
+            }
+            let l1 = ((b[1] - c[1]) * (x - c[0]) + (c[0] - b[0]) * (y - c[1])) / det;
+            let l2 = ((c[1] - a[1]) * (x - c[0]) + (a[0] - c[0]) * (y - c[1])) / det;
+            let l3 = 1. - l1 - l2;
+            // "On the border" qualifies as within
+            const EPS: f64 = -1e-10;
+            if l1 >= EPS && l2 >= EPS && l3 >= EPS {
+                return Some((t, [l1, l2, l3]));
+            }
+        }
+        None
+    }
+}
+
+// ----- J S O N -----------------------------------------------------------------------
+
+// Tinshift files nest no deeper than a handful of levels. The limit protects
+// the recursive descent parser below against stack overflows on hostile input
+const MAX_JSON_DEPTH: usize = 64;
+
+// Just enough JSON to read tinshift files, without pulling in a JSON
+// dependency for the library
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> Result<Json, Error> {
+        let mut chars = text.chars().peekable();
+        let json = Self::value(&mut chars, 0)?;
+        Self::skip_whitespace(&mut chars);
+        if chars.next().is_some() {
+            return Err(Error::Syntax("JSON: trailing characters".to_string()));
+        }
+        Ok(json)
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        let Json::Object(members) = self else {
+            return None;
+        };
+        members.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&Vec<Json>> {
+        match self {
+            Json::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn value(
+        chars: &mut std::iter::Peekable<std::str::Chars>,
+        depth: usize,
+    ) -> Result<Json, Error> {
+        let error = |what: &str| Error::Syntax(format!("JSON: {what}"));
+        Self::skip_whitespace(chars);
+        let next = chars.peek().copied();
+        if matches!(next, Some('{' | '[')) && depth >= MAX_JSON_DEPTH {
+            return Err(Error::General("JSON: nesting too deep"));
+        }
+        match next {
+            Some('{') => {
+                chars.next();
+                let mut members = Vec::new();
+                loop {
+                    Self::skip_whitespace(chars);
+                    if chars.next_if_eq(&'}').is_some() && members.is_empty() {
+                        return Ok(Json::Object(members));
+                    }
+                    let Json::String(key) = Self::value(chars, depth + 1)? else {
+                        return Err(error("expected object key"));
+                    };
+                    Self::skip_whitespace(chars);
+                    if chars.next() != Some(':') {
+                        return Err(error("expected ':'"));
+                    }
+                    members.push((key, Self::value(chars, depth + 1)?));
+                    Self::skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some('}') => return Ok(Json::Object(members)),
+                        _ => return Err(error("expected ',' or '}'")),
+                    }
+                }
+            }
+            Some('[') => {
+                chars.next();
+                let mut elements = Vec::new();
+                loop {
+                    Self::skip_whitespace(chars);
+                    if chars.next_if_eq(&']').is_some() && elements.is_empty() {
+                        return Ok(Json::Array(elements));
+                    }
+                    elements.push(Self::value(chars, depth + 1)?);
+                    Self::skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(Json::Array(elements)),
+                        _ => return Err(error("expected ',' or ']'")),
+                    }
+                }
+            }
+            Some('"') => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => return Ok(Json::String(s)),
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some('r') => s.push('\r'),
+                            Some('b') => s.push('\u{8}'),
+                            Some('f') => s.push('\u{c}'),
+                            Some('u') => {
+                                let hex: String = chars.by_ref().take(4).collect();
+                                let code = u32::from_str_radix(&hex, 16)
+                                    .map_err(|_| error("bad unicode escape"))?;
+                                s.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                            }
+                            Some(c) => s.push(c),
+                            None => return Err(error("unterminated string")),
+                        },
+                        Some(c) => s.push(c),
+                        None => return Err(error("unterminated string")),
+                    }
+                }
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(c) = chars.next_if(|c| "+-.eE".contains(*c) || c.is_ascii_digit()) {
+                    number.push(c);
+                }
+                number
+                    .parse()
+                    .map(Json::Number)
+                    .map_err(|_| error(&format!("bad number '{number}'")))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let mut word = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    "null" => Ok(Json::Null),
+                    _ => Err(error(&format!("unexpected '{word}'"))),
+                }
+            }
+            _ => Err(error("unexpected end of input")),
+        }
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() -> Result<(), Error> {
+        let json = Json::parse(r#" { "a": [1, -2.5e1, true, null], "b": "c\"d", "e": {} } "#)?;
+        let a = json.get("a").and_then(Json::as_array).unwrap();
+        assert_eq!(a[1], Json::Number(-25.));
+        assert_eq!(a[2], Json::Bool(true));
+        assert_eq!(json.get("b").and_then(Json::as_str), Some("c\"d"));
+        assert_eq!(json.get("e"), Some(&Json::Object(Vec::new())));
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("[1, 2] 3").is_err());
+
+        // Nesting is limited
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(Json::parse(&nested(MAX_JSON_DEPTH)).is_ok());
+        assert!(matches!(
+            Json::parse(&nested(100_000)),
+            Err(Error::General(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn tinshift() -> Result<(), Error> {
+        let buf = include_bytes!("../../geodesy/json/tinshift_test.json");
+        let tin = Tin::tinshift(buf)?;
+        assert_eq!(tin.bands(), 2);
+        assert!(tin.is_projected());
+        assert_eq!(tin.units(), GridUnits::METERS);
+
+        // At a vertex
+        let d = tin
+            .at(&Coor4D::raw(600_000., 200_000., 0., 0.), 0.)
+            .unwrap();
+        assert!((d[0] - 2_000_000.).abs() < 1e-9);
+        assert!((d[1] - 1_000_000.).abs() < 1e-9);
+
+        // Inside a triangle: Linear interpolation of the vertex shifts
+        let d = tin
+            .at(&Coor4D::raw(625_000., 225_000., 0., 0.), 0.)
+            .unwrap();
+        assert!((d[0] - 2_000_000.5).abs() < 1e-9);
+        assert!((d[1] - 999_999.75).abs() < 1e-9);
+
+        // Outside
+        assert!(tin
+            .at(&Coor4D::raw(500_000., 225_000., 0., 0.), 0.)
+            .is_none());
+
+        // Vertical only, using the format version 1.1 offset_z column
+        let vertical = r#"{
+            "file_type": "triangulation_file", "format_version": "1.1",
+            "transformed_components": ["vertical"],
+            "vertices_columns": ["source_x", "source_y", "offset_z"],
+            "triangles_columns": ["idx_vertex1", "idx_vertex2", "idx_vertex3"],
+            "vertices": [[0, 0, 1], [10, 0, 2], [0, 10, 3]],
+            "triangles": [[0, 1, 2]]
+        }"#;
+        let tin = Tin::tinshift(vertical.as_bytes())?;
+        assert_eq!(tin.bands(), 1);
+        // Swapped sign, such that gridshift treats it as a geoid model
+        let d = tin.at(&Coor4D::raw(5., 0., 0., 0.), 0.).unwrap();
+        assert!((d[0] - -1.5).abs() < 1e-12);
+
+        // Missing columns, and bad indices
+        let bad = vertical.replace("offset_z", "target_z");
+        assert!(Tin::tinshift(bad.as_bytes()).is_err());
+        let bad = vertical.replace("[0, 1, 2]", "[0, 1, 3]");
+        assert!(Tin::tinshift(bad.as_bytes()).is_err());
+        Ok(())
+    }
+}
@@ -20,6 +20,10 @@ These 8 spatio-temporal directional designations have convenient short forms,
 Also, we introduce the 3 common angular representations "degrees, gradians, radians",
 conveniently abbrevieated as "deg", "gon" and "rad".
 
+For projected coordinates, the angular unit may be replaced by a linear unit, e.g.
+"ft" or "us-ft", and a second, linear, unit may be given for the upish axis, as in
+`neuf_deg_ft`, or `enuf_us-ft_us-ft`.
+
 The Rust Geodesy internal format of a four dimensional coordinate tuple is e, n, u, f,
 and the internal unit of measure for angular coordinates is radians. In `adapt`, terms,
 this is described as `enuf_rad`.
@@ -57,6 +61,7 @@ geo:in | cart ... | helmert ... | cart inv ... | geo:out
 
 !*/
 
+use super::units::{ANGULAR_UNITS, LINEAR_UNITS};
 use crate::authoring::*;
 
 const POST_DEFAULT: [f64; 4] = [0., 1., 2., 3.];
@@ -78,17 +83,15 @@ fn fwd(op: &Op, _ctx: &dyn Context, data: &mut dyn CoordinateSet) -> usize {
         post[3] as usize,
     ];
     let mult = op.params.series("mult").unwrap_or(&MULT_DEFAULT);
-    for i in 0..n {
-        let mut coord = data.get_coord(i);
-        coord = Coor4D([
+    for_each_coord(data, |coord| {
+        *coord = Coor4D([
             coord[post[0]] * mult[0],
             coord[post[1]] * mult[1],
             coord[post[2]] * mult[2],
             coord[post[3]] * mult[3],
         ]);
-        data.set_coord(i, &coord);
-    }
-    n
+        true
+    })
 }
 
 // ----- I N V E R S E --------------------------------------------------------------
@@ -110,16 +113,36 @@ fn inv(op: &Op, _ctx: &dyn Context, data: &mut dyn CoordinateSet) -> usize {
     let mult = op.params.series("mult").unwrap_or(&MULT_DEFAULT);
     let mult = [1. / mult[0], 1. / mult[1], 1. / mult[2], 1. / mult[3]];
 
-    for i in 0..n {
-        let coord = data.get_coord(i);
+    for_each_coord(data, |coord| {
         let mut c = Coor4D::default();
         for j in 0..4_usize {
-            c[post[j]] = coord[j] * mult[post[j]];
+            c[post[j]] = coord[j] * mult[j];
         }
-        data.set_coord(i, &c);
+        *coord = c;
+        true
+    })
+}
+
+// ----- D E R I V A T I V E S ------------------------------------------------------
+
+fn jacobian(op: &Op, _ctx: &dyn Context, _at: &Coor4D) -> Option<[f64; 4]> {
+    if op.params.boolean("noop") {
+        return Some([1., 0., 0., 1.]);
     }
+    let post = op.params.series("post").unwrap_or(&POST_DEFAULT);
+    let mult = op.params.series("mult").unwrap_or(&MULT_DEFAULT);
 
-    n
+    // Only meaningful if the first two output coordinates stem
+    // from the first two input coordinates
+    let mut derivatives = [0.; 4];
+    for i in 0..2 {
+        let from = post[i] as usize;
+        if from > 1 {
+            return None;
+        }
+        derivatives[2 * from + i] = mult[i];
+    }
+    Some(derivatives)
 }
 
 // ----- C O N S T R U C T O R ------------------------------------------------------
@@ -134,7 +157,9 @@ pub const GAMUT: [OpParameter; 3] = [
 
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
-    let descriptor = OpDescriptor::new(&parameters.definition, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor =
+        OpDescriptor::new(&parameters.definition, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.jacobian = Some(InnerJacobian(jacobian));
     let steps = Vec::<Op>::new();
 
     // What we go `from` and what we go `to` both defaults to the internal
@@ -198,28 +223,27 @@ fn coordinate_order_descriptor(desc: &str) -> Option<CoordinateOrderDescriptor>
         });
     }
 
-    if desc.len() != 4 && desc.len() != 8 {
+    // The axis designators, followed by the optional units of
+    // the horizontal and the vertical axes, e.g. "neuf_deg_ft"
+    let mut parts = desc.split('_');
+    let axes = parts.next()?;
+    if axes.chars().count() != 4 {
         return None;
     }
-
-    let mut torad = 1_f64;
-    if desc.len() == 8 {
-        let good_angular = desc.ends_with("_deg")
-            || desc.ends_with("_gon")
-            || desc.ends_with("_rad")
-            || desc.ends_with("_any");
-        if !good_angular {
-            return None;
-        }
-        if desc.ends_with("_deg") {
-            torad = std::f64::consts::PI / 180.;
-        } else if desc.ends_with("_gon") {
-            torad = std::f64::consts::PI / 200.;
-        }
+    let horizontal = match parts.next() {
+        Some(unit) => horizontal_unit(unit)?,
+        None => 1.,
+    };
+    let vertical = match parts.next() {
+        Some(unit) => vertical_unit(unit)?,
+        None => 1.,
+    };
+    if parts.next().is_some() {
+        return None;
     }
 
     // Now figure out what goes (resp. comes from) where
-    let desc: Vec<char> = desc[0..4].chars().collect();
+    let desc: Vec<char> = axes.chars().collect();
     let mut indices = [1i32, 2, 3, 4];
     for i in 0..4 {
         let d = desc[i];
@@ -258,13 +282,43 @@ fn coordinate_order_descriptor(desc: &str) -> Option<CoordinateOrderDescriptor>
     for i in 0..4 {
         let d = indices[i];
         post[i] = (d.abs() - 1) as usize;
-        mult[i] = d.signum() as f64 * if i > 1 { 1.0 } else { torad };
+        let unit = match post[i] {
+            0 | 1 => horizontal,
+            2 => vertical,
+            _ => 1.,
+        };
+        mult[i] = d.signum() as f64 * unit;
     }
     let noop = mult == [1.0; 4] && post == [0_usize, 1, 2, 3];
 
     Some(CoordinateOrderDescriptor { post, mult, noop })
 }
 
+// Multiplier from the horizontal unit `name` to the internal unit: Radians
+// for angular units, meters for linear. `any` means "leave as is"
+fn horizontal_unit(name: &str) -> Option<f64> {
+    match name {
+        "any" => Some(1.),
+        "gon" => Some(std::f64::consts::PI / 200.),
+        _ => ANGULAR_UNITS
+            .iter()
+            .chain(LINEAR_UNITS.iter())
+            .find(|u| u.name() == name)
+            .map(|u| u.multiplier()),
+    }
+}
+
+// Multiplier from the vertical unit `name` to meters
+fn vertical_unit(name: &str) -> Option<f64> {
+    if name == "any" {
+        return Some(1.);
+    }
+    LINEAR_UNITS
+        .iter()
+        .find(|u| u.name() == name)
+        .map(|u| u.multiplier())
+}
+
 #[allow(clippy::float_cmp)]
 fn combine_descriptors(
     from: &CoordinateOrderDescriptor,
@@ -272,8 +326,8 @@ fn combine_descriptors(
 ) -> CoordinateOrderDescriptor {
     let mut give = CoordinateOrderDescriptor::default();
     for i in 0..4 {
-        give.mult[i] = from.mult[i] / to.mult[i];
         give.post[i] = from.post.iter().position(|&p| p == to.post[i]).unwrap();
+        give.mult[i] = from.mult[give.post[i]] / to.mult[i];
     }
     give.noop = give.mult == [1.0; 4] && give.post == [0_usize, 1, 2, 3];
     give
@@ -325,6 +379,45 @@ mod tests {
         assert!(!give.noop);
     }
 
+    // Test linear and vertical units
+    #[test]
+    fn units() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // US survey feet in the plane, meters in the vertical
+        let op = ctx.op("adapt from=neuf_us-ft")?;
+        let mut data = [Coor4D::raw(3937., 1200., 100., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_float_eq!(
+            data[0].0,
+            [1200. * 1200. / 3937., 1200., 100., 0.],
+            abs_all <= 1e-9
+        );
+        ctx.apply(op, Inv, &mut data)?;
+        assert_float_eq!(data[0].0, [3937., 1200., 100., 0.], abs_all <= 1e-9);
+
+        // Degrees in the plane, feet in the vertical
+        let op = ctx.op("adapt from=neuf_deg_ft")?;
+        let mut data = [Coor4D::raw(55., 12., 1000., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        let expected = [12_f64.to_radians(), 55_f64.to_radians(), 304.8, 0.];
+        assert_float_eq!(data[0].0, expected, abs_all <= 1e-12);
+
+        // Combinations of units and orientations, with the upish axis in front
+        let op = ctx.op("adapt from=unef_deg_ft to=dwnf_gon_m")?;
+        let mut data = [Coor4D::raw(1000., 90., 45., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_float_eq!(data[0].0, [-304.8, -50., 100., 0.], abs_all <= 1e-9);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_float_eq!(data[0].0, [1000., 90., 45., 0.], abs_all <= 1e-9);
+
+        // Angular units are not valid for the vertical, and unknown units not at all
+        assert!(ctx.op("adapt from=neuf_deg_deg").is_err());
+        assert!(ctx.op("adapt from=neuf_furlong").is_err());
+        assert!(ctx.op("adapt from=neuf_deg_m_m").is_err());
+        Ok(())
+    }
+
     // Test the basic adapt functionality
     #[test]
     fn adapt() -> Result<(), Error> {
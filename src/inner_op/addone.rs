@@ -3,29 +3,19 @@ use crate::authoring::*;
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(_op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let n = operands.len();
-    let mut successes = 0;
-    for i in 0..n {
-        let mut o = operands.get_coord(i);
+    for_each_coord(operands, |o| {
         o[0] += 1.;
-        successes += 1;
-        operands.set_coord(i, &o);
-    }
-    successes
+        true
+    })
 }
 
 // ----- I N V E R S E -----------------------------------------------------------------
 
 fn inv(_op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let n = operands.len();
-    let mut successes = 0;
-    for i in 0..n {
-        let mut o = operands.get_coord(i);
+    for_each_coord(operands, |o| {
         o[0] -= 1.;
-        successes += 1;
-        operands.set_coord(i, &o);
-    }
-    successes
+        true
+    })
 }
 
 // ----- C O N S T R U C T O R ---------------------------------------------------------
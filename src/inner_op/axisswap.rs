@@ -3,67 +3,51 @@ use super::*;
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let n = operands.len();
-
     // We default to order=1,2,3,4, so if order is not given, we are done already
     let Ok(order) = op.params.series("order") else {
-        return n;
+        return operands.len();
     };
-
     let dimensionality = order.len();
+    let (pos, sgn) = permutation(order);
 
-    let mut pos = [0_usize, 1, 2, 3];
-    let mut sgn = [1., 1., 1., 1.];
-    for (index, value) in order.iter().enumerate() {
-        pos[index] = (value.abs() - 1.) as usize;
-        sgn[index] = 1_f64.copysign(*value);
-    }
-
-    let mut successes = 0_usize;
-    for i in 0..n {
-        let inp = operands.get_coord(i);
-        let mut out = inp;
+    for_each_coord(operands, |coord| {
+        let inp = *coord;
         for index in 0..dimensionality {
-            out[index] = inp[pos[index]] * sgn[index];
+            coord[index] = inp[pos[index]] * sgn[index];
         }
-        operands.set_coord(i, &out);
-        successes += 1;
-    }
-
-    successes
+        true
+    })
 }
 
 // ----- I N V E R S E -----------------------------------------------------------------
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let n = operands.len();
-
     // We default to order=1,2,3,4, so if order is not given, we are done already
     let Ok(order) = op.params.series("order") else {
-        return n;
+        return operands.len();
     };
-
     let dimensionality = order.len();
+    let (pos, sgn) = permutation(order);
 
+    for_each_coord(operands, |coord| {
+        let inp = *coord;
+        for index in 0..dimensionality {
+            coord[pos[index]] = inp[index] * sgn[index];
+        }
+        true
+    })
+}
+
+// The source positions (zero based) and signs of the output axes,
+// from the (validated) one based, signed, `order` series
+fn permutation(order: &[f64]) -> ([usize; 4], [f64; 4]) {
     let mut pos = [0_usize, 1, 2, 3];
     let mut sgn = [1., 1., 1., 1.];
     for (index, value) in order.iter().enumerate() {
         pos[index] = (value.abs() - 1.) as usize;
         sgn[index] = 1_f64.copysign(*value);
     }
-
-    let mut successes = 0_usize;
-    for i in 0..n {
-        let inp = operands.get_coord(i);
-        let mut out = inp;
-        for index in 0..dimensionality {
-            out[pos[index]] = inp[index] * sgn[index];
-        }
-        operands.set_coord(i, &out);
-        successes += 1;
-    }
-
-    successes
+    (pos, sgn)
 }
 
 // ----- C O N S T R U C T O R ---------------------------------------------------------
@@ -190,6 +174,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn enu_ned_and_time_reversal() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // East-North-Up to North-East-Down, and back
+        let op = ctx.op("axisswap order=2,1,-3")?;
+        let mut operands = [Coor4D([1., 2., 3., 4.])];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_eq!(operands[0], Coor4D([2., 1., -3., 4.]));
+        ctx.apply(op, Inv, &mut operands)?;
+        assert_eq!(operands[0], Coor4D([1., 2., 3., 4.]));
+
+        // Also for 3D data, taking the get/set route
+        let mut operands = [Coor3D([1., 2., 3.])];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_eq!(operands[0], Coor3D([2., 1., -3.]));
+
+        // Time reversal, moving the time axis to the front
+        let op = ctx.op("axisswap order=-4,1,2,3")?;
+        let mut operands = [Coor4D([1., 2., 3., 4.])];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_eq!(operands[0], Coor4D([-4., 1., 2., 3.]));
+        ctx.apply(op, Inv, &mut operands)?;
+        assert_eq!(operands[0], Coor4D([1., 2., 3., 4.]));
+        Ok(())
+    }
+
     #[test]
     fn bad_parameters() -> Result<(), Error> {
         let mut ctx = Minimal::default();
@@ -4,8 +4,10 @@ use crate::authoring::*;
 // ----- F O R W A R D -----------------------------------------------------------------
 
 // Forward transverse mercator, following Bowring (1989)
-fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+pub(super) fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let eps = ellps.second_eccentricity_squared();
     let lat_0 = op.params.lat(0).to_radians();
     let lon_0 = op.params.lon(0).to_radians();
@@ -52,8 +54,10 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- I N V E R S E -----------------------------------------------------------------
 
 // Inverse transverse mercator, following Bowring (1989)
-fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+pub(super) fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let eps = ellps.second_eccentricity_squared();
     let lat_0 = op.params.lat(0).to_radians();
     let lon_0 = op.params.lon(0).to_radians();
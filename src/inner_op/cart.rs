@@ -4,24 +4,33 @@ use crate::authoring::*;
 // ----- F O R W A R D --------------------------------------------------------------
 
 fn cart_fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let n = operands.len();
-    let mut successes = 0;
-    let ellps = op.params.ellps(0);
-    for i in 0..n {
-        let mut coord = operands.get_coord(i);
-        coord = ellps.cartesian(&coord);
-        if !coord.0.iter().any(|c| c.is_nan()) {
-            successes += 1;
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    #[cfg(feature = "simd")]
+    if !ellps.is_triaxial() {
+        if let Some(coords) = operands.as_coor4d_mut() {
+            return super::lanes::cart_fwd(coords, &ellps);
         }
-        operands.set_coord(i, &coord);
     }
-    successes
+    for_each_coord(operands, |coord| {
+        *coord = ellps.cartesian(coord);
+        !coord.0.iter().any(|c| c.is_nan())
+    })
 }
 
 // ----- I N V E R S E --------------------------------------------------------------
 
 fn cart_inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    #[cfg(feature = "simd")]
+    if !ellps.is_triaxial() {
+        if let Some(coords) = operands.as_coor4d_mut() {
+            return super::lanes::cart_inv(coords, &ellps);
+        }
+    }
 
     // eccentricity squared, Fukushima's E, Claessens' c3 = 1-c2`
     let es = ellps.eccentricity_squared();
@@ -36,12 +45,10 @@ fn cart_inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> us
     let ce4 = 1.5 * es * es;
     // if we're closer than this to the Z axis, we force latitude to one of the poles
     let cutoff = ellps.semimajor_axis() * 1e-16;
+    let evolute = Evolute::new(a, b);
 
-    let n = operands.len();
-    let mut successes = 0;
     #[allow(non_snake_case)]
-    for i in 0..n {
-        let mut coord = operands.get_coord(i);
+    for_each_coord(operands, |coord| {
         let X = coord[0];
         let Y = coord[1];
         let Z = coord[2];
@@ -53,6 +60,13 @@ fn cart_inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> us
         // The perpendicular distance from the point coordinate to the Z-axis (HM eq. 5-28)
         let p = X.hypot(Y);
 
+        // Degenerate input: Non-finite, or with ambiguous geographical coordinates
+        if !(p.is_finite() && Z.is_finite()) || evolute.contains(p, Z) {
+            debug!("cart: Cannot convert ({X}, {Y}, {Z}) to geographical coordinates");
+            *coord = Coor4D::raw(f64::NAN, f64::NAN, f64::NAN, t);
+            return false;
+        }
+
         // If we're close to the Z-axis, the full algorithm breaks down. But if
         // we're close to the Z-axis, we also assert that the latitude is close
         // to one of the poles. So we force the latitude to the relevant pole and
@@ -60,9 +74,8 @@ fn cart_inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> us
         if p < cutoff {
             let phi = std::f64::consts::FRAC_PI_2.copysign(Z);
             let h = Z.abs() - b;
-            coord = Coor4D::raw(lam, phi, h, t);
-            operands.set_coord(i, &coord);
-            continue;
+            *coord = Coor4D::raw(lam, phi, h, t);
+            return ![lam, phi, h, t].iter().any(|c| c.is_nan());
         }
 
         let P = ra * p;
@@ -85,14 +98,48 @@ fn cart_inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> us
         let phi = S1.atan2(CC);
         let h = (p * CC.abs() + Z.abs() * S1.abs() - a * CC.hypot(ar * S1)) / CC.hypot(S1);
         // Bowring's height formula works better close to the ellipsoid, but requires a (sin, cos)-pair
-        coord = Coor4D::raw(lam, phi, h, t);
-        operands.set_coord(i, &coord);
+        *coord = Coor4D::raw(lam, phi, h, t);
+        ![lam, phi, h, t].iter().any(|c| c.is_nan())
+    })
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S --------------------------------------
+
+/// The evolute of the meridian ellipse, i.e. the astroid
+/// (a p)^⅔ + (b |Z|)^⅔ = (a² - b²)^⅔, extending roughly 43 km from the
+/// geocenter for the Earth. Points inside it have more than one ellipsoidal
+/// normal through them, so their geographical coordinates are ambiguous.
+/// For the sphere, it degenerates to the center point.
+pub(super) struct Evolute {
+    a: f64,
+    b: f64,
+    limit: f64,
+    // The extent of the astroid along the p and Z axes: (a² - b²)/a and (a² - b²)/b
+    p_max: f64,
+    z_max: f64,
+}
 
-        if ![lam, phi, h, t].iter().any(|c| c.is_nan()) {
-            successes += 1;
+impl Evolute {
+    pub(super) fn new(a: f64, b: f64) -> Evolute {
+        let c2 = a * a - b * b;
+        let limit = c2.cbrt().powi(2);
+        Evolute {
+            a,
+            b,
+            limit,
+            p_max: c2 / a,
+            z_max: c2 / b,
         }
     }
-    successes
+
+    pub(super) fn contains(&self, p: f64, z: f64) -> bool {
+        // Cheap bounding box check first, so the roots are only computed
+        // for points very close to the geocenter
+        if p > self.p_max || z.abs() > self.z_max {
+            return false;
+        }
+        (self.a * p).cbrt().powi(2) + (self.b * z.abs()).cbrt().powi(2) <= self.limit
+    }
 }
 
 // ----- C O N S T R U C T O R ------------------------------------------------------
@@ -103,6 +150,17 @@ pub const GAMUT: [OpParameter; 2] = [
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 ];
 
+// Geographic/geocentric conversions, EPSG method 9602
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 1] = [OpTestVector {
+    source: "EPSG Guidance Note 7-2, Geographic/geocentric conversions (9602)",
+    definition: "geo:in | cart ellps=WGS84",
+    input: [53. + 48. / 60. + 33.82 / 3600., 2. + 7. / 60. + 46.38 / 3600., 73., 0.],
+    output: [3_771_793.968, 140_253.342, 5_124_304.349, 0.],
+    tolerance: [1e-3, 1e-3, 1e-3, 0.],
+    inverse_tolerance: [1e-8, 1e-8, 1e-3, 0.],
+}];
+
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     Op::plain(
         parameters,
@@ -185,4 +243,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn degenerate() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let op = ctx.op("cart")?;
+
+        // On the rotation axis: Well defined, outside of the evolute
+        let mut operands = [
+            Coor4D::raw(0., 0., 6_356_752.314_140_347, 0.),
+            Coor4D::raw(0., 0., -100_000., 0.),
+            Coor4D::raw(1e-12, 0., 1e7, 0.),
+        ];
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 3);
+        assert_eq!(operands[0][1], std::f64::consts::FRAC_PI_2);
+        assert!(operands[0][2].abs() < 1e-9);
+        assert_eq!(operands[1][1], -std::f64::consts::FRAC_PI_2);
+        assert!((operands[1][2] + 6_256_752.314_140_347).abs() < 1e-6);
+
+        // Close to the geocenter, and non-finite input: Rejected
+        let mut operands = [
+            Coor4D::raw(0., 0., 0., 0.),
+            Coor4D::raw(1., 1., 1., 0.),
+            Coor4D::raw(10_000., 0., -10_000., 0.),
+            Coor4D::raw(f64::INFINITY, 0., 0., 0.),
+            Coor4D::raw(f64::NAN, 0., 0., 0.),
+        ];
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 0);
+        assert!(operands.iter().all(|c| c[1].is_nan() && c[2].is_nan()));
+
+        // The tips of the evolute: (a² - b²)/a = 42_697.67 m along the equatorial
+        // plane, and (a² - b²)/b = 42_841.31 m along the rotation axis
+        let mut operands = [
+            Coor4D::raw(42_697., 0., 0., 0.),
+            Coor4D::raw(0., 0., -42_841., 0.),
+            Coor4D::raw(42_698., 0., 0., 0.),
+            Coor4D::raw(0., 0., -42_842., 0.),
+        ];
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 2);
+        assert!(operands[0][1].is_nan() && operands[1][1].is_nan());
+        assert_eq!(operands[2][1], 0.);
+        assert_eq!(operands[3][1], -std::f64::consts::FRAC_PI_2);
+
+        // Just outside of the evolute, and at extreme heights: Roundtrips
+        let geo = [
+            Coor4D::geo(0., 10., -6_330_000., 0.),
+            Coor4D::geo(89.999_999, 10., -6_000_000., 0.),
+            Coor4D::geo(45., 10., 1e9, 0.),
+            Coor4D::geo(-45., 10., 1e12, 0.),
+        ];
+        let mut operands = geo;
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 4);
+        for i in 0..operands.len() {
+            assert!((operands[i][1] - geo[i][1]).abs() < 1e-11);
+            assert!((operands[i][2] - geo[i][2]).abs() < 1e-9 * geo[i][2].abs().max(1.));
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,115 @@
+/// Clip the coordinates into a geographical bounding box and/or into ranges
+/// of the individual coordinate dimensions. Useful for sanitizing input
+/// streams before projection
+use super::select::{constrained, ranges};
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+// Clipping is idempotent, so the forward and inverse operations are identical
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let (Ok(lo), Ok(hi)) = (op.params.series("lo"), op.params.series("hi")) else {
+        return 0;
+    };
+
+    // Only the constrained dimensions count: The others may be NaN for
+    // coordinate types with fewer than 4 dimensions
+    for_each_coord(operands, |coord| {
+        for i in 0..4 {
+            coord[i] = coord[i].clamp(lo[i], hi[i]);
+        }
+        (0..4)
+            .filter(|&i| constrained(lo[i], hi[i]))
+            .all(|i| !coord[i].is_nan())
+    })
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 6] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Series { key: "bbox", default: Some("") },
+    OpParameter::Series { key: "x", default: Some("") },
+    OpParameter::Series { key: "y", default: Some("") },
+    OpParameter::Series { key: "z", default: Some("") },
+    OpParameter::Series { key: "t", default: Some("") },
+];
+
+pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+    let (mut lo, mut hi) = ranges(&params)?;
+
+    // The bounding box is given in degrees as west, south, east, north, and
+    // narrows the ranges of the first two coordinates. Unlike for `select`,
+    // boxes crossing the antimeridian are not supported
+    if let Ok(bbox) = params.series("bbox") {
+        if bbox.len() != 4 || bbox[0] > bbox[2] || bbox[1] > bbox[3] {
+            return Err(Error::BadParam("bbox".to_string(), format!("{bbox:?}")));
+        }
+        lo[0] = lo[0].max(bbox[0].to_radians());
+        lo[1] = lo[1].max(bbox[1].to_radians());
+        hi[0] = hi[0].min(bbox[2].to_radians());
+        hi[1] = hi[1].min(bbox[3].to_radians());
+        if lo[0] > hi[0] || lo[1] > hi[1] {
+            return Err(Error::BadParam("bbox".to_string(), format!("{bbox:?}")));
+        }
+    }
+
+    params.series.insert("lo", Vec::from(lo));
+    params.series.insert("hi", Vec::from(hi));
+
+    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(fwd)));
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Clip latitudes beyond ±90°, and negative heights
+        let op = ctx.op("clamp bbox=-180,-90,180,90 z=0,10000")?;
+        let mut data = [
+            Coor4D::geo(55., 12., 100., 0.),
+            Coor4D::geo(95., 12., -5., 0.),
+        ];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert_eq!(data[0], Coor4D::geo(55., 12., 100., 0.));
+        assert_eq!(data[1], Coor4D::geo(90., 12., 0., 0.));
+
+        // The inverse is identical to the forward operation
+        let op = ctx.op("clamp x=0,1")?;
+        let mut data = [Coor4D::raw(-1., 0., 0., 0.), Coor4D::raw(2., 0., 0., 0.)];
+        assert_eq!(ctx.apply(op, Inv, &mut data)?, 2);
+        assert_eq!(data[0][0], 0.);
+        assert_eq!(data[1][0], 1.);
+
+        // 2D coordinates (with NaN for the missing dimensions)
+        let op = ctx.op("clamp bbox=-180,-90,180,90")?;
+        let mut data = [Coor2D::geo(55., 12.), Coor2D::geo(95., 12.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert_eq!(data[0], Coor2D::geo(55., 12.));
+        assert_eq!(data[1], Coor2D::geo(90., 12.));
+
+        // Malformed boxes and ranges
+        assert!(ctx.op("clamp bbox=170,-20,-170,20").is_err());
+        assert!(ctx.op("clamp x=1,0").is_err());
+        assert!(ctx.op("clamp bbox=0,0,10,10 x=1,2").is_err());
+        Ok(())
+    }
+}
@@ -6,13 +6,17 @@ use crate::authoring::*;
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let n = operands.len();
     let sliced = 0..n;
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
 
     let prime = op.params.boolean("prime");
     let meridional = op.params.boolean("meridian");
     let gaussian = op.params.boolean("gaussian");
     let mean = op.params.boolean("mean");
     let azimuthal = op.params.boolean("azimuthal");
+    let gaussian_curvature = op.params.boolean("gaussian_curvature");
+    let mean_curvature = op.params.boolean("mean_curvature");
 
     let mut successes = 0_usize;
 
@@ -39,10 +43,7 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     if gaussian {
         for i in sliced {
             let (lat, lon) = operands.xy(i);
-            let lat = lat.to_radians();
-            let m = ellps.meridian_radius_of_curvature(lat);
-            let n = ellps.prime_vertical_radius_of_curvature(lat);
-            let lat = (n * m).sqrt();
+            let lat = ellps.gaussian_radius_of_curvature(lat.to_radians());
             operands.set_xy(i, lat, lon);
             successes += 1;
         }
@@ -52,10 +53,27 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     if mean {
         for i in sliced {
             let (lat, lon) = operands.xy(i);
-            let lat = lat.to_radians();
-            let m = ellps.meridian_radius_of_curvature(lat);
-            let n = ellps.prime_vertical_radius_of_curvature(lat);
-            let lat = 2.0 * (n.recip() + m.recip()).recip();
+            let lat = ellps.mean_radius_of_curvature(lat.to_radians());
+            operands.set_xy(i, lat, lon);
+            successes += 1;
+        }
+        return successes;
+    }
+
+    if gaussian_curvature {
+        for i in sliced {
+            let (lat, lon) = operands.xy(i);
+            let lat = ellps.gaussian_curvature(lat.to_radians());
+            operands.set_xy(i, lat, lon);
+            successes += 1;
+        }
+        return successes;
+    }
+
+    if mean_curvature {
+        for i in sliced {
+            let (lat, lon) = operands.xy(i);
+            let lat = ellps.mean_curvature(lat.to_radians());
             operands.set_xy(i, lat, lon);
             successes += 1;
         }
@@ -65,10 +83,7 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     if azimuthal {
         for i in sliced {
             let (lat, azi) = operands.xy(i).xy_to_radians();
-            let m = ellps.meridian_radius_of_curvature(lat);
-            let n = ellps.prime_vertical_radius_of_curvature(lat);
-            let (s, c) = azi.sin_cos();
-            let lat = (c * c / m + s * s / n).recip();
+            let lat = ellps.azimuthal_radius_of_curvature(lat, azi);
             operands.set_xy(i, lat, azi);
             successes += 1;
         }
@@ -82,12 +97,14 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
 // Example...
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 6] = [
+pub const GAMUT: [OpParameter; 8] = [
     OpParameter::Flag { key: "prime" },
     OpParameter::Flag { key: "meridian" },
     OpParameter::Flag { key: "gaussian" },
     OpParameter::Flag { key: "mean" },
     OpParameter::Flag { key: "azimuthal" },
+    OpParameter::Flag { key: "gaussian_curvature" },
+    OpParameter::Flag { key: "mean_curvature" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") }
 ];
 
@@ -97,19 +114,13 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
 
     for parameter in GAMUT {
         number_of_flags += match parameter {
-            OpParameter::Flag { key } => {
-                if op.params.boolean(key) {
-                    1
-                } else {
-                    0
-                }
-            }
+            OpParameter::Flag { key } if op.params.boolean(key) => 1,
             _ => 0,
         }
     }
     if number_of_flags != 1 {
         return Err(Error::MissingParam(
-            "curvature: must specify exactly one of flags prime/meridian/gaussian/mean/azimuthal"
+            "curvature: must specify exactly one of flags prime/meridian/gaussian/mean/azimuthal/gaussian_curvature/mean_curvature"
                 .to_string(),
         ));
     }
@@ -142,7 +153,7 @@ mod tests {
 
         // Unknown ellipsoid name
         let op = ctx.op("curvature ellps=non_existing meridian");
-        assert!(matches!(op, Err(Error::NotFound(_, _))));
+        assert!(matches!(op, Err(Error::BadParam(_, _))));
 
         // Regression test: Curvatures for a random range of latitudes
         let latitudes = [
@@ -234,6 +245,24 @@ mod tests {
             assert!((m - meridian_radii_of_curvature[i]).abs() < 1e-9);
         }
 
+        // Gaussian and mean curvature
+        let op = ctx.op("curvature gaussian_curvature ellps=GRS80")?;
+        let mut operands: Vec<Coor2D> = latitudes.iter().map(|&lat| Coor2D([lat, 0.0])).collect();
+        ctx.apply(op, Fwd, &mut operands)?;
+        for (i, coord) in operands.iter().enumerate() {
+            let mn = prime_vertical_radii_of_curvature[i] * meridian_radii_of_curvature[i];
+            assert!((coord[0] * mn - 1.).abs() < 1e-12);
+        }
+
+        let op = ctx.op("curvature mean_curvature ellps=GRS80")?;
+        let mut operands: Vec<Coor2D> = latitudes.iter().map(|&lat| Coor2D([lat, 0.0])).collect();
+        ctx.apply(op, Fwd, &mut operands)?;
+        for (i, coord) in operands.iter().enumerate() {
+            let m = meridian_radii_of_curvature[i];
+            let n = prime_vertical_radii_of_curvature[i];
+            assert!((coord[0] - (1. / m + 1. / n) / 2.).abs() < 1e-20);
+        }
+
         Ok(())
     }
 }
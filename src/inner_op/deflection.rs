@@ -1,18 +1,23 @@
 /// Estimate deflection of the vertical from a geoid model.
 /// Mostly for manual look-ups, so it takes input in degrees and conventional
-/// nautical latitude-longitude order, and provides output in arcsec in the
-/// corresponding (ξ, η) order.
+/// nautical latitude-longitude order, and provides output in arcsec (or
+/// radians) in the corresponding (ξ, η) order.
 ///
 /// Note that this is mostly for order-of-magnitude considerations:
 /// Typically observations of deflections of the vertical are input
 /// data for geoid determination, not the other way round, as here.
 use crate::authoring::*;
+use std::sync::Arc;
 
 // ----- F O R W A R D --------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let grids = &op.params.grids;
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    let cells = op.params.natural("cells").unwrap_or(0);
+    let radians = op.params.text("units").is_ok_and(|units| units == "rad");
 
     let mut successes = 0_usize;
     let n = operands.len();
@@ -29,49 +34,98 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         coord[0] = lon;
         coord[1] = lat;
 
-        // The latitude step corresponding to a 1 m linear step along the local meridian
-        let lat_dist = ellps.meridian_latitude_to_distance(lat);
-        let dlat = ellps.meridian_distance_to_latitude(lat_dist + 1.0) - lat;
-
-        // The longitude step corresponding to a 1 m linear step along the local parallel
-        let dlon = (lat.cos() * ellps.prime_vertical_radius_of_curvature(lat)).recip();
-
         let Some(origin) = grids_at(grids, &coord, false) else {
             operands.set_coord(i, &Coor4D::nan());
             continue;
         };
 
-        coord[1] += dlat;
-        let Some(lat_1) = grids_at(grids, &coord, false) else {
-            operands.set_coord(i, &Coor4D::nan());
-            continue;
+        // The metric lengths of a unit step in latitude and longitude
+        let m = ellps.meridian_radius_of_curvature(lat);
+        let n = lat.cos() * ellps.prime_vertical_radius_of_curvature(lat);
+
+        // The stencil: Either a number of grid cells, or a 1 m linear step
+        let (dlat, dlon) = match resolution(grids, &coord) {
+            Some((dlat, dlon)) if cells > 0 => (cells as f64 * dlat, cells as f64 * dlon),
+            _ => (m.recip(), n.recip()),
         };
-        coord[1] = lat;
-        coord[0] += dlon;
-        let Some(lon_1) = grids_at(grids, &coord, false) else {
+
+        let north = gradient(grids, &coord, origin[0], [0., dlat], dlat * m);
+        let east = gradient(grids, &coord, origin[0], [dlon, 0.], dlon * n);
+        let (Some(north), Some(east)) = (north, east) else {
             operands.set_coord(i, &Coor4D::nan());
             continue;
         };
 
-        coord[0] = (lat_1[0] - origin[0]).atan2(1.0); // xi
-        coord[1] = (lon_1[0] - origin[0]).atan2(1.0); // eta
-        operands.set_coord(i, &coord.to_arcsec());
+        coord[0] = north.atan(); // xi
+        coord[1] = east.atan(); // eta
+        if !radians {
+            coord = coord.to_arcsec();
+        }
+        operands.set_coord(i, &coord);
         successes += 1;
     }
     successes
 }
 
+// The geoid slope at `coord`, in the direction of `step`, which covers the
+// linear distance `distance`. Central differences where possible, and one
+// sided differences where the stencil extends beyond the grid coverage
+fn gradient(
+    grids: &[Arc<dyn Grid>],
+    coord: &Coor4D,
+    origin: f64,
+    step: [f64; 2],
+    distance: f64,
+) -> Option<f64> {
+    let sample = |sign: f64| {
+        let mut at = *coord;
+        at[0] += sign * step[0];
+        at[1] += sign * step[1];
+        grids
+            .iter()
+            .find_map(|grid| grid.at(&at, 0.0))
+            .map(|v| v[0])
+    };
+    match (sample(1.), sample(-1.)) {
+        (Some(ahead), Some(behind)) => Some((ahead - behind) / (2. * distance)),
+        (Some(ahead), None) => Some((ahead - origin) / distance),
+        (None, Some(behind)) => Some((origin - behind) / distance),
+        (None, None) => None,
+    }
+}
+
+// The resolution, in radians, of the (sub)grid containing `coord`, if known
+fn resolution(grids: &[Arc<dyn Grid>], coord: &Coor4D) -> Option<(f64, f64)> {
+    let grid = grids.iter().find(|grid| grid.contains(coord, 0.5))?;
+    let info = grid.info();
+    let (lon, lat) = (coord[0], coord[1]);
+    let within = |i: &&GridInfo| {
+        let (s, n) = (i.lat_s.min(i.lat_n), i.lat_s.max(i.lat_n));
+        let (w, e) = (i.lon_w.min(i.lon_e), i.lon_w.max(i.lon_e));
+        (s..=n).contains(&lat) && (w..=e).contains(&lon)
+    };
+    // The innermost subgrid containing the point, i.e. the last one
+    let info = info.iter().rfind(within).or(info.first())?;
+    Some((info.dlat.abs(), info.dlon.abs()))
+}
+
 // ----- C O N S T R U C T O R ------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 2] = [
+pub const GAMUT: [OpParameter; 4] = [
     OpParameter::Texts { key: "grids", default: None },
+    OpParameter::Natural { key: "cells", default: Some(0) },
+    OpParameter::Text { key: "units", default: Some("arcsec") },
     OpParameter::Text { key: "ellps", default: Some("GRS80") }
 ];
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+    let units = params.text("units")?;
+    if units != "arcsec" && units != "rad" {
+        return Err(Error::BadParam("units".to_string(), units));
+    }
 
     for mut grid_name in params.texts("grids")?.clone() {
         let optional = grid_name.starts_with('@');
@@ -122,8 +176,31 @@ mod tests {
         let mut data = [cph];
 
         ctx.apply(op, Fwd, &mut data)?;
-        assert!((data[0][0] - 1.8527755901425906).abs() < 1e-6);
-        assert!((data[0][1] - 0.032238719594433175).abs() < 1e-6);
+        // The test geoid is linear in longitude, so central differences give
+        // the exact east-west slope of 0.01 m per degree
+        assert!((data[0][0] - 1.852_841_045_286_196_8).abs() < 1e-6);
+        assert!((data[0][1] - 0.032_232_570_671_323).abs() < 1e-6);
+
+        // Radians
+        let op = ctx.op("deflection grids=test.geoid units=rad")?;
+        let mut data = [cph];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - 1.852_841_045_286_196_8_f64.to_radians() / 3600.).abs() < 1e-11);
+        assert!(ctx
+            .op("deflection grids=test.geoid units=furlongs")
+            .is_err());
+
+        // A stencil of grid cells: Smoother, but same order of magnitude
+        let op = ctx.op("deflection grids=test.geoid cells=1")?;
+        let mut data = [cph];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - 1.85).abs() < 0.5);
+
+        // At the grid edges, we fall back to one sided differences
+        let op = ctx.op("deflection grids=test.geoid cells=2")?;
+        let mut data = [Coor4D::raw(54., 12., 0., 0.), Coor4D::raw(58., 8., 0., 0.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert!(data[0][0].is_finite() && data[1][1].is_finite());
         Ok(())
     }
 }
@@ -41,6 +41,14 @@
 ///
 /// - T1 is the observation epoch of the input coordinate tuple (X, Y, Z)
 ///
+/// #### The time span
+///
+/// The time span, T1 - T0, is either given directly, as a fixed interval,
+/// by the `dt` parameter, or computed from the observation epoch, T1, given
+/// by the 4th coordinate element, and the frame epoch, T0, given by the
+/// `t_epoch` parameter. The two parameters are mutually exclusive. When using
+/// `t_epoch`, coordinates without an observation epoch are stomped on with NaN.
+///
 /// #### The transformation
 ///
 /// While you may obtain the deformation vector and its Euclidean norm
@@ -64,14 +72,18 @@
 /// frame. In other words, we want to remove the deformation effect such
 /// that *no matter when* we go and re-survey a given point, we will always
 /// obtain the same coordinate tuple, after transforming the observed
-/// coordinates back in time to the frame epoch. Hence, for the forward
-/// transformation we must *remove* the effect of the deformation by negating
-/// the sign of the deformation terms in eq. 2:
+/// coordinates back in time to the frame epoch. Hence, we must *remove* the
+/// effect of the deformation by negating the sign of the deformation terms
+/// in eq. 2:
 ///
 /// |         X'   =   X - DX   =   X - (T1 - T0) * Vx(φ, λ)
 /// |   (3)   Y'   =   Y - DY   =   Y - (T1 - T0) * Vy(φ, λ)
 /// |         Z'   =   Z - DZ   =   Z - (T1 - T0) * Vz(φ, λ)
 ///
+/// Following PROJ, the *forward* operation implements eq. 2, and the *inverse*
+/// operation implements eq. 3. So going from observed to canonical coordinates
+/// is written `deformation inv t_epoch=...`.
+///
 /// In order to be able to discuss the remaining intricacies of the task, we
 /// now introduce the designations *observed coordinates* for (X, Y, Z), and
 /// *canonical coordinates* for (X', Y', Z').
@@ -118,69 +130,28 @@ use crate::authoring::*;
 // ----- F O R W A R D --------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let grids = &op.params.grids;
-    let mut successes = 0_usize;
-    let n = operands.len();
-
-    let dt = op.params.real("dt").unwrap();
-    let epoch = op.params.real("t_epoch").unwrap();
-    let ellps = op.params.ellps(0);
-    let raw = op.params.boolean("raw");
-    let use_null_grid = op.params.boolean("null_grid");
-
-    // Datum shift
-    'points: for i in 0..n {
-        let cart = operands.get_coord(i);
-        let geo = ellps.geographic(&cart);
-        for margin in [0.0, 0.5] {
-            for grid in grids.iter() {
-                // Interpolated deformation velocity
-                if let Some(v) = grid.at(&geo, margin) {
-                    // The deformation duration may be given either as a fixed duration or
-                    // as the difference between the frame epoch and the observation epoch
-                    let d = if dt.is_finite() { dt } else { epoch - geo[3] };
-
-                    let deformation =
-                        rotate_and_integrate_velocity(v.scale(-1.), geo[0], geo[1], d);
-
-                    // Finally apply the deformation to the input coordinate - or just
-                    // provide the raw correction if that was what was requested
-                    if raw {
-                        let mut deformation_with_length = deformation;
-                        deformation_with_length[3] = deformation.dot(deformation).sqrt();
-                        operands.set_coord(i, &deformation_with_length);
-                    } else {
-                        operands.set_coord(i, &(cart + deformation));
-                    }
-                    successes += 1;
-
-                    // We've found the grid that contains the point, so we can move on
-                    continue 'points;
-                }
-            }
-        }
-
-        if use_null_grid {
-            successes += 1;
-            continue;
-        }
-
-        // No grid found so we stomp on the coordinate
-        operands.set_coord(i, &Coor4D::nan());
-    }
-    successes
+    deformation_common(op, operands, 1.)
 }
 
 // ----- I N V E R S E --------------------------------------------------------------
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    deformation_common(op, operands, -1.)
+}
+
+// ----- C O M M O N ----------------------------------------------------------------
+
+// The forward and inverse cases differ only in the sign of the deformation
+fn deformation_common(op: &Op, operands: &mut dyn CoordinateSet, sign: f64) -> usize {
     let grids = &op.params.grids;
     let mut successes = 0_usize;
     let n = operands.len();
 
     let dt = op.params.real("dt").unwrap();
     let epoch = op.params.real("t_epoch").unwrap();
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let raw = op.params.boolean("raw");
     let use_null_grid = op.params.boolean("null_grid");
 
@@ -188,15 +159,20 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     'points: for i in 0..n {
         let cart = operands.get_coord(i);
         let geo = ellps.geographic(&cart);
+
+        // The deformation duration may be given either as a fixed duration or
+        // as the difference between the observation epoch and the frame epoch
+        let d = if dt.is_finite() { dt } else { geo[3] - epoch };
+        if d.is_nan() {
+            operands.set_coord(i, &Coor4D::nan());
+            continue;
+        }
+
         for margin in [0.0, 0.5] {
             for grid in grids.iter() {
                 // Interpolated deformation velocity
                 if let Some(v) = grid.at(&geo, margin) {
-                    // The deformation duration may be given either as a fixed duration or
-                    // as the difference between the frame epoch and the observation epoch
-                    let d = if dt.is_finite() { dt } else { epoch - geo[3] };
-
-                    let deformation = rotate_and_integrate_velocity(v, geo[0], geo[1], d);
+                    let deformation = rotate_and_integrate_velocity(v, geo[0], geo[1], sign * d);
 
                     // Finally apply the deformation to the input coordinate - or just
                     // provide the raw correction if that was what was requested
@@ -244,11 +220,19 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
 
-    if params.real("dt")?.is_nan() && params.real("t_epoch")?.is_nan() {
+    let dt = params.real("dt")?;
+    let t_epoch = params.real("t_epoch")?;
+    if dt.is_nan() && t_epoch.is_nan() {
         return Err(Error::MissingParam(
             "- either t_epoch or dt must be given".to_string(),
         ));
     }
+    if !dt.is_nan() && !t_epoch.is_nan() {
+        return Err(Error::BadParam(
+            "dt".to_string(),
+            "- dt and t_epoch are mutually exclusive".to_string(),
+        ));
+    }
 
     for mut grid_name in params.texts("grids")?.clone() {
         let optional = grid_name.starts_with('@');
@@ -269,6 +253,14 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
                         found: n.to_string(),
                     });
                 }
+                // Velocities are in linear units per year
+                if grid.units().is_angular() {
+                    return Err(Error::Unexpected {
+                        message: "Bad units of deformation model grid".to_string(),
+                        expected: "linear".to_string(),
+                        found: format!("{:?}", grid.units().unit),
+                    });
+                }
                 params.grids.push(grid);
             }
 
@@ -428,4 +420,47 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn time_span() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let test_deformation = include_str!("../../geodesy/deformation/test.deformation");
+        ctx.register_resource("test.deformation", test_deformation);
+        let ellps = Ellipsoid::default();
+        let cph = ellps.cartesian(&Coor4D::geo(55., 12., 0., 2010.));
+
+        // Following PROJ, the forward operation adds dt times the velocity
+        let op = ctx.op("deformation raw dt=10 grids=test.deformation")?;
+        let mut data = [cph];
+        ctx.apply(op, Fwd, &mut data)?;
+        let grid = BaseGrid::gravsoft(&ctx.get_blob("test.deformation")?)?;
+        let v = grid.at(&ellps.geographic(&cph), 0.0).unwrap();
+        let expected =
+            rotate_and_integrate_velocity(v, 12_f64.to_radians(), 55_f64.to_radians(), 10.);
+        assert!(data[0].hypot3(&expected) < 1e-9);
+
+        // ... where dt corresponds to the observation epoch minus t_epoch
+        let fixed = ctx.op("deformation dt=10 grids=test.deformation")?;
+        let epoch = ctx.op("deformation t_epoch=2000 grids=test.deformation")?;
+        let mut a = [cph];
+        let mut b = [cph];
+        ctx.apply(fixed, Fwd, &mut a)?;
+        ctx.apply(epoch, Fwd, &mut b)?;
+        assert!(a[0].hypot3(&b[0]) < 1e-9);
+        ctx.apply(fixed, Inv, &mut a)?;
+        ctx.apply(epoch, Inv, &mut b)?;
+        assert!(a[0].hypot3(&b[0]) < 1e-9);
+
+        // Points without an observation epoch are stomped on
+        let mut data = [Coor4D([cph[0], cph[1], cph[2], f64::NAN])];
+        assert_eq!(ctx.apply(epoch, Fwd, &mut data)?, 0);
+        assert!(data[0][0].is_nan());
+
+        // dt and t_epoch are mutually exclusive
+        assert!(ctx
+            .op("deformation dt=10 t_epoch=2000 grids=test.deformation")
+            .is_err());
+        assert!(ctx.op("deformation grids=test.deformation").is_err());
+        Ok(())
+    }
 }
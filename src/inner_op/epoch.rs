@@ -0,0 +1,189 @@
+/// Epoch arithmetic: Set, shift, or convert the time coordinate. The time
+/// coordinate is converted from the `from` unit to decimal years, assigned
+/// the value `t_obs` (if given), shifted by `dt` years, and converted to the
+/// `to` unit. The inverse operation reverses the shift and the conversion,
+/// but cannot undo the assignment.
+///
+/// The time units supported are decimal years (`year`), Modified Julian
+/// Dates (`mjd`), and (fractional) GPS weeks (`gpsweek`). The conversions
+/// ignore the differences between time scales, i.e. leap seconds
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let (Ok(from), Ok(to)) = (op.params.text("from"), op.params.text("to")) else {
+        return 0;
+    };
+    let t_obs = op.params.real("t_obs").unwrap_or(f64::NAN);
+    let dt = op.params.real("dt").unwrap_or(0.);
+
+    for_each_coord(operands, |coord| {
+        let mut t = coord[3];
+        if !t_obs.is_nan() {
+            t = convert(t_obs, "year", &from);
+        }
+        t = shift(t, &from, dt);
+        coord[3] = convert(t, &from, &to);
+        true
+    })
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let (Ok(from), Ok(to)) = (op.params.text("from"), op.params.text("to")) else {
+        return 0;
+    };
+    let dt = op.params.real("dt").unwrap_or(0.);
+
+    for_each_coord(operands, |coord| {
+        let t = convert(coord[3], &to, &from);
+        coord[3] = shift(t, &from, -dt);
+        true
+    })
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 5] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Real { key: "t_obs", default: Some(f64::NAN) },
+    OpParameter::Real { key: "dt", default: Some(0.) },
+    OpParameter::Text { key: "from", default: Some("year") },
+    OpParameter::Text { key: "to", default: Some("year") },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    for key in ["from", "to"] {
+        let unit = op.params.text(key)?;
+        if !TIME_UNITS.contains(&unit.as_str()) {
+            return Err(Error::BadParam(key.to_string(), unit));
+        }
+    }
+    Ok(op)
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+
+const TIME_UNITS: [&str; 3] = ["year", "mjd", "gpsweek"];
+
+// The Modified Julian Date of the GPS epoch, 1980-01-06
+const GPS_EPOCH: f64 = 44244.;
+
+/// Convert `t` from the time unit `from` to the time unit `to`
+fn convert(t: f64, from: &str, to: &str) -> f64 {
+    if from == to {
+        return t;
+    }
+    let mjd = match from {
+        "year" => year_to_mjd(t),
+        "gpsweek" => GPS_EPOCH + 7. * t,
+        _ => t,
+    };
+    match to {
+        "year" => mjd_to_year(mjd),
+        "gpsweek" => (mjd - GPS_EPOCH) / 7.,
+        _ => mjd,
+    }
+}
+
+/// Shift `t`, given in `unit`, by `dt` years
+fn shift(t: f64, unit: &str, dt: f64) -> f64 {
+    if dt == 0. {
+        return t;
+    }
+    convert(convert(t, unit, "year") + dt, "year", unit)
+}
+
+/// The Modified Julian Date of January 1st of `year` (proleptic Gregorian),
+/// following Howard Hinnant's `days_from_civil` algorithm
+fn new_year_mjd(year: i64) -> f64 {
+    // Counting years from March 1st, January belongs to the previous year
+    let y = year - 1;
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + 306;
+    // 719468 days from 0000-03-01 to 1970-01-01, which is MJD 40587
+    (era * 146_097 + doe - 719_468 + 40_587) as f64
+}
+
+fn year_to_mjd(year: f64) -> f64 {
+    if !year.is_finite() {
+        return year;
+    }
+    let y = year.floor();
+    let start = new_year_mjd(y as i64);
+    let length = new_year_mjd(y as i64 + 1) - start;
+    start + (year - y) * length
+}
+
+fn mjd_to_year(mjd: f64) -> f64 {
+    if !mjd.is_finite() {
+        return mjd;
+    }
+    let mut y = (2000. + (mjd - 51544.) / 365.2425).floor() as i64;
+    while mjd < new_year_mjd(y) {
+        y -= 1;
+    }
+    while mjd >= new_year_mjd(y + 1) {
+        y += 1;
+    }
+    let start = new_year_mjd(y);
+    y as f64 + (mjd - start) / (new_year_mjd(y + 1) - start)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(new_year_mjd(1970), 40587.);
+        assert_eq!(new_year_mjd(2000), 51544.);
+        assert_eq!(new_year_mjd(1859), 45.);
+        assert_eq!(year_to_mjd(2000.5), 51544. + 183.);
+        assert_eq!(mjd_to_year(51544. + 183.), 2000.5);
+        assert!(convert(1980. + 5. / 366., "year", "gpsweek").abs() < 1e-10);
+        assert_eq!(convert(1024., "gpsweek", "mjd"), 51412.);
+        assert_eq!(shift(51544., "mjd", 1.), 51544. + 366.);
+    }
+
+    #[test]
+    fn epoch() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Shift
+        let op = ctx.op("epoch dt=0.5")?;
+        let mut data = [Coor4D::raw(1., 2., 3., 2020.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1., 2., 3., 2020.5));
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][3], 2020.);
+
+        // Assignment, then shift
+        let op = ctx.op("epoch t_obs=2010 dt=1")?;
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][3], 2011.);
+
+        // Conversion: GPS week 2087 starts 2020-01-05, i.e. MJD 58853
+        let op = ctx.op("epoch from=gpsweek to=mjd")?;
+        let mut data = [Coor4D::raw(0., 0., 0., 2087.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][3], 58853.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][3], 2087.);
+
+        let op = ctx.op("epoch from=mjd")?;
+        let mut data = [Coor4D::raw(0., 0., 0., 58853.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][3] - (2020. + 4. / 366.)).abs() < 1e-10);
+
+        assert!(ctx.op("epoch from=fortnights").is_err());
+        Ok(())
+    }
+}
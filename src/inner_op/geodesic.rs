@@ -1,10 +1,13 @@
-/// Geodesics
+/// Geodesics, using Karney's algorithm - or Vincenty's, if the `vincenty` flag is set
 use crate::authoring::*;
 
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    let vincenty = op.params.boolean("vincenty");
 
     let n = operands.len();
     let sliced = 0..n;
@@ -16,7 +19,12 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         let azimuth = args[2].to_radians();
         let distance = args[3];
 
-        let destination = ellps.geodesic_fwd(&origin, azimuth, distance).to_degrees();
+        let destination = if vincenty {
+            ellps.geodesic_fwd_vincenty(&origin, azimuth, distance)
+        } else {
+            ellps.geodesic_fwd(&origin, azimuth, distance)
+        }
+        .to_degrees();
 
         // No convergence?
         if destination[3] > 990.0 {
@@ -35,8 +43,11 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- I N V E R S E -----------------------------------------------------------------
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let reversible = op.params.boolean("reversible");
+    let vincenty = op.params.boolean("vincenty");
 
     let n = operands.len();
     let sliced = 0..n;
@@ -52,7 +63,12 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         to[0] = coord[3].to_radians();
         to[1] = coord[2].to_radians();
 
-        let mut geodesic = ellps.geodesic_inv(&from, &to).to_degrees();
+        let mut geodesic = if vincenty {
+            ellps.geodesic_inv_vincenty(&from, &to)
+        } else {
+            ellps.geodesic_inv(&from, &to)
+        }
+        .to_degrees();
 
         // No convergence?
         if geodesic[3] > 990.0 {
@@ -79,9 +95,10 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
 // Example...
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 3] = [
+pub const GAMUT: [OpParameter; 4] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Flag { key: "reversible" },
+    OpParameter::Flag { key: "vincenty" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") }
 ];
 
@@ -109,10 +126,10 @@ mod tests {
         ctx.apply(op, Inv, &mut operands)?;
 
         let expected = Coor4D([
-            -130.1540604203936,
-            -138.05257941840648,
-            956066.2319619625,
-            41.94742058159352,
+            -130.15406042036466,
+            -138.05257941838454,
+            956066.2319626831,
+            41.94742058161546,
         ]);
 
         assert!((operands[0][0] - expected[0]).abs() < 1e-9);
@@ -125,7 +142,7 @@ mod tests {
         let mut operands = [cph_cdg];
         ctx.apply(op, Inv, &mut operands)?;
 
-        let expected = Coor4D([49.0, 2.0, 41.94742058159352, 956066.2319619625]);
+        let expected = Coor4D([49.0, 2.0, 41.94742058161546, 956066.2319626831]);
 
         assert!((operands[0][0] - expected[0]).abs() < 1e-9);
         assert!((operands[0][1] - expected[1]).abs() < 1e-9);
@@ -140,6 +157,21 @@ mod tests {
         assert!((operands[0][2] - cph_cdg[2]).abs() < 1e-10);
         assert!((operands[0][3] - cph_cdg[3]).abs() < 1e-10);
 
+        // The Vincenty algorithm agrees to within a micrometer here
+        let op = ctx.op("geodesic vincenty")?;
+        let mut operands = [cph_cdg];
+        ctx.apply(op, Inv, &mut operands)?;
+        let expected = Coor4D([
+            -130.1540604203936,
+            -138.05257941840648,
+            956066.2319619625,
+            41.94742058159352,
+        ]);
+        assert!((operands[0][0] - expected[0]).abs() < 1e-9);
+        assert!((operands[0][1] - expected[1]).abs() < 1e-9);
+        assert!((operands[0][2] - expected[2]).abs() < 1e-9);
+        assert!((operands[0][3] - expected[3]).abs() < 1e-9);
+
         Ok(())
     }
 }
@@ -72,7 +72,9 @@ fn cassinis(operands: &mut dyn CoordinateSet, ellps: &Ellipsoid, zero_height: bo
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let zero_height = op.params.boolean("zero-height");
     let Some(action) = op.params.text.get("action") else {
         return 0;
@@ -1,11 +1,20 @@
 /// Datum shift using grid interpolation.
+///
+/// 1-band grids are geoid models, 2-band grids horizontal datum shifts,
+/// and 3-band grids combined horizontal and vertical datum shifts. The
+/// corrections are interpreted according to the units of the grid: Angular
+/// corrections are applied directly, while linear ones are taken as (east,
+/// north, up) offsets, and converted to angular corrections, unless the grid
+/// is in projected coordinates (e.g. OSTN15), where they are applied directly
 use crate::authoring::*;
+use std::sync::Arc;
 
 // ----- F O R W A R D --------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let grids = &op.params.grids;
-    let use_null_grid = op.params.boolean("null_grid");
+    let ellps = op.params.ellps(0).ok();
+    let null_grid = op.params.boolean("null_grid");
 
     let mut successes = 0_usize;
     let n = operands.len();
@@ -18,7 +27,7 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     for i in 0..n {
         let mut coord = operands.get_coord(i);
 
-        if let Some(d) = grids_at(grids, &coord, use_null_grid) {
+        if let Some(d) = correction(grids, ellps.as_ref(), null_grid, &coord) {
             // Geoid
             if grids[0].bands() == 1 {
                 coord[2] -= d[0];
@@ -31,6 +40,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
             // Datum shift
             coord[0] += d[0];
             coord[1] += d[1];
+            if grids[0].bands() == 3 {
+                coord[2] += d[2];
+            }
             operands.set_coord(i, &coord);
             successes += 1;
 
@@ -48,7 +60,19 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let grids = &op.params.grids;
-    let use_null_grid = op.params.boolean("null_grid");
+    let maxiter = op.params.natural("maxiter").unwrap_or(10);
+    let ellps = op.params.ellps(0).ok();
+    let null_grid = op.params.boolean("null_grid");
+
+    // The tolerance is given in meters, so for geographical grids, we
+    // convert it to radians
+    let mut tol = op.params.real("tol").unwrap_or(1e-5);
+    if !grids.is_empty() && !grids[0].is_projected() {
+        let Some(ellps) = &ellps else {
+            return 0;
+        };
+        tol /= ellps.semimajor_axis();
+    }
 
     let mut successes = 0_usize;
     let n = operands.len();
@@ -60,7 +84,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
     'points: for i in 0..n {
         let mut coord = operands.get_coord(i);
-        if let Some(t) = grids_at(grids, &coord, use_null_grid) {
+        if let Some(t) = correction(grids, ellps.as_ref(), null_grid, &coord) {
             // Geoid
             if grids[0].bands() == 1 {
                 coord[2] += t[0];
@@ -71,11 +95,11 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
             // Inverse case datum shift - iteration needed
             let mut t = coord - t;
-            for _ in 0..10 {
-                if let Some(t2) = grids_at(grids, &t, use_null_grid) {
+            for _ in 0..maxiter {
+                if let Some(t2) = correction(grids, ellps.as_ref(), null_grid, &t) {
                     let d = t - coord + t2;
                     t = t - d;
-                    if d[0].hypot(d[1]) < 1e-12 {
+                    if d[0].hypot(d[1]) < tol {
                         operands.set_coord(i, &t);
                         successes += 1;
                         continue 'points;
@@ -83,30 +107,68 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
                     continue;
                 }
 
-                // The iteration has wandered off the grids, so we stomp
-                // on the coordinate and go on with the next
-                operands.set_coord(i, &Coor4D::nan());
-                continue 'points;
+                // The iteration has wandered off the grids
+                break;
             }
         }
+
+        // No grid contained the point, or the iteration did not converge,
+        // so we stomp on the coordinate
+        operands.set_coord(i, &Coor4D::nan());
     }
 
     successes
 }
 
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+
+// The grid correction at `coord`. For geographical grids in linear units,
+// the (east, north, up) offsets in meters are converted to (longitude,
+// latitude) corrections in radians, and a height correction in meters
+fn correction(
+    grids: &[Arc<dyn Grid>],
+    ellps: Option<&Ellipsoid>,
+    null_grid: bool,
+    coord: &Coor4D,
+) -> Option<Coor4D> {
+    let (d, units) = grids_at_with_units(grids, coord, null_grid)?;
+    if grids[0].bands() == 1 || units.is_angular() || grids[0].is_projected() {
+        return Some(d);
+    }
+
+    let ellps = ellps?;
+    let (lat, h) = (coord[1], coord[2]);
+    let h = if h.is_finite() { h } else { 0. };
+    let dlon = d[0] / ((ellps.prime_vertical_radius_of_curvature(lat) + h) * lat.cos());
+    let dlat = d[1] / (ellps.meridian_radius_of_curvature(lat) + h);
+    Some(Coor4D::raw(dlon, dlat, d[2], 0.))
+}
+
 // ----- C O N S T R U C T O R ------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 3] = [
+pub const GAMUT: [OpParameter; 6] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Texts { key: "grids", default: None },
+    OpParameter::Text { key: "ellps", default: Some("GRS80") },
     OpParameter::Real { key: "padding", default: Some(0.5) },
+    OpParameter::Real { key: "tol", default: Some(1e-5) },
+    OpParameter::Natural { key: "maxiter", default: Some(10) },
 ];
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
 
+    // Convergence criteria for the iterative inverse
+    let tol = params.real("tol")?;
+    if tol.is_nan() || tol <= 0. {
+        return Err(Error::BadParam("tol".to_string(), tol.to_string()));
+    }
+    if params.natural("maxiter")? == 0 {
+        return Err(Error::BadParam("maxiter".to_string(), "0".to_string()));
+    }
+
     for mut grid_name in params.texts("grids")?.clone() {
         let optional = grid_name.starts_with('@');
         if optional {
@@ -119,7 +181,14 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
         }
 
         match ctx.get_grid(&grid_name) {
-            Ok(grid) => params.grids.push(grid),
+            Ok(grid) => {
+                if grid.units().is_rate() {
+                    return Err(Error::Unsupported(format!(
+                        "gridshift: Velocity grid '{grid_name}' - use `deformation`"
+                    )));
+                }
+                params.grids.push(grid)
+            }
             Err(e) => {
                 if !optional {
                     return Err(e);
@@ -294,6 +363,112 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn combined() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let op = ctx.op("gridshift grids=test_3d.datum")?;
+        let cph = Coor4D::geo(55., 12., 100., 0.);
+        let mut data = [cph];
+
+        // 1 m north, 2 m east, and 3 m up, in a single step
+        let ellps = Ellipsoid::named("GRS80")?;
+        let lat = cph[1];
+        let m = ellps.meridian_radius_of_curvature(lat) + 100.;
+        let n = ellps.prime_vertical_radius_of_curvature(lat) + 100.;
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][1] - cph[1] - 1. / m).abs() < 1e-15);
+        assert!((data[0][0] - cph[0] - 2. / (n * lat.cos())).abs() < 1e-15);
+        assert!((data[0][2] - 103.).abs() < 1e-9);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0] - cph[0]).abs() < 1e-12);
+        assert!((data[0][1] - cph[1]).abs() < 1e-12);
+        assert!((data[0][2] - cph[2]).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn ostn15() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let tm = "tmerc ellps=GRS80 lat_0=49 lon_0=-2 k_0=0.9996012717 x_0=400000 y_0=-100000";
+        let projection = ctx.op(tm)?;
+        let op = ctx.op(&format!("{tm} | gridshift grids=ostn15_test.txt"))?;
+
+        // A point in the middle of a cell of the (synthetic) test grid
+        let etrs89 = Coor4D::geo(52.660_110_136_1, 1.719_107_549_4, 50., 0.);
+        let mut projected = [etrs89];
+        ctx.apply(projection, Fwd, &mut projected)?;
+        let mut data = [etrs89];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - projected[0][0] - 102.0225).abs() < 1e-4);
+        assert!((data[0][1] - projected[0][1] - -77.9985).abs() < 1e-4);
+        assert!((data[0][2] - (50. - 44.015)).abs() < 1e-4);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot2(&etrs89) < 1e-9);
+        assert!((data[0][2] - 50.).abs() < 1e-6);
+
+        // The register entry refers to the full data file, which is not
+        // distributed with RG
+        let Err(Error::NotFound(name, _)) = ctx.op("uk:osgb36_to_etrs89") else {
+            panic!("Expected the OSTN15 data file to be missing");
+        };
+        assert_eq!(name, "OSTN15_OSGM15_DataFile.txt");
+        Ok(())
+    }
+
+    #[test]
+    fn par() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let op = ctx.op("gridshift grids=test.par")?;
+
+        // Tokyo datum to JGD2000: Add the corrections. At the node of
+        // mesh 53394622, they are 11.624" north, and 11.714" west
+        let tokyo = Coor4D::geo(35. + 41. / 60., 139. + 46.5 / 60., 0., 0.);
+        let mut data = [tokyo];
+        ctx.apply(op, Fwd, &mut data)?;
+        let d = (data[0] - tokyo).to_degrees();
+        assert!((d[1] * 3600. - 11.624).abs() < 1e-4);
+        assert!((d[0] * 3600. - -11.714).abs() < 1e-4);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0] - tokyo[0]).abs() < 1e-12);
+        assert!((data[0][1] - tokyo[1]).abs() < 1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn convergence() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let cph = Coor4D::geo(55., 12., 0., 0.);
+
+        // A single iteration does not suffice for the default tolerance,
+        // so the point is stomped on, and not counted as a success
+        let op = ctx.op("gridshift grids=test.datum maxiter=1")?;
+        let mut data = [cph];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(ctx.apply(op, Inv, &mut data)?, 0);
+        assert!(data[0][0].is_nan());
+
+        // But it does for a sufficiently loose tolerance
+        let op = ctx.op("gridshift grids=test.datum maxiter=1 tol=5")?;
+        let mut data = [cph];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(ctx.apply(op, Inv, &mut data)?, 1);
+        assert!((data[0][0] - cph[0]).abs() < 1e-6);
+        assert!((data[0][1] - cph[1]).abs() < 1e-6);
+
+        // Points outside of the grids are stomped on in the inverse case too
+        let op = ctx.op("gridshift grids=test.datum")?;
+        let mut data = [Coor4D::geo(51.505, -0.09, 0., 0.)];
+        assert_eq!(ctx.apply(op, Inv, &mut data)?, 0);
+        assert!(data[0][0].is_nan());
+
+        assert!(ctx.op("gridshift grids=test.datum maxiter=0").is_err());
+        assert!(ctx.op("gridshift grids=test.datum tol=0").is_err());
+        Ok(())
+    }
+
     #[test]
     fn missing_grid() -> Result<(), Error> {
         let mut ctx = Plain::default();
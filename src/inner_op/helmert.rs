@@ -44,11 +44,18 @@ fn helmert_common(
     let mut TT = [T[0], T[1], T[2]];
     let mut SS = S;
 
-    let mut prev_t = f64::NAN;
-    let n = operands.len();
-    for i in 0..n {
-        let mut c = operands.get_coord(i);
+    let forward = direction == Direction::Fwd;
 
+    // Static case: Bulk processing in lanes
+    #[cfg(feature = "simd")]
+    if !dynamic || fixed_t {
+        if let Some(coords) = operands.as_coor4d_mut() {
+            return super::lanes::helmert(coords, &ROT, SS, &TT, forward, rotated);
+        }
+    }
+
+    let mut prev_t = f64::NAN;
+    for_each_coord(operands, |c| {
         // Time varying case?
         if dynamic && !fixed_t {
             // Necessary to update parameters?
@@ -56,9 +63,9 @@ fn helmert_common(
             if c[3] != prev_t {
                 prev_t = c[3];
                 let dt = c[3] - epoch;
-                TT[0] += dt * DT[0];
-                TT[1] += dt * DT[1];
-                TT[2] += dt * DT[2];
+                TT[0] = T[0] + dt * DT[0];
+                TT[1] = T[1] + dt * DT[1];
+                TT[2] = T[2] + dt * DT[2];
                 if rotated {
                     let RR = [R[0] + dt * DR[0], R[1] + dt * DR[1], R[2] + dt * DR[2]];
                     ROT = rotation_matrix(&RR, exact, position_vector);
@@ -69,7 +76,7 @@ fn helmert_common(
 
         // ----- Forward direction -----
 
-        if direction == Direction::Fwd {
+        if forward {
             if rotated {
                 // Rotate
                 let x = c[0] * ROT[0][0] + c[1] * ROT[0][1] + c[2] * ROT[0][2];
@@ -80,16 +87,14 @@ fn helmert_common(
                 c[0] = SS * x + TT[0];
                 c[1] = SS * y + TT[1];
                 c[2] = SS * z + TT[2];
-                operands.set_coord(i, &c);
-                continue;
+                return true;
             }
 
             // scale and offset without rotation
             c[0] = SS * c[0] + TT[0];
             c[1] = SS * c[1] + TT[1];
             c[2] = SS * c[2] + TT[2];
-            operands.set_coord(i, &c);
-            continue;
+            return true;
         }
 
         // ----- Inverse direction -----
@@ -109,9 +114,8 @@ fn helmert_common(
             c[1] = y;
             c[2] = z;
         }
-        operands.set_coord(i, &c);
-    }
-    n
+        true
+    })
 }
 
 // ----- F O R W A R D --------------------------------------------------------------
@@ -129,7 +133,7 @@ fn helmert_inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) ->
 // ----- C O N S T R U C T O R ------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 25] = [
+pub const GAMUT: [OpParameter; 26] = [
     OpParameter::Flag { key: "inv" },
 
     // Translation
@@ -160,6 +164,9 @@ pub const GAMUT: [OpParameter; 25] = [
     OpParameter::Text { key: "convention", default: Some("") },
     OpParameter::Flag { key: "exact" },
 
+    // Full 3x3 rotation matrix, row by row, overriding the rotation angles
+    OpParameter::Series { key: "matrix", default: Some("") },
+
     // Scale and its time evoution
     OpParameter::Real { key: "scale", default: Some(0f64) },
     OpParameter::Real { key: "s",  default: Some(0f64) },
@@ -173,6 +180,17 @@ pub const GAMUT: [OpParameter; 25] = [
     OpParameter::Real { key: "t_obs", default: Some(f64::NAN) },
 ];
 
+// Position Vector transformation (geocentric domain), EPSG method 1033
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 1] = [OpTestVector {
+    source: "EPSG Guidance Note 7-2, Position Vector transformation (1033)",
+    definition: "helmert z=4.5 rz=0.554 s=0.219 convention=position_vector",
+    input: [3_657_660.66, 255_768.55, 5_201_382.11, 0.],
+    output: [3_657_660.78, 255_778.43, 5_201_387.75, 0.],
+    tolerance: [0.01, 0.01, 0.01, 0.],
+    inverse_tolerance: [0.01, 0.01, 0.01, 0.],
+}];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
@@ -289,9 +307,14 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     let convention = params.text("convention")?;
     let rotated = !(R == [0., 0., 0.] && DR == [0., 0., 0.]);
     let mut position_vector = true;
+    if !["", "position_vector", "coordinate_frame"].contains(&convention.as_str()) {
+        return Err(Error::BadParam("convention".to_string(), convention));
+    }
     if rotated {
-        if !["position_vector", "coordinate_frame"].contains(&convention.as_str()) {
-            return Err(Error::BadParam("convention".to_string(), convention));
+        // Silently assuming one convention is a classic source of meter level
+        // errors, so we make some noise before falling back to position vector
+        if convention.is_empty() {
+            warn!("helmert: Rotations given, but no convention - assuming position_vector");
         }
         if "coordinate_frame" == convention {
             position_vector = false;
@@ -358,6 +381,18 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     ROTFLAT.extend(ROT[1].iter());
     ROTFLAT.extend(ROT[2].iter());
     assert_eq!(ROTFLAT.len(), 9);
+
+    // A matrix given explicitly (e.g. by the pipeline optimizer) takes precedence
+    if let Some(matrix) = params.series.get("matrix") {
+        if matrix.len() != 9 || dynamic {
+            return Err(Error::BadParam(
+                "matrix".to_string(),
+                parameters.invocation.clone(),
+            ));
+        }
+        ROTFLAT = matrix.clone();
+        params.boolean.insert("rotated");
+    }
     params.series.insert("ROTFLAT", ROTFLAT);
 
     let fwd = InnerOp(helmert_fwd);
@@ -373,6 +408,72 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     })
 }
 
+// ----- P I P E L I N E   O P T I M I Z A T I O N -------------------------------------
+
+// The forward mapping, x' = s·M·x + t, of the static Helmert transformation
+// represented by `op`, taking operator inversion into account. `None` for
+// time dependent transformations
+fn static_mapping(op: &Op) -> Option<([[f64; 3]; 3], f64, [f64; 3])> {
+    if op.params.boolean("dynamic") && !op.params.boolean("fixed_time") {
+        return None;
+    }
+    let T = op.params.series("T").ok()?;
+    let S = op.params.real("S").ok()?;
+    let M = op.params.series("ROTFLAT").ok()?;
+    let ROT = [[M[0], M[1], M[2]], [M[3], M[4], M[5]], [M[6], M[7], M[8]]];
+    if !op.descriptor.inverted {
+        return Some((ROT, S, [T[0], T[1], T[2]]));
+    }
+
+    // The inverse mapping: x = ROTᵀ·(x' - t) / s
+    let mut TRANS = [[0.; 3]; 3];
+    let mut t = [0.; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            TRANS[i][j] = ROT[j][i];
+            t[i] -= ROT[j][i] * T[j] / S;
+        }
+    }
+    Some((TRANS, 1. / S, t))
+}
+
+/// Is `op` a static Helmert transformation without any effect?
+/// Typically the result of merging a step with its inverse
+pub(super) fn is_identity(op: &Op) -> bool {
+    let Some((M, S, T)) = static_mapping(op) else {
+        return false;
+    };
+    M == [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]] && S == 1. && T == [0.; 3]
+}
+
+/// Merge two consecutive static Helmert steps into one, for use by the
+/// pipeline optimizer. Returns `None` if either step is time dependent
+pub(super) fn merge(first: &Op, second: &Op, ctx: &dyn Context) -> Option<Op> {
+    let (M1, S1, T1) = static_mapping(first)?;
+    let (M2, S2, T2) = static_mapping(second)?;
+
+    // x'' = s₂·M₂·(s₁·M₁·x + t₁) + t₂ = s₁s₂·M₂M₁·x + (s₂·M₂·t₁ + t₂)
+    let mut M = [0.; 9];
+    let mut T = T2;
+    for i in 0..3 {
+        for j in 0..3 {
+            M[3 * i + j] = (0..3).map(|k| M2[i][k] * M1[k][j]).sum::<f64>();
+            T[i] += S2 * M2[i][j] * T1[j];
+        }
+    }
+
+    // The merged step is instantiated from a definition of its own, so
+    // the step definitions of the pipeline stay in sync with its steps
+    let list = |v: &[f64]| v.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    let definition = format!(
+        "helmert translation={} matrix={} s={}",
+        list(&T).join(","),
+        list(&M).join(","),
+        (S1 * S2 - 1.) * 1e6
+    );
+    Op::op(RawParameters::new(&definition, &BTreeMap::new()), ctx).ok()
+}
+
 // ----- R O T A T I O N   M A T R I X   B U I L D E R ---------------------------------
 
 // Based on Karsten Engsager's implementation in set_dtm_1.c (trlib),
@@ -485,6 +586,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn convention() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let rotations = "helmert x=0.06155 rx=-0.0394924 ry=-0.0327221 rz=-0.0328979";
+
+        // Without a convention, we warn, and fall back to position vector
+        let implicit = ctx.op(rotations)?;
+        let position_vector = ctx.op(&format!("{rotations} convention=position_vector"))?;
+        let coordinate_frame = ctx.op(&format!("{rotations} convention=coordinate_frame"))?;
+
+        let mut a = [GDA94];
+        let mut b = [GDA94];
+        let mut c = [GDA94];
+        ctx.apply(implicit, Fwd, &mut a)?;
+        ctx.apply(position_vector, Fwd, &mut b)?;
+        ctx.apply(coordinate_frame, Fwd, &mut c)?;
+        assert_eq!(a[0], b[0]);
+        assert!(a[0].hypot3(&c[0]) > 1.);
+
+        // Unknown conventions are errors, with or without rotations
+        let op = ctx.op(&format!("{rotations} convention=position"));
+        assert!(matches!(op, Err(Error::BadParam(_, _))));
+        let op = ctx.op("helmert x=1 convention=frame");
+        assert!(matches!(op, Err(Error::BadParam(_, _))));
+        Ok(())
+    }
+
+    #[test]
+    fn exact() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // A quarter turn around the z axis - far outside of small-angle territory
+        let definition = "helmert rz=324000 convention=position_vector";
+        let exact = ctx.op(&format!("{definition} exact"))?;
+        let approximate = ctx.op(definition)?;
+
+        let mut operands = [Coor4D::raw(1000., 0., 0., 0.)];
+        ctx.apply(exact, Fwd, &mut operands)?;
+        assert!(operands[0].hypot3(&Coor4D::raw(0., 1000., 0., 0.)) < 1e-9);
+
+        // The exact rotation matrix is orthonormal, so the roundtrip closes
+        ctx.apply(exact, Inv, &mut operands)?;
+        assert!(operands[0].hypot3(&Coor4D::raw(1000., 0., 0., 0.)) < 1e-9);
+
+        // The small-angle approximation does not even preserve lengths
+        let mut operands = [Coor4D::raw(1000., 0., 0., 0.)];
+        ctx.apply(approximate, Fwd, &mut operands)?;
+        assert!(operands[0].hypot3(&Coor4D::origin()) > 1500.);
+        Ok(())
+    }
+
     // A time varying example from the same source: ITRF2014@2018 to GDA2020,
     // Test point ALIC (Alice Springs)
     #[test]
@@ -530,6 +682,65 @@ mod tests {
         Ok(())
     }
 
+    // Same as `dynamic` above, but with the observation time coming from
+    // the context, rather than from the 4th coordinate element
+    #[test]
+    fn dynamic_at_epoch() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let definition = "
+            helmert  exact    convention = coordinate_frame
+            drx = 0.00150379  dry = 0.00118346  drz = 0.00120716
+            t_epoch = 2020.0
+        ";
+        let op = ctx.op(definition)?;
+        let (x, y, z, _) = ITRF2014.xyzt();
+        let (xx, yy, zz, _) = GDA2020B.xyzt();
+
+        // Explicitly given epoch, for coordinates lacking a time element
+        let mut operands = [Coor3D::raw(x, y, z), Coor3D::raw(x, y, z)];
+        ctx.apply_at_epoch(op, Fwd, &mut operands, 2018.0)?;
+        for coord in operands {
+            assert!(Coor3D::raw(xx, yy, zz).hypot3(&coord) < 40e-6);
+        }
+
+        // Explicitly given times take precedence over the epoch
+        let mut operands = [ITRF2014, ITRF2014];
+        operands[1][3] = f64::NAN;
+        ctx.apply_at_epoch(op, Fwd, &mut operands, 2030.0)?;
+        assert!(GDA2020B.hypot3(&operands[0]) < 40e-6);
+        assert!(GDA2020B.hypot3(&operands[1]) > 0.01);
+        assert_eq!(operands[1][3], 2030.0);
+
+        // Epoch given as coordinate metadata
+        struct Observations(Vec<Coor3D>);
+        impl CoordinateMetadata for Observations {
+            fn coordinate_epoch(&self) -> Option<DataEpoch> {
+                Some(2018.0.into())
+            }
+        }
+        impl CoordinateSet for Observations {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+            fn dim(&self) -> usize {
+                3
+            }
+            fn get_coord(&self, index: usize) -> Coor4D {
+                self.0.get_coord(index)
+            }
+            fn set_coord(&mut self, index: usize, value: &Coor4D) {
+                self.0.set_coord(index, value)
+            }
+        }
+        let mut operands = Observations(vec![Coor3D::raw(x, y, z)]);
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!(Coor3D::raw(xx, yy, zz).hypot3(&operands.0[0]) < 40e-6);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!(Coor3D::raw(x, y, z).hypot3(&operands.0[0]) < 40e-8);
+
+        Ok(())
+    }
+
     //& MY TESTS
 
     #[test]
@@ -1,5 +1,9 @@
 //! Lambert azimuthal equal area: EPSG coordinate operation method 9820, implemented
-//! following [IOGP, 2019](crate::Bibliography::Iogp19), pp. 78-80
+//! following [IOGP, 2019](crate::Bibliography::Iogp19), pp. 78-80.
+//!
+//! For spherical "ellipsoids" (e.g. `ellps=6371228`, or `+R=6371228` in PROJ
+//! syntax), the formulas reduce to the spherical form, EPSG method 1027, with
+//! the authalic latitude identical to the geographic.
 use crate::authoring::*;
 
 use std::f64::consts::FRAC_PI_2;
@@ -20,14 +24,15 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     };
     let Ok(d) = op.params.real("d") else { return 0 };
 
-    let oblique = op.params.boolean("oblique");
     let north_polar = op.params.boolean("north_polar");
     let south_polar = op.params.boolean("south_polar");
 
     let lon_0 = op.params.real("lon_0").unwrap_or(0.).to_radians();
     let x_0 = op.params.real("x_0").unwrap_or(0.);
     let y_0 = op.params.real("y_0").unwrap_or(0.);
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let e = ellps.eccentricity();
     let a = ellps.semimajor_axis();
 
@@ -44,7 +49,7 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
             let (sin_lon, cos_lon) = (lon - lon_0).sin_cos();
 
             let q = ancillary::qs(lat.sin(), e);
-            let rho = a * (qp + sign * q).sqrt();
+            let rho = a * (qp + sign * q).max(0.0).sqrt();
 
             let easting = x_0 + rho * sin_lon;
             let northing = y_0 + sign * rho * cos_lon;
@@ -59,16 +64,13 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         let (lon, lat) = operands.xy(i);
         let (sin_lon, cos_lon) = (lon - lon_0).sin_cos();
 
-        // Authalic latitude, 𝜉
-        let xi = (ancillary::qs(lat.sin(), e) / qp).asin();
+        // Authalic latitude, 𝜉. Clamped, since rounding may bring the ratio
+        // slightly outside of [-1, 1] at the poles
+        let xi = (ancillary::qs(lat.sin(), e) / qp).clamp(-1.0, 1.0).asin();
         let (sin_xi, cos_xi) = xi.sin_cos();
 
-        let b = if oblique {
-            let factor = 1.0 + sin_xi_0 * sin_xi + (cos_xi_0 * cos_xi * cos_lon);
-            rq * (2.0 / factor).sqrt()
-        } else {
-            1.0
-        };
+        let factor = 1.0 + sin_xi_0 * sin_xi + (cos_xi_0 * cos_xi * cos_lon);
+        let b = rq * (2.0 / factor).sqrt();
 
         let easting = x_0 + (b * d) * (cos_xi * sin_lon);
         let northing = y_0 + (b / d) * (cos_xi_0 * sin_xi - sin_xi_0 * cos_xi * cos_lon);
@@ -90,6 +92,9 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         return 0;
     };
     let Ok(d) = op.params.real("d") else { return 0 };
+    let Ok(qp) = op.params.real("qp") else {
+        return 0;
+    };
     let Ok(authalic) = op.params.fourier_coefficients("authalic") else {
         return 0;
     };
@@ -102,10 +107,10 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let x_0 = op.params.real("x_0").unwrap_or(0.);
     let y_0 = op.params.real("y_0").unwrap_or(0.);
 
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
-    let es = ellps.eccentricity_squared();
-    let e = es.sqrt();
 
     let (sin_xi_0, cos_xi_0) = xi_0.sin_cos();
 
@@ -119,9 +124,16 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
             let (x, y) = operands.xy(i);
             let rho = (x - x_0).hypot(y - y_0);
 
-            // The authalic latitude is a bit convoluted
-            let denom = a * a * (1.0 - ((1.0 - es) / (2.0 * e)) * ((1.0 - e) / (1.0 + e)).ln());
-            let xi = (-sign) * (1.0 - rho * rho / denom);
+            // The authalic latitude. The IOGP expression for the denominator,
+            // a²(1 - (1-e²)/2e · ln((1-e)/(1+e))), is a²·qp, which also holds
+            // (as 2a²) for the sphere, where the IOGP form is undefined
+            let sin_xi = 1.0 - rho * rho / (a * a * qp);
+            if sin_xi < -1.0 - EPS10 {
+                debug!("LAEA: ({x}, {y}) outside domain");
+                operands.set_xy(i, f64::NAN, f64::NAN);
+                continue;
+            }
+            let xi = (-sign) * sin_xi.clamp(-1.0, 1.0).asin();
 
             let lon = lon_0 + (x - x_0).atan2(sign * (y - y_0));
             let lat = ellps.latitude_authalic_to_geographic(xi, &authalic);
@@ -183,6 +195,43 @@ pub const GAMUT: [OpParameter; 6] = [
     OpParameter::Real { key: "y_0",   default: Some(0_f64) },
 ];
 
+// Lambert Azimuthal Equal Area, EPSG methods 9820 and 1027 (spherical)
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 4] = [
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Lambert Azimuthal Equal Area (9820)",
+        definition: "geo:in | laea lat_0=52 lon_0=10 x_0=4321000 y_0=3210000 ellps=GRS80",
+        input: [50., 5., 0., 0.],
+        output: [3_962_799.45, 2_999_718.85, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EASE-Grid 2.0 North (EPSG:6931), computed from the IOGP polar formulas",
+        definition: "geo:in | laea lat_0=90 lon_0=0 ellps=WGS84",
+        input: [50., 5., 0., 0.],
+        output: [380_977.735_3, -4_354_595.440_8, 0., 0.],
+        tolerance: [0.001, 0.001, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EASE-Grid 2.0 South (EPSG:6932), computed from the IOGP polar formulas",
+        definition: "geo:in | laea lat_0=-90 lon_0=0 ellps=WGS84",
+        input: [-80., -150., 0., 0.],
+        output: [-557_704.525_5, -965_972.573_7, 0., 0.],
+        tolerance: [0.001, 0.001, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EASE-Grid North (EPSG:3408), spherical (1027), computed following Snyder (1987)",
+        definition: "geo:in | laea lat_0=90 lon_0=0 ellps=6371228",
+        input: [50., 5., 0., 0.],
+        output: [379_840.121, -4_341_592.449_6, 0., 0.],
+        tolerance: [0.001, 0.001, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
@@ -201,9 +250,8 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     }
 
     let polar = (t - FRAC_PI_2).abs() < EPS10;
-    let north = polar && (t > 0.0);
+    let north = polar && (lat_0 > 0.0);
     let equatorial = !polar && t < EPS10;
-    let oblique = !polar && !equatorial;
     match (polar, equatorial, north) {
         (true, _, true) => params.boolean.insert("north_polar"),
         (true, _, false) => params.boolean.insert("south_polar"),
@@ -213,7 +261,7 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 
     // --- Precompute some latitude invariant factors ---
 
-    let ellps = params.ellps(0);
+    let ellps = params.ellps(0)?;
     let a = ellps.semimajor_axis();
     let es = ellps.eccentricity_squared();
     let e = es.sqrt();
@@ -227,13 +275,12 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     let xi_0 = (q0 / qp).asin();
     // Rq in the IOGP text
     let rq = a * (0.5 * qp).sqrt();
-    // D in the IOGP text
-    let d = if oblique {
-        a * (cos_phi_0 / (1.0 - es * sin_phi_0 * sin_phi_0).sqrt()) / (rq * xi_0.cos())
-    } else if equatorial {
-        rq.recip()
-    } else {
+    // D in the IOGP text. For the equatorial aspect this is a / Rq, as
+    // the general expression also gives
+    let d = if polar {
         a
+    } else {
+        a * (cos_phi_0 / (1.0 - es * sin_phi_0 * sin_phi_0).sqrt()) / (rq * xi_0.cos())
     };
 
     params.real.insert("xi_0", xi_0);
@@ -292,8 +339,78 @@ mod tests {
         ctx.apply(op, Inv, &mut operands)?;
         assert!(operands[0][0].is_nan());
 
-        // Missing test points for the polar aspects
+        Ok(())
+    }
+
+    #[test]
+    fn laea_polar() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // EASE-Grid 2.0 North and South
+        let north = ctx.op("laea lat_0=90 lon_0=0 ellps=WGS84")?;
+        let south = ctx.op("laea lat_0=-90 lon_0=0 ellps=WGS84")?;
+        let mut operands = [Coor2D::geo(50., 5.), Coor2D::geo(80., -150.)];
+        ctx.apply(north, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 380_977.735_3).abs() < 1e-3);
+        assert!((operands[0][1] + 4_354_595.440_8).abs() < 1e-3);
+        assert!((operands[1][0] + 557_704.525_5).abs() < 1e-3);
+        assert!((operands[1][1] - 965_972.573_7).abs() < 1e-3);
+        ctx.apply(north, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 50.).abs() < 1e-10);
+        assert!((operands[1][0].to_degrees() + 150.).abs() < 1e-10);
+
+        let mut operands = [Coor2D::geo(-50., 5.), Coor2D::geo(-90., 0.)];
+        ctx.apply(south, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 380_977.735_3).abs() < 1e-3);
+        assert!((operands[0][1] - 4_354_595.440_8).abs() < 1e-3);
+        assert!(operands[1][0].abs() < 1e-6 && operands[1][1].abs() < 1e-6);
+        ctx.apply(south, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() + 50.).abs() < 1e-10);
+        assert!((operands[1][1].to_degrees() + 90.).abs() < 1e-10);
+
+        // Outside of the domain
+        let mut operands = [Coor2D::raw(1e8, 1e8)];
+        ctx.apply(north, Inv, &mut operands)?;
+        assert!(operands[0][0].is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn laea_spherical() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Polar, EASE-Grid North
+        let op = ctx.op("laea lat_0=90 lon_0=0 ellps=6371228")?;
+        let mut operands = [Coor2D::geo(50., 5.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 379_840.121).abs() < 1e-3);
+        assert!((operands[0][1] + 4_341_592.449_6).abs() < 1e-3);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][0].to_degrees() - 5.).abs() < 1e-10);
+        assert!((operands[0][1].to_degrees() - 50.).abs() < 1e-10);
+
+        // Oblique, following Snyder (1987), eqs. 24-2 - 24-4
+        let op = ctx.op("laea lat_0=52 lon_0=10 ellps=6371228")?;
+        let mut operands = [Coor2D::geo(50., 5.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] + 357_121.844_2).abs() < 1e-3);
+        assert!((operands[0][1] + 210_183.435_9).abs() < 1e-3);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 50.).abs() < 1e-10);
+        Ok(())
+    }
 
+    #[test]
+    fn laea_equatorial() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let op = ctx.op("laea lat_0=0 lon_0=0 ellps=GRS80")?;
+        let mut operands = [Coor2D::geo(10., 20.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 2_189_747.330_8).abs() < 1e-3);
+        assert!((operands[0][1] - 1_121_357.324).abs() < 1e-3);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][0].to_degrees() - 20.).abs() < 1e-10);
+        assert!((operands[0][1].to_degrees() - 10.).abs() < 1e-10);
         Ok(())
     }
 
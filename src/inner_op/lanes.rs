@@ -0,0 +1,259 @@
+//! Lane-wise bulk kernels for the `helmert` and `cart` operators.
+//!
+//! Available with the `simd` feature. Rather than relying on the (still
+//! unstable) `std::simd`, the coordinate tuples are transposed into lanes of
+//! [`LANES`] x-, y-, z- and t-values, and the kernels are written as
+//! straight-line loops over the lanes, which the compiler turns into SIMD
+//! instructions where the target supports it. The transcendental functions
+//! (`sin_cos`, `atan2`) are still evaluated one element at a time, but
+//! everything in between vectorizes.
+//!
+//! The kernels are only engaged for operands stored as contiguous `Coor4D`s
+//! (cf. [`CoordinateSet::as_coor4d_mut()`]), and only for static Helmert
+//! transformations and biaxial ellipsoids. Everything else takes the
+//! ordinary, scalar route.
+use crate::authoring::*;
+
+/// Number of coordinate tuples processed per lane group: 4 doubles fill
+/// an AVX2 register, and make two rounds on SSE2 and NEON
+pub(super) const LANES: usize = 4;
+
+type Lane = [f64; LANES];
+
+/// Transpose `coords` into lanes of (at most) [`LANES`] tuples, hand them
+/// over to `kernel`, and transpose the results back. Padding lanes are
+/// zero-filled, and ignored on return. Returns the number of successes,
+/// as reported by `kernel`
+fn in_lanes(
+    coords: &mut [Coor4D],
+    mut kernel: impl FnMut(&mut [Lane; 4]) -> [bool; LANES],
+) -> usize {
+    let mut successes = 0;
+    for group in coords.chunks_mut(LANES) {
+        let mut lanes = [[0.; LANES]; 4];
+        for (k, coord) in group.iter().enumerate() {
+            for (i, lane) in lanes.iter_mut().enumerate() {
+                lane[k] = coord[i];
+            }
+        }
+
+        let ok = kernel(&mut lanes);
+
+        for (k, coord) in group.iter_mut().enumerate() {
+            for (i, lane) in lanes.iter().enumerate() {
+                coord[i] = lane[k];
+            }
+            successes += usize::from(ok[k]);
+        }
+    }
+    successes
+}
+
+// ----- H E L M E R T -----------------------------------------------------------------
+
+/// Static (i.e. time independent) Helmert transformation, mirroring the
+/// scalar implementation in `helmert::helmert_common()`
+#[allow(non_snake_case)]
+pub(super) fn helmert(
+    coords: &mut [Coor4D],
+    ROT: &[[f64; 3]; 3],
+    S: f64,
+    T: &[f64; 3],
+    forward: bool,
+    rotated: bool,
+) -> usize {
+    in_lanes(coords, |[x, y, z, _]| {
+        if forward {
+            for k in 0..LANES {
+                let (u, v, w) = if rotated {
+                    (
+                        x[k] * ROT[0][0] + y[k] * ROT[0][1] + z[k] * ROT[0][2],
+                        x[k] * ROT[1][0] + y[k] * ROT[1][1] + z[k] * ROT[1][2],
+                        x[k] * ROT[2][0] + y[k] * ROT[2][1] + z[k] * ROT[2][2],
+                    )
+                } else {
+                    (x[k], y[k], z[k])
+                };
+                x[k] = S * u + T[0];
+                y[k] = S * v + T[1];
+                z[k] = S * w + T[2];
+            }
+            return [true; LANES];
+        }
+
+        for k in 0..LANES {
+            let u = (x[k] - T[0]) / S;
+            let v = (y[k] - T[1]) / S;
+            let w = (z[k] - T[2]) / S;
+            if rotated {
+                x[k] = u * ROT[0][0] + v * ROT[1][0] + w * ROT[2][0];
+                y[k] = u * ROT[0][1] + v * ROT[1][1] + w * ROT[2][1];
+                z[k] = u * ROT[0][2] + v * ROT[1][2] + w * ROT[2][2];
+            } else {
+                x[k] = u;
+                y[k] = v;
+                z[k] = w;
+            }
+        }
+        [true; LANES]
+    })
+}
+
+// ----- C A R T -----------------------------------------------------------------------
+
+/// Geographical to cartesian conversion on a biaxial ellipsoid
+#[allow(non_snake_case)]
+pub(super) fn cart_fwd(coords: &mut [Coor4D], ellps: &Ellipsoid) -> usize {
+    let a = ellps.semimajor_axis();
+    let es = ellps.eccentricity_squared();
+    in_lanes(coords, |[lam, phi, h, t]| {
+        let mut sinphi = [0.; LANES];
+        let mut cosphi = [0.; LANES];
+        let mut sinlam = [0.; LANES];
+        let mut coslam = [0.; LANES];
+        for k in 0..LANES {
+            (sinphi[k], cosphi[k]) = phi[k].sin_cos();
+            (sinlam[k], coslam[k]) = lam[k].sin_cos();
+        }
+
+        let mut ok = [true; LANES];
+        for k in 0..LANES {
+            let N = a / (1. - es * sinphi[k] * sinphi[k]).sqrt();
+            let X = (N + h[k]) * cosphi[k] * coslam[k];
+            let Y = (N + h[k]) * cosphi[k] * sinlam[k];
+            let Z = (N * (1. - es) + h[k]) * sinphi[k];
+            ok[k] = !(X.is_nan() || Y.is_nan() || Z.is_nan() || t[k].is_nan());
+            (lam[k], phi[k], h[k]) = (X, Y, Z);
+        }
+        ok
+    })
+}
+
+/// Cartesian to geographical conversion on a biaxial ellipsoid, following
+/// the Fukushima/Claessens algorithm of the scalar implementation in `cart`
+#[allow(non_snake_case)]
+pub(super) fn cart_inv(coords: &mut [Coor4D], ellps: &Ellipsoid) -> usize {
+    let es = ellps.eccentricity_squared();
+    let a = ellps.semimajor_axis();
+    let b = ellps.semiminor_axis();
+    let ra = 1. / a;
+    let ar = b * ra;
+    let ce4 = 1.5 * es * es;
+    let cutoff = a * 1e-16;
+    let evolute = super::cart::Evolute::new(a, b);
+
+    in_lanes(coords, |[X, Y, Z, t]| {
+        let mut lam = [0.; LANES];
+        let mut S1 = [0.; LANES];
+        let mut CC = [0.; LANES];
+        let mut p = [0.; LANES];
+
+        // The arithmetic part, which vectorizes
+        for k in 0..LANES {
+            p[k] = (X[k] * X[k] + Y[k] * Y[k]).sqrt();
+            let P = ra * p[k];
+            let S0 = ra * Z[k];
+            let C0 = ar * P;
+            let A = (S0 * S0 + C0 * C0).sqrt();
+            let F = P * A * A * A - es * C0 * C0 * C0;
+            let B = ce4 * S0 * S0 * C0 * C0 * P * (A - ar);
+            S1[k] = (ar * S0 * A * A * A + es * S0 * S0 * S0) * F - B * S0;
+            let C1 = F * F - B * C0;
+            CC[k] = ar * C1;
+        }
+
+        // The transcendental part, which does not
+        let mut phi = [0.; LANES];
+        for k in 0..LANES {
+            lam[k] = Y[k].atan2(X[k]);
+            phi[k] = S1[k].atan2(CC[k]);
+        }
+
+        let mut ok = [true; LANES];
+        for k in 0..LANES {
+            let h = if !(p[k].is_finite() && Z[k].is_finite()) || evolute.contains(p[k], Z[k]) {
+                // Degenerate: Non-finite, or ambiguous close to the geocenter
+                (lam[k], phi[k]) = (f64::NAN, f64::NAN);
+                f64::NAN
+            } else if p[k] < cutoff {
+                // Close to the Z-axis: Force the latitude to the relevant pole
+                phi[k] = std::f64::consts::FRAC_PI_2.copysign(Z[k]);
+                Z[k].abs() - b
+            } else {
+                let n = (CC[k] * CC[k] + ar * ar * S1[k] * S1[k]).sqrt();
+                let d = (CC[k] * CC[k] + S1[k] * S1[k]).sqrt();
+                (p[k] * CC[k].abs() + Z[k].abs() * S1[k].abs() - a * n) / d
+            };
+            (X[k], Y[k], Z[k]) = (lam[k], phi[k], h);
+            ok[k] = ok[k] && !(lam[k].is_nan() || phi[k].is_nan() || h.is_nan() || t[k].is_nan());
+        }
+        ok
+    })
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lanes() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // An odd number of points, to exercise the padding of the last group
+        let geo: Vec<Coor4D> = (0..7)
+            .map(|i| {
+                let i = i as f64;
+                Coor4D::geo(50. + i, 10. - 3. * i, 100. * i, 0.)
+            })
+            .collect();
+
+        let definitions = [
+            "cart",
+            "cart ellps=GRS80 | helmert x=-87 y=-96 z=-120",
+            "cart | helmert x=-87 y=-96 z=-120 rx=1 ry=2 rz=3 s=4 convention=position_vector",
+            "cart | helmert inv x=-87 y=-96 z=-120 rx=1 ry=2 rz=3 s=4 convention=coordinate_frame",
+            "cart | helmert x=1 rx=1 drx=1 t_epoch=2000 t_obs=2010 convention=position_vector",
+        ];
+
+        for definition in definitions {
+            let op = ctx.op(definition)?;
+
+            // Vec<Coor4D> takes the lane-wise route, Vec<Coor3D> the scalar
+            let mut lanewise = geo.clone();
+            let mut scalar: Vec<Coor3D> =
+                geo.iter().map(|c| Coor3D::raw(c[0], c[1], c[2])).collect();
+
+            // (the scalar success counts are spoiled by the NaN time coordinate)
+            assert_eq!(ctx.apply(op, Fwd, &mut lanewise)?, 7);
+            ctx.apply(op, Fwd, &mut scalar)?;
+            for i in 0..7 {
+                assert!(
+                    lanewise[i].hypot3(&Coor4D::raw(scalar[i][0], scalar[i][1], scalar[i][2], 0.))
+                        < 1e-9
+                );
+            }
+
+            assert_eq!(ctx.apply(op, Inv, &mut lanewise)?, 7);
+            ctx.apply(op, Inv, &mut scalar)?;
+            for i in 0..7 {
+                assert!((lanewise[i][0] - scalar[i][0]).abs() < 1e-14);
+                assert!((lanewise[i][1] - scalar[i][1]).abs() < 1e-14);
+                assert!((lanewise[i][2] - scalar[i][2]).abs() < 1e-8);
+            }
+        }
+
+        // The poles are forced, and the geocenter rejected - as in the scalar case
+        let op = ctx.op("cart")?;
+        let mut operands = [
+            Coor4D::raw(0., 0., 6_400_000., 0.),
+            Coor4D::raw(6_400_000., 0., 0., 0.),
+            Coor4D::raw(0., 0., 0., 0.),
+        ];
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 2);
+        assert!((operands[0][1] - std::f64::consts::FRAC_PI_2).abs() < 1e-15);
+        assert!(operands[2][1].is_nan());
+        Ok(())
+    }
+}
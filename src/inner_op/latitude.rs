@@ -4,172 +4,214 @@ use crate::authoring::*;
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let mut successes = 0_usize;
-    let n = operands.len();
-    let sliced = 0..n;
-    let ellps = op.params.ellps(0);
-
-    if op.params.boolean("geocentric") {
-        for i in sliced {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_geographic_to_geocentric(coord[1]);
-            operands.set_coord(i, &coord);
-            successes += 1;
-        }
-    } else if op.params.boolean("reduced") || op.params.boolean("parametric") {
-        for i in sliced {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_geographic_to_reduced(coord[1]);
-            operands.set_coord(i, &coord);
-            successes += 1;
-        }
-    } else if op.params.boolean("conformal") {
-        let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
-            return 0;
-        };
+    let Some((from, to, coefficients)) = conversion(op) else {
+        return 0;
+    };
+    convert(op, operands, from, to, &coefficients.fwd)
+}
 
-        for i in sliced {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_geographic_to_conformal(coord[1], coefficients);
-            operands.set_coord(i, &coord);
-            successes += 1;
-        }
-    } else if op.params.boolean("rectifying") {
-        let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
-            return 0;
-        };
+// ----- I N V E R S E -----------------------------------------------------------------
 
-        for i in sliced {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_geographic_to_rectifying(coord[1], coefficients);
-            operands.set_coord(i, &coord);
-            successes += 1;
-        }
-    } else if op.params.boolean("authalic") {
-        let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
-            return 0;
-        };
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Some((from, to, coefficients)) = conversion(op) else {
+        return 0;
+    };
+    convert(op, operands, to, from, &coefficients.inv)
+}
 
-        for i in sliced {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_geographic_to_authalic(coord[1], coefficients);
-            operands.set_coord(i, &coord);
-            successes += 1;
-        }
-    }
+// ----- J A C O B I A N ---------------------------------------------------------------
 
-    successes
+// Only the latitude changes, so the only non-trivial partial derivative is
+// that of the output latitude with respect to the input latitude
+fn jacobian(op: &Op, _ctx: &dyn Context, at: &Coor4D) -> Option<[f64; 4]> {
+    let (from, to, coefficients) = conversion(op)?;
+    let ellps = op.params.ellps(0).ok()?;
+    let derivative = derivative(&ellps, at[1], from, to, &coefficients.fwd);
+    Some([1., 0., 0., derivative])
 }
 
-// ----- I N V E R S E -----------------------------------------------------------------
+// ----- E N D P O I N T S -------------------------------------------------------------
 
-fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let mut successes = 0_usize;
-    let n = operands.len();
-    let ellps = op.params.ellps(0);
-
-    if op.params.boolean("geocentric") {
-        for i in 0..n {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_geocentric_to_geographic(coord[1]);
-            operands.set_coord(i, &coord);
-            successes += 1;
-        }
-    } else if op.params.boolean("reduced") || op.params.boolean("parametric") {
-        for i in 0..n {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_reduced_to_geographic(coord[1]);
-            operands.set_coord(i, &coord);
-            successes += 1;
+// The endpoints of a conversion: Any of the auxiliary latitudes, or the
+// isometric latitude, which (being unbounded) does not fit into the Fourier
+// series framework, so it is handled in closed form, via the geographic
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Endpoint {
+    Auxiliary(AuxiliaryLatitude),
+    Isometric,
+}
+
+impl Endpoint {
+    fn parse(name: &str) -> Result<Endpoint, Error> {
+        if name.eq_ignore_ascii_case("isometric") {
+            return Ok(Endpoint::Isometric);
         }
-    } else if op.params.boolean("conformal") {
-        let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
-            return 0;
-        };
+        Ok(Endpoint::Auxiliary(name.parse()?))
+    }
 
-        for i in 0..n {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_conformal_to_geographic(coord[1], coefficients);
-            operands.set_coord(i, &coord);
-            successes += 1;
+    fn name(&self) -> &'static str {
+        match self {
+            Endpoint::Auxiliary(latitude) => latitude.name(),
+            Endpoint::Isometric => "isometric",
         }
-    } else if op.params.boolean("rectifying") {
-        let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
-            return 0;
-        };
+    }
 
-        for i in 0..n {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_rectifying_to_geographic(coord[1], coefficients);
-            operands.set_coord(i, &coord);
-            successes += 1;
+    // The auxiliary latitude to convert to/from, in the Fourier series step
+    fn auxiliary(&self) -> AuxiliaryLatitude {
+        match self {
+            Endpoint::Auxiliary(latitude) => *latitude,
+            Endpoint::Isometric => AuxiliaryLatitude::Geographic,
         }
-    } else if op.params.boolean("authalic") {
-        let Some(coefficients) = op.params.fourier_coefficients.get("coefficients") else {
-            return 0;
-        };
+    }
+}
+
+// The endpoints and coefficients of the conversion, as established by `new()`
+fn conversion(op: &Op) -> Option<(Endpoint, Endpoint, &FourierCoefficients)> {
+    let from = Endpoint::parse(&op.params.text("from").ok()?).ok()?;
+    let to = Endpoint::parse(&op.params.text("to").ok()?).ok()?;
+    let coefficients = op.params.fourier_coefficients.get("coefficients")?;
+    Some((from, to, coefficients))
+}
+
+// Convert the latitudes of all operands from `from` to `to`. With the
+// `derivative` flag set, the derivative of the conversion, evaluated at
+// the input latitude, replaces the third coordinate element, i.e. the height
+fn convert(
+    op: &Op,
+    operands: &mut dyn CoordinateSet,
+    from: Endpoint,
+    to: Endpoint,
+    coefficients: &[f64],
+) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    let with_derivative = op.params.boolean("derivative");
+    let n = operands.len();
 
-        for i in 0..n {
-            let mut coord = operands.get_coord(i);
-            coord[1] = ellps.latitude_authalic_to_geographic(coord[1], coefficients);
-            operands.set_coord(i, &coord);
-            successes += 1;
+    for i in 0..n {
+        let mut coord = operands.get_coord(i);
+        let lat = coord[1];
+        coord[1] = convert_latitude(&ellps, lat, from, to, coefficients);
+        if with_derivative {
+            coord[2] = derivative(&ellps, lat, from, to, coefficients);
         }
+        operands.set_coord(i, &coord);
     }
+    n
+}
 
-    successes
+// Convert a single latitude. The classical geographic/geocentric/parametric
+// conversions are handled in closed form, the remaining through Fourier series
+fn convert_latitude(
+    ellps: &Ellipsoid,
+    lat: f64,
+    from: Endpoint,
+    to: Endpoint,
+    coefficients: &[f64],
+) -> f64 {
+    use AuxiliaryLatitude::*;
+    let lat = match from {
+        Endpoint::Isometric => ellps.latitude_isometric_to_geographic(lat),
+        _ => lat,
+    };
+    let lat = match (from.auxiliary(), to.auxiliary()) {
+        (Geographic, Geographic) => lat,
+        (Geographic, Geocentric) => ellps.latitude_geographic_to_geocentric(lat),
+        (Geocentric, Geographic) => ellps.latitude_geocentric_to_geographic(lat),
+        (Geographic, Parametric) => ellps.latitude_geographic_to_reduced(lat),
+        (Parametric, Geographic) => ellps.latitude_reduced_to_geographic(lat),
+        _ => lat + fourier::sin(2. * lat, coefficients),
+    };
+    match to {
+        Endpoint::Isometric => ellps.latitude_geographic_to_isometric(lat),
+        _ => lat,
+    }
+}
+
+// The derivative of the conversion at `lat`, by central differences. The
+// step size balances truncation and rounding errors, giving around 10
+// significant digits - more than sufficient for error analysis
+fn derivative(
+    ellps: &Ellipsoid,
+    lat: f64,
+    from: Endpoint,
+    to: Endpoint,
+    coefficients: &[f64],
+) -> f64 {
+    let h = 1e-6;
+    let ahead = convert_latitude(ellps, lat + h, from, to, coefficients);
+    let behind = convert_latitude(ellps, lat - h, from, to, coefficients);
+    (ahead - behind) / (2. * h)
 }
 
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
-// Example...
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 8] = [
+pub const GAMUT: [OpParameter; 12] = [
     OpParameter::Flag { key: "inv" },
+    // Replaces the height (the third coordinate element) by the derivative of
+    // the conversion, so the height is lost. `Context::partial_derivatives()`
+    // provides the derivative without touching the coordinates
+    OpParameter::Flag { key: "derivative" },
     OpParameter::Flag { key: "geocentric" },
     OpParameter::Flag { key: "reduced" },
     OpParameter::Flag { key: "parametric" },
     OpParameter::Flag { key: "conformal" },
     OpParameter::Flag { key: "authalic" },
     OpParameter::Flag { key: "rectifying" },
+    OpParameter::Flag { key: "isometric" },
+    OpParameter::Text { key: "from", default: Some("") },
+    OpParameter::Text { key: "to", default: Some("") },
     OpParameter::Text { key: "ellps", default: Some("GRS80") }
 ];
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
-    let ellps = op.params.ellps(0);
+    let ellps = op.params.ellps(0)?;
 
-    let mut number_of_flags = 0_usize;
-    if op.params.boolean("geocentric") {
-        number_of_flags += 1;
-    }
-    if op.params.boolean("reduced") || op.params.boolean("parametric") {
-        number_of_flags += 1;
-    }
-    if op.params.boolean("conformal") {
-        let coefficients = ellps.coefficients_for_conformal_latitude_computations();
-        op.params
-            .fourier_coefficients
-            .insert("coefficients", coefficients);
-        number_of_flags += 1;
-    }
-    if op.params.boolean("authalic") {
-        let coefficients = ellps.coefficients_for_authalic_latitude_computations();
-        op.params
-            .fourier_coefficients
-            .insert("coefficients", coefficients);
-        number_of_flags += 1;
-    }
-    if op.params.boolean("rectifying") {
-        let coefficients = ellps.coefficients_for_rectifying_latitude_computations();
-        op.params
-            .fourier_coefficients
-            .insert("coefficients", coefficients);
-        number_of_flags += 1;
-    }
-    if number_of_flags != 1 {
-        return Err(Error::MissingParam("latitude: must specify exactly one of flags authalic/conformal/geocentric/rectifying/reduced/parametric".to_string()));
-    }
+    // The flags select the target of a conversion from the geographic latitude
+    let flags = [
+        "geocentric",
+        "reduced",
+        "parametric",
+        "conformal",
+        "authalic",
+        "rectifying",
+        "isometric",
+    ];
+    let given: Vec<_> = flags
+        .into_iter()
+        .filter(|&flag| op.params.boolean(flag))
+        .collect();
+
+    // Alternatively, `from` and `to` select any pair of auxiliary latitudes
+    let from = op.params.text("from")?;
+    let to = op.params.text("to")?;
+    let explicit = !from.is_empty() || !to.is_empty();
+
+    let (from, to) = match (given.len(), explicit) {
+        (1, false) => (Endpoint::Auxiliary(AuxiliaryLatitude::Geographic), Endpoint::parse(given[0])?),
+        (0, true) => {
+            let from = if from.is_empty() { "geographic" } else { &from };
+            let to = if to.is_empty() { "geographic" } else { &to };
+            (Endpoint::parse(from)?, Endpoint::parse(to)?)
+        }
+        _ => return Err(Error::MissingParam("latitude: must specify either from/to, or exactly one of flags authalic/conformal/geocentric/isometric/rectifying/reduced/parametric".to_string())),
+    };
+
+    let (aux_from, aux_to) = (from.auxiliary(), to.auxiliary());
+    let coefficients = FourierCoefficients {
+        fwd: ellps.latitude_conversion_coefficients(aux_from, aux_to),
+        inv: ellps.latitude_conversion_coefficients(aux_to, aux_from),
+        etc: [0.; 2],
+    };
+    op.params
+        .fourier_coefficients
+        .insert("coefficients", coefficients);
+    op.params.text.insert("from", from.name().to_string());
+    op.params.text.insert("to", to.name().to_string());
+    op.descriptor.jacobian = Some(InnerJacobian(jacobian));
 
     Ok(op)
 }
@@ -220,7 +262,7 @@ mod tests {
         let op = ctx.op("latitude rectifying ellps=GRS80")?;
         let mut operands = [Coor4D::geo(55., 12., 0., 0.)];
         ctx.apply(op, Fwd, &mut operands)?;
-        assert!((operands[0][1].to_degrees() - 54.772_351_809_646_84).abs() < 1e-12);
+        assert!((operands[0][1].to_degrees() - 54.864_287_983_539_56).abs() < 1e-12);
         ctx.apply(op, Inv, &mut operands)?;
         assert!((operands[0][1].to_degrees() - 55.).abs() < 1e-12);
 
@@ -232,6 +274,88 @@ mod tests {
         ctx.apply(op, Inv, &mut operands)?;
         assert!((operands[0][1].to_degrees() - 55.).abs() < 1e-12);
 
+        // Any auxiliary latitude to any other: Conformal to authalic
+        let op = ctx.op("latitude from=conformal to=authalic ellps=GRS80")?;
+        let mut operands = [Coor4D::geo(54.819_109_023_689_02, 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 54.879_361_594_517_796).abs() < 1e-12);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 54.819_109_023_689_02).abs() < 1e-12);
+
+        // `from` defaults to geographic
+        let op = ctx.op("latitude to=reduced ellps=GRS80")?;
+        let mut operands = [Coor4D::geo(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 54.909_538_187_092_245).abs() < 1e-12);
+
+        // Flags and from/to do not mix, and unknown latitudes are rejected
+        assert!(ctx.op("latitude conformal to=authalic").is_err());
+        assert!(ctx.op("latitude conformal authalic").is_err());
+        assert!(ctx.op("latitude from=bogus").is_err());
+        assert!(ctx.op("latitude").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn isometric() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let ellps = Ellipsoid::named("GRS80")?;
+        let psi = ellps.latitude_geographic_to_isometric(55_f64.to_radians());
+
+        // Geographic to isometric, and back
+        let op = ctx.op("latitude isometric ellps=GRS80")?;
+        let mut operands = [Coor4D::geo(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][1] - psi).abs() < 1e-14);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 55.).abs() < 1e-12);
+
+        // The isometric latitude is a function of the conformal
+        let op = ctx.op("latitude from=isometric to=conformal ellps=GRS80")?;
+        let mut operands = [Coor4D::raw(0., psi, 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 54.819_109_023_689_02).abs() < 1e-12);
+        assert!((operands[0][1].tan().asinh() - psi).abs() < 1e-12);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1] - psi).abs() < 1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn derivative() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let ellps = Ellipsoid::named("GRS80")?;
+        let lat = 55_f64.to_radians();
+
+        // The derivative of the isometric latitude is M / (N cos 𝜙)
+        let expected = ellps.meridian_radius_of_curvature(lat)
+            / (ellps.prime_vertical_radius_of_curvature(lat) * lat.cos());
+        let op = ctx.op("latitude isometric derivative ellps=GRS80")?;
+        let mut operands = [Coor4D::geo(55., 12., 100., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][2] - expected).abs() < 1e-9);
+
+        // ...and its inverse the reciprocal
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][2] - 1. / expected).abs() < 1e-9);
+        assert!((operands[0][1] - lat).abs() < 1e-12);
+
+        // The derivatives are also available as analytical partial derivatives
+        let op = ctx.op("latitude conformal ellps=GRS80")?;
+        let at = Coor4D::geo(55., 12., 0., 0.);
+        let [a, b, c, d] = ctx.partial_derivatives(op, Fwd, &at)?.unwrap();
+        assert_eq!([a, b, c], [1., 0., 0.]);
+        let h = 1e-7;
+        let conformal = |lat: f64| {
+            ellps.latitude_convert(
+                lat,
+                AuxiliaryLatitude::Geographic,
+                AuxiliaryLatitude::Conformal,
+            )
+        };
+        let expected = (conformal(at[1] + h) - conformal(at[1] - h)) / (2. * h);
+        assert!((d - expected).abs() < 1e-8);
         Ok(())
     }
 }
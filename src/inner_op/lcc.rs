@@ -1,4 +1,5 @@
-//! Lambert Conformal Conic
+//! Lambert Conformal Conic, EPSG methods 9801 (1SP), 9802 (2SP), and the
+//! variants 9803 (2SP Belgium), 1051 (2SP Michigan), and 9826 (1SP West Orientated)
 use crate::authoring::*;
 use std::f64::consts::FRAC_PI_2;
 
@@ -9,7 +10,9 @@ const EPS10: f64 = 1e-10;
 // Forward Lambert conformal conic, following the PROJ implementation,
 // cf.  https://proj.org/operations/projections/lcc.html
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
     let e = ellps.eccentricity();
     let lon_0 = op.params.lon(0);
@@ -21,6 +24,10 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let Ok(rho0) = op.params.real("rho0") else {
         return 0;
     };
+    let west = op.params.boolean("west");
+    let Ok(theta_offset) = op.params.real("theta_offset") else {
+        return 0;
+    };
     let mut successes = 0_usize;
     let length = operands.len();
 
@@ -38,8 +45,12 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         } else {
             rho = c * crate::math::ancillary::ts(phi.sin_cos(), e).powf(n);
         }
-        let sc = (lam * n).sin_cos();
-        let x = a * k_0 * rho * sc.0 + x_0;
+        let sc = (lam * n - theta_offset).sin_cos();
+        let x = if west {
+            x_0 - a * k_0 * rho * sc.0
+        } else {
+            x_0 + a * k_0 * rho * sc.0
+        };
         let y = a * k_0 * (rho0 - rho * sc.1) + y_0;
         operands.set_xy(i, x, y);
         successes += 1;
@@ -49,7 +60,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
 // ----- I N V E R S E -----------------------------------------------------------------
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
     let e = ellps.eccentricity();
     let lon_0 = op.params.lon(0);
@@ -61,11 +74,18 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let Ok(rho0) = op.params.real("rho0") else {
         return 0;
     };
+    let west = op.params.boolean("west");
+    let Ok(theta_offset) = op.params.real("theta_offset") else {
+        return 0;
+    };
     let mut successes = 0_usize;
 
     for i in 0..operands.len() {
         let (mut x, mut y) = operands.xy(i);
         x = (x - x_0) / (a * k_0);
+        if west {
+            x = -x;
+        }
         y = rho0 - (y - y_0) / (a * k_0);
 
         let mut rho = x.hypot(y);
@@ -92,21 +112,58 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
             operands.set_coord(i, &Coor4D::nan());
             continue;
         }
-        let lon = x.atan2(y) / n + lon_0;
+        let lon = (x.atan2(y) + theta_offset) / n + lon_0;
         operands.set_xy(i, lon, lat);
         successes += 1;
     }
     successes
 }
 
+// ----- D E R I V A T I V E S ---------------------------------------------------------
+
+fn jacobian(op: &Op, _ctx: &dyn Context, at: &Coor4D) -> Option<[f64; 4]> {
+    let ellps = op.params.ellps(0).ok()?;
+    let ak_0 = ellps.semimajor_axis() * op.params.k(0);
+    let e = ellps.eccentricity();
+    let n = op.params.real("n").ok()?;
+    let c = op.params.real("c").ok()?;
+
+    let lam = at[0] - op.params.lon(0);
+    let phi = at[1];
+    if (phi.abs() - FRAC_PI_2).abs() < EPS10 {
+        return None;
+    }
+
+    // 𝜌 = c exp(-n𝜓), so d𝜌/d𝜙 = -n𝜌 d𝜓/d𝜙, with d𝜓/d𝜙 = M / (N cos 𝜙)
+    let rho = c * crate::math::ancillary::ts(phi.sin_cos(), e).powf(n);
+    let dpsi_dphi = ellps.meridian_radius_of_curvature(phi)
+        / (ellps.prime_vertical_radius_of_curvature(phi) * phi.cos());
+    let drho_dphi = -n * rho * dpsi_dphi;
+
+    // The West orientated variant flips the sign of the easting derivatives
+    let sign = if op.params.boolean("west") { -1. } else { 1. };
+    let theta_offset = op.params.real("theta_offset").ok()?;
+    let (s, c) = (lam * n - theta_offset).sin_cos();
+    Some([
+        sign * ak_0 * rho * n * c,
+        ak_0 * rho * n * s,
+        sign * ak_0 * drho_dphi * s,
+        -ak_0 * drho_dphi * c,
+    ])
+}
+
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
-// Example...
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 9] = [
+pub const GAMUT: [OpParameter; 11] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 
+    // "belgium", "michigan", or "west", for the EPSG variants
+    OpParameter::Text { key: "variant", default: Some("") },
+    // Ellipsoid scaling factor, for the Michigan variant
+    OpParameter::Real { key: "k_ellps", default: Some(1_f64) },
+
     OpParameter::Real { key: "lat_1", default: Some(0_f64) },
     OpParameter::Real { key: "lat_2", default: Some(f64::NAN) },
     OpParameter::Real { key: "lat_0", default: Some(f64::NAN) },
@@ -117,6 +174,35 @@ pub const GAMUT: [OpParameter; 9] = [
     OpParameter::Real { key: "y_0",   default: Some(0_f64) },
 ];
 
+// Lambert Conic Conformal (1SP), EPSG method 9801, and the Belgium and Michigan variants
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 3] = [
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Lambert Conic Conformal (1SP) (9801)",
+        definition: "geo:in | lcc lat_1=18 lat_0=18 lon_0=-77 k_0=1 x_0=250000 y_0=150000 ellps=clrk66",
+        input: [17. + 55. / 60. + 55.80 / 3600., -(76. + 56. / 60. + 37.26 / 3600.), 0., 0.],
+        output: [255_966.58, 142_493.51, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Lambert Conic Conformal (2SP Belgium) (9803)",
+        definition: "geo:in | lcc variant=belgium lat_1=49:50 lat_2=51:10 lat_0=90 lon_0=4:21:24.983 x_0=150000.01 y_0=5400088.44 ellps=intl",
+        input: [50. + 40. / 60. + 46.461 / 3600., 5. + 48. / 60. + 26.533 / 3600., 0., 0.],
+        output: [251_763.20, 153_034.13, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Lambert Conic Conformal (2SP Michigan) (1051)",
+        definition: "geo:in | lcc variant=michigan k_ellps=1.0000382 lat_1=44:11 lat_2=45:42 lat_0=43:19 lon_0=-84:20 x_0=609601.2192024384 ellps=clrk66 | unitconvert xy_out=us-ft",
+        input: [43. + 45. / 60., -(83. + 10. / 60.), 0., 0.],
+        output: [2_308_335.75, 160_210.48, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
@@ -138,6 +224,32 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     params.real.insert("lat_1", phi1);
     params.real.insert("lat_2", phi2);
 
+    // The EPSG variants
+    let mut theta_offset = 0.;
+    let variant = params.text("variant")?.to_lowercase();
+    match variant.as_str() {
+        "" => (),
+        // The 29.2985" correction of the angle from the central meridian
+        "belgium" => theta_offset = (29.2985_f64 / 3600.).to_radians(),
+        // Scaling the ellipsoid scales everything
+        "michigan" => {
+            let k_0 = params.k(0) * params.real("k_ellps")?;
+            params.real.insert("k_0", k_0);
+        }
+        // Westing rather than easting
+        "west" => {
+            params.boolean.insert("west");
+        }
+        _ => return Err(Error::BadParam("variant".to_string(), variant)),
+    }
+    if variant != "michigan" && params.given.contains_key("k_ellps") {
+        return Err(Error::BadParam(
+            "k_ellps".to_string(),
+            "Lcc: k_ellps is only supported by variant=michigan".to_string(),
+        ));
+    }
+    params.real.insert("theta_offset", theta_offset);
+
     let mut lat_0 = params.lat(0);
     if lat_0.is_nan() {
         lat_0 = 0.;
@@ -148,7 +260,7 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 
     let sc = phi1.sin_cos();
     let mut n = sc.0;
-    let ellps = params.ellps(0);
+    let ellps = params.ellps(0)?;
     let e = ellps.eccentricity();
     let es = ellps.eccentricity_squared();
 
@@ -200,7 +312,8 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     params.real.insert("rho0", rho0);
     params.real.insert("lat_0", lat_0);
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.jacobian = Some(InnerJacobian(jacobian));
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
     Ok(Op {
@@ -396,4 +509,40 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn variants() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let geo = [Coor4D::geo(56., 10., 0., 0.)];
+
+        // West orientated: The easting changes sign around x_0
+        let east = ctx.op("lcc lat_1=55 lat_0=55 lon_0=12 x_0=500000")?;
+        let west = ctx.op("lcc variant=west lat_1=55 lat_0=55 lon_0=12 x_0=500000")?;
+        let mut eastern = geo;
+        ctx.apply(east, Fwd, &mut eastern)?;
+        let mut western = geo;
+        ctx.apply(west, Fwd, &mut western)?;
+        assert!((eastern[0][0] - 375_212.334_842).abs() < 1e-5);
+        assert!((western[0][0] - 624_787.665_158).abs() < 1e-5);
+        assert!((western[0][1] - eastern[0][1]).abs() < 1e-9);
+        ctx.apply(west, Inv, &mut western)?;
+        assert!(western[0].hypot2(&geo[0]) < 1e-9);
+
+        // Michigan: Scaling the ellipsoid by K equals scaling by k_0 = K
+        let michigan = ctx.op("lcc variant=michigan k_ellps=1.0000382 lat_1=44 lat_2=46")?;
+        let scaled = ctx.op("lcc k_0=1.0000382 lat_1=44 lat_2=46")?;
+        let mut a = geo;
+        let mut b = geo;
+        ctx.apply(michigan, Fwd, &mut a)?;
+        ctx.apply(scaled, Fwd, &mut b)?;
+        assert!(a[0].hypot2(&b[0]) < 1e-9);
+
+        // Bad and conflicting parameters
+        assert!(matches!(
+            ctx.op("lcc variant=bogus lat_1=55"),
+            Err(Error::BadParam(_, _))
+        ));
+        assert!(ctx.op("lcc k_ellps=1.0000382 lat_1=55").is_err());
+        Ok(())
+    }
 }
@@ -1,24 +1,35 @@
-//! Mercator
+//! Mercator, EPSG coordinate operation methods 9804 (variant A, given by `k_0`),
+//! 9805 (variant B, given by the latitude of true scale, `lat_ts`), and 1026
+//! (spherical)
 use crate::authoring::*;
 
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
+    let spherical = op.params.boolean("spherical");
     let k_0 = op.params.k(0);
     let x_0 = op.params.x(0);
     let y_0 = op.params.y(0);
-    let lat_0 = op.params.lat(0);
     let lon_0 = op.params.lon(0);
+    let Ok(psi_0) = op.params.real("psi_0") else {
+        return 0;
+    };
 
     let mut successes = 0_usize;
     for i in 0..operands.len() {
         let (lon, lat) = operands.xy(i);
 
-        let easting = (lon - lon_0) * k_0 * a - x_0;
-        let isometric = ellps.latitude_geographic_to_isometric(lat + lat_0);
-        let northing = a * k_0 * isometric - y_0;
+        let easting = x_0 + (lon - lon_0) * k_0 * a;
+        let isometric = if spherical {
+            lat.tan().asinh()
+        } else {
+            ellps.latitude_geographic_to_isometric(lat)
+        };
+        let northing = y_0 + a * k_0 * (isometric - psi_0);
 
         operands.set_xy(i, easting, northing);
         successes += 1;
@@ -30,26 +41,33 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- I N V E R S E -----------------------------------------------------------------
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
+    let spherical = op.params.boolean("spherical");
     let k_0 = op.params.k(0);
     let x_0 = op.params.x(0);
     let y_0 = op.params.y(0);
-    let lat_0 = op.params.lat(0);
     let lon_0 = op.params.lon(0);
+    let Ok(psi_0) = op.params.real("psi_0") else {
+        return 0;
+    };
 
     let mut successes = 0_usize;
     for i in 0..operands.len() {
-        let (mut x, mut y) = operands.xy(i);
+        let (x, y) = operands.xy(i);
 
         // Easting -> Longitude
-        x += x_0;
-        let lon = x / (a * k_0) - lon_0;
+        let lon = (x - x_0) / (a * k_0) + lon_0;
 
         // Northing -> Latitude
-        y += y_0;
-        let psi = y / (a * k_0);
-        let lat = ellps.latitude_isometric_to_geographic(psi) - lat_0;
+        let psi = (y - y_0) / (a * k_0) + psi_0;
+        let lat = if spherical {
+            psi.sinh().atan()
+        } else {
+            ellps.latitude_isometric_to_geographic(psi)
+        };
         operands.set_xy(i, lon, lat);
         successes += 1;
     }
@@ -57,12 +75,31 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     successes
 }
 
+// ----- D E R I V A T I V E S ---------------------------------------------------------
+
+fn jacobian(op: &Op, _ctx: &dyn Context, at: &Coor4D) -> Option<[f64; 4]> {
+    let ellps = op.params.ellps(0).ok()?;
+    let ak_0 = ellps.semimajor_axis() * op.params.k(0);
+    let lat = at[1];
+
+    // The derivative of the isometric latitude is M / (N cos 𝜙), which
+    // reduces to 1 / cos 𝜙 for the sphere
+    if op.params.boolean("spherical") {
+        return Some([ak_0, 0., 0., ak_0 / lat.cos()]);
+    }
+    let dpsi_dlat = ellps.meridian_radius_of_curvature(lat)
+        / (ellps.prime_vertical_radius_of_curvature(lat) * lat.cos());
+    Some([ak_0, 0., 0., ak_0 * dpsi_dlat])
+}
+
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 8] = [
+pub const GAMUT: [OpParameter; 10] = [
     OpParameter::Flag { key: "inv" },
-    OpParameter::Text { key: "ellps",  default: Some("GRS80") },
+    OpParameter::Flag { key: "spherical" },
+    OpParameter::Text { key: "ellps",   default: Some("GRS80") },
+    OpParameter::Text { key: "variant", default: Some("") },
 
     OpParameter::Real { key: "lat_0",  default: Some(0_f64) },
     OpParameter::Real { key: "lon_0",  default: Some(0_f64) },
@@ -73,10 +110,50 @@ pub const GAMUT: [OpParameter; 8] = [
     OpParameter::Real { key: "lat_ts", default: Some(0_f64) },
 ];
 
+// Mercator, EPSG methods 9804, 9805, and 1026
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 3] = [
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Mercator variant A (9804)",
+        definition: "geo:in | merc variant=A lon_0=110 k_0=0.997 x_0=3900000 y_0=900000 ellps=bessel",
+        input: [-3., 120., 0., 0.],
+        output: [5_009_726.58, 569_150.82, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Mercator variant B (9805)",
+        definition: "geo:in | merc variant=B lat_ts=42 lon_0=51 ellps=krass",
+        input: [53., 53., 0., 0.],
+        output: [165_704.29, 5_171_848.07, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Mercator (Spherical) (1026)",
+        definition: "geo:in | merc ellps=6371007",
+        input: [24. + 22. / 60. + 54.433 / 3600., -(100. + 20. / 60.), 0., 0.],
+        output: [-11_156_569.90, 2_796_869.94, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
-    let ellps = params.ellps(0);
+    let ellps = params.ellps(0)?;
+
+    // A sphere, either by definition, or by request (using the semimajor axis)
+    let spherical = params.boolean("spherical") || ellps.flattening() == 0.0;
+    if spherical {
+        params.boolean.insert("spherical");
+    }
+    let es = if spherical {
+        0.0
+    } else {
+        ellps.eccentricity_squared()
+    };
 
     let lat_ts = params.real("lat_ts")?;
     if lat_ts.abs() > 90. {
@@ -85,14 +162,47 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         ));
     }
 
+    // The EPSG variants differ only in how the scale is given, so we merely
+    // check that the parameters match the variant asked for
+    let variant = params.text("variant")?.to_lowercase();
+    let given_k_0 = params.given.contains_key("k_0");
+    let given_lat_ts = params.given.contains_key("lat_ts");
+    match variant.as_str() {
+        "" => (),
+        "a" if !given_lat_ts => (),
+        "b" if !given_k_0 => (),
+        "a" | "b" => {
+            return Err(Error::Unsupported(format!(
+                "Merc: variant {variant} does not support `{}`",
+                if given_lat_ts { "lat_ts" } else { "k_0" }
+            )))
+        }
+        _ => return Err(Error::BadParam("variant".to_string(), variant)),
+    }
+
     // lat_ts trumps k_0
-    if lat_ts != 0.0 {
+    if lat_ts != 0.0 || given_lat_ts {
         let sc = lat_ts.to_radians().sin_cos();
-        let k_0 = sc.1 / (1. - ellps.eccentricity_squared() * sc.0 * sc.0).sqrt();
+        let k_0 = sc.1 / (1. - es * sc.0 * sc.0).sqrt();
         params.real.insert("k_0", k_0);
     }
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    // Angular parameters are given in degrees, but used in radians
+    let lon_0 = params.lon(0).to_radians();
+    params.real.insert("lon_0", lon_0);
+
+    // Latitude of the false origin: 0 for the EPSG variants, where
+    // the natural origin is on the equator
+    let lat_0 = params.lat(0).to_radians();
+    let psi_0 = if spherical {
+        lat_0.tan().asinh()
+    } else {
+        ellps.latitude_geographic_to_isometric(lat_0)
+    };
+    params.real.insert("psi_0", psi_0);
+
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.jacobian = Some(InnerJacobian(jacobian));
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
@@ -181,4 +291,71 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn merc_spherical() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Validation value from PROJ: echo 12 55 0 0 | cct -d18 +proj=merc +R=6378137
+        let op = ctx.op("merc spherical")?;
+        let mut operands = [Coor4D::geo(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 1_335_833.889_519_282_8).abs() < 1e-6);
+        assert!((operands[0][1] - 7_361_866.113_051_185).abs() < 1e-6);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[0][1].to_degrees() - 55.).abs() < 1e-12);
+
+        // Spherical with lat_ts: k_0 = cos(lat_ts)
+        let op = ctx.op("merc ellps=6371007 lat_ts=56")?;
+        let mut operands = [Coor4D::geo(0., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        let expected = 6371007. * 56_f64.to_radians().cos() * 12_f64.to_radians();
+        assert!((operands[0][0] - expected).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn merc_variants() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        assert!(ctx.op("merc variant=A k_0=0.997").is_ok());
+        assert!(ctx.op("merc variant=b lat_ts=42").is_ok());
+        assert!(matches!(
+            ctx.op("merc variant=A lat_ts=42"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            ctx.op("merc variant=B k_0=0.997"),
+            Err(Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            ctx.op("merc variant=C"),
+            Err(Error::BadParam(_, _))
+        ));
+
+        // Explicit lat_ts=0 still trumps k_0
+        let op = ctx.op("merc lat_ts=0 k_0=0.5")?;
+        let mut operands = [Coor4D::geo(0., 1., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 6_378_137. * 1_f64.to_radians()).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn merc_false_origin() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let op = ctx.op("merc lat_0=30 lon_0=-3 x_0=500000 y_0=100000")?;
+
+        // The false origin maps to (x_0, y_0)
+        let mut operands = [Coor4D::geo(30., -3., 0., 0.), Coor4D::geo(55., 9., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 500_000.).abs() < 1e-6);
+        assert!((operands[0][1] - 100_000.).abs() < 1e-6);
+        assert!((operands[1][0] - 1_835_833.889_519_282_8).abs() < 1e-6);
+        assert!((operands[1][1] - 3_944_648.629_569_846).abs() < 1e-6);
+
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!((operands[1][0].to_degrees() - 9.).abs() < 1e-12);
+        assert!((operands[1][1].to_degrees() - 55.).abs() < 1e-12);
+        Ok(())
+    }
 }
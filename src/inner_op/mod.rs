@@ -10,73 +10,109 @@ mod addone;
 mod axisswap;
 mod btmerc;
 mod cart;
+mod clamp;
 mod curvature;
 mod deflection;
 mod deformation;
+mod epoch;
 mod geodesic;
 mod gravity;
 mod gridshift;
 mod helmert;
 mod iso6709;
 mod laea;
+#[cfg(feature = "simd")]
+mod lanes;
 mod latitude;
 mod lcc;
 mod merc;
 mod molodensky;
+mod nadcon5;
 mod noop;
 mod omerc;
 mod permtide;
 pub(crate) mod pipeline; // Needed by Op for instantiation
+mod primem;
 mod pushpop;
+mod rhumb;
+mod round;
+mod select;
+mod set;
 mod somerc;
 mod stack;
+mod tinshift;
+mod tissot;
 mod tmerc;
+mod tmerc_exact;
+mod trace;
 mod unitconvert;
 mod units;
+mod vertical_datum;
 mod webmerc;
 
+// Name, constructor, parameter gamut, test vectors, and description
+type BuiltinOperator = (
+    &'static str,
+    OpConstructor,
+    &'static [OpParameter],
+    &'static [OpTestVector],
+    &'static str,
+);
+
 #[rustfmt::skip]
-const BUILTIN_OPERATORS: [(&str, OpConstructor); 36] = [
-    ("adapt",        OpConstructor(adapt::new)),
-    ("addone",       OpConstructor(addone::new)),
-    ("axisswap",     OpConstructor(axisswap::new)),
-    ("btmerc",       OpConstructor(btmerc::new)),
-    ("butm",         OpConstructor(btmerc::utm)),
-    ("cart",         OpConstructor(cart::new)),
-    ("curvature",    OpConstructor(curvature::new)),
-    ("deflection",   OpConstructor(deflection::new)),
-    ("deformation",  OpConstructor(deformation::new)),
-    ("dm",           OpConstructor(iso6709::dm)),
-    ("dms",          OpConstructor(iso6709::dms)),
-    ("geodesic",     OpConstructor(geodesic::new)),
-    ("gravity",      OpConstructor(gravity::new)),
-    ("gridshift",    OpConstructor(gridshift::new)),
-    ("helmert",      OpConstructor(helmert::new)),
-    ("laea",         OpConstructor(laea::new)),
-    ("latitude",     OpConstructor(latitude::new)),
-    ("lcc",          OpConstructor(lcc::new)),
-    ("merc",         OpConstructor(merc::new)),
-    ("webmerc",      OpConstructor(webmerc::new)),
-    ("molodensky",   OpConstructor(molodensky::new)),
-    ("omerc",        OpConstructor(omerc::new)),
-    ("permtide",     OpConstructor(permtide::new)),
-    ("somerc",       OpConstructor(somerc::new)),
-    ("tmerc",        OpConstructor(tmerc::new)),
-    ("unitconvert",  OpConstructor(unitconvert::new)),
-    ("utm",          OpConstructor(tmerc::utm)),
+const BUILTIN_OPERATORS: [BuiltinOperator; 48] = [
+    ("adapt",        OpConstructor(adapt::new),        &adapt::GAMUT,        &[],                       "Adapt axis order, axis direction, and angular units"),
+    ("addone",       OpConstructor(addone::new),       &addone::GAMUT,       &[],                       "Add 1 to the first coordinate (for testing)"),
+    ("axisswap",     OpConstructor(axisswap::new),     &axisswap::GAMUT,     &[],                       "Swap axis order and direction, PROJ style"),
+    ("btmerc",       OpConstructor(btmerc::new),       &btmerc::GAMUT,       &[],                       "Transverse Mercator, following Bowring (1989)"),
+    ("butm",         OpConstructor(btmerc::utm),       &btmerc::UTM_GAMUT,   &[],                       "Universal Transverse Mercator, following Bowring (1989)"),
+    ("cart",         OpConstructor(cart::new),         &cart::GAMUT,         &cart::TEST_VECTORS,       "Geographical to cartesian coordinates"),
+    ("clamp",        OpConstructor(clamp::new),        &clamp::GAMUT,        &[],                       "Clip coordinates into a bounding box or ranges"),
+    ("curvature",    OpConstructor(curvature::new),    &curvature::GAMUT,    &[],                       "Ellipsoidal radii of curvature"),
+    ("deflection",   OpConstructor(deflection::new),   &deflection::GAMUT,   &[],                       "Deflection of the vertical, from a geoid model"),
+    ("deformation",  OpConstructor(deformation::new),  &deformation::GAMUT,  &[],                       "Kinematic datum shift, using a 3D deformation model"),
+    ("dm",           OpConstructor(iso6709::dm),       &iso6709::GAMUT,      &[],                       "Angles in ISO-6709 style DDDMM.mmm format to degrees"),
+    ("dms",          OpConstructor(iso6709::dms),      &iso6709::GAMUT,      &[],                       "Angles in ISO-6709 style DDDMMSS.sss format to degrees"),
+    ("epoch",        OpConstructor(epoch::new),        &epoch::GAMUT,        &[],                       "Set, shift, or convert the time coordinate"),
+    ("geodesic",     OpConstructor(geodesic::new),     &geodesic::GAMUT,     &[],                       "Direct and inverse geodesics"),
+    ("gravity",      OpConstructor(gravity::new),      &gravity::GAMUT,      &[],                       "Normal gravity"),
+    ("gridshift",    OpConstructor(gridshift::new),    &gridshift::GAMUT,    &[],                       "Datum shift using grid interpolation"),
+    ("helmert",      OpConstructor(helmert::new),      &helmert::GAMUT,      &helmert::TEST_VECTORS,    "Helmert transformation, static or time dependent"),
+    ("laea",         OpConstructor(laea::new),         &laea::GAMUT,         &laea::TEST_VECTORS,       "Lambert azimuthal equal area"),
+    ("latitude",     OpConstructor(latitude::new),     &latitude::GAMUT,     &[],                       "Auxiliary latitudes"),
+    ("lcc",          OpConstructor(lcc::new),          &lcc::GAMUT,          &lcc::TEST_VECTORS,        "Lambert conformal conic"),
+    ("merc",         OpConstructor(merc::new),         &merc::GAMUT,         &merc::TEST_VECTORS,       "Mercator"),
+    ("webmerc",      OpConstructor(webmerc::new),      &webmerc::GAMUT,      &webmerc::TEST_VECTORS,    "Web Mercator"),
+    ("molodensky",   OpConstructor(molodensky::new),   &molodensky::GAMUT,   &molodensky::TEST_VECTORS, "Full and abridged Molodensky datum shifts"),
+    ("nadcon5",      OpConstructor(nadcon5::new),      &nadcon5::GAMUT,      &[],                       "NADCON5 datum shifts, chaining the per-step grids"),
+    ("omerc",        OpConstructor(omerc::new),        &omerc::GAMUT,        &omerc::TEST_VECTORS,      "Oblique Mercator"),
+    ("permtide",     OpConstructor(permtide::new),     &permtide::GAMUT,     &[],                       "Conversions between permanent tide systems"),
+    ("primem",       OpConstructor(primem::new),       &primem::GAMUT,       &[],                       "Change of prime meridian"),
+    ("rhumb",        OpConstructor(rhumb::new),        &rhumb::GAMUT,        &[],                       "Direct and inverse rhumb lines (loxodromes)"),
+    ("round",        OpConstructor(round::new),        &round::GAMUT,        &[],                       "Round to a given number of decimals, or to a quantum"),
+    ("select",       OpConstructor(select::new),       &select::GAMUT,       &[],                       "Mark coordinates outside a bounding box or ranges as invalid"),
+    ("set",          OpConstructor(set::new),          &set::GAMUT,          &[],                       "Assign constant values to coordinate elements"),
+    ("somerc",       OpConstructor(somerc::new),       &somerc::GAMUT,       &[],                       "Swiss oblique Mercator"),
+    ("tinshift",     OpConstructor(tinshift::new),     &tinshift::GAMUT,     &[],                       "Triangulation based transformation (PROJ tinshift format)"),
+    ("tmerc",        OpConstructor(tmerc::new),        &tmerc::GAMUT,        &tmerc::TEST_VECTORS,      "Transverse Mercator, following Engsager & Poder (2007)"),
+    ("unitconvert",  OpConstructor(unitconvert::new),  &unitconvert::GAMUT,  &[],                       "Conversion between linear and temporal units"),
+    ("utm",          OpConstructor(tmerc::utm),        &tmerc::UTM_GAMUT,    &tmerc::UTM_TEST_VECTORS,  "Universal Transverse Mercator"),
+    ("vertical_datum", OpConstructor(vertical_datum::new), &vertical_datum::GAMUT, &[],                   "Ellipsoidal to physical heights, using a stack of regional geoid models"),
 
     // Pipeline handlers
-    ("pipeline",     OpConstructor(pipeline::new)),
-    ("pop",          OpConstructor(pushpop::pop)),
-    ("push",         OpConstructor(pushpop::push)),
-    ("stack",        OpConstructor(stack::new)),
+    ("pipeline",     OpConstructor(pipeline::new),     &pipeline::GAMUT,     &[],                       "Sequence of operator steps"),
+    ("pop",          OpConstructor(pushpop::pop),      &pushpop::PUSH_POP_GAMUT, &[],                       "Pop coordinates from the pipeline stack (deprecated: use stack)"),
+    ("push",         OpConstructor(pushpop::push),     &pushpop::PUSH_POP_GAMUT, &[],                       "Push coordinates to the pipeline stack (deprecated: use stack)"),
+    ("stack",        OpConstructor(stack::new),        &stack::STACK_GAMUT,  &[],                       "Pipeline stack manipulation"),
+    ("tissot",       OpConstructor(tissot::new),       &tissot::GAMUT,       &[],                       "Distortion parameters of the preceding projection"),
+    ("trace",        OpConstructor(trace::new),        &trace::GAMUT,        &[],                       "Log the coordinates passing through a pipeline"),
 
     // Some commonly used noop-aliases
-    ("noop",         OpConstructor(noop::new)),
-    ("longlat",      OpConstructor(noop::new)),
-    ("latlon",       OpConstructor(noop::new)),
-    ("latlong",      OpConstructor(noop::new)),
-    ("lonlat",       OpConstructor(noop::new)),
+    ("noop",         OpConstructor(noop::new),         &noop::GAMUT,         &[],                       "The no-operation"),
+    ("longlat",      OpConstructor(noop::new),         &noop::GAMUT,         &[],                       "Alias for noop"),
+    ("latlon",       OpConstructor(noop::new),         &noop::GAMUT,         &[],                       "Alias for noop"),
+    ("latlong",      OpConstructor(noop::new),         &noop::GAMUT,         &[],                       "Alias for noop"),
+    ("lonlat",       OpConstructor(noop::new),         &noop::GAMUT,         &[],                       "Alias for noop"),
 ];
 // A BTreeMap would have been a better choice for BUILTIN_OPERATORS, except
 // for the annoying fact that it cannot be compile-time const-constructed.
@@ -92,6 +128,53 @@ pub(crate) fn builtin(name: &str) -> Result<OpConstructor, Error> {
     Err(Error::NotFound(name.to_string(), String::default()))
 }
 
+/// Introspection: The name, a short description, the parameter gamut, and
+/// the self-test vectors of a builtin operator
+#[derive(Debug, Clone, Copy)]
+pub struct OpDocumentation {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub gamut: &'static [OpParameter],
+    pub test_vectors: &'static [OpTestVector],
+}
+
+/// A canonical test point for an operator, typically from EPSG Guidance
+/// Note 7-2, cf. [`Context::selftest()`]. The `definition` includes any
+/// adaptors needed, so `input` and `output` are given in the units of the
+/// source, i.e. typically degrees and meters
+#[derive(Debug, Clone, Copy)]
+pub struct OpTestVector {
+    pub source: &'static str,
+    pub definition: &'static str,
+    pub input: [f64; 4],
+    pub output: [f64; 4],
+    /// Tolerance for each element of the output of the forward operation
+    pub tolerance: [f64; 4],
+    /// Tolerance for each element of the output of the inverse operation
+    pub inverse_tolerance: [f64; 4],
+}
+
+/// The documentation of all builtin operators, in registration order
+pub fn builtin_operators() -> Vec<OpDocumentation> {
+    BUILTIN_OPERATORS
+        .iter()
+        .map(|p| OpDocumentation {
+            name: p.0,
+            description: p.4,
+            gamut: p.2,
+            test_vectors: p.3,
+        })
+        .collect()
+}
+
+/// The documentation of the builtin operator `name`
+pub fn builtin_operator(name: &str) -> Result<OpDocumentation, Error> {
+    builtin_operators()
+        .into_iter()
+        .find(|doc| doc.name == name)
+        .ok_or(Error::NotFound(name.to_string(), String::default()))
+}
+
 // ----- S T R U C T   O P C O N S T R U C T O R ---------------------------------------
 
 /// Blueprint for the overall instantiation of an operator.
@@ -115,6 +198,7 @@ impl core::fmt::Debug for OpConstructor {
 /// InnerOp needs to be a newtype, rather than a type alias, since we
 /// must implement the Debug-trait for InnerOp (to make auto derive
 /// of the Debug-trait work for any derived type).
+#[derive(Clone, Copy)]
 pub struct InnerOp(pub fn(op: &Op, ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize);
 
 // Cannot autoderive the Debug trait
@@ -136,3 +220,53 @@ fn noop_placeholder(_op: &Op, _ctx: &dyn Context, _operands: &mut dyn Coordinate
     // non-existing or non-implemented inverse operation
     0
 }
+
+// ----- H E L P E R S -----------------------------------------------------------------
+
+/// Apply `f` to each coordinate tuple of `operands`, returning the number of
+/// tuples for which `f` reports success. The workhorse of the operator
+/// implementations: For sets stored as contiguous `Coor4D`s (cf.
+/// [`CoordinateSet::as_coor4d_mut()`]), the closure is applied directly to
+/// the slice elements, in a loop the compiler can monomorphize and inline,
+/// rather than through a pair of dynamically dispatched
+/// `get_coord()`/`set_coord()` calls per tuple
+#[inline]
+pub fn for_each_coord(
+    operands: &mut dyn CoordinateSet,
+    mut f: impl FnMut(&mut Coor4D) -> bool,
+) -> usize {
+    if let Some(coords) = operands.as_coor4d_mut() {
+        return coords.iter_mut().map(|coord| usize::from(f(coord))).sum();
+    }
+    let mut successes = 0;
+    for i in 0..operands.len() {
+        let mut coord = operands.get_coord(i);
+        successes += usize::from(f(&mut coord));
+        operands.set_coord(i, &coord);
+    }
+    successes
+}
+
+// ----- S T R U C T   I N N E R J A C O B I A N ---------------------------------------
+
+/// Blueprint for functions computing the analytical partial derivatives of
+/// the *forward* transformation of an operator, at the point `at` (given in
+/// the input units of the operator).
+///
+/// The return value is `[∂x/∂u, ∂y/∂u, ∂x/∂v, ∂y/∂v]`, where *u, v* are the
+/// first two input coordinates, and *x, y* the first two output coordinates,
+/// i.e. for a projection `[∂E/∂λ, ∂N/∂λ, ∂E/∂𝜙, ∂N/∂𝜙]`, in metres per radian.
+/// Returns `None` where the derivatives are undefined, or where the first two
+/// output coordinates depend on more than the first two input coordinates.
+///
+/// Like InnerOp, InnerJacobian needs to be a newtype to support autoderive
+/// of the Debug-trait for derived types.
+#[derive(Clone, Copy)]
+pub struct InnerJacobian(pub fn(op: &Op, ctx: &dyn Context, at: &Coor4D) -> Option<[f64; 4]>);
+
+// Cannot autoderive the Debug trait
+impl core::fmt::Debug for InnerJacobian {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "InnerJacobian")
+    }
+}
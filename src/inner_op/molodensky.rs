@@ -25,7 +25,9 @@ fn common(
     operands: &mut dyn CoordinateSet,
     direction: Direction,
 ) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
     let f = ellps.flattening();
     let es = ellps.eccentricity_squared();
@@ -46,6 +48,11 @@ fn common(
         return 0;
     };
     let adffda = ellps.semimajor_axis() * df + ellps.flattening() * da;
+
+    // Height handling: Use a fixed height in the computations, and/or leave
+    // the height coordinate untouched
+    let keep_height = op.params.boolean("keep_height");
+    let h_fixed = op.params.real("h_fixed").unwrap_or(f64::NAN);
     let moped = Molodensky {
         a,
         f,
@@ -64,7 +71,15 @@ fn common(
 
     for i in 0..n {
         let mut coord = operands.get_coord(i);
-        let par = calc_molodensky_params(&moped, &coord);
+        let mut par = if h_fixed.is_nan() {
+            calc_molodensky_params(&moped, &coord)
+        } else {
+            let fixed = Coor4D::raw(coord[0], coord[1], h_fixed, coord[3]);
+            calc_molodensky_params(&moped, &fixed)
+        };
+        if keep_height {
+            par[2] = 0.;
+        }
         if direction == Fwd {
             coord[0] += par[0];
             coord[1] += par[1];
@@ -93,9 +108,11 @@ fn inv(op: &Op, ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 10] = [
+pub const GAMUT: [OpParameter; 12] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Flag { key: "abridged" },
+    OpParameter::Flag { key: "keep_height" },
+    OpParameter::Real { key: "h_fixed", default: Some(f64::NAN) },
     OpParameter::Real { key: "dx", default: Some(0f64) },
     OpParameter::Real { key: "dy", default: Some(0f64) },
     OpParameter::Real { key: "dz", default: Some(0f64) },
@@ -106,12 +123,42 @@ pub const GAMUT: [OpParameter; 10] = [
     OpParameter::Text { key: "ellps_1",  default: Some("GRS80") },
 ];
 
+// Molodensky transformation, EPSG method 9604, and the AGD66 to WGS84
+// example from Deakin (2004), in its full and abridged forms
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 3] = [
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Molodensky transformation (9604)",
+        definition: "geo:in | molodensky dx=84.87 dy=96.49 dz=116.95 da=251 df=1.41927e-5 ellps=WGS84 | geo:out",
+        input: [53. + 48. / 60. + 33.82 / 3600., 2. + 7. / 60. + 46.38 / 3600., 73., 0.],
+        output: [53. + 48. / 60. + 36.565 / 3600., 2. + 7. / 60. + 51.477 / 3600., 28.02, 0.],
+        tolerance: [1e-6, 1e-6, 0.01, 0.],
+        inverse_tolerance: [1e-6, 1e-6, 0.01, 0.],
+    },
+    OpTestVector {
+        source: "Deakin (2004), AGD66 to WGS84, standard Molodensky",
+        definition: "geo:in | molodensky ellps=6378160,298.25 da=-23 df=-8.120449e-8 dx=-134 dy=-48 dz=149 | geo:out",
+        input: [-37.8, 144.9667, 50., 0.],
+        output: [-37.79848, 144.968, 46.378, 0.],
+        tolerance: [1e-5, 1e-4, 1e-3, 0.],
+        inverse_tolerance: [1e-5, 1e-4, 0.01, 0.],
+    },
+    OpTestVector {
+        source: "Deakin (2004), AGD66 to WGS84, abridged Molodensky",
+        definition: "geo:in | molodensky abridged ellps=6378160,298.25 da=-23 df=-8.120449e-8 dx=-134 dy=-48 dz=149 | geo:out",
+        input: [-37.8, 144.9667, 50., 0.],
+        output: [-37.79848, 144.968, 46.378, 0.],
+        tolerance: [1e-5, 1e-4, 1e-3, 0.],
+        inverse_tolerance: [1e-5, 1e-4, 0.01, 0.],
+    },
+];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
 
-    let ellps_0 = params.ellps(0);
-    let ellps_1 = params.ellps(1);
+    let ellps_0 = params.ellps(0)?;
+    let ellps_1 = params.ellps(1)?;
 
     // We may use `ellps, da, df`, to parameterize the op, but `ellps_0, ellps_1`
     // is a more likely set of parameters to come across in real life.
@@ -277,4 +324,75 @@ mod tests {
         assert!((WGS84[2] - operands[0][2]).abs() < 0.075);
         Ok(())
     }
+
+    #[test]
+    fn deakin() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let e = Ellipsoid::named("WGS84")?;
+
+        // Deakin (2004): AGD66 (ANS) to WGS84, with the parameters and test
+        // point of the numerical example, at 37°48'S 144°58'E, h = 50 m. The
+        // published result is given to 5 decimals in latitude, and 3 in
+        // longitude and height, so for the latitude and longitude we compare
+        // against a direct 3 parameter Helmert computation with the same
+        // constants, as above
+        let parameters = "ellps=6378160,298.25 da=-23 df=-8.120449e-8 dx=-134 dy=-48 dz=149";
+        let AGD66 = Coor4D::geo(-37.8, 144.9667, 50., 0.);
+        let WGS84 = Coor4D::geo(-37.798_480_355_4, 144.968_019_660_3, 46.378, 0.);
+
+        // The full formulas reproduce Helmert to the cm level in the plane,
+        // and the published height to the mm level
+        let op = ctx.op(&format!("molodensky {parameters}"))?;
+        let mut operands = [AGD66];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!(e.distance(&WGS84, &operands[0]) < 0.01);
+        assert!((operands[0][2] - WGS84[2]).abs() < 1e-3);
+        ctx.apply(op, Inv, &mut operands)?;
+        assert!(e.distance(&AGD66, &operands[0]) < 0.01);
+
+        // The abridged formulas are within a few cm in the plane, and the
+        // height is within a mm of the published value
+        let op = ctx.op(&format!("molodensky abridged {parameters}"))?;
+        let mut operands = [AGD66];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!(e.distance(&WGS84, &operands[0]) < 0.05);
+        assert!((operands[0][2] - WGS84[2]).abs() < 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn height_handling() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let e = Ellipsoid::default();
+        let shift = "molodensky ellps_0=WGS84 ellps_1=intl dx=84.87 dy=96.49 dz=116.95";
+        let WGS84 = Coor4D::geo(53.80939444444444, 2.12955, 73., 0.0);
+
+        // keep_height: The plane coordinates are shifted, the height is not
+        let full = ctx.op(shift)?;
+        let kept = ctx.op(&format!("{shift} keep_height"))?;
+        let mut a = [WGS84];
+        let mut b = [WGS84];
+        ctx.apply(full, Fwd, &mut a)?;
+        ctx.apply(kept, Fwd, &mut b)?;
+        assert_eq!(a[0][0], b[0][0]);
+        assert_eq!(a[0][1], b[0][1]);
+        assert_eq!(b[0][2], 73.);
+        ctx.apply(kept, Inv, &mut b)?;
+        assert_eq!(b[0][2], 73.);
+
+        // h_fixed: The third coordinate is ignored in the computations, so
+        // a fixed height of 73 m, given with a dummy z of 1000, matches the
+        // ordinary case, apart from the dummy offset
+        let fixed = ctx.op(&format!("{shift} h_fixed=73"))?;
+        let mut c = [Coor4D::geo(53.80939444444444, 2.12955, 1000., 0.0)];
+        ctx.apply(fixed, Fwd, &mut c)?;
+        assert!(e.distance(&a[0], &c[0]) < 1e-9);
+        assert!((c[0][2] - 1000. - (a[0][2] - 73.)).abs() < 1e-9);
+
+        // ... while the full formulas are (slightly) sensitive to the height
+        let mut d = [Coor4D::geo(53.80939444444444, 2.12955, 1000., 0.0)];
+        ctx.apply(full, Fwd, &mut d)?;
+        assert!(e.distance(&c[0], &d[0]) > 1e-4);
+        Ok(())
+    }
 }
@@ -0,0 +1,202 @@
+/// NADCON5 datum shifts, chaining the per-step grids of the NGS NADCON5
+/// distribution, e.g. `nadcon5 region=conus chain=nad27,nad83_1986,nad83_harn`.
+///
+/// Each step of the chain is given by a set of `.b` grids, named as in the
+/// NADCON5 distribution, `nadcon5.<from>.<to>.<region>.<lat|lon|eht>.trn.<date>.b`.
+/// The latitude and longitude grids are mandatory, while the ellipsoidal height
+/// grid is optional, as NADCON5 does not provide one for all steps. In the
+/// forward direction, the steps are applied in the order given, by bilinear
+/// interpolation in the grids. The inverse is iterative, step by step.
+///
+/// Note that NADCON5 proper interpolates using biquadratic splines, so results
+/// may differ slightly (typically at the sub-millimeter level) from those of
+/// the NGS reference implementation.
+use crate::authoring::*;
+use std::sync::Arc;
+
+// The convergence criterion for the iterative inverse, in radians (~0.1 mm)
+const TOL: f64 = 1e-11;
+const MAXITER: usize = 10;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(steps) = op.params.series("steps") else {
+        return 0;
+    };
+
+    for_each_coord(operands, |coord| {
+        let mut first = 0;
+        for &n in steps {
+            let grids = &op.params.grids[first..first + n as usize];
+            first += n as usize;
+            let Some(d) = correction(grids, coord) else {
+                // Outside of the grids, so we stomp on the coordinate
+                *coord = Coor4D::nan();
+                return false;
+            };
+            *coord = *coord + d;
+        }
+        true
+    })
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(steps) = op.params.series("steps") else {
+        return 0;
+    };
+
+    let success = |coord: &mut Coor4D| {
+        let mut last = op.params.grids.len();
+        'steps: for &n in steps.iter().rev() {
+            let grids = &op.params.grids[last - n as usize..last];
+            last -= n as usize;
+
+            let Some(d) = correction(grids, coord) else {
+                return false;
+            };
+            let mut t = *coord - d;
+            for _ in 0..MAXITER {
+                let Some(d) = correction(grids, &t) else {
+                    return false;
+                };
+                let next = *coord - d;
+                let delta = (next[0] - t[0]).hypot(next[1] - t[1]);
+                t = next;
+                if delta < TOL {
+                    *coord = t;
+                    continue 'steps;
+                }
+            }
+            // No convergence
+            return false;
+        }
+        true
+    };
+
+    for_each_coord(operands, |coord| {
+        if success(coord) {
+            return true;
+        }
+        // Outside of the grids, or no convergence, so we stomp on the coordinate
+        *coord = Coor4D::nan();
+        false
+    })
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+
+// The correction for a single step, from its latitude, longitude and
+// (optionally) ellipsoidal height grids. The grids convert the angular
+// corrections from seconds of arc to radians, according to their units
+fn correction(grids: &[Arc<dyn Grid>], coord: &Coor4D) -> Option<Coor4D> {
+    let dlat = grids[0].at(coord, 0.)?[0];
+    let dlon = grids[1].at(coord, 0.)?[0];
+    let deht = match grids.get(2) {
+        Some(grid) => grid.at(coord, 0.)?[0],
+        None => 0.,
+    };
+    Some(Coor4D::raw(dlon, dlat, deht, 0.))
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 4] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "region", default: Some("conus") },
+    OpParameter::Texts { key: "chain", default: None },
+    OpParameter::Text { key: "date", default: Some("20160901") },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    let region = op.params.text("region")?;
+    let date = op.params.text("date")?;
+    let chain = op.params.texts("chain")?.clone();
+    if chain.len() < 2 {
+        return Err(Error::BadParam("chain".to_string(), chain.join(",")));
+    }
+
+    // The number of grids for each step: 2 without, and 3 with, height shifts
+    let mut steps = Vec::new();
+    for step in chain.windows(2) {
+        let name = |kind: &str| {
+            let (from, to) = (&step[0], &step[1]);
+            format!("nadcon5.{from}.{to}.{region}.{kind}.trn.{date}.b")
+        };
+        for kind in ["lat", "lon"] {
+            let grid = ctx.get_grid(&name(kind))?;
+            if !grid.units().is_angular() {
+                return Err(Error::Unexpected {
+                    message: format!("Units of NADCON5 {kind} grid"),
+                    expected: "angular".to_string(),
+                    found: format!("{:?}", grid.units().unit),
+                });
+            }
+            op.params.grids.push(grid);
+        }
+        match ctx.get_grid(&name("eht")) {
+            Ok(grid) => {
+                op.params.grids.push(grid);
+                steps.push(3.);
+            }
+            Err(Error::NotFound(..)) => steps.push(2.),
+            Err(e) => return Err(e),
+        }
+    }
+    op.params.series.insert("steps", steps);
+    Ok(op)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nadcon5() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let op = ctx.op("nadcon5 region=test chain=test_a,test_b")?;
+
+        // At 37N, 100W (row 4, column 10 of the test grids), the shifts are
+        // 0.56" of latitude, -1.588" of longitude, and -0.196 m of height
+        let mut data = [Coor4D::geo(37., -100., 100., 0.)];
+        let expected = Coor4D::geo(37. + 0.56 / 3600., -100. - 1.588 / 3600., 99.804, 0.);
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert!((data[0][0] - expected[0]).abs() < 1e-11);
+        assert!((data[0][1] - expected[1]).abs() < 1e-11);
+        assert!((data[0][2] - expected[2]).abs() < 1e-6);
+
+        // Roundtrip
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0] - (-100_f64).to_radians()).abs() < 1e-11);
+        assert!((data[0][1] - 37_f64.to_radians()).abs() < 1e-11);
+        assert!((data[0][2] - 100.).abs() < 1e-6);
+
+        // Chaining: The second step has no height grid, and constant shifts
+        let op = ctx.op("nadcon5 region=test chain=test_a,test_b,test_c")?;
+        let mut data = [Coor4D::geo(37., -100., 100., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        let expected = Coor4D::geo(37. + 0.66 / 3600., -100. - 1.388 / 3600., 99.804, 0.);
+        assert!((data[0][0] - expected[0]).abs() < 1e-11);
+        assert!((data[0][1] - expected[1]).abs() < 1e-11);
+        assert!((data[0][2] - expected[2]).abs() < 1e-6);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0] - (-100_f64).to_radians()).abs() < 1e-11);
+        assert!((data[0][1] - 37_f64.to_radians()).abs() < 1e-11);
+
+        // Outside of the grids
+        let mut data = [Coor4D::geo(50., -100., 0., 0.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 0);
+        assert!(data[0][0].is_nan());
+
+        // Missing grids, and too short chains
+        assert!(ctx.op("nadcon5 region=test chain=test_a,test_c").is_err());
+        assert!(ctx.op("nadcon5 region=test chain=test_a").is_err());
+        Ok(())
+    }
+}
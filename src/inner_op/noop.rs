@@ -13,6 +13,12 @@ fn inv(_op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize
     operands.len()
 }
 
+// ----- D E R I V A T I V E S ------------------------------------------------------
+
+fn jacobian(_op: &Op, _ctx: &dyn Context, _at: &Coor4D) -> Option<[f64; 4]> {
+    Some([1., 0., 0., 1.])
+}
+
 // ----- C O N S T R U C T O R ------------------------------------------------------
 
 // Example...
@@ -21,7 +27,9 @@ pub const GAMUT: [OpParameter; 0] = [
 ];
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
-    Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)
+    let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    op.descriptor.jacobian = Some(InnerJacobian(jacobian));
+    Ok(op)
 }
 
 // ----- T E S T S ------------------------------------------------------------------
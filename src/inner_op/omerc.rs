@@ -8,8 +8,10 @@ use std::f64::consts::FRAC_PI_4;
 // ----- F O R W A R D -----------------------------------------------------------------
 
 #[allow(non_snake_case)]
-fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+pub(super) fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let es = ellps.eccentricity_squared();
     let e = es.sqrt();
 
@@ -26,22 +28,10 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let alpha = op.params.real["alpha"];
     let ninety = alpha == 90_f64;
     let alpha = alpha.to_radians();
+    let gamma_c = op.params.real["gamma_c"].to_radians();
 
-    // Detect the Laborde case by a missing gamma_c
-    let mut gamma_c = op.params.real["gamma_c"];
-    let laborde = gamma_c.is_nan();
-    gamma_c = gamma_c.to_radians();
-
-    // Discern between Hotine variant A and B cases, and the Laborde
-    // case, which we currently approximate by Hotine with gamma_c = alpha
-    let mut variant = op.params.boolean("variant");
-    if laborde {
-        variant = true;
-        gamma_c = alpha;
-    }
-
-    // Remove mutability
-    let gamma_c = gamma_c;
+    // Hotine variant B (or the Laborde approximation), rather than A?
+    let variant = op.params.boolean("variant");
 
     // A horrible mess of constants. But by-and-large, just a transcription of
     // the material from Guidance Note 7-2.
@@ -54,8 +44,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let F = D + DD * latc.signum();
     let H = F * t0.powf(B);
     let G = (F - 1.0 / F) / 2.0;
-    let gamma_0 = (alpha.sin() / D).asin();
-    let lambda_0 = lonc - (G * gamma_0.tan()).asin() / B;
+    let gamma_0 = (alpha.sin() / D).clamp(-1.0, 1.0).asin();
+    // For alpha = 90°, the argument is 1, up to rounding errors
+    let lambda_0 = lonc - (G * gamma_0.tan()).clamp(-1.0, 1.0).asin() / B;
 
     // (uc, vc): Intermediate coordinates of the projection center
     // let vc = 0.0;
@@ -94,23 +85,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
             continue;
         }
 
-        // Variant B and/or Laborde
-
-        // The special case
-        if ninety {
-            let u = if lon == lambda_0 {
-                0.0
-            } else {
-                A * (S * c0 + V * s0).atan2(cblon) / B - uc.copysign(latc) * (lonc - lon).signum()
-            };
-            let x = v * cc + u * sc + Ec;
-            let y = u * cc - v * sc + Nc;
-            operands.set_xy(i, x, y);
-            successes += 1;
-            continue;
-        }
-
-        // The general case
+        // Variant B and/or Laborde. Note that GN 7-2 gives a special case for
+        // alpha = 90°, compensating for the branch change of atan at the
+        // projection center. Since we use atan2, the general case covers it
         let u = A * (S * c0 + V * s0).atan2(cblon) / B - uc.copysign(latc);
         let x = v * cc + u * sc + Ec;
         let y = u * cc - v * sc + Nc;
@@ -124,8 +101,10 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- I N V E R S E -----------------------------------------------------------------
 
 #[allow(non_snake_case)]
-fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+pub(super) fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let es = ellps.eccentricity_squared();
     let e = es.sqrt();
 
@@ -140,15 +119,10 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let alpha = op.params.real["alpha"];
     let ninety = alpha == 90_f64;
     let alpha = alpha.to_radians();
+    let gamma_c = op.params.real["gamma_c"].to_radians();
 
-    // Detect the Laborde case by a missing gamma_c
-    let gamma_c = op.params.real["gamma_c"];
-    let laborde = gamma_c.is_nan();
-
-    // Discern between Hotine variant A and B cases, and the Laborde
-    // case, which we currently approximate by Hotine with gamma_c = alpha
-    let gamma_c = if laborde { alpha } else { gamma_c.to_radians() };
-    let variant = op.params.boolean("variant") || laborde;
+    // Hotine variant B (or the Laborde approximation), rather than A?
+    let variant = op.params.boolean("variant");
 
     // A horrible mess of constants. But by-and-large, just a transcription of
     // the material from Guidance Note 7-2.
@@ -161,8 +135,9 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let F = D + DD * latc.signum();
     let H = F * t0.powf(B);
     let G = (F - 1.0 / F) / 2.0;
-    let gamma_0 = (alpha.sin() / D).asin();
-    let lambda_0 = lonc - (G * gamma_0.tan()).asin() / B;
+    let gamma_0 = (alpha.sin() / D).clamp(-1.0, 1.0).asin();
+    // For alpha = 90°, the argument is 1, up to rounding errors
+    let lambda_0 = lonc - (G * gamma_0.tan()).clamp(-1.0, 1.0).asin() / B;
 
     // (uc, vc): Intermediate coordinates of the projection center
     // let vc = 0.0;
@@ -220,23 +195,29 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 10] = [
+pub const GAMUT: [OpParameter; 15] = [
     OpParameter::Flag { key: "inv" },
 
-    // Default to Hotine Variant A
+    // Default to Hotine Variant A when gamma_c is given, otherwise Laborde
     OpParameter::Flag { key: "variant" }, // Set if Hotine variant B
+    OpParameter::Flag { key: "no_uoff" }, // PROJ style explicit variant A
+    OpParameter::Flag { key: "no_off" },  // ... also PROJ style
+    OpParameter::Flag { key: "laborde" }, // Variant B, with gamma_c = alpha
 
     OpParameter::Text { key: "ellps",  default: Some("GRS80") },
 
-    // Projection center. Note: PROJ uses (lat_0, lonc).
+    // Projection center. Note: PROJ uses (lat_0, lonc), which we also accept
     OpParameter::Real { key: "latc",  default: Some(0_f64) },
     OpParameter::Real { key: "lonc",  default: Some(0_f64) },
+    OpParameter::Real { key: "lat_0", default: Some(0_f64) },
 
     // Azimuth of the initial line
     OpParameter::Real { key: "alpha",  default: Some(f64::NAN) },
 
-    // Angle from the rectified grid to the oblique grid (Hotine only)
+    // Angle from the rectified grid to the oblique grid (Hotine only).
+    // `gamma` is the PROJ name
     OpParameter::Real { key: "gamma_c",  default: Some(f64::NAN) },
+    OpParameter::Real { key: "gamma",    default: Some(f64::NAN) },
 
     // False nothing/easting - at natural origin (Hotine variant A)
     // or projection center (Hotine variant B)
@@ -247,9 +228,57 @@ pub const GAMUT: [OpParameter; 10] = [
     OpParameter::Real { key: "k_0",    default: Some(1_f64) },
 ];
 
+// Hotine oblique Mercator, EPSG methods 9812 (variant A) and 9815 (variant B)
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 2] = [
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Hotine Oblique Mercator variant A (9812)",
+        definition: "geo:in | omerc ellps=evrstSS no_uoff latc=4 lonc=115 k_0=0.99984 alpha=53:18:56.9537 gamma_c=53:07:48.3685",
+        input: [5.3872535833, 115.8055054444, 0., 0.],
+        output: [679_245.73, 596_562.78, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+    OpTestVector {
+        source: "EPSG Guidance Note 7-2, Hotine Oblique Mercator variant B (9815)",
+        definition: "geo:in | omerc ellps=evrstSS variant x_0=590476.87 y_0=442857.65 latc=4 lonc=115 k_0=0.99984 alpha=53:18:56.9537 gamma_c=53:07:48.3685",
+        input: [5.3872535833, 115.8055054444, 0., 0.],
+        output: [679_245.73, 596_562.78, 0., 0.],
+        tolerance: [0.01, 0.01, 0., 0.],
+        inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+    },
+];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
-    let params = ParsedParameters::new(parameters, &GAMUT)?;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    // PROJ names for the latitude of the projection center, and the rectified skew
+    if !params.given.contains_key("latc") && params.given.contains_key("lat_0") {
+        params.real.insert("latc", params.lat(0));
+    }
+    if params.real["gamma_c"].is_nan() {
+        params.real.insert("gamma_c", params.real["gamma"]);
+    }
+
+    // Variant A, B, or the Laborde approximation. A missing gamma_c implies
+    // the latter, unless variant A is explicitly asked for
+    let no_uoff = params.boolean("no_uoff") || params.boolean("no_off");
+    if no_uoff && (params.boolean("variant") || params.boolean("laborde")) {
+        return Err(Error::BadParam(
+            "no_uoff".to_string(),
+            "Omerc: Hotine variant A conflicts with variant B/laborde".to_string(),
+        ));
+    }
+    let laborde = params.boolean("laborde") || (params.real["gamma_c"].is_nan() && !no_uoff);
+    if laborde {
+        params.boolean.insert("laborde");
+        params.boolean.insert("variant");
+        params.real.insert("gamma_c", params.real["alpha"]);
+    }
+
+    resolve_angles(&mut params)?;
+
     let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
@@ -262,6 +291,39 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
     })
 }
 
+// Either of the azimuth of the initial line (alpha) and the rectified skew
+// (gamma_c) may be left out: A missing gamma_c defaults to alpha,
+// while a missing alpha is derived from gamma_c, taken as the angle of the
+// initial line at the projection center (i.e. gamma_0 in the IOGP text)
+#[allow(non_snake_case)]
+pub(super) fn resolve_angles(params: &mut ParsedParameters) -> Result<(), Error> {
+    let alpha = params.real["alpha"];
+    let gamma_c = params.real["gamma_c"];
+    match (alpha.is_nan(), gamma_c.is_nan()) {
+        (false, false) => (),
+        (false, true) => {
+            params.real.insert("gamma_c", alpha);
+        }
+        (true, false) => {
+            let ellps = params.ellps(0)?;
+            let es = ellps.eccentricity_squared();
+            let (s, c) = params.real["latc"].to_radians().sin_cos();
+            let B = (1. + c.powi(4) * ellps.second_eccentricity_squared()).sqrt();
+            let D = B * (1.0 - es).sqrt() / (c * (1.0 - es * s * s).sqrt());
+            let sin_alpha = D * gamma_c.to_radians().sin();
+            if sin_alpha.abs() > 1.0 {
+                return Err(Error::BadParam(
+                    "gamma_c".to_string(),
+                    "Omerc: No azimuth corresponds to the rectified skew given".to_string(),
+                ));
+            }
+            params.real.insert("alpha", sin_alpha.asin().to_degrees());
+        }
+        (true, true) => return Err(Error::MissingParam("alpha".to_string())),
+    }
+    Ok(())
+}
+
 // ----- T E S T S ---------------------------------------------------------------------
 
 #[cfg(test)]
@@ -315,4 +377,83 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn omerc_ninety() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // With alpha = gamma_c = 90°, variant B is the Swiss oblique Mercator
+        let common = "k_0=1 x_0=2600000 y_0=1200000 ellps=bessel";
+        let center = "46.9524055555556";
+        let omerc = format!(
+            "omerc variant latc={center} lonc=7.43958333333333 alpha=90 gamma_c=90 {common}"
+        );
+        let somerc = format!("somerc lat_0={center} lon_0=7.43958333333333 {common}");
+        let omerc = ctx.op(&omerc)?;
+        let somerc = ctx.op(&somerc)?;
+
+        // Points on both sides of the projection center
+        let geo = [Coor2D::geo(46., 7.), Coor2D::geo(47.5, 9.)];
+        let mut expected = geo;
+        ctx.apply(somerc, Fwd, &mut expected)?;
+
+        let mut operands = geo;
+        ctx.apply(omerc, Fwd, &mut operands)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&expected[i]) < 1e-6);
+        }
+        ctx.apply(omerc, Inv, &mut operands)?;
+        for i in 0..operands.len() {
+            assert!(operands[i].hypot2(&geo[i]) < 1e-12);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn omerc_defaults() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let geo = [Coor2D::geo(45., -123.)];
+
+        // A missing gamma_c defaults to alpha (here, for variant A, as in PROJ),
+        // and `gamma` is an alias for `gamma_c`
+        let explicit = ctx.op("omerc no_uoff lat_0=44.75 lonc=-124.05 alpha=5 gamma_c=5")?;
+        let implicit = ctx.op("omerc no_uoff latc=44.75 lonc=-124.05 alpha=5")?;
+        let aliased = ctx.op("omerc latc=44.75 lonc=-124.05 alpha=5 gamma=5")?;
+        let mut expected = geo;
+        ctx.apply(explicit, Fwd, &mut expected)?;
+        for op in [implicit, aliased] {
+            let mut operands = geo;
+            ctx.apply(op, Fwd, &mut operands)?;
+            assert!(operands[0].hypot2(&expected[0]) < 1e-9);
+        }
+
+        // Otherwise, a missing gamma_c implies the Laborde approximation,
+        // i.e. variant B with gamma_c = alpha
+        let implicit = ctx.op("omerc latc=44.75 lonc=-124.05 alpha=5")?;
+        let laborde = ctx.op("omerc laborde latc=44.75 lonc=-124.05 alpha=5")?;
+        let variant_b = ctx.op("omerc variant latc=44.75 lonc=-124.05 alpha=5 gamma_c=5")?;
+        assert!(ctx.params(implicit, 0)?.boolean("variant"));
+        let mut expected = geo;
+        ctx.apply(variant_b, Fwd, &mut expected)?;
+        for op in [implicit, laborde] {
+            let mut operands = geo;
+            ctx.apply(op, Fwd, &mut operands)?;
+            assert!(operands[0].hypot2(&expected[0]) < 1e-9);
+        }
+
+        // A missing alpha is derived from gamma_c: For the Timbalai case,
+        // the azimuth 53:18:56.9537 corresponds to gamma_0 = 53:07:48.3685
+        let op = ctx.op("omerc ellps=evrstSS latc=4 lonc=115 gamma_c=53:07:48.3685")?;
+        let alpha = ctx.params(op, 0)?.real("alpha")?;
+        assert!((alpha - (53. + 18. / 60. + 56.9537 / 3600.)).abs() < 1e-3);
+
+        // Conflicting and missing parameters
+        assert!(ctx.op("omerc variant no_uoff alpha=5").is_err());
+        assert!(ctx.op("omerc laborde no_off alpha=5").is_err());
+        assert!(matches!(
+            ctx.op("omerc latc=4 lonc=115"),
+            Err(Error::MissingParam(_))
+        ));
+        Ok(())
+    }
 }
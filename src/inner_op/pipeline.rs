@@ -1,20 +1,41 @@
+use super::helmert;
 use super::pushpop::{do_the_pop, do_the_push};
-use super::stack::{stack_fwd, stack_inv};
+use super::stack::{stack_fwd, stack_inv, Registers};
+use super::tissot::tissot;
+use super::trace::trace;
 use crate::authoring::*;
 
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn pipeline_fwd(op: &Op, ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let mut stack = Vec::new();
+    let mut registers = Registers::new();
     let mut n = usize::MAX;
-    for step in &op.steps {
-        if step.params.boolean("omit_fwd") {
-            continue;
+    let steps: Vec<&Op> = op
+        .steps
+        .iter()
+        .filter(|step| !step.params.boolean("omit_fwd"))
+        .collect();
+
+    // The input to the step preceding a `tissot` step
+    let mut input = Vec::new();
+
+    for (i, step) in steps.iter().enumerate() {
+        if steps
+            .get(i + 1)
+            .is_some_and(|next| next.params.name == "tissot")
+        {
+            input = (0..operands.len()).map(|j| operands.get_coord(j)).collect();
         }
         let m = match step.params.name.as_str() {
             "push" => do_the_push(&mut stack, operands, &step.params.boolean),
             "pop" => do_the_pop(&mut stack, operands, &step.params.boolean),
-            "stack" => stack_fwd(&mut stack, operands, &step.params),
+            "stack" => stack_fwd(&mut stack, &mut registers, operands, &step.params),
+            "tissot" => {
+                let previous = i.checked_sub(1).map(|j| steps[j]);
+                tissot(step, ctx, previous, &input, operands)
+            }
+            "trace" => trace(step, i.checked_sub(1).map(|j| steps[j]), Fwd, operands),
             _ => step.apply(ctx, operands, Fwd),
         };
         n = n.min(m);
@@ -31,7 +52,10 @@ fn pipeline_fwd(op: &Op, ctx: &dyn Context, operands: &mut dyn CoordinateSet) ->
 
 fn pipeline_inv(op: &Op, ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let mut stack = Vec::new();
+    let mut registers = Registers::new();
     let mut n = usize::MAX;
+    // The step most recently applied, for `trace`
+    let mut previous = None;
     for step in op.steps.iter().rev() {
         if step.params.boolean("omit_inv") {
             continue;
@@ -40,9 +64,11 @@ fn pipeline_inv(op: &Op, ctx: &dyn Context, operands: &mut dyn CoordinateSet) ->
         let m = match step.params.name.as_str() {
             "push" => do_the_pop(&mut stack, operands, &step.params.boolean),
             "pop" => do_the_push(&mut stack, operands, &step.params.boolean),
-            "stack" => stack_inv(&mut stack, operands, &step.params),
+            "stack" => stack_inv(&mut stack, &mut registers, operands, &step.params),
+            "trace" => trace(step, previous, Inv, operands),
             _ => step.apply(ctx, operands, Inv),
         };
+        previous = Some(step);
         n = n.min(m);
     }
 
@@ -83,6 +109,90 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     })
 }
 
+// ----- O P T I M I Z E R -------------------------------------------------------------
+
+// The builtin noop and its aliases
+const NOOPS: [&str; 5] = ["noop", "longlat", "latlon", "latlong", "lonlat"];
+
+/// Simplify the steps of a pipeline, for better throughput with machine
+/// generated pipelines. Called from `Op::op` when the global parameter
+/// `optimize` is set (e.g. as an argument to a macro invocation). The
+/// optimizer:
+///
+/// - removes `noop`s, and steps omitted in both directions,
+/// - cancels adjacent `cart | cart inv` pairs with identical ellipsoids, and
+/// - merges consecutive static `helmert` steps into one, with the combined
+///   translation, rotation matrix and scale precomputed, and removes the
+///   result if it is the identity.
+///
+/// The step definitions (`Context::steps()`) are rebuilt to match the
+/// remaining steps, with a merged Helmert step defined by its `translation`,
+/// `matrix` and `s` parameters.
+///
+/// Apart from the merged Helmert parameters, no constants are hoisted: The
+/// remaining steps already precompute their constants at instantiation.
+///
+/// Steps marked `omit_fwd`, `omit_inv` or `omit_outside` are never merged or
+/// cancelled, and
+/// pipelines with a `tissot` step (which investigates its predecessor) are
+/// left untouched. The results are unchanged, except for rounding and
+/// second order terms of small-angle Helmert rotations
+pub(crate) fn optimize(mut op: Op, ctx: &dyn Context) -> Op {
+    if op.steps.iter().any(|step| step.params.name == "tissot") {
+        return op;
+    }
+    let conditional = |step: &Op| {
+        step.params.boolean("omit_fwd")
+            || step.params.boolean("omit_inv")
+            || step.params.series.contains_key("omit_outside")
+    };
+    let dead = |step: &Op| step.params.boolean("omit_fwd") && step.params.boolean("omit_inv");
+
+    // The step definitions must stay in sync with the steps, so we handle them in pairs
+    let definitions = std::mem::take(&mut op.descriptor.steps);
+    let mut steps: Vec<(String, Op)> = definitions.into_iter().zip(op.steps).collect();
+    steps.retain(|(_, step)| !NOOPS.contains(&step.params.name.as_str()) && !dead(step));
+
+    let mut i = 0;
+    while i + 1 < steps.len() {
+        let (a, b) = (&steps[i].1, &steps[i + 1].1);
+        if conditional(a) || conditional(b) || a.params.name != b.params.name {
+            i += 1;
+            continue;
+        }
+
+        // Geographical to cartesian and back again (or vice versa)
+        if a.params.name == "cart"
+            && a.descriptor.inverted != b.descriptor.inverted
+            && matches!((a.params.ellps(0), b.params.ellps(0)), (Ok(x), Ok(y)) if x == y)
+        {
+            steps.drain(i..i + 2);
+            // The cancellation may have created a new pair of neighbours
+            i = i.saturating_sub(1);
+            continue;
+        }
+
+        // Two Helmerts in a row. Stay in place, to try merging with the next one too
+        if a.params.name == "helmert" {
+            if let Some(merged) = helmert::merge(a, b, ctx) {
+                if helmert::is_identity(&merged) {
+                    steps.drain(i..i + 2);
+                    i = i.saturating_sub(1);
+                } else {
+                    let definition = merged.descriptor.definition.clone();
+                    steps.splice(i..i + 2, [(definition, merged)]);
+                }
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    (op.descriptor.steps, op.steps) = steps.into_iter().unzip();
+    op.descriptor.definition = op.descriptor.steps.join(" | ");
+    op
+}
+
 // ----- T E S T S ---------------------------------------------------------------------
 
 #[cfg(test)]
@@ -123,4 +233,82 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn optimize() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let definition = "
+            cart | cart inv | noop | cart ellps=intl
+            | helmert x=-87 y=-96 z=-120
+            | helmert x=10 rx=1.5 s=2.5 convention=position_vector
+            | helmert x=1 y=2 z=3 rz=-2 s=-1 convention=coordinate_frame inv
+            | cart inv
+        ";
+        ctx.register_resource("test:optimize", definition);
+
+        // The `optimize` flag enters the globals through the macro invocation
+        let plain = ctx.op("test:optimize")?;
+        let optimized = ctx.op("test:optimize optimize")?;
+
+        let globals = BTreeMap::from([("optimize".to_string(), "true".to_string())]);
+        let op = Op::op(RawParameters::new(definition, &globals), &ctx)?;
+        let names: Vec<_> = op.steps.iter().map(|s| s.params.name.as_str()).collect();
+        assert_eq!(names, ["cart", "helmert", "cart"]);
+        let op = Op::op(RawParameters::new(definition, &ctx.globals()), &ctx)?;
+        assert_eq!(op.steps.len(), 8);
+
+        // Same results, forward and inverse
+        let mut a = [
+            Coor4D::geo(55., 12., 100., 0.),
+            Coor4D::geo(-30., 150., 0., 0.),
+        ];
+        let mut b = a;
+        ctx.apply(plain, Fwd, &mut a)?;
+        ctx.apply(optimized, Fwd, &mut b)?;
+        for (a, b) in a.iter().zip(&b) {
+            assert!(a.hypot3(b) < 1e-6);
+        }
+        ctx.apply(plain, Inv, &mut a)?;
+        ctx.apply(optimized, Inv, &mut b)?;
+        for (a, b) in a.iter().zip(&b) {
+            assert!((a[0] - b[0]).abs() < 1e-10 && (a[1] - b[1]).abs() < 1e-10);
+        }
+
+        // Different ellipsoids, one-way steps and tissot block the optimizer
+        for (definition, steps) in [
+            ("cart | cart inv ellps=intl", 2),
+            ("cart | cart inv omit_inv", 2),
+            (
+                "helmert x=1 omit_fwd | helmert x=2 | noop omit_fwd omit_inv",
+                2,
+            ),
+            ("cart | cart inv | tissot", 3),
+            ("cart | helmert x=1 | helmert x=1 inv | cart inv", 0),
+        ] {
+            let op = Op::op(RawParameters::new(definition, &globals), &ctx)?;
+            assert_eq!(op.steps.len(), steps, "{definition}");
+        }
+
+        // The step definitions follow the optimized steps, so step-by-step
+        // introspection (e.g. by `Context::selftest`) remains usable
+        let steps = ctx.steps(optimized)?.clone();
+        assert_eq!(steps.len(), 3);
+        assert!(steps[1].starts_with("helmert translation="));
+        for (i, step) in steps.iter().enumerate() {
+            assert!(!step.contains('|'));
+            let params = ctx.params(optimized, i)?;
+            assert_eq!(params.name, step.operator_name());
+        }
+        assert!(ctx.params(optimized, 3).is_err());
+
+        // Instantiating the merged step by its definition reproduces it
+        let merged = ctx.op(&steps[1])?;
+        let mut c = [Coor4D([3_500_000., 800_000., 5_250_000., 0.])];
+        let mut d = c;
+        let pair = ctx.op("helmert x=-87 y=-96 z=-120 | helmert x=10 rx=1.5 s=2.5 convention=position_vector | helmert x=1 y=2 z=3 rz=-2 s=-1 convention=coordinate_frame inv")?;
+        ctx.apply(merged, Fwd, &mut c)?;
+        ctx.apply(pair, Fwd, &mut d)?;
+        assert!(c[0].hypot3(&d[0]) < 1e-6);
+        Ok(())
+    }
 }
@@ -0,0 +1,118 @@
+/// Change of prime meridian: Longitudes referring to Greenwich are converted
+/// to longitudes referring to the prime meridian given by `pm`, which is
+/// either one of the named meridians of `PRIME_MERIDIANS`, or an angle in
+/// degrees east of Greenwich (decimal or sexagesimal).
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let pm = op.params.real("pm").unwrap_or(0.);
+    for_each_coord(operands, |o| {
+        o[0] -= pm;
+        true
+    })
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let pm = op.params.real("pm").unwrap_or(0.);
+    for_each_coord(operands, |o| {
+        o[0] += pm;
+        true
+    })
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 2] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "pm", default: Some("greenwich") },
+];
+
+pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    let pm = params.text("pm")?;
+    let Some(longitude) = prime_meridian(&pm) else {
+        return Err(Error::BadParam("pm".to_string(), pm));
+    };
+    params.real.insert("pm", longitude.to_radians());
+
+    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+/// The longitude (in degrees east of Greenwich) of the prime meridian `pm`,
+/// given by name or by value
+pub(crate) fn prime_meridian(pm: &str) -> Option<f64> {
+    if let Some((_, longitude)) = PRIME_MERIDIANS.iter().find(|(name, _)| *name == pm) {
+        return Some(*longitude);
+    }
+    Some(angular::parse_sexagesimal(pm)).filter(|longitude| longitude.is_finite())
+}
+
+// The named prime meridians known to PROJ, with longitudes from the EPSG registry
+#[rustfmt::skip]
+const PRIME_MERIDIANS: [(&str, f64); 14] = [
+    ("greenwich",     0.0),
+    ("lisbon",       -9.131_906_111),
+    ("paris",         2.337_229_167),
+    ("bogota",      -74.080_916_667),
+    ("madrid",       -3.687_938_889),
+    ("rome",         12.452_333_333),
+    ("bern",          7.439_583_333),
+    ("jakarta",     106.807_719_444),
+    ("ferro",       -17.666_666_666_667),
+    ("brussels",      4.367_975),
+    ("stockholm",    18.058_277_778),
+    ("athens",       23.716_337_5),
+    ("oslo",         10.722_916_667),
+    ("copenhagen",   12.577_880_556),
+];
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primem() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // The Panthéon, in Paris
+        let greenwich = Coor4D::geo(48.846_2, 2.346_4, 0., 0.);
+        let op = ctx.op("primem pm=paris")?;
+        let mut data = [greenwich];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0].to_degrees() - 0.009_171).abs() < 1e-6);
+        assert_eq!(data[0][1], greenwich[1]);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0] - greenwich[0]).abs() < 1e-14);
+
+        // Named and numerical meridians are interchangeable
+        let named = ctx.op("primem pm=ferro")?;
+        let numerical = ctx.op("primem pm=17:40W")?;
+        let mut a = [greenwich];
+        let mut b = [greenwich];
+        ctx.apply(named, Fwd, &mut a)?;
+        ctx.apply(numerical, Fwd, &mut b)?;
+        assert!((a[0][0] - b[0][0]).abs() < 1e-12);
+        assert!((a[0][0].to_degrees() - 20.013_066_667).abs() < 1e-6);
+
+        assert!(ctx.op("primem pm=atlantis").is_err());
+        Ok(())
+    }
+}
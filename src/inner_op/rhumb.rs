@@ -0,0 +1,131 @@
+/// Rhumb lines (loxodromes): The navigational counterpart to `geodesic`
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+
+    let n = operands.len();
+    let sliced = 0..n;
+
+    let mut successes = 0_usize;
+    for i in sliced {
+        let args = operands.get_coord(i);
+        let origin = Coor2D::geo(args[0], args[1]);
+        let azimuth = args[2].to_radians();
+        let distance = args[3];
+
+        let destination = ellps.rhumb_fwd(&origin, azimuth, distance).to_degrees();
+
+        // Beyond the pole?
+        if destination[0].is_nan() {
+            operands.set_coord(i, &Coor4D::nan());
+            continue;
+        }
+
+        let result = Coor4D([destination[1], destination[0], args[0], args[1]]);
+        operands.set_coord(i, &result);
+        successes += 1;
+    }
+
+    successes
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    let reversible = op.params.boolean("reversible");
+
+    let n = operands.len();
+    let sliced = 0..n;
+
+    let mut successes = 0_usize;
+    for i in sliced {
+        let coord = operands.get_coord(i);
+        let from = Coor2D::geo(coord[0], coord[1]);
+        let to = Coor2D::geo(coord[2], coord[3]);
+
+        let mut rhumb = ellps.rhumb_inv(&from, &to).to_degrees();
+        rhumb[3] = (rhumb[1] + 180.0) % 360.0;
+
+        if reversible {
+            let distance = rhumb[2];
+            let return_azi = rhumb[3];
+            operands.set_coord(i, &Coor4D::raw(coord[2], coord[3], return_azi, distance));
+            successes += 1;
+            continue;
+        }
+
+        operands.set_coord(i, &rhumb);
+        successes += 1;
+    }
+
+    successes
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 3] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Flag { key: "reversible" },
+    OpParameter::Text { key: "ellps", default: Some("GRS80") }
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    Ok(op)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rhumb() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Approximate coordinates of Copenhagen and Paris airports
+        let cph_cdg = Coor4D::raw(55., 12., 49., 2.);
+
+        // A rhumb line from Copenhagen to Paris is slightly longer than the geodesic
+        let op = ctx.op("rhumb")?;
+        let mut operands = [cph_cdg];
+        ctx.apply(op, Inv, &mut operands)?;
+        let azimuth = operands[0][0];
+        let distance = operands[0][2];
+        assert_eq!(operands[0][0], operands[0][1]);
+        assert!((operands[0][3] - (azimuth + 180.)).abs() < 1e-12);
+        assert!(distance > 956066.231 && distance < 960e3);
+
+        // In the "reversible" format...
+        let op = ctx.op("rhumb reversible")?;
+        let mut operands = [cph_cdg];
+        ctx.apply(op, Inv, &mut operands)?;
+        assert_eq!(operands[0][0], 49.);
+        assert_eq!(operands[0][1], 2.);
+        assert!((operands[0][2] - (azimuth + 180.)).abs() < 1e-12);
+        assert!((operands[0][3] - distance).abs() < 1e-9);
+
+        // ...we can go back to Copenhagen
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - cph_cdg[0]).abs() < 1e-10);
+        assert!((operands[0][1] - cph_cdg[1]).abs() < 1e-10);
+        assert!((operands[0][2] - cph_cdg[2]).abs() < 1e-10);
+        assert!((operands[0][3] - cph_cdg[3]).abs() < 1e-10);
+
+        // There is no way beyond the north pole
+        let mut operands = [Coor4D::raw(55., 12., 0., 1e7)];
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 0);
+        assert!(operands[0][0].is_nan());
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+/// Round the coordinates to a given number of decimals, or to a multiple of
+/// a given quantum, for deterministic, storage friendly output precision.
+/// Typically used as the last step of a pipeline. Rounding is not invertible,
+/// so the inverse operation is a no-op
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    // Per axis: Round to multiples of 1/scale (if scale is non-zero),
+    // or to multiples of quantum (if quantum is non-zero)
+    let (Ok(scale), Ok(quantum)) = (op.params.series("scale"), op.params.series("quantum")) else {
+        return 0;
+    };
+
+    for_each_coord(operands, |coord| {
+        for i in 0..4 {
+            if scale[i] != 0. {
+                coord[i] = (coord[i] * scale[i]).round() / scale[i];
+            } else if quantum[i] != 0. {
+                coord[i] = (coord[i] / quantum[i]).round() * quantum[i];
+            }
+        }
+        true
+    })
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(_op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    operands.len()
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 3] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Series { key: "decimals", default: Some("") },
+    OpParameter::Series { key: "quantum", default: Some("") },
+];
+
+pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    let decimals = params.series("decimals").ok().map(per_axis);
+    let quantum = params.series("quantum").ok().map(per_axis);
+
+    let mut scale = [0_f64; 4];
+    let mut quanta = [0_f64; 4];
+    match (decimals, quantum) {
+        (Some(Some(decimals)), None) => {
+            for i in 0..4 {
+                if decimals[i].is_nan() {
+                    continue;
+                }
+                if decimals[i].fract() != 0. {
+                    return Err(Error::BadParam("decimals".to_string(), def.clone()));
+                }
+                scale[i] = 10_f64.powi(decimals[i] as i32);
+            }
+        }
+        (None, Some(Some(quantum))) => {
+            for i in 0..4 {
+                if quantum[i].is_nan() {
+                    continue;
+                }
+                if quantum[i] <= 0. {
+                    return Err(Error::BadParam("quantum".to_string(), def.clone()));
+                }
+                // Quanta like 0.001 are inexact in binary, while their reciprocals
+                // are not, so we prefer dividing by those, when available
+                let reciprocal = 1. / quantum[i];
+                if (reciprocal.round() - reciprocal).abs() < 1e-9 * reciprocal {
+                    scale[i] = reciprocal.round();
+                } else {
+                    quanta[i] = quantum[i];
+                }
+            }
+        }
+        (Some(None), _) => return Err(Error::BadParam("decimals".to_string(), def.clone())),
+        (_, Some(None)) => return Err(Error::BadParam("quantum".to_string(), def.clone())),
+        _ => {
+            return Err(Error::MissingParam(
+                "round: must specify exactly one of decimals/quantum".to_string(),
+            ))
+        }
+    }
+    params.series.insert("scale", Vec::from(scale));
+    params.series.insert("quantum", Vec::from(quanta));
+
+    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+// A single value applies to all axes, while a list of up to 4 values applies to
+// the corresponding axes, leaving the remaining ones (marked by NaN) untouched
+fn per_axis(values: &[f64]) -> Option<[f64; 4]> {
+    match values.len() {
+        1 => Some([values[0]; 4]),
+        2..=4 => {
+            let mut axes = [f64::NAN; 4];
+            axes[..values.len()].copy_from_slice(values);
+            Some(axes)
+        }
+        _ => None,
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Millimeter precision for projected output
+        let op = ctx.op("utm zone=32 | round decimals=3")?;
+        let mut data = [Coor4D::geo(55., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 691_875.632);
+        assert_eq!(data[0][1], 6_098_907.825);
+
+        // Rounding is not invertible, so the inverse is a no-op
+        let op = ctx.op("round decimals=1")?;
+        let mut data = [Coor4D::raw(1.23, 4.56, 7.89, 0.12)];
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1.23, 4.56, 7.89, 0.12));
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1.2, 4.6, 7.9, 0.1));
+
+        // Per axis decimals and quanta, leaving the remaining axes untouched
+        let op = ctx.op("round decimals=2,2,-1")?;
+        let mut data = [Coor4D::raw(1.234, 5.678, 1234., 0.12)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1.23, 5.68, 1230., 0.12));
+
+        let op = ctx.op("round quantum=0.001,0.001,0.25")?;
+        let mut data = [Coor4D::raw(1.2344, 5.6786, 7.4, 0.12)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1.234, 5.679, 7.5, 0.12));
+
+        // Syntax checks
+        assert!(ctx.op("round").is_err());
+        assert!(ctx.op("round decimals=1 quantum=0.1").is_err());
+        assert!(ctx.op("round decimals=1.5").is_err());
+        assert!(ctx.op("round quantum=0").is_err());
+        assert!(ctx.op("round decimals=1,2,3,4,5").is_err());
+        Ok(())
+    }
+}
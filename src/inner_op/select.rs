@@ -0,0 +1,151 @@
+/// Select the coordinates inside a geographical bounding box and/or within
+/// ranges of the individual coordinate dimensions, and mark the remaining
+/// ones as invalid (i.e. NaN), so they are not counted as successes.
+/// Useful for sanitizing input streams before projection
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+// Selection is idempotent, so the forward and inverse operations are identical
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let bbox = op.params.series("bbox").ok();
+    let (Ok(lo), Ok(hi)) = (op.params.series("lo"), op.params.series("hi")) else {
+        return 0;
+    };
+
+    for_each_coord(operands, |coord| {
+        let inside_box = bbox.map_or(true, |bbox| crate::op::within(bbox, coord));
+        // Unconstrained dimensions are not checked, as they may be NaN
+        // for coordinate types with fewer than 4 dimensions
+        let inside_ranges = (0..4)
+            .filter(|&i| constrained(lo[i], hi[i]))
+            .all(|i| lo[i] <= coord[i] && coord[i] <= hi[i]);
+        if inside_box && inside_ranges {
+            return true;
+        }
+        *coord = Coor4D::nan();
+        false
+    })
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 6] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Series { key: "bbox", default: Some("") },
+    OpParameter::Series { key: "x", default: Some("") },
+    OpParameter::Series { key: "y", default: Some("") },
+    OpParameter::Series { key: "z", default: Some("") },
+    OpParameter::Series { key: "t", default: Some("") },
+];
+
+pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    // The bounding box is given in degrees as west, south, east, north,
+    // with west > east for boxes crossing the antimeridian
+    if let Ok(bbox) = params.series("bbox") {
+        if bbox.len() != 4 || bbox[1] > bbox[3] {
+            return Err(Error::BadParam("bbox".to_string(), format!("{bbox:?}")));
+        }
+        let bbox = bbox.iter().map(|v| v.to_radians()).collect();
+        params.series.insert("bbox", bbox);
+    }
+
+    let (lo, hi) = ranges(&params)?;
+    params.series.insert("lo", Vec::from(lo));
+    params.series.insert("hi", Vec::from(hi));
+
+    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(fwd)));
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
+/// The lower and upper limits of the coordinate dimensions, as given by
+/// the `x`, `y`, `z` and `t` parameters (in internal units, i.e. radians
+/// for angular coordinates). Unlimited if not given
+pub(super) fn ranges(params: &ParsedParameters) -> Result<([f64; 4], [f64; 4]), Error> {
+    let mut lo = [f64::NEG_INFINITY; 4];
+    let mut hi = [f64::INFINITY; 4];
+    for (i, key) in ["x", "y", "z", "t"].iter().enumerate() {
+        let Ok(range) = params.series(key) else {
+            continue;
+        };
+        if range.len() != 2 || range[0] > range[1] {
+            return Err(Error::BadParam(key.to_string(), format!("{range:?}")));
+        }
+        (lo[i], hi[i]) = (range[0], range[1]);
+    }
+    Ok((lo, hi))
+}
+
+/// Is a coordinate dimension limited by the range `lo..=hi`?
+pub(super) fn constrained(lo: f64, hi: f64) -> bool {
+    lo != f64::NEG_INFINITY || hi != f64::INFINITY
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Reject latitudes beyond ±90° before projection
+        let op = ctx.op("select bbox=-180,-90,180,90 | utm zone=32")?;
+        let mut data = [
+            Coor4D::geo(55., 12., 0., 0.),
+            Coor4D::geo(95., 12., 0., 0.),
+            Coor4D::geo(55., 12., 0., 0.),
+        ];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert!((data[0][0] - 691_875.632_139_661).abs() < 1e-6);
+        assert!(data[1][0].is_nan());
+        assert_eq!(data[0], data[2]);
+
+        // Ranges of individual dimensions, in internal units
+        let op = ctx.op("select z=0,100 t=2000,2030")?;
+        let mut data = [
+            Coor4D::raw(1., 2., 50., 2020.),
+            Coor4D::raw(1., 2., 150., 2020.),
+            Coor4D::raw(1., 2., 50., 1999.),
+        ];
+        assert_eq!(ctx.apply(op, Inv, &mut data)?, 1);
+        assert_eq!(data[0], Coor4D::raw(1., 2., 50., 2020.));
+        assert!(data[1][2].is_nan() && data[2][3].is_nan());
+
+        // 2D coordinates (with NaN for the missing dimensions) are accepted,
+        // unless a range is given for a missing dimension
+        let op = ctx.op("select bbox=-180,-90,180,90 | utm zone=32")?;
+        let mut data = [Coor2D::geo(55., 12.), Coor2D::geo(95., 12.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert!((data[0][0] - 691_875.632_139_661).abs() < 1e-6);
+        assert!(data[1][0].is_nan());
+
+        let op = ctx.op("select x=0,2")?;
+        let mut data = [Coor2D::raw(1., 2.), Coor2D::raw(3., 2.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert_eq!(data[0], Coor2D::raw(1., 2.));
+        let op = ctx.op("select t=2000,2030")?;
+        let mut data = [Coor2D::raw(1., 2.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 0);
+
+        // Malformed boxes and ranges
+        assert!(ctx.op("select bbox=1,2,3").is_err());
+        assert!(ctx.op("select z=100,0").is_err());
+        assert!(ctx.op("select t=1").is_err());
+        Ok(())
+    }
+}
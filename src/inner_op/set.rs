@@ -0,0 +1,88 @@
+/// Assign constant values to selected coordinate elements, e.g. `set z=0 t=2020.5`,
+/// for deterministic promotion of 2D data before 3D or 4D operations reading the
+/// height or the epoch. With the `missing` flag, only elements that are NaN
+/// are assigned. The values cannot be unassigned, so the inverse operation is
+/// a no-op
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(values) = op.params.series("values") else {
+        return 0;
+    };
+    let missing = op.params.boolean("missing");
+
+    for_each_coord(operands, |coord| {
+        for i in 0..4 {
+            if values[i].is_nan() || (missing && !coord[i].is_nan()) {
+                continue;
+            }
+            coord[i] = values[i];
+        }
+        true
+    })
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(_op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    operands.len()
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 6] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Flag { key: "missing" },
+    OpParameter::Real { key: "x", default: Some(f64::NAN) },
+    OpParameter::Real { key: "y", default: Some(f64::NAN) },
+    OpParameter::Real { key: "z", default: Some(f64::NAN) },
+    OpParameter::Real { key: "t", default: Some(f64::NAN) },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    let mut values = [f64::NAN; 4];
+    for (i, key) in ["x", "y", "z", "t"].iter().enumerate() {
+        values[i] = op.params.real(key)?;
+    }
+    op.params.series.insert("values", Vec::from(values));
+    Ok(op)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        let op = ctx.op("set z=0 t=2020.5")?;
+        let mut data = [Coor4D::raw(1., 2., 3., 4.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert_eq!(data[0], Coor4D::raw(1., 2., 0., 2020.5));
+
+        // The inverse is a no-op
+        let mut data = [Coor4D::raw(1., 2., 3., 4.)];
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1., 2., 3., 4.));
+
+        // Only assign missing values
+        let op = ctx.op("set missing z=0 t=2020.5")?;
+        let mut data = [Coor4D::raw(1., 2., 3., f64::NAN)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(1., 2., 3., 2020.5));
+
+        // Promote 2D data to 4D, for a time dependent Helmert transformation
+        let op = ctx.op("set z=0 t=2020 | helmert x=1 dx=1 t_epoch=2010")?;
+        let mut data = [Coor4D::raw(1., 2., f64::NAN, f64::NAN)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0], Coor4D::raw(12., 2., 0., 2020.));
+        Ok(())
+    }
+}
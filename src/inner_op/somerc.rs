@@ -7,6 +7,11 @@
 //     - [proj4rs](https://github.com/3liz/proj4rs/blob/main/src/projections/somerc.rs)
 //     - [proj4js](https://github.com/proj4js/proj4js/blob/5995fa62fc7f4fdbbafb23d89b260bd863b0ca03/lib/projections/somerc.js)
 //     - [PROJ](https://proj.org/operations/projections/somerc.html)
+//
+// The Swiss projection is the special case of the Hotine oblique Mercator
+// (variant B) with azimuth and rectified grid angle both 90°. For other
+// azimuths (`alpha`) or rectified grid angles (`gamma_c`), we hand over to
+// the `omerc` implementation, with `lat_0`/`lon_0` as the projection center
 use crate::authoring::*;
 use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
 
@@ -20,7 +25,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let mut successes = 0_usize;
     let n = operands.len();
 
-    let el = op.params.ellps(0);
+    let Ok(el) = op.params.ellps(0) else {
+        return 0;
+    };
     let e = el.eccentricity();
     let hlf_e = e * 0.5;
 
@@ -70,7 +77,9 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     let n = operands.len();
     const MAX_ITERATIONS: isize = 20;
 
-    let el = op.params.ellps(0);
+    let Ok(el) = op.params.ellps(0) else {
+        return 0;
+    };
     let e = el.eccentricity();
 
     // Grab pre-computed values
@@ -130,7 +139,7 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 7] = [
+pub const GAMUT: [OpParameter; 9] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps",  default: Some("GRS80") },
     // TODO: Handle case when R is used.
@@ -143,13 +152,26 @@ pub const GAMUT: [OpParameter; 7] = [
     OpParameter::Real { key: "y_0",    default: Some(0_f64) },
 
     OpParameter::Real { key: "k_0",    default: Some(1_f64) },
+
+    // Azimuth of the initial line, and angle from the rectified grid to
+    // the oblique grid. Anything but 90° is handled by `omerc`, and, as
+    // there, a missing gamma_c defaults to alpha
+    OpParameter::Real { key: "alpha",   default: Some(90_f64) },
+    OpParameter::Real { key: "gamma_c", default: Some(f64::NAN) },
 ];
 
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
     let mut params = ParsedParameters::new(parameters, &GAMUT)?;
 
-    let el = params.ellps(0);
+    let alpha = params.real("alpha")?;
+    let gamma_c = params.real("gamma_c")?;
+    if alpha != 90.0 || !(gamma_c.is_nan() || gamma_c == 90.0) {
+        return oblique(parameters, &params);
+    }
+    params.real.insert("gamma_c", 90.0);
+
+    let el = params.ellps(0)?;
     let e = el.eccentricity();
     let hlf_e = e * 0.5;
     let es = el.eccentricity_squared();
@@ -192,6 +214,33 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 
 // ----- Ancillary functions -----------------------------------------------------------
 
+// The general azimuth case: Hotine oblique Mercator, variant B, i.e. with the
+// false easting/northing given at the projection center, as for the Swiss case
+fn oblique(parameters: &RawParameters, somerc: &ParsedParameters) -> Result<Op, Error> {
+    let def = &parameters.definition;
+    let mut params = ParsedParameters::new(parameters, &super::omerc::GAMUT)?;
+
+    params.boolean.insert("variant");
+    params.real.insert("latc", somerc.lat(0));
+    params.real.insert("lonc", somerc.lon(0));
+    params.real.insert("alpha", somerc.real("alpha")?);
+    params.real.insert("gamma_c", somerc.real("gamma_c")?);
+    super::omerc::resolve_angles(&mut params)?;
+
+    let fwd = InnerOp(super::omerc::fwd);
+    let inv = InnerOp(super::omerc::inv);
+    let descriptor = OpDescriptor::new(def, fwd, Some(inv));
+    let steps = Vec::<Op>::new();
+    let id = OpHandle::new();
+
+    Ok(Op {
+        descriptor,
+        params,
+        steps,
+        id,
+    })
+}
+
 // ----- T E S T S ---------------------------------------------------------------------
 
 #[cfg(test)]
@@ -289,4 +338,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn somerc_oblique() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Timbalai 1948 / RSO Borneo, from EPSG Guidance Note 7-2, Hotine
+        // oblique Mercator variant B (9815), expressed as a somerc
+        let op = ctx.op(
+            "somerc ellps=evrstSS x_0=590476.87 y_0=442857.65 lat_0=4 lon_0=115
+             k_0=0.99984 alpha=53:18:56.9537 gamma_c=53:07:48.3685",
+        )?;
+
+        let geo = [Coor4D::geo(5.3872535833, 115.8055054444, 0., 0.)];
+        let projected = [Coor4D::raw(679_245.73, 596_562.78, 0., 0.)];
+
+        let mut operands = geo;
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_float_eq!(operands[0][0], projected[0][0], abs_all <= 1e-2);
+        assert_float_eq!(operands[0][1], projected[0][1], abs_all <= 1e-2);
+
+        ctx.apply(op, Inv, &mut operands)?;
+        assert_float_eq!(operands[0][0], geo[0][0], abs_all <= 1e-9);
+        assert_float_eq!(operands[0][1], geo[0][1], abs_all <= 1e-9);
+
+        // As for omerc, a missing gamma_c defaults to alpha
+        let implicit = ctx.op("somerc ellps=evrstSS lat_0=4 lon_0=115 alpha=53")?;
+        let explicit = ctx.op("omerc variant ellps=evrstSS latc=4 lonc=115 alpha=53 gamma_c=53")?;
+        assert_eq!(ctx.params(implicit, 0)?.real("gamma_c")?, 53.);
+        let mut operands = geo;
+        let mut expected = geo;
+        ctx.apply(implicit, Fwd, &mut operands)?;
+        ctx.apply(explicit, Fwd, &mut expected)?;
+        assert!(operands[0].hypot2(&expected[0]) < 1e-9);
+        assert!(operands[0][0].is_finite());
+
+        Ok(())
+    }
 }
@@ -1,9 +1,12 @@
-//! Stack functionality for pipelines (push/pop/swap)
+//! Stack functionality for pipelines (push/pop/swap), and named registers (store/recall)
 use crate::authoring::*;
 
+/// Named registers, living for the duration of a pipeline execution
+pub(super) type Registers = BTreeMap<String, Vec<f64>>;
+
 // NOTE: roll and drop are not implemented yet
 #[rustfmt::skip]
-pub const STACK_GAMUT: [OpParameter; 7] = [
+pub const STACK_GAMUT: [OpParameter; 10] = [
     OpParameter::Series  { key: "push", default: Some("") },
     OpParameter::Series  { key: "pop",  default: Some("") },
     OpParameter::Series  { key: "roll", default: Some("") },
@@ -11,6 +14,9 @@ pub const STACK_GAMUT: [OpParameter; 7] = [
     OpParameter::Series  { key: "flip", default: Some("") },
     OpParameter::Flag    { key: "swap" },
     OpParameter::Flag    { key: "drop" },
+    OpParameter::Texts   { key: "store", default: Some("") },
+    OpParameter::Texts   { key: "recall", default: Some("") },
+    OpParameter::Series  { key: "dim", default: Some("") },
 ];
 
 /// Construct a new stack operator. Check the syntax and semantics
@@ -88,6 +94,30 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         params.text.insert("action", "unroll".to_string());
     }
 
+    // store and recall take a list of register names, and an equally long
+    // list of the coordinate dimensions to store from, resp. recall into
+    for action in ["store", "recall"] {
+        let Ok(names) = params.texts(action) else {
+            continue;
+        };
+        subcommands_given += 1;
+        let dims = params.series("dim").unwrap_or(&[]);
+        if dims.len() != names.len() {
+            return Err(Error::MissingParam(format!(
+                "{action} needs a 'dim' with one coordinate dimension for each register name"
+            )));
+        }
+        for i in dims.iter() {
+            if !valid_indices.contains(i) {
+                return Err(Error::BadParam("dim".to_string(), i.to_string()));
+            }
+        }
+        if names.iter().any(|name| name.is_empty()) {
+            return Err(Error::BadParam(action.to_string(), names.join(",")));
+        }
+        params.text.insert("action", action.to_string());
+    }
+
     if params.boolean("swap") {
         subcommands_given += 1;
         params.text.insert("action", "swap".to_string());
@@ -100,7 +130,8 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 
     if subcommands_given != 1 {
         return Err(Error::MissingParam(
-            "stack: must specify exactly one of push/pop/roll/swap/unroll/drop".to_string(),
+            "stack: must specify exactly one of push/pop/roll/swap/unroll/drop/store/recall"
+                .to_string(),
         ));
     }
 
@@ -120,6 +151,7 @@ pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
 /// Called by `pipeline_fwd` to execute stack operations in forward mode
 pub(super) fn stack_fwd(
     stack: &mut Vec<Vec<f64>>,
+    registers: &mut Registers,
     operands: &mut dyn CoordinateSet,
     params: &ParsedParameters,
 ) -> usize {
@@ -154,6 +186,16 @@ pub(super) fn stack_fwd(
             stack_flip(stack, operands, &args)
         }
 
+        "store" => {
+            let args = params.series_as_usize("dim").unwrap();
+            store(registers, operands, params.texts("store").unwrap(), &args)
+        }
+
+        "recall" => {
+            let args = params.series_as_usize("dim").unwrap();
+            recall(registers, operands, params.texts("recall").unwrap(), &args)
+        }
+
         "swap" => {
             let n = stack.len();
             if n > 1 {
@@ -174,9 +216,11 @@ pub(super) fn stack_fwd(
 
 /// Called by `pipeline_inv` to execute stack operations in inverse mode.
 /// Inverse mode has two major differences from forward: push and pop switches
-/// functionality, and their argument order swaps direction
+/// functionality, and their argument order swaps direction. Likewise for
+/// store and recall
 pub(super) fn stack_inv(
     stack: &mut Vec<Vec<f64>>,
+    registers: &mut Registers,
     operands: &mut dyn CoordinateSet,
     params: &ParsedParameters,
 ) -> usize {
@@ -215,6 +259,17 @@ pub(super) fn stack_inv(
             stack_flip(stack, operands, &args)
         }
 
+        // An inverse store is a recall, and vice versa
+        "store" => {
+            let args = params.series_as_usize("dim").unwrap();
+            recall(registers, operands, params.texts("store").unwrap(), &args)
+        }
+
+        "recall" => {
+            let args = params.series_as_usize("dim").unwrap();
+            store(registers, operands, params.texts("recall").unwrap(), &args)
+        }
+
         // Swap TOS and 2OS
         "swap" => {
             let n = stack.len();
@@ -235,6 +290,7 @@ pub(super) fn stack_inv(
 }
 
 /// Push elements from a CoordinateSet onto the stack
+#[allow(clippy::needless_range_loop)]
 fn stack_push(
     stack: &mut Vec<Vec<f64>>,
     operands: &mut dyn CoordinateSet,
@@ -261,6 +317,7 @@ fn stack_push(
 }
 
 /// Flip the operator and the TOS
+#[allow(clippy::needless_range_loop)]
 fn stack_flip(stack: &mut [Vec<f64>], operands: &mut dyn CoordinateSet, args: &[usize]) -> usize {
     let number_of_flips = args.len();
     let number_of_operands = operands.len();
@@ -321,6 +378,7 @@ fn stack_roll(stack: &mut Vec<Vec<f64>>, operands: &mut dyn CoordinateSet, args:
 }
 
 /// Pop elements from the stack into elements of a CoordinateSet
+#[allow(clippy::needless_range_loop)]
 fn stack_pop(stack: &mut Vec<Vec<f64>>, operands: &mut dyn CoordinateSet, args: &[usize]) -> usize {
     let number_of_pops = args.len();
     let number_of_operands = operands.len();
@@ -353,6 +411,53 @@ fn stack_pop(stack: &mut Vec<Vec<f64>>, operands: &mut dyn CoordinateSet, args:
     number_of_operands
 }
 
+/// Store elements from a CoordinateSet in the named registers
+fn store(
+    registers: &mut Registers,
+    operands: &mut dyn CoordinateSet,
+    names: &[String],
+    args: &[usize],
+) -> usize {
+    for (name, &dim) in names.iter().zip(args) {
+        // args are 1 based so we adjust
+        let values = (0..operands.len())
+            .map(|i| operands.get_coord(i)[dim - 1])
+            .collect();
+        registers.insert(name.clone(), values);
+    }
+    operands.len()
+}
+
+/// Recall elements of a CoordinateSet from the named registers
+#[allow(clippy::needless_range_loop)]
+fn recall(
+    registers: &mut Registers,
+    operands: &mut dyn CoordinateSet,
+    names: &[String],
+    args: &[usize],
+) -> usize {
+    let number_of_operands = operands.len();
+
+    // In case of an unknown (or incompatible) register, we stomp on all input coordinates
+    for name in names {
+        if registers.get(name).map(|r| r.len()) != Some(number_of_operands) {
+            warn!("Recall of unknown register '{name}' in pipeline");
+            operands.stomp();
+            return 0;
+        }
+    }
+
+    for i in 0..number_of_operands {
+        let mut coord = operands.get_coord(i);
+        for (name, &dim) in names.iter().zip(args) {
+            // args are 1 based so we adjust
+            coord[dim - 1] = registers[name][i];
+        }
+        operands.set_coord(i, &coord);
+    }
+    number_of_operands
+}
+
 // ----- T E S T S ---------------------------------------------------------------------
 
 #[cfg(test)]
@@ -543,4 +648,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn registers() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // Keep the original latitude as the third coordinate of the projected output
+        let op = ctx.op("stack store=lat dim=2 | utm zone=32 | stack recall=lat dim=3")?;
+        let geo = Coor4D::geo(55., 12., 0., 0.);
+        let mut data = [geo];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert!((data[0][0] - 691_875.632_139_661).abs() < 1e-6);
+        assert_eq!(data[0][2], geo[1]);
+
+        // In the inverse case, recall stores, and store recalls
+        assert_eq!(ctx.apply(op, Inv, &mut data)?, 1);
+        assert!((data[0][0] - geo[0]).abs() < 1e-12);
+        assert_eq!(data[0][1], geo[1]);
+
+        // Several registers at a time, recalled in any order
+        let op = ctx.op("stack store=a,b dim=1,2 | stack recall=b,a dim=1,2")?;
+        let mut data = [Coor4D([1., 2., 3., 4.])];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0].0, [2., 1., 3., 4.]);
+
+        // Recalling an unknown register stomps on the operands
+        let op = ctx.op("stack store=a dim=1 | stack recall=b dim=1")?;
+        let mut data = [Coor4D([1., 2., 3., 4.])];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 0);
+        assert!(data[0][0].is_nan());
+
+        // Syntax checks
+        assert!(ctx.op("stack store=a").is_err());
+        assert!(ctx.op("stack store=a,b dim=1").is_err());
+        assert!(ctx.op("stack recall=a dim=5").is_err());
+        assert!(ctx.op("stack store=a recall=a dim=1").is_err());
+        Ok(())
+    }
 }
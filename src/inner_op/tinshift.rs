@@ -0,0 +1,103 @@
+/// Triangulation based transformation, e.g. the Swiss FINELTRA based CHENyx06
+/// transformation between LV03 and LV95, using a triangulation file in the
+/// PROJ `tinshift` JSON format.
+///
+/// The forward transformation interpolates the shifts linearly within the
+/// triangle of the source vertices containing the point, while the inverse
+/// uses the triangulation of the target vertices. Hence, the inverse is
+/// exact (to within rounding), and no iteration is needed. Points outside
+/// of the triangulation are stomped on with NaN
+use crate::authoring::*;
+use std::sync::Arc;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    shift(op.params.grids[0].as_ref(), operands)
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    shift(op.params.grids[1].as_ref(), operands)
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+
+// Apply the shifts of `tin`, following the band conventions of `gridshift`
+fn shift(tin: &dyn Grid, operands: &mut dyn CoordinateSet) -> usize {
+    for_each_coord(operands, |coord| {
+        let Some(d) = tin.at(coord, 0.) else {
+            *coord = Coor4D::nan();
+            return false;
+        };
+        match tin.bands() {
+            1 => coord[2] -= d[0],
+            2 => {
+                coord[0] += d[0];
+                coord[1] += d[1];
+            }
+            _ => {
+                coord[0] += d[0];
+                coord[1] += d[1];
+                coord[2] += d[2];
+            }
+        }
+        true
+    })
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 2] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Text { key: "file", default: None },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    let buf = ctx.get_blob(&op.params.text("file")?)?;
+    let tin = Tin::tinshift(&buf)?;
+    let inverse = tin.inverse();
+    op.params.grids.push(Arc::new(tin));
+    op.params.grids.push(Arc::new(inverse));
+    Ok(op)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tinshift() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let op = ctx.op("tinshift file=tinshift_test.json")?;
+
+        // LV03 to LV95 style: Huge offsets, and small, linearly varying, corrections
+        let lv03 = Coor4D::raw(625_000., 225_000., 500., 0.);
+        let mut data = [lv03];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert!((data[0][0] - 2_625_000.5).abs() < 1e-6);
+        assert!((data[0][1] - 1_224_999.75).abs() < 1e-6);
+        assert_eq!(data[0][2], 500.);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!(data[0].hypot2(&lv03) < 1e-6);
+
+        // Outside of the triangulation
+        let mut data = [Coor4D::raw(700_000., 225_000., 0., 0.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 0);
+        assert!(data[0][0].is_nan());
+
+        // The register entry refers to the full CHENyx06 triangulation,
+        // which is not distributed with RG
+        let Err(Error::NotFound(name, _)) = ctx.op("ch:lv03_to_lv95") else {
+            panic!("Expected the CHENyx06 triangulation to be missing");
+        };
+        assert_eq!(name, "chenyx06_lv03_to_lv95.json");
+        Ok(())
+    }
+}
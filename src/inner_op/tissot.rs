@@ -0,0 +1,146 @@
+//! Tissot indicatrix: Distortion parameters of the preceding projection.
+//!
+//! In a pipeline, `tissot` replaces the output of the preceding step with
+//! its distortion parameters, evaluated at the input to that step, i.e.
+//! `geo:in | utm zone=32 | tissot` turns geographical coordinates into
+//! the distortion characteristics of the UTM zone 32 projection.
+//!
+//! The output coordinate tuple consists of the semimajor and semiminor
+//! axes of the Tissot indicatrix, *a, b*, the areal scale, *s = ab*, and
+//! the maximum angular distortion, *ω* (in degrees).
+//! With the `convergence` flag, the areal scale is replaced by the
+//! meridian convergence, *α* (in degrees).
+//!
+//! The distortion parameters are computed from the analytical partial
+//! derivatives of the projection, if available, and otherwise from
+//! numerical differentiation.
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+// Outside of a pipeline, there is no preceding projection to investigate
+fn fwd(_op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    for i in 0..operands.len() {
+        operands.set_coord(i, &Coor4D::nan());
+    }
+    0
+}
+
+/// The actual work, called from the pipeline driver: Replace the output of
+/// the `projection` step by its distortion parameters at the `input` points
+pub(super) fn tissot(
+    op: &Op,
+    ctx: &dyn Context,
+    projection: Option<&Op>,
+    input: &[Coor4D],
+    operands: &mut dyn CoordinateSet,
+) -> usize {
+    let Some(projection) = projection else {
+        return fwd(op, ctx, operands);
+    };
+
+    let Ok(ellps) = projection.params.ellps(0) else {
+        return 0;
+    };
+    let a = ellps.semimajor_axis();
+    let convergence = op.params.boolean("convergence");
+
+    let mut successes = 0_usize;
+    for (i, at) in input.iter().enumerate().take(operands.len()) {
+        let d = projection
+            .partial_derivatives(ctx, Fwd, at)
+            .unwrap_or([f64::NAN; 4]);
+
+        let jacobian = Jacobian {
+            latitude: at[1].to_degrees(),
+            longitude: at[0].to_degrees(),
+            dx_dlam: d[0] / a,
+            dy_dlam: d[1] / a,
+            dx_dphi: d[2] / a,
+            dy_dphi: d[3] / a,
+            ellps,
+        };
+        let f = jacobian.factors();
+        let third = if convergence {
+            f.meridian_convergence
+        } else {
+            f.areal_scale
+        };
+        let result = Coor4D::raw(
+            f.tissot_semimajor,
+            f.tissot_semiminor,
+            third,
+            f.angular_distortion.to_degrees(),
+        );
+        if result.0.iter().all(|c| c.is_finite()) {
+            successes += 1;
+        }
+        operands.set_coord(i, &result);
+    }
+    successes
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 2] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Flag { key: "convergence" },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    Op::plain(parameters, InnerOp(fwd), None, &GAMUT, ctx)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tissot() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Mercator is conformal, so the indicatrix is a circle of radius
+        // 1/cos(lat) on the sphere
+        let op = ctx.op("geo:in | merc ellps=sphere | tissot")?;
+        let mut operands = [Coor4D::raw(60., 12., 0., 0.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 1);
+        assert!((operands[0][0] - 2.).abs() < 1e-12);
+        assert!((operands[0][1] - 2.).abs() < 1e-12);
+        assert!((operands[0][2] - 4.).abs() < 1e-12);
+        assert!(operands[0][3].abs() < 1e-9);
+
+        // UTM, through numerical differentiation: The scale factor on the
+        // central meridian is 0.9996, and the meridian convergence vanishes
+        let op = ctx.op("geo:in | utm zone=32 | tissot convergence")?;
+        let mut operands = [Coor4D::raw(55., 9., 0., 0.), Coor4D::raw(55., 12., 0., 0.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 2);
+        assert!((operands[0][0] - 0.9996).abs() < 1e-7);
+        assert!((operands[0][1] - 0.9996).abs() < 1e-7);
+        assert!(operands[0][2].abs() < 1e-9);
+        assert!(operands[0][3].abs() < 1e-5);
+
+        // ...while 3 degrees east of the central meridian, the convergence is
+        // approximately Δλ·sin(𝜙), and the scale has grown
+        assert!((operands[1][2] - 3. * 55_f64.to_radians().sin()).abs() < 0.01);
+        assert!(operands[1][0] > 1.0);
+
+        // Omitted steps are not considered preceding projections
+        let op = ctx.op("geo:in | merc ellps=sphere | addone omit_fwd | tissot")?;
+        let mut operands = [Coor4D::raw(60., 12., 0., 0.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert!((operands[0][0] - 2.).abs() < 1e-12);
+
+        // Without a preceding step, there is nothing to investigate
+        let op = ctx.op("tissot")?;
+        let mut operands = [Coor4D::raw(60., 12., 0., 0.)];
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 0);
+        assert!(operands[0][0].is_nan());
+
+        // Tissot is not invertible
+        assert!(ctx.op("tissot inv").is_err());
+        Ok(())
+    }
+}
@@ -6,7 +6,9 @@ use crate::authoring::*;
 // Forward transverse mercator, following Engsager & Poder(2007)
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     // Make all precomputed parameters directly accessible
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let lon_0 = op.params.lon(0).to_radians();
     let x_0 = op.params.x(0);
     let Some(conformal) = op.params.fourier_coefficients.get("conformal") else {
@@ -26,11 +28,8 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         return 0;
     };
 
-    let range = 0..operands.len();
-    let mut successes = 0_usize;
-    for i in range {
-        //let mut coord = operands.get_coord(i);
-        let (lon, lat) = operands.xy(i);
+    for_each_coord(operands, |coord| {
+        let (lon, lat) = coord.xy();
 
         // --- 1. Geographical -> Conformal latitude, rotated longitude
 
@@ -75,8 +74,8 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
         // Don't wanna play if we're too far from the center meridian
         if lon.abs() > 2.623395162778 {
-            operands.set_xy(i, f64::NAN, f64::NAN);
-            continue;
+            coord.set_xy(f64::NAN, f64::NAN);
+            return false;
         }
 
         // --- 4. ellipsoidal normalized N, E -> metric N, E
@@ -85,11 +84,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         let northing = qs * lat + zb; // Northing
 
         // Done!
-        operands.set_xy(i, easting, northing);
-        successes += 1;
-    }
-
-    successes
+        coord.set_xy(easting, northing);
+        true
+    })
 }
 
 // ----- I N V E R S E -----------------------------------------------------------------
@@ -97,7 +94,9 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // Inverse Transverse Mercator, following Engsager & Poder (2007)
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     // Make all precomputed parameters directly accessible
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let lon_0 = op.params.lon(0).to_radians();
     let x_0 = op.params.x(0);
     let Some(conformal) = op.params.fourier_coefficients.get("conformal") else {
@@ -117,10 +116,8 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         return 0;
     };
 
-    let range = 0..operands.len();
-    let mut successes = 0_usize;
-    for i in range {
-        let (x, y) = operands.xy(i);
+    for_each_coord(operands, |coord| {
+        let (x, y) = coord.xy();
 
         // --- 1. Normalize N, E
 
@@ -129,8 +126,8 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 
         // Don't wanna play if we're too far from the center meridian
         if lon.abs() > 2.623395162778 {
-            operands.set_xy(i, f64::NAN, f64::NAN);
-            continue;
+            coord.set_xy(f64::NAN, f64::NAN);
+            return false;
         }
 
         // --- 2. Normalized N, E -> complex spherical LAT, LNG
@@ -154,20 +151,22 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
         let lat = ellps.latitude_conformal_to_geographic(lat, conformal);
 
         // Done!
-        operands.set_xy(i, lon, lat);
-        successes += 1;
-    }
-
-    successes
+        coord.set_xy(lon, lat);
+        true
+    })
 }
 
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 7] = [
+pub const GAMUT: [OpParameter; 9] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
 
+    // Selection of the computational engine, directly or by accuracy hint
+    OpParameter::Text { key: "engine",   default: Some("poder_engsager") },
+    OpParameter::Text { key: "accuracy", default: Some("") },
+
     OpParameter::Real { key: "lat_0", default: Some(0_f64) },
     OpParameter::Real { key: "lon_0", default: Some(0_f64) },
     OpParameter::Real { key: "x_0",   default: Some(0_f64) },
@@ -176,12 +175,37 @@ pub const GAMUT: [OpParameter; 7] = [
     OpParameter::Real { key: "k_0",   default: Some(1_f64) },
 ];
 
+// Transverse Mercator, EPSG method 9807
 #[rustfmt::skip]
-pub const UTM_GAMUT: [OpParameter; 4] = [
+pub const TEST_VECTORS: [OpTestVector; 1] = [OpTestVector {
+    source: "EPSG Guidance Note 7-2, Transverse Mercator (9807)",
+    definition: "geo:in | tmerc lat_0=49 lon_0=-2 k_0=0.9996012717 x_0=400000 y_0=-100000 ellps=airy",
+    input: [50.5, 0.5, 0., 0.],
+    output: [577_274.98, 69_740.49, 0., 0.],
+    tolerance: [0.01, 0.01, 0., 0.],
+    inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+}];
+
+// UTM zone 32, validation values from PROJ:
+// echo 12 55 0 0 | cct -d18 +proj=utm +zone=32
+#[rustfmt::skip]
+pub const UTM_TEST_VECTORS: [OpTestVector; 1] = [OpTestVector {
+    source: "PROJ, cct +proj=utm +zone=32",
+    definition: "geo:in | utm zone=32",
+    input: [55., 12., 0., 0.],
+    output: [691_875.632_139_661, 6_098_907.825_005_012, 0., 0.],
+    tolerance: [1e-6, 1e-6, 0., 0.],
+    inverse_tolerance: [1e-10, 1e-10, 0., 0.],
+}];
+
+#[rustfmt::skip]
+pub const UTM_GAMUT: [OpParameter; 6] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Flag { key: "south" },
     OpParameter::Text { key: "ellps", default: Some("GRS80") },
     OpParameter::Natural { key: "zone", default: None },
+    OpParameter::Text { key: "engine",   default: Some("poder_engsager") },
+    OpParameter::Text { key: "accuracy", default: Some("") },
 ];
 
 // ----- C O N S T R U C T O R,   U T M ------------------------------------------------
@@ -229,40 +253,77 @@ pub fn utm(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error>
         id,
     };
 
-    precompute(&mut op);
+    if use_engine(&mut op)? {
+        precompute(&mut op)?;
+    }
     Ok(op)
 }
 
 // ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
 
-#[rustfmt::skip]
-const TRANSVERSE_MERCATOR: PolynomialCoefficients = PolynomialCoefficients {
-    // Geodetic to TM. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
-    fwd: [
-        [1./2.,   -2./3.,   5./16.,   41./180.,   -127./288.0 ,   7891./37800.],
-        [0., 13./48.,   -3./5.,   557./1440.,   281./630.,   -1983433./1935360.],
-        [0., 0., 61./240.,  -103./140.,   15061./26880.,   167603./181440.],
-        [0., 0., 0., 49561./161280.,   -179./168.,   6601661./7257600.],
-        [0., 0., 0., 0., 34729./80640.,   -3418889./1995840.],
-        [0., 0., 0., 0., 0., 212378941./319334400.]
-    ],
-
-    // TM to Geodetic. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
-    inv: [
-        [-1./2.,   2./3.,   -37./96.,   1./360.,   81./512.,   -96199./604800.],
-        [0., -1./48.,   -1./15.,   437./1440.,   -46./105.,   1118711./3870720.],
-        [0., 0., -17./480.,   37./840.,   209./4480.,   -5569./90720.],
-        [0., 0., 0., -4397./161280.,   11./504.,   830251./7257600.],
-        [0., 0., 0., 0., -4583./161280.,   108847./3991680.],
-        [0., 0., 0., 0., 0., -20648693./638668800.]
-    ]
-};
+// The computational engines
+enum Engine {
+    PoderEngsager,
+    Bowring,
+    Exact,
+}
+
+// Engine selection: Poder/Engsager (the default) is accurate to the nanometer
+// level out to several thousand km from the central meridian. Bowring (1989)
+// is faster, but only accurate to the millimeter level within a few degrees
+// of it. The exact engine, following Lee (1976) and Karney (2011), is slower,
+// but accurate all over the ellipsoid. The `accuracy` hint, if given, trumps
+// the `engine` selection.
+fn engine(params: &ParsedParameters) -> Result<Engine, Error> {
+    let engine = match params.text("accuracy")?.as_str() {
+        "" => params.text("engine")?,
+        "high" => "poder_engsager".to_string(),
+        "fast" => "bowring".to_string(),
+        other => return Err(Error::BadParam("accuracy".to_string(), other.to_string())),
+    };
+    match engine.as_str() {
+        "poder_engsager" => Ok(Engine::PoderEngsager),
+        "bowring" => Ok(Engine::Bowring),
+        "exact" => {
+            // The exact engine needs an oblate ellipsoid. For the sphere, the
+            // default engine is exact anyway
+            let f = params.ellps(0)?.flattening();
+            if f < 0. {
+                return Err(Error::BadParam("engine".to_string(), engine));
+            }
+            Ok(if f == 0. {
+                Engine::PoderEngsager
+            } else {
+                Engine::Exact
+            })
+        }
+        _ => Err(Error::BadParam("engine".to_string(), engine)),
+    }
+}
+
+// Hand over the computations to the implementation selected: Bowring from
+// `btmerc`, or the exact implementation. Returns true if the selected
+// engine needs the Poder/Engsager precomputations.
+fn use_engine(op: &mut Op) -> Result<bool, Error> {
+    match engine(&op.params)? {
+        Engine::PoderEngsager => return Ok(true),
+        Engine::Bowring => {
+            op.descriptor.fwd = InnerOp(super::btmerc::fwd);
+            op.descriptor.inv = InnerOp(super::btmerc::inv);
+        }
+        Engine::Exact => {
+            op.descriptor.fwd = InnerOp(super::tmerc_exact::fwd);
+            op.descriptor.inv = InnerOp(super::tmerc_exact::inv);
+        }
+    }
+    Ok(false)
+}
 
 // Common setup workhorse between utm and the plain tmerc:
 // Pre-compute some of the computationally heavy prerequisites,
 // to get better amortization over the full operator lifetime.
-fn precompute(op: &mut Op) {
-    let ellps = op.params.ellps(0);
+fn precompute(op: &mut Op) -> Result<(), Error> {
+    let ellps = op.params.ellps(0)?;
     let n = ellps.third_flattening();
     let lat_0 = op.params.lat(0).to_radians();
     let y_0 = op.params.y(0);
@@ -289,11 +350,14 @@ fn precompute(op: &mut Op) {
     // i.e. true northing = N - zb
     let zb = y_0 - qs * (z + fourier::sin(2. * z, &tm.fwd));
     op.params.real.insert("zb", zb);
+    Ok(())
 }
 
 pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
     let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
-    precompute(&mut op);
+    if use_engine(&mut op)? {
+        precompute(&mut op)?;
+    }
     Ok(op)
 }
 
@@ -303,6 +367,7 @@ pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
 mod tests {
     use super::*;
     use float_eq::assert_float_eq;
+    use std::f64::consts::FRAC_PI_2;
 
     #[test]
     fn tmerc() -> Result<(), Error> {
@@ -435,4 +500,119 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn engine() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let geo = [Coor2D::geo(55., 12.), Coor2D::geo(-55., 10.)];
+
+        // Close to the central meridian, the engines agree to the sub-mm level
+        let mut expected = geo;
+        let op = ctx.op("tmerc k_0=0.9996 lon_0=9 x_0=500000")?;
+        ctx.apply(op, Fwd, &mut expected)?;
+
+        for definition in [
+            "tmerc engine=bowring k_0=0.9996 lon_0=9 x_0=500000",
+            "tmerc accuracy=fast k_0=0.9996 lon_0=9 x_0=500000",
+            "utm zone=32 engine=bowring",
+        ] {
+            let op = ctx.op(definition)?;
+            let mut operands = geo;
+            ctx.apply(op, Fwd, &mut operands)?;
+            for i in 0..operands.len() {
+                assert!(operands[i].hypot2(&expected[i]) < 1e-3);
+            }
+            ctx.apply(op, Inv, &mut operands)?;
+            for i in 0..operands.len() {
+                assert!(operands[i].hypot2(&geo[i]) < 1e-9);
+            }
+        }
+
+        // The accuracy hint trumps the engine selection
+        let op = ctx.op("tmerc engine=bowring accuracy=high k_0=0.9996 lon_0=9 x_0=500000")?;
+        let mut operands = geo;
+        ctx.apply(op, Fwd, &mut operands)?;
+        assert_eq!(operands[0], expected[0]);
+
+        assert!(matches!(
+            ctx.op("tmerc engine=bogus"),
+            Err(Error::BadParam(_, _))
+        ));
+        assert!(matches!(
+            ctx.op("utm zone=32 accuracy=bogus"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn exact() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let geo = [Coor2D::geo(55., 12.), Coor2D::geo(-55., -6.)];
+
+        // Close to the central meridian, the default engine agrees with
+        // the exact one to the nanometer level
+        let mut expected = geo;
+        let op = ctx.op("utm zone=32")?;
+        ctx.apply(op, Fwd, &mut expected)?;
+        for definition in [
+            "utm zone=32 engine=exact",
+            "tmerc engine=exact k_0=0.9996 lon_0=9 x_0=500000",
+        ] {
+            let op = ctx.op(definition)?;
+            let mut operands = geo;
+            assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 2);
+            for i in 0..operands.len() {
+                assert!(operands[i].hypot2(&expected[i]) < 1e-8);
+            }
+            assert_eq!(ctx.apply(op, Inv, &mut operands)?, 2);
+            for i in 0..operands.len() {
+                assert_float_eq!(operands[i].0, geo[i].0, abs_all <= 1e-14);
+            }
+        }
+
+        // Including the latitude of origin and the false northing
+        let definition = "lat_0=49 lon_0=-2 k_0=0.9996012717 x_0=400000 y_0=-100000 ellps=airy";
+        let op = ctx.op(&format!("tmerc {definition}"))?;
+        let exact = ctx.op(&format!("tmerc engine=exact {definition}"))?;
+        let mut operands = [Coor2D::geo(52., 1.)];
+        let mut expected = operands;
+        ctx.apply(op, Fwd, &mut expected)?;
+        ctx.apply(exact, Fwd, &mut operands)?;
+        assert!(operands[0].hypot2(&expected[0]) < 1e-8);
+
+        // The north pole is a quarter meridian from the equator
+        let op = ctx.op("utm zone=32 engine=exact")?;
+        let mut operands = [Coor2D::geo(90., 9.)];
+        ctx.apply(op, Fwd, &mut operands)?;
+        let quadrant = Ellipsoid::named("GRS80")?.meridian_latitude_to_distance(FRAC_PI_2);
+        assert!((operands[0][0] - 500_000.).abs() < 1e-9);
+        assert!((operands[0][1] - 0.9996 * quadrant).abs() < 1e-6);
+
+        // Far from the central meridian, where the default engine gives up,
+        // the exact engine still roundtrips
+        let geo = [
+            Coor2D::geo(1., 98.),
+            Coor2D::geo(30., 69.),
+            Coor2D::geo(-80., 170.),
+        ];
+        let mut operands = geo;
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 3);
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 3);
+        for i in 0..operands.len() {
+            assert_float_eq!(operands[i].0, geo[i].0, abs_all <= 1e-14);
+        }
+        let mut operands = [geo[0]];
+        let op = ctx.op("utm zone=32")?;
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 0);
+
+        // For the sphere, the default engine is exact, and for prolate
+        // ellipsoids, the exact engine is unavailable
+        assert!(ctx.op("tmerc engine=exact ellps=6378137,0").is_ok());
+        assert!(matches!(
+            ctx.op("tmerc engine=exact ellps=6378137,-300"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
 }
@@ -0,0 +1,444 @@
+//! Exact transverse Mercator, following [Lee (1976)](crate::bibliography::Bibliography::Lee76),
+//! as implemented by [Karney (2011)](crate::bibliography::Bibliography::Kar11) in the
+//! `TransverseMercatorExact` class of GeographicLib. Selected in `tmerc` and `utm`
+//! by `engine=exact`
+use crate::authoring::*;
+use crate::ellipsoid::triaxial::{carlson_rd, carlson_rf};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+pub(super) fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    let tm = ExactTm::new(&ellps);
+    let lon_0 = op.params.lon(0).to_radians();
+    let x_0 = op.params.x(0);
+    let scale = op.params.k(0) * ellps.semimajor_axis();
+    let zb = op.params.y(0) - scale * tm.fwd(0., op.params.lat(0).to_radians()).1;
+
+    for_each_coord(operands, |coord| {
+        let (lon, lat) = coord.xy();
+        let (eta, xi) = tm.fwd(lon - lon_0, lat);
+        if eta.is_nan() || xi.is_nan() {
+            coord.set_xy(f64::NAN, f64::NAN);
+            return false;
+        }
+        coord.set_xy(scale * eta + x_0, scale * xi + zb);
+        true
+    })
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+pub(super) fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
+    let tm = ExactTm::new(&ellps);
+    let lon_0 = op.params.lon(0).to_radians();
+    let x_0 = op.params.x(0);
+    let scale = op.params.k(0) * ellps.semimajor_axis();
+    let zb = op.params.y(0) - scale * tm.fwd(0., op.params.lat(0).to_radians()).1;
+
+    for_each_coord(operands, |coord| {
+        let (x, y) = coord.xy();
+        let (lon, lat) = tm.inv((x - x_0) / scale, (y - zb) / scale);
+        if lon.is_nan() || lat.is_nan() {
+            coord.set_xy(f64::NAN, f64::NAN);
+            return false;
+        }
+        coord.set_xy(angular::normalize_symmetric(lon + lon_0), lat);
+        true
+    })
+}
+
+// ----- J A C O B I   E L L I P T I C   F U N C T I O N S -----------------------------
+
+// The complete elliptic integrals, and the Jacobi elliptic functions, for
+// the parameter k² (with k'² = 1 - k²)
+struct Elliptic {
+    k2: f64,
+    kp2: f64,
+    // The complete integrals of the first and second kind, K(k) and E(k),
+    // and their difference, K(k) - E(k), evaluated without cancellation
+    k: f64,
+    e: f64,
+    ke: f64,
+}
+
+impl Elliptic {
+    fn new(k2: f64) -> Elliptic {
+        let kp2 = 1. - k2;
+        let k = carlson_rf(0., kp2, 1.);
+        let ke = k2 * carlson_rd(0., kp2, 1.) / 3.;
+        Elliptic {
+            k2,
+            kp2,
+            k,
+            e: k - ke,
+            ke,
+        }
+    }
+
+    // The Jacobi elliptic functions sn(x), cn(x) and dn(x), following
+    // Bulirsch (1965), as implemented in GeographicLib
+    fn sncndn(&self, x: f64) -> (f64, f64, f64) {
+        if self.kp2 == 0. {
+            let cn = x.cosh().recip();
+            return (x.tanh(), cn, cn);
+        }
+
+        const N: usize = 13;
+        let tolerance = (f64::EPSILON * 0.01).sqrt();
+        let mut m = [0.; N];
+        let mut n = [0.; N];
+        let mut mc = self.kp2;
+        let mut a = 1.;
+        let mut c = 0.;
+        let mut l = 0;
+        while l < N {
+            // Quadratic convergence: At most 5 trips
+            m[l] = a;
+            mc = mc.sqrt();
+            n[l] = mc;
+            c = (a + mc) / 2.;
+            l += 1;
+            if (a - mc).abs() <= tolerance * a {
+                break;
+            }
+            mc *= a;
+            a = c;
+        }
+
+        let x = x * c;
+        let (mut sn, mut cn) = x.sin_cos();
+        let mut dn = 1.;
+        if sn != 0. {
+            let mut a = cn / sn;
+            c *= a;
+            while l > 0 {
+                l -= 1;
+                let b = m[l];
+                a *= c;
+                c *= dn;
+                dn = (n[l] + a) / (b + a);
+                a = c / b;
+            }
+            a = (c * c + 1.).sqrt().recip();
+            sn = a.copysign(sn);
+            cn = c * sn;
+        }
+        (sn, cn, dn)
+    }
+
+    // The incomplete elliptic integral of the second kind, E(φ, k), given
+    // in terms of sn, cn and dn of its argument. DLMF 19.25.E10, extended
+    // beyond |φ| = π/2 by symmetry
+    fn e(&self, sn: f64, cn: f64, dn: f64) -> f64 {
+        let (sn2, cn2, dn2) = (sn * sn, cn * cn, dn * dn);
+        let mut e = self.e;
+        if cn2 != 0. {
+            e = sn.abs()
+                * (self.kp2 * carlson_rf(cn2, dn2, 1.)
+                    + self.k2 * self.kp2 * sn2 * carlson_rd(cn2, 1., dn2) / 3.
+                    + self.k2 * cn.abs() / dn);
+        }
+        if cn < 0. {
+            e = 2. * self.e - e;
+        }
+        e.copysign(sn)
+    }
+}
+
+// ----- T H E   E X A C T   M A P P I N G ---------------------------------------------
+
+// The transverse Mercator projection of the ellipsoid with eccentricity e,
+// on the unit sphere, by way of the Thompson transverse Mercator, w = u + iv.
+// The computations are organized as in GeographicLib, which supplies the
+// details, and follow the notation of Lee (1976)
+struct ExactTm {
+    e: f64,
+    mu: f64,
+    mv: f64,
+    eu: Elliptic,
+    ev: Elliptic,
+}
+
+impl ExactTm {
+    fn new(ellps: &Ellipsoid) -> ExactTm {
+        let mu = ellps.eccentricity_squared();
+        ExactTm {
+            e: mu.sqrt(),
+            mu,
+            mv: 1. - mu,
+            eu: Elliptic::new(mu),
+            ev: Elliptic::new(1. - mu),
+        }
+    }
+
+    // The conformal coordinates, ζ = ψ + iλ, in terms of sinh(ψ) and λ (Lee 54.17)
+    fn zeta(&self, snu: f64, cnu: f64, dnu: f64, snv: f64, cnv: f64, dnv: f64) -> (f64, f64) {
+        let (e, mu, mv) = (self.e, self.mu, self.mv);
+        let overflow = (f64::EPSILON * f64::EPSILON).recip();
+        let d1 = (cnu * cnu + mv * (snu * snv).powi(2)).sqrt();
+        let d2 = (mu * cnu * cnu + mv * cnv * cnv).sqrt();
+        let t1 = if d1 != 0. {
+            snu * dnv / d1
+        } else {
+            overflow.copysign(snu)
+        };
+        let t2 = if d2 != 0. {
+            (e * (e * snu / d2).asinh()).sinh()
+        } else {
+            overflow.copysign(snu)
+        };
+        let taup = t1 * t2.hypot(1.) - t2 * t1.hypot(1.);
+        let lam = if d1 != 0. && d2 != 0. {
+            (dnu * snv).atan2(cnu * cnv) - e * (e * cnu * snv).atan2(dnu * cnv)
+        } else {
+            0.
+        };
+        (taup, lam)
+    }
+
+    // The reciprocal of the derivative dζ/dw (Lee 54.21)
+    fn dwdzeta(&self, snu: f64, cnu: f64, dnu: f64, snv: f64, cnv: f64, dnv: f64) -> (f64, f64) {
+        let (mu, mv) = (self.mu, self.mv);
+        let d = mv * (cnv * cnv + mu * (snu * snv).powi(2)).powi(2);
+        let du = cnu * dnu * dnv * (cnv * cnv - mu * (snu * snv).powi(2)) / d;
+        let dv = -snu * snv * cnv * ((dnu * dnv).powi(2) + mu * cnu * cnu) / d;
+        (du, dv)
+    }
+
+    // Starting point for the inversion of ζ(w). Returns true as the third
+    // element, if the starting point is accurate enough to use as is
+    fn zetainv0(&self, psi: f64, lam: f64) -> (f64, f64, bool) {
+        let (e, mu, mv) = (self.e, self.mu, self.mv);
+        let taytol = f64::EPSILON.powf(0.6);
+
+        // Close to the log singularity at the south pole, w = K(e) + iK(e')
+        if psi < -e * PI / 4. && lam > (1. - 2. * e) * FRAC_PI_2 && psi < lam - (1. - e) * FRAC_PI_2
+        {
+            let psix = 1. - psi / e;
+            let lamx = (FRAC_PI_2 - lam) / e;
+            let u = (lamx.sin() / lamx.cos().hypot(psix.sinh())).asinh() * (1. + mu / 2.);
+            let v = lamx.cos().atan2(psix.sinh()) * (1. + mu / 2.);
+            return (self.eu.k - u, self.ev.k - v, false);
+        }
+
+        // Close to the branch point at w = iK(e'), where ζ' = ζ'' = 0, so
+        // ζ = ζ₀ - (e'²e/3)·(w - w₀)³, to the first non-vanishing order
+        if psi < e * FRAC_PI_2 && lam > (1. - 2. * e) * FRAC_PI_2 {
+            let dlam = lam - (1. - e) * FRAC_PI_2;
+            let rad = psi.hypot(dlam);
+            let ang = (dlam - psi).atan2(psi + dlam) - 0.75 * PI;
+            let done = rad < e * taytol;
+            let rad = (3. / (mv * e) * rad).cbrt();
+            let ang = ang / 3.;
+            return (rad * ang.cos(), rad * ang.sin() + self.ev.k, done);
+        }
+
+        // Elsewhere, the spherical transverse Mercator (Lee 12.6), scaled to
+        // put the north pole in the right place
+        let v = (lam.sin() / lam.cos().hypot(psi.sinh())).asinh();
+        let u = psi.sinh().atan2(lam.cos());
+        let scale = self.eu.k / FRAC_PI_2;
+        (u * scale, v * scale, false)
+    }
+
+    // Invert ζ(w), given sinh(ψ) and λ, using Newton's method
+    fn zetainv(&self, taup: f64, lam: f64) -> (f64, f64) {
+        let psi = taup.asinh();
+        let scale = taup.hypot(1.).recip();
+        let (mut u, mut v, done) = self.zetainv0(psi, lam);
+        if done {
+            return (u, v);
+        }
+        let stol2 = 0.1 * f64::EPSILON / psi.max(1.).powi(2);
+        let mut trip = false;
+        for _ in 0..10 {
+            let (snu, cnu, dnu) = self.eu.sncndn(u);
+            let (snv, cnv, dnv) = self.ev.sncndn(v);
+            let (tau1, lam1) = self.zeta(snu, cnu, dnu, snv, cnv, dnv);
+            let (du1, dv1) = self.dwdzeta(snu, cnu, dnu, snv, cnv, dnv);
+            let tau1 = (tau1 - taup) * scale;
+            let lam1 = lam1 - lam;
+            let delu = tau1 * du1 - lam1 * dv1;
+            let delv = tau1 * dv1 + lam1 * du1;
+            u -= delu;
+            v -= delv;
+            if trip {
+                break;
+            }
+            // One more iteration after convergence, for good measure
+            let delw2 = delu * delu + delv * delv;
+            trip = delw2 < stol2 || delw2.is_nan();
+        }
+        (u, v)
+    }
+
+    // The transverse Mercator coordinates, σ = ξ + iη (Lee 55.4)
+    #[allow(clippy::too_many_arguments)]
+    fn sigma(
+        &self,
+        v: f64,
+        snu: f64,
+        cnu: f64,
+        dnu: f64,
+        snv: f64,
+        cnv: f64,
+        dnv: f64,
+    ) -> (f64, f64) {
+        let (mu, mv) = (self.mu, self.mv);
+        let d = mu * cnu * cnu + mv * cnv * cnv;
+        let xi = self.eu.e(snu, cnu, dnu) - mu * snu * cnu * dnu / d;
+        let eta = v - self.ev.e(snv, cnv, dnv) + mv * snv * cnv * dnv / d;
+        (xi, eta)
+    }
+
+    // The reciprocal of the derivative dσ/dw (Lee 55.9)
+    fn dwdsigma(&self, snu: f64, cnu: f64, dnu: f64, snv: f64, cnv: f64, dnv: f64) -> (f64, f64) {
+        let (mu, mv) = (self.mu, self.mv);
+        let d = mv * (cnv * cnv + mu * (snu * snv).powi(2)).powi(2);
+        let dnr = dnu * cnv * dnv;
+        let dni = -mu * snu * cnu * snv;
+        ((dnr * dnr - dni * dni) / d, 2. * dnr * dni / d)
+    }
+
+    // Starting point for the inversion of σ(w). Returns true as the third
+    // element, if the starting point is accurate enough to use as is
+    fn sigmainv0(&self, xi: f64, eta: f64) -> (f64, f64, bool) {
+        let (eu, ev) = (&self.eu, &self.ev);
+        let taytol = f64::EPSILON.powf(0.6);
+
+        // Close to the simple pole at w = K(e) + iK(e')
+        if eta > 1.25 * ev.ke || (xi < -0.25 * eu.e && xi < eta - ev.ke) {
+            let x = xi - eu.e;
+            let y = eta - ev.ke;
+            let r2 = x * x + y * y;
+            return (eu.k + x / r2, ev.k - y / r2, false);
+        }
+
+        // Close to the branch point at w = iK(e'), where σ' = σ'' = 0, so
+        // σ = σ₀ - (e'²/3)·(w - w₀)³, to the first non-vanishing order
+        if (eta > 0.75 * ev.ke && xi < 0.25 * eu.e) || eta > ev.ke {
+            let deta = eta - ev.ke;
+            let rad = xi.hypot(deta);
+            let ang = (deta - xi).atan2(xi + deta) - 0.75 * PI;
+            let done = rad < 2. * taytol;
+            let rad = (3. / self.mv * rad).cbrt();
+            let ang = ang / 3.;
+            return (rad * ang.cos(), rad * ang.sin() + ev.k, done);
+        }
+
+        // Elsewhere, w = σ·K(e)/E(e), which is correct in the limit e → 0
+        (xi * eu.k / eu.e, eta * eu.k / eu.e, false)
+    }
+
+    // Invert σ(w) using Newton's method
+    fn sigmainv(&self, xi: f64, eta: f64) -> (f64, f64) {
+        let (mut u, mut v, done) = self.sigmainv0(xi, eta);
+        if done {
+            return (u, v);
+        }
+        let tol2 = 0.1 * f64::EPSILON;
+        let mut trip = false;
+        for _ in 0..10 {
+            let (snu, cnu, dnu) = self.eu.sncndn(u);
+            let (snv, cnv, dnv) = self.ev.sncndn(v);
+            let (xi1, eta1) = self.sigma(v, snu, cnu, dnu, snv, cnv, dnv);
+            let (du1, dv1) = self.dwdsigma(snu, cnu, dnu, snv, cnv, dnv);
+            let xi1 = xi1 - xi;
+            let eta1 = eta1 - eta;
+            let delu = xi1 * du1 - eta1 * dv1;
+            let delv = xi1 * dv1 + eta1 * du1;
+            u -= delu;
+            v -= delv;
+            if trip {
+                break;
+            }
+            let delw2 = delu * delu + delv * delv;
+            trip = delw2 < tol2 || delw2.is_nan();
+        }
+        (u, v)
+    }
+
+    // Longitude (reckoned from the central meridian) and latitude, in
+    // radians, to easting and northing (η, ξ) on the unit sphere
+    fn fwd(&self, lon: f64, lat: f64) -> (f64, f64) {
+        if lat.is_nan() || lat.abs() > FRAC_PI_2 {
+            return (f64::NAN, f64::NAN);
+        }
+        let lon = angular::normalize_symmetric(lon);
+
+        // Work in the first quadrant, and account for the signs afterwards
+        let mut latsign = if lat.is_sign_negative() { -1. } else { 1. };
+        let lonsign = if lon.is_sign_negative() { -1. } else { 1. };
+        let lat = lat.abs();
+        let mut lon = lon.abs();
+        let backside = lon > FRAC_PI_2;
+        if backside {
+            if lat == 0. {
+                latsign = -1.;
+            }
+            lon = PI - lon;
+        }
+
+        let (u, v) = if lat == FRAC_PI_2 {
+            (self.eu.k, 0.)
+        } else if lat == 0. && lon == FRAC_PI_2 * (1. - self.e) {
+            (0., self.ev.k)
+        } else {
+            // sinh(ψ), where ψ is the isometric latitude
+            let tau = lat.tan();
+            let tau1 = tau.hypot(1.);
+            let sig = (self.e * (self.e * tau / tau1).atanh()).sinh();
+            let taup = sig.hypot(1.) * tau - sig * tau1;
+            self.zetainv(taup, lon)
+        };
+
+        let (snu, cnu, dnu) = self.eu.sncndn(u);
+        let (snv, cnv, dnv) = self.ev.sncndn(v);
+        let (mut xi, eta) = self.sigma(v, snu, cnu, dnu, snv, cnv, dnv);
+        if backside {
+            xi = 2. * self.eu.e - xi;
+        }
+        (eta * lonsign, xi * latsign)
+    }
+
+    // Easting and northing (η, ξ) on the unit sphere, to longitude
+    // (reckoned from the central meridian) and latitude, in radians
+    fn inv(&self, eta: f64, xi: f64) -> (f64, f64) {
+        // Work in the first quadrant, and account for the signs afterwards
+        let xisign = if xi.is_sign_negative() { -1. } else { 1. };
+        let etasign = if eta.is_sign_negative() { -1. } else { 1. };
+        let mut xi = xi.abs();
+        let eta = eta.abs();
+        let backside = xi > self.eu.e;
+        if backside {
+            xi = 2. * self.eu.e - xi;
+        }
+
+        let (u, v) = if xi == 0. && eta == self.ev.ke {
+            (0., self.ev.k)
+        } else {
+            self.sigmainv(xi, eta)
+        };
+
+        let (mut lon, lat) = if v != 0. || u != self.eu.k {
+            let (snu, cnu, dnu) = self.eu.sncndn(u);
+            let (snv, cnv, dnv) = self.ev.sncndn(v);
+            let (taup, lam) = self.zeta(snu, cnu, dnu, snv, cnv, dnv);
+            (lam, ancillary::sinhpsi_to_tanphi(taup, self.e).atan())
+        } else {
+            (0., FRAC_PI_2)
+        };
+
+        if backside {
+            lon = PI - lon;
+        }
+        (lon * etasign, lat * xisign)
+    }
+}
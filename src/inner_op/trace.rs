@@ -0,0 +1,125 @@
+//! Debugging aid: Log the first few coordinates passing through a pipeline.
+//!
+//! `trace` leaves the data untouched, but logs (at the `info` level) the
+//! first `n` coordinate tuples passing through it, together with the
+//! definition of the step they just passed through, the direction of
+//! travel, and an optional `label`. The first two coordinates may be
+//! converted to degrees or gradians (`unit=deg`, `unit=gon`) for
+//! readability, which is helpful when diagnosing sign and axis order
+//! problems, e.g.
+//!
+//! ```sh
+//! echo 55 12 | kp -vv "geo:in | trace unit=deg | utm zone=32 | trace"
+//! ```
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    trace(op, None, Fwd, operands)
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    trace(op, None, Inv, operands)
+}
+
+/// The actual work, called from the pipeline driver, which knows the `previous`
+/// step, i.e. the one the operands just passed through
+pub(super) fn trace(
+    op: &Op,
+    previous: Option<&Op>,
+    direction: Direction,
+    operands: &mut dyn CoordinateSet,
+) -> usize {
+    for message in messages(op, previous, direction, operands) {
+        info!("{message}");
+    }
+    operands.len()
+}
+
+// The log messages, one for each of the first n operands
+fn messages(
+    op: &Op,
+    previous: Option<&Op>,
+    direction: Direction,
+    operands: &dyn CoordinateSet,
+) -> Vec<String> {
+    let n = op.params.natural("n").unwrap_or(1).min(operands.len());
+    let factor = op.params.real("factor").unwrap_or(1.);
+    let label = op.params.text("label").unwrap_or_default();
+
+    let mut header = String::from("trace");
+    if !label.is_empty() {
+        header += &format!(" [{label}]");
+    }
+    header += if direction == Fwd { " fwd" } else { " inv" };
+    if let Some(previous) = previous {
+        header += &format!(" after '{}'", previous.descriptor.definition.trim());
+    }
+
+    (0..n)
+        .map(|i| {
+            let c = operands.get_coord(i);
+            let (x, y) = (c[0] * factor, c[1] * factor);
+            format!("{header} #{i}: {x} {y} {} {}", c[2], c[3])
+        })
+        .collect()
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 4] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Natural { key: "n", default: Some(1) },
+    OpParameter::Text { key: "unit", default: Some("any") },
+    OpParameter::Text { key: "label", default: Some("") },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+
+    // Conversion factor from radians to the unit of presentation
+    let unit = op.params.text("unit")?;
+    let factor = match unit.as_str() {
+        "any" | "rad" => 1.,
+        "deg" => 180. / std::f64::consts::PI,
+        "gon" => 200. / std::f64::consts::PI,
+        _ => return Err(Error::BadParam("unit".to_string(), unit)),
+    };
+    op.params.real.insert("factor", factor);
+    Ok(op)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+
+        // The data pass through untouched
+        let op = ctx.op("utm zone=32 | trace n=2 label=projected | utm inv zone=32")?;
+        let geo = [Coor4D::geo(55., 12., 0., 0.), Coor4D::geo(59., 18., 0., 0.)];
+        let mut data = geo;
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert!(data[0].hypot2(&geo[0]) < 1e-9);
+        assert!(data[1].hypot2(&geo[1]) < 1e-9);
+
+        // The messages
+        let trace = Op::new("trace n=5 unit=deg label=here", &ctx)?;
+        let previous = Op::new("utm zone=32", &ctx)?;
+        let messages = messages(&trace, Some(&previous), Inv, &geo);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("trace [here] inv after 'utm zone=32' #0: 12"));
+        assert!(messages[1].starts_with("trace [here] inv after 'utm zone=32' #1: 18"));
+
+        assert!(ctx.op("trace unit=furlong").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+/// Conversion between ellipsoidal and physical heights, using a prioritized
+/// stack of regional geoid (or height correction) models, e.g.
+/// `vertical_datum grids=@dk.geoid,egm08.geoid datums=DVR90,EGM2008`.
+///
+/// For each point, the first grid of the stack containing it is used, in
+/// the sense of `grids_at`, and the target datum of the region is available
+/// from the `regions` parameter of the operator. With the `tag` flag, the
+/// index of the region used is written to the fourth coordinate element.
+use crate::authoring::*;
+
+// ----- F O R W A R D -----------------------------------------------------------------
+
+fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    convert(op, operands, -1.)
+}
+
+// ----- I N V E R S E -----------------------------------------------------------------
+
+fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
+    convert(op, operands, 1.)
+}
+
+// ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
+
+// The forward (ellipsoidal to physical) and inverse cases differ only in the
+// sign of the geoid undulation
+fn convert(op: &Op, operands: &mut dyn CoordinateSet, sign: f64) -> usize {
+    let tag = op.params.boolean("tag");
+    for_each_coord(operands, |coord| {
+        let Some((region, n)) = undulation(op, coord) else {
+            *coord = Coor4D::nan();
+            return false;
+        };
+        coord[2] += sign * n;
+        if tag {
+            coord[3] = region as f64;
+        }
+        true
+    })
+}
+
+// The index of the region containing `coord`, and the geoid undulation there.
+// As for `grids_at`, the grids are first searched without, then with a margin
+fn undulation(op: &Op, coord: &Coor4D) -> Option<(usize, f64)> {
+    let grids = &op.params.grids;
+    for margin in [0.0, 0.5] {
+        for (region, grid) in grids.iter().enumerate() {
+            if let Some(n) = grid.at(coord, margin) {
+                return Some((region, n[0]));
+            }
+        }
+    }
+
+    // The null grid is the last region, if present
+    if op.params.boolean("null_grid") {
+        return Some((grids.len(), 0.));
+    }
+    None
+}
+
+// ----- C O N S T R U C T O R ---------------------------------------------------------
+
+#[rustfmt::skip]
+pub const GAMUT: [OpParameter; 4] = [
+    OpParameter::Flag { key: "inv" },
+    OpParameter::Flag { key: "tag" },
+    OpParameter::Texts { key: "grids", default: None },
+    OpParameter::Texts { key: "datums", default: Some("") },
+];
+
+pub fn new(parameters: &RawParameters, ctx: &dyn Context) -> Result<Op, Error> {
+    let mut op = Op::plain(parameters, InnerOp(fwd), Some(InnerOp(inv)), &GAMUT, ctx)?;
+    let grids = op.params.texts("grids")?.clone();
+
+    // Default to naming the target datums after the grids
+    let datums = match op.params.texts("datums") {
+        Ok(datums) => datums.clone(),
+        Err(_) => grids
+            .iter()
+            .map(|g| g.trim_start_matches('@').to_string())
+            .collect(),
+    };
+    if datums.len() != grids.len() {
+        return Err(Error::BadParam("datums".to_string(), datums.join(",")));
+    }
+
+    // The target datums of the grids actually available
+    let mut regions = Vec::new();
+    for (grid_name, datum) in grids.iter().zip(datums) {
+        let optional = grid_name.starts_with('@');
+        let grid_name = grid_name.trim_start_matches('@');
+
+        if grid_name == "null" {
+            op.params.boolean.insert("null_grid");
+            regions.push(datum);
+            break; // ignore any additional grids after a null grid
+        }
+
+        match ctx.get_grid(grid_name) {
+            Ok(grid) => {
+                if grid.bands() != 1 {
+                    return Err(Error::Unsupported(format!(
+                        "vertical_datum: '{grid_name}' is not a geoid model"
+                    )));
+                }
+                op.params.grids.push(grid);
+                regions.push(datum);
+            }
+            Err(e) => {
+                if !optional {
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    if regions.is_empty() {
+        return Err(Error::MissingParam("grids".to_string()));
+    }
+    op.params.texts.insert("regions", regions);
+    Ok(op)
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertical_datum() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+        let op = ctx.op("vertical_datum tag grids=@missing.geoid,test.geoid,null datums=A,B,C")?;
+
+        // The target datums of the available regions
+        let regions = ctx.params(op, 0)?.texts("regions")?.clone();
+        assert_eq!(regions, ["B", "C"]);
+
+        // Inside the test geoid, the undulation is latitude + longitude / 100
+        let inside = Coor4D::geo(55.5, 12.5, 100., 0.);
+        let outside = Coor4D::geo(45., 12.5, 100., 0.);
+        let mut data = [inside, outside];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert!((data[0][2] - (100. - 55.625)).abs() < 1e-5);
+        assert_eq!(data[0][3], 0.);
+        // The null grid region
+        assert_eq!(data[1][2], 100.);
+        assert_eq!(data[1][3], 1.);
+
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][2] - 100.).abs() < 1e-9);
+
+        // Without the null grid, points outside of the stack are stomped on
+        let op = ctx.op("vertical_datum grids=test.geoid")?;
+        let mut data = [inside, outside];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 1);
+        assert!(data[1][2].is_nan());
+        // And without the tag flag, the fourth coordinate is left untouched
+        assert_eq!(data[0][3], 0.);
+        assert_eq!(ctx.params(op, 0)?.texts("regions")?[0], "test.geoid");
+
+        // Mismatching number of datums, and non-geoid grids
+        assert!(ctx
+            .op("vertical_datum grids=test.geoid datums=A,B")
+            .is_err());
+        assert!(ctx.op("vertical_datum grids=test.datum").is_err());
+        assert!(ctx.op("vertical_datum grids=@missing.geoid").is_err());
+        Ok(())
+    }
+}
@@ -2,17 +2,45 @@
 use crate::authoring::*;
 use std::f64::consts::FRAC_PI_2;
 use std::f64::consts::FRAC_PI_4;
+use std::f64::consts::PI;
+
+// The latitude mapping to the northing π·a, i.e. making the projected
+// world a square: atan(sinh(π)) ≈ 85.0511°
+fn latitude_limit() -> f64 {
+    PI.sinh().atan()
+}
+
+// Handle coordinates outside of the domain, according to the `domain` parameter.
+// Returns true if the coordinate should be rejected
+fn reject(op: &Op, outside: bool, (u, v): (f64, f64)) -> bool {
+    if !outside {
+        return false;
+    }
+    if op.params.boolean("warn") {
+        warn!("Webmerc: ({u}, {v}) outside of the Web Mercator domain");
+    }
+    op.params.boolean("strict")
+}
 
 // ----- F O R W A R D -----------------------------------------------------------------
 
 fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
+    let lat_max = latitude_limit();
 
     let mut successes = 0_usize;
     for i in 0..operands.len() {
         let (lon, lat) = operands.xy(i);
 
+        let outside = lat.abs() > lat_max + 1e-12 || lon.abs() > PI + 1e-12;
+        if reject(op, outside, (lon.to_degrees(), lat.to_degrees())) {
+            operands.set_xy(i, f64::NAN, f64::NAN);
+            continue;
+        }
+
         let easting = lon * a;
         let northing = a * (FRAC_PI_4 + lat / 2.0).tan().ln();
 
@@ -26,13 +54,24 @@ fn fwd(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
 // ----- I N V E R S E -----------------------------------------------------------------
 
 fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
-    let ellps = op.params.ellps(0);
+    let Ok(ellps) = op.params.ellps(0) else {
+        return 0;
+    };
     let a = ellps.semimajor_axis();
 
+    // The EPSG:3857 bounds: ±20037508.34 m for the WGS84 semimajor axis
+    let limit = PI * a * (1.0 + 1e-12);
+
     let mut successes = 0_usize;
     for i in 0..operands.len() {
         let (easting, northing) = operands.xy(i);
 
+        let outside = easting.abs() > limit || northing.abs() > limit;
+        if reject(op, outside, (easting, northing)) {
+            operands.set_xy(i, f64::NAN, f64::NAN);
+            continue;
+        }
+
         // Easting -> Longitude
         let longitude = easting / a;
 
@@ -46,19 +85,52 @@ fn inv(op: &Op, _ctx: &dyn Context, operands: &mut dyn CoordinateSet) -> usize {
     successes
 }
 
+// ----- D E R I V A T I V E S ---------------------------------------------------------
+
+fn jacobian(op: &Op, _ctx: &dyn Context, at: &Coor4D) -> Option<[f64; 4]> {
+    let a = op.params.ellps(0).ok()?.semimajor_axis();
+    Some([a, 0., 0., a / at[1].cos()])
+}
+
 // ----- C O N S T R U C T O R ---------------------------------------------------------
 
 #[rustfmt::skip]
-pub const GAMUT: [OpParameter; 2] = [
+pub const GAMUT: [OpParameter; 3] = [
     OpParameter::Flag { key: "inv" },
     OpParameter::Text { key: "ellps",  default: Some("WGS84") },
+    OpParameter::Text { key: "domain", default: Some("none") },
 ];
 
+// Popular Visualisation Pseudo Mercator, EPSG method 1024
+#[rustfmt::skip]
+pub const TEST_VECTORS: [OpTestVector; 1] = [OpTestVector {
+    source: "EPSG Guidance Note 7-2, Popular Visualisation Pseudo Mercator (1024)",
+    definition: "geo:in | webmerc",
+    input: [24. + 22. / 60. + 54.433 / 3600., -(100. + 20. / 60.), 0., 0.],
+    output: [-11_169_055.58, 2_800_000.00, 0., 0.],
+    tolerance: [0.01, 0.01, 0., 0.],
+    inverse_tolerance: [1e-7, 1e-7, 0., 0.],
+}];
+
 pub fn new(parameters: &RawParameters, _ctx: &dyn Context) -> Result<Op, Error> {
     let def = &parameters.definition;
-    let params = ParsedParameters::new(parameters, &GAMUT)?;
+    let mut params = ParsedParameters::new(parameters, &GAMUT)?;
+
+    // Handling of coordinates outside of the domain: Ignore (the default),
+    // warn, or reject (i.e. return NaN)
+    match params.text("domain")?.as_str() {
+        "none" => (),
+        "warn" => {
+            params.boolean.insert("warn");
+        }
+        "strict" => {
+            params.boolean.insert("strict");
+        }
+        other => return Err(Error::BadParam("domain".to_string(), other.to_string())),
+    }
 
-    let descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    let mut descriptor = OpDescriptor::new(def, InnerOp(fwd), Some(InnerOp(inv)));
+    descriptor.jacobian = Some(InnerJacobian(jacobian));
     let steps = Vec::<Op>::new();
     let id = OpHandle::new();
 
@@ -109,4 +181,51 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn webmerc_domain() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        assert!(matches!(
+            ctx.op("webmerc domain=bogus"),
+            Err(Error::BadParam(_, _))
+        ));
+
+        // Beyond ±85.0511°, or outside of ±20037508.34 m, for the inverse
+        let geo = [
+            Coor4D::geo(85.05, 12., 0., 0.),
+            Coor4D::geo(-85.06, 12., 0., 0.),
+            Coor4D::geo(55., 181., 0., 0.),
+        ];
+        let projected = [
+            Coor4D::raw(20_037_508.34, -20_037_508.34, 0., 0.),
+            Coor4D::raw(0., 20_037_509., 0., 0.),
+        ];
+
+        // Default: No checks
+        let op = ctx.op("webmerc")?;
+        let mut operands = geo;
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 3);
+        let mut operands = projected;
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 2);
+
+        // Warnings only
+        let op = ctx.op("webmerc domain=warn")?;
+        let mut operands = geo;
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 3);
+        assert!(!operands[1][1].is_nan());
+
+        // Rejection
+        let op = ctx.op("webmerc domain=strict")?;
+        let mut operands = geo;
+        assert_eq!(ctx.apply(op, Fwd, &mut operands)?, 1);
+        assert!((operands[0][1] - 20_036_051.919).abs() < 1e-3);
+        assert!(operands[1][0].is_nan() && operands[2][1].is_nan());
+
+        let mut operands = projected;
+        assert_eq!(ctx.apply(op, Inv, &mut operands)?, 1);
+        assert!((operands[0][1].to_degrees() + 85.051_128_78).abs() < 1e-8);
+        assert!(operands[1][0].is_nan());
+
+        Ok(())
+    }
 }
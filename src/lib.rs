@@ -38,7 +38,11 @@ pub mod ctx {
     #[cfg(feature = "with_plain")]
     pub use crate::context::plain::Plain;
     pub use crate::context::Context;
+    pub use crate::context::Metric;
+    pub use crate::context::SelfTest;
+    pub use crate::op::between;
     pub use crate::op::OpHandle;
+    pub use crate::op::OpSnapshot;
     pub use crate::Direction;
     pub use crate::Direction::Fwd;
     pub use crate::Direction::Inv;
@@ -50,8 +54,12 @@ pub mod ellps {
     pub use crate::ellipsoid::geocart::GeoCart;
     pub use crate::ellipsoid::geodesics::Geodesics;
     pub use crate::ellipsoid::gravity::Gravity;
+    pub use crate::ellipsoid::gravity::GravityConstants;
+    pub use crate::ellipsoid::latitudes::AuxiliaryLatitude;
+    pub use crate::ellipsoid::latitudes::AuxiliaryLatitudes;
     pub use crate::ellipsoid::latitudes::Latitudes;
     pub use crate::ellipsoid::meridians::Meridians;
+    pub use crate::ellipsoid::rhumblines::Rhumblines;
     pub use crate::ellipsoid::triaxial::TriaxialEllipsoid;
     pub use crate::ellipsoid::EllipsoidBase;
 }
@@ -62,19 +70,37 @@ pub mod coord {
     pub use crate::coordinate::coor2d::Coor2D;
     pub use crate::coordinate::coor32::Coor32;
     pub use crate::coordinate::coor3d::Coor3D;
+    pub use crate::coordinate::coor3d32::Coor3D32;
     pub use crate::coordinate::coor4d::Coor4D;
+    #[cfg(feature = "mmap")]
+    pub use crate::coordinate::mapped::MappedCoordinateSet;
     // Coordinate traits
     pub use crate::coordinate::set::CoordinateSet;
+    pub use crate::coordinate::set::CoordinateSubset;
     pub use crate::coordinate::tuple::CoordinateTuple;
     pub use crate::coordinate::AngularUnits;
     pub use crate::coordinate::CoordinateMetadata;
+    pub use crate::coordinate::Crs;
+    pub use crate::coordinate::DataEpoch;
+    pub use crate::coordinate::MdIdentifier;
     pub use crate::math::angular;
 }
 
+/// Type safe, programmatic construction of operator definitions
+pub mod builder {
+    pub use crate::op::builder::*;
+}
+
 /// Elements for building operators
 mod ops {
+    pub use crate::inner_op::builtin_operator;
+    pub use crate::inner_op::builtin_operators;
+    pub use crate::inner_op::for_each_coord;
+    pub use crate::inner_op::InnerJacobian;
     pub use crate::inner_op::InnerOp;
     pub use crate::inner_op::OpConstructor;
+    pub use crate::inner_op::OpDocumentation;
+    pub use crate::inner_op::OpTestVector;
     pub use crate::op::Op;
     pub use crate::op::OpDescriptor;
     pub use crate::op::OpParameter;
@@ -85,9 +111,15 @@ mod ops {
 /// Elements for handling grids
 mod grd {
     pub use crate::grid::grids_at;
+    pub use crate::grid::grids_at_with_units;
     pub use crate::grid::ntv2::Ntv2Grid;
+    pub use crate::grid::AxisOrder;
     pub use crate::grid::BaseGrid;
     pub use crate::grid::Grid;
+    pub use crate::grid::GridInfo;
+    pub use crate::grid::GridUnit;
+    pub use crate::grid::GridUnits;
+    pub use crate::grid::Tin;
 }
 
 /// Elements for parsing both Geodesy and PROJ syntax
@@ -96,6 +128,8 @@ mod parse {
     pub use crate::token::Tokenize;
     // PROJ interoperability
     pub use crate::token::parse_proj;
+    // Environment variable expansion
+    pub use crate::token::interpolate_environment;
 }
 
 use thiserror::Error;
@@ -155,7 +189,7 @@ pub enum Error {
 /// should run in the *forward* direction.
 /// `Inv`: Indicate that a two-way operator, function, or method,
 /// should run in the *inverse* direction.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Fwd,
     Inv,
@@ -165,9 +199,14 @@ mod bibliography;
 mod context;
 mod coordinate;
 mod ellipsoid;
+#[cfg(feature = "epsg")]
+pub mod epsg;
+pub mod gie;
 mod grid;
 mod inner_op;
 mod math;
+#[cfg(feature = "nkg")]
+pub mod nkg;
 mod op;
 mod token;
 
@@ -65,6 +65,27 @@ pub fn dd_to_iso_dms(dd: f64) -> f64 {
     sign * (d * 10000. + m * 100. + s)
 }
 
+/// Transformation from gradians (gon) to degrees-with-decimals.
+/// A full circle is 400 gon.
+pub fn gon_to_dd(gon: f64) -> f64 {
+    gon * 0.9
+}
+
+/// Transformation from degrees-with-decimals to gradians (gon).
+pub fn dd_to_gon(dd: f64) -> f64 {
+    dd / 0.9
+}
+
+/// Transformation from turns (full circles) to degrees-with-decimals.
+pub fn turns_to_dd(turns: f64) -> f64 {
+    turns * 360.
+}
+
+/// Transformation from degrees-with-decimals to turns (full circles).
+pub fn dd_to_turns(dd: f64) -> f64 {
+    dd / 360.
+}
+
 /// normalize arbitrary angles to [-π, π)
 pub fn normalize_symmetric(angle: f64) -> f64 {
     use std::f64::consts::PI;
@@ -83,44 +104,136 @@ pub fn normalize_positive(angle: f64) -> f64 {
 }
 
 /// Parse sexagesimal degrees, i.e. degrees, minutes and seconds in the
-/// format 45:30:36, 45:30:36N,-45:30:36 etc.
+/// format 45:30:36, 45:30:36N, -45:30:36, 45°30'36"N, 045 30 36W, N45 30 36 etc.
+///
+/// The elements may be separated by colons, whitespace, or the symbols for
+/// degrees (°), minutes (' or ′) and seconds (" or ″). When given, the symbols
+/// decide which element a number belongs to, so 30'36" is 30 minutes and
+/// 36 seconds. A hemisphere letter, NSEW, may lead or trail: S and W negate.
+/// A leading minus sign negates the angle as a whole, so -30'36" is -0.51°.
+/// Signs elsewhere, and a hemisphere letter combined with a minus sign
+/// (which is ambiguous), give NaN, as does the absence of any numbers.
 pub fn parse_sexagesimal(angle: &str) -> f64 {
     // Degrees, minutes, and seconds
     let mut dms = [0.0, 0.0, 0.0];
     let mut angle = angle.trim();
 
     // Empty?
-    let n = angle.len();
-    if n == 0 || angle == "NaN" {
+    if angle.is_empty() || angle == "NaN" {
         return f64::NAN;
     }
 
     // Handle NSEW indicators
-    let mut postfix_sign = 1.0;
-    if "wWsSeEnN".contains(&angle[n - 1..]) {
-        if "wWsS".contains(&angle[n - 1..]) {
-            postfix_sign = -1.0;
+    let mut hemisphere_sign = 1.0;
+    let hemisphere = |c: &char| "wWsSeEnN".contains(*c);
+    let hemisphere_given = if let Some(last) = angle.chars().last().filter(hemisphere) {
+        if "wWsS".contains(last) {
+            hemisphere_sign = -1.0;
+        }
+        angle = angle[..angle.len() - 1].trim_end();
+        true
+    } else if let Some(first) = angle.chars().next().filter(hemisphere) {
+        if "wWsS".contains(first) {
+            hemisphere_sign = -1.0;
         }
-        angle = &angle[..n - 1];
+        angle = angle[1..].trim_start();
+        true
+    } else {
+        false
+    };
+
+    // Is -55S in the southern or the northern hemisphere?
+    if hemisphere_given && angle.starts_with('-') {
+        warn!("Ambiguous sign of sexagesimal angle: {angle}");
+        return f64::NAN;
+    }
+
+    // The sign applies to the angle as a whole, so -30'36" is -0.51°
+    let mut sign = hemisphere_sign;
+    if let Some(rest) = angle.strip_prefix('-') {
+        sign = -1.0;
+        angle = rest.trim_start();
+    } else if let Some(rest) = angle.strip_prefix('+') {
+        angle = rest.trim_start();
     }
 
-    // Split into as many elements as given: D, D:M, D:M:S
-    for (i, element) in angle.split(':').enumerate() {
-        if i < 3 {
-            if let Ok(v) = element.parse::<f64>() {
-                dms[i] = v;
+    // Split into as many elements as given: D, D:M, D:M:S. A trailing space
+    // terminates the final element
+    let mut next = 0;
+    let mut element = String::new();
+    for c in angle.chars().chain(std::iter::once(' ')) {
+        let index = match c {
+            '°' | 'º' => Some(0),
+            '\'' | '′' => Some(1),
+            '"' | '″' => Some(2),
+            ':' => None,
+            c if c.is_whitespace() => {
+                if element.is_empty() {
+                    continue;
+                }
+                None
+            }
+            c => {
+                element.push(c);
                 continue;
             }
+        };
+        let index = index.unwrap_or(next);
+
+        // Elements must be given in order, at most 3 of them, and signs
+        // are only allowed at the start of the angle, as handled above
+        if element.starts_with(['-', '+']) {
+            warn!("Cannot parse {angle} as a real number or sexagesimal angle");
+            return f64::NAN;
         }
-        // More than 3 elements?
+        let Ok(v) = element.parse::<f64>() else {
+            warn!("Cannot parse {angle} as a real number or sexagesimal angle");
+            return f64::NAN;
+        };
+        if index < next || index > 2 {
+            warn!("Cannot parse {angle} as a real number or sexagesimal angle");
+            return f64::NAN;
+        }
+        dms[index] = v;
+        next = index + 1;
+        element.clear();
+    }
+
+    // Nothing but a sign and/or a hemisphere letter?
+    if next == 0 {
         warn!("Cannot parse {angle} as a real number or sexagesimal angle");
         return f64::NAN;
     }
 
     // Sexagesimal conversion if we have more than one element. Otherwise
     // decay gracefully to plain real/f64 conversion
-    let sign = dms[0].signum() * postfix_sign;
-    sign * (dms[0].abs() + (dms[1] + dms[2] / 60.0) / 60.0)
+    sign * (dms[0] + (dms[1] + dms[2] / 60.0) / 60.0)
+}
+
+/// Format an angle, given in degrees, as sexagesimal degrees, minutes and
+/// seconds, with `decimals` decimals on the seconds, and a hemisphere letter
+/// from `hemispheres` (positive, negative) indicating the sign, e.g.
+/// `format_sexagesimal(55.51, 1, ['N', 'S'])` gives `55°30'36.0"N`
+pub fn format_sexagesimal(angle: f64, decimals: usize, hemispheres: [char; 2]) -> String {
+    if !angle.is_finite() {
+        return format!("{angle}");
+    }
+    let hemisphere = if angle.is_sign_negative() && angle != 0. {
+        hemispheres[1]
+    } else {
+        hemispheres[0]
+    };
+
+    // Round to the number of decimals first, so the carries propagate
+    // properly, i.e. we never get 60 seconds or 60 minutes
+    let unit = 10_f64.powi(decimals as i32);
+    let total = (angle.abs() * 3600. * unit).round();
+    let seconds = (total % (60. * unit)) / unit;
+    let minutes = ((total / (60. * unit)).floor() % 60.) as u32;
+    let degrees = (total / (3600. * unit)).floor() as u64;
+
+    let width = if decimals == 0 { 2 } else { decimals + 3 };
+    format!("{degrees}°{minutes:02}'{seconds:0width$.decimals$}\"{hemisphere}")
 }
 
 // ----- Tests ---------------------------------------------------------------------
@@ -149,6 +262,28 @@ mod tests {
         assert_eq!(iso_dm_to_dd(-5500.), -55.);
         assert_eq!(iso_dm_to_dd(5530.60), -iso_dm_to_dd(-5530.60));
         assert_eq!(iso_dms_to_dd(553036.), -iso_dms_to_dd(-553036.00));
+
+        // gon + turns
+        assert_eq!(gon_to_dd(100.), 90.);
+        assert_eq!(dd_to_gon(-90.), -100.);
+        assert!((gon_to_dd(dd_to_gon(55.51)) - 55.51).abs() < 1e-12);
+        assert_eq!(turns_to_dd(0.25), 90.);
+        assert_eq!(dd_to_turns(-180.), -0.5);
+    }
+
+    #[test]
+    fn test_format_sexagesimal() {
+        assert_eq!(format_sexagesimal(55.51, 1, ['N', 'S']), "55°30'36.0\"N");
+        assert_eq!(format_sexagesimal(-55.51, 3, ['N', 'S']), "55°30'36.000\"S");
+        assert_eq!(format_sexagesimal(-12.0014, 0, ['E', 'W']), "12°00'05\"W");
+        assert_eq!(format_sexagesimal(0., 2, ['E', 'W']), "0°00'00.00\"E");
+
+        // Rounding carries over to minutes and degrees
+        assert_eq!(
+            format_sexagesimal(12.99999999, 2, ['E', 'W']),
+            "13°00'00.00\"E"
+        );
+        assert_eq!(format_sexagesimal(f64::NAN, 2, ['E', 'W']), "NaN");
     }
 
     #[test]
@@ -160,5 +295,43 @@ mod tests {
         assert_eq!(1.51, parse_sexagesimal("1:30:36e"));
         assert_eq!(-1.51, parse_sexagesimal("1:30:36w"));
         assert!(parse_sexagesimal("q1:30:36w").is_nan());
+        assert!(parse_sexagesimal("ø").is_nan());
+        assert!(parse_sexagesimal("1:30:36:12").is_nan());
+        assert!(parse_sexagesimal("1::30").is_nan());
+        assert_eq!(12.5, parse_sexagesimal("12.5"));
+
+        // Symbols, whitespace, and hemisphere letters
+        let expected = 55. + (30. + 15.2 / 60.) / 60.;
+        assert_eq!(expected, parse_sexagesimal("55°30'15.2\"N"));
+        assert_eq!(expected, parse_sexagesimal("55° 30′ 15.2″ N"));
+        assert_eq!(expected, parse_sexagesimal("N55 30 15.2"));
+        assert_eq!(-expected, parse_sexagesimal("55 30 15.2 S"));
+        assert_eq!(-3.125, parse_sexagesimal("003 07 30W"));
+        assert_eq!(-3.125, parse_sexagesimal("W 3°7.5'"));
+        assert_eq!(55., parse_sexagesimal("55°"));
+
+        // The symbols decide which element a number belongs to
+        assert_eq!(0.51, parse_sexagesimal("30'36\""));
+        assert!(parse_sexagesimal("36\"30'").is_nan());
+        assert!(parse_sexagesimal("1°2°").is_nan());
+
+        // A minus sign combined with a hemisphere letter is ambiguous
+        assert!(parse_sexagesimal("-55S").is_nan());
+        assert!(parse_sexagesimal("-55:30N").is_nan());
+        assert!(parse_sexagesimal("W -3 7 30").is_nan());
+
+        // The sign is taken once, for the angle as a whole
+        assert_eq!(-0.51, parse_sexagesimal("-30'36\""));
+        assert_eq!(-0.51, parse_sexagesimal("-0:30:36"));
+        assert_eq!(1.51, parse_sexagesimal("+1:30:36"));
+        assert!(parse_sexagesimal("0°-30'").is_nan());
+        assert!(parse_sexagesimal("1:-30:36").is_nan());
+        assert!(parse_sexagesimal("--1").is_nan());
+        assert_eq!(1e-7, parse_sexagesimal("1e-7"));
+
+        // No numeric components
+        assert!(parse_sexagesimal("N").is_nan());
+        assert!(parse_sexagesimal("-").is_nan());
+        assert!(parse_sexagesimal("°'").is_nan());
     }
 }
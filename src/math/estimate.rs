@@ -0,0 +1,460 @@
+//! Least squares estimation of transformation parameters from point
+//! correspondences, i.e. the inverse problem of applying a `helmert`
+//! transformation: Given the coordinates of a set of points in two systems,
+//! find the parameters best transforming the first set into the second,
+//! and the residuals remaining after the transformation.
+use crate::authoring::*;
+
+/// The transformation models supported by [`estimate()`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Model {
+    /// 3 parameters: Translation, `[x, y, z]` (m)
+    Helmert3,
+    /// 4 parameters: 2D similarity, `[x, y, rz, s]`: Translation (m), rotation
+    /// around the z axis (arcsec, position vector convention), and scale (ppm)
+    Helmert4,
+    /// 7 parameters: 3D similarity, `[x, y, z, rx, ry, rz, s]`: Translation (m),
+    /// small-angle rotations (arcsec, position vector convention), and scale (ppm)
+    Helmert7,
+    /// 6 parameters: 2D affine, `[x, a11, a12, y, a21, a22]`, i.e.
+    /// x' = x + a11·X + a12·Y, and y' = y + a21·X + a22·Y
+    Affine2D,
+}
+
+impl Model {
+    /// The number of parameters of the model
+    pub fn parameters(&self) -> usize {
+        match self {
+            Model::Helmert3 => 3,
+            Model::Helmert4 => 4,
+            Model::Helmert7 => 7,
+            Model::Affine2D => 6,
+        }
+    }
+
+    /// The number of coordinate elements (observations) per point
+    pub fn dimension(&self) -> usize {
+        match self {
+            Model::Helmert3 | Model::Helmert7 => 3,
+            Model::Helmert4 | Model::Affine2D => 2,
+        }
+    }
+}
+
+/// The result of an estimation: The parameters, and the residual statistics
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    pub model: Model,
+    /// The estimated parameters, in the order and units given by the [`Model`]
+    pub parameters: Vec<f64>,
+    /// Per-point residuals, i.e. observed minus transformed coordinates.
+    /// For the 2D models, the third element is zero
+    pub residuals: Vec<Coor3D>,
+    /// Root mean square of the lengths of the residual vectors
+    pub rms: f64,
+    /// Length of the largest residual vector
+    pub max: f64,
+    /// A posteriori standard deviation of unit weight, √(vᵀv / redundancy).
+    /// NaN when the redundancy is zero
+    pub sigma0: f64,
+    /// The number of observations minus the number of parameters
+    pub redundancy: usize,
+}
+
+impl Estimate {
+    /// Transform `from` using the estimated parameters. The 2D models
+    /// pass the third coordinate element through unchanged
+    pub fn transform(&self, from: &Coor3D) -> Coor3D {
+        let p = &self.parameters;
+        let [x, y, z] = from.0;
+        match self.model {
+            Model::Helmert3 => Coor3D::raw(x + p[0], y + p[1], z + p[2]),
+            Model::Helmert4 => {
+                let s = 1. + p[3] * 1e-6;
+                let (sin, cos) = arcsec(p[2]).sin_cos();
+                let x_ = p[0] + s * (cos * x - sin * y);
+                let y_ = p[1] + s * (sin * x + cos * y);
+                Coor3D::raw(x_, y_, z)
+            }
+            Model::Helmert7 => {
+                let t = [p[0], p[1], p[2]];
+                let r = [arcsec(p[3]), arcsec(p[4]), arcsec(p[5])];
+                let s = 1. + p[6] * 1e-6;
+                Coor3D(similarity(t, r, s, &from.0))
+            }
+            Model::Affine2D => {
+                let x_ = p[0] + p[1] * x + p[2] * y;
+                let y_ = p[3] + p[4] * x + p[5] * y;
+                Coor3D::raw(x_, y_, z)
+            }
+        }
+    }
+
+    /// A Rust Geodesy operator definition, applying the estimated
+    /// transformation, e.g. for use with [`Context::op()`]. Note that for
+    /// the `Helmert4` model, the `helmert` operator also scales the third
+    /// coordinate element. There is no operator for the 2D affine case
+    pub fn definition(&self) -> Option<String> {
+        let p = &self.parameters;
+        match self.model {
+            Model::Helmert3 => Some(format!("helmert x={} y={} z={}", p[0], p[1], p[2])),
+            Model::Helmert4 => Some(format!(
+                "helmert x={} y={} rz={} s={} exact convention=position_vector",
+                p[0], p[1], p[2], p[3]
+            )),
+            Model::Helmert7 => Some(format!(
+                "helmert x={} y={} z={} rx={} ry={} rz={} s={} convention=position_vector",
+                p[0], p[1], p[2], p[3], p[4], p[5], p[6]
+            )),
+            Model::Affine2D => None,
+        }
+    }
+}
+
+/// Estimate the parameters of the transformation `model`, best (in the
+/// least squares sense) transforming the points in `from` into the
+/// corresponding points in `to`. All observations are equally weighted.
+pub fn estimate(
+    model: Model,
+    from: &dyn CoordinateSet,
+    to: &dyn CoordinateSet,
+) -> Result<Estimate, Error> {
+    let n = from.len();
+    if to.len() != n {
+        return Err(Error::Unexpected {
+            message: "Point correspondences must come in pairs".to_string(),
+            expected: format!("{n} points"),
+            found: format!("{} points", to.len()),
+        });
+    }
+    let observations = n * model.dimension();
+    if observations < model.parameters() {
+        return Err(Error::Unexpected {
+            message: format!("Too few points for {model:?}"),
+            expected: format!("at least {} observations", model.parameters()),
+            found: format!("{observations}"),
+        });
+    }
+
+    let from: Vec<[f64; 3]> = (0..n).map(|i| from.xyz(i).into()).collect();
+    let to: Vec<[f64; 3]> = (0..n).map(|i| to.xyz(i).into()).collect();
+
+    // Work on coordinates reduced to their centroid, for better conditioning
+    let mut centroid = [0.; 3];
+    for p in &from {
+        for i in 0..3 {
+            centroid[i] += p[i] / n as f64;
+        }
+    }
+    let reduced: Vec<[f64; 3]> = from
+        .iter()
+        .map(|p| [p[0] - centroid[0], p[1] - centroid[1], p[2] - centroid[2]])
+        .collect();
+
+    let parameters = match model {
+        Model::Helmert3 => {
+            let mut t = [0.; 3];
+            for (f, t_) in from.iter().zip(&to) {
+                for i in 0..3 {
+                    t[i] += (t_[i] - f[i]) / n as f64;
+                }
+            }
+            t.to_vec()
+        }
+        Model::Helmert4 => helmert4(&reduced, &to, &centroid)?,
+        Model::Helmert7 => helmert7(&reduced, &to, &centroid)?,
+        Model::Affine2D => affine2d(&reduced, &to, &centroid)?,
+    };
+
+    let mut estimate = Estimate {
+        model,
+        parameters,
+        residuals: Vec::with_capacity(n),
+        rms: 0.,
+        max: 0.,
+        sigma0: 0.,
+        redundancy: observations - model.parameters(),
+    };
+
+    // Residual statistics
+    let mut sum_of_squares = 0.;
+    for (f, t) in from.iter().zip(&to) {
+        let transformed = estimate.transform(&Coor3D(*f));
+        let mut v = Coor3D::raw(t[0], t[1], t[2]) - transformed;
+        if model.dimension() == 2 {
+            v[2] = 0.;
+        }
+        let length = v.dot(v).sqrt();
+        sum_of_squares += length * length;
+        estimate.max = estimate.max.max(length);
+        estimate.residuals.push(v);
+    }
+    estimate.rms = (sum_of_squares / n as f64).sqrt();
+    estimate.sigma0 = (sum_of_squares / estimate.redundancy as f64).sqrt();
+    if estimate.redundancy == 0 {
+        estimate.sigma0 = f64::NAN;
+    }
+    Ok(estimate)
+}
+
+// ----- M O D E L S -------------------------------------------------------------------
+
+// x' = x + a·X - b·Y,  y' = y + b·X + a·Y,  with a = s·cos(rz), b = s·sin(rz)
+fn helmert4(from: &[[f64; 3]], to: &[[f64; 3]], centroid: &[f64; 3]) -> Result<Vec<f64>, Error> {
+    let mut normal = Normal::new(4);
+    for (f, t) in from.iter().zip(to) {
+        normal.add(&[1., 0., f[0], -f[1]], t[0]);
+        normal.add(&[0., 1., f[1], f[0]], t[1]);
+    }
+    let [x, y, a, b] = normal.solve()?[..] else {
+        unreachable!()
+    };
+
+    // Move the translation from the centroid back to the origin
+    let x = x - (a * centroid[0] - b * centroid[1]);
+    let y = y - (b * centroid[0] + a * centroid[1]);
+    let rz = b.atan2(a).to_degrees() * 3600.;
+    let s = (a.hypot(b) - 1.) * 1e6;
+    Ok(vec![x, y, rz, s])
+}
+
+fn affine2d(from: &[[f64; 3]], to: &[[f64; 3]], centroid: &[f64; 3]) -> Result<Vec<f64>, Error> {
+    let mut normal = Normal::new(6);
+    for (f, t) in from.iter().zip(to) {
+        normal.add(&[1., f[0], f[1], 0., 0., 0.], t[0]);
+        normal.add(&[0., 0., 0., 1., f[0], f[1]], t[1]);
+    }
+    let mut p = normal.solve()?;
+    p[0] -= p[1] * centroid[0] + p[2] * centroid[1];
+    p[3] -= p[4] * centroid[0] + p[5] * centroid[1];
+    Ok(p)
+}
+
+// The 7 parameter model is non-linear (due to the product of scale and
+// rotation), so we iterate, Gauss-Newton style, starting from the identity
+fn helmert7(from: &[[f64; 3]], to: &[[f64; 3]], centroid: &[f64; 3]) -> Result<Vec<f64>, Error> {
+    // Translation, rotation (radians) and scale deviation (unitless)
+    let mut t = [0.; 3];
+    let mut r = [0.; 3];
+    let mut d = 0.;
+
+    for _ in 0..10 {
+        let s = 1. + d;
+        let mut normal = Normal::new(7);
+        for (f, o) in from.iter().zip(to) {
+            let c = similarity(t, r, s, f);
+            let m = similarity([0.; 3], r, 1., f);
+            let [x, y, z] = *f;
+            normal.add(&[1., 0., 0., 0., s * z, -s * y, m[0]], o[0] - c[0]);
+            normal.add(&[0., 1., 0., -s * z, 0., s * x, m[1]], o[1] - c[1]);
+            normal.add(&[0., 0., 1., s * y, -s * x, 0., m[2]], o[2] - c[2]);
+        }
+        let delta = normal.solve()?;
+        for i in 0..3 {
+            t[i] += delta[i];
+            r[i] += delta[i + 3];
+        }
+        d += delta[6];
+
+        // Converged when the corrections are insignificant at the micrometre level
+        let extent = from.iter().map(|p| p.iter().map(|c| c.abs()).sum::<f64>());
+        let extent = extent.fold(1., f64::max);
+        let rotation_and_scale = delta[3..].iter().map(|c| c.abs()).fold(0., f64::max);
+        let translation = delta[..3].iter().map(|c| c.abs()).fold(0., f64::max);
+        if translation < 1e-9 && rotation_and_scale * extent < 1e-9 {
+            break;
+        }
+    }
+
+    // Move the translation from the centroid back to the origin
+    let c = similarity([0.; 3], r, 1. + d, centroid);
+    let mut p = vec![t[0] - c[0], t[1] - c[1], t[2] - c[2]];
+    p.extend(r.iter().map(|r| r.to_degrees() * 3600.));
+    p.push(d * 1e6);
+    Ok(p)
+}
+
+// Small-angle, position vector convention similarity transformation, matching
+// the non-`exact` case of the `helmert` operator: t + s·R·p
+fn similarity(t: [f64; 3], r: [f64; 3], s: f64, p: &[f64; 3]) -> [f64; 3] {
+    let [rx, ry, rz] = r;
+    let [x, y, z] = *p;
+    [
+        t[0] + s * (x - rz * y + ry * z),
+        t[1] + s * (rz * x + y - rx * z),
+        t[2] + s * (-ry * x + rx * y + z),
+    ]
+}
+
+fn arcsec(angle: f64) -> f64 {
+    (angle / 3600.).to_radians()
+}
+
+// ----- N O R M A L   E Q U A T I O N S -----------------------------------------------
+
+// Accumulation and solution of the normal equations, AᵀA·x = Aᵀl
+struct Normal {
+    n: Vec<Vec<f64>>,
+    u: Vec<f64>,
+}
+
+impl Normal {
+    fn new(size: usize) -> Self {
+        Normal {
+            n: vec![vec![0.; size]; size],
+            u: vec![0.; size],
+        }
+    }
+
+    // Add the observation `l`, with design matrix row `a`
+    fn add(&mut self, a: &[f64], l: f64) {
+        for (i, ai) in a.iter().enumerate() {
+            for (j, aj) in a.iter().enumerate() {
+                self.n[i][j] += ai * aj;
+            }
+            self.u[i] += ai * l;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting, after scaling the
+    // system to unit diagonal, since the unknowns may differ by many
+    // orders of magnitude (e.g. metres vs. radians)
+    fn solve(mut self) -> Result<Vec<f64>, Error> {
+        let size = self.u.len();
+        let scale: Vec<f64> = (0..size)
+            .map(|i| match self.n[i][i] {
+                d if d > 0. => 1. / d.sqrt(),
+                _ => 1.,
+            })
+            .collect();
+        for i in 0..size {
+            for j in 0..size {
+                self.n[i][j] *= scale[i] * scale[j];
+            }
+            self.u[i] *= scale[i];
+        }
+
+        for k in 0..size {
+            let pivot = (k..size)
+                .max_by(|&a, &b| self.n[a][k].abs().total_cmp(&self.n[b][k].abs()))
+                .unwrap_or(k);
+            if self.n[pivot][k].abs() < 1e-12 {
+                return Err(Error::General(
+                    "Singular normal equations - degenerate point configuration",
+                ));
+            }
+            self.n.swap(k, pivot);
+            self.u.swap(k, pivot);
+            for i in k + 1..size {
+                let factor = self.n[i][k] / self.n[k][k];
+                for j in k..size {
+                    self.n[i][j] -= factor * self.n[k][j];
+                }
+                self.u[i] -= factor * self.u[k];
+            }
+        }
+
+        let mut x = vec![0.; size];
+        for k in (0..size).rev() {
+            let sum: f64 = (k + 1..size).map(|j| self.n[k][j] * x[j]).sum();
+            x[k] = (self.u[k] - sum) / self.n[k][k];
+        }
+        Ok(x.iter().zip(&scale).map(|(x, s)| x * s).collect())
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+
+        // Geocentric coordinates of points spread over Denmark
+        let cart = ctx.op("cart")?;
+        let mut from = Vec::new();
+        for (lat, lon) in [(55., 12.), (57., 10.), (56., 8.), (55., 9.), (54.8, 11.5)] {
+            from.push(Coor4D::geo(lat, lon, 40., 0.));
+        }
+        ctx.apply(cart, Fwd, &mut from)?;
+
+        // 7 parameters: Recover the parameters of a known transformation
+        let definition =
+            "helmert x=-81.07 y=-89.36 z=-115.75 rx=0.485 ry=0.024 rz=0.413 s=-0.54 convention=position_vector";
+        let mut to = from.clone();
+        let helmert = ctx.op(definition)?;
+        ctx.apply(helmert, Fwd, &mut to)?;
+        let e = super::estimate(Model::Helmert7, &from, &to)?;
+        let expected = [-81.07, -89.36, -115.75, 0.485, 0.024, 0.413, -0.54];
+        for (p, e) in e.parameters.iter().zip(expected) {
+            assert!((p - e).abs() < 1e-6, "{p} vs {e}");
+        }
+        assert!(e.max < 1e-6);
+        assert_eq!(e.redundancy, 15 - 7);
+
+        // ...and the operator definition reproduces the transformation
+        let mut check = from.clone();
+        let helmert = ctx.op(&e.definition().unwrap())?;
+        ctx.apply(helmert, Fwd, &mut check)?;
+        for (c, t) in check.iter().zip(&to) {
+            assert!(c.hypot3(t) < 1e-6);
+        }
+
+        // 3 parameters: With a rotation in the data, the residuals grow,
+        // but their sum vanishes
+        let e = super::estimate(Model::Helmert3, &from, &to)?;
+        assert!(e.max > 0.1);
+        assert!(e.sigma0 > 0.1);
+        let sum = e.residuals.iter().fold(Coor3D::origin(), |a, b| a + *b);
+        assert!(sum.dot(sum).sqrt() < 1e-6);
+
+        // 4 parameters and affine: A 2D similarity, with a bit of noise
+        let from = [
+            Coor2D::raw(500_000., 6_100_000.),
+            Coor2D::raw(520_000., 6_100_000.),
+            Coor2D::raw(520_000., 6_130_000.),
+            Coor2D::raw(500_000., 6_130_000.),
+        ];
+        let (s, theta) = (1. + 12e-6, arcsec(3.));
+        let noise = [0.002, -0.002, 0.002, -0.002];
+        let to: Vec<Coor2D> = from
+            .iter()
+            .zip(noise)
+            .map(|(p, n)| {
+                let (sin, cos) = theta.sin_cos();
+                let x = 100. + s * (cos * p[0] - sin * p[1]) + n;
+                let y = -200. + s * (sin * p[0] + cos * p[1]) + n;
+                Coor2D::raw(x, y)
+            })
+            .collect();
+        let e = super::estimate(Model::Helmert4, &from, &to)?;
+        assert!((e.parameters[2] - 3.).abs() < 0.01);
+        assert!((e.parameters[3] - 12.).abs() < 0.1);
+        assert!(e.max < 0.005 && e.max > 0.001);
+        assert_eq!(e.residuals[0][2], 0.);
+        assert_eq!(e.redundancy, 4);
+        let helmert = ctx.op(&e.definition().unwrap())?;
+        let mut check = from.to_vec();
+        ctx.apply(helmert, Fwd, &mut check)?;
+        for (c, p) in check.iter().zip(&from) {
+            let expected = e.transform(&Coor3D::raw(p[0], p[1], 0.));
+            assert!((c[0] - expected[0]).hypot(c[1] - expected[1]) < 1e-6);
+        }
+
+        // The 2D similarity is a special case of the affine transformation
+        let affine = super::estimate(Model::Affine2D, &from, &to)?;
+        assert!(affine.rms <= e.rms);
+        assert!((affine.parameters[1] - s * theta.cos()).abs() < 1e-6);
+        assert!(affine.definition().is_none());
+
+        // Degenerate cases
+        assert!(super::estimate(Model::Helmert7, &from[..2].to_vec(), &to[..2].to_vec()).is_err());
+        assert!(super::estimate(Model::Helmert4, &from, &to[..2].to_vec()).is_err());
+        let collinear = [Coor2D::origin(), Coor2D::raw(1., 1.), Coor2D::raw(2., 2.)];
+        assert!(super::estimate(Model::Affine2D, &collinear, &collinear).is_err());
+        Ok(())
+    }
+}
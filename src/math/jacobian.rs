@@ -59,7 +59,14 @@ impl Jacobian {
     /// most demanding situations it is, however, probably fine to just select
     /// `Ellipsoid::default()`, i.e. GRS80.
     ///
-    /// Mostly based on the PROJ function [pj_deriv](https://github.com/OSGeo/PROJ/blob/master/src/deriv.cpp),
+    /// The derivatives are obtained from
+    /// [`Op::partial_derivatives()`](crate::authoring::Op::partial_derivatives),
+    /// i.e. analytically where `op` provides them, and for pipelines composed
+    /// from the derivatives of the individual steps, so e.g. a datum shift
+    /// preceding the projection is taken into account. Where that fails, the
+    /// derivatives of the full operation are computed numerically, mostly
+    /// based on the PROJ function
+    /// [pj_deriv](https://github.com/OSGeo/PROJ/blob/master/src/deriv.cpp),
     #[rustfmt::skip]
     pub fn new(ctx: &impl Context, op: OpHandle, scale: [f64; 2], swap: [bool; 2], ellps: Ellipsoid, at: Coor2D) -> Result<Jacobian, Error> {
 
@@ -76,17 +83,33 @@ impl Jacobian {
         // are in feet, and scale[1]=201.168 indicates that output is in furlongs
         let linear_scale = scale[1];
 
-        let h = 1e-5 * angular_scale;
-        let d = (4.0 * h * ellps.semimajor_axis()).recip() * linear_scale * angular_scale;
-
-        let mut coo = [Coor2D::origin(); 4];
-
         let (e, n) = if swap[0] {(at[1], at[0])} else {(at[0], at[1])};
 
         // Latitude and longitude in degrees for the return value
         let latitude = n * scale[0];
         let longitude = e * scale[0];
 
+        // Use the partial derivatives, if available. These are given with respect
+        // to the input coordinates, so we must take the swapping into account
+        let point = Coor4D::raw(at[0], at[1], 0., 0.);
+        if let Some(derivatives) = ctx.partial_derivatives(op, Fwd, &point)? {
+            let d = ellps.semimajor_axis().recip() * linear_scale * angular_scale;
+            let (lam, phi) = if swap[0] {(1, 0)} else {(0, 1)};
+            let (e, n) = if swap[1] {(1, 0)} else {(0, 1)};
+            // derivatives[2 * i + j] is the derivative of output j w.r.t. input i
+            let dx_dlam = derivatives[2 * lam + e] * d;
+            let dy_dlam = derivatives[2 * lam + n] * d;
+            let dx_dphi = derivatives[2 * phi + e] * d;
+            let dy_dphi = derivatives[2 * phi + n] * d;
+            return Ok(Jacobian{latitude, longitude, dx_dlam, dy_dlam, dx_dphi, dy_dphi, ellps});
+        }
+
+        // Otherwise, fall back to numerical differentiation
+        let h = 1e-5 * angular_scale;
+        let d = (4.0 * h * ellps.semimajor_axis()).recip() * linear_scale * angular_scale;
+
+        let mut coo = [Coor2D::origin(); 4];
+
         // North-east of POI
         coo[0] = Coor2D::raw(e + h, n + h);
         // South-east of POI
@@ -158,3 +181,216 @@ impl Jacobian {
         f
     }
 }
+
+/// Numerical approximation of the partial derivatives of `op`, in the same
+/// form as [`Op::partial_derivatives()`](crate::authoring::Op::partial_derivatives),
+/// i.e. `[∂x/∂u, ∂y/∂u, ∂x/∂v, ∂y/∂v]` at the point `at`, in the native units
+/// of the operator. The step size is scaled with the magnitude of the input
+/// coordinates, so the same scheme works for both angular and linear input.
+pub(crate) fn numerical_partial_derivatives(
+    op: &crate::authoring::Op,
+    ctx: &dyn Context,
+    direction: Direction,
+    at: &Coor4D,
+) -> [f64; 4] {
+    numerical_partial_derivatives_of_steps(&[op], ctx, direction, at)
+}
+
+/// As [`numerical_partial_derivatives()`], but for the composition of the
+/// operators in `steps`, applied in the order given
+pub(crate) fn numerical_partial_derivatives_of_steps(
+    steps: &[&crate::authoring::Op],
+    ctx: &dyn Context,
+    direction: Direction,
+    at: &Coor4D,
+) -> [f64; 4] {
+    let hu = 1e-5 * at[0].abs().max(1.);
+    let hv = 1e-5 * at[1].abs().max(1.);
+
+    //                  NE         SE          SW           NW
+    let mut coo = [(hu, hv), (hu, -hv), (-hu, -hv), (-hu, hv)].map(|(du, dv)| {
+        let mut c = *at;
+        c[0] += du;
+        c[1] += dv;
+        c
+    });
+    for step in steps {
+        step.apply(ctx, &mut coo, direction);
+    }
+
+    let du = (4. * hu).recip();
+    let dv = (4. * hv).recip();
+    [
+        (coo[0][0] + coo[1][0] - coo[2][0] - coo[3][0]) * du,
+        (coo[0][1] + coo[1][1] - coo[2][1] - coo[3][1]) * du,
+        (coo[0][0] - coo[1][0] - coo[2][0] + coo[3][0]) * dv,
+        (coo[0][1] - coo[1][1] - coo[2][1] + coo[3][1]) * dv,
+    ]
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authoring::*;
+
+    // Analytical and numerical derivatives should agree. Appending the
+    // `push v_3 | pop v_3` no-operation, which defeats the chain rule,
+    // forces the use of numerical differentiation of the full pipeline
+    #[test]
+    fn analytical_vs_numerical() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let ellps = Ellipsoid::default();
+        let cph = Coor2D::raw(55., 12.);
+
+        for definition in [
+            "geo:in | merc",
+            "geo:in | merc lat_ts=56",
+            "geo:in | webmerc",
+            "geo:in | lcc lat_1=33 lat_2=45 lon_0=10",
+            "geo:in | merc | merc inv | lcc lat_1=-33 lat_2=-45 lon_0=10 | neu:out",
+        ] {
+            let op = ctx.op(definition)?;
+            assert!(ctx
+                .partial_derivatives(op, Fwd, &Coor4D::raw(55., 12., 0., 0.))?
+                .is_some());
+            let numerical = ctx.op(&(definition.to_string() + " | push v_3 | pop v_3"))?;
+            assert!(ctx
+                .composed_partial_derivatives(numerical, Fwd, &Coor4D::origin())?
+                .is_none());
+
+            let swap = [true, definition.ends_with("neu:out")];
+            let a = Jacobian::new(&ctx, op, [1., 1.], swap, ellps, cph)?;
+            let n = Jacobian::new(&ctx, numerical, [1., 1.], swap, ellps, cph)?;
+            assert!((a.dx_dlam - n.dx_dlam).abs() < 1e-9);
+            assert!((a.dy_dlam - n.dy_dlam).abs() < 1e-9);
+            assert!((a.dx_dphi - n.dx_dphi).abs() < 1e-9);
+            assert!((a.dy_dphi - n.dy_dphi).abs() < 1e-9);
+
+            let (a, n) = (a.factors(), n.factors());
+            assert!((a.meridional_scale - n.meridional_scale).abs() < 1e-8);
+            assert!((a.parallel_scale - n.parallel_scale).abs() < 1e-8);
+            assert!((a.areal_scale - n.areal_scale).abs() < 1e-8);
+            assert!((a.meridian_convergence - n.meridian_convergence).abs() < 1e-7);
+        }
+
+        // Mercator is conformal, with no meridian convergence
+        let op = ctx.op("geo:in | merc")?;
+        let f = Jacobian::new(&ctx, op, [1., 1.], [true, false], ellps, cph)?.factors();
+        let (s, c) = 55_f64.to_radians().sin_cos();
+        let k = (1. - ellps.eccentricity_squared() * s * s).sqrt() / c;
+        assert!((f.meridional_scale - k).abs() < 1e-12);
+        assert!((f.parallel_scale - k).abs() < 1e-12);
+        assert!(f.angular_distortion.abs() < 1e-12);
+        assert!(f.meridian_convergence.abs() < 1e-12);
+
+        // The derivatives of the inverse are the inverse of the derivatives
+        let op = ctx.op("lcc lat_1=33 lat_2=45 lon_0=10")?;
+        let mut p = [Coor4D::geo(55., 12., 0., 0.)];
+        let fwd = ctx.partial_derivatives(op, Fwd, &p[0])?.unwrap();
+        ctx.apply(op, Fwd, &mut p)?;
+        let inv = ctx.partial_derivatives(op, Inv, &p[0])?.unwrap();
+        let product = [
+            fwd[0] * inv[0] + fwd[2] * inv[1],
+            fwd[1] * inv[0] + fwd[3] * inv[1],
+            fwd[0] * inv[2] + fwd[2] * inv[3],
+            fwd[1] * inv[2] + fwd[3] * inv[3],
+        ];
+        for (p, i) in product.iter().zip([1., 0., 0., 1.]) {
+            assert!((p - i).abs() < 1e-12);
+        }
+
+        // Stack manipulations defeat the chain rule, so the full pipeline
+        // is differentiated numerically
+        let op = ctx.op("push v_3 | merc | pop v_3")?;
+        assert!(ctx.composed_partial_derivatives(op, Fwd, &p[0])?.is_none());
+        let merc = ctx.op("merc")?;
+        let at = Coor4D::geo(55., 12., 0., 0.);
+        let n = ctx.partial_derivatives(op, Fwd, &at)?.unwrap();
+        let a = ctx.partial_derivatives(merc, Fwd, &at)?.unwrap();
+        for (n, a) in n.iter().zip(a) {
+            assert!((n - a).abs() < 1e-6 * a.abs().max(1.));
+        }
+
+        // Operators without analytical derivatives are differentiated numerically
+        let op = ctx.op("tmerc lon_0=9")?;
+        let d = ctx.partial_derivatives(op, Fwd, &at)?.unwrap();
+        let f = Jacobian::new(
+            &ctx,
+            op,
+            [1f64.to_degrees(), 1.],
+            [false, false],
+            ellps,
+            Coor2D::raw(at[0], at[1]),
+        )?;
+        assert!((d[0] / ellps.semimajor_axis() - f.dx_dlam).abs() < 1e-12);
+        let f = f.factors();
+        assert!((f.meridional_scale - f.parallel_scale).abs() < 1e-8);
+
+        // ...but for invalid input, no derivatives are available
+        assert!(ctx.partial_derivatives(op, Fwd, &Coor4D::nan())?.is_none());
+        assert!(ctx
+            .partial_derivatives(merc, Fwd, &Coor4D::nan())?
+            .is_none());
+        Ok(())
+    }
+    // Steps without analytical derivatives are differentiated numerically,
+    // while the remaining steps are chained analytically
+    #[test]
+    fn composed() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let intl = Ellipsoid::named("intl")?;
+        let cph = Coor2D::raw(55., 12.);
+
+        // A projection preceded by a datum shift
+        let op = ctx.op(
+            "geo:in | cart | helmert x=-87 y=-96 z=-120 | cart inv ellps=intl | utm zone=32 ellps=intl",
+        )?;
+        let at = Coor4D::raw(55., 12., 0., 0.);
+        let composed = ctx.composed_partial_derivatives(op, Fwd, &at)?.unwrap();
+        assert_eq!(ctx.partial_derivatives(op, Fwd, &at)?, Some(composed));
+
+        // ... should agree with numerical differentiation of the full pipeline.
+        // Since the intermediate cartesian coordinates are 3D, this requires
+        // 4D operands, so we cannot use the fallback of `Jacobian::new()`
+        let h = 1e-5;
+        let mut coo = [(h, h), (h, -h), (-h, -h), (-h, h)]
+            .map(|(du, dv)| Coor4D::raw(at[0] + du, at[1] + dv, 0., 0.));
+        ctx.apply(op, Fwd, &mut coo)?;
+        let d = (4. * h).recip();
+        let numerical = [
+            (coo[0][0] + coo[1][0] - coo[2][0] - coo[3][0]) * d,
+            (coo[0][1] + coo[1][1] - coo[2][1] - coo[3][1]) * d,
+            (coo[0][0] - coo[1][0] - coo[2][0] + coo[3][0]) * d,
+            (coo[0][1] - coo[1][1] - coo[2][1] + coo[3][1]) * d,
+        ];
+        for (c, n) in composed.iter().zip(numerical) {
+            assert!((c - n).abs() < 1e-8 * n.abs().max(1.));
+        }
+
+        // The datum shift changes the scale factors by some ppm, at most
+        let f = Jacobian::new(&ctx, op, [1., 1.], [true, false], intl, cph)?.factors();
+        let utm = ctx.op("geo:in | utm zone=32 ellps=intl")?;
+        let u = Jacobian::new(&ctx, utm, [1., 1.], [true, false], intl, cph)?.factors();
+        assert!((f.meridional_scale - u.meridional_scale).abs() < 1e-4);
+        assert!((f.parallel_scale - u.parallel_scale).abs() < 1e-4);
+        assert!((f.meridian_convergence - u.meridian_convergence).abs() < 1e-3);
+
+        // Inverse and forward derivatives of the pipeline are mutually inverse
+        let mut p = [at];
+        ctx.apply(op, Fwd, &mut p)?;
+        let inv = ctx.composed_partial_derivatives(op, Inv, &p[0])?.unwrap();
+        let fwd = composed;
+        let product = [
+            fwd[0] * inv[0] + fwd[2] * inv[1],
+            fwd[1] * inv[0] + fwd[3] * inv[1],
+            fwd[0] * inv[2] + fwd[2] * inv[3],
+            fwd[1] * inv[2] + fwd[3] * inv[3],
+        ];
+        for (p, i) in product.iter().zip([1., 0., 0., 1.]) {
+            assert!((p - i).abs() < 1e-8);
+        }
+        Ok(())
+    }
+}
@@ -12,6 +12,10 @@ pub mod angular;
 ///  of the geometrical properties of map projections.
 pub mod jacobian;
 
+/// Least squares estimation of transformation parameters
+/// from point correspondences.
+pub mod estimate;
+
 /// Fourier- and Taylor series
 pub mod series;
 pub use series::fourier;
@@ -20,3 +24,10 @@ pub use series::taylor;
 pub use series::taylor::fourier_coefficients;
 pub use series::FourierCoefficients;
 pub use series::PolynomialCoefficients;
+pub use series::TRANSVERSE_MERCATOR;
+
+// Clenshaw summation, under the names used in the literature
+pub use series::fourier::complex_cos as clenshaw_complex_cos;
+pub use series::fourier::complex_sin as clenshaw_complex;
+pub use series::fourier::cos as clenshaw_cos;
+pub use series::fourier::sin as clenshaw_sin;
@@ -1,3 +1,24 @@
+//! Taylor series evaluation, and Fourier series summation by Clenshaw's
+//! recurrence. Available through the [`authoring`](crate::authoring) prelude
+//! for reuse in user-defined operators, e.g. for a Transverse Mercator
+//! variant, which would compute the conformal latitude and the TM Fourier
+//! series just like the builtin `tmerc`:
+//!
+//! ```
+//! use geodesy::authoring::*;
+//! let ellps = Ellipsoid::named("GRS80")?;
+//! let n = ellps.third_flattening();
+//! let tm = fourier_coefficients(n, &TRANSVERSE_MERCATOR);
+//! let conformal = ellps.coefficients_for_conformal_latitude_computations();
+//!
+//! // Conformal latitude and its TM-transformed counterpart at 55°N
+//! let chi = ellps.latitude_geographic_to_conformal(55_f64.to_radians(), &conformal);
+//! let [re, im] = clenshaw_complex([2. * chi, 0.], &tm.fwd);
+//! assert_eq!(im, 0.);
+//! assert!((re - clenshaw_sin(2. * chi, &tm.fwd)).abs() < 1e-15);
+//! # Ok::<(), Error>(())
+//! ```
+
 /// The order of the Fourier series used to compute e.g. auxiliary latitudes
 pub const POLYNOMIAL_ORDER: usize = 6;
 
@@ -17,6 +38,32 @@ pub struct FourierCoefficients {
     pub etc: [f64; 2],
 }
 
+/// Polynomial coefficients for the Fourier series of the Transverse Mercator
+/// projection. Evaluate with [`fourier_coefficients`](taylor::fourier_coefficients),
+/// using the third flattening, *n*, of the ellipsoid as argument
+#[rustfmt::skip]
+pub const TRANSVERSE_MERCATOR: PolynomialCoefficients = PolynomialCoefficients {
+    // Geodetic to TM. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
+    fwd: [
+        [1./2.,   -2./3.,   5./16.,   41./180.,   -127./288.0 ,   7891./37800.],
+        [0., 13./48.,   -3./5.,   557./1440.,   281./630.,   -1983433./1935360.],
+        [0., 0., 61./240.,  -103./140.,   15061./26880.,   167603./181440.],
+        [0., 0., 0., 49561./161280.,   -179./168.,   6601661./7257600.],
+        [0., 0., 0., 0., 34729./80640.,   -3418889./1995840.],
+        [0., 0., 0., 0., 0., 212378941./319334400.]
+    ],
+
+    // TM to Geodetic. [Engsager & Poder, 2007](crate::Bibliography::Eng07)
+    inv: [
+        [-1./2.,   2./3.,   -37./96.,   1./360.,   81./512.,   -96199./604800.],
+        [0., -1./48.,   -1./15.,   437./1440.,   -46./105.,   1118711./3870720.],
+        [0., 0., -17./480.,   37./840.,   209./4480.,   -5569./90720.],
+        [0., 0., 0., -4397./161280.,   11./504.,   830251./7257600.],
+        [0., 0., 0., 0., -4583./161280.,   108847./3991680.],
+        [0., 0., 0., 0., 0., -20648693./638668800.]
+    ]
+};
+
 // --- Taylor series polynomium evaluation ----
 
 pub mod taylor {
@@ -69,7 +116,7 @@ pub mod fourier {
         sin_arg * c0
     }
 
-    // Evaluate Σ cᵢ cos( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation
+    /// Evaluate Σ cᵢ cos( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation
     pub fn cos(arg: f64, coefficients: &[f64]) -> f64 {
         let cos_arg = arg.cos();
         let x = 2.0 * cos_arg;
@@ -118,12 +165,48 @@ pub mod fourier {
         [r * hr - i * hi, r * hi + i * hr]
     }
 
+    /// Evaluate Σ cᵢ Cos( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation.
+    /// i.e. a series of complex cosines with real coefficients
+    #[allow(unused_assignments)] // For symmetric initialization of hr2, hi2
+    pub fn complex_cos(arg: [f64; 2], coefficients: &[f64]) -> [f64; 2] {
+        // Prepare the trigonometric factors
+        let (sin_r, cos_r) = arg[0].sin_cos();
+        let sinh_i = arg[1].sinh();
+        let cosh_i = arg[1].cosh();
+        let r = 2. * cos_r * cosh_i;
+        let i = -2. * sin_r * sinh_i;
+        let mut coefficients = coefficients.iter().rev();
+
+        // Handle zero length series by conventionally assigning them the sum of 0
+        let Some(c) = coefficients.next() else {
+            return [0.; 2];
+        };
+
+        // Initialize the recurrence coefficients
+        let (mut hr2, mut hr1, mut hr) = (0., 0., *c);
+        let (mut hi2, mut hi1, mut hi) = (0., 0., 0.);
+
+        for c in coefficients {
+            // Rotate the recurrence coefficients
+            (hr2, hi2, hr1, hi1) = (hr1, hi1, hr, hi);
+
+            // Update the recurrent sum
+            hr = -hr2 + r * hr1 - i * hi1 + c;
+            hi = -hi2 + i * hr1 + r * hi1;
+        }
+
+        // Finalize the sum: Cos(arg) · h₁ - h₂
+        let r = cos_r * cosh_i;
+        let i = -sin_r * sinh_i;
+        [r * hr - i * hi - hr1, r * hi + i * hr - hi1]
+    }
+
     // --- Clenshaw versions optimized for Transverse Mercator ---
 
     /// Evaluate Σ cᵢ sin( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation
     ///
-    /// Functionally identical to [clenshaw_sin](crate::math::series::fourier::sin), but
-    /// takes advantage trigonometric factors, which are conveniently computed ahead-of-call in
+    /// Functionally identical to [clenshaw_sin](crate::math::clenshaw_sin), but
+    /// takes advantage of trigonometric factors, which are conveniently computed ahead-of-call in
     /// the Transverse Mercator code, tmerc. Since tmerc is so widely used, this optimization
     /// makes good sense, despite the more clumsy call signature. Also, for the same reason
     /// we assert that, despite that compiler heuristics may beg to differ, this function should
@@ -145,7 +228,7 @@ pub mod fourier {
     /// Evaluate Σ cᵢ Sin( i · arg ), for i ∈ {order, ... , 1}, using Clenshaw summation.
     /// i.e. a series of complex sines with real coefficients.
     ///
-    /// Functionally identical to [clenshaw_complex_sin](crate::math::series::fourier::complex_sin), but
+    /// Functionally identical to [clenshaw_complex](crate::math::clenshaw_complex), but
     /// takes advantage of some trigonometric and hyperbolic factors, which are conveniently
     /// computed ahead-of-call in the Transverse Mercator code, tmerc. Since tmerc is so widely
     /// used, this optimization makes good sense, despite the more clumsy call signature. Also,
@@ -256,8 +339,22 @@ mod tests {
         assert!((sum[1] - i).abs() < 1e-14);
 
         // Canonical result for complex cosine clenshaw, from Poder/Engsager implementation
-        // let r = -461.338884918028953;
-        // let i = -246.855278649982154;
+        let r = -461.338_884_918_028_95;
+        let i = -246.855_278_649_982_15;
+        let sum = fourier::complex_cos(arg, &coefficients);
+        assert!((sum[0] - r).abs() < 1e-12);
+        assert!((sum[1] - i).abs() < 1e-12);
+        assert_eq!(fourier::complex_cos(arg, &[]), [0.; 2]);
+
+        // The public aliases are the same functions
+        assert_eq!(
+            clenshaw_sin(x, &[1., 2., 3.]),
+            fourier::sin(x, &[1., 2., 3.])
+        );
+        assert_eq!(
+            clenshaw_complex(arg, &coefficients),
+            fourier::complex_sin(arg, &coefficients)
+        );
 
         Ok(())
     }
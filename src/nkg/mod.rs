@@ -0,0 +1,105 @@
+//! An embedded register of the NKG transformations between ITRF2014 and the
+//! national realizations of ETRS89 in the Nordic countries, cf.
+//! [Knudsen et al. (2019)](crate::Bibliography::Knu19).
+//!
+//! The register is read from `nkg.md` at compile time, and maps identifiers
+//! of the form `nkg:itrf2014-sweref99` to the macro definitions given in
+//! the `geodesy:` blocks of the register, in the same format as the
+//! resource registers of the [Plain](crate::context::plain::Plain) context.
+//!
+//! The register is consulted by [`Op::op()`](crate::op::Op::op) *after* the
+//! resources provided by the context, so a user provided `nkg` register
+//! takes precedence over the builtin one. The macros depend on the NKG
+//! deformation model grid, `eur_nkg_nkgrf17vel.deformation`, which must
+//! be provided by the context.
+use crate::Error;
+
+const REGISTER: &str = include_str!("nkg.md");
+
+/// The names of all items in the embedded register
+pub fn names() -> Vec<&'static str> {
+    REGISTER
+        .split("```geodesy:")
+        .skip(1)
+        .filter_map(|item| item.lines().next())
+        .map(str::trim)
+        .collect()
+}
+
+/// Look up a resource name of the form `nkg:name` in the embedded register
+pub(crate) fn resource(name: &str) -> Result<String, Error> {
+    let Some(suffix) = name.strip_prefix("nkg:") else {
+        return Err(Error::BadParam(
+            "needing nkg:name format".to_string(),
+            name.to_string(),
+        ));
+    };
+    let tag = format!("```geodesy:{suffix}\n");
+    let Some(start) = REGISTER.find(&tag).map(|start| start + tag.len()) else {
+        return Err(Error::NotFound(
+            name.to_string(),
+            ": NKG register".to_string(),
+        ));
+    };
+    let length = REGISTER[start..]
+        .find("```")
+        .unwrap_or(REGISTER.len() - start);
+    Ok(REGISTER[start..start + length].trim().to_string())
+}
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn register() -> Result<(), Error> {
+        let names = names();
+        assert!(names.contains(&"itrf2014-sweref99"));
+        assert!(names.contains(&"itrf2014-etrs89dk"));
+        for name in names {
+            assert!(resource(&format!("nkg:{name}"))?.starts_with('|'));
+        }
+        assert!(matches!(resource("nkg:foo"), Err(Error::NotFound(_, _))));
+        assert!(matches!(resource("foo:bar"), Err(Error::BadParam(_, _))));
+        Ok(())
+    }
+
+    #[test]
+    fn transformations() -> Result<(), Error> {
+        let mut ctx = Plain::default();
+
+        // The sample register in `geodesy/resources/nkg.md` holds the
+        // classic, single macro, versions of the transformations, which
+        // take precedence over the embedded ones. So we register the
+        // embedded ones under other names, for comparison
+        for (name, point) in [
+            ("itrf2014-sweref99", Coor4D::raw(59., 18., 0., 2020.)),
+            ("itrf2014-etrs89dk", Coor4D::raw(55., 12., 0., 2020.)),
+        ] {
+            ctx.register_resource("embedded:nkg", &resource(&format!("nkg:{name}"))?);
+            let embedded = ctx.op("embedded:nkg")?;
+            let sample = ctx.op(&format!("nkg:{name}"))?;
+
+            let mut data = [point];
+            ctx.apply(embedded, Fwd, &mut data)?;
+            let mut expected = [point];
+            ctx.apply(sample, Fwd, &mut expected)?;
+            assert!((data[0] - expected[0]).0.iter().all(|d| d.abs() < 1e-12));
+
+            // At epoch 2020, ITRF2014 and the national realizations differ
+            // by some decimeters, due to the plate motion since realization
+            let from = Coor4D::geo(point[0], point[1], 0., 0.);
+            let to = Coor4D::geo(data[0][0], data[0][1], 0., 0.);
+            let d = Ellipsoid::named("GRS80")?.distance(&from, &to);
+            assert!(d > 0.1 && d < 1.0);
+
+            ctx.apply(embedded, Inv, &mut data)?;
+            let d = data[0] - point;
+            assert!(d[0].abs() < 1e-9 && d[1].abs() < 1e-9 && d[2].abs() < 1e-6);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,423 @@
+//! Type safe, programmatic construction of operator definitions.
+//!
+//! Gluing definitions together from strings is easy to get wrong: A misspelled
+//! parameter name is silently ignored at instantiation time. The builders below
+//! provide one method per parameter of each builtin operator, so misspellings
+//! are caught by the compiler instead. The result renders to the usual,
+//! normalized, definition string, which is what [Context::op] expects:
+//!
+//! ```rust
+//! # use geodesy::prelude::*;
+//! use geodesy::builder::*;
+//! # fn main() -> Result<(), Error> {
+//! let pipeline = Pipeline::new()
+//!     .step("geo:in")
+//!     .step(Tmerc::new().lat_0(49.).lon_0(-2.).k_0(0.9996012717).ellps("airy"))
+//!     .step("neu:out");
+//! assert_eq!(
+//!     pipeline.definition(),
+//!     "geo:in|tmerc lat_0=49 lon_0=-2 k_0=0.9996012717 ellps=airy|neu:out"
+//! );
+//!
+//! let mut ctx = Minimal::new();
+//! let op = ctx.op(&pipeline.definition())?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Macros, user defined operators, and anything else not covered by the
+//! builders, can be given as plain text steps, since `Step` is implemented
+//! for `&str` and `String`.
+use crate::token::Tokenize;
+
+/// Anything that can render itself as (part of) an operator definition
+pub trait Step {
+    /// The definition, in normalized form
+    fn definition(&self) -> String;
+}
+
+impl Step for &str {
+    fn definition(&self) -> String {
+        self.normalize()
+    }
+}
+
+impl Step for String {
+    fn definition(&self) -> String {
+        self.normalize()
+    }
+}
+
+// ----- P I P E L I N E ---------------------------------------------------------------
+
+/// A sequence of steps, rendering to a pipeline definition
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pipeline {
+    steps: Vec<String>,
+}
+
+impl Pipeline {
+    #[must_use]
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Append a step to the pipeline
+    #[must_use]
+    pub fn step(mut self, step: impl Step) -> Pipeline {
+        let definition = step.definition();
+        if !definition.is_empty() {
+            self.steps.push(definition);
+        }
+        self
+    }
+
+    /// The number of steps in the pipeline
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl Step for Pipeline {
+    fn definition(&self) -> String {
+        self.steps.join("|")
+    }
+}
+
+impl core::fmt::Display for Pipeline {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.definition())
+    }
+}
+
+// ----- O P E R A T O R   S T E P S ---------------------------------------------------
+
+// The key of a parameter is the name of its setter method, unless explicitly
+// given (for the few cases where the key is not a valid Rust identifier)
+macro_rules! key {
+    ($method:ident) => {
+        stringify!($method)
+    };
+    ($method:ident, $key:literal) => {
+        $key
+    };
+}
+
+// Generate a builder for a builtin operator, with one setter method for each
+// element of its gamut. `omit_fwd` and `omit_inv` are valid for all operators,
+// while `inv` must be given explicitly in the list of flags, for invertible
+// operators.
+macro_rules! step_builder {
+    (
+        $builder:ident, $name:literal
+        $(, flag: [$($flag:ident $(= $flag_key:literal)?),* $(,)?])?
+        $(, natural: [$($natural:ident),* $(,)?])?
+        $(, real: [$($real:ident),* $(,)?])?
+        $(, series: [$($series:ident),* $(,)?])?
+        $(, text: [$($text:ident),* $(,)?])?
+        $(, texts: [$($texts:ident),* $(,)?])?
+    ) => {
+        #[doc = concat!("Builder for steps using the `", $name, "` operator")]
+        #[derive(Debug, Clone, Default, PartialEq)]
+        pub struct $builder {
+            args: Vec<(&'static str, String)>,
+        }
+
+        impl $builder {
+            #[must_use]
+            pub fn new() -> $builder {
+                $builder::default()
+            }
+
+            // Set (or reset) the value of a parameter, maintaining the order
+            // in which the parameters were originally given
+            fn set(mut self, key: &'static str, value: String) -> $builder {
+                if let Some(arg) = self.args.iter_mut().find(|arg| arg.0 == key) {
+                    arg.1 = value;
+                } else {
+                    self.args.push((key, value));
+                }
+                self
+            }
+
+            /// Skip this step when running the pipeline in the forward direction
+            #[must_use]
+            pub fn omit_fwd(self) -> $builder {
+                self.set("omit_fwd", String::new())
+            }
+
+            /// Skip this step when running the pipeline in the inverse direction
+            #[must_use]
+            pub fn omit_inv(self) -> $builder {
+                self.set("omit_inv", String::new())
+            }
+
+            $($(
+                #[must_use]
+                pub fn $flag(self) -> $builder {
+                    self.set(key!($flag $(, $flag_key)?), String::new())
+                }
+            )*)?
+
+            $($(
+                #[must_use]
+                pub fn $natural(self, value: usize) -> $builder {
+                    self.set(stringify!($natural), value.to_string())
+                }
+            )*)?
+
+            $($(
+                #[must_use]
+                pub fn $real(self, value: f64) -> $builder {
+                    self.set(stringify!($real), value.to_string())
+                }
+            )*)?
+
+            $($(
+                #[must_use]
+                pub fn $series<T: Into<f64> + Copy>(self, values: &[T]) -> $builder {
+                    let values: Vec<String> =
+                        values.iter().map(|v| (*v).into().to_string()).collect();
+                    self.set(stringify!($series), values.join(","))
+                }
+            )*)?
+
+            $($(
+                #[must_use]
+                pub fn $text(self, value: &str) -> $builder {
+                    self.set(stringify!($text), value.to_string())
+                }
+            )*)?
+
+            $($(
+                #[must_use]
+                pub fn $texts(self, values: &[&str]) -> $builder {
+                    self.set(stringify!($texts), values.join(","))
+                }
+            )*)?
+        }
+
+        // The name of the operator, and the key and kind of each parameter
+        // covered by the builder, for checking against the operator gamut
+        #[cfg(test)]
+        impl $builder {
+            const NAME: &'static str = $name;
+            const KEYS: &'static [(&'static str, &'static str)] = &[
+                $($((key!($flag $(, $flag_key)?), "flag"),)*)?
+                $($((stringify!($natural), "natural"),)*)?
+                $($((stringify!($real), "real"),)*)?
+                $($((stringify!($series), "series"),)*)?
+                $($((stringify!($text), "text"),)*)?
+                $($((stringify!($texts), "texts"),)*)?
+            ];
+        }
+
+        impl Step for $builder {
+            fn definition(&self) -> String {
+                let mut definition = String::from($name);
+                for (key, value) in &self.args {
+                    definition += " ";
+                    definition += key;
+                    if !value.is_empty() {
+                        definition += "=";
+                        definition += value;
+                    }
+                }
+                definition.normalize()
+            }
+        }
+    };
+}
+
+step_builder!(Adapt, "adapt", flag: [inv], text: [from, to]);
+step_builder!(Addone, "addone", flag: [inv]);
+step_builder!(Axisswap, "axisswap", flag: [inv], series: [order]);
+step_builder!(Btmerc, "btmerc", flag: [inv],
+    real: [lat_0, lon_0, x_0, y_0, k_0], text: [ellps]);
+step_builder!(Butm, "butm", flag: [inv, south], natural: [zone], text: [ellps]);
+step_builder!(Cart, "cart", flag: [inv], text: [ellps]);
+step_builder!(Clamp, "clamp", flag: [inv], series: [bbox, x, y, z, t]);
+step_builder!(Curvature, "curvature",
+    flag: [prime, meridian, gaussian, mean, azimuthal, gaussian_curvature, mean_curvature],
+    text: [ellps]);
+step_builder!(Deflection, "deflection", natural: [cells], text: [units, ellps], texts: [grids]);
+step_builder!(Deformation, "deformation", flag: [inv, raw],
+    real: [padding, dt, t_epoch], text: [ellps], texts: [grids]);
+step_builder!(Dm, "dm", flag: [inv]);
+step_builder!(Dms, "dms", flag: [inv]);
+step_builder!(Epoch, "epoch", flag: [inv], real: [t_obs, dt], text: [from, to]);
+step_builder!(Geodesic, "geodesic", flag: [inv, reversible, vincenty], text: [ellps]);
+// Named `GravityStep` to avoid clashing with the `Gravity` trait from the prelude
+step_builder!(GravityStep, "gravity",
+    flag: [cassinis, jeffreys, grs67, grs80, welmec, zero_height = "zero-height"],
+    text: [ellps]);
+step_builder!(Gridshift, "gridshift", flag: [inv], natural: [maxiter],
+    real: [padding, tol], text: [ellps], texts: [grids]);
+step_builder!(Helmert, "helmert", flag: [inv, exact],
+    real: [x, y, z, dx, dy, dz, rx, ry, rz, drx, dry, drz, scale, s, scale_trend, ds, t_epoch, t_obs],
+    series: [translation, velocity, rotation, angular_velocity, matrix],
+    text: [convention]);
+step_builder!(Laea, "laea", flag: [inv], real: [lat_0, lon_0, x_0, y_0], text: [ellps]);
+step_builder!(Latitude, "latitude",
+    flag: [inv, derivative, geocentric, reduced, parametric, conformal, authalic, rectifying, isometric],
+    text: [from, to, ellps]);
+step_builder!(Lcc, "lcc", flag: [inv],
+    real: [k_ellps, lat_0, lat_1, lat_2, lon_0, k_0, x_0, y_0], text: [ellps, variant]);
+step_builder!(Merc, "merc", flag: [inv, spherical],
+    real: [lat_0, lon_0, x_0, y_0, k_0, lat_ts], text: [ellps, variant]);
+step_builder!(Molodensky, "molodensky", flag: [inv, abridged, keep_height],
+    real: [h_fixed, dx, dy, dz, da, df], text: [ellps, ellps_0, ellps_1]);
+step_builder!(Nadcon5, "nadcon5", flag: [inv], text: [region, date], texts: [chain]);
+step_builder!(Noop, "noop");
+step_builder!(Omerc, "omerc", flag: [inv, variant, no_uoff, no_off, laborde],
+    real: [latc, lonc, lat_0, alpha, gamma_c, gamma, x_0, y_0, k_0], text: [ellps]);
+step_builder!(Permtide, "permtide", flag: [inv], real: [k], text: [ellps, from, to]);
+step_builder!(Pop, "pop", flag: [v_1, v_2, v_3, v_4]);
+step_builder!(Primem, "primem", flag: [inv], text: [pm]);
+step_builder!(Push, "push", flag: [v_1, v_2, v_3, v_4]);
+step_builder!(Rhumb, "rhumb", flag: [inv, reversible], text: [ellps]);
+step_builder!(Round, "round", flag: [inv], series: [decimals, quantum]);
+step_builder!(Select, "select", flag: [inv], series: [bbox, x, y, z, t]);
+step_builder!(Set, "set", flag: [inv, missing], real: [x, y, z, t]);
+step_builder!(Somerc, "somerc", flag: [inv],
+    real: [lat_0, lon_0, x_0, y_0, k_0, alpha, gamma_c], text: [ellps]);
+step_builder!(Stack, "stack", flag: [swap, drop],
+    series: [push, pop, roll, unroll, flip, dim], texts: [store, recall]);
+step_builder!(Tinshift, "tinshift", flag: [inv], text: [file]);
+step_builder!(Tissot, "tissot", flag: [inv, convergence]);
+step_builder!(Tmerc, "tmerc", flag: [inv],
+    real: [lat_0, lon_0, x_0, y_0, k_0], text: [ellps, engine, accuracy]);
+step_builder!(Trace, "trace", flag: [inv], natural: [n], text: [unit, label]);
+step_builder!(Unitconvert, "unitconvert", flag: [inv],
+    text: [xy_in, xy_out, z_in, z_out]);
+step_builder!(Utm, "utm", flag: [inv, south], natural: [zone], text: [ellps, engine, accuracy]);
+step_builder!(VerticalDatum, "vertical_datum", flag: [inv, tag], texts: [grids, datums]);
+step_builder!(Webmerc, "webmerc", flag: [inv], text: [ellps, domain]);
+
+// ----- T E S T S ------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authoring::*;
+    use float_eq::assert_float_eq;
+
+    #[test]
+    fn rendering() -> Result<(), Error> {
+        // Parameters are rendered in the order given, and flags without values
+        let step = Utm::new().zone(32).south().inv();
+        assert_eq!(step.definition(), "utm zone=32 south inv");
+
+        // Resetting a parameter does not change its position
+        let step = Tmerc::new().lon_0(9.).k_0(0.9996).lon_0(15.);
+        assert_eq!(step.definition(), "tmerc lon_0=15 k_0=0.9996");
+
+        // Series and lists are comma separated
+        let step = Axisswap::new().order(&[2, -1]);
+        assert_eq!(step.definition(), "axisswap order=2,-1");
+        let step = Gridshift::new().grids(&["@foo.gsb", "bar.gsb"]);
+        assert_eq!(step.definition(), "gridshift grids=@foo.gsb,bar.gsb");
+
+        // Parameter keys which are not valid Rust identifiers
+        let step = GravityStep::new().grs80().zero_height();
+        assert_eq!(step.definition(), "gravity grs80 zero-height");
+
+        // Pipelines, including text steps, and empty steps
+        let pipeline = Pipeline::new()
+            .step("geo:in  ")
+            .step("")
+            .step(Cart::new().ellps("intl"))
+            .step(Helmert::new().x(-87.).y(-96.).z(-120.))
+            .step(Cart::new().ellps("GRS80").inv())
+            .step(String::from("geo:out"));
+        assert_eq!(pipeline.len(), 5);
+        assert_eq!(
+            pipeline.to_string(),
+            "geo:in|cart ellps=intl|helmert x=-87 y=-96 z=-120|cart ellps=GRS80 inv|geo:out"
+        );
+
+        // A pipeline is a step in its own right
+        let outer = Pipeline::new().step(Addone::new()).step(pipeline);
+        assert_eq!(outer.len(), 2);
+        assert!(outer.definition().starts_with("addone|geo:in|cart"));
+        assert!(Pipeline::new().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn instantiation() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        let built = Pipeline::new()
+            .step("geo:in")
+            .step(Utm::new().zone(32))
+            .step(Adapt::new().to("neuf"));
+        let by_hand = ctx.op("geo:in | utm zone=32 | neu:out")?;
+        let built = ctx.op(&built.definition())?;
+
+        let mut data = crate::test_data::coor2d();
+        let mut expected = data;
+        ctx.apply(built, Fwd, &mut data)?;
+        ctx.apply(by_hand, Fwd, &mut expected)?;
+        assert_float_eq!(data[0].0, expected[0].0, abs_all <= 1e-9);
+        assert_float_eq!(data[1].0, expected[1].0, abs_all <= 1e-9);
+        Ok(())
+    }
+
+    // Each builder must cover the full gamut of its operator, with parameters
+    // of the right kind, and each builtin operator must have a builder
+    #[test]
+    fn gamut() -> Result<(), Error> {
+        macro_rules! builders {
+            ($($builder:ident),* $(,)?) => {
+                [$(($builder::NAME, $builder::KEYS)),*]
+            };
+        }
+        #[rustfmt::skip]
+        let builders = builders![
+            Adapt, Addone, Axisswap, Btmerc, Butm, Cart, Clamp, Curvature,
+            Deflection, Deformation, Dm, Dms, Epoch, Geodesic, GravityStep,
+            Gridshift, Helmert, Laea, Latitude, Lcc, Merc, Molodensky, Nadcon5,
+            Noop, Omerc, Permtide, Pop, Primem, Push, Rhumb, Round, Select, Set,
+            Somerc, Stack, Tinshift, Tissot, Tmerc, Trace, Unitconvert, Utm,
+            VerticalDatum, Webmerc,
+        ];
+
+        for (name, keys) in builders {
+            let gamut: Vec<(&str, &str)> = builtin_operator(name)?
+                .gamut
+                .iter()
+                .map(|parameter| match *parameter {
+                    OpParameter::Flag { key } => (key, "flag"),
+                    OpParameter::Natural { key, .. } => (key, "natural"),
+                    OpParameter::Integer { key, .. } => (key, "integer"),
+                    OpParameter::Real { key, .. } => (key, "real"),
+                    OpParameter::Series { key, .. } => (key, "series"),
+                    OpParameter::Text { key, .. } => (key, "text"),
+                    OpParameter::Texts { key, .. } => (key, "texts"),
+                })
+                .collect();
+            for parameter in &gamut {
+                assert!(keys.contains(parameter), "{name}: missing {parameter:?}");
+            }
+            for key in keys {
+                assert!(gamut.contains(key), "{name}: unknown {key:?}");
+            }
+        }
+
+        // The pipeline is built by `Pipeline`, and the noop aliases by `Noop`
+        let aliases = ["pipeline", "longlat", "latlon", "latlong", "lonlat"];
+        for operator in builtin_operators() {
+            let covered = builders.iter().any(|b| b.0 == operator.name);
+            assert!(
+                covered || aliases.contains(&operator.name),
+                "{}",
+                operator.name
+            );
+        }
+        Ok(())
+    }
+}
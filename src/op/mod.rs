@@ -1,15 +1,78 @@
+pub mod builder;
 mod op_descriptor;
 mod parameter;
 mod parsed_parameters;
 mod raw_parameters;
+mod snapshot;
 
 use crate::authoring::*;
+use crate::math::jacobian::{
+    numerical_partial_derivatives, numerical_partial_derivatives_of_steps,
+};
 use std::collections::BTreeMap;
 
 pub use op_descriptor::OpDescriptor;
 pub use parameter::OpParameter;
 pub use parsed_parameters::ParsedParameters;
 pub use raw_parameters::RawParameters;
+pub use snapshot::OpSnapshot;
+
+// The chain rule for partial derivatives in the `[∂x/∂u, ∂y/∂u, ∂x/∂v, ∂y/∂v]`
+// form: The derivatives of `outer ∘ inner`, given those of `inner` and `outer`
+fn chain(inner: [f64; 4], outer: [f64; 4]) -> [f64; 4] {
+    let [a, b, c, d] = outer;
+    let [p, q, r, s] = inner;
+    [a * p + c * q, b * p + d * q, a * r + c * s, b * r + d * s]
+}
+
+/// Is the (geographical) coordinate `coord` inside the bounding box `bbox`,
+/// given as `[west, south, east, north]` in radians? Boxes crossing the
+/// antimeridian are given with `west > east`
+pub(crate) fn within(bbox: &[f64], coord: &Coor4D) -> bool {
+    let (lon, lat) = (coord[0], coord[1]);
+    if lat < bbox[1] || lat > bbox[3] {
+        return false;
+    }
+    if bbox[0] <= bbox[2] {
+        return bbox[0] <= lon && lon <= bbox[2];
+    }
+    bbox[0] <= lon || lon <= bbox[2]
+}
+
+// A view of the elements of `operands` given by `indices`, for operating on
+// a selection in place, while retaining the metadata of the full set. Cf.
+// `CoordinateSubset` for the contiguous case
+struct Selection<'a> {
+    operands: &'a mut dyn CoordinateSet,
+    indices: &'a [usize],
+}
+
+impl CoordinateMetadata for Selection<'_> {
+    fn crs_id(&self) -> Option<MdIdentifier> {
+        self.operands.crs_id()
+    }
+    fn crs(&self) -> Option<Crs> {
+        self.operands.crs()
+    }
+    fn coordinate_epoch(&self) -> Option<DataEpoch> {
+        self.operands.coordinate_epoch()
+    }
+}
+
+impl CoordinateSet for Selection<'_> {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+    fn dim(&self) -> usize {
+        self.operands.dim()
+    }
+    fn get_coord(&self, index: usize) -> Coor4D {
+        self.operands.get_coord(self.indices[index])
+    }
+    fn set_coord(&mut self, index: usize, value: &Coor4D) {
+        self.operands.set_coord(self.indices[index], value)
+    }
+}
 
 /// The key, returned to the user, representing the actual operation handled by the `Context`
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
@@ -26,7 +89,7 @@ impl Default for OpHandle {
 }
 
 /// The defining parameters and functions for an operator
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Op {
     pub descriptor: OpDescriptor,
     pub params: ParsedParameters,
@@ -42,6 +105,10 @@ impl Op {
         operands: &mut dyn CoordinateSet,
         direction: Direction,
     ) -> usize {
+        self.check_crs(operands, direction);
+        if let Some(bbox) = self.params.series.get("omit_outside") {
+            return self.apply_inside(bbox, ctx, operands, direction);
+        }
         let forward = direction == Direction::Fwd;
         // Short form of (inverted && !forward) || (forward && !inverted)
         if self.descriptor.inverted != forward {
@@ -50,10 +117,211 @@ impl Op {
         self.descriptor.inv.0(self, ctx, operands)
     }
 
+    // Warn if the CRS of the operands is known, and differs from the one
+    // expected as input by the operator (when given, cf. `op_with_crs()`)
+    fn check_crs(&self, operands: &dyn CoordinateSet, direction: Direction) {
+        let expected = match direction {
+            Direction::Fwd => &self.descriptor.source_crs,
+            Direction::Inv => &self.descriptor.target_crs,
+        };
+        if !expected.is_known() {
+            return;
+        }
+        if let Some(crs) = operands.crs() {
+            if crs.is_known() && crs != *expected {
+                warn!("CRS mismatch: Operands are in {crs}, but the operator expects {expected}");
+            }
+        }
+    }
+
+    // Apply the operator to the operands inside the bounding box given by the
+    // `omit_outside` parameter, and pass the remaining operands through unchanged,
+    // counting them as successes
+    fn apply_inside(
+        &self,
+        bbox: &[f64],
+        ctx: &dyn Context,
+        operands: &mut dyn CoordinateSet,
+        direction: Direction,
+    ) -> usize {
+        let inside: Vec<usize> = (0..operands.len())
+            .filter(|&i| within(bbox, &operands.get_coord(i)))
+            .collect();
+        let outside = operands.len() - inside.len();
+        let mut selection = Selection {
+            operands,
+            indices: &inside,
+        };
+
+        let forward = direction == Direction::Fwd;
+        let successes = if self.descriptor.inverted != forward {
+            self.descriptor.fwd.0(self, ctx, &mut selection)
+        } else {
+            self.descriptor.inv.0(self, ctx, &mut selection)
+        };
+        successes + outside
+    }
+
+    /// The partial derivatives `[∂x/∂u, ∂y/∂u, ∂x/∂v, ∂y/∂v]` of the first two
+    /// output coordinates, *x, y*, with respect to the first two input coordinates,
+    /// *u, v*, at the point `at`, when operating in the given `direction`. Cf.
+    /// [`InnerJacobian`].
+    ///
+    /// Analytical derivatives are used where the operator provides them, and
+    /// inverse operations are handled by inverting the derivatives of the forward
+    /// operation. Otherwise, the derivatives are computed numerically. Pipelines
+    /// are handled by composing the derivatives of their steps, cf.
+    /// [`Op::composed_partial_derivatives()`], or, for pipelines including stack
+    /// manipulations, by numerical differentiation of the full pipeline.
+    /// Returns `None` if the derivatives are not finite, e.g. outside of the
+    /// domain of the operator.
+    pub fn partial_derivatives(
+        &self,
+        ctx: &dyn Context,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Option<[f64; 4]> {
+        let derivatives = if self.steps.is_empty() {
+            self.analytical_partial_derivatives(ctx, direction, at)
+        } else {
+            self.composed_partial_derivatives(ctx, direction, at)
+        };
+        let derivatives =
+            derivatives.unwrap_or_else(|| numerical_partial_derivatives(self, ctx, direction, at));
+        derivatives
+            .iter()
+            .all(|d| d.is_finite())
+            .then_some(derivatives)
+    }
+
+    // The analytical partial derivatives of a single operator, if available
+    fn analytical_partial_derivatives(
+        &self,
+        ctx: &dyn Context,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Option<[f64; 4]> {
+        let jacobian = self.descriptor.jacobian.as_ref()?;
+        if (direction == Direction::Fwd) != self.descriptor.inverted {
+            return jacobian.0(self, ctx, at);
+        }
+
+        // Invert the derivatives of the forward operation, evaluated at the
+        // point we get from the inverse operation
+        let mut point = [*at];
+        self.apply(ctx, &mut point, direction);
+        let [a, b, c, d] = jacobian.0(self, ctx, &point[0])?;
+        let det = a * d - b * c;
+        if det == 0. || !det.is_finite() {
+            return None;
+        }
+        Some([d / det, -b / det, -c / det, a / det])
+    }
+
+    /// The partial derivatives of a pipeline, in the form given by
+    /// [`Op::partial_derivatives()`], composed by the chain rule from those of
+    /// its steps, e.g. to obtain the [`Factors`](crate::math::jacobian::Factors)
+    /// of a projection preceded by a datum shift. Steps with analytical derivatives
+    /// are chained as such, while consecutive steps without are differentiated
+    /// numerically as a whole, since the horizontal part of e.g.
+    /// `cart | helmert | cart inv` is meaningful only for the full sequence.
+    ///
+    /// Returns `None` for pipelines including stack manipulations, which defeat
+    /// the chain rule. For single operators, this is the same as
+    /// [`Op::partial_derivatives()`].
+    pub fn composed_partial_derivatives(
+        &self,
+        ctx: &dyn Context,
+        direction: Direction,
+        at: &Coor4D,
+    ) -> Option<[f64; 4]> {
+        if self.steps.is_empty() {
+            return self.partial_derivatives(ctx, direction, at);
+        }
+
+        let forward = (direction == Direction::Fwd) != self.descriptor.inverted;
+        let direction = if forward {
+            Direction::Fwd
+        } else {
+            Direction::Inv
+        };
+        let omit = if forward { "omit_fwd" } else { "omit_inv" };
+        let steps: Box<dyn Iterator<Item = &Op>> = if forward {
+            Box::new(self.steps.iter())
+        } else {
+            Box::new(self.steps.iter().rev())
+        };
+
+        let mut total = [1., 0., 0., 1.];
+        let mut point = [*at];
+        // The current run of steps without analytical derivatives, and its input
+        let mut run: Vec<&Op> = Vec::new();
+        let mut start = *at;
+        for step in steps {
+            if step.params.boolean(omit) {
+                continue;
+            }
+            if ["push", "pop", "stack"].contains(&step.params.name.as_str()) {
+                return None;
+            }
+            let derivatives = if step.steps.is_empty() {
+                step.analytical_partial_derivatives(ctx, direction, &point[0])
+            } else {
+                step.composed_partial_derivatives(ctx, direction, &point[0])
+            };
+            match derivatives {
+                Some(derivatives) => {
+                    if !run.is_empty() {
+                        let numerical =
+                            numerical_partial_derivatives_of_steps(&run, ctx, direction, &start);
+                        total = chain(total, numerical);
+                        run.clear();
+                    }
+                    total = chain(total, derivatives);
+                }
+                None => {
+                    if run.is_empty() {
+                        start = point[0];
+                    }
+                    run.push(step);
+                }
+            }
+            step.apply(ctx, &mut point, direction);
+        }
+        if !run.is_empty() {
+            let numerical = numerical_partial_derivatives_of_steps(&run, ctx, direction, &start);
+            total = chain(total, numerical);
+        }
+        Some(total)
+    }
+
     pub fn new(definition: &str, ctx: &dyn Context) -> Result<Op, Error> {
         let globals = ctx.globals();
-        let parameters = RawParameters::new(definition, &globals);
-        Self::op(parameters, ctx)
+        let definition = expand_includes(definition, ctx, 0)?;
+        let parameters = RawParameters::new(&definition, &globals);
+        let mut op = Self::op(parameters, ctx)?;
+        op.descriptor.invocation = definition;
+        Ok(op)
+    }
+
+    /// A self contained representation of the operator, i.e. its definition,
+    /// and the definitions of the resources (macros, user defined ellipsoids)
+    /// it depends on, for instantiation in another context, cf. [`OpSnapshot`]
+    pub fn snapshot(&self) -> OpSnapshot {
+        let mut resources = BTreeMap::new();
+        self.collect_resources(&mut resources);
+        OpSnapshot {
+            definition: self.descriptor.invocation.clone(),
+            resources,
+        }
+    }
+
+    // The resources used by the operator, and by its steps
+    fn collect_resources(&self, resources: &mut BTreeMap<String, String>) {
+        resources.extend(self.descriptor.resources.clone());
+        for step in &self.steps {
+            step.collect_resources(resources);
+        }
     }
 
     // Helper for implementation of `InnerOp`s: Instantiate an `Op` for the simple
@@ -109,30 +377,57 @@ impl Op {
 
         // A pipeline?
         if parameters.definition.is_pipeline() {
-            return super::inner_op::pipeline::new(&parameters, ctx);
+            let op = super::inner_op::pipeline::new(&parameters, ctx)?;
+            // Optional simplification, cf. `pipeline::optimize()`
+            if parameters
+                .globals
+                .get("optimize")
+                .is_some_and(|value| value != "false")
+            {
+                return Ok(super::inner_op::pipeline::optimize(op, ctx));
+            }
+            return Ok(op);
         }
 
+        // Ellipsoids unknown to `Ellipsoid::named()` may be user defined resources
+        let mut parameters = parameters;
+        parameters.ellipsoids = user_defined_ellipsoids(&parameters, ctx);
+
         // A user defined operator?
         if !name.is_resource_name() {
             if let Ok(constructor) = ctx.get_op(&name) {
-                return constructor.0(&parameters, ctx)?.handle_op_inversion();
+                let op = constructor.0(&parameters, ctx)?;
+                return op.with_ellipsoids(&parameters).handle_op_inversion();
             }
         }
-        // A user defined macro?
-        else if let Ok(macro_definition) = ctx.get_resource(&name) {
+        // A user defined macro, or an entry from the builtin EPSG registry?
+        else if let Ok((name, macro_definition)) = resource(&parameters.definition, ctx) {
             // search for whitespace-delimited "inv" in order to avoid matching
             // tokens *containing* inv (INVariant, subINVolution, and a few other
             // pathological cases)
             let def = &parameters.definition;
             let inverted = def.contains(" inv ") || def.ends_with(" inv");
+            // A bounding box given with the invocation restricts the macro as a
+            // whole - pipelines included, as they do not look it up themselves
+            let locals = def.split_into_parameters();
+            let bbox = parsed_parameters::chase(&parameters.globals, &locals, "omit_outside")?
+                .map(parsed_parameters::bounding_box)
+                .transpose()?;
             let mut next_param = parameters.next(def);
-            next_param.definition = macro_definition;
-            return Op::op(next_param, ctx)?.handle_inversion(inverted);
+            next_param.definition = expand_includes(&macro_definition, ctx, 0)?;
+            let definition = next_param.definition.clone();
+            let mut op = Op::op(next_param, ctx)?;
+            op.descriptor.resources.insert(name, definition);
+            if let Some(bbox) = bbox {
+                op.params.series.insert("omit_outside", bbox);
+            }
+            return op.handle_inversion(inverted);
         }
 
         // A built in operator?
         if let Ok(constructor) = super::inner_op::builtin(&name) {
-            return constructor.0(&parameters, ctx)?.handle_op_inversion();
+            let op = constructor.0(&parameters, ctx)?;
+            return op.with_ellipsoids(&parameters).handle_op_inversion();
         }
 
         Err(Error::NotFound(
@@ -141,6 +436,15 @@ impl Op {
         ))
     }
 
+    // Record the user defined ellipsoids used by the operator, as resources
+    fn with_ellipsoids(mut self, parameters: &RawParameters) -> Op {
+        for (name, definition) in &parameters.ellipsoids {
+            let name = format!("ellps:{name}");
+            self.descriptor.resources.insert(name, definition.clone());
+        }
+        self
+    }
+
     fn handle_op_inversion(self) -> Result<Op, Error> {
         let inverted = self.params.boolean("inv");
         self.handle_inversion(inverted)
@@ -161,6 +465,167 @@ impl Op {
     }
 }
 
+// Resolve the ellipsoid names referred to by `parameters`, which are not known
+// by `Ellipsoid::named()`, through the context, as resources named `ellps:name`
+fn user_defined_ellipsoids(
+    parameters: &RawParameters,
+    ctx: &dyn Context,
+) -> BTreeMap<String, String> {
+    let locals = parameters.definition.split_into_parameters();
+    let mut ellipsoids = BTreeMap::new();
+    for (key, name) in parameters.globals.iter().chain(locals.iter()) {
+        if !key.starts_with("ellps") || ellipsoids.contains_key(name) {
+            continue;
+        }
+        if Ellipsoid::named(name).is_ok() {
+            continue;
+        }
+        if let Ok(definition) = ctx.get_resource(&format!("ellps:{name}")) {
+            ellipsoids.insert(name.clone(), definition.trim().to_string());
+        }
+    }
+    ellipsoids
+}
+
+// Look up the definition of a macro. A specific version of a resource may be
+// selected either as `prefix:suffix@version`, or through the `version`
+// parameter, as in `prefix:suffix version=version`. Resolving the versioned
+// name is up to the context: `Plain` looks for a `prefix_suffix@version.resource`
+// file, or a `geodesy:suffix@version` item in the `prefix.md` register. The
+// unversioned name refers to the current version. Returns the resolved name, and
+// the definition
+fn resource(definition: &str, ctx: &dyn Context) -> Result<(String, String), Error> {
+    let params = definition.split_into_parameters();
+    let mut name = params.get("_name").cloned().unwrap_or_default();
+    if let Some(version) = params.get("version") {
+        if !name.contains('@') {
+            name = format!("{name}@{version}");
+        }
+    }
+    let definition = ctx
+        .get_resource(&name)
+        .or_else(|_| builtin_resource(&name))?;
+    Ok((name, definition))
+}
+
+/// The definition of the transformation from the output of `src` to the output of
+/// `dst`, i.e. the inverse of `src`, followed by `dst`, cf. [`Context::between()`].
+/// Both definitions are include expanded and normalized. With `optimize`, steps
+/// shared by the leading parts of `src` and `dst` are removed, as they would
+/// cancel each other anyway - provided they have a proper inverse.
+pub fn between(src: &str, dst: &str, optimize: bool, ctx: &dyn Context) -> Result<String, Error> {
+    let mut src = expand_includes(src, ctx, 0)?.split_into_steps();
+    let mut dst = expand_includes(dst, ctx, 0)?.split_into_steps();
+
+    if optimize {
+        // Steps handled specially by the pipeline are never considered shared
+        let special = |step: &str| {
+            let name = step.operator_name();
+            ["push", "pop", "stack", "tissot", "trace"].contains(&name.as_str())
+                || step.contains("omit_fwd")
+                || step.contains("omit_inv")
+        };
+        // Neither are steps without a proper inverse, as e.g. `round` followed
+        // by its (pass-through) inverse is not the identity
+        let invertible = |step: &str| {
+            Op::op(RawParameters::new(step, &ctx.globals()), ctx).is_ok_and(|op| invertible(&op))
+        };
+        let shared = src
+            .iter()
+            .zip(&dst)
+            .take_while(|(a, b)| a == b && !special(a) && invertible(a))
+            .count();
+        src.drain(..shared);
+        dst.drain(..shared);
+    }
+
+    let mut steps = Vec::with_capacity(src.len() + dst.len());
+    for step in src.iter().rev() {
+        steps.push(crate::token::invert_step(step)?);
+    }
+    steps.extend(dst);
+    if steps.is_empty() {
+        return Ok("noop".to_string());
+    }
+    Ok(steps.join(" | "))
+}
+
+// Does `op`, and each of its steps, have an inverse, undoing its effect?
+// The inverses of the lossy operators are mere pass-throughs
+fn invertible(op: &Op) -> bool {
+    const LOSSY: [&str; 4] = ["clamp", "round", "select", "set"];
+    op.descriptor.invertible
+        && !LOSSY.contains(&op.params.name.as_str())
+        && op.steps.iter().all(invertible)
+}
+
+// Textually expand `include:prefix:suffix` directives, i.e. replace them by
+// the definition of the resource `prefix:suffix`, before the definition is
+// split into steps. Hence, included fragments need not be complete steps or
+// pipelines, and any `$parameter` references in the fragment are resolved
+// in the scope of the including definition. Directives in comments are
+// not expanded
+fn expand_includes(definition: &str, ctx: &dyn Context, depth: usize) -> Result<String, Error> {
+    const DIRECTIVE: &str = "include:";
+    if !definition.contains(DIRECTIVE) {
+        return Ok(definition.to_string());
+    }
+    if depth > 100 {
+        return Err(Error::Recursion(
+            DIRECTIVE.to_string(),
+            definition.to_string(),
+        ));
+    }
+
+    let delimiter = |c: char| c.is_whitespace() || "|<>".contains(c);
+    let mut expanded = String::new();
+    for line in definition.lines() {
+        let (mut code, comment) = line.split_at(line.find('#').unwrap_or(line.len()));
+        while let Some(start) = code.find(DIRECTIVE) {
+            // Only whole tokens are directives
+            let (before, after) = code.split_at(start);
+            let after = &after[DIRECTIVE.len()..];
+            expanded += before;
+            if !before.is_empty() && !before.ends_with(delimiter) {
+                expanded += DIRECTIVE;
+                code = after;
+                continue;
+            }
+
+            let after = after.trim_start();
+            let end = after.find(delimiter).unwrap_or(after.len());
+            let name = &after[..end];
+            let Ok(fragment) = ctx.get_resource(name).or_else(|_| builtin_resource(name)) else {
+                return Err(Error::NotFound(
+                    name.to_string(),
+                    ": Included resource".to_string(),
+                ));
+            };
+            expanded += "\n";
+            expanded += &expand_includes(&fragment, ctx, depth + 1)?;
+            expanded += "\n";
+            code = &after[end..];
+        }
+        expanded += code;
+        expanded += comment;
+        expanded += "\n";
+    }
+    Ok(expanded)
+}
+
+// Resources provided by the library itself, rather than by the context
+fn builtin_resource(name: &str) -> Result<String, Error> {
+    #[cfg(feature = "epsg")]
+    if name.to_lowercase().starts_with("epsg:") {
+        return crate::epsg::resource(name);
+    }
+    #[cfg(feature = "nkg")]
+    if name.starts_with("nkg:") {
+        return crate::nkg::resource(name);
+    }
+    Err(Error::NotFound(name.to_string(), String::default()))
+}
+
 // ----- T E S T S ------------------------------------------------------------------
 
 #[cfg(test)]
@@ -234,6 +699,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn omit_outside() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        let copenhagen = Coor4D::geo(55., 12., 0., 0.);
+        let new_york = Coor4D::geo(40.7, -74., 0., 0.);
+
+        // Only Copenhagen is inside the box, but both are counted as successes
+        let op = ctx.op("addone omit_outside=10,50,20,60 | addone")?;
+        let mut data = [copenhagen, new_york];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert!((data[0][0] - (copenhagen[0] + 2.)).abs() < 1e-15);
+        assert_eq!(data[1][0], new_york[0] + 1.);
+
+        // The box is checked against the input of the step, in both directions
+        let op = ctx.op("addone omit_outside=10,50,20,60")?;
+        let mut data = [copenhagen, new_york];
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][0], copenhagen[0] - 1.);
+        assert_eq!(data[1][0], new_york[0]);
+
+        // Boxes crossing the antimeridian
+        let op = ctx.op("addone omit_outside=170,-20,-170,20")?;
+        let mut data = [Coor4D::geo(0., 175., 0., 0.), Coor4D::geo(0., 0., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 175_f64.to_radians() + 1.);
+        assert_eq!(data[1][0], 0.);
+
+        // Macros, including pipelines, are restricted as a whole
+        ctx.register_resource("test:two", "addone | addone");
+        let op = ctx.op("test:two omit_outside=10,50,20,60")?;
+        let mut data = [copenhagen, new_york];
+        assert_eq!(ctx.apply(op, Fwd, &mut data)?, 2);
+        assert!((data[0][0] - (copenhagen[0] + 2.)).abs() < 1e-15);
+        assert_eq!(data[1][0], new_york[0]);
+
+        // Malformed boxes
+        assert!(matches!(
+            ctx.op("test:two omit_outside=10,50,20"),
+            Err(Error::BadParam(_, _))
+        ));
+        assert!(matches!(
+            ctx.op("addone omit_outside=10,50,20"),
+            Err(Error::BadParam(_, _))
+        ));
+        assert!(matches!(
+            ctx.op("addone omit_outside=10,60,20,50"),
+            Err(Error::BadParam(_, _))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn macro_expansion() -> Result<(), Error> {
         let mut data = crate::test_data::coor2d();
@@ -297,6 +813,198 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn user_defined_ellipsoids() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_resource("ellps:hayford", "6378388, 297");
+        ctx.register_resource("ellps:sphere", "1");
+
+        let user = ctx.op("cart ellps=hayford")?;
+        let builtin = ctx.op("cart ellps=intl")?;
+        let mut a = [Coor4D::geo(55., 12., 0., 0.)];
+        let mut b = a;
+        ctx.apply(user, Fwd, &mut a)?;
+        ctx.apply(builtin, Fwd, &mut b)?;
+        assert_eq!(a, b);
+
+        // Builtins take precedence
+        let sphere = ctx.op("cart ellps=sphere")?;
+        let mut c = [Coor4D::geo(0., 0., 0., 0.)];
+        ctx.apply(sphere, Fwd, &mut c)?;
+        assert_eq!(c[0][0], 6_370_997.);
+
+        // User defined ellipsoids can be passed as macro arguments
+        ctx.register_resource("test:ellps", "cart ellps=$ellps(GRS80)");
+        let user = ctx.op("test:ellps ellps=hayford")?;
+        let mut c = [Coor4D::geo(55., 12., 0., 0.)];
+        ctx.apply(user, Fwd, &mut c)?;
+        assert_eq!(b, c);
+        Ok(())
+    }
+
+    #[test]
+    fn macro_versions() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_resource("add:some", "addone | addone | addone");
+        ctx.register_resource("add:some@1", "addone");
+        ctx.register_resource("add:some@2", "addone | addone");
+
+        // The unversioned name refers to the current version...
+        let op = ctx.op("add:some")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 58.);
+
+        // ...while earlier versions may be selected in two ways
+        for definition in [
+            "add:some@1",
+            "add:some version=1",
+            "addone inv | add:some version=2",
+        ] {
+            let op = ctx.op(definition)?;
+            let mut data = crate::test_data::coor2d();
+            ctx.apply(op, Fwd, &mut data)?;
+            assert_eq!(data[0][0], 56.);
+        }
+
+        // Also when inverted
+        let op = ctx.op("add:some inv version=2")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 53.);
+
+        // Non-existing versions are not silently replaced by the current one
+        assert!(ctx.op("add:some@3").is_err());
+        assert!(ctx.op("add:some version=3").is_err());
+
+        // Macro parameters named `v` are not mistaken for versions
+        ctx.register_resource("add:v", "helmert x=$v");
+        let op = ctx.op("add:v v=2")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 57.);
+        Ok(())
+    }
+
+    #[test]
+    fn includes() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_resource("frag:two", "addone | addone # include:frag:none");
+        ctx.register_resource("frag:three", "include:frag:two | addone");
+        ctx.register_resource("frag:shift", "helmert x=$shift");
+
+        // Nested includes, comments in the fragments, and a fragment which
+        // is not a complete step
+        let definition = "include:frag:three\n| addone inv | include:frag:two\n> addone";
+        let op = ctx.op(definition)?;
+        assert_eq!(ctx.steps(op)?.len(), 7);
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 60.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert_eq!(data[0][0], 56.);
+
+        // Macro parameters are resolved in the scope of the includer
+        ctx.register_resource("frag:shifter", "include:frag:shift");
+        let op = ctx.op("frag:shifter shift=3")?;
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 58.);
+
+        // Directives are whole tokens only
+        ctx.register_resource("frag:noinclude:two", "addone");
+        let op = ctx.op("frag:noinclude:two")?;
+        assert_eq!(ctx.steps(op)?.len(), 1);
+
+        // Missing and circular includes
+        assert!(matches!(
+            ctx.op("include:frag:none"),
+            Err(Error::NotFound(_, _))
+        ));
+        ctx.register_resource("frag:ping", "include:frag:pong");
+        ctx.register_resource("frag:pong", "include:frag:ping");
+        assert!(matches!(ctx.op("frag:ping"), Err(Error::Recursion(_, _))));
+        Ok(())
+    }
+
+    #[test]
+    fn between() -> Result<(), Error> {
+        let mut ctx = Minimal::new();
+        ctx.register_resource("test:geo", "geo:in | cart");
+        let src = "include:test:geo | helmert x=1 | cart inv | utm zone=32";
+        let dst = "geo:in|cart|helmert x=1|cart inv|push v_3|utm zone=33|pop v_3";
+
+        let definition = super::between(src, dst, false, &ctx)?;
+        assert!(definition.starts_with("utm zone=32 inv | cart | helmert x=1 inv | "));
+        let definition = super::between(src, dst, true, &ctx)?;
+        assert_eq!(
+            definition,
+            "utm zone=32 inv | push v_3 | utm zone=33 | pop v_3"
+        );
+        assert_eq!(super::between(src, src, true, &ctx)?, "noop");
+
+        // The optimized and unoptimized versions give the same results,
+        // which roundtrip through the two projections
+        let plain = ctx.between(src, dst, false)?;
+        let optimized = ctx.between(src, dst, true)?;
+        let utm32 = ctx.op("geo:in | utm zone=32")?;
+        let utm33 = ctx.op("geo:in | utm zone=33")?;
+
+        let geo = Coor4D::raw(55., 12., 100., 0.);
+        let mut a = [geo];
+        ctx.apply(utm32, Fwd, &mut a)?;
+        let mut b = a;
+        ctx.apply(plain, Fwd, &mut a)?;
+        ctx.apply(optimized, Fwd, &mut b)?;
+        assert!(a[0].hypot3(&b[0]) < 1e-6);
+        ctx.apply(utm33, Inv, &mut b)?;
+        assert!(b[0].hypot3(&geo) < 1e-9);
+
+        ctx.apply(plain, Inv, &mut a)?;
+        ctx.apply(utm32, Inv, &mut a)?;
+        assert!(a[0].hypot2(&geo) < 1e-9);
+
+        // Shared steps without a proper inverse are kept
+        for step in [
+            "round decimals=2",
+            "set z=0",
+            "select z=0,1",
+            "clamp z=0,1",
+            "gravity",
+        ] {
+            let src = format!("{step} | addone");
+            let dst = format!("{step} | addone inv");
+            let definition = super::between(&src, &dst, true, &ctx)?;
+            assert!(definition.contains(step), "{definition}");
+        }
+        assert_eq!(
+            super::between("cart | addone", "cart | addone inv", true, &ctx)?,
+            "addone inv | addone inv"
+        );
+
+        // The stack operator cannot be inverted
+        assert!(ctx
+            .between("stack push=1,2 | stack pop=2,1", "noop", true)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn quoted_values() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_resource("quote:one", "addone # 'a' | b");
+
+        // Quoted values survive the splitting into steps and the macro expansion
+        let op = ctx.op("quote:one label='a | b # c' | addone note=\"x y\"")?;
+        let steps = ctx.steps(op)?;
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0], "quote:one label='a | b # c'");
+        let mut data = crate::test_data::coor2d();
+        ctx.apply(op, Fwd, &mut data)?;
+        assert_eq!(data[0][0], 57.);
+        Ok(())
+    }
+
     #[test]
     fn macro_expansion_with_defaults() -> Result<(), Error> {
         let mut data = crate::test_data::coor2d();
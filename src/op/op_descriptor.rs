@@ -1,36 +1,50 @@
-use super::*;
-
-/// The fundamental elements of an operator (i.e. everything but steps and args)
-#[derive(Debug, Default)]
-pub struct OpDescriptor {
-    pub invocation: String, // e.g. geo:helmert ellps_0=GRS80 x=1 y=2 z=3 ellps_1=intl
-    pub definition: String, // e.g. cart ellps=$ellps_0 | helmert | cart inv ellps=$ellps_1
-    pub steps: Vec<String>,
-    pub invertible: bool,
-    pub inverted: bool,
-    pub fwd: InnerOp,
-    pub inv: InnerOp,
-    pub id: OpHandle,
-}
-
-impl OpDescriptor {
-    pub fn new(definition: &str, fwd: InnerOp, inv: Option<InnerOp>) -> OpDescriptor {
-        let steps = definition.split_into_steps();
-        let definition = definition.to_string();
-        let invertible = inv.is_some();
-        let inverted = false; // Handled higher up in the call hierarchy
-        let invocation = "".to_string(); // Handled higher up in the call hierarchy
-        let inv = inv.unwrap_or_default();
-        let id = OpHandle::new();
-        OpDescriptor {
-            invocation,
-            definition,
-            steps,
-            invertible,
-            inverted,
-            fwd,
-            inv,
-            id,
-        }
-    }
-}
+use super::*;
+
+/// The fundamental elements of an operator (i.e. everything but steps and args)
+#[derive(Debug, Default, Clone)]
+pub struct OpDescriptor {
+    pub invocation: String, // e.g. geo:helmert ellps_0=GRS80 x=1 y=2 z=3 ellps_1=intl
+    pub definition: String, // e.g. cart ellps=$ellps_0 | helmert | cart inv ellps=$ellps_1
+    pub steps: Vec<String>,
+    pub invertible: bool,
+    pub inverted: bool,
+    pub fwd: InnerOp,
+    pub inv: InnerOp,
+    pub jacobian: Option<InnerJacobian>,
+    pub id: OpHandle,
+    /// The definitions of the resources (macros, user defined ellipsoids)
+    /// resolved when instantiating the operator, keyed by name
+    pub resources: BTreeMap<String, String>,
+    /// The CRS of the input and output of the operator, when given by the
+    /// user, cf. [`Context::op_with_crs()`]
+    pub source_crs: Crs,
+    pub target_crs: Crs,
+}
+
+impl OpDescriptor {
+    pub fn new(definition: &str, fwd: InnerOp, inv: Option<InnerOp>) -> OpDescriptor {
+        let steps = definition.split_into_steps();
+        let definition = definition.to_string();
+        let invertible = inv.is_some();
+        let inverted = false; // Handled higher up in the call hierarchy
+        let invocation = "".to_string(); // Handled higher up in the call hierarchy
+        let inv = inv.unwrap_or_default();
+        let jacobian = None; // Set by the operator constructor, where available
+        let id = OpHandle::new();
+        let resources = BTreeMap::new();
+        OpDescriptor {
+            invocation,
+            definition,
+            steps,
+            invertible,
+            inverted,
+            fwd,
+            inv,
+            jacobian,
+            id,
+            resources,
+            source_crs: Crs::Unknown,
+            target_crs: Crs::Unknown,
+        }
+    }
+}
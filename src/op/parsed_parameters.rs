@@ -139,19 +139,19 @@ impl ParsedParameters {
         self.ignored.clone()
     }
 
-    pub fn ellps(&self, index: usize) -> Ellipsoid {
+    pub fn ellps(&self, index: usize) -> Result<Ellipsoid, Error> {
         // if 'ellps' was explicitly given, it will override 'ellps_0'
         if index == 0 {
             if let Some(e) = self.text.get("ellps") {
-                return Ellipsoid::named(e).unwrap();
+                return Ellipsoid::named(e);
             }
         }
         let key = format!("ellps_{index}");
         if let Some(e) = self.text.get(&key[..]) {
-            return Ellipsoid::named(e).unwrap();
+            return Ellipsoid::named(e);
         }
         // If none of them existed, i.e. no defaults were given, we return the general default
-        Ellipsoid::default()
+        Ok(Ellipsoid::default())
     }
 
     pub fn k(&self, index: usize) -> f64 {
@@ -369,6 +369,35 @@ impl ParsedParameters {
             };
         }
 
+        // Ellipsoids given by the name of a user defined resource
+        for (key, value) in text.iter_mut() {
+            if let (true, Some(ellps)) =
+                (key.starts_with("ellps"), parameters.ellipsoids.get(value))
+            {
+                *value = ellps.clone();
+            }
+        }
+
+        // Ellipsoids given PROJ style, by their defining parameters
+        if let Some(ellps) = text.get("ellps") {
+            if let Some(ellps) = ellipsoid_from_parameters(globals, &locals, ellps)? {
+                text.insert("ellps", ellps);
+            }
+        }
+
+        // Unknown ellipsoids are reported here, rather than when used
+        for (key, value) in &text {
+            if !key.starts_with("ellps") || Ellipsoid::named(value).is_ok() {
+                continue;
+            }
+            let mut message = value.clone();
+            if let Some(name) = Ellipsoid::similar(value) {
+                message += &format!(" (did you mean '{name}'?)");
+            }
+            warn!("Unknown ellipsoid {key}={message}");
+            return Err(Error::BadParam(key.to_string(), message));
+        }
+
         // Default gamut elements - traditionally supported for all operators
 
         // omit_fwd and omit_inv are implicitly valid for all ops
@@ -383,6 +412,15 @@ impl ParsedParameters {
             }
         }
 
+        // omit_outside=west,south,east,north (in degrees) is implicitly valid for all
+        // ops, restricting them to operands inside the bounding box. Only looked up
+        // locally, as a global bounding box would restrict every step of a pipeline
+        if locals.contains_key("omit_outside") && !parameters.definition.is_pipeline() {
+            if let Some(value) = chase(globals, &locals, "omit_outside")? {
+                series.insert("omit_outside", bounding_box(value)?);
+            }
+        }
+
         for k in ZERO_VALUED_IMPLICIT_GAMUT_ELEMENTS {
             if !real.contains_key(k) {
                 real.insert(k, 0.);
@@ -426,6 +464,56 @@ impl ParsedParameters {
 
 // ----- A N C I L L A R Y   F U N C T I O N S -----------------------------------------
 
+// The PROJ style ellipsoid parameters: The semimajor axis, `a`, and one of the
+// shape parameters `b`, `rf`, `f`, `e` or `es`. If only one of the two is
+// given, the other is taken from `ellps`. Returns the ellipsoid in the
+// `a, rf` form, or `None` if no ellipsoid parameters are given
+fn ellipsoid_from_parameters(
+    globals: &BTreeMap<String, String>,
+    locals: &BTreeMap<String, String>,
+    ellps: &str,
+) -> Result<Option<String>, Error> {
+    let real = |key: &str| -> Result<Option<f64>, Error> {
+        let Some(value) = chase(globals, locals, key)? else {
+            return Ok(None);
+        };
+        match value.parse::<f64>() {
+            Ok(v) => Ok(Some(v)),
+            Err(_) => Err(Error::BadParam(key.to_string(), value)),
+        }
+    };
+
+    let a = real("a")?;
+    let mut shape = None;
+    for key in ["b", "rf", "f", "e", "es"] {
+        if let Some(value) = real(key)? {
+            shape = Some((key, value));
+            break;
+        }
+    }
+    if a.is_none() && shape.is_none() {
+        return Ok(None);
+    }
+
+    let given = Ellipsoid::named(ellps)?;
+    let a = a.unwrap_or(given.semimajor_axis());
+    let (key, value) = shape.unwrap_or(("f", given.flattening()));
+    let f = Ellipsoid::from_shape(a, key, value)?.flattening();
+    let rf = if f == 0. { 0. } else { 1. / f };
+    Ok(Some(format!("{a}, {rf}")))
+}
+
+// Parse the `omit_outside=west,south,east,north` bounding box (in degrees),
+// returning it in radians
+pub(crate) fn bounding_box(value: String) -> Result<Vec<f64>, Error> {
+    let bbox: Vec<f64> = value.split(',').map(angular::parse_sexagesimal).collect();
+    if bbox.len() != 4 || bbox.iter().any(|v| v.is_nan()) || bbox[1] > bbox[3] {
+        warn!("Cannot parse omit_outside:{value} as a bounding box");
+        return Err(Error::BadParam("omit_outside".to_string(), value));
+    }
+    Ok(bbox.iter().map(|v| v.to_radians()).collect())
+}
+
 pub fn chase(
     globals: &BTreeMap<String, String>,
     locals: &BTreeMap<String, String>,
@@ -567,7 +655,7 @@ mod tests {
         assert_eq!(*p.text.get("text").unwrap(), "text");
 
         assert_eq!(
-            p.ellps(0).semimajor_axis(),
+            p.ellps(0)?.semimajor_axis(),
             Ellipsoid::new(123., 1. / 456.).semimajor_axis()
         );
 
@@ -614,4 +702,62 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn ellipsoid_parameters() -> Result<(), Error> {
+        let globals = BTreeMap::<String, String>::new();
+        let gamut = [OpParameter::Text {
+            key: "ellps",
+            default: Some("GRS80"),
+        }];
+        let ellps = |invocation: &str| -> Result<Ellipsoid, Error> {
+            let raw = RawParameters::new(invocation, &globals);
+            ParsedParameters::new(&raw, &gamut)?.ellps(0)
+        };
+
+        // The semimajor axis combined with any of the shape parameters
+        let intl = Ellipsoid::named("intl")?;
+        let b = intl.semiminor_axis();
+        let es = intl.eccentricity_squared();
+        for invocation in [
+            format!("cucumber a=6378388 b={b}"),
+            format!("cucumber a=6378388 es={es}"),
+            format!("cucumber a=6378388 e={}", es.sqrt()),
+            format!("cucumber a=6378388 f={}", 1. / 297.),
+            "cucumber a=6378388 rf=297".to_string(),
+        ] {
+            let e = ellps(&invocation)?;
+            assert_eq!(e.semimajor_axis(), 6_378_388.);
+            assert!((e.flattening() - intl.flattening()).abs() < 1e-15);
+        }
+
+        // ...or modifying the ellipsoid given
+        let e = ellps("cucumber ellps=intl a=1")?;
+        assert_eq!(e.semimajor_axis(), 1.);
+        assert_eq!(e.flattening(), intl.flattening());
+        let e = ellps("cucumber rf=297")?;
+        assert_eq!(e.semimajor_axis(), 6_378_137.);
+        assert!((e.flattening() - intl.flattening()).abs() < 1e-15);
+        assert_eq!(ellps("cucumber a=6371000 b=6371000")?.flattening(), 0.);
+
+        assert!(ellps("cucumber a=6378388 b=foo").is_err());
+        assert!(ellps("cucumber a=6378388 es=2").is_err());
+
+        // Unknown ellipsoids are reported at instantiation, with a suggestion
+        let Err(Error::BadParam(key, message)) = ellps("cucumber ellps=GRS1980") else {
+            panic!("Expected BadParam");
+        };
+        assert_eq!(key, "ellps");
+        assert_eq!(message, "GRS1980 (did you mean 'GRS80'?)");
+        let mut ctx = Minimal::new();
+        assert!(matches!(
+            ctx.op("utm zone=32 ellps=intl1924"),
+            Err(Error::BadParam(..))
+        ));
+        assert!(matches!(
+            ctx.op("cart ellps=atlantis"),
+            Err(Error::BadParam(..))
+        ));
+        Ok(())
+    }
 }
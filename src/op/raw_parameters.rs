@@ -15,6 +15,9 @@ pub struct RawParameters {
     pub invocation: String,
     pub definition: String,
     pub globals: BTreeMap<String, String>,
+    /// Definitions of the user defined ellipsoids referred to by the step,
+    /// as resolved by the context (cf. `Op::op()`)
+    pub ellipsoids: BTreeMap<String, String>,
     recursion_level: usize,
 }
 
@@ -34,6 +37,7 @@ impl RawParameters {
                 definition,
                 globals,
                 recursion_level,
+                ..Default::default()
             };
             return previous.next(&previous.invocation);
         }
@@ -45,6 +49,7 @@ impl RawParameters {
             definition,
             globals,
             recursion_level,
+            ..Default::default()
         }
     }
 
@@ -69,6 +74,7 @@ impl RawParameters {
             definition,
             globals,
             recursion_level,
+            ..Default::default()
         }
     }
 
@@ -0,0 +1,148 @@
+//! A self contained, serializable representation of an instantiated operator.
+//!
+//! An [`Op`] refers to its grids through `Arc`s, and to its code through plain
+//! function pointers, so it is `Clone + Send + Sync`, and may be shared between
+//! threads as is. Across process boundaries, however, we need something that can
+//! be written out as text, and read back in. That is an `OpSnapshot`: the
+//! (include expanded) definition of the operator, and the definitions of all
+//! the user defined resources, i.e. macros and ellipsoids, it depends on.
+//!
+//! Instantiating a snapshot in another context is cheap: the resources are
+//! registered with the context, and the definition is instantiated as usual.
+//! Grids and blobs are looked up by name in the receiving context, and the
+//! global parameters of the receiving context apply.
+//!
+//! The text representation is the definition, followed by one section per
+//! resource, each introduced by a line of the form `#! resource <name>`:
+//!
+//! ```txt
+//! my:utm32
+//! #! resource my:utm32
+//! utm zone=32 ellps=my_ellps
+//! #! resource ellps:my_ellps
+//! 6378137, 298.25
+//! ```
+use crate::authoring::*;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const SECTION: &str = "#! resource ";
+
+/// The definition of an operator, and of the resources it depends on.
+/// Obtained from [`Op::snapshot`] or [`Context::snapshot`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpSnapshot {
+    pub definition: String,
+    pub resources: BTreeMap<String, String>,
+}
+
+impl OpSnapshot {
+    /// Register the resources with `ctx`, and instantiate the operator there
+    pub fn instantiate(&self, ctx: &mut dyn Context) -> Result<OpHandle, Error> {
+        for (name, definition) in &self.resources {
+            ctx.register_resource(name, definition);
+        }
+        ctx.op(&self.definition)
+    }
+}
+
+impl Display for OpSnapshot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.definition.trim())?;
+        for (name, definition) in &self.resources {
+            writeln!(f, "{SECTION}{name}")?;
+            writeln!(f, "{}", definition.trim())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for OpSnapshot {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut snapshot = OpSnapshot::default();
+        let mut current: Option<String> = None;
+        let mut text = Vec::new();
+
+        // Store the text collected for the current section, and start a new one
+        let mut store = |current: &Option<String>, text: &mut Vec<&str>| {
+            let content = text.join("\n").trim().to_string();
+            text.clear();
+            match current {
+                None => snapshot.definition = content,
+                Some(name) => {
+                    snapshot.resources.insert(name.clone(), content);
+                }
+            }
+        };
+
+        for line in s.lines() {
+            if let Some(name) = line.strip_prefix(SECTION) {
+                store(&current, &mut text);
+                current = Some(name.trim().to_string());
+                continue;
+            }
+            text.push(line);
+        }
+        store(&current, &mut text);
+
+        if snapshot.definition.is_empty() {
+            return Err(Error::Syntax("Empty operator snapshot".to_string()));
+        }
+        Ok(snapshot)
+    }
+}
+
+// ----- T E S T S ---------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Op must be shareable between threads
+    fn _assert_send_sync<T: Send + Sync + Clone>() {}
+    const _: fn() = _assert_send_sync::<Op>;
+
+    #[test]
+    fn snapshot() -> Result<(), Error> {
+        let mut ctx = Minimal::default();
+        ctx.register_resource("ellps:my_ellps", "6378137, 298.25");
+        ctx.register_resource("my:utm32", "utm zone=32 ellps=my_ellps");
+        ctx.register_resource("my:roundtrip", "my:utm32 | my:utm32 inv");
+        let op = ctx.op("my:roundtrip | helmert x=1")?;
+
+        let snapshot = ctx.snapshot(op)?;
+        assert_eq!(snapshot.definition, "my:roundtrip | helmert x=1");
+        assert_eq!(snapshot.resources.len(), 3);
+        assert_eq!(snapshot.resources["ellps:my_ellps"], "6378137, 298.25");
+
+        // Roundtrip through the text representation
+        let text = snapshot.to_string();
+        let parsed: OpSnapshot = text.parse()?;
+        assert_eq!(parsed, snapshot);
+
+        // Instantiate in a fresh context, and compare the results
+        let mut other = Minimal::default();
+        let clone = parsed.instantiate(&mut other)?;
+        let mut a = [Coor4D::geo(55., 12., 0., 0.)];
+        let mut b = a;
+        ctx.apply(op, Fwd, &mut a)?;
+        other.apply(clone, Fwd, &mut b)?;
+        assert_eq!(a, b);
+
+        // The ellipsoid is actually used
+        let utm = other.op("my:utm32")?;
+        let p = other.params(utm, 0)?;
+        assert_eq!(p.ellps(0)?.semimajor_axis(), 6378137.);
+        assert!((p.ellps(0)?.flattening() - 1. / 298.25).abs() < 1e-15);
+
+        // Only the resources actually used are included
+        let op = ctx.op("my:utm32")?;
+        let snapshot = ctx.snapshot(op)?;
+        assert!(!snapshot.resources.contains_key("my:roundtrip"));
+        assert!("".parse::<OpSnapshot>().is_err());
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::ellps::Ellipsoid;
+use crate::ellps::EllipsoidBase;
 use crate::Error;
 use std::collections::BTreeMap;
 
@@ -17,6 +19,12 @@ pub trait Tokenize {
     /// ```txt
     /// 'foo bar=baz bonk=blue flag' -> ('name=foo', 'bar=baz', 'bonk=blue', 'flag=true')
     /// ```
+    /// Values containing whitespace, or any of the syntactically significant
+    /// characters, may be given in single or double quotes. The quotes are
+    /// removed from the value:
+    /// ```txt
+    /// 'foo bar="baz bonk"' -> ('name=foo', 'bar=baz bonk')
+    /// ```
     fn split_into_parameters(&self) -> BTreeMap<String, String>;
 
     /// Helper function for 'split_into_steps' and 'split_into_parameters':
@@ -40,6 +48,8 @@ pub trait Tokenize {
     ///    ```txt
     ///     foo > bar < baz  ->  foo|omit_inv bar|omit_fwd baz
     ///    ```
+    ///
+    /// Quoted strings are left untouched.
     fn normalize(&self) -> String;
 
     fn is_pipeline(&self) -> bool;
@@ -64,29 +74,25 @@ where
         // Remove comments
         let mut trimmed = String::new();
         for line in all.lines() {
-            let line = line.trim();
-
-            // Remove comments - both inline and separate lines
-            let line: Vec<&str> = line.trim().split('#').collect();
-            // Full line comment - just skip
-            if line[0].starts_with('#') {
-                continue;
-            }
+            // Remove comments - both inline and separate lines.
+            // A `#` inside a quoted string does not start a comment
+            let (protected, quoted) = protect_quotes(line.trim());
+            let code = protected.split('#').next().unwrap_or_default();
 
             // Inline comment, or no comment at all: Collect everything before `#`
             trimmed += " ";
-            trimmed += line[0].trim();
+            trimmed += &restore_quotes(code.trim(), &quoted);
         }
 
         // Remove empty steps and other non-significant whitespace
-        let steps: Vec<String> = trimmed
-            .normalize()
+        let (protected, quoted) = protect_quotes(&trimmed.normalize());
+        let steps: Vec<String> = protected
             // split into steps
             .split('|')
             // remove empty steps
             .filter(|x| !x.is_empty())
-            // convert &str to String
-            .map(|x| x.to_string())
+            // convert &str to String, reinserting any quoted strings
+            .map(|x| restore_quotes(x, &quoted))
             // and turn into Vec<String>
             .collect();
 
@@ -94,8 +100,8 @@ where
     }
 
     fn split_into_parameters(&self) -> BTreeMap<String, String> {
-        // Remove non-significant whitespace
-        let step = self.as_ref().normalize();
+        // Remove non-significant whitespace, and hide quoted strings
+        let (step, quoted) = protect_quotes(&self.as_ref().normalize());
         let mut params = BTreeMap::new();
         let mut elements: Vec<_> = step.split_whitespace().collect();
         if elements.is_empty() {
@@ -122,15 +128,17 @@ where
                 continue;
             }
 
-            params.insert(String::from(parts[0]), String::from(parts[1]));
+            let value = restore_quotes(parts[1], &quoted);
+            params.insert(String::from(parts[0]), unquote(&value).to_string());
         }
 
         params
     }
 
     fn normalize(&self) -> String {
+        let (protected, quoted) = protect_quotes(self.as_ref());
         // Tweak everything into canonical form
-        self.as_ref()
+        let normalized = protected
             .trim()
             .trim_matches(':')
             .replace("\n:", "\n")
@@ -164,11 +172,13 @@ where
             .replace("$ ", "$") // But keep " $" as is!
             .split_whitespace()
             .collect::<Vec<_>>()
-            .join(" ")
+            .join(" ");
+        restore_quotes(&normalized, &quoted)
     }
 
     fn is_pipeline(&self) -> bool {
-        self.as_ref().contains('|') || self.as_ref().contains('<') || self.as_ref().contains('>')
+        let (protected, _) = protect_quotes(self.as_ref());
+        protected.contains('|') || protected.contains('<') || protected.contains('>')
     }
 
     fn is_resource_name(&self) -> bool {
@@ -186,6 +196,97 @@ where
     }
 }
 
+// Quoted strings are hidden from the tokenizer by replacing them with
+// placeholders, consisting of the index of the string, enclosed in a pair
+// of private use characters. A quote starts a quoted string only at the
+// start of a token (so apostrophes in words are left alone), and only if
+// it is matched later in the same text
+const PLACEHOLDER: char = '\u{E000}';
+
+fn protect_quotes(text: &str) -> (String, Vec<String>) {
+    let mut protected = String::new();
+    let mut quoted = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let at_token_start = protected
+            .chars()
+            .last()
+            .map_or(true, |p| p.is_whitespace() || "=,|<>".contains(p));
+        if (c == '"' || c == '\'') && at_token_start {
+            if let Some(end) = rest[1..].find(c) {
+                let end = end + 2;
+                protected.push(PLACEHOLDER);
+                protected += &quoted.len().to_string();
+                protected.push(PLACEHOLDER);
+                quoted.push(rest[..end].to_string());
+                rest = &rest[end..];
+                continue;
+            }
+        }
+        protected.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    (protected, quoted)
+}
+
+fn restore_quotes(text: &str, quoted: &[String]) -> String {
+    if quoted.is_empty() {
+        return text.to_string();
+    }
+    let mut restored = String::new();
+    for (i, part) in text.split(PLACEHOLDER).enumerate() {
+        if i % 2 == 0 {
+            restored += part;
+            continue;
+        }
+        match part.parse::<usize>().ok().and_then(|i| quoted.get(i)) {
+            Some(q) => restored += q,
+            None => restored += part,
+        }
+    }
+    restored
+}
+
+// Remove a pair of matching quotes surrounding `text`
+fn unquote(text: &str) -> &str {
+    for q in ['"', '\''] {
+        if text.len() > 1 && text.starts_with(q) && text.ends_with(q) {
+            return &text[1..text.len() - 1];
+        }
+    }
+    text
+}
+
+/// Expand references to environment variables, `${NAME}`, in `text`.
+/// Intended for use by contexts offering interpolation of site specific
+/// values (e.g. grid directories) into operator definitions and resources,
+/// before tokenization. Note that the `$name` syntax, without braces,
+/// refers to macro parameters, and is left untouched.
+pub fn interpolate_environment(text: &str) -> Result<String, Error> {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        result += &rest[..start];
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            return Err(Error::Syntax(format!(
+                "Unterminated environment variable reference in '{text}'"
+            )));
+        };
+        let name = &after[..end];
+        let Ok(value) = std::env::var(name) else {
+            return Err(Error::NotFound(
+                name.to_string(),
+                ": Environment variable".to_string(),
+            ));
+        };
+        result += &value;
+        rest = &after[end + 1..];
+    }
+    result += rest;
+    Ok(result)
+}
+
 /// Translate a PROJ string into Rust Geodesy format. Since PROJ is syntactically
 /// unrestrictive, we do not try to detect any syntax errors: If the input
 /// is so cursed as to be intranslatable, this will become clear when trying to
@@ -198,12 +299,17 @@ where
 /// ## Known differences between PROJ and Rust Geodesy definitions:
 ///
 /// ## Ellipsoid definitions
-/// - Geodesy only supports a limited set of builtin ellipsoids OR or definition
-///   via semi-major and reverse-flattening parameters  `ellps=a,rf`.
+/// - Geodesy supports a limited set of builtin ellipsoids, definition via
+///   semi-major and reverse-flattening parameters  `ellps=a,rf`, and operator
+///   parameters `a` combined with one of `b`, `rf`, `f`, `e`, `es`.
 /// - PROJ has [richer ellipsoid](https://proj.org/en/9.3/usage/ellipsoids.html#ellipsoid-size-parameters)
 ///   support which *parse_proj* provides partial support for.
-/// - Specifically if an ellipsoid is defined via `a` and `rf` parameters, *parse_proj*
-///   will redefine them as `ellps=a,rf` and remove the `a` and `rf` parameters.
+/// - Specifically if an ellipsoid is defined via `a` and one of `rf`, `b`, `f`,
+///   `e` or `es` parameters, *parse_proj* will redefine them as `ellps=a,rf`
+///   and remove the original parameters.
+/// - Spheres given by a radius, `R`, are redefined as `ellps=R`, and spheres
+///   with a radius derived from the ellipsoid (`R_A`, `R_V`, `R_a`, `R_g`, `R_h`)
+///   as e.g. `ellps=R_A:GRS80`.
 /// - All other cases supported by PROJ are NOT handled by *parse_proj* and will
 ///   fail when instantiating the operator.
 ///
@@ -212,6 +318,20 @@ where
 ///   have the scaling defined as `k` instead of `k_0`.
 /// - *parse_proj* will replace `k` with `k_0` whenever it is encountered.
 ///
+/// ## Axis order and units via `axis`, `units` and `vunits`
+/// - Geodesy operators work in meters, in the east-north-up order, so *parse_proj*
+///   translates these parameters into `unitconvert` and `adapt` steps.
+/// - For projections, the steps follow the projection, for geographical
+///   steps (`proj=longlat`), they precede it. `units` is ignored for the latter.
+///
+/// ## Datum shifts via `towgs84`, `nadgrids`, `geoidgrids` and `pm`
+/// - Geodesy has no notion of a CRS, so the datum shift parameters of PROJ.4
+///   style definitions are expanded into explicit datum shift steps:
+///   `towgs84` into `cart | helmert | cart inv`, `nadgrids` and `geoidgrids`
+///   into `gridshift` steps, and a prime meridian, `pm`, into a `primem` step.
+/// - Geographical steps (`proj=longlat`) become the shift from their datum to
+///   WGS84. Projections are preceded by the shift from WGS84 to their datum.
+///
 pub fn parse_proj(definition: &str) -> Result<String, Error> {
     // If it doesn't look like a PROJ string, we return it unchanged
     if definition.contains('|') | !definition.contains("proj") {
@@ -320,10 +440,18 @@ pub fn parse_proj(definition: &str) -> Result<String, Error> {
 
         tidy_proj(&mut elements)?;
 
-        // Skip empty steps, insert pipeline globals, handle step and pipeline
-        // inversions, and handle directional omissions (omit_fwd, omit_inv)
-        let mut geodesy_step = elements.join(" ").trim().to_string();
-        if !geodesy_step.is_empty() {
+        // Axis, unit and datum shift parameters expand the step into a pipeline
+        let mut expanded = Vec::new();
+        for step in expand_axis_and_units(elements)? {
+            expanded.extend(expand_datum_shift(step)?);
+        }
+        for mut elements in expanded {
+            // Skip empty steps, insert pipeline globals, handle step and pipeline
+            // inversions, and handle directional omissions (omit_fwd, omit_inv)
+            let mut geodesy_step = elements.join(" ").trim().to_string();
+            if geodesy_step.is_empty() {
+                continue;
+            }
             if !pipeline_globals.is_empty() {
                 elements.insert(1, pipeline_globals.clone());
             }
@@ -355,8 +483,225 @@ pub fn parse_proj(definition: &str) -> Result<String, Error> {
     Ok(geodesy_steps.join(" | ").trim().to_string())
 }
 
+// Expand a step with datum shift parameters into the equivalent pipeline.
+// Following PROJ, the parameters describe the shift from the datum of the
+// step to WGS84:
+// - `towgs84=dx,dy,dz[,rx,ry,rz,s]`: A Helmert shift, using the position
+//   vector convention for the rotations (in arcsec) and the scale (in ppm):
+//       cart ellps=E | helmert ... | cart inv ellps=WGS84
+// - `nadgrids=...`: A horizontal grid shift, `gridshift grids=...`. As in
+//   PROJ, `nadgrids` takes precedence over `towgs84`
+// - `geoidgrids=...`: Orthometric heights, converted to ellipsoidal by the
+//   inverse vertical grid shift, `gridshift inv grids=...`
+// - `pm=...`: Longitudes referring to a prime meridian other than Greenwich,
+//   converted by the inverse prime meridian shift, `primem inv pm=...`
+// Grid lists are passed on unchanged, including `@optional` and `null` entries.
+//
+// Geographical steps (`longlat` etc.) are replaced by the datum shift.
+// Projections are preceded by the datum shift in the opposite sense, so the
+// projection is applied to WGS84 geographical coordinates, as when converting
+// from WGS84 to the projected CRS.
+// Steps without datum shift parameters are returned unchanged, as the single element
+fn expand_datum_shift(mut elements: Vec<String>) -> Result<Vec<Vec<String>>, Error> {
+    let towgs84 = take_parameter(&mut elements, "towgs84");
+    let nadgrids = take_parameter(&mut elements, "nadgrids");
+    let geoidgrids = take_parameter(&mut elements, "geoidgrids");
+    let pm = take_parameter(&mut elements, "pm")
+        .filter(|pm| pm != "greenwich" && pm.parse::<f64>() != Ok(0.));
+    if towgs84.is_none() && nadgrids.is_none() && geoidgrids.is_none() && pm.is_none() {
+        return Ok(vec![elements]);
+    }
+
+    // Modifiers apply to every step of the expansion
+    let inverted = elements.iter().any(|e| e == "inv");
+    elements.retain(|e| e != "inv");
+    let modifiers = modifiers(&elements);
+
+    let ellps = elements
+        .iter()
+        .find(|e| e.starts_with("ellps="))
+        .cloned()
+        .unwrap_or("ellps=GRS80".to_string());
+
+    // The steps from the datum of the step to WGS84
+    let mut to_wgs84 = Vec::new();
+    if let Some(pm) = pm {
+        to_wgs84.push(format!("primem inv pm={pm}"));
+    }
+    if let Some(grids) = geoidgrids {
+        to_wgs84.push(format!("gridshift inv grids={grids}"));
+    }
+    if let Some(grids) = nadgrids {
+        to_wgs84.push(format!("gridshift grids={grids}"));
+    } else if let Some(towgs84) = towgs84 {
+        let values: Vec<&str> = towgs84.split(',').map(|v| v.trim()).collect();
+        if (values.len() != 3 && values.len() != 7)
+            || values.iter().any(|v| v.parse::<f64>().is_err())
+        {
+            return Err(Error::BadParam("towgs84".to_string(), towgs84));
+        }
+        let mut helmert = format!("helmert x={} y={} z={}", values[0], values[1], values[2]);
+        if values.len() == 7 {
+            helmert += &format!(
+                " rx={} ry={} rz={} s={} convention=position_vector",
+                values[3], values[4], values[5], values[6]
+            );
+        }
+        to_wgs84.push(format!("cart {ellps}"));
+        to_wgs84.push(helmert);
+        to_wgs84.push("cart inv ellps=WGS84".to_string());
+    }
+
+    let mut steps = substeps(&to_wgs84, &modifiers);
+    if !PROJ_GEOGRAPHIC.contains(&elements[0].as_str()) {
+        invert_steps(&mut steps);
+        steps.push(elements);
+    }
+
+    // An inverted step becomes an inverted pipeline
+    if inverted {
+        invert_steps(&mut steps);
+    }
+    Ok(steps)
+}
+
+// Expand a step with `axis`, `units` and `vunits` parameters into a pipeline
+// of the step itself, and `adapt` and `unitconvert` steps handling the axis
+// order and units of the CRS. For geographical steps, the handling precedes
+// the step (cf. `expand_datum_shift()`), while for projections it follows it.
+// As in PROJ, `units` is ignored for geographical steps.
+// Steps without axis and unit parameters are returned unchanged
+fn expand_axis_and_units(mut elements: Vec<String>) -> Result<Vec<Vec<String>>, Error> {
+    let axis = take_parameter(&mut elements, "axis").filter(|axis| axis != "enu");
+    let units = take_parameter(&mut elements, "units").filter(|units| units != "m");
+    let vunits = take_parameter(&mut elements, "vunits").filter(|units| units != "m");
+    if axis.is_none() && units.is_none() && vunits.is_none() {
+        return Ok(vec![elements]);
+    }
+    if let Some(axis) = axis.as_ref().filter(|axis| axis.len() != 3) {
+        return Err(Error::BadParam("axis".to_string(), axis.clone()));
+    }
+
+    let inverted = elements.iter().any(|e| e == "inv");
+    elements.retain(|e| e != "inv");
+    let modifiers = modifiers(&elements);
+
+    let geographic = PROJ_GEOGRAPHIC.contains(&elements[0].as_str());
+    let mut steps = Vec::new();
+    if geographic {
+        if let Some(axis) = axis {
+            steps.push(format!("adapt from={axis}f"));
+        }
+        if let Some(vunits) = vunits {
+            steps.push(format!("unitconvert z_in={vunits}"));
+        }
+    } else {
+        let units = [("xy_out", units), ("z_out", vunits)]
+            .iter()
+            .filter_map(|(key, units)| units.as_ref().map(|units| format!(" {key}={units}")))
+            .collect::<String>();
+        if !units.is_empty() {
+            steps.push(format!("unitconvert{units}"));
+        }
+        if let Some(axis) = axis {
+            steps.push(format!("adapt to={axis}f"));
+        }
+    }
+
+    let mut steps = substeps(&steps, &modifiers);
+    if geographic {
+        steps.push(elements);
+    } else {
+        steps.insert(0, elements);
+    }
+
+    // An inverted step becomes an inverted pipeline
+    if inverted {
+        invert_steps(&mut steps);
+    }
+    Ok(steps)
+}
+
+// The PROJ names for geographical coordinates, i.e. the no-operation
+const PROJ_GEOGRAPHIC: [&str; 4] = ["longlat", "latlong", "lonlat", "latlon"];
+
+// Remove the parameter `key` from `elements`, and return its value
+fn take_parameter(elements: &mut Vec<String>, key: &str) -> Option<String> {
+    let prefix = format!("{key}=");
+    let index = elements.iter().position(|e| e.starts_with(&prefix))?;
+    Some(elements.remove(index)[prefix.len()..].to_string())
+}
+
+// The directional omissions of a step, which apply to every step of its expansion
+fn modifiers(elements: &[String]) -> Vec<String> {
+    elements
+        .iter()
+        .filter(|e| *e == "omit_fwd" || *e == "omit_inv")
+        .cloned()
+        .collect()
+}
+
+// Split the steps of an expansion into elements, and add the modifiers
+fn substeps(steps: &[String], modifiers: &[String]) -> Vec<Vec<String>> {
+    steps
+        .iter()
+        .map(|step| {
+            let mut step: Vec<String> = step.split_whitespace().map(|e| e.to_string()).collect();
+            for modifier in modifiers {
+                if !step.contains(modifier) {
+                    step.push(modifier.clone());
+                }
+            }
+            step
+        })
+        .collect()
+}
+
+// Invert a sequence of steps, by reversing their order and toggling their `inv` flags
+fn invert_steps(steps: &mut [Vec<String>]) {
+    steps.reverse();
+    for step in steps {
+        if let Some(i) = step.iter().position(|e| e == "inv") {
+            step.remove(i);
+        } else {
+            step.push("inv".to_string());
+        }
+    }
+}
+
+// Invert a single (normalized) step of a pipeline, so it may take part in the
+// reversed sequence of steps of an inverted pipeline: Toggle the `inv` flag,
+// swap the `omit_fwd` and `omit_inv` modifiers, and swap `push` and `pop`,
+// which the pipeline handles by name, rather than through the `inv` flag.
+// The `stack` operator has no such counterpart, so it cannot be inverted
+pub(crate) fn invert_step(step: &str) -> Result<String, Error> {
+    let (protected, quoted) = protect_quotes(step);
+    let mut elements: Vec<&str> = protected.split_whitespace().collect();
+    let modifiers = ["inv", "omit_fwd", "omit_inv"];
+    let Some(name) = elements.iter().position(|e| !modifiers.contains(e)) else {
+        return Err(Error::Syntax(step.to_string()));
+    };
+
+    let has_inv = elements.contains(&"inv");
+    match elements[name] {
+        "push" => elements[name] = "pop",
+        "pop" => elements[name] = "push",
+        "stack" => return Err(Error::Unsupported(format!("Cannot invert '{step}'"))),
+        _ if has_inv => elements.retain(|e| *e != "inv"),
+        _ => elements.push("inv"),
+    }
+    for e in &mut elements {
+        match *e {
+            "omit_fwd" => *e = "omit_inv",
+            "omit_inv" => *e = "omit_fwd",
+            _ => (),
+        }
+    }
+    Ok(restore_quotes(&elements.join(" "), &quoted))
+}
+
 // Address some known incompatibilities between PROJ and Rust Geodesy
-// - Ellipsoid definitions
+// - Ellipsoid definitions, including spheres
 // - Scaling via the deprecated `k` parameter
 fn tidy_proj(elements: &mut Vec<String>) -> Result<(), Error> {
     // Geodesy only supports ellipsoid definitions as named builtins or ellps=a,rf
@@ -397,6 +742,73 @@ fn tidy_proj(elements: &mut Vec<String>) -> Result<(), Error> {
         }
     }
 
+    // The other PROJ shape parameters are converted to the same format, when
+    // they make sense. Otherwise we leave them for the operator instantiation
+    // to complain about
+    let a = elements.iter().position(|e| e.starts_with("a="));
+    if let (Some(a_idx), false) = (a, elements.iter().any(|e| e.starts_with("ellps="))) {
+        for key in ["b", "f", "e", "es"] {
+            let prefix = format!("{key}=");
+            let Some(idx) = elements.iter().position(|e| e.starts_with(&prefix)) else {
+                continue;
+            };
+            let a = elements[a_idx][2..].to_string();
+            let shape = elements[idx][prefix.len()..].parse::<f64>();
+            let ellps = match (a.parse::<f64>(), shape) {
+                (Ok(a), Ok(shape)) => Ellipsoid::from_shape(a, key, shape),
+                _ => break,
+            };
+            let Ok(ellps) = ellps else {
+                break;
+            };
+            let f = ellps.flattening();
+            let rf = if f == 0. { 0. } else { 1. / f };
+            elements.retain(|e| !e.starts_with("a=") && !e.starts_with(&prefix));
+            elements.push(format!("ellps={a},{rf}"));
+            break;
+        }
+    }
+
+    // Spheres: As in PROJ, a radius, `R`, takes precedence over any other
+    // ellipsoid definition, and is given as the single-number `ellps=R` form
+    if let Some(index) = elements.iter().skip(1).position(|e| e.starts_with("R=")) {
+        let radius = elements.remove(index + 1)[2..].to_string();
+        elements.retain(|e| !["ellps=", "a=", "rf="].iter().any(|k| e.starts_with(k)));
+        elements.push(format!("ellps={radius}"));
+    }
+
+    // Spheres with a radius derived from the ellipsoid, e.g. `R_A`, are given
+    // in the `ellps=R_A:ellipsoid` form
+    let derived = ["R_A", "R_V", "R_a", "R_g", "R_h"];
+    if let Some(index) = elements
+        .iter()
+        .skip(1)
+        .position(|e| derived.contains(&e.as_str()))
+    {
+        let radius = elements.remove(index + 1);
+        let ellps = match elements.iter().position(|e| e.starts_with("ellps=")) {
+            Some(i) => elements.remove(i)[6..].to_string(),
+            None => "GRS80".to_string(),
+        };
+        elements.push(format!("ellps={radius}:{ellps}"));
+    }
+
+    // PROJ's `omerc` defaults to Hotine variant B, unless `no_uoff` (or `no_off`)
+    // is given, while RG defaults to variant A, unless `variant` is given.
+    // PROJ also uses `lat_0` for the latitude of the projection center
+    if elements.first().is_some_and(|e| e == "omerc") {
+        let no_uoff = elements.iter().any(|e| e == "no_uoff" || e == "no_off");
+        elements.retain(|e| e != "no_uoff" && e != "no_off");
+        if !no_uoff {
+            elements.push("variant".to_string());
+        }
+        for element in elements.iter_mut() {
+            if let Some(stripped) = element.strip_prefix("lat_0=") {
+                *element = "latc=".to_string() + stripped;
+            }
+        }
+    }
+
     // `projinfo`  still produces strings with scaling defined as `k` instead of `k_0`
     // We replace `k` with `k_0` wherever it is encountered.
     for (i, element) in elements.iter().enumerate() {
@@ -470,6 +882,54 @@ mod tests {
 
         // ... and the operator name
         assert_eq!("foo bar baz=  $bonk".operator_name(), "foo");
+
+        // Quoted values are preserved verbatim through normalization...
+        let step = "foo  bar = 'baz  | bonk'  bonk=\"it's # x\"";
+        assert_eq!(step.normalize(), "foo bar='baz  | bonk' bonk=\"it's # x\"");
+        assert!(!step.is_pipeline());
+
+        // ...and splitting, where the quotes are removed from the values
+        let args = step.split_into_parameters();
+        assert_eq!(args["_name"], "foo");
+        assert_eq!(args["bar"], "baz  | bonk");
+        assert_eq!(args["bonk"], "it's # x");
+
+        let steps = "foo bar='a|b' # 'comment' | not a step\n| baz".split_into_steps();
+        assert_eq!(steps, ["foo bar='a|b'", "baz"]);
+
+        // Apostrophes, and unmatched quotes, are not quotes
+        assert_eq!("Poder's  x = 'tis".normalize(), "Poder's x='tis");
+        Ok(())
+    }
+
+    #[test]
+    fn invert_step() -> Result<(), Error> {
+        assert_eq!(super::invert_step("utm zone=32")?, "utm zone=32 inv");
+        assert_eq!(super::invert_step("inv utm zone=32")?, "utm zone=32");
+        assert_eq!(super::invert_step("omit_fwd cart inv")?, "omit_inv cart");
+        assert_eq!(super::invert_step("push v_3 omit_inv")?, "pop v_3 omit_fwd");
+        assert_eq!(super::invert_step("pop v_1 v_2")?, "push v_1 v_2");
+        assert_eq!(super::invert_step("set v_4='a  b'")?, "set v_4='a  b' inv");
+        assert!(super::invert_step("stack push=1,2").is_err());
+        assert!(super::invert_step("inv").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn environment() -> Result<(), Error> {
+        std::env::set_var("GEODESY_TOKEN_TEST_DIR", "/some/where");
+        assert_eq!(
+            interpolate_environment("grids=${GEODESY_TOKEN_TEST_DIR}/a.gsb x=$x")?,
+            "grids=/some/where/a.gsb x=$x"
+        );
+        assert!(matches!(
+            interpolate_environment("${GEODESY_TOKEN_TEST_UNDEFINED}"),
+            Err(Error::NotFound(_, _))
+        ));
+        assert!(matches!(
+            interpolate_environment("${GEODESY_TOKEN_TEST_DIR"),
+            Err(Error::Syntax(_))
+        ));
         Ok(())
     }
 
@@ -573,16 +1033,234 @@ mod tests {
         assert_eq!(parse_proj("+proj=tmerc +ellps=GRS80")?, "tmerc ellps=GRS80");
 
         // Ellipsoid is defined with a builtin but is modified by `a` or `rf`
-        // Note we don't remove `a` here even though this modification is not supported in RG
-        // it's expected to fail in the operator instantiation
+        // Note we don't remove `a` here: The modification is handled at
+        // operator instantiation
         assert_eq!(
             parse_proj("+proj=tmerc +ellps=GRS80 +a=1")?,
             "tmerc ellps=GRS80 a=1"
         );
 
+        // Ellipsoid defined with `a` and other shape parameters
+        assert_eq!(
+            parse_proj("+proj=tmerc +a=6378137 +f=0.0033528106647474805")?,
+            "tmerc ellps=6378137,298.257223563"
+        );
+        assert_eq!(
+            parse_proj("+proj=tmerc +a=6378137 +b=6378137")?,
+            "tmerc ellps=6378137,0"
+        );
+        assert_eq!(
+            parse_proj("+proj=tmerc +a=6378137 +es=x")?,
+            "tmerc a=6378137 es=x"
+        );
+
+        // Oblique Mercator: PROJ defaults to Hotine variant B
+        assert_eq!(
+            parse_proj("+proj=omerc +lat_0=4 +lonc=115 +alpha=53.3 +gamma=53.1")?,
+            "omerc latc=4 lonc=115 alpha=53.3 gamma=53.1 variant"
+        );
+        assert_eq!(
+            parse_proj("+proj=omerc +lat_0=44.75 +lonc=-124.05 +alpha=5 +no_uoff")?,
+            "omerc latc=44.75 lonc=-124.05 alpha=5"
+        );
+
         // Replace occurrences of `k=` with `k_0=`
         assert_eq!(parse_proj("+proj=tmerc +k=1.5")?, "tmerc k_0=1.5");
 
+        // Spheres: The radius takes precedence over other ellipsoid definitions
+        assert_eq!(
+            parse_proj("+proj=merc +a=6378137 +rf=298.25 +R=6371000")?,
+            "merc ellps=6371000"
+        );
+        assert_eq!(
+            parse_proj("+proj=laea +lat_0=90 +ellps=intl +R_A")?,
+            "laea lat_0=90 ellps=R_A:intl"
+        );
+        assert_eq!(
+            parse_proj("+proj=laea +a=6378388 +rf=297 +R_V")?,
+            "laea ellps=R_V:6378388,297"
+        );
+        assert_eq!(parse_proj("+proj=laea +R_A")?, "laea ellps=R_A:GRS80");
+
+        // ...and they instantiate
+        let mut ctx = Minimal::new();
+        let op = ctx.op(&parse_proj("+proj=merc +R=6371000")?)?;
+        let mut data = [Coor4D::geo(0., 1., 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - 6_371_000_f64 * 1_f64.to_radians()).abs() < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn towgs84() -> Result<(), Error> {
+        // Geographical: A datum shift to WGS84
+        assert_eq!(
+            parse_proj("+proj=longlat +ellps=intl +towgs84=-87,-98,-121")?,
+            "cart ellps=intl | helmert x=-87 y=-98 z=-121 | cart inv ellps=WGS84"
+        );
+
+        // ...and back again, with 7 parameters in the position vector convention
+        assert_eq!(
+            parse_proj("+proj=longlat +ellps=intl +towgs84=1,2,3,4,5,6,7 +inv")?,
+            "cart ellps=WGS84 | helmert inv x=1 y=2 z=3 rx=4 ry=5 rz=6 s=7 convention=position_vector | cart inv ellps=intl"
+        );
+
+        // Projected: From WGS84 to the projected CRS
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +ellps=intl +towgs84=-87,-98,-121")?,
+            "cart ellps=WGS84 | helmert inv x=-87 y=-98 z=-121 | cart inv ellps=intl | utm zone=32 ellps=intl"
+        );
+
+        // In a pipeline, and with an ellipsoid given as a,rf
+        assert_eq!(
+            parse_proj("+proj=pipeline +step +proj=longlat +a=6378388 +rf=297 +towgs84=1,2,3 +step +proj=utm +zone=32")?,
+            "cart ellps=6378388,297 | helmert x=1 y=2 z=3 | cart inv ellps=WGS84 | utm zone=32"
+        );
+
+        // Malformed parameter lists
+        assert!(parse_proj("+proj=longlat +towgs84=1,2").is_err());
+        assert!(parse_proj("+proj=longlat +towgs84=1,2,x").is_err());
+
+        // The expansion is a working definition: Compare with the plain
+        // Geodesy version
+        let mut ctx = Minimal::new();
+        let proj = ctx.op(&parse_proj(
+            "+proj=utm +zone=32 +ellps=intl +towgs84=-87,-98,-121",
+        )?)?;
+        let geodesy = ctx.op("cart ellps=WGS84 | helmert inv x=-87 y=-98 z=-121 | cart inv ellps=intl | utm zone=32 ellps=intl")?;
+        let mut a = [Coor4D::geo(55., 12., 0., 0.)];
+        let mut b = a;
+        ctx.apply(proj, Fwd, &mut a)?;
+        ctx.apply(geodesy, Fwd, &mut b)?;
+        assert!(a[0].hypot2(&b[0]) < 1e-9);
+        assert!(a[0].hypot2(&Coor4D::raw(691_950., 6_099_110., 0., 0.)) < 100.);
+        Ok(())
+    }
+
+    #[test]
+    fn datum_grids() -> Result<(), Error> {
+        // Horizontal grids, including the optional and null markers
+        assert_eq!(
+            parse_proj("+proj=longlat +ellps=intl +nadgrids=@foo.gsb,@null")?,
+            "gridshift grids=@foo.gsb,@null"
+        );
+
+        // ...taking precedence over towgs84, and combined with a geoid
+        assert_eq!(
+            parse_proj("+proj=longlat +towgs84=1,2,3 +nadgrids=foo.gsb +geoidgrids=bar.gtx")?,
+            "gridshift inv grids=bar.gtx | gridshift grids=foo.gsb"
+        );
+
+        // Projected: From WGS84 to the projected CRS
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +ellps=intl +towgs84=1,2,3 +geoidgrids=bar.gtx")?,
+            "cart ellps=WGS84 | helmert inv x=1 y=2 z=3 | cart inv ellps=intl | gridshift grids=bar.gtx | utm zone=32 ellps=intl"
+        );
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +nadgrids=foo.gsb +inv")?,
+            "utm inv zone=32 | gridshift grids=foo.gsb"
+        );
+
+        // The expansion is a working definition, also for GTX geoid grids
+        #[cfg(feature = "with_plain")]
+        {
+            let mut ctx = Plain::default();
+            let proj = ctx.op(&parse_proj(
+                "+proj=longlat +nadgrids=test.datum +geoidgrids=test.gtx",
+            )?)?;
+            let geodesy = ctx.op("gridshift inv grids=test.geoid | gridshift grids=test.datum")?;
+            let mut a = [Coor4D::geo(55., 12., 0., 0.)];
+            let mut b = a;
+            ctx.apply(proj, Fwd, &mut a)?;
+            ctx.apply(geodesy, Fwd, &mut b)?;
+            assert_eq!(a, b);
+            assert!(a[0][2] > 0.);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn prime_meridian() -> Result<(), Error> {
+        assert_eq!(
+            parse_proj("+proj=longlat +ellps=clrk80ign +pm=paris")?,
+            "primem inv pm=paris"
+        );
+        assert_eq!(
+            parse_proj("+proj=longlat +ellps=intl +pm=greenwich")?,
+            "longlat ellps=intl"
+        );
+
+        // Projected: Greenwich longitudes are converted before projecting
+        let definition = parse_proj(
+            "+proj=lcc +lat_1=46.8 +lat_0=46.8 +lon_0=0 +k_0=0.99987742 +x_0=600000 +y_0=2200000 +ellps=clrk80ign +pm=paris",
+        )?;
+        assert_eq!(
+            definition,
+            "primem pm=paris | lcc lat_1=46.8 lat_0=46.8 lon_0=0 k_0=0.99987742 x_0=600000 y_0=2200000 ellps=clrk80ign"
+        );
+
+        // Combined with a datum shift, the prime meridian is handled closest
+        // to the projection
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +ellps=intl +towgs84=1,2,3 +pm=2.5")?,
+            "cart ellps=WGS84 | helmert inv x=1 y=2 z=3 | cart inv ellps=intl | primem pm=2.5 | utm zone=32 ellps=intl"
+        );
+
+        // Lambert zone II (NTF Paris), EPSG:27572: The Panthéon
+        let mut ctx = Minimal::new();
+        let op = ctx.op(&definition)?;
+        let mut data = [Coor4D::geo(48.846_2, 2.346_4, 0., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        assert!((data[0][0] - 600_673.).abs() < 1.);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][0].to_degrees() - 2.346_4).abs() < 1e-10);
+        Ok(())
+    }
+
+    #[test]
+    fn axis_and_units() -> Result<(), Error> {
+        // Projected: Units and axis order of the output
+        assert_eq!(
+            parse_proj("+proj=tmerc +lat_0=31 +lon_0=-110.1667 +k=0.9999 +x_0=213360 +y_0=0 +ellps=GRS80 +units=us-ft +vunits=us-ft +axis=wsu")?,
+            "tmerc lat_0=31 lon_0=-110.1667 k_0=0.9999 x_0=213360 y_0=0 ellps=GRS80 | unitconvert xy_out=us-ft z_out=us-ft | adapt to=wsuf"
+        );
+
+        // Default units and axis order are left out...
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +units=m +axis=enu")?,
+            "utm zone=32"
+        );
+
+        // ...and inversion inverts the pipeline
+        assert_eq!(
+            parse_proj("+proj=utm +zone=32 +units=ft +inv")?,
+            "unitconvert inv xy_out=ft | utm inv zone=32"
+        );
+
+        // Geographical: Axis order and vertical units of the input. Units are ignored
+        assert_eq!(
+            parse_proj("+proj=longlat +axis=neu +units=ft +vunits=ft")?,
+            "adapt from=neuf | unitconvert z_in=ft | longlat"
+        );
+        assert_eq!(
+            parse_proj("+proj=longlat +ellps=intl +towgs84=1,2,3 +axis=neu")?,
+            "adapt from=neuf | cart ellps=intl | helmert x=1 y=2 z=3 | cart inv ellps=WGS84"
+        );
+        assert!(parse_proj("+proj=utm +zone=32 +axis=ne").is_err());
+
+        // The expansion is a working definition
+        let mut ctx = Minimal::new();
+        let op = ctx.op(&parse_proj(
+            "+proj=utm +zone=32 +units=us-ft +vunits=us-ft +axis=neu",
+        )?)?;
+        let mut data = [Coor4D::geo(55., 12., 100., 0.)];
+        ctx.apply(op, Fwd, &mut data)?;
+        let feet = 3937. / 1200.;
+        assert!((data[0][0] - 6_098_907.825 * feet).abs() < 1e-2);
+        assert!((data[0][1] - 691_875.632 * feet).abs() < 1e-2);
+        assert!((data[0][2] - 100. * feet).abs() < 1e-9);
+        ctx.apply(op, Inv, &mut data)?;
+        assert!((data[0][1].to_degrees() - 55.).abs() < 1e-10);
         Ok(())
     }
 }